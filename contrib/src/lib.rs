@@ -0,0 +1,40 @@
+//! An example of a third party widget library for Limn.
+//!
+//! Only depends on `limn-core`, using the API in `limn_core::extension`. Apps call
+//! `register_library(&Contrib)` once, then can use the widgets like any bundled widget.
+//! The widgets' class styles are kept in the `contrib` style scope, apps can override the
+//! exported `rating` class by registering styles for the `contrib::rating` class.
+
+#[macro_use]
+extern crate limn_core;
+
+pub mod rating;
+
+use limn_core::extension::*;
+use limn_core::color::*;
+
+pub use rating::{RatingBuilder, RatingState, RatingStyle, SetRating, RatingChanged};
+
+/// Name of the library, and of its style scope
+pub const SCOPE: &'static str = "contrib";
+
+#[derive(Debug, Copy, Clone)]
+pub struct Contrib;
+
+impl WidgetLibrary for Contrib {
+    fn name(&self) -> &str {
+        SCOPE
+    }
+    fn register_styles(&self, theme: &mut Theme) {
+        theme.register_type_style(RatingStyle::default());
+        theme.scope(SCOPE)
+            .register_class_style("rating", style!(RatingStyle {
+                filled_color: YELLOW,
+                empty_color: GRAY_80,
+            }))
+            .register_class_prop_style("rating", INACTIVE.clone(), style!(RatingStyle {
+                filled_color: GRAY_60,
+            }))
+            .export_class("rating");
+    }
+}
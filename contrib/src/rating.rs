@@ -0,0 +1,115 @@
+//! A row of dots for picking a rating, ex. 3 out of 5.
+
+use limn_core::extension::*;
+use limn_core::color::*;
+use limn_core::webrender::api::{ComplexClipRegion, BorderRadius, LocalClip, PrimitiveInfo, ClipMode};
+
+use SCOPE;
+
+component_style!{pub struct RatingState<name="rating", style=RatingStyle> {
+    value: usize = 0,
+    max: usize = 5,
+    filled_color: Color = BLACK,
+    empty_color: Color = GRAY_80,
+    spacing: f32 = 4.0,
+}}
+
+/// Draws `max` dots spread across the bounds, the first `value` of them filled
+impl RatingState {
+    fn slot_width(&self, bounds: Rect) -> f32 {
+        let spacing = self.spacing * self.max.saturating_sub(1) as f32;
+        ((bounds.width() - spacing) / self.max as f32).max(0.0)
+    }
+    /// The rating picked by clicking at `position`
+    fn value_at(&self, bounds: Rect, position: Point) -> usize {
+        let slot = (position.x - bounds.left()) / (self.slot_width(bounds) + self.spacing);
+        ::std::cmp::min(slot.max(0.0) as usize + 1, self.max)
+    }
+}
+
+impl Draw for RatingState {
+    fn draw(&mut self, bounds: Rect, _: Rect, renderer: &mut RenderBuilder) {
+        let slot_width = self.slot_width(bounds);
+        let diameter = slot_width.min(bounds.height());
+        for slot in 0..self.max {
+            let left = bounds.left() + slot as f32 * (slot_width + self.spacing) + (slot_width - diameter) / 2.0;
+            let top = bounds.top() + (bounds.height() - diameter) / 2.0;
+            let rect = Rect::new(Point::new(left, top), Size::new(diameter, diameter)).round();
+            let clip_region = ComplexClipRegion::new(rect, BorderRadius::uniform(diameter / 2.0), ClipMode::Clip);
+            let info = PrimitiveInfo::with_clip(rect, LocalClip::RoundedRect(rect, clip_region));
+            let color = if slot < self.value { self.filled_color } else { self.empty_color };
+            renderer.builder.push_rect(&info, color.into());
+        }
+    }
+}
+
+/// Set the rating shown by a rating widget
+#[derive(Debug, Copy, Clone)]
+pub struct SetRating(pub usize);
+
+/// Sent by a rating widget when the user picks a new rating
+#[derive(Debug, Copy, Clone)]
+pub struct RatingChanged(pub usize);
+
+#[derive(Debug, Copy, Clone)]
+pub struct RatingBuilder {
+    value: usize,
+    max: usize,
+}
+
+impl Default for RatingBuilder {
+    fn default() -> Self {
+        RatingBuilder {
+            value: 0,
+            max: 5,
+        }
+    }
+}
+
+impl RatingBuilder {
+    pub fn new() -> Self {
+        Self::default()
+    }
+    pub fn set_value(&mut self, value: usize) -> &mut Self {
+        self.value = value;
+        self
+    }
+    pub fn set_max(&mut self, max: usize) -> &mut Self {
+        self.max = max;
+        self
+    }
+
+    /// Creates the rating widget, clicking a dot sets the rating and sends `RatingChanged`
+    pub fn build(&self) -> Widget {
+        let mut widget = Widget::new("rating");
+        let mut draw_style = DrawStyle::from_class::<RatingStyle>("rating");
+        draw_style.merge(DrawStyle::new(style!(RatingStyle {
+            value: self.value,
+            max: self.max,
+        })));
+        widget
+            .set_draw_style(draw_style)
+            .set_style_scope(SCOPE)
+            .add_handler(|event: &SetRating, mut args: EventArgs| {
+                // stored in the widget's style, so it isn't lost when the style is resolved again
+                args.widget.set_draw_style(style!(RatingStyle {
+                    value: event.0,
+                }));
+            })
+            .add_handler(|event: &ClickEvent, mut args: EventArgs| {
+                let bounds = args.widget.bounds();
+                let value = args.widget.draw_state().downcast_ref::<RatingState>()
+                    .map(|state| state.value_at(bounds, event.position));
+                if let Some(value) = value {
+                    args.widget.event(SetRating(value));
+                    args.widget.event(RatingChanged(value));
+                }
+                *args.handled = true;
+            });
+        widget.layout().add(constraints![
+            min_height(20.0),
+            min_width(20.0 * self.max as f32),
+        ]);
+        widget
+    }
+}
@@ -0,0 +1,174 @@
+//! Adaptive layouts, that switch constraints and visibility as a container crosses breakpoints
+//! in width, so the same widgets can suit both small and large windows.
+//!
+//! The width of the container is classified into a `SizeClass` by its `Breakpoints`. An
+//! `AdaptiveLayout` holds, for widgets inside the container, constraints that only apply in some
+//! size classes and the size classes the widgets are visible in. When the class of the container
+//! changes, the constraints of the old class are removed, those of the new class are added, and
+//! `SizeClassChanged` is sent to the container and every widget inside it, for anything else that
+//! needs to adapt. Attach it to the root widget to adapt to the size of the window.
+
+use layout::LayoutUpdated;
+use layout::constraint::ConstraintBuilder;
+use cassowary::Constraint;
+
+use event::{EventArgs, EventHandler};
+use widget::Widget;
+
+/// Size classes, from narrowest to widest
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum SizeClass {
+    /// Phones, and narrow windows
+    Compact,
+    /// Tablets, and half screen windows
+    Medium,
+    /// Desktop windows
+    Expanded,
+}
+
+/// Widths at which a container moves into the next size class
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct Breakpoints {
+    /// Narrowest width in the medium class
+    pub medium: f32,
+    /// Narrowest width in the expanded class
+    pub expanded: f32,
+}
+
+impl Default for Breakpoints {
+    fn default() -> Self {
+        Breakpoints {
+            medium: 600.0,
+            expanded: 840.0,
+        }
+    }
+}
+
+impl Breakpoints {
+    pub fn new(medium: f32, expanded: f32) -> Self {
+        Breakpoints {
+            medium: medium,
+            expanded: expanded,
+        }
+    }
+    pub fn size_class(&self, width: f32) -> SizeClass {
+        if width >= self.expanded {
+            SizeClass::Expanded
+        } else if width >= self.medium {
+            SizeClass::Medium
+        } else {
+            SizeClass::Compact
+        }
+    }
+}
+
+/// Sent to an adaptive container, and every widget inside it, when its size class changes
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct SizeClassChanged(pub SizeClass);
+
+struct ClassConstraints {
+    widget: Widget,
+    classes: Vec<SizeClass>,
+    constraints: Vec<Constraint>,
+}
+
+struct ClassVisibility {
+    widget: Widget,
+    classes: Vec<SizeClass>,
+}
+
+/// Constraints and visibility by size class, see `Widget::adaptive_layout`
+pub struct AdaptiveLayout {
+    breakpoints: Breakpoints,
+    constraints: Vec<ClassConstraints>,
+    visibility: Vec<ClassVisibility>,
+    size_class: Option<SizeClass>,
+}
+
+impl AdaptiveLayout {
+    pub fn new(breakpoints: Breakpoints) -> Self {
+        AdaptiveLayout {
+            breakpoints: breakpoints,
+            constraints: Vec::new(),
+            visibility: Vec::new(),
+            size_class: None,
+        }
+    }
+    /// Constrain `widget` only while the container is in one of `classes`
+    pub fn constrain<B: ConstraintBuilder>(&mut self, widget: &Widget, classes: &[SizeClass], builder: B) -> &mut Self {
+        let constraints = builder.build(&widget.layout_vars());
+        self.constraints.push(ClassConstraints {
+            widget: widget.clone(),
+            classes: classes.to_vec(),
+            constraints: constraints,
+        });
+        self
+    }
+    /// Show `widget` only while the container is in one of `classes`
+    pub fn visible_in(&mut self, widget: &Widget, classes: &[SizeClass]) -> &mut Self {
+        self.visibility.push(ClassVisibility {
+            widget: widget.clone(),
+            classes: classes.to_vec(),
+        });
+        self
+    }
+
+    fn set_size_class(&mut self, container: &Widget, size_class: SizeClass) {
+        let previous = self.size_class;
+        if previous == Some(size_class) {
+            return;
+        }
+        self.size_class = Some(size_class);
+        let applied = |classes: &Vec<SizeClass>, size_class: Option<SizeClass>| {
+            size_class.map_or(false, |size_class| classes.contains(&size_class))
+        };
+        // remove the constraints of the old class before adding any, so they never conflict
+        for class_constraints in &mut self.constraints {
+            if applied(&class_constraints.classes, previous) && !applied(&class_constraints.classes, Some(size_class)) {
+                class_constraints.widget.layout().remove_constraints(class_constraints.constraints.clone());
+            }
+        }
+        for class_constraints in &mut self.constraints {
+            if !applied(&class_constraints.classes, previous) && applied(&class_constraints.classes, Some(size_class)) {
+                class_constraints.widget.layout().add(class_constraints.constraints.clone());
+            }
+        }
+        for class_visibility in &mut self.visibility {
+            if class_visibility.classes.contains(&size_class) {
+                class_visibility.widget.layout().show();
+            } else {
+                class_visibility.widget.layout().hide();
+            }
+        }
+        container.event_subtree(SizeClassChanged(size_class));
+    }
+}
+
+struct AdaptiveLayoutHandler {
+    container: Widget,
+    layout: AdaptiveLayout,
+}
+
+impl EventHandler<LayoutUpdated> for AdaptiveLayoutHandler {
+    fn handle(&mut self, _: &LayoutUpdated, _: EventArgs) {
+        let width = self.container.bounds().width();
+        let size_class = self.layout.breakpoints.size_class(width);
+        self.layout.set_size_class(&self.container, size_class);
+    }
+}
+
+impl Widget {
+    /// Switch the constraints and visibility of widgets inside this one as its width crosses
+    /// the breakpoints of `layout`. The constraints of the current size class are added straight
+    /// away, before the first layout that's the compact class.
+    pub fn adaptive_layout(&mut self, mut layout: AdaptiveLayout) -> &mut Self {
+        let size_class = layout.breakpoints.size_class(self.bounds().width());
+        layout.set_size_class(self, size_class);
+        let handler = AdaptiveLayoutHandler {
+            container: self.clone(),
+            layout: layout,
+        };
+        self.add_handler(handler);
+        self
+    }
+}
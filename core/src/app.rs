@@ -3,14 +3,21 @@
 use std::time::{Instant, Duration};
 use std::rc::Rc;
 use std::cell::RefCell;
+use std::thread;
 
 use glutin::{self, dpi::LogicalSize};
+use webrender::api::DeviceUintSize;
+use image::RgbaImage;
 
 use window::Window;
-use ui::Ui;
+use ui::{Ui, WidgetAttachedEvent, WidgetDetachedEvent};
 use input::InputEvent;
-use widget::Widget;
-use event::{self, EventHandler};
+use input::coalesce::EventCoalescer;
+use widget::{Widget, WidgetWeak};
+use resources::WidgetId;
+use event::{self, EventHandler, EventArgs, Priority};
+use clock;
+use watchdog::FramePhase;
 use geometry::Size;
 
 /// The `App` type is just a thin wrapper around a `Ui` containing
@@ -31,6 +38,10 @@ pub struct App {
     events_loop: Rc<RefCell<glutin::EventsLoop>>,
     /// Used to ignore resize events before ui has been measured
     window_initialized: bool,
+    /// Merges cursor moves, scrolls and resizes that arrive between frames
+    coalescer: EventCoalescer,
+    /// When the app was created, until the first frame is presented
+    started: Option<Instant>,
 }
 
 impl App {
@@ -39,7 +50,7 @@ impl App {
     /// desktop app:
     ///
     /// `ui_handlers`, `layout_handlers`, `input_handlers`,
-    /// `mouse_handlers`, `keyboard_handlers` and `drag_handlers`
+    /// `mouse_handlers`, `keyboard_handlers`, `command_handlers` and `drag_handlers`
     pub fn new(window: Window, events_loop: glutin::EventsLoop) -> Self {
         event::queue_set_events_loop(&events_loop);
         let ui = Ui::new(window, &events_loop);
@@ -48,6 +59,8 @@ impl App {
             next_frame_time: Instant::now(),
             events_loop: Rc::new(RefCell::new(events_loop)),
             window_initialized: false,
+            coalescer: EventCoalescer::default(),
+            started: Some(Instant::now()),
         };
         app.initialize_handlers();
         app
@@ -62,23 +75,49 @@ impl App {
 
         self.add_mouse_handlers();
         self.add_keyboard_handlers();
+        self.add_keymap_handlers();
+        self.add_command_handlers();
         self.add_drag_handlers();
+        self.add_frame_handlers();
+    }
+
+    /// Dispatch every window event as it arrives, rather than merging cursor moves, scrolls and
+    /// resizes that arrive between frames. Enabled by default, disable for apps that need every
+    /// point the cursor passes through, ex. for drawing.
+    pub fn set_event_coalescing(&mut self, enabled: bool) -> &mut Self {
+        self.coalescer.enabled = enabled;
+        self
     }
 
     fn handle_window_event(&mut self, event: glutin::Event) {
         debug!("handle window event {:?}", event);
         if let glutin::Event::WindowEvent { event, .. } = event {
-            if let glutin::WindowEvent::Resized(LogicalSize {width, height}) = event {
-                // ignore resize events before ui has been measured
-                if self.window_initialized {
-                    self.ui.window_resized(Size::new(width as f32, height as f32));
-                }
-            } else {
-                self.ui.event(InputEvent(event));
+            for event in self.coalescer.push(event) {
+                self.dispatch_window_event(event);
             }
         }
     }
 
+    /// Dispatch the window event held back to be merged with the next one, if any
+    fn flush_window_events(&mut self) {
+        for event in self.coalescer.flush() {
+            self.dispatch_window_event(event);
+        }
+    }
+
+    pub(crate) fn dispatch_window_event(&mut self, event: glutin::WindowEvent) {
+        if let glutin::WindowEvent::Resized(LogicalSize {width, height}) = event {
+            // ignore resize events before ui has been measured
+            if self.window_initialized {
+                self.ui.window_resized(Size::new(width as f32, height as f32));
+            }
+        } else if let glutin::WindowEvent::HiDpiFactorChanged(factor) = event {
+            self.ui.hidpi_factor_changed(factor as f32);
+        } else {
+            self.ui.get_root().event_with_priority(Priority::Input, InputEvent(event));
+        }
+    }
+
     /// Updates the UI and redraws the window (the applications main loop)
     pub fn main_loop(mut self, root: Widget) {
         self.ui.root.add_child(root);
@@ -98,32 +137,113 @@ impl App {
                     glutin::ControlFlow::Break
                 });
             }
+            let start = Instant::now();
             events_loop.poll_events(|event| {
                 self.handle_window_event(event);
             });
+            self.flush_window_events();
             self.handle_events();
+            self.record_phase(FramePhase::Events, start);
             if self.ui.should_close() {
                 self.ui.render.deinit();
                 return;
             }
             let now = Instant::now();
-            if now > self.next_frame_time {
-                let frame_length = Duration::new(0, 1_000_000_000 / 60);
+            let frame_drawn = now > self.next_frame_time;
+            if frame_drawn {
+                let frame_length = clock::frame_length();
                 if self.next_frame_time + frame_length > now {
                     self.next_frame_time = now + frame_length;
                 } else {
                     self.next_frame_time += frame_length;
                 }
                 self.ui.draw_if_needed();
-                self.ui.get_root().event(FrameEvent);
+                self.record_phase(FramePhase::Draw, now);
+                let start = Instant::now();
+                clock::advance(frame_length);
+                self.ui.get_root().event_with_priority(Priority::Animation, FrameEvent);
                 self.handle_events();
+                self.record_phase(FramePhase::Events, start);
             }
+            let frame_presented = self.ui.render.frame_ready();
             self.ui.update();
+            if frame_presented {
+                self.first_frame_presented();
+            }
+            if frame_drawn {
+                self.end_frame();
+            }
+        }
+    }
+
+    /// Send `FirstFramePresented` the first time a frame is shown
+    fn first_frame_presented(&mut self) {
+        if let Some(started) = self.started.take() {
+            let startup = started.elapsed();
+            info!("first frame presented {}ms after startup", startup.as_secs() * 1000 + u64::from(startup.subsec_nanos()) / 1_000_000);
+            self.ui.event(FirstFramePresented { startup: startup });
+        }
+    }
+
+    /// Lay out and render `root` without showing the window, and read back the result.
+    /// The window is sized to fit `root` and rendered at `hidpi_factor` rather than the screen's.
+    /// Anything captured before is replaced, so this can be called repeatedly, for example to
+    /// render the same widgets under different themes.
+    pub fn capture(&mut self, root: Widget, hidpi_factor: f32) -> RgbaImage {
+        let mut window_root = self.ui.get_root();
+        for child in window_root.children() {
+            window_root.remove_child(child);
+        }
+        window_root.add_child(root);
+        self.handle_events();
+
+        let size = window_root.bounds().size;
+        let size_px = DeviceUintSize::new(
+            (size.width * hidpi_factor).round() as u32,
+            (size.height * hidpi_factor).round() as u32);
+        {
+            // size the framebuffer for the requested scale, rather than the screen's
+            let mut window = self.ui.window.borrow_mut();
+            let screen_factor = window.hidpi_factor();
+            window.resize((size_px.width as f32 / screen_factor) as u32, (size_px.height as f32 / screen_factor) as u32);
+        }
+        self.ui.render.set_device_pixel_ratio(hidpi_factor, size_px);
+        self.ui.redraw();
+        self.ui.draw_if_needed();
+        while !self.ui.render.frame_ready() {
+            thread::sleep(Duration::from_millis(1));
+        }
+        self.ui.render.update(size_px);
+        let pixels = self.ui.render.read_pixels(size_px);
+        RgbaImage::from_raw(size_px.width, size_px.height, pixels).unwrap()
+    }
+
+    /// Time every frame, and report frames that take longer than `budget`.
+    /// See the [`watchdog`](../watchdog/index.html) module.
+    pub fn enable_watchdog(&mut self, budget: Duration) -> &mut Self {
+        self.ui.enable_watchdog(budget);
+        self
+    }
+
+    fn record_phase(&mut self, phase: FramePhase, start: Instant) {
+        if let Some(ref mut watchdog) = self.ui.watchdog {
+            watchdog.record_phase(phase, start.elapsed());
+        }
+    }
+
+    fn end_frame(&mut self) {
+        let report = match self.ui.watchdog {
+            Some(ref mut watchdog) => watchdog.end_frame(),
+            None => None,
+        };
+        if let Some(report) = report {
+            warn!("{}", report);
+            self.ui.event(report);
         }
     }
 
     /// Handle all the pending events in the event queue
-    fn handle_events(&mut self) {
+    pub(crate) fn handle_events(&mut self) {
         while let Some((event_address, type_id, data)) = event::queue_next() {
             self.ui.handle_event(event_address, type_id, data.as_ref());
         }
@@ -150,3 +270,63 @@ impl App {
 /// [`args.ui.redraw()`](../ui/struct.Ui.html#method.redraw) to draw a new frame.
 #[derive(Debug, Copy, Clone)]
 pub struct FrameEvent;
+
+/// Sent to the root widget once the first frame has been rendered and shown in the window.
+/// Work that isn't needed for the first frame, ex. loading fonts and images for views that aren't
+/// shown yet, can be started in a handler for this event, so it doesn't delay the window appearing.
+#[derive(Debug, Copy, Clone)]
+pub struct FirstFramePresented {
+    /// Time from the `App` being created to the frame being shown
+    pub startup: Duration,
+}
+
+impl Widget {
+    /// Receive `FrameEvent`s on this widget as well as the root, for widgets that animate themselves.
+    /// The events are forwarded from the root while the widget is attached.
+    pub fn receive_frame_events(&mut self) -> &mut Self {
+        let widget = self.downgrade();
+        self.add_handler(move |_: &WidgetAttachedEvent, args: EventArgs| {
+            args.ui.get_root().event(FrameSubscription::Add(args.widget.id(), widget.clone()));
+        });
+        self.add_handler(|_: &WidgetDetachedEvent, args: EventArgs| {
+            args.ui.get_root().event(FrameSubscription::Remove(args.widget.id()));
+        })
+    }
+}
+
+/// Sent to the root to start or stop forwarding `FrameEvent`s to a widget
+#[derive(Clone)]
+enum FrameSubscription {
+    Add(WidgetId, WidgetWeak),
+    Remove(WidgetId),
+}
+
+impl App {
+    fn add_frame_handlers(&mut self) {
+        let subscribers = Rc::new(RefCell::new(Vec::<(WidgetId, WidgetWeak)>::new()));
+        let frame_subscribers = Rc::clone(&subscribers);
+        self.add_handler(move |event: &FrameSubscription, _: EventArgs| {
+            let mut subscribers = subscribers.borrow_mut();
+            match *event {
+                FrameSubscription::Add(id, ref widget) => {
+                    if !subscribers.iter().any(|&(subscriber, _)| subscriber == id) {
+                        subscribers.push((id, widget.clone()));
+                    }
+                }
+                FrameSubscription::Remove(id) => subscribers.retain(|&(subscriber, _)| subscriber != id),
+            }
+        });
+        self.add_handler(move |event: &FrameEvent, _: EventArgs| {
+            // widgets dropped without being detached are removed here
+            frame_subscribers.borrow_mut().retain(|&(_, ref widget)| {
+                match widget.upgrade() {
+                    Some(widget) => {
+                        widget.event(*event);
+                        true
+                    }
+                    None => false,
+                }
+            });
+        });
+    }
+}
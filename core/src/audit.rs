@@ -0,0 +1,108 @@
+//! Accessibility audit of the widget tree, for finding common problems while developing an app.
+//!
+//! Run the audit with `Ui::audit_accessibility`, or press F9 with `DebugSettingsHandler` to log
+//! what it finds as warnings. It checks that:
+//!
+//! - widgets that can be clicked have a name, set with `Widget::set_accessible_name`, or the
+//!   text they or their descendants show
+//! - widgets that can be clicked, or one of their descendants, handle keyboard input, so they
+//!   can be used without a mouse
+//! - text contrasts with the background it's drawn over, per WCAG
+//!
+//! Widgets with no size, ex. hidden widgets, are skipped. What widgets show is found with
+//! `Draw::describe`, so draw states that don't describe themselves aren't checked.
+
+use std::fmt;
+
+use widget::Widget;
+use resources::WidgetId;
+use input::mouse::ClickEvent;
+use input::keyboard::{WidgetKeyboardInput, WidgetReceivedCharacter};
+use color::*;
+
+/// Least contrast ratio between text and its background, the WCAG AA level for body text
+pub const MIN_CONTRAST: f32 = 4.5;
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum AuditIssue {
+    /// The widget can be clicked, but has no name to announce
+    MissingName,
+    /// The widget can be clicked, but doesn't handle keyboard input
+    NoKeyboardHandler,
+    /// The widget's text doesn't contrast enough with its background
+    LowContrast { text: Color, background: Color, ratio: f32 },
+}
+
+/// A problem found by the audit, and the widget it was found in
+#[derive(Debug, Clone)]
+pub struct AuditFinding {
+    pub widget_id: WidgetId,
+    pub widget_name: String,
+    pub issue: AuditIssue,
+}
+
+impl fmt::Display for AuditFinding {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{} ({:?}): ", self.widget_name, self.widget_id)?;
+        match self.issue {
+            AuditIssue::MissingName => write!(f, "clickable widget has no accessible name or text"),
+            AuditIssue::NoKeyboardHandler => write!(f, "clickable widget can't be used with the keyboard"),
+            AuditIssue::LowContrast { text, background, ratio } => {
+                write!(f, "contrast of text {:?} on {:?} is {:.2}, less than {}", text, background, ratio, MIN_CONTRAST)
+            }
+        }
+    }
+}
+
+/// Audit `root` and its descendants
+pub fn audit(root: &Widget) -> Vec<AuditFinding> {
+    let mut findings = Vec::new();
+    audit_widget(root, &mut findings);
+    findings
+}
+
+fn audit_widget(widget: &Widget, findings: &mut Vec<AuditFinding>) {
+    let bounds = widget.bounds();
+    if bounds.size.width <= 0.0 || bounds.size.height <= 0.0 {
+        return;
+    }
+    let mut issues = Vec::new();
+    if widget.has_handler::<ClickEvent>() {
+        if widget.accessible_name().is_none() && !shows_text(widget) {
+            issues.push(AuditIssue::MissingName);
+        }
+        if !handles_keyboard(widget) {
+            issues.push(AuditIssue::NoKeyboardHandler);
+        }
+    }
+    let description = widget.clone().draw_state().describe();
+    if let (Some(text), Some(text_color)) = (description.text, description.text_color) {
+        if !text.trim().is_empty() && text_color.0 & 0xFF != 0 {
+            let background = widget.effective_background_color();
+            let ratio = text_color.contrast_ratio(background);
+            if ratio < MIN_CONTRAST {
+                issues.push(AuditIssue::LowContrast { text: text_color, background: background, ratio: ratio });
+            }
+        }
+    }
+    for issue in issues {
+        findings.push(AuditFinding {
+            widget_id: widget.id(),
+            widget_name: widget.name(),
+            issue: issue,
+        });
+    }
+    for child in widget.children() {
+        audit_widget(&child, findings);
+    }
+}
+
+fn shows_text(widget: &Widget) -> bool {
+    let text = widget.clone().draw_state().describe().text;
+    text.map_or(false, |text| !text.trim().is_empty()) || widget.children().iter().any(shows_text)
+}
+
+fn handles_keyboard(widget: &Widget) -> bool {
+    widget.has_handler::<WidgetKeyboardInput>() || widget.has_handler::<WidgetReceivedCharacter>() ||
+        widget.children().iter().any(handles_keyboard)
+}
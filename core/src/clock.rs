@@ -0,0 +1,121 @@
+//! The clock used for animations, timers and input timing, like double click detection.
+//!
+//! Normally this is the system clock. In deterministic mode time stands still except when advanced
+//! explicitly, by exactly one frame per frame drawn, or by `advance`. Events sent from other threads
+//! are also only delivered once the events already queued on the UI thread have been handled, rather
+//! than interleaved with them. Together this means the same input produces the same results regardless
+//! of how fast the machine is, which tests and recorded sessions depend on.
+//!
+//! Widgets should use `clock::now()` rather than `Instant::now()` for anything time dependent, and
+//! `clock::system_now()` rather than `SystemTime::now()`.
+//!
+//! Deterministic mode alone isn't enough to rewind an app to an earlier state. App state lives in
+//! the widget tree and in handler closures, not in a serializable store updated by actions, so
+//! there's no list of actions to step through and no snapshot to restore. Replaying the input
+//! from the start, ex. with `App::simulate`, up to the point of interest is the closest option.
+
+use std::sync::Mutex;
+use std::time::{Duration, Instant, SystemTime};
+
+lazy_static! {
+    /// The current virtual time, if in deterministic mode
+    static ref VIRTUAL_TIME: Mutex<Option<Instant>> = Mutex::new(None);
+    static ref FRAME_LENGTH: Mutex<Duration> = Mutex::new(Duration::new(0, 1_000_000_000 / 60));
+    /// The same moment by both clocks, to convert between them
+    static ref EPOCH: (Instant, SystemTime) = (Instant::now(), SystemTime::now());
+}
+
+/// The current time, according to the system clock, or the virtual clock in deterministic mode.
+pub fn now() -> Instant {
+    VIRTUAL_TIME.lock().unwrap().unwrap_or_else(Instant::now)
+}
+
+/// The current wall clock time, ex. for showing how long ago something happened. Follows the
+/// virtual clock in deterministic mode, like `now`.
+pub fn system_now() -> SystemTime {
+    let (instant, system) = *EPOCH;
+    let now = now();
+    if now >= instant { system + (now - instant) } else { system - (instant - now) }
+}
+
+/// Enable or disable deterministic mode. The virtual clock starts at the current time.
+pub fn set_deterministic(deterministic: bool) {
+    let mut time = VIRTUAL_TIME.lock().unwrap();
+    *time = if deterministic { Some(time.unwrap_or_else(Instant::now)) } else { None };
+}
+
+pub fn is_deterministic() -> bool {
+    VIRTUAL_TIME.lock().unwrap().is_some()
+}
+
+/// Move the virtual clock forward, does nothing unless in deterministic mode.
+pub fn advance(duration: Duration) {
+    if let Some(ref mut time) = *VIRTUAL_TIME.lock().unwrap() {
+        *time += duration;
+    }
+}
+
+/// Time between frames, the frame rate is capped at 60 FPS unless changed with `set_max_frame_rate`
+pub fn frame_length() -> Duration {
+    *FRAME_LENGTH.lock().unwrap()
+}
+
+/// Cap the frame rate at `frames_per_second`, ex. lower to save power
+pub fn set_max_frame_rate(frames_per_second: u32) {
+    *FRAME_LENGTH.lock().unwrap() = Duration::new(0, 1_000_000_000 / frames_per_second.max(1));
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::thread;
+    use event::run_on_ui_thread;
+
+    #[test]
+    fn deterministic_time_only_moves_when_advanced() {
+        run_on_ui_thread(|| {
+            set_deterministic(true);
+            assert!(is_deterministic());
+            let start = now();
+            let system_start = system_now();
+            thread::sleep(Duration::from_millis(5));
+            assert_eq!(now(), start);
+
+            advance(Duration::from_millis(250));
+            assert_eq!(now() - start, Duration::from_millis(250));
+            assert_eq!(system_now().duration_since(system_start).unwrap(), Duration::from_millis(250));
+            // enabling it again keeps the virtual time
+            set_deterministic(true);
+            assert_eq!(now() - start, Duration::from_millis(250));
+
+            set_deterministic(false);
+            assert!(!is_deterministic());
+            let real = now();
+            advance(Duration::from_secs(60));
+            assert!(now() < real + Duration::from_secs(60));
+        });
+    }
+
+    #[test]
+    fn timers_fire_when_advanced_past() {
+        run_on_ui_thread(|| {
+            set_deterministic(true);
+            let deadline = now() + Duration::from_millis(500);
+            advance(Duration::from_millis(499));
+            assert!(now() < deadline);
+            advance(Duration::from_millis(1));
+            assert!(now() >= deadline);
+        });
+    }
+
+    #[test]
+    fn frame_rate_cap() {
+        run_on_ui_thread(|| {
+            set_max_frame_rate(30);
+            assert_eq!(frame_length(), Duration::new(0, 1_000_000_000 / 30));
+            set_max_frame_rate(0);
+            assert_eq!(frame_length(), Duration::from_secs(1));
+            set_max_frame_rate(60);
+        });
+    }
+}
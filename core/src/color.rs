@@ -20,6 +20,40 @@ impl ::std::fmt::Debug for Color {
     }
 }
 
+impl Color {
+    /// The color `amount` of the way from this color to `other`, mixing each channel
+    pub fn mix(self, other: Color, amount: f32) -> Color {
+        let channel = |offset: u32| {
+            let low = (self.0 >> offset & 0xFF) as f32;
+            let high = (other.0 >> offset & 0xFF) as f32;
+            ((low + (high - low) * amount).round() as u32) << offset
+        };
+        Color(channel(24) | channel(16) | channel(8) | channel(0))
+    }
+    /// Relative luminance as defined by WCAG, from 0 for black to 1 for white, ignoring alpha
+    pub fn luminance(self) -> f32 {
+        let channel = |offset: u32| {
+            let value = (self.0 >> offset & 0xFF) as f32 / 255.0;
+            if value <= 0.039_28 { value / 12.92 } else { ((value + 0.055) / 1.055).powf(2.4) }
+        };
+        0.2126 * channel(24) + 0.7152 * channel(16) + 0.0722 * channel(8)
+    }
+    /// Contrast ratio with `other` as defined by WCAG, from 1 for colors of the same luminance
+    /// to 21 for black and white. Body text should have a ratio of at least 4.5 with its background.
+    pub fn contrast_ratio(self, other: Color) -> f32 {
+        let (a, b) = (self.luminance(), other.luminance());
+        (a.max(b) + 0.05) / (a.min(b) + 0.05)
+    }
+    /// Whichever of `light` and `dark` is more readable on this color, ex. for text or icons on a
+    /// background chosen by the user
+    pub fn contrasting(self, light: Color, dark: Color) -> Color {
+        if self.contrast_ratio(light) >= self.contrast_ratio(dark) { light } else { dark }
+    }
+    pub fn is_opaque(self) -> bool {
+        self.0 & 0xFF == 0xFF
+    }
+}
+
 impl Into<ColorF> for Color {
     fn into(self) -> ColorF {
         rgba(self.0)
@@ -0,0 +1,225 @@
+//! Document model for apps that edit one document at a time: the path of the document, whether
+//! it has unsaved changes, and hooks that save it.
+//!
+//! Install a `Document` with `App::add_document`, then send `DocumentEvent`s to the root widget as
+//! the document is edited, opened and saved. The window title shows the name of the document,
+//! "Untitled" until it's saved, followed by "— edited" while it has unsaved changes.
+//!
+//! Closing the window with unsaved changes sends `UnsavedChanges` to the root widget rather than
+//! closing it. The app asks the user what to do, then sends `DocumentEvent::SaveAndClose`,
+//! `DocumentEvent::Close` to discard the changes, or nothing to keep the window open.
+//!
+//! Documents opened or saved to a path are reported as `DocumentOpened` and `DocumentSaved`, ex. to
+//! keep a list of recent files.
+
+use std::path::{Path, PathBuf};
+
+use failure::Error;
+
+use app::App;
+use event::{EventHandler, EventArgs};
+use ui::{Ui, WindowCloseRequested};
+use widget::Widget;
+
+/// Send to the root widget
+#[derive(Debug, Clone)]
+pub enum DocumentEvent {
+    /// The document was changed
+    Edited,
+    /// Replace the document with a new, untitled one
+    New,
+    /// The document was opened from a path
+    Opened(PathBuf),
+    /// Save to the document's path, or ask for a path if it doesn't have one
+    Save,
+    /// Ask for a path and save the document to it
+    SaveAs,
+    /// Save the document and close the window, if it's saved
+    SaveAndClose,
+    /// Close the window, discarding unsaved changes
+    Close,
+}
+
+/// Sent to the root widget whenever the path of the document changes or it's edited or saved
+#[derive(Debug, Clone)]
+pub struct DocumentChanged {
+    pub path: Option<PathBuf>,
+    pub edited: bool,
+}
+
+/// Sent to the root widget when a document is opened from a path
+#[derive(Debug, Clone)]
+pub struct DocumentOpened(pub PathBuf);
+
+/// Sent to the root widget when the document is saved to a path
+#[derive(Debug, Clone)]
+pub struct DocumentSaved(pub PathBuf);
+
+/// Sent to the root widget when saving fails, with the error from the save hook
+#[derive(Debug, Clone)]
+pub struct DocumentSaveFailed(pub String);
+
+/// Sent to the root widget when the window is closed while the document has unsaved changes
+#[derive(Debug, Copy, Clone)]
+pub struct UnsavedChanges;
+
+type SaveHook = Box<FnMut(&Path) -> Result<(), Error>>;
+type SaveAsHook = Box<FnMut(Option<&Path>) -> Option<PathBuf>>;
+
+pub struct Document {
+    path: Option<PathBuf>,
+    edited: bool,
+    save_hook: Option<SaveHook>,
+    save_as_hook: Option<SaveAsHook>,
+}
+
+impl Default for Document {
+    fn default() -> Self {
+        Document {
+            path: None,
+            edited: false,
+            save_hook: None,
+            save_as_hook: None,
+        }
+    }
+}
+
+multi_event!{impl EventHandler<DocumentInput> for Document {
+    DocumentEvent => document_event,
+    WindowCloseRequested => close_requested,
+}}
+
+impl Document {
+    pub fn new() -> Self {
+        Self::default()
+    }
+    /// Start with a document that was opened from `path`
+    pub fn set_path(&mut self, path: PathBuf) -> &mut Self {
+        self.path = Some(path);
+        self
+    }
+    /// Set the hook that writes the document to a path
+    pub fn set_save_hook<F>(&mut self, hook: F) -> &mut Self
+        where F: FnMut(&Path) -> Result<(), Error> + 'static
+    {
+        self.save_hook = Some(Box::new(hook));
+        self
+    }
+    /// Set the hook that asks where to save the document, given its current path, ex. with a file
+    /// dialog. Returns `None` if the user cancels. Without it, untitled documents can't be saved.
+    pub fn set_save_as_hook<F>(&mut self, hook: F) -> &mut Self
+        where F: FnMut(Option<&Path>) -> Option<PathBuf> + 'static
+    {
+        self.save_as_hook = Some(Box::new(hook));
+        self
+    }
+
+    /// The window title for the document
+    pub fn title(&self) -> String {
+        let name = self.path.as_ref()
+            .and_then(|path| path.file_name())
+            .map_or("Untitled".to_owned(), |name| name.to_string_lossy().into_owned());
+        if self.edited {
+            format!("{} — edited", name)
+        } else {
+            name
+        }
+    }
+
+    fn changed(&self, ui: &mut Ui) {
+        ui.set_title(&self.title());
+        ui.set_confirm_close(self.edited);
+        ui.event(DocumentChanged {
+            path: self.path.clone(),
+            edited: self.edited,
+        });
+    }
+
+    /// Save the document, asking for a path if `choose_path` is set or it doesn't have one.
+    /// Returns false if it wasn't saved.
+    fn save(&mut self, choose_path: bool, ui: &mut Ui) -> bool {
+        let path = if choose_path || self.path.is_none() {
+            let current = self.path.clone();
+            match self.save_as_hook {
+                Some(ref mut hook) => hook(current.as_ref().map(|path| path.as_path())),
+                None => None,
+            }
+        } else {
+            self.path.clone()
+        };
+        let path = match path {
+            Some(path) => path,
+            None => return false,
+        };
+        let result = match self.save_hook {
+            Some(ref mut hook) => hook(&path),
+            None => Ok(()),
+        };
+        match result {
+            Ok(()) => {
+                self.path = Some(path.clone());
+                self.edited = false;
+                self.changed(ui);
+                ui.event(DocumentSaved(path));
+                true
+            }
+            Err(err) => {
+                warn!("failed to save {}: {}", path.display(), err);
+                ui.event(DocumentSaveFailed(err.to_string()));
+                false
+            }
+        }
+    }
+
+    fn document_event(&mut self, event: &DocumentEvent, args: EventArgs) {
+        match *event {
+            DocumentEvent::Edited => {
+                if !self.edited {
+                    self.edited = true;
+                    self.changed(args.ui);
+                }
+            }
+            DocumentEvent::New => {
+                self.path = None;
+                self.edited = false;
+                self.changed(args.ui);
+            }
+            DocumentEvent::Opened(ref path) => {
+                self.path = Some(path.clone());
+                self.edited = false;
+                self.changed(args.ui);
+                args.ui.event(DocumentOpened(path.clone()));
+            }
+            DocumentEvent::Save => {
+                self.save(false, args.ui);
+            }
+            DocumentEvent::SaveAs => {
+                self.save(true, args.ui);
+            }
+            DocumentEvent::SaveAndClose => {
+                if self.save(false, args.ui) {
+                    args.ui.close();
+                }
+            }
+            DocumentEvent::Close => args.ui.close(),
+        }
+    }
+
+    fn close_requested(&mut self, _: &WindowCloseRequested, args: EventArgs) {
+        if self.edited {
+            args.ui.event(UnsavedChanges);
+        } else {
+            args.ui.close();
+        }
+    }
+}
+
+impl App {
+    /// Track the state of a document, and show it in the window title
+    pub fn add_document(&mut self, document: Document) -> &mut Self {
+        self.window().set_title(&document.title());
+        self.add_handler(document);
+        Document::add_adapters(&mut self.get_root());
+        self
+    }
+}
@@ -33,6 +33,9 @@
 //! `Send` and can be called from any thread, without a reference to the `Ui`. `Widget` and any other types that can
 //! modify the UI are not thread safe, so can't currently be referenced from other threads, so if any specific widgets need
 //! to be notified from another thread, it's necessary to add a handler to the root widget to forward events.
+//! For the common case of just modifying a widgets draw state, `EventSender::update_widget` can be used instead,
+//! which sends a closure to the UI thread that is run against the widget with the given `WidgetId`, and
+//! `EventSender::widget_event` can send an event to a single widget the same way.
 //!
 //! For further explanation of the single threaded event architecture see
 //! https://github.com/christolliday/limn/pull/20#discussion_r145373568
@@ -44,8 +47,11 @@ use std::collections::VecDeque;
 
 use glutin::{EventsLoop, EventsLoopProxy};
 
-use ui::Ui;
+use ui::{Ui, UpdateWidget};
 use widget::Widget;
+use widget::draw::Draw;
+use resources::WidgetId;
+use clock;
 
 /// Defines the different targets that events can be delivered to.
 /// An event will be sent to all handlers that match both the Target,
@@ -63,15 +69,53 @@ pub(crate) enum Target {
     Root,
 }
 
+/// The lanes of the event queue. Every event in a higher priority lane is handled before any
+/// event in a lower one, and events sent while handling an event go in the same lane as it.
+/// This way input, and everything it causes, isn't delayed by a flood of application events,
+/// ex. log lines streaming into a list from another thread.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Priority {
+    /// Window input events
+    Input,
+    /// `FrameEvent`s
+    Animation,
+    /// The default, for events sent from other threads, or outside of any handler
+    Application,
+    /// Only handled when there's nothing else to do
+    Idle,
+}
+
+const LANES: usize = 4;
+
+impl Priority {
+    fn from_lane(lane: usize) -> Self {
+        match lane {
+            0 => Priority::Input,
+            1 => Priority::Animation,
+            2 => Priority::Application,
+            _ => Priority::Idle,
+        }
+    }
+}
+
+fn new_lanes<T>() -> Vec<VecDeque<T>> {
+    (0..LANES).map(|_| VecDeque::new()).collect()
+}
+
+/// The highest priority non empty lane
+fn next_lane<T>(lanes: &[VecDeque<T>]) -> Option<usize> {
+    lanes.iter().position(|lane| !lane.is_empty())
+}
+
 struct Queue {
-    queue: VecDeque<(Target, TypeId, Box<Any>)>,
+    lanes: Vec<VecDeque<(Target, TypeId, Box<Any>)>>,
     events_loop_proxy: Option<EventsLoopProxy>,
 }
 
 impl Queue {
     fn new() -> Self {
         Queue {
-            queue: VecDeque::new(),
+            lanes: new_lanes(),
             events_loop_proxy: None,
         }
     }
@@ -79,19 +123,16 @@ impl Queue {
         self.events_loop_proxy = Some(events_loop);
     }
     /// Push a new event on the queue and wake the window up if it is asleep
-    fn push<T: 'static>(&mut self, address: Target, data: T) {
+    fn push<T: 'static>(&mut self, address: Target, priority: Priority, data: T) {
         let type_id = TypeId::of::<T>();
-        self.queue.push_back((address, type_id, Box::new(data)));
+        self.lanes[priority as usize].push_back((address, type_id, Box::new(data)));
         if let Some(ref events_loop_proxy) = self.events_loop_proxy {
             events_loop_proxy.wakeup().unwrap();
         }
     }
-}
-impl Iterator for Queue {
-    type Item = (Target, TypeId, Box<Any>);
     /// Take the next event off the Queue, should only be called by App
-    fn next(&mut self) -> Option<(Target, TypeId, Box<Any>)> {
-        self.queue.pop_front()
+    fn pop(&mut self, lane: usize) -> Option<(Target, TypeId, Box<Any>)> {
+        self.lanes[lane].pop_front()
     }
 }
 
@@ -119,6 +160,9 @@ impl <T, E> EventHandler<E> for T where T: FnMut(&E, EventArgs) {
 pub(super) struct EventHandlerWrapper {
     handler: Box<Any>,
     handle_fn: Box<Fn(&mut Any, &Any, EventArgs)>,
+    /// Names of the handler and event types, for the watchdog
+    pub(crate) handler_name: &'static str,
+    pub(crate) event_name: &'static str,
 }
 
 impl EventHandlerWrapper {
@@ -134,6 +178,8 @@ impl EventHandlerWrapper {
         EventHandlerWrapper {
             handler: Box::new(handler),
             handle_fn: Box::new(handle_fn),
+            handler_name: ::type_name::<H>(),
+            event_name: ::type_name::<E>(),
         }
     }
     pub fn new_from_fn<H, E>(handler: H) -> Self
@@ -149,6 +195,8 @@ impl EventHandlerWrapper {
         EventHandlerWrapper {
             handler: Box::new(handler),
             handle_fn: Box::new(handle_fn),
+            handler_name: ::type_name::<H>(),
+            event_name: ::type_name::<E>(),
         }
     }
     pub fn handle(&mut self, event: &Any, args: EventArgs) {
@@ -162,6 +210,8 @@ lazy_static! {
 }
 
 thread_local! {
+    /// Lane of the event being handled, events sent by its handlers go in the same lane
+    static CURRENT_PRIORITY: Cell<Priority> = Cell::new(Priority::Application);
     static LOCAL_QUEUE: Option<RefCell<Queue>> = {
         let first = FIRST_THREAD.lock().unwrap();
         if first.get() {
@@ -173,14 +223,40 @@ thread_local! {
     }
 }
 
+fn local_queue_lane() -> Option<usize> {
+    LOCAL_QUEUE.with(|queue| next_lane(&queue.as_ref().unwrap().borrow().lanes))
+}
+
+fn local_queue_pop(lane: usize) -> Option<(Target, TypeId, Box<Any>)> {
+    LOCAL_QUEUE.with(|queue| queue.as_ref().unwrap().borrow_mut().pop(lane))
+}
+
+fn global_queue_pop(lane: usize) -> Option<(Target, TypeId, Box<Any>)> {
+    GLOBAL_QUEUE.lock().unwrap().pop(lane).map(|next| (Target::Root, next.0, next.1))
+}
+
 pub(super) fn queue_next() -> Option<(Target, TypeId, Box<Any>)> {
-    if let Some(next) = GLOBAL_QUEUE.lock().unwrap().next() {
-        Some((Target::Root, next.0, next.1))
-    } else {
-        let mut next = None;
-        LOCAL_QUEUE.with(|queue| next = Some(queue.as_ref().unwrap().borrow_mut().next()));
-        next.unwrap()
-    }
+    let local_lane = local_queue_lane();
+    let global_lane = next_lane(&GLOBAL_QUEUE.lock().unwrap().lanes);
+    let next = match (local_lane, global_lane) {
+        (Some(local), Some(global)) => {
+            // events from other threads arrive at unpredictable times, so when deterministic
+            // only deliver them between batches of UI thread events
+            if clock::is_deterministic() || local < global {
+                Some((local, local_queue_pop(local)))
+            } else {
+                Some((global, global_queue_pop(global)))
+            }
+        }
+        (Some(local), None) => Some((local, local_queue_pop(local))),
+        (None, Some(global)) => Some((global, global_queue_pop(global))),
+        (None, None) => None,
+    };
+    let lane = next.as_ref().map(|&(lane, _)| lane);
+    CURRENT_PRIORITY.with(|priority| {
+        priority.set(lane.map_or(Priority::Application, Priority::from_lane));
+    });
+    next.and_then(|(_, event)| event)
 }
 
 pub(super) fn queue_set_events_loop(events_loop: &EventsLoop) {
@@ -189,11 +265,17 @@ pub(super) fn queue_set_events_loop(events_loop: &EventsLoop) {
 }
 
 /// Send message to target address, must be sent from main UI thread.
+/// The event goes in the lane of the event being handled, if any.
 pub(crate) fn event<T: 'static>(address: Target, data: T) {
+    let priority = CURRENT_PRIORITY.with(|priority| priority.get());
+    event_with_priority(address, priority, data);
+}
+
+pub(crate) fn event_with_priority<T: 'static>(address: Target, priority: Priority, data: T) {
     LOCAL_QUEUE.with(|queue| {
         if let Some(queue) = queue.as_ref() {
             debug!("push event {}", ::type_name::<T>());
-            queue.borrow_mut().push(address, data);
+            queue.borrow_mut().push(address, priority, data);
         } else {
             eprintln!("Tried to send event off the main thread, use event_global");
         }
@@ -202,18 +284,52 @@ pub(crate) fn event<T: 'static>(address: Target, data: T) {
 
 /// Send message to UI from any thread.
 pub fn event_global<T: 'static + Send>(data: T) {
-    GLOBAL_QUEUE.lock().unwrap().push(data);
+    event_global_with_priority(Priority::Application, data);
+}
+
+/// Send message to UI from any thread, in the given lane of the event queue,
+/// ex. `Priority::Idle` for background work that shouldn't hold up the UI.
+pub fn event_global_with_priority<T: 'static + Send>(priority: Priority, data: T) {
+    GLOBAL_QUEUE.lock().unwrap().push(priority, data);
+}
+
+/// Handle for sending events to the UI from any thread, obtained from `Ui::event_sender`.
+#[derive(Debug, Copy, Clone, Default)]
+pub struct EventSender;
+
+impl EventSender {
+    /// Send an event to the root widget, same as `event_global`.
+    pub fn event<T: 'static + Send>(&self, data: T) {
+        event_global(data);
+    }
+    /// Send an event to the root widget, in the given lane of the event queue.
+    pub fn event_with_priority<T: 'static + Send>(&self, priority: Priority, data: T) {
+        event_global_with_priority(priority, data);
+    }
+    /// Run a closure against the draw state of a widget on the UI thread.
+    /// Does nothing if the widget has been removed by the time the update is handled.
+    pub fn update_widget<T, F>(&self, widget_id: WidgetId, f: F)
+        where T: Draw + 'static,
+              F: FnOnce(&mut T) + Send + 'static
+    {
+        event_global(UpdateWidget::new(widget_id, f));
+    }
+    /// Send an event to the widget with the given `WidgetId`, on the UI thread.
+    /// Does nothing if the widget has been removed by the time the event is handled.
+    pub fn widget_event<T: 'static + Send>(&self, widget_id: WidgetId, data: T) {
+        event_global(UpdateWidget::event(widget_id, data));
+    }
 }
 
 struct GlobalQueue {
-    queue: VecDeque<(TypeId, Box<Any + Send>)>,
+    lanes: Vec<VecDeque<(TypeId, Box<Any + Send>)>>,
     events_loop_proxy: Option<EventsLoopProxy>,
 }
 
 impl GlobalQueue {
     fn new() -> Self {
         GlobalQueue {
-            queue: VecDeque::new(),
+            lanes: new_lanes(),
             events_loop_proxy: None,
         }
     }
@@ -221,18 +337,15 @@ impl GlobalQueue {
         self.events_loop_proxy = Some(events_loop);
     }
     /// Push a new event on the queue and wake the window up if it is asleep
-    pub fn push<T: 'static + Send>(&mut self, data: T) {
+    pub fn push<T: 'static + Send>(&mut self, priority: Priority, data: T) {
         let type_id = TypeId::of::<T>();
-        self.queue.push_back((type_id, Box::new(data)));
+        self.lanes[priority as usize].push_back((type_id, Box::new(data)));
         if let Some(ref events_loop_proxy) = self.events_loop_proxy {
             events_loop_proxy.wakeup().unwrap();
         }
     }
-}
-impl Iterator for GlobalQueue {
-    type Item = (TypeId, Box<Any + Send>);
-    fn next(&mut self) -> Option<(TypeId, Box<Any + Send>)> {
-        self.queue.pop_front()
+    fn pop(&mut self, lane: usize) -> Option<(TypeId, Box<Any + Send>)> {
+        self.lanes[lane].pop_front()
     }
 }
 
@@ -285,3 +398,116 @@ macro_rules! forward_event {
         });
     }
 }
+
+/// Runs tests one at a time on a single thread, the thread owning the UI event queue, since the
+/// queue and the clock are global
+#[cfg(test)]
+pub(crate) fn run_on_ui_thread<F: FnOnce() + Send + 'static>(test: F) {
+    use std::panic::{self, AssertUnwindSafe};
+    use std::sync::mpsc::{channel, Sender};
+    use std::thread;
+
+    type Test = Box<FnMut() + Send>;
+    lazy_static! {
+        static ref TESTS: Mutex<Sender<(Test, Sender<bool>)>> = {
+            let (sender, receiver) = channel::<(Test, Sender<bool>)>();
+            thread::spawn(move || {
+                for (mut test, passed) in receiver {
+                    // leave nothing queued for the next test, whether this one passes or not
+                    while queue_next().is_some() {}
+                    clock::set_deterministic(false);
+                    let result = panic::catch_unwind(AssertUnwindSafe(|| test()));
+                    while queue_next().is_some() {}
+                    passed.send(result.is_ok()).unwrap();
+                }
+            });
+            Mutex::new(sender)
+        };
+    }
+    let mut test = Some(test);
+    let test: Test = Box::new(move || (test.take().unwrap())());
+    let (passed, result) = channel();
+    TESTS.lock().unwrap().send((test, passed)).unwrap();
+    assert!(result.recv().unwrap(), "test failed on the UI thread");
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[derive(Debug, Copy, Clone, PartialEq)]
+    struct Sent(&'static str);
+
+    /// Take every queued event, returning the `Sent` events in the order they're delivered
+    fn delivered() -> Vec<&'static str> {
+        let mut sent = Vec::new();
+        while let Some((_, _, data)) = queue_next() {
+            if let Ok(event) = data.downcast::<Sent>() {
+                sent.push(event.0);
+            }
+        }
+        sent
+    }
+
+    #[test]
+    fn other_threads_wait_when_deterministic() {
+        run_on_ui_thread(|| {
+            let send = || {
+                event(Target::Root, Sent("ui 1"));
+                event_global_with_priority(Priority::Input, Sent("global"));
+                event(Target::Root, Sent("ui 2"));
+            };
+            // normally the higher priority lane goes first, wherever the event came from
+            send();
+            assert_eq!(delivered(), vec!["global", "ui 1", "ui 2"]);
+            // deterministic, events from other threads wait for the UI thread's events
+            clock::set_deterministic(true);
+            send();
+            assert_eq!(delivered(), vec!["ui 1", "ui 2", "global"]);
+        });
+    }
+
+    /// Take the next queued event, if it's a `Sent` event
+    fn next_sent() -> Option<&'static str> {
+        queue_next().and_then(|(_, _, data)| data.downcast::<Sent>().ok()).map(|event| event.0)
+    }
+
+    #[test]
+    fn lanes_in_priority_order() {
+        run_on_ui_thread(|| {
+            event_with_priority(Target::Root, Priority::Idle, Sent("idle"));
+            event_with_priority(Target::Root, Priority::Application, Sent("application"));
+            event_global_with_priority(Priority::Animation, Sent("animation"));
+            event_with_priority(Target::Root, Priority::Input, Sent("input 1"));
+            event_with_priority(Target::Root, Priority::Input, Sent("input 2"));
+            assert_eq!(delivered(), vec!["input 1", "input 2", "animation", "application", "idle"]);
+        });
+    }
+
+    #[test]
+    fn events_inherit_the_lane_being_handled() {
+        run_on_ui_thread(|| {
+            event_with_priority(Target::Root, Priority::Input, Sent("input"));
+            event_global_with_priority(Priority::Animation, Sent("animation"));
+            event_with_priority(Target::Root, Priority::Idle, Sent("idle"));
+
+            // sent while handling input, so it's handled before the animation frame
+            assert_eq!(next_sent(), Some("input"));
+            event(Target::Root, Sent("caused by input"));
+            assert_eq!(next_sent(), Some("caused by input"));
+            // events from other threads set the lane too
+            assert_eq!(next_sent(), Some("animation"));
+            event(Target::Root, Sent("caused by animation"));
+            event_global(Sent("from another thread"));
+            assert_eq!(next_sent(), Some("caused by animation"));
+            // application is the default for other threads, ahead of idle work
+            assert_eq!(next_sent(), Some("from another thread"));
+            assert_eq!(next_sent(), Some("idle"));
+            event(Target::Root, Sent("caused by idle"));
+            assert_eq!(next_sent(), Some("caused by idle"));
+            // nothing being handled, back to the default lane
+            assert_eq!(next_sent(), None);
+            assert_eq!(CURRENT_PRIORITY.with(|priority| priority.get()), Priority::Application);
+        });
+    }
+}
@@ -0,0 +1,49 @@
+//! The API for crates shipping their own widget libraries.
+//!
+//! Everything a widget crate needs is re-exported here, so it can depend on `limn-core` alone:
+//!
+//! - `Draw` to draw a widget, with `RenderBuilder` and the geometry types
+//! - `EventHandler` and the input events to make widgets interactive
+//! - `LayoutContainer` to arrange a widget's children, and the layout constraints
+//! - `Component`, `ComponentStyle` and `WidgetModifier` to make drawing and widgets styleable
+//! - `Theme` and `StyleScope` to register default styles, and `Resources` to load fonts and images
+//!
+//! A library implements `WidgetLibrary` to register the styles and resources its widgets rely on,
+//! and apps register it once with `register_library` before building any of its widgets.
+//! Libraries should keep their class styles in a style scope named after the library (see
+//! `Widget::set_style_scope`), so their widgets look the same whatever classes an app defines.
+
+pub use widget::{Widget, WidgetWeak, StateUpdated, StyleUpdated};
+pub use widget::draw::{Draw, DrawEventHandler};
+pub use widget::property::{Property, PropSet};
+pub use widget::property::states::*;
+pub use event::{EventHandler, EventArgs};
+pub use input::mouse::{ClickEvent, WidgetMouseButton, WidgetMouseMoved, WidgetMouseWheel};
+pub use input::keyboard::{WidgetReceivedCharacter, KeyboardInput, UnhandledKeyboardInput};
+pub use layout::{Layout, LayoutVars, LayoutRef, LayoutContainer};
+pub use layout::constraint::*;
+pub use style::{Component, ComponentStyle, WidgetModifier, DrawStyle, Theme, StyleScope};
+pub use render::RenderBuilder;
+pub use resources::{resources, Resources};
+pub use geometry::{Point, Rect, RectExt, Size, SizeExt, Vector};
+pub use color::Color;
+
+/// A collection of widgets provided by a third party crate
+pub trait WidgetLibrary {
+    /// Unique name of the library, used as the name of its style scope
+    fn name(&self) -> &str;
+    /// Register the type styles of the library's draw states, and its scoped class styles.
+    /// Called once by `register_library`. Apps that switch themes by replacing `Resources::theme`
+    /// need to call it again on the new theme, ex. from each theme added to a `ThemePreview`.
+    fn register_styles(&self, theme: &mut Theme);
+    /// Load any fonts or images the library's widgets use
+    fn load_resources(&self, _: &mut Resources) {}
+}
+
+/// Make a widget library's styles and resources available, call before building any of its widgets
+pub fn register_library<L: WidgetLibrary>(library: &L) {
+    debug!("registering widget library {}", library.name());
+    let mut resources = resources();
+    library.load_resources(&mut resources);
+    library.register_styles(&mut resources.theme);
+}
@@ -0,0 +1,400 @@
+//! Formatting and parsing numbers, percentages and amounts of money for display, following the
+//! conventions of the user's locale, so every widget showing numbers shows them the same way.
+//!
+//! The locale is global, like the clock: it's read from the environment at startup, and can be
+//! replaced with `set_locale`, ex. from an app setting. A `NumberFormat` describes how a value
+//! should look, ex. a percentage with one decimal, and formats with the current locale unless
+//! given one of its own.
+//!
+//! Parsing is lenient, since it's for text typed by users: group separators and spaces are accepted
+//! anywhere they'd be written, as are currency symbols when parsing money and a percent sign when
+//! parsing a percentage, negatives can be written with a minus sign or in parentheses, and where
+//! it's unambiguous a `.` or `,` is taken as the decimal separator even if the locale uses the other
+//! one. Text with a currency symbol or percent sign isn't a plain number, so it isn't parsed as one,
+//! rather than losing what the symbol meant.
+
+use std::env;
+use std::sync::RwLock;
+
+lazy_static! {
+    static ref LOCALE: RwLock<NumberLocale> = RwLock::new(NumberLocale::from_env());
+}
+
+/// The locale numbers are formatted in, unless a `NumberFormat` has its own
+pub fn locale() -> NumberLocale {
+    LOCALE.read().unwrap().clone()
+}
+
+pub fn set_locale(locale: NumberLocale) {
+    *LOCALE.write().unwrap() = locale;
+}
+
+/// How numbers are written in a locale
+#[derive(Debug, Clone, PartialEq)]
+pub struct NumberLocale {
+    /// The language of the locale, ex. `fr`, for widgets putting words around numbers
+    pub language: String,
+    pub decimal: char,
+    /// Separates groups of three digits in the integer part
+    pub group: char,
+    /// Whether the currency symbol is written before the amount, ex. `$5` rather than `5 €`
+    pub currency_before: bool,
+    /// Whether the currency symbol is separated from the amount by a space
+    pub currency_space: bool,
+    /// Whether the percent sign is separated from the number by a space, ex. `50 %`
+    pub percent_space: bool,
+}
+
+impl Default for NumberLocale {
+    fn default() -> Self {
+        NumberLocale {
+            language: "en".to_owned(),
+            decimal: '.',
+            group: ',',
+            currency_before: true,
+            currency_space: false,
+            percent_space: false,
+        }
+    }
+}
+
+impl NumberLocale {
+    /// The conventions of a locale, from a tag like `de-DE`, `fr_CA.UTF-8` or just `ja`. Unknown
+    /// locales get the default, English conventions.
+    pub fn from_tag(tag: &str) -> Self {
+        let tag = tag.split(|c| c == '.' || c == '@').next().unwrap_or("").to_lowercase().replace('_', "-");
+        let mut parts = tag.split('-');
+        let language = parts.next().unwrap_or("");
+        let region = parts.next().unwrap_or("");
+        // symbols written before the amount are only spaced from it outside East Asia and English
+        let spaced = !["en", "ja", "zh", "ko"].contains(&language);
+        let locale = |decimal, group, currency_before, percent_space| NumberLocale {
+            language: language.to_owned(),
+            decimal: decimal,
+            group: group,
+            currency_before: currency_before,
+            currency_space: !currency_before || spaced,
+            percent_space: percent_space,
+        };
+        match (language, region) {
+            ("de", "ch") | ("it", "ch") | ("fr", "ch") => locale('.', '\'', true, false),
+            ("de", _) => locale(',', '.', false, true),
+            ("fr", _) => locale(',', '\u{202f}', false, true),
+            ("pt", "br") => locale(',', '.', true, false),
+            ("nl", _) => locale(',', '.', true, false),
+            ("es", _) | ("it", _) | ("pt", _) | ("id", _) | ("tr", _) => locale(',', '.', false, false),
+            ("ru", _) | ("uk", _) | ("pl", _) | ("cs", _) | ("sk", _) | ("sv", _) | ("nb", _) |
+            ("no", _) | ("fi", _) | ("hu", _) => locale(',', '\u{a0}', false, true),
+            ("", _) | ("c", _) | ("posix", _) => NumberLocale::default(),
+            _ => NumberLocale { language: language.to_owned(), ..NumberLocale::default() },
+        }
+    }
+    /// The locale of the environment, from `LC_ALL`, `LC_NUMERIC` or `LANG`
+    pub fn from_env() -> Self {
+        ["LC_ALL", "LC_NUMERIC", "LANG"].iter()
+            .filter_map(|var| env::var(var).ok())
+            .find(|tag| !tag.is_empty())
+            .map_or_else(NumberLocale::default, |tag| NumberLocale::from_tag(&tag))
+    }
+}
+
+/// What kind of value a number is
+#[derive(Debug, Clone, PartialEq)]
+pub enum NumberStyle {
+    Decimal,
+    /// A fraction shown as a percentage, 0.5 is shown as 50%
+    Percent,
+    /// An amount of money, with the currency symbol
+    Currency(String),
+}
+
+/// How a number is shown, see the [module docs](index.html)
+#[derive(Debug, Clone)]
+pub struct NumberFormat {
+    style: NumberStyle,
+    min_decimals: usize,
+    max_decimals: usize,
+    grouping: bool,
+    locale: Option<NumberLocale>,
+}
+
+impl Default for NumberFormat {
+    fn default() -> Self {
+        NumberFormat {
+            style: NumberStyle::Decimal,
+            min_decimals: 0,
+            max_decimals: 3,
+            grouping: true,
+            locale: None,
+        }
+    }
+}
+
+impl NumberFormat {
+    pub fn new() -> Self {
+        Self::default()
+    }
+    /// A percentage with no decimals
+    pub fn percent() -> Self {
+        NumberFormat {
+            style: NumberStyle::Percent,
+            max_decimals: 0,
+            ..NumberFormat::default()
+        }
+    }
+    /// An amount of money with two decimals
+    pub fn currency(symbol: &str) -> Self {
+        NumberFormat {
+            style: NumberStyle::Currency(symbol.to_owned()),
+            min_decimals: 2,
+            max_decimals: 2,
+            ..NumberFormat::default()
+        }
+    }
+    pub fn set_style(&mut self, style: NumberStyle) -> &mut Self {
+        self.style = style;
+        self
+    }
+    /// Show at least `min` decimals and round to `max`, trailing zeros past `min` are dropped
+    pub fn set_decimals(&mut self, min: usize, max: usize) -> &mut Self {
+        self.min_decimals = min;
+        self.max_decimals = max.max(min);
+        self
+    }
+    /// Separate groups of digits in the integer part, on by default
+    pub fn set_grouping(&mut self, grouping: bool) -> &mut Self {
+        self.grouping = grouping;
+        self
+    }
+    /// Use `locale` rather than the global locale
+    pub fn set_locale(&mut self, locale: NumberLocale) -> &mut Self {
+        self.locale = Some(locale);
+        self
+    }
+
+    fn with_locale<T, F: FnOnce(&NumberLocale) -> T>(&self, f: F) -> T {
+        match self.locale {
+            Some(ref locale) => f(locale),
+            None => f(&LOCALE.read().unwrap()),
+        }
+    }
+
+    pub fn format(&self, value: f64) -> String {
+        self.with_locale(|locale| self.format_in(value, locale))
+    }
+    fn format_in(&self, value: f64, locale: &NumberLocale) -> String {
+        if value.is_nan() {
+            return "NaN".to_owned();
+        }
+        let value = if self.style == NumberStyle::Percent { value * 100.0 } else { value };
+        let digits = if value.is_infinite() {
+            "∞".to_owned()
+        } else {
+            let rounded = format!("{:.*}", self.max_decimals, value.abs());
+            let mut parts = rounded.splitn(2, '.');
+            let integer = parts.next().unwrap_or("0");
+            let mut fraction = parts.next().unwrap_or("").to_owned();
+            while fraction.len() > self.min_decimals && fraction.ends_with('0') {
+                fraction.pop();
+            }
+            let mut digits = String::new();
+            for (i, digit) in integer.chars().enumerate() {
+                if self.grouping && i > 0 && (integer.len() - i) % 3 == 0 {
+                    digits.push(locale.group);
+                }
+                digits.push(digit);
+            }
+            if !fraction.is_empty() {
+                digits.push(locale.decimal);
+                digits.push_str(&fraction);
+            }
+            digits
+        };
+        // don't show "-0" for small negatives rounded to zero
+        let negative = value < 0.0 && (value.is_infinite() || digits.chars().any(|c| c.is_digit(10) && c != '0'));
+        let sign = if negative { "-" } else { "" };
+        match self.style {
+            NumberStyle::Decimal => format!("{}{}", sign, digits),
+            NumberStyle::Percent => {
+                let space = if locale.percent_space { "\u{a0}" } else { "" };
+                format!("{}{}{}%", sign, digits, space)
+            }
+            NumberStyle::Currency(ref symbol) => {
+                let space = if locale.currency_space { "\u{a0}" } else { "" };
+                if locale.currency_before {
+                    format!("{}{}{}{}", sign, symbol, space, digits)
+                } else {
+                    format!("{}{}{}{}", sign, digits, space, symbol)
+                }
+            }
+        }
+    }
+
+    /// Parse a number typed by the user, see the [module docs](index.html). Percentages are
+    /// returned as fractions, so that parsing what `format` returns gives back the value.
+    pub fn parse(&self, text: &str) -> Option<f64> {
+        self.with_locale(|locale| self.parse_in(text, locale))
+    }
+    fn parse_in(&self, text: &str, locale: &NumberLocale) -> Option<f64> {
+        let mut text = text.trim().to_owned();
+        if let NumberStyle::Currency(ref symbol) = self.style {
+            text = text.replace(symbol.as_str(), "");
+        }
+        let mut negative = false;
+        let percent = self.style == NumberStyle::Percent;
+        let currency = match self.style {
+            NumberStyle::Currency(_) => true,
+            _ => false,
+        };
+        let chars: Vec<char> = text.chars().collect();
+        let mut number = String::new();
+        let mut decimal_seen = false;
+        let mut i = 0;
+        while i < chars.len() {
+            let c = chars[i];
+            match c {
+                c if c.is_digit(10) => number.push(c),
+                '-' | '\u{2212}' | '(' | ')' if number.is_empty() || i == chars.len() - 1 => negative = true,
+                '+' if number.is_empty() => (),
+                '%' if percent => (),
+                'e' | 'E' if !number.is_empty() => {
+                    number.push('e');
+                    if i + 1 < chars.len() && (chars[i + 1] == '-' || chars[i + 1] == '+') {
+                        number.push(chars[i + 1]);
+                        i += 1;
+                    }
+                }
+                '$' | '€' | '£' | '¥' | '₹' if currency => (),
+                c if c.is_whitespace() || c == '\u{a0}' || c == '\u{202f}' || c == '\'' => (),
+                c if c == locale.decimal && !decimal_seen => {
+                    decimal_seen = true;
+                    number.push('.');
+                }
+                '.' | ',' => {
+                    // a group separator followed by a group of three digits is taken as grouping,
+                    // any other separator as the decimal separator
+                    let group_digits = chars[i + 1..].iter().take_while(|c| c.is_digit(10)).count();
+                    if decimal_seen {
+                        return None;
+                    } else if c != locale.group || group_digits != 3 {
+                        decimal_seen = true;
+                        number.push('.');
+                    }
+                }
+                _ => return None,
+            }
+            i += 1;
+        }
+        if !number.chars().any(|c| c.is_digit(10)) {
+            return None;
+        }
+        let value: f64 = number.parse().ok()?;
+        let value = if negative { -value } else { value };
+        Some(if percent { value / 100.0 } else { value })
+    }
+}
+
+/// Format a number with the default format and the current locale
+pub fn number(value: f64) -> String {
+    NumberFormat::new().format(value)
+}
+
+/// Parse a number typed by the user with the current locale, see `NumberFormat::parse`
+pub fn parse_number(text: &str) -> Option<f64> {
+    NumberFormat::new().parse(text)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn in_locale(tag: &str, format: &mut NumberFormat) -> NumberFormat {
+        format.set_locale(NumberLocale::from_tag(tag)).clone()
+    }
+
+    const LOCALES: &[&str] = &["en-US", "de-DE", "de-CH", "fr-FR", "pt-BR", "ru-RU", "ja-JP"];
+
+    #[test]
+    fn round_trip() {
+        let values = [0.0, 0.5, -0.5, 7.0, 999.0, 1000.0, -1000.0, 100_000.0, 1_234_567.5, -1_234_567.5, 0.25];
+        for tag in LOCALES {
+            let formats = [
+                in_locale(tag, &mut NumberFormat::new()),
+                in_locale(tag, NumberFormat::new().set_grouping(false)),
+                in_locale(tag, NumberFormat::percent().set_decimals(0, 2)),
+                in_locale(tag, &mut NumberFormat::currency("€")),
+                in_locale(tag, &mut NumberFormat::currency("CHF")),
+            ];
+            for format in &formats {
+                for &value in &values {
+                    let text = format.format(value);
+                    assert_eq!(format.parse(&text), Some(value), "{} in {} via {:?}", value, tag, text);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn format_in_locales() {
+        let number = |tag| in_locale(tag, &mut NumberFormat::new()).format(-1_234_567.5);
+        assert_eq!(number("en-US"), "-1,234,567.5");
+        assert_eq!(number("de-DE"), "-1.234.567,5");
+        assert_eq!(number("de-CH"), "-1'234'567.5");
+        assert_eq!(number("fr-FR"), "-1\u{202f}234\u{202f}567,5");
+        assert_eq!(number("ru-RU"), "-1\u{a0}234\u{a0}567,5");
+        assert_eq!(in_locale("en-US", &mut NumberFormat::currency("$")).format(-5.0), "-$5.00");
+        assert_eq!(in_locale("de-DE", &mut NumberFormat::currency("€")).format(5.0), "5,00\u{a0}€");
+        assert_eq!(in_locale("fr-FR", &mut NumberFormat::percent()).format(0.5), "50\u{a0}%");
+        // small negatives rounded to zero don't keep their sign
+        assert_eq!(in_locale("en-US", &mut NumberFormat::new()).format(-0.0001), "0");
+    }
+
+    #[test]
+    fn grouping() {
+        let en = in_locale("en-US", &mut NumberFormat::new());
+        assert_eq!(en.format(999.0), "999");
+        assert_eq!(en.format(1000.0), "1,000");
+        assert_eq!(en.format(999_999.0), "999,999");
+        assert_eq!(en.format(1_000_000.0), "1,000,000");
+        assert_eq!(en.parse("1,234"), Some(1234.0));
+        assert_eq!(en.parse("1,234,567.25"), Some(1_234_567.25));
+        // a separator not followed by exactly three digits isn't grouping
+        assert_eq!(en.parse("1,5"), Some(1.5));
+        assert_eq!(en.parse("1,2345"), Some(1.2345));
+        let de = in_locale("de-DE", &mut NumberFormat::new());
+        assert_eq!(de.parse("1.234"), Some(1234.0));
+        assert_eq!(de.parse("1.5"), Some(1.5));
+        assert_eq!(de.parse("1 234,5"), Some(1234.5));
+    }
+
+    #[test]
+    fn negatives() {
+        let en = in_locale("en-US", &mut NumberFormat::new());
+        assert_eq!(en.parse("-12"), Some(-12.0));
+        assert_eq!(en.parse("\u{2212}12"), Some(-12.0));
+        assert_eq!(en.parse("(12)"), Some(-12.0));
+        assert_eq!(en.parse("12-"), Some(-12.0));
+        assert_eq!(en.parse("+12"), Some(12.0));
+        assert_eq!(in_locale("en-US", &mut NumberFormat::currency("$")).parse("($5.00)"), Some(-5.0));
+    }
+
+    #[test]
+    fn rejected() {
+        let en = in_locale("en-US", &mut NumberFormat::new());
+        for text in &["", " ", "-", "abc", "12abc", "e5", "1.2.3", "1,234.5.6", "1-2", "--"] {
+            assert_eq!(en.parse(text), None, "{:?}", text);
+        }
+    }
+
+    #[test]
+    fn symbols_only_for_their_style() {
+        let en = in_locale("en-US", &mut NumberFormat::new());
+        assert_eq!(en.parse("50%"), None);
+        assert_eq!(en.parse("$5"), None);
+        assert_eq!(en.parse("5 €"), None);
+        assert_eq!(in_locale("en-US", &mut NumberFormat::percent()).parse("50%"), Some(0.5));
+        assert_eq!(in_locale("en-US", &mut NumberFormat::percent()).parse("50"), Some(0.5));
+        assert_eq!(in_locale("en-US", &mut NumberFormat::currency("$")).parse("$5"), Some(5.0));
+        assert_eq!(in_locale("en-US", &mut NumberFormat::currency("$")).parse("5%"), None);
+    }
+}
@@ -39,6 +39,9 @@ pub trait RectExt<T> {
     fn height(&self) -> T;
     fn center(&self) -> Point;
     fn shrink_bounds(&self, size: T) -> Self;
+    /// Move each edge to the nearest device pixel boundary, when there are `scale` device pixels
+    /// to a `DensityIndependentPixel`, so edges stay sharp at fractional scales like 1.25 or 1.5
+    fn snap_to_device(&self, scale: T) -> Self;
 }
 impl RectExt<f32> for Rect {
     fn from_rusttype<S: Into<f32>>(rect: rusttype::Rect<S>) -> Self {
@@ -75,6 +78,11 @@ impl RectExt<f32> for Rect {
             Point::new(self.origin.x + size / 2.0, self.origin.y + size / 2.0),
             Size::new(self.size.width - size, self.size.height - size))
     }
+    fn snap_to_device(&self, scale: f32) -> Self {
+        let snap = |value: f32| (value * scale).round() / scale;
+        let (left, top) = (snap(self.left()), snap(self.top()));
+        Rect::new(Point::new(left, top), Size::new(snap(self.right()) - left, snap(self.bottom()) - top))
+    }
 }
 
 /// Extension trait for sizes.
@@ -0,0 +1,159 @@
+//! System-wide hotkeys, pressed while any app has focus, ex. to take a screenshot or show a
+//! launcher. Needs the `global-hotkeys` feature.
+//!
+//! Each hotkey is registered with the OS from a background thread, and a `HotkeyPressed` event is
+//! sent to the root widget when it's pressed, even if the window is unfocused or minimized.
+//! A registered hotkey is taken from every other app, so only register hotkeys the user has chosen
+//! or can turn off.
+//!
+//! Media keys can be captured with `register_media_keys`, so a media player can be controlled
+//! from the background. Presses are sent as `MediaKey` events as well as `HotkeyPressed`.
+//!
+//! Supported on Windows, and on Linux and the BSDs under X11, including XWayland. Wayland has no
+//! protocol for global hotkeys, and macOS isn't supported yet, `register` fails with
+//! `HotkeyError::Unsupported` on both.
+
+#[cfg(all(unix, not(target_os = "macos")))]
+mod x11;
+#[cfg(windows)]
+mod windows;
+
+use std::collections::HashMap;
+use std::sync::Mutex;
+use std::sync::atomic::{AtomicUsize, Ordering, ATOMIC_USIZE_INIT};
+use std::sync::mpsc::{self, Sender, Receiver};
+
+use failure::Error;
+use glutin::{VirtualKeyCode, ModifiersState};
+
+use input::commands::MediaKey;
+
+#[cfg(all(unix, not(target_os = "macos")))]
+use self::x11 as platform;
+#[cfg(windows)]
+use self::windows as platform;
+
+/// A key and the modifiers held with it
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct Hotkey {
+    pub key: VirtualKeyCode,
+    /// The logo modifier is the Windows or Super key
+    pub modifiers: ModifiersState,
+}
+
+impl Hotkey {
+    pub fn new(key: VirtualKeyCode, modifiers: ModifiersState) -> Self {
+        Hotkey {
+            key: key,
+            modifiers: modifiers,
+        }
+    }
+}
+
+/// Identifies a hotkey registered with `register`
+#[derive(Copy, Clone, Debug, Hash, PartialEq, Eq, PartialOrd, Ord)]
+pub struct HotkeyId(pub usize);
+
+/// Sent to the root widget when a registered hotkey is pressed
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct HotkeyPressed(pub HotkeyId);
+
+#[derive(Debug, Fail)]
+pub enum HotkeyError {
+    #[fail(display = "global hotkeys aren't supported on this platform")]
+    Unsupported,
+    #[fail(display = "{:?} can't be used as a global hotkey", _0)]
+    UnsupportedKey(VirtualKeyCode),
+    #[fail(display = "hotkey is already registered by another app")]
+    InUse,
+}
+
+enum Request {
+    Register(HotkeyId, Hotkey, Sender<Result<(), HotkeyError>>),
+    Unregister(HotkeyId),
+}
+
+/// Handle to the thread the hotkeys are registered on
+struct Backend {
+    requests: Sender<Request>,
+    /// Wake the thread to handle new requests
+    wake: Box<Fn() + Send>,
+}
+
+lazy_static! {
+    static ref BACKEND: Mutex<Option<Backend>> = Mutex::new(None);
+    static ref MEDIA_KEYS: Mutex<HashMap<HotkeyId, MediaKey>> = Mutex::new(HashMap::new());
+}
+
+static NEXT_ID: AtomicUsize = ATOMIC_USIZE_INIT;
+
+/// Register a system-wide hotkey, returning an id identifying it in `HotkeyPressed` events.
+/// Can be called from any thread.
+pub fn register(hotkey: Hotkey) -> Result<HotkeyId, Error> {
+    let mut backend = BACKEND.lock().unwrap();
+    if backend.is_none() {
+        let (requests, receiver) = mpsc::channel();
+        let wake = start(receiver)?;
+        *backend = Some(Backend { requests: requests, wake: wake });
+    }
+    let backend = backend.as_ref().unwrap();
+    let id = HotkeyId(NEXT_ID.fetch_add(1, Ordering::Relaxed));
+    let (reply, result) = mpsc::channel();
+    backend.requests.send(Request::Register(id, hotkey, reply)).unwrap();
+    (backend.wake)();
+    result.recv().unwrap_or(Err(HotkeyError::Unsupported))?;
+    Ok(id)
+}
+
+/// Unregister a hotkey, releasing it for other apps
+pub fn unregister(id: HotkeyId) {
+    if let Some(ref backend) = *BACKEND.lock().unwrap() {
+        backend.requests.send(Request::Unregister(id)).unwrap();
+        (backend.wake)();
+    }
+}
+
+/// Register every media key as a hotkey, so `MediaKey` events are sent while the app is in the
+/// background. Keys that can't be registered, ex. because another player has them, are skipped,
+/// the media keys that were registered are returned.
+pub fn register_media_keys() -> Result<Vec<MediaKey>, Error> {
+    let keys = [MediaKey::PlayPause, MediaKey::Stop, MediaKey::NextTrack, MediaKey::PreviousTrack,
+                MediaKey::VolumeUp, MediaKey::VolumeDown, MediaKey::Mute];
+    let mut registered = Vec::new();
+    for &media_key in &keys {
+        match register(Hotkey::new(media_key.key(), ModifiersState::default())) {
+            Ok(id) => {
+                MEDIA_KEYS.lock().unwrap().insert(id, media_key);
+                registered.push(media_key);
+            }
+            Err(err) => {
+                if let Some(&HotkeyError::Unsupported) = err.downcast_ref::<HotkeyError>() {
+                    return Err(err);
+                }
+            }
+        }
+    }
+    Ok(registered)
+}
+
+/// Unregister the media keys registered with `register_media_keys`
+pub fn unregister_media_keys() {
+    for (id, _) in MEDIA_KEYS.lock().unwrap().drain() {
+        unregister(id);
+    }
+}
+
+/// The media key registered as hotkey `id`, if any
+pub(crate) fn media_key(id: HotkeyId) -> Option<MediaKey> {
+    MEDIA_KEYS.lock().unwrap().get(&id).cloned()
+}
+
+#[cfg(any(windows, all(unix, not(target_os = "macos"))))]
+fn start(requests: Receiver<Request>) -> Result<Box<Fn() + Send>, HotkeyError> {
+    platform::start(requests)
+}
+
+#[cfg(not(any(windows, all(unix, not(target_os = "macos")))))]
+fn start(_: Receiver<Request>) -> Result<Box<Fn() + Send>, HotkeyError> {
+    Err(HotkeyError::Unsupported)
+}
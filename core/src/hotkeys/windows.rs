@@ -0,0 +1,151 @@
+//! Hotkeys on Windows, registered with `RegisterHotKey` for a thread rather than a window.
+//!
+//! Hotkey messages go to the thread that registered the hotkey, so the hotkey thread runs its own
+//! message loop, and is woken to handle requests with a thread message.
+
+use std::os::raw::{c_int, c_uint, c_void};
+use std::ptr;
+use std::sync::mpsc::{self, Receiver};
+use std::thread;
+
+use glutin::{VirtualKeyCode, ModifiersState};
+
+use event::event_global;
+use super::{HotkeyId, HotkeyPressed, HotkeyError, Request};
+
+type Hwnd = *mut c_void;
+type Bool = c_int;
+
+const WM_USER: c_uint = 0x0400;
+const WM_HOTKEY: c_uint = 0x0312;
+/// Sent to the hotkey thread when there are requests waiting
+const WM_HOTKEY_REQUEST: c_uint = WM_USER + 1;
+const PM_NOREMOVE: c_uint = 0;
+const MOD_ALT: c_uint = 0x1;
+const MOD_CONTROL: c_uint = 0x2;
+const MOD_SHIFT: c_uint = 0x4;
+const MOD_WIN: c_uint = 0x8;
+/// Only report the first press while the key is held
+const MOD_NOREPEAT: c_uint = 0x4000;
+
+#[repr(C)]
+struct Point {
+    x: i32,
+    y: i32,
+}
+
+#[repr(C)]
+struct Msg {
+    hwnd: Hwnd,
+    message: c_uint,
+    wparam: usize,
+    lparam: isize,
+    time: u32,
+    pt: Point,
+}
+
+#[link(name = "user32")]
+extern "system" {
+    fn RegisterHotKey(hwnd: Hwnd, id: c_int, modifiers: c_uint, vk: c_uint) -> Bool;
+    fn UnregisterHotKey(hwnd: Hwnd, id: c_int) -> Bool;
+    fn GetMessageW(msg: *mut Msg, hwnd: Hwnd, filter_min: c_uint, filter_max: c_uint) -> Bool;
+    fn PeekMessageW(msg: *mut Msg, hwnd: Hwnd, filter_min: c_uint, filter_max: c_uint, remove: c_uint) -> Bool;
+    fn PostThreadMessageW(thread_id: u32, msg: c_uint, wparam: usize, lparam: isize) -> Bool;
+}
+
+#[link(name = "kernel32")]
+extern "system" {
+    fn GetCurrentThreadId() -> u32;
+}
+
+pub(super) fn start(requests: Receiver<Request>) -> Result<Box<Fn() + Send>, HotkeyError> {
+    let (started, thread_id) = mpsc::channel();
+    thread::spawn(move || {
+        let mut msg: Msg = unsafe { ::std::mem::zeroed() };
+        // a thread only gets a message queue once it looks for messages, and can't be woken until then
+        unsafe { PeekMessageW(&mut msg, ptr::null_mut(), WM_USER, WM_USER, PM_NOREMOVE) };
+        started.send(unsafe { GetCurrentThreadId() }).unwrap();
+        while unsafe { GetMessageW(&mut msg, ptr::null_mut(), 0, 0) } > 0 {
+            match msg.message {
+                WM_HOTKEY => event_global(HotkeyPressed(HotkeyId(msg.wparam))),
+                WM_HOTKEY_REQUEST => {
+                    for request in requests.try_iter() {
+                        match request {
+                            Request::Register(id, hotkey, reply) => {
+                                let result = match vk(hotkey.key) {
+                                    Some(vk) => {
+                                        let modifiers = modifier_flags(hotkey.modifiers) | MOD_NOREPEAT;
+                                        if unsafe { RegisterHotKey(ptr::null_mut(), id.0 as c_int, modifiers, vk) } != 0 {
+                                            Ok(())
+                                        } else {
+                                            Err(HotkeyError::InUse)
+                                        }
+                                    }
+                                    None => Err(HotkeyError::UnsupportedKey(hotkey.key)),
+                                };
+                                reply.send(result).ok();
+                            }
+                            Request::Unregister(id) => {
+                                unsafe { UnregisterHotKey(ptr::null_mut(), id.0 as c_int) };
+                            }
+                        }
+                    }
+                }
+                _ => (),
+            }
+        }
+    });
+    let thread_id = thread_id.recv().map_err(|_| HotkeyError::Unsupported)?;
+    Ok(Box::new(move || {
+        unsafe { PostThreadMessageW(thread_id, WM_HOTKEY_REQUEST, 0, 0) };
+    }))
+}
+
+fn modifier_flags(modifiers: ModifiersState) -> c_uint {
+    let mut flags = 0;
+    if modifiers.shift { flags |= MOD_SHIFT; }
+    if modifiers.ctrl { flags |= MOD_CONTROL; }
+    if modifiers.alt { flags |= MOD_ALT; }
+    if modifiers.logo { flags |= MOD_WIN; }
+    flags
+}
+
+fn vk(key: VirtualKeyCode) -> Option<c_uint> {
+    use glutin::VirtualKeyCode::*;
+    let vk = match key {
+        A => 0x41, B => 0x42, C => 0x43, D => 0x44, E => 0x45, F => 0x46, G => 0x47,
+        H => 0x48, I => 0x49, J => 0x4a, K => 0x4b, L => 0x4c, M => 0x4d, N => 0x4e,
+        O => 0x4f, P => 0x50, Q => 0x51, R => 0x52, S => 0x53, T => 0x54, U => 0x55,
+        V => 0x56, W => 0x57, X => 0x58, Y => 0x59, Z => 0x5a,
+        Key0 => 0x30, Key1 => 0x31, Key2 => 0x32, Key3 => 0x33, Key4 => 0x34,
+        Key5 => 0x35, Key6 => 0x36, Key7 => 0x37, Key8 => 0x38, Key9 => 0x39,
+        F1 => 0x70, F2 => 0x71, F3 => 0x72, F4 => 0x73, F5 => 0x74, F6 => 0x75,
+        F7 => 0x76, F8 => 0x77, F9 => 0x78, F10 => 0x79, F11 => 0x7a, F12 => 0x7b,
+        Space => 0x20,
+        Escape => 0x1b,
+        Return => 0x0d,
+        Tab => 0x09,
+        Back => 0x08,
+        Insert => 0x2d,
+        Delete => 0x2e,
+        Home => 0x24,
+        End => 0x23,
+        PageUp => 0x21,
+        PageDown => 0x22,
+        Left => 0x25,
+        Up => 0x26,
+        Right => 0x27,
+        Down => 0x28,
+        Snapshot => 0x2c,
+        Pause => 0x13,
+        Mute => 0xad,
+        VolumeDown => 0xae,
+        VolumeUp => 0xaf,
+        NextTrack => 0xb0,
+        PrevTrack => 0xb1,
+        MediaStop => 0xb2,
+        PlayPause => 0xb3,
+        _ => return None,
+    };
+    Some(vk)
+}
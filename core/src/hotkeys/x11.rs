@@ -0,0 +1,239 @@
+//! Hotkeys on X11, grabbing keys on the root window with Xlib.
+//!
+//! Xlib isn't initialized for threads, so the hotkey thread opens its own connection to the
+//! display and nothing else uses it.
+
+use std::os::raw::{c_char, c_int, c_long, c_uchar, c_uint, c_ulong, c_void};
+use std::ptr;
+use std::sync::atomic::{AtomicBool, Ordering, ATOMIC_BOOL_INIT};
+use std::sync::mpsc::{self, Receiver, TryRecvError};
+use std::thread;
+use std::time::Duration;
+
+use glutin::{VirtualKeyCode, ModifiersState};
+
+use event::event_global;
+use super::{Hotkey, HotkeyId, HotkeyPressed, HotkeyError, Request};
+
+/// How often the thread checks for new requests, while no keys are pressed
+const POLL_INTERVAL_MS: u64 = 20;
+
+type Display = c_void;
+type Window = c_ulong;
+type KeySym = c_ulong;
+type Bool = c_int;
+type ErrorHandler = Option<unsafe extern "C" fn(*mut Display, *mut c_void) -> c_int>;
+
+const KEY_PRESS: c_int = 2;
+const GRAB_MODE_ASYNC: c_int = 1;
+const SHIFT_MASK: c_uint = 1 << 0;
+const LOCK_MASK: c_uint = 1 << 1;
+const CONTROL_MASK: c_uint = 1 << 2;
+const MOD1_MASK: c_uint = 1 << 3;
+const MOD2_MASK: c_uint = 1 << 4;
+const MOD4_MASK: c_uint = 1 << 6;
+/// Caps lock and num lock, which shouldn't stop a hotkey from working
+const IGNORED_MASKS: [c_uint; 4] = [0, LOCK_MASK, MOD2_MASK, LOCK_MASK | MOD2_MASK];
+
+#[repr(C)]
+struct XKeyEvent {
+    kind: c_int,
+    serial: c_ulong,
+    send_event: Bool,
+    display: *mut Display,
+    window: Window,
+    root: Window,
+    subwindow: Window,
+    time: c_ulong,
+    x: c_int,
+    y: c_int,
+    x_root: c_int,
+    y_root: c_int,
+    state: c_uint,
+    keycode: c_uint,
+    same_screen: Bool,
+}
+
+/// `XEvent` is a union padded to 24 longs
+#[repr(C)]
+struct XEvent {
+    pad: [c_long; 24],
+}
+
+#[link(name = "X11")]
+extern "C" {
+    fn XOpenDisplay(name: *const c_char) -> *mut Display;
+    fn XDefaultRootWindow(display: *mut Display) -> Window;
+    fn XKeysymToKeycode(display: *mut Display, keysym: KeySym) -> c_uchar;
+    fn XGrabKey(display: *mut Display, keycode: c_int, modifiers: c_uint, window: Window,
+                owner_events: Bool, pointer_mode: c_int, keyboard_mode: c_int) -> c_int;
+    fn XUngrabKey(display: *mut Display, keycode: c_int, modifiers: c_uint, window: Window) -> c_int;
+    fn XPending(display: *mut Display) -> c_int;
+    fn XNextEvent(display: *mut Display, event: *mut XEvent) -> c_int;
+    fn XSync(display: *mut Display, discard: Bool) -> c_int;
+    fn XSetErrorHandler(handler: ErrorHandler) -> ErrorHandler;
+}
+
+/// Set by the error handler while grabbing, if the key is already grabbed by another client
+static GRAB_FAILED: AtomicBool = ATOMIC_BOOL_INIT;
+
+unsafe extern "C" fn grab_error(_: *mut Display, _: *mut c_void) -> c_int {
+    GRAB_FAILED.store(true, Ordering::SeqCst);
+    0
+}
+
+struct Grab {
+    id: HotkeyId,
+    keycode: c_int,
+    modifiers: c_uint,
+}
+
+struct Grabs {
+    display: *mut Display,
+    root: Window,
+    grabs: Vec<Grab>,
+}
+
+impl Grabs {
+    fn register(&mut self, id: HotkeyId, hotkey: Hotkey) -> Result<(), HotkeyError> {
+        let keysym = keysym(hotkey.key).ok_or(HotkeyError::UnsupportedKey(hotkey.key))?;
+        let keycode = unsafe { XKeysymToKeycode(self.display, keysym) } as c_int;
+        if keycode == 0 {
+            return Err(HotkeyError::UnsupportedKey(hotkey.key));
+        }
+        let modifiers = modifier_mask(hotkey.modifiers);
+        unsafe {
+            // the error handler is global, so is only replaced while grabbing
+            GRAB_FAILED.store(false, Ordering::SeqCst);
+            let previous = XSetErrorHandler(Some(grab_error));
+            for ignored in &IGNORED_MASKS {
+                XGrabKey(self.display, keycode, modifiers | ignored, self.root, 0, GRAB_MODE_ASYNC, GRAB_MODE_ASYNC);
+            }
+            XSync(self.display, 0);
+            XSetErrorHandler(previous);
+        }
+        let grab = Grab { id: id, keycode: keycode, modifiers: modifiers };
+        if GRAB_FAILED.load(Ordering::SeqCst) {
+            self.ungrab(&grab);
+            return Err(HotkeyError::InUse);
+        }
+        self.grabs.push(grab);
+        Ok(())
+    }
+
+    fn unregister(&mut self, id: HotkeyId) {
+        if let Some(index) = self.grabs.iter().position(|grab| grab.id == id) {
+            let grab = self.grabs.remove(index);
+            self.ungrab(&grab);
+        }
+    }
+
+    fn ungrab(&self, grab: &Grab) {
+        unsafe {
+            for ignored in &IGNORED_MASKS {
+                XUngrabKey(self.display, grab.keycode, grab.modifiers | ignored, self.root);
+            }
+            XSync(self.display, 0);
+        }
+    }
+
+    fn key_pressed(&self, event: &XKeyEvent) {
+        let modifiers = event.state & !(LOCK_MASK | MOD2_MASK);
+        for grab in &self.grabs {
+            if grab.keycode == event.keycode as c_int && grab.modifiers == modifiers {
+                event_global(HotkeyPressed(grab.id));
+            }
+        }
+    }
+}
+
+pub(super) fn start(requests: Receiver<Request>) -> Result<Box<Fn() + Send>, HotkeyError> {
+    let (started, result) = mpsc::channel();
+    thread::spawn(move || {
+        let display = unsafe { XOpenDisplay(ptr::null()) };
+        if display.is_null() {
+            // no X server, ex. a Wayland session without XWayland
+            started.send(Err(HotkeyError::Unsupported)).unwrap();
+            return;
+        }
+        started.send(Ok(())).unwrap();
+        let mut grabs = Grabs {
+            display: display,
+            root: unsafe { XDefaultRootWindow(display) },
+            grabs: Vec::new(),
+        };
+        loop {
+            loop {
+                match requests.try_recv() {
+                    Ok(Request::Register(id, hotkey, reply)) => {
+                        reply.send(grabs.register(id, hotkey)).ok();
+                    }
+                    Ok(Request::Unregister(id)) => grabs.unregister(id),
+                    Err(TryRecvError::Empty) => break,
+                    Err(TryRecvError::Disconnected) => return,
+                }
+            }
+            while unsafe { XPending(display) } > 0 {
+                let mut event = XEvent { pad: [0; 24] };
+                unsafe { XNextEvent(display, &mut event) };
+                let event = unsafe { &*(&event as *const XEvent as *const XKeyEvent) };
+                if event.kind == KEY_PRESS {
+                    grabs.key_pressed(event);
+                }
+            }
+            thread::sleep(Duration::from_millis(POLL_INTERVAL_MS));
+        }
+    });
+    result.recv().unwrap_or(Err(HotkeyError::Unsupported))?;
+    // the thread polls for requests, so doesn't need waking
+    Ok(Box::new(|| ()))
+}
+
+fn modifier_mask(modifiers: ModifiersState) -> c_uint {
+    let mut mask = 0;
+    if modifiers.shift { mask |= SHIFT_MASK; }
+    if modifiers.ctrl { mask |= CONTROL_MASK; }
+    if modifiers.alt { mask |= MOD1_MASK; }
+    if modifiers.logo { mask |= MOD4_MASK; }
+    mask
+}
+
+fn keysym(key: VirtualKeyCode) -> Option<KeySym> {
+    use glutin::VirtualKeyCode::*;
+    let keysym = match key {
+        A => 0x61, B => 0x62, C => 0x63, D => 0x64, E => 0x65, F => 0x66, G => 0x67,
+        H => 0x68, I => 0x69, J => 0x6a, K => 0x6b, L => 0x6c, M => 0x6d, N => 0x6e,
+        O => 0x6f, P => 0x70, Q => 0x71, R => 0x72, S => 0x73, T => 0x74, U => 0x75,
+        V => 0x76, W => 0x77, X => 0x78, Y => 0x79, Z => 0x7a,
+        Key0 => 0x30, Key1 => 0x31, Key2 => 0x32, Key3 => 0x33, Key4 => 0x34,
+        Key5 => 0x35, Key6 => 0x36, Key7 => 0x37, Key8 => 0x38, Key9 => 0x39,
+        F1 => 0xffbe, F2 => 0xffbf, F3 => 0xffc0, F4 => 0xffc1, F5 => 0xffc2, F6 => 0xffc3,
+        F7 => 0xffc4, F8 => 0xffc5, F9 => 0xffc6, F10 => 0xffc7, F11 => 0xffc8, F12 => 0xffc9,
+        Space => 0x20,
+        Escape => 0xff1b,
+        Return => 0xff0d,
+        Tab => 0xff09,
+        Back => 0xff08,
+        Insert => 0xff63,
+        Delete => 0xffff,
+        Home => 0xff50,
+        End => 0xff57,
+        PageUp => 0xff55,
+        PageDown => 0xff56,
+        Left => 0xff51,
+        Up => 0xff52,
+        Right => 0xff53,
+        Down => 0xff54,
+        Snapshot => 0xff61,
+        Pause => 0xff13,
+        Mute => 0x1008ff12,
+        VolumeDown => 0x1008ff11,
+        VolumeUp => 0x1008ff13,
+        PlayPause => 0x1008ff14,
+        MediaStop => 0x1008ff15,
+        PrevTrack => 0x1008ff16,
+        NextTrack => 0x1008ff17,
+        _ => return None,
+    };
+    Some(keysym)
+}
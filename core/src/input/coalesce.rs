@@ -0,0 +1,200 @@
+//! Merges high frequency window events that arrive between frames, so handlers that do a lot of
+//! work per event don't fall behind a fast mouse.
+//!
+//! Consecutive cursor moves are replaced by the latest one, consecutive scrolls are replaced by
+//! one with the sum of their deltas, and consecutive resizes by the latest size. Any other event
+//! is dispatched after the pending merged event, so the order of events is kept.
+
+use std::mem;
+
+use glutin::{WindowEvent, MouseScrollDelta, TouchPhase};
+use glutin::dpi::LogicalPosition;
+
+pub(crate) struct EventCoalescer {
+    pub enabled: bool,
+    pending: Option<WindowEvent>,
+}
+
+impl Default for EventCoalescer {
+    fn default() -> Self {
+        EventCoalescer {
+            enabled: true,
+            pending: None,
+        }
+    }
+}
+
+impl EventCoalescer {
+    /// Add an event, returning the events that are ready to dispatch, in order
+    pub fn push(&mut self, event: WindowEvent) -> Vec<WindowEvent> {
+        if !self.enabled {
+            let mut events = self.flush();
+            events.push(event);
+            return events;
+        }
+        let event = match self.pending {
+            Some(ref mut pending) => match merge(pending, event) {
+                Ok(()) => return Vec::new(),
+                Err(event) => event,
+            },
+            None => event,
+        };
+        let mut events = self.flush();
+        if coalesces(&event) {
+            self.pending = Some(event);
+        } else {
+            events.push(event);
+        }
+        events
+    }
+    /// Take the pending merged event, if any, ex. once the window has no more events
+    pub fn flush(&mut self) -> Vec<WindowEvent> {
+        mem::replace(&mut self.pending, None).into_iter().collect()
+    }
+}
+
+fn coalesces(event: &WindowEvent) -> bool {
+    match *event {
+        WindowEvent::CursorMoved { .. } |
+        WindowEvent::MouseWheel { phase: TouchPhase::Moved, .. } |
+        WindowEvent::Resized(_) => true,
+        _ => false,
+    }
+}
+
+/// Merge `next` into `pending` if they're the same kind of event, otherwise return `next`
+fn merge(pending: &mut WindowEvent, next: WindowEvent) -> Result<(), WindowEvent> {
+    match (pending, next) {
+        (&mut WindowEvent::CursorMoved { device_id, ref mut position, ref mut modifiers },
+         WindowEvent::CursorMoved { device_id: next_device, position: next_position, modifiers: next_modifiers })
+            if device_id == next_device => {
+            *position = next_position;
+            *modifiers = next_modifiers;
+            Ok(())
+        }
+        (&mut WindowEvent::MouseWheel { device_id, ref mut delta, modifiers, .. },
+         WindowEvent::MouseWheel { device_id: next_device, delta: next_delta, phase: TouchPhase::Moved, modifiers: next_modifiers })
+            if device_id == next_device && modifiers == next_modifiers => {
+            match (*delta, next_delta) {
+                (MouseScrollDelta::LineDelta(x, y), MouseScrollDelta::LineDelta(next_x, next_y)) => {
+                    *delta = MouseScrollDelta::LineDelta(x + next_x, y + next_y);
+                    Ok(())
+                }
+                (MouseScrollDelta::PixelDelta(position), MouseScrollDelta::PixelDelta(next_position)) => {
+                    let sum = LogicalPosition::new(position.x + next_position.x, position.y + next_position.y);
+                    *delta = MouseScrollDelta::PixelDelta(sum);
+                    Ok(())
+                }
+                (_, next_delta) => Err(WindowEvent::MouseWheel {
+                    device_id: next_device,
+                    delta: next_delta,
+                    phase: TouchPhase::Moved,
+                    modifiers: next_modifiers,
+                }),
+            }
+        }
+        (&mut WindowEvent::Resized(ref mut size), WindowEvent::Resized(next_size)) => {
+            *size = next_size;
+            Ok(())
+        }
+        (_, next) => Err(next),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use glutin::{DeviceId, ElementState, ModifiersState, MouseButton};
+    use glutin::dpi::LogicalSize;
+    use input::simulate::{NO_MODIFIERS, SHIFT};
+
+    fn device() -> DeviceId {
+        unsafe { DeviceId::dummy() }
+    }
+    fn moved(x: f64, y: f64) -> WindowEvent {
+        WindowEvent::CursorMoved { device_id: device(), position: LogicalPosition::new(x, y), modifiers: NO_MODIFIERS }
+    }
+    fn wheel(delta: MouseScrollDelta, modifiers: ModifiersState) -> WindowEvent {
+        WindowEvent::MouseWheel { device_id: device(), delta: delta, phase: TouchPhase::Moved, modifiers: modifiers }
+    }
+    fn lines(y: f32) -> WindowEvent {
+        wheel(MouseScrollDelta::LineDelta(0.0, y), NO_MODIFIERS)
+    }
+    fn click() -> WindowEvent {
+        WindowEvent::MouseInput { device_id: device(), state: ElementState::Pressed, button: MouseButton::Left, modifiers: NO_MODIFIERS }
+    }
+    fn push_all(coalescer: &mut EventCoalescer, events: Vec<WindowEvent>) -> Vec<WindowEvent> {
+        let mut dispatched = Vec::new();
+        for event in events {
+            dispatched.extend(coalescer.push(event));
+        }
+        dispatched.extend(coalescer.flush());
+        dispatched
+    }
+
+    #[test]
+    fn cursor_moves_keep_the_latest() {
+        let mut coalescer = EventCoalescer::default();
+        assert_eq!(coalescer.push(moved(1.0, 1.0)), vec![]);
+        assert_eq!(coalescer.push(moved(2.0, 2.0)), vec![]);
+        assert_eq!(coalescer.push(moved(3.0, 5.0)), vec![]);
+        assert_eq!(coalescer.flush(), vec![moved(3.0, 5.0)]);
+        assert_eq!(coalescer.flush(), vec![]);
+    }
+
+    #[test]
+    fn scrolls_sum_their_deltas() {
+        let mut coalescer = EventCoalescer::default();
+        assert_eq!(push_all(&mut coalescer, vec![lines(1.0), lines(2.0), lines(-0.5)]), vec![lines(2.5)]);
+        let pixels = |y| wheel(MouseScrollDelta::PixelDelta(LogicalPosition::new(1.0, y)), NO_MODIFIERS);
+        assert_eq!(push_all(&mut coalescer, vec![pixels(10.0), pixels(5.0)]),
+            vec![wheel(MouseScrollDelta::PixelDelta(LogicalPosition::new(2.0, 15.0)), NO_MODIFIERS)]);
+    }
+
+    #[test]
+    fn different_scrolls_kept_apart() {
+        let mut coalescer = EventCoalescer::default();
+        // lines and pixels can't be added up
+        let pixels = wheel(MouseScrollDelta::PixelDelta(LogicalPosition::new(0.0, 4.0)), NO_MODIFIERS);
+        assert_eq!(push_all(&mut coalescer, vec![lines(1.0), pixels.clone()]), vec![lines(1.0), pixels]);
+        // shift scrolls horizontally in some widgets
+        let shifted = wheel(MouseScrollDelta::LineDelta(0.0, 1.0), SHIFT);
+        assert_eq!(push_all(&mut coalescer, vec![lines(1.0), shifted.clone(), lines(1.0)]),
+            vec![lines(1.0), shifted, lines(1.0)]);
+        // the start and end of a touchpad gesture aren't merged
+        let ended = WindowEvent::MouseWheel {
+            device_id: device(), delta: MouseScrollDelta::LineDelta(0.0, 0.0), phase: TouchPhase::Ended, modifiers: NO_MODIFIERS,
+        };
+        assert_eq!(push_all(&mut coalescer, vec![lines(1.0), ended.clone(), lines(1.0)]),
+            vec![lines(1.0), ended, lines(1.0)]);
+    }
+
+    #[test]
+    fn resizes_keep_the_latest() {
+        let mut coalescer = EventCoalescer::default();
+        let resized = |width| WindowEvent::Resized(LogicalSize::new(width, 100.0));
+        assert_eq!(push_all(&mut coalescer, vec![resized(100.0), resized(200.0), resized(300.0)]), vec![resized(300.0)]);
+    }
+
+    #[test]
+    fn other_events_keep_their_order() {
+        let mut coalescer = EventCoalescer::default();
+        assert_eq!(coalescer.push(moved(1.0, 1.0)), vec![]);
+        assert_eq!(coalescer.push(moved(2.0, 2.0)), vec![]);
+        // a click flushes the move first, so it happens where the cursor was
+        assert_eq!(coalescer.push(click()), vec![moved(2.0, 2.0), click()]);
+        assert_eq!(push_all(&mut coalescer, vec![moved(3.0, 3.0), lines(1.0), lines(1.0), moved(4.0, 4.0)]),
+            vec![moved(3.0, 3.0), lines(2.0), moved(4.0, 4.0)]);
+    }
+
+    #[test]
+    fn disabled_passes_everything_through() {
+        let mut coalescer = EventCoalescer::default();
+        coalescer.push(moved(1.0, 1.0));
+        coalescer.enabled = false;
+        // the event held back before disabling still comes first
+        assert_eq!(coalescer.push(moved(2.0, 2.0)), vec![moved(1.0, 1.0), moved(2.0, 2.0)]);
+        assert_eq!(coalescer.push(moved(3.0, 3.0)), vec![moved(3.0, 3.0)]);
+        assert_eq!(coalescer.flush(), vec![]);
+    }
+}
@@ -0,0 +1,107 @@
+//! Media keys and standard application commands, as typed events sent to the root widget.
+//!
+//! Media keys are reported while the window has focus. With the `global-hotkeys` feature they can
+//! also be captured while the app is in the background, see `hotkeys::register_media_keys`.
+//!
+//! Application commands come from each platform's standard shortcut, ex. cmd+, for preferences on
+//! macOS and ctrl+, elsewhere. The app decides what each command does: quitting, for instance, is
+//! left to the app, so it can ask to save changes first. Apps with their own menus or buttons for
+//! these commands can send the same events to the root widget, so each command is handled in one place.
+
+use glutin::{self, VirtualKeyCode, ElementState};
+
+use event::EventArgs;
+use input::keyboard::KeyboardInput;
+use app::App;
+
+/// Sent to the root widget when a media key is pressed
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum MediaKey {
+    PlayPause,
+    Stop,
+    NextTrack,
+    PreviousTrack,
+    VolumeUp,
+    VolumeDown,
+    Mute,
+}
+
+impl MediaKey {
+    pub fn from_key(key: VirtualKeyCode) -> Option<Self> {
+        let media_key = match key {
+            VirtualKeyCode::PlayPause => MediaKey::PlayPause,
+            VirtualKeyCode::MediaStop => MediaKey::Stop,
+            VirtualKeyCode::NextTrack => MediaKey::NextTrack,
+            VirtualKeyCode::PrevTrack => MediaKey::PreviousTrack,
+            VirtualKeyCode::VolumeUp => MediaKey::VolumeUp,
+            VirtualKeyCode::VolumeDown => MediaKey::VolumeDown,
+            VirtualKeyCode::Mute => MediaKey::Mute,
+            _ => return None,
+        };
+        Some(media_key)
+    }
+    pub fn key(&self) -> VirtualKeyCode {
+        match *self {
+            MediaKey::PlayPause => VirtualKeyCode::PlayPause,
+            MediaKey::Stop => VirtualKeyCode::MediaStop,
+            MediaKey::NextTrack => VirtualKeyCode::NextTrack,
+            MediaKey::PreviousTrack => VirtualKeyCode::PrevTrack,
+            MediaKey::VolumeUp => VirtualKeyCode::VolumeUp,
+            MediaKey::VolumeDown => VirtualKeyCode::VolumeDown,
+            MediaKey::Mute => VirtualKeyCode::Mute,
+        }
+    }
+}
+
+/// Sent to the root widget when the standard shortcut for a command is pressed
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum AppCommand {
+    /// Show information about the app, there's no standard shortcut
+    About,
+    /// cmd+, on macOS, ctrl+, elsewhere
+    Preferences,
+    /// Hide the app's windows, cmd+h on macOS only
+    Hide,
+    /// cmd+q on macOS, ctrl+q elsewhere
+    Quit,
+}
+
+impl AppCommand {
+    /// The command whose shortcut is `key` pressed with `modifiers` on this platform, if any
+    pub fn from_shortcut(key: VirtualKeyCode, modifiers: glutin::ModifiersState) -> Option<Self> {
+        let command_modifier = if cfg!(target_os = "macos") { modifiers.logo } else { modifiers.ctrl };
+        if !command_modifier || modifiers.shift || modifiers.alt {
+            return None;
+        }
+        match key {
+            VirtualKeyCode::Comma => Some(AppCommand::Preferences),
+            VirtualKeyCode::H if cfg!(target_os = "macos") => Some(AppCommand::Hide),
+            VirtualKeyCode::Q => Some(AppCommand::Quit),
+            _ => None,
+        }
+    }
+}
+
+impl App {
+    pub fn add_command_handlers(&mut self) {
+        self.add_handler(|event: &KeyboardInput, args: EventArgs| {
+            let &KeyboardInput(input) = event;
+            if input.state != ElementState::Pressed {
+                return;
+            }
+            if let Some(key) = input.virtual_keycode {
+                if let Some(media_key) = MediaKey::from_key(key) {
+                    args.widget.event(media_key);
+                } else if let Some(command) = AppCommand::from_shortcut(key, input.modifiers) {
+                    args.widget.event(command);
+                }
+            }
+        });
+        #[cfg(feature = "global-hotkeys")]
+        self.add_handler(|event: &::hotkeys::HotkeyPressed, args: EventArgs| {
+            if let Some(media_key) = ::hotkeys::media_key(event.0) {
+                args.widget.event(media_key);
+            }
+        });
+    }
+}
@@ -5,6 +5,7 @@ use widget::Widget;
 use input::mouse::{MouseMoved, MouseButton, WidgetMouseButton};
 use geometry::{Point, Vector};
 use app::App;
+use ui::Ui;
 
 #[derive(Debug, Copy, Clone)]
 pub struct DragEvent {
@@ -93,6 +94,11 @@ impl Widget {
         });
         self
     }
+    /// Start dragging this widget until the mouse is released, call when handling a mouse button press.
+    /// For widgets that only drag with some buttons or modifiers, rather than on any press like `make_draggable`.
+    pub fn start_drag(&self, ui: &mut Ui) {
+        ui.event(DragInputEvent::WidgetPressed(self.clone()));
+    }
 }
 
 impl App {
@@ -106,7 +112,7 @@ impl App {
             args.ui.event(DragInputEvent::MouseMoved(event.0));
         });
         self.add_handler(|event: &MouseButton, args: EventArgs| {
-            if let MouseButton(glutin::ElementState::Released, _) = *event {
+            if let MouseButton(glutin::ElementState::Released, _, _) = *event {
                 args.ui.event(DragInputEvent::MouseReleased);
             }
         });
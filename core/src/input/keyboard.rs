@@ -10,6 +10,10 @@ use widget::property::Property;
 use input::mouse::ClickEvent;
 use event::{EventHandler, EventArgs};
 use app::App;
+use ui::Ui;
+use geometry::Rect;
+use insets::{InsetKind, InsetsChanged};
+use input::keymap::InputLocaleChanged;
 
 use glutin;
 
@@ -21,6 +25,22 @@ pub struct KeyboardInput(pub glutin::KeyboardInput);
 pub struct WidgetKeyboardInput(pub glutin::KeyboardInput);
 #[derive(Debug, Copy, Clone)]
 pub struct WidgetReceivedCharacter(pub char);
+/// Sent to the focused widget after `WidgetKeyboardInput`, then to each of its ancestors in turn until
+/// a handler marks it as handled. Used for keys with a meaning for containing widgets, like scrolling,
+/// so widgets that use those keys themselves, like text fields, should mark it as handled.
+#[derive(Debug, Copy, Clone)]
+pub struct UnhandledKeyboardInput(pub glutin::KeyboardInput);
+/// Sent to a widget, then to each of its ancestors in turn until a handler marks it as handled,
+/// to ask scroll containers to scroll `area` into view. Sent from the focused widget when the
+/// on-screen keyboard is shown, or focus moves while it's shown, so the field being typed into
+/// isn't covered, and by widgets that move a selection, ex. a grid of cells.
+#[derive(Debug, Copy, Clone)]
+pub struct ScrollIntoView {
+    pub area: Rect,
+    /// The part of the window the area should be visible in, ex. the window less the insets.
+    /// If `None` the area is scrolled into the visible part of the nearest scroll container.
+    pub visible: Option<Rect>,
+}
 
 
 /// Note on focus:
@@ -36,6 +56,9 @@ pub struct FocusHandler {
     focusable: TreeMap<usize, Widget>,
     focused: Option<Widget>,
     focus_index_max: usize,
+    input_locales: HashMap<Widget, String>,
+    /// The input locale of the focused widget
+    input_locale: Option<String>,
 }
 impl FocusHandler {
     pub fn new() -> Self {
@@ -52,9 +75,48 @@ impl FocusHandler {
             }
         }
     }
+    /// Scroll the focused widget out from under the on-screen keyboard, if it's shown
+    fn reveal_focused(&self, ui: &Ui) {
+        if ui.insets_of(InsetKind::Keyboard) == Default::default() {
+            return;
+        }
+        if let Some(ref focused) = self.focused {
+            focused.event_bubble_up(ScrollIntoView {
+                area: focused.bounds(),
+                visible: Some(ui.insets().inset_rect(ui.get_root().bounds())),
+            });
+        }
+    }
+    /// Tell the app the input locale changed, if the newly focused widget has a different one
+    fn update_input_locale(&mut self, ui: &Ui) {
+        let mut input_locale = None;
+        let mut widget = self.focused.clone();
+        while let Some(ancestor) = widget {
+            if let Some(locale) = self.input_locales.get(&ancestor) {
+                input_locale = Some(locale.clone());
+                break;
+            }
+            widget = ancestor.parent();
+        }
+        if input_locale != self.input_locale {
+            self.input_locale = input_locale.clone();
+            ui.event(InputLocaleChanged(input_locale));
+        }
+    }
+    fn focus_within(&self, widget: &Widget) -> bool {
+        let mut focused = self.focused.clone();
+        while let Some(ancestor) = focused {
+            if ancestor == *widget {
+                return true;
+            }
+            focused = ancestor.parent();
+        }
+        false
+    }
 }
 impl EventHandler<KeyboardInputEvent> for FocusHandler {
-    fn handle(&mut self, event: &KeyboardInputEvent, _: EventArgs) {
+    fn handle(&mut self, event: &KeyboardInputEvent, args: EventArgs) {
+        let focused = self.focused.clone();
         match *event {
             KeyboardInputEvent::AddFocusable(ref widget_id) => {
                 self.focusable.insert(self.focus_index_max, widget_id.clone());
@@ -76,11 +138,16 @@ impl EventHandler<KeyboardInputEvent> for FocusHandler {
             KeyboardInputEvent::FocusChange(ref new_focus) => {
                 self.set_focus(new_focus.clone());
             }
+            KeyboardInputEvent::FocusWithin(ref widget) => {
+                if !self.focus_within(widget) {
+                    self.set_focus(Some(widget.clone()));
+                }
+            }
             KeyboardInputEvent::KeyboardInput(ref key_input) => {
                 if let Some(ref focused) = self.focused {
                     let &KeyboardInput(input) = key_input;
-                    let event = WidgetKeyboardInput(input);
-                    focused.event_subtree(event);
+                    focused.event_subtree(WidgetKeyboardInput(input));
+                    focused.event_bubble_up(UnhandledKeyboardInput(input));
                 }
             }
             KeyboardInputEvent::ReceivedCharacter(ref received_char) => {
@@ -100,6 +167,20 @@ impl EventHandler<KeyboardInputEvent> for FocusHandler {
                     focused.event_subtree(event);
                 }
             }
+            KeyboardInputEvent::RevealFocused => {
+                self.reveal_focused(args.ui);
+            }
+            KeyboardInputEvent::SetInputLocale(ref widget, ref locale) => {
+                match *locale {
+                    Some(ref locale) => self.input_locales.insert(widget.clone(), locale.clone()),
+                    None => self.input_locales.remove(widget),
+                };
+                self.update_input_locale(args.ui);
+            }
+        }
+        if self.focused != focused {
+            self.reveal_focused(args.ui);
+            self.update_input_locale(args.ui);
         }
     }
 }
@@ -108,8 +189,14 @@ pub enum KeyboardInputEvent {
     AddFocusable(Widget),
     RemoveFocusable(Widget),
     FocusChange(Option<Widget>),
+    /// Focus the widget, unless it or one of its descendants already has focus
+    FocusWithin(Widget),
     KeyboardInput(KeyboardInput),
     ReceivedCharacter(ReceivedCharacter),
+    /// Scroll the focused widget into view, if the on-screen keyboard is covering the window
+    RevealFocused,
+    /// Set or clear the input locale asked for by a widget
+    SetInputLocale(Widget, Option<String>),
 }
 
 impl Widget {
@@ -118,6 +205,14 @@ impl Widget {
             args.ui.get_root().event(KeyboardInputEvent::FocusChange(Some(args.widget)));
         })
     }
+    /// Make a container focusable by clicking it, without taking focus from a focusable descendant
+    /// that was clicked, or already has focus. Keys the focused descendant doesn't handle reach the
+    /// container as `UnhandledKeyboardInput`.
+    pub fn make_focusable_container(&mut self) -> &mut Self {
+        self.add_handler(|_: &ClickEvent, args: EventArgs| {
+            args.ui.get_root().event(KeyboardInputEvent::FocusWithin(args.widget));
+        })
+    }
 }
 
 impl App {
@@ -129,5 +224,10 @@ impl App {
             args.widget.event(KeyboardInputEvent::ReceivedCharacter(*event));
         });
         self.add_handler(FocusHandler::new());
+        self.add_handler(|event: &InsetsChanged, args: EventArgs| {
+            if event.kind == InsetKind::Keyboard {
+                args.widget.event(KeyboardInputEvent::RevealFocused);
+            }
+        });
     }
 }
@@ -0,0 +1,168 @@
+//! What the user's keyboard layout types, for showing shortcuts with the labels on their keys and
+//! matching mnemonics to the keys that type them.
+//!
+//! The windowing library doesn't report the keyboard layout, so the `Keymap` of each window is
+//! learned from input: each key pressed without modifiers is matched with the character it
+//! typed. A key typing something other than what it typed before means the layout changed, the
+//! keymap is cleared and `KeymapChanged` is sent to the root widget. Platform
+//! integrations that know the layout can report it with `Ui::set_keyboard_layout`.
+//!
+//! Widgets can ask for an input locale, ex. a field for Japanese text, with
+//! `Widget::set_input_locale`. When focus moves, `InputLocaleChanged` is sent to the root widget
+//! with the locale of the focused widget, or its nearest ancestor that has one, for the app or
+//! platform integration to switch the input method.
+
+use std::collections::HashMap;
+
+use glutin::{self, VirtualKeyCode, ModifiersState, ElementState};
+
+use event::{self, EventArgs, Target};
+use input::keyboard::{KeyboardInput, ReceivedCharacter, KeyboardInputEvent};
+use widget::Widget;
+use app::App;
+
+/// Sent to the root widget when the keyboard layout changes
+#[derive(Debug, Clone, PartialEq)]
+pub struct KeymapChanged {
+    /// Name of the new layout, if it was reported by the platform
+    pub layout: Option<String>,
+}
+
+/// Sent to the root widget when focus moves to a widget with a different input locale
+#[derive(Debug, Clone, PartialEq)]
+pub struct InputLocaleChanged(pub Option<String>);
+
+#[derive(Debug, Copy, Clone, PartialEq)]
+struct KeyInfo {
+    key: Option<VirtualKeyCode>,
+    character: char,
+}
+
+/// The keys of the keyboard layout of a window, as far as they've been seen
+#[derive(Debug, Default)]
+pub struct Keymap {
+    layout: Option<String>,
+    /// By scancode
+    keys: HashMap<u32, KeyInfo>,
+    /// The last key pressed, waiting for the character it types
+    pending: Option<(u32, Option<VirtualKeyCode>)>,
+}
+
+impl Keymap {
+    /// Name of the layout, if it was reported by the platform
+    pub fn layout(&self) -> Option<&str> {
+        self.layout.as_ref().map(|layout| layout.as_str())
+    }
+    /// The character `key` types without modifiers, if it's been seen
+    pub fn character(&self, key: VirtualKeyCode) -> Option<char> {
+        self.keys.values().find(|info| info.key == Some(key)).map(|info| info.character)
+    }
+    /// The key that types `character`, ignoring case, ex. to match a mnemonic
+    pub fn key_for_char(&self, character: char) -> Option<VirtualKeyCode> {
+        let character = lowercase(character);
+        self.keys.values().find(|info| info.character == character).and_then(|info| info.key)
+    }
+    /// The scancode of `key`, its position on the keyboard, if it's been seen
+    pub fn scancode(&self, key: VirtualKeyCode) -> Option<u32> {
+        self.keys.iter().find(|&(_, info)| info.key == Some(key)).map(|(scancode, _)| *scancode)
+    }
+    /// The label of `key`, as printed on the keyboard
+    pub fn key_label(&self, key: VirtualKeyCode) -> String {
+        if let Some(character) = self.character(key) {
+            return character.to_uppercase().collect();
+        }
+        use glutin::VirtualKeyCode::*;
+        let label = match key {
+            Key1 => "1", Key2 => "2", Key3 => "3", Key4 => "4", Key5 => "5",
+            Key6 => "6", Key7 => "7", Key8 => "8", Key9 => "9", Key0 => "0",
+            Return => "Enter", Back => "Backspace", Escape => "Esc", Delete => "Del",
+            Insert => "Ins", PageUp => "Page Up", PageDown => "Page Down",
+            Comma => ",", Period => ".", Minus => "-", Equals => "=", Slash => "/",
+            Semicolon => ";", Apostrophe => "'", LBracket => "[", RBracket => "]", Backslash => "\\",
+            _ => return format!("{:?}", key),
+        };
+        label.to_owned()
+    }
+    /// The label of a shortcut, ex. "Ctrl+Shift+Z", with the modifiers named as on this platform
+    pub fn shortcut_label(&self, key: VirtualKeyCode, modifiers: ModifiersState) -> String {
+        let mut label = String::new();
+        let names = if cfg!(target_os = "macos") {
+            [(modifiers.ctrl, "Ctrl+"), (modifiers.alt, "Option+"), (modifiers.shift, "Shift+"), (modifiers.logo, "Cmd+")]
+        } else {
+            [(modifiers.ctrl, "Ctrl+"), (modifiers.alt, "Alt+"), (modifiers.shift, "Shift+"), (modifiers.logo, "Super+")]
+        };
+        for &(held, name) in &names {
+            if held {
+                label.push_str(name);
+            }
+        }
+        label.push_str(&self.key_label(key));
+        label
+    }
+
+    /// Returns true if the layout changed
+    pub(crate) fn set_layout(&mut self, layout: &str) -> bool {
+        if self.layout() == Some(layout) {
+            return false;
+        }
+        self.layout = Some(layout.to_owned());
+        self.keys.clear();
+        true
+    }
+    fn observe_key(&mut self, input: glutin::KeyboardInput) {
+        let modifiers = input.modifiers;
+        let unmodified = !modifiers.shift && !modifiers.ctrl && !modifiers.alt && !modifiers.logo;
+        if input.state == ElementState::Pressed && unmodified {
+            self.pending = Some((input.scancode, input.virtual_keycode));
+        } else {
+            self.pending = None;
+        }
+    }
+    /// Returns true if the character shows the layout changed
+    fn observe_char(&mut self, character: char) -> bool {
+        let (scancode, key) = match self.pending.take() {
+            Some(pending) => pending,
+            None => return false,
+        };
+        if character.is_control() || character.is_whitespace() {
+            return false;
+        }
+        let info = KeyInfo {
+            key: key,
+            character: lowercase(character),
+        };
+        let changed = self.keys.get(&scancode).map_or(false, |known| *known != info);
+        if changed {
+            self.layout = None;
+            self.keys.clear();
+        }
+        self.keys.insert(scancode, info);
+        changed
+    }
+}
+
+fn lowercase(character: char) -> char {
+    character.to_lowercase().next().unwrap_or(character)
+}
+
+impl Widget {
+    /// Ask for `locale` to be used for input while this widget, or a widget inside it, has focus
+    pub fn set_input_locale(&mut self, locale: &str) -> &mut Self {
+        let event = KeyboardInputEvent::SetInputLocale(self.clone(), Some(locale.to_owned()));
+        event::event(Target::Root, event);
+        self
+    }
+}
+
+impl App {
+    pub fn add_keymap_handlers(&mut self) {
+        self.add_handler(|event: &KeyboardInput, args: EventArgs| {
+            args.ui.keymap.observe_key(event.0);
+        });
+        self.add_handler(|event: &ReceivedCharacter, args: EventArgs| {
+            if args.ui.keymap.observe_char(event.0) {
+                args.widget.event(KeymapChanged { layout: None });
+            }
+        });
+    }
+}
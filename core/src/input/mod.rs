@@ -4,6 +4,11 @@
 pub mod mouse;
 pub mod keyboard;
 pub mod drag;
+pub mod commands;
+pub mod keymap;
+pub mod simulate;
+pub mod transcript;
+pub(crate) mod coalesce;
 
 use glutin;
 use glutin::ElementState;
@@ -14,6 +19,7 @@ use input::mouse::{MouseMoved, MouseButton, MouseWheel, CursorLeftWindow};
 use input::keyboard::{KeyboardInput, ReceivedCharacter};
 use geometry::Point;
 use app::App;
+use ui::WindowCloseRequested;
 
 #[derive(Clone)]
 pub struct InputEvent(pub glutin::WindowEvent);
@@ -24,13 +30,17 @@ impl App {
             let InputEvent(event) = event.clone();
             match event {
                 glutin::WindowEvent::CloseRequested => {
-                    args.ui.close();
+                    if args.ui.confirm_close() {
+                        args.widget.event(WindowCloseRequested);
+                    } else {
+                        args.ui.close();
+                    }
                 }
-                glutin::WindowEvent::MouseWheel { delta, .. } => {
-                    args.widget.event(MouseWheel(delta));
+                glutin::WindowEvent::MouseWheel { delta, modifiers, .. } => {
+                    args.widget.event(MouseWheel(delta, modifiers));
                 }
-                glutin::WindowEvent::MouseInput { state, button, .. } => {
-                    args.widget.event(MouseButton(state, button));
+                glutin::WindowEvent::MouseInput { state, button, modifiers, .. } => {
+                    args.widget.event(MouseButton(state, button, modifiers));
                 }
                 glutin::WindowEvent::CursorMoved { position, .. } => {
                     let point = Point::new(position.x as f32, position.y as f32);
@@ -67,13 +77,17 @@ impl EventHandler<KeyboardInput> for EscKeyCloseHandler {
 /// Triggers various debugging settings/events on function key presses.
 #[derive(Debug, Copy, Clone)]
 pub struct DebugSettingsHandler {
-    debug_on: bool
+    debug_on: bool,
+    frame_times_on: bool,
+    paint_flashing_on: bool,
 }
 
 impl Default for DebugSettingsHandler {
     fn default() -> Self {
         DebugSettingsHandler {
             debug_on: false,
+            frame_times_on: false,
+            paint_flashing_on: false,
         }
     }
 }
@@ -99,6 +113,21 @@ impl EventHandler<KeyboardInput> for DebugSettingsHandler {
                 Some(glutin::VirtualKeyCode::F4) => ui.solver.debug_variables(),
                 Some(glutin::VirtualKeyCode::F5) => ui.render.toggle_flags(webrender::DebugFlags::PROFILER_DBG),
                 Some(glutin::VirtualKeyCode::F6) => ui.print_widgets(),
+                Some(glutin::VirtualKeyCode::F7) => {
+                    self.frame_times_on = !self.frame_times_on;
+                    ui.set_debug_draw_frame_times(self.frame_times_on);
+                },
+                Some(glutin::VirtualKeyCode::F8) => {
+                    self.paint_flashing_on = !self.paint_flashing_on;
+                    ui.set_debug_paint_flashing(self.paint_flashing_on);
+                },
+                Some(glutin::VirtualKeyCode::F9) => {
+                    let findings = ui.audit_accessibility();
+                    for finding in &findings {
+                        warn!("accessibility: {}", finding);
+                    }
+                    info!("accessibility audit found {} issues", findings.len());
+                },
                 _ => {}
             }
         }
@@ -11,21 +11,28 @@ use app::App;
 
 #[derive(Debug, Copy, Clone)]
 pub struct MouseMoved(pub Point);
+/// Mouse wheel or touchpad scroll, with the modifier keys held while scrolling
 #[derive(Debug, Copy, Clone)]
-pub struct MouseWheel(pub glutin::MouseScrollDelta);
+pub struct MouseWheel(pub glutin::MouseScrollDelta, pub glutin::ModifiersState);
+/// Mouse button press or release, with the modifier keys held at the time
 #[derive(Debug, Copy, Clone)]
-pub struct MouseButton(pub glutin::ElementState, pub glutin::MouseButton);
+pub struct MouseButton(pub glutin::ElementState, pub glutin::MouseButton, pub glutin::ModifiersState);
 #[derive(Debug, Copy, Clone)]
 pub struct CursorLeftWindow;
 
+/// Sent to the widget under the mouse as it moves, with the mouse position.
 #[derive(Debug, Copy, Clone)]
-pub struct WidgetMouseWheel(pub glutin::MouseScrollDelta);
+pub struct WidgetMouseMoved(pub Point);
+#[derive(Debug, Copy, Clone)]
+pub struct WidgetMouseWheel(pub glutin::MouseScrollDelta, pub glutin::ModifiersState);
 #[derive(Debug, Copy, Clone)]
 pub struct WidgetMouseButton(pub glutin::ElementState, pub glutin::MouseButton);
 
 #[derive(Debug, Copy, Clone)]
 pub struct ClickEvent {
     pub position: Point,
+    /// Modifier keys held when the button was released, ex. for ctrl/shift click selection
+    pub modifiers: glutin::ModifiersState,
 }
 
 #[derive(Default, Debug, Clone)]
@@ -56,6 +63,9 @@ impl MouseController {
         let &MouseMoved(mouse) = event;
         self.mouse = Some(mouse);
         self.check_widget_under_cursor(args);
+        if let Some(ref widget_under) = self.widget_under_mouse {
+            widget_under.event_bubble_up(WidgetMouseMoved(mouse));
+        }
     }
 
     fn mouse_left(&mut self, _: &CursorLeftWindow, args: EventArgs) {
@@ -64,20 +74,23 @@ impl MouseController {
     }
 
     fn mouse_button(&mut self, event: &MouseButton, _: EventArgs) {
-        let &MouseButton(state, button) = event;
+        let &MouseButton(state, button, modifiers) = event;
         if let Some(ref widget_under) = self.widget_under_mouse {
             widget_under.event_bubble_up(WidgetMouseButton(state, button));
             if (state == glutin::ElementState::Released) && (button == glutin::MouseButton::Left) && self.mouse.is_some() {
-                let event = ClickEvent { position: self.mouse.unwrap() };
+                let event = ClickEvent {
+                    position: self.mouse.unwrap(),
+                    modifiers: modifiers,
+                };
                 widget_under.event_bubble_up(event);
             }
         }
     }
 
     fn mouse_wheel(&mut self, event: &MouseWheel, _: EventArgs) {
-        let &MouseWheel(mouse_scroll_delta) = event;
+        let &MouseWheel(mouse_scroll_delta, modifiers) = event;
         if let Some(ref widget_under) = self.widget_under_mouse {
-            widget_under.event_bubble_up(WidgetMouseWheel(mouse_scroll_delta));
+            widget_under.event_bubble_up(WidgetMouseWheel(mouse_scroll_delta, modifiers));
         }
     }
 }
@@ -0,0 +1,223 @@
+//! Simulated input, for end-to-end tests of real widget trees.
+//!
+//! `App::simulate` returns a `Simulator`, which sends the same sequence of window events the
+//! platform would for each action, through the same dispatch path, then handles the events they
+//! cause before returning. Widgets are targeted by the center of their bounds, so the tree should
+//! be laid out first, ex. by `App::capture` or a first `Simulator::settle`.
+//!
+//! ```no_run
+//! # extern crate limn_core;
+//! # use limn_core::prelude::*;
+//! # use limn_core::input::simulate::CTRL;
+//! # fn test(app: &mut App, name_field: &Widget, save_button: &Widget) {
+//! app.simulate()
+//!     .click(name_field)
+//!     .type_text("hi")
+//!     .press(glutin::VirtualKeyCode::S, CTRL)
+//!     .click(save_button);
+//! # }
+//! # fn main() {}
+//! ```
+
+use glutin::{self, WindowEvent, ElementState, MouseButton, MouseScrollDelta, TouchPhase, VirtualKeyCode, ModifiersState};
+use glutin::dpi::LogicalPosition;
+
+use app::App;
+use widget::Widget;
+use geometry::{Point, RectExt};
+
+pub const NO_MODIFIERS: ModifiersState = ModifiersState { shift: false, ctrl: false, alt: false, logo: false };
+pub const CTRL: ModifiersState = ModifiersState { shift: false, ctrl: true, alt: false, logo: false };
+pub const SHIFT: ModifiersState = ModifiersState { shift: true, ctrl: false, alt: false, logo: false };
+
+/// Sends simulated input to an `App`, see the [module docs](index.html)
+pub struct Simulator<'a> {
+    app: &'a mut App,
+    /// Modifier keys held down
+    modifiers: ModifiersState,
+}
+
+impl App {
+    /// Simulate input, for tests, see the [`simulate`](input/simulate/index.html) module
+    pub fn simulate(&mut self) -> Simulator {
+        Simulator {
+            app: self,
+            modifiers: NO_MODIFIERS,
+        }
+    }
+}
+
+impl<'a> Simulator<'a> {
+    /// Handle the events queued so far, including layout updates
+    pub fn settle(&mut self) -> &mut Self {
+        self.app.handle_events();
+        self
+    }
+    pub fn move_mouse(&mut self, point: Point) -> &mut Self {
+        let modifiers = self.modifiers;
+        self.send(WindowEvent::CursorMoved {
+            device_id: device_id(),
+            position: LogicalPosition::new(f64::from(point.x), f64::from(point.y)),
+            modifiers: modifiers,
+        })
+    }
+    /// Move the cursor to the center of `widget`
+    pub fn move_mouse_to(&mut self, widget: &Widget) -> &mut Self {
+        self.move_mouse(widget.bounds().center())
+    }
+    /// Press and release the left mouse button where the cursor is
+    pub fn click_here(&mut self) -> &mut Self {
+        self.mouse_button(ElementState::Pressed).mouse_button(ElementState::Released)
+    }
+    /// Move the cursor to the center of `widget`, and click
+    pub fn click(&mut self, widget: &Widget) -> &mut Self {
+        self.move_mouse_to(widget).click_here()
+    }
+    /// Press the left mouse button at the center of `widget`, move to `to` in steps, and release
+    pub fn drag(&mut self, widget: &Widget, to: Point) -> &mut Self {
+        let from = widget.bounds().center();
+        self.move_mouse(from).mouse_button(ElementState::Pressed);
+        const STEPS: usize = 4;
+        for step in 1..STEPS + 1 {
+            let amount = step as f32 / STEPS as f32;
+            self.move_mouse(from.lerp(to, amount));
+        }
+        self.mouse_button(ElementState::Released)
+    }
+    /// Scroll over the center of `widget` by `dy` lines, positive to scroll up
+    pub fn scroll(&mut self, widget: &Widget, dy: f32) -> &mut Self {
+        self.move_mouse_to(widget);
+        let modifiers = self.modifiers;
+        self.send(WindowEvent::MouseWheel {
+            device_id: device_id(),
+            delta: MouseScrollDelta::LineDelta(0.0, dy),
+            phase: TouchPhase::Moved,
+            modifiers: modifiers,
+        })
+    }
+    /// Type `text` into the focused widget, pressing and releasing a key for each character
+    pub fn type_text(&mut self, text: &str) -> &mut Self {
+        for character in text.chars() {
+            let key = key_for_char(character);
+            let shift = character.is_uppercase();
+            if shift {
+                self.key(VirtualKeyCode::LShift, ElementState::Pressed);
+                self.modifiers.shift = true;
+            }
+            self.key_option(key, ElementState::Pressed);
+            self.send(WindowEvent::ReceivedCharacter(character));
+            self.key_option(key, ElementState::Released);
+            if shift {
+                self.modifiers.shift = false;
+                self.key(VirtualKeyCode::LShift, ElementState::Released);
+            }
+        }
+        self
+    }
+    /// Press and release `key` while holding `modifiers`, ex. `press(VirtualKeyCode::S, CTRL)`
+    pub fn press(&mut self, key: VirtualKeyCode, modifiers: ModifiersState) -> &mut Self {
+        let held = [
+            (modifiers.ctrl, VirtualKeyCode::LControl),
+            (modifiers.shift, VirtualKeyCode::LShift),
+            (modifiers.alt, VirtualKeyCode::LAlt),
+            (modifiers.logo, VirtualKeyCode::LWin),
+        ];
+        for &(down, modifier) in &held {
+            if down {
+                self.key(modifier, ElementState::Pressed);
+            }
+        }
+        self.modifiers = modifiers;
+        self.key(key, ElementState::Pressed);
+        // keys that type a character also send it, unless a shortcut modifier is held
+        if !modifiers.ctrl && !modifiers.alt && !modifiers.logo {
+            if let Some(character) = char_for_key(key, modifiers.shift) {
+                self.send(WindowEvent::ReceivedCharacter(character));
+            }
+        }
+        self.key(key, ElementState::Released);
+        self.modifiers = NO_MODIFIERS;
+        for &(down, modifier) in held.iter().rev() {
+            if down {
+                self.key(modifier, ElementState::Released);
+            }
+        }
+        self
+    }
+
+    fn mouse_button(&mut self, state: ElementState) -> &mut Self {
+        let modifiers = self.modifiers;
+        self.send(WindowEvent::MouseInput {
+            device_id: device_id(),
+            state: state,
+            button: MouseButton::Left,
+            modifiers: modifiers,
+        })
+    }
+    fn key(&mut self, key: VirtualKeyCode, state: ElementState) -> &mut Self {
+        self.key_option(Some(key), state)
+    }
+    fn key_option(&mut self, key: Option<VirtualKeyCode>, state: ElementState) -> &mut Self {
+        let modifiers = self.modifiers;
+        self.send(WindowEvent::KeyboardInput {
+            device_id: device_id(),
+            input: glutin::KeyboardInput {
+                scancode: 0,
+                state: state,
+                virtual_keycode: key,
+                modifiers: modifiers,
+            },
+        })
+    }
+    fn send(&mut self, event: WindowEvent) -> &mut Self {
+        self.app.dispatch_window_event(event);
+        self.app.handle_events();
+        self
+    }
+}
+
+fn device_id() -> glutin::DeviceId {
+    // only compared with other device ids, there's no device behind it
+    unsafe { glutin::DeviceId::dummy() }
+}
+
+/// Characters a single key types without modifiers
+const TYPED_CHARS: &str = "abcdefghijklmnopqrstuvwxyz0123456789 \t\r\u{8}";
+
+fn key_for_char(character: char) -> Option<VirtualKeyCode> {
+    use glutin::VirtualKeyCode::*;
+    const LETTERS: [VirtualKeyCode; 26] = [A, B, C, D, E, F, G, H, I, J, K, L, M, N, O, P, Q, R, S, T, U, V, W, X, Y, Z];
+    const DIGITS: [VirtualKeyCode; 10] = [Key0, Key1, Key2, Key3, Key4, Key5, Key6, Key7, Key8, Key9];
+    match character.to_ascii_lowercase() {
+        lower @ 'a'..='z' => Some(LETTERS[(lower as u8 - b'a') as usize]),
+        digit @ '0'..='9' => Some(DIGITS[(digit as u8 - b'0') as usize]),
+        ' ' => Some(Space),
+        '\t' => Some(Tab),
+        '\r' | '\n' => Some(Return),
+        '\u{8}' => Some(Back),
+        _ => None,
+    }
+}
+
+/// The character `key` types, the inverse of `key_for_char`
+fn char_for_key(key: VirtualKeyCode, shift: bool) -> Option<char> {
+    TYPED_CHARS.chars().find(|&character| key_for_char(character) == Some(key))
+        .map(|character| if shift { character.to_ascii_uppercase() } else { character })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn keys_round_trip() {
+        for character in TYPED_CHARS.chars() {
+            let key = key_for_char(character).unwrap();
+            assert_eq!(char_for_key(key, false), Some(character));
+        }
+        assert_eq!(char_for_key(VirtualKeyCode::A, true), Some('A'));
+        assert_eq!(key_for_char('A'), Some(VirtualKeyCode::A));
+        assert_eq!(char_for_key(VirtualKeyCode::Key1, false), Some('1'));
+        assert_eq!(char_for_key(VirtualKeyCode::Escape, false), None);
+    }
+}
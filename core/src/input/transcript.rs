@@ -0,0 +1,99 @@
+//! Golden transcripts of the events widgets send, for catching behavior changes in tests.
+//!
+//! A `Transcript` records the events of chosen types that reach chosen widgets, in the order
+//! they arrive, ex. the `SliderEvent`s a slider sends while it's dragged with a `Simulator`.
+//! `Transcript::assert_golden` compares the recording against a stored transcript and panics at
+//! the first difference. Run tests with `LIMN_UPDATE_GOLDEN=1` to write the stored transcripts
+//! from the current behavior, then review the changes to them like any other change.
+//!
+//! ```no_run
+//! # extern crate limn_core;
+//! # use limn_core::prelude::*;
+//! # use std::path::Path;
+//! # #[derive(Debug)] struct SliderEvent;
+//! # fn test(app: &mut App, slider: &mut Widget) {
+//! let transcript = Transcript::new();
+//! transcript.record::<SliderEvent>(slider);
+//! transcript.note("drag to the end");
+//! app.simulate().drag(slider, Point::new(200.0, 0.0));
+//! transcript.assert_golden(Path::new("tests/golden/slider_drag.txt"));
+//! # }
+//! # fn main() {}
+//! ```
+
+use std::cell::RefCell;
+use std::env;
+use std::fmt::Debug;
+use std::fs;
+use std::path::Path;
+use std::rc::Rc;
+
+use event::EventArgs;
+use widget::Widget;
+
+/// Set to write stored transcripts rather than compare against them
+pub const UPDATE_GOLDEN_VAR: &str = "LIMN_UPDATE_GOLDEN";
+
+/// Events recorded from widgets, one line per event, see the [module docs](index.html)
+#[derive(Clone, Default)]
+pub struct Transcript {
+    lines: Rc<RefCell<Vec<String>>>,
+}
+
+impl Transcript {
+    pub fn new() -> Self {
+        Self::default()
+    }
+    /// Record events of type `E` that reach `widget`, with the widget's name
+    pub fn record<E: Debug + 'static>(&self, widget: &mut Widget) -> &Self {
+        let lines = self.lines.clone();
+        let name = widget.name();
+        widget.add_handler(move |event: &E, _: EventArgs| {
+            lines.borrow_mut().push(format!("{}: {:?}", name, event));
+        });
+        self
+    }
+    /// Add a line marking the next step of input, so transcripts are easier to follow
+    pub fn note(&self, text: &str) -> &Self {
+        self.lines.borrow_mut().push(format!("# {}", text));
+        self
+    }
+    pub fn lines(&self) -> Vec<String> {
+        self.lines.borrow().clone()
+    }
+    pub fn clear(&self) {
+        self.lines.borrow_mut().clear();
+    }
+    pub fn to_text(&self) -> String {
+        let mut text = String::new();
+        for line in self.lines.borrow().iter() {
+            text.push_str(line);
+            text.push('\n');
+        }
+        text
+    }
+
+    /// Panic if the recording differs from the transcript stored in `golden`, or write it there
+    /// if `LIMN_UPDATE_GOLDEN` is set
+    pub fn assert_golden(&self, golden: &Path) {
+        let text = self.to_text();
+        if env::var_os(UPDATE_GOLDEN_VAR).is_some() {
+            if let Some(dir) = golden.parent() {
+                fs::create_dir_all(dir).unwrap();
+            }
+            fs::write(golden, &text).unwrap_or_else(|err| panic!("can't write {}: {}", golden.display(), err));
+            return;
+        }
+        let expected = fs::read_to_string(golden).unwrap_or_else(|err| {
+            panic!("can't read {}: {}, run with {}=1 to create it", golden.display(), err, UPDATE_GOLDEN_VAR)
+        });
+        let expected: Vec<&str> = expected.lines().collect();
+        let actual: Vec<&str> = text.lines().collect();
+        if let Some(index) = (0..expected.len().max(actual.len())).find(|&index| expected.get(index) != actual.get(index)) {
+            panic!("transcript differs from {} at line {}\n  expected: {}\n  actual:   {}\nrun with {}=1 to accept the new transcript",
+                golden.display(), index + 1,
+                expected.get(index).unwrap_or(&"<end>"), actual.get(index).unwrap_or(&"<end>"),
+                UPDATE_GOLDEN_VAR);
+        }
+    }
+}
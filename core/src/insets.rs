@@ -0,0 +1,143 @@
+//! Insets of the window that content shouldn't be placed under, ex. a display notch, a title bar
+//! drawn over the window with client side decorations, or an on-screen keyboard.
+//!
+//! Each kind of inset is reported separately with `Ui::set_insets`, by the platform integration
+//! or by the app, and the insets that apply are the largest of each kind on each edge, since
+//! they overlap rather than stack. `SAFE_AREA` is the area of the root widget inside the insets,
+//! as layout variables any widget can be constrained to, ex. `bound_by(&*SAFE_AREA)`, from any
+//! time, before or after the `Ui` exists. When the insets change, `InsetsChanged` is sent to
+//! every widget, so widgets can move out of the way, ex. a focused text field under the keyboard.
+
+use std::collections::HashMap;
+
+use cassowary::Variable;
+use cassowary::WeightedRelation::*;
+use cassowary::strength::*;
+
+use layout::{Layout, LayoutVars, VariableEditable};
+use geometry::{Rect, Point, Size};
+
+lazy_static! {
+    /// The area of the root widget inside the window insets
+    pub static ref SAFE_AREA: LayoutVars = LayoutVars::new();
+}
+
+/// Distances in from each edge of the window
+#[derive(Debug, Copy, Clone, PartialEq, Default)]
+pub struct Insets {
+    pub top: f32,
+    pub right: f32,
+    pub bottom: f32,
+    pub left: f32,
+}
+
+impl Insets {
+    pub fn new(top: f32, right: f32, bottom: f32, left: f32) -> Self {
+        Insets {
+            top: top,
+            right: right,
+            bottom: bottom,
+            left: left,
+        }
+    }
+    /// The part of `rect` inside the insets
+    pub fn inset_rect(&self, rect: Rect) -> Rect {
+        Rect::new(
+            Point::new(rect.origin.x + self.left, rect.origin.y + self.top),
+            Size::new((rect.size.width - self.left - self.right).max(0.0),
+                      (rect.size.height - self.top - self.bottom).max(0.0)))
+    }
+    /// The larger of the two insets on each edge
+    pub fn max(&self, other: &Insets) -> Insets {
+        Insets {
+            top: self.top.max(other.top),
+            right: self.right.max(other.right),
+            bottom: self.bottom.max(other.bottom),
+            left: self.left.max(other.left),
+        }
+    }
+}
+
+/// What an inset is reserved for
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum InsetKind {
+    /// Parts of the display that can't show content, ex. notches and rounded corners
+    Display,
+    /// Window controls drawn over the content, with client side decorations
+    TitleBar,
+    /// An on-screen keyboard covering the window
+    Keyboard,
+}
+
+/// Sent to every widget when an inset changes
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct InsetsChanged {
+    pub kind: InsetKind,
+    /// The new insets of this kind
+    pub insets: Insets,
+    /// The insets that now apply, of every kind
+    pub total: Insets,
+}
+
+/// The insets of the window, and the variables the safe area is solved from
+pub(crate) struct WindowInsets {
+    insets: HashMap<InsetKind, Insets>,
+    top: Variable,
+    right: Variable,
+    bottom: Variable,
+    left: Variable,
+}
+
+impl WindowInsets {
+    /// Add the safe area to the layout of the root widget
+    pub fn new(root: &mut Layout) -> Self {
+        let insets = WindowInsets {
+            insets: HashMap::new(),
+            top: Variable::new(),
+            right: Variable::new(),
+            bottom: Variable::new(),
+            left: Variable::new(),
+        };
+        let safe_area = *SAFE_AREA;
+        let vars = root.vars;
+        root.add_associated_vars(&safe_area, "safe_area");
+        root.add_associated_var(insets.top, "inset_top");
+        root.add_associated_var(insets.right, "inset_right");
+        root.add_associated_var(insets.bottom, "inset_bottom");
+        root.add_associated_var(insets.left, "inset_left");
+        root.add(vec![
+            safe_area.left | EQ(REQUIRED) | vars.left + insets.left,
+            safe_area.top | EQ(REQUIRED) | vars.top + insets.top,
+            safe_area.right | EQ(REQUIRED) | vars.right - insets.right,
+            safe_area.bottom | EQ(REQUIRED) | vars.bottom - insets.bottom,
+            safe_area.width | EQ(REQUIRED) | safe_area.right - safe_area.left,
+            safe_area.height | EQ(REQUIRED) | safe_area.bottom - safe_area.top,
+        ]);
+        insets.update_vars(root);
+        insets
+    }
+    /// The insets that apply, of every kind
+    pub fn total(&self) -> Insets {
+        self.insets.values().fold(Insets::default(), |total, insets| total.max(insets))
+    }
+    pub fn get(&self, kind: InsetKind) -> Insets {
+        self.insets.get(&kind).cloned().unwrap_or_default()
+    }
+    /// Returns true if the insets of this kind changed
+    pub fn set(&mut self, root: &mut Layout, kind: InsetKind, insets: Insets) -> bool {
+        if self.get(kind) == insets {
+            return false;
+        }
+        self.insets.insert(kind, insets);
+        self.update_vars(root);
+        true
+    }
+    fn update_vars(&self, root: &mut Layout) {
+        let total = self.total();
+        // like the window size, insets could conflict with required constraints on the root
+        for &(var, value) in &[(self.top, total.top), (self.right, total.right),
+                               (self.bottom, total.bottom), (self.left, total.left)] {
+            VariableEditable::new(root, var).set(value).strength(REQUIRED - 1.0);
+        }
+    }
+}
@@ -1,14 +1,23 @@
 use std::ops::DerefMut;
+use std::time::{Duration, Instant};
+use std::rc::Rc;
+use std::cell::Cell;
 
-use limn_layout::linear_layout::{LinearLayout, LinearLayoutSettings};
+use limn_layout::linear_layout::{LinearLayout, LinearLayoutSettings, Orientation};
 use limn_layout::grid_layout::GridLayout;
+use limn_layout::arrange::{Arrange, ArrangeContainer};
+use limn_layout::direct::{DirectLayout, DirectFlipStack};
+use limn_layout::Rect as LayoutRect;
 
 use resources::WidgetId;
 
-use app::App;
+use app::{App, FrameEvent};
+use clock;
 
 use widget::Widget;
-use event::EventArgs;
+use event::{self, EventArgs, EventHandler, Target};
+use geometry::Rect;
+use ui::ChildrenUpdatedEvent;
 
 pub use self::solver::LimnSolver;
 pub use limn_layout::*;
@@ -29,6 +38,149 @@ impl Widget {
         self.layout().set_container(container);
         self
     }
+
+    /// Set this widgets container to be an `ArrangeContainer`.
+    /// Children added to this widget will be placed by the given `Arrange` implementation,
+    /// which is re-run for neighbouring children whenever children are added, removed or moved.
+    pub fn arrange<A: Arrange + 'static>(&mut self, arrange: A) -> &mut Self {
+        self.layout().set_container(ArrangeContainer::new(arrange));
+        self
+    }
+
+    /// Place children added to this widget with a `DirectLayout`, computing their bounds from the
+    /// bounds of this widget without the solver. See `limn_layout::direct`.
+    pub fn direct_layout<D: DirectLayout + 'static>(&mut self, layout: D) -> &mut Self {
+        self.layout().set_direct_container();
+        DirectLayoutHandler::add_adapters(self);
+        self.add_handler(DirectLayoutHandler { layout: Box::new(layout) });
+        self
+    }
+
+    /// Place children added to this widget in a row while it's wide and in a column while it's
+    /// tall, sliding them to their new places over `duration` when it flips
+    pub fn flip_layout(&mut self, stack: DirectFlipStack, duration: Duration) -> &mut Self {
+        let animating = Rc::new(Cell::new(false));
+        self.direct_layout(FlipTransition {
+            stack: stack,
+            duration: duration,
+            orientation: None,
+            shown: Vec::new(),
+            from: Vec::new(),
+            start: None,
+            animating: animating.clone(),
+        });
+        self.add_handler(move |_: &FrameEvent, args: EventArgs| {
+            if animating.get() {
+                args.widget.event(ArrangeChildren);
+            }
+        });
+        self.receive_frame_events();
+        self
+    }
+
+    /// Drop the cached layouts of any hidden subtree this widget is in, so they're solved again
+    /// when shown. Changes to constraints do this already, this is for widgets whose layout
+    /// depends on something the solver can't see, ex. a `DirectLayout` reading the draw state.
+    pub fn invalidate_layout_cache(&self) {
+        event::event(Target::Root, InvalidateLayoutCache(self.clone()));
+    }
+}
+
+/// Sent to a widget with a `DirectLayout` to place its children again
+#[derive(Debug, Copy, Clone)]
+struct ArrangeChildren;
+
+struct DirectLayoutHandler {
+    layout: Box<DirectLayout>,
+}
+
+impl DirectLayoutHandler {
+    fn add_adapters(widget: &mut Widget) {
+        widget.add_handler(|_: &LayoutUpdated, args: EventArgs| {
+            args.widget.event(ArrangeChildren);
+        });
+        widget.add_handler(|_: &ChildrenUpdatedEvent, args: EventArgs| {
+            args.widget.event(ArrangeChildren);
+        });
+    }
+}
+
+impl EventHandler<ArrangeChildren> for DirectLayoutHandler {
+    fn handle(&mut self, _: &ArrangeChildren, args: EventArgs) {
+        let children = args.widget.children();
+        let (visible, hidden): (Vec<Widget>, Vec<Widget>) = children.into_iter()
+            .partition(|child| !child.widget().layout.hidden);
+        let rects = self.layout.arrange(args.widget.bounds().to_untyped(), visible.len());
+        let rects = rects.iter().map(|rect| Rect::from_untyped(rect));
+        let hidden = hidden.into_iter().map(|child| (child, Rect::zero()));
+        for (mut child, rect) in visible.into_iter().zip(rects).chain(hidden) {
+            if child.widget().layout.bypasses_solver() {
+                if child.bounds() != rect {
+                    args.ui.flash_repaint(child.id());
+                }
+                child.set_bounds(rect);
+            } else {
+                // has constraints of its own, suggest the bounds and let the solver refine them
+                let mut layout = child.layout();
+                layout.edit_left().set(rect.origin.x);
+                layout.edit_top().set(rect.origin.y);
+                layout.edit_width().set(rect.size.width);
+                layout.edit_height().set(rect.size.height);
+            }
+        }
+        args.ui.redraw();
+    }
+}
+
+/// A `DirectFlipStack` that moves children between their old and new places when it flips
+struct FlipTransition {
+    stack: DirectFlipStack,
+    duration: Duration,
+    orientation: Option<Orientation>,
+    /// The bounds of the children as last arranged
+    shown: Vec<LayoutRect>,
+    /// The bounds of the children when the stack flipped
+    from: Vec<LayoutRect>,
+    start: Option<Instant>,
+    /// Shared with the frame handler, to arrange the children again every frame of the transition
+    animating: Rc<Cell<bool>>,
+}
+
+impl DirectLayout for FlipTransition {
+    fn arrange(&mut self, bounds: LayoutRect, count: usize) -> Vec<LayoutRect> {
+        let target = self.stack.arrange(bounds, count);
+        let orientation = self.stack.orientation(bounds);
+        if self.orientation.map_or(false, |shown| shown != orientation) && self.shown.len() == count {
+            self.from = self.shown.clone();
+            self.start = Some(clock::now());
+        }
+        self.orientation = Some(orientation);
+        let progress = match self.start {
+            Some(start) if self.from.len() == count => {
+                let elapsed = clock::now().duration_since(start);
+                let elapsed = elapsed.as_secs() as f32 + elapsed.subsec_nanos() as f32 / 1_000_000_000.0;
+                let duration = self.duration.as_secs() as f32 + self.duration.subsec_nanos() as f32 / 1_000_000_000.0;
+                if duration > 0.0 { elapsed / duration } else { 1.0 }
+            }
+            // children added or removed part way through, skip to the end
+            _ => 1.0,
+        };
+        let rects = if progress < 1.0 {
+            // ease out, fast at first then settling into place
+            let t = 1.0 - (1.0 - progress) * (1.0 - progress);
+            self.from.iter().zip(target).map(|(from, to)| {
+                LayoutRect::new(
+                    from.origin + (to.origin - from.origin) * t,
+                    from.size + (to.size - from.size) * t)
+            }).collect()
+        } else {
+            self.start = None;
+            target
+        };
+        self.animating.set(self.start.is_some());
+        self.shown = rects.clone();
+        rects
+    }
 }
 
 #[derive(Clone)]
@@ -39,6 +191,8 @@ pub struct ResizeWindow;
 pub struct LayoutChanged(pub Vec<(usize, VarType, f64)>);
 #[derive(Debug, Copy, Clone)]
 pub struct LayoutUpdated;
+#[derive(Clone)]
+pub struct InvalidateLayoutCache(pub Widget);
 
 impl App {
     pub fn add_layout_handlers(&mut self) {
@@ -48,10 +202,26 @@ impl App {
         self.add_handler(|event: &UpdateLayout, args: EventArgs| {
             let event = event.clone();
             let UpdateLayout(widget_ref) = event;
-            let mut widget = widget_ref.widget_mut();
-            args.ui.solver.update_layout(&mut widget.layout);
+            if widget_ref.widget().layout.bypasses_solver() {
+                // ex. shown or hidden, the parent places it
+                if let Some(parent) = widget_ref.parent() {
+                    parent.event(ArrangeChildren);
+                }
+                return;
+            }
+            let start = Instant::now();
+            {
+                let mut widget = widget_ref.widget_mut();
+                args.ui.solver.update_layout(&mut widget.layout);
+            }
+            if let Some(ref mut watchdog) = args.ui.watchdog {
+                watchdog.record_layout(widget_ref.id(), widget_ref.name(), start.elapsed());
+            }
             args.ui.check_layout_changes();
         });
+        self.add_handler(|event: &InvalidateLayoutCache, args: EventArgs| {
+            args.ui.solver.invalidate_cache(event.0.id().0);
+        });
         self.add_handler(|event: &LayoutChanged, args: EventArgs| {
             let changes = &event.0;
             for &(widget_id, var, value) in changes {
@@ -59,6 +229,7 @@ impl App {
                 if let Some(mut widget) = args.ui.get_widget(widget_id) {
                     debug!("{:?}: {:?} = {}", widget.name(), var, value);
                     widget.update_bounds(var, value as f32);
+                    args.ui.flash_repaint(widget.id());
                 }
             }
             // redraw everything when layout changes, for now
@@ -1,4 +1,7 @@
-//! Limn is a cross platform, event driven, component based GUI library.
+//! Foundation of the Limn GUI library: windowing, rendering, layout, styling, resources and the event system.
+//!
+//! This is the boundary widget sets are built against, the standard widgets in `limn-widgets` only use
+//! its public API. Apps usually depend on `limn`, which re-exports both.
 
 // uncomment to debug macros
 //#![feature(trace_macros)]
@@ -24,12 +27,12 @@ pub extern crate cassowary;
 pub extern crate rusttype;
 pub extern crate glutin;
 pub extern crate webrender;
+pub extern crate image;
 extern crate euclid;
 extern crate linked_hash_map;
 extern crate stable_bst;
 extern crate gleam;
 extern crate app_units;
-extern crate image;
 extern crate font_loader;
 
 #[macro_use]
@@ -42,8 +45,11 @@ pub mod widget;
 /// Module for layout / resizing handlers and layout solving
 #[macro_use]
 pub mod layout;
+pub mod adaptive;
 
 pub mod app;
+pub mod clock;
+pub mod format;
 pub mod ui;
 pub mod geometry;
 /// Font, image and texture resources
@@ -52,6 +58,17 @@ pub mod color;
 pub mod input;
 pub mod prelude;
 pub mod render;
+pub mod watchdog;
+pub mod audit;
+pub mod quality;
+pub mod extension;
+pub mod notifications;
+pub mod stream;
+pub mod insets;
+pub mod document;
+pub mod recent_files;
+#[cfg(feature = "global-hotkeys")]
+pub mod hotkeys;
 /// Wrapper around `glutin::Window`
 pub mod window;
 
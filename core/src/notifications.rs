@@ -0,0 +1,154 @@
+//! Native OS notifications, for telling the user about events while the app is in the background,
+//! ex. a download finishing or a message arriving.
+//!
+//! Notifications are posted with the notification tool of the platform, so there are no extra
+//! dependencies: `notify-send` on Linux and the BSDs, `osascript` on macOS and PowerShell on Windows.
+//! When the notification is clicked, or closed without being clicked, a `NotificationEvent` is sent
+//! to the root widget. Only `notify-send` reports clicks, on other platforms no event is sent.
+//!
+//! None of these tools require the app to ask permission, the user allows or blocks
+//! notifications in the system settings. A notification that is blocked is silently dropped.
+//! `native` fails with `NotificationError::Unavailable` if the tool can't be found.
+
+use std::io;
+use std::path::Path;
+use std::process::{Command, Stdio};
+use std::sync::atomic::{AtomicUsize, Ordering, ATOMIC_USIZE_INIT};
+use std::thread;
+
+use failure::Error;
+
+use event::event_global;
+
+/// Identifies a notification posted with `native`
+#[derive(Copy, Clone, Debug, Hash, PartialEq, Eq, PartialOrd, Ord)]
+pub struct NotificationId(pub usize);
+
+static NEXT_ID: AtomicUsize = ATOMIC_USIZE_INIT;
+
+/// Sent to the root widget when the user responds to a notification
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum NotificationEvent {
+    /// The notification was clicked, usually the app should show the window and what the
+    /// notification was about
+    Clicked(NotificationId),
+    /// The notification was closed or expired without being clicked
+    Dismissed(NotificationId),
+}
+
+#[derive(Debug, Fail)]
+pub enum NotificationError {
+    #[fail(display = "notifications aren't supported on this platform")]
+    Unsupported,
+    #[fail(display = "notification tool {} not found", _0)]
+    Unavailable(&'static str),
+    #[fail(display = "notification tool {} failed", _0)]
+    Failed(&'static str),
+}
+
+/// Post a notification with a `title` and `body`, and optionally an icon image, returning an id
+/// identifying it in later `NotificationEvent`s. Can be called from any thread.
+pub fn native(title: &str, body: &str, icon: Option<&Path>) -> Result<NotificationId, Error> {
+    let id = NotificationId(NEXT_ID.fetch_add(1, Ordering::Relaxed));
+    post(id, title, body, icon)?;
+    Ok(id)
+}
+
+#[cfg(all(unix, not(target_os = "macos")))]
+fn post(id: NotificationId, title: &str, body: &str, icon: Option<&Path>) -> Result<(), Error> {
+    const TOOL: &str = "notify-send";
+    let mut options = Vec::new();
+    if let Some(icon) = icon {
+        options.push(format!("--icon={}", icon.display()));
+    }
+    let text = vec!["--".to_owned(), title.to_owned(), body.to_owned()];
+    // `--action` needs libnotify 0.7.9 or later, and waits for the notification to close,
+    // printing the action if it was clicked
+    let mut command = Command::new(TOOL);
+    command.args(&options).arg("--action=default=Open").arg("--wait").args(&text)
+        .stdout(Stdio::piped()).stderr(Stdio::null());
+    let child = command.spawn().map_err(|err| tool_error(TOOL, err))?;
+    thread::spawn(move || {
+        let output = match child.wait_with_output() {
+            Ok(output) => output,
+            Err(_) => return,
+        };
+        if output.status.success() {
+            let clicked = String::from_utf8_lossy(&output.stdout).trim() == "default";
+            event_global(if clicked { NotificationEvent::Clicked(id) } else { NotificationEvent::Dismissed(id) });
+        } else {
+            // older versions don't know the options, post it again without click reporting
+            let status = Command::new(TOOL).args(&options).args(&text).stderr(Stdio::null()).status();
+            if !status.map(|status| status.success()).unwrap_or(false) {
+                warn!("{}", NotificationError::Failed(TOOL));
+            }
+        }
+    });
+    Ok(())
+}
+
+#[cfg(target_os = "macos")]
+fn post(_: NotificationId, title: &str, body: &str, _: Option<&Path>) -> Result<(), Error> {
+    const TOOL: &str = "osascript";
+    // notifications from AppleScript always show the Script Editor icon
+    let script = format!("display notification {} with title {}", quote(body), quote(title));
+    run(Command::new(TOOL).arg("-e").arg(script), TOOL)
+}
+
+#[cfg(windows)]
+fn post(_: NotificationId, title: &str, body: &str, icon: Option<&Path>) -> Result<(), Error> {
+    const TOOL: &str = "powershell";
+    let image = icon.map_or(String::new(), |icon| {
+        format!("<image placement=\"appLogoOverride\" src=\"{}\" />", escape_xml(&icon.display().to_string()))
+    });
+    let xml = format!(
+        "<toast><visual><binding template=\"ToastGeneric\"><text>{}</text><text>{}</text>{}</binding></visual></toast>",
+        escape_xml(title), escape_xml(body), image);
+    // shown as coming from PowerShell, apps need to be installed with their own app id to show their name.
+    // The xml is passed in the environment, text in the script itself could end its quoted strings.
+    let script =
+        "[Windows.UI.Notifications.ToastNotificationManager, Windows.UI.Notifications, ContentType = WindowsRuntime] | Out-Null;\
+         [Windows.Data.Xml.Dom.XmlDocument, Windows.Data.Xml.Dom.XmlDocument, ContentType = WindowsRuntime] | Out-Null;\
+         $xml = New-Object Windows.Data.Xml.Dom.XmlDocument;\
+         $xml.LoadXml($env:LIMN_TOAST_XML);\
+         $toast = New-Object Windows.UI.Notifications.ToastNotification $xml;\
+         $app = '{1AC14E77-02E7-4E5D-B744-2EB1AE5198B7}\\WindowsPowerShell\\v1.0\\powershell.exe';\
+         [Windows.UI.Notifications.ToastNotificationManager]::CreateToastNotifier($app).Show($toast);";
+    run(Command::new(TOOL).args(&["-NoProfile", "-NonInteractive", "-Command", script]).env("LIMN_TOAST_XML", xml), TOOL)
+}
+
+#[cfg(not(any(unix, windows)))]
+fn post(_: NotificationId, _: &str, _: &str, _: Option<&Path>) -> Result<(), Error> {
+    Err(NotificationError::Unsupported.into())
+}
+
+/// Run a notification tool in the background, logging if it fails
+#[cfg(any(target_os = "macos", windows))]
+fn run(command: &mut Command, tool: &'static str) -> Result<(), Error> {
+    let mut child = command.stdout(Stdio::null()).stderr(Stdio::null()).spawn().map_err(|err| tool_error(tool, err))?;
+    thread::spawn(move || {
+        if !child.wait().map(|status| status.success()).unwrap_or(false) {
+            warn!("{}", NotificationError::Failed(tool));
+        }
+    });
+    Ok(())
+}
+
+fn tool_error(tool: &'static str, err: io::Error) -> Error {
+    if err.kind() == io::ErrorKind::NotFound {
+        NotificationError::Unavailable(tool).into()
+    } else {
+        err.into()
+    }
+}
+
+/// Quote a string for AppleScript
+#[cfg(target_os = "macos")]
+fn quote(text: &str) -> String {
+    format!("\"{}\"", text.replace('\\', "\\\\").replace('"', "\\\""))
+}
+
+#[cfg(windows)]
+fn escape_xml(text: &str) -> String {
+    text.replace('&', "&amp;").replace('<', "&lt;").replace('>', "&gt;").replace('"', "&quot;")
+}
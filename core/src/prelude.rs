@@ -8,33 +8,50 @@ pub use glutin;
 
 pub use geometry::{Point, Rect, RectExt, Size, SizeExt, Vector};
 pub use event::{EventHandler, EventArgs};
-pub use event::event_global;
+pub use event::{event_global, event_global_with_priority, EventSender, Priority};
 pub use widget::{Widget, StateUpdated, StyleUpdated};
-pub use widget::draw::{Draw, DrawEventHandler};
+pub use widget::draw::{Draw, DrawDescription, DrawEventHandler};
 pub use widget::property::Property;
 pub use widget::property::states::*;
-pub use widget::filter::OpacityFilter;
+pub use widget::filter::{OpacityFilter, ScaleFilter};
+pub use widget::clip::ClipPath;
 pub use style::{Component, DrawState, DrawStyle, ComponentStyle, WidgetModifier};
-pub use render::RenderBuilder;
+pub use render::{RenderBuilder, GpuInfo, GpuTier};
 pub use resources::resources;
 pub use resources::WidgetId;
 pub use resources::id::{Id, IdGen};
 pub use resources::image::ImageSource;
+pub use resources::color_space::ColorSpace;
 pub use ui::Ui;
-pub use ui::{WidgetAttachedEvent, WidgetDetachedEvent};
-pub use app::{App, FrameEvent};
+pub use ui::{WidgetAttachedEvent, WidgetDetachedEvent, WindowCloseRequested};
+pub use app::{App, FrameEvent, FirstFramePresented};
+pub use input::simulate::Simulator;
+pub use input::transcript::Transcript;
+pub use notifications::{NotificationEvent, NotificationId};
+pub use adaptive::{AdaptiveLayout, Breakpoints, SizeClass, SizeClassChanged};
+pub use insets::{Insets, InsetKind, InsetsChanged, SAFE_AREA};
+pub use stream::{stream, Overflow, StreamSender, StreamReceiver, StreamBatch};
+pub use document::{Document, DocumentEvent, DocumentChanged, DocumentOpened, DocumentSaved, DocumentSaveFailed, UnsavedChanges};
+pub use recent_files::RecentFiles;
+pub use quality::{QualityPreset, QualitySettings, QualityChanged};
+pub use format::{NumberFormat, NumberStyle, NumberLocale};
 pub use window::Window;
 pub use color::*;
 // re exports macros in limn-layout
 pub use layout::*;
 pub use layout::constraint::*;
 pub use layout::LAYOUT;
-pub use layout::linear_layout::{LinearLayoutSettings, Orientation, Spacing, ItemAlignment};
+pub use layout::linear_layout::{LinearLayoutSettings, Orientation, Spacing, ItemAlignment, AxisSize};
+pub use layout::arrange::{Arrange, ChildSlot};
+pub use layout::direct::{DirectLayout, DirectStack, DirectGrid, DirectOverlay, DirectFlipStack};
+pub use layout::guide::{Guideline, GuidePosition, Anchor};
 pub use text_layout::{Align, Wrap};
 
-pub use input::mouse::{ClickEvent, WidgetMouseButton, WidgetMouseWheel};
+pub use input::mouse::{ClickEvent, WidgetMouseButton, WidgetMouseMoved, WidgetMouseWheel};
 pub use input::drag::{DragEvent, DragState};
-pub use input::keyboard::{WidgetReceivedCharacter, KeyboardInputEvent, KeyboardInput};
+pub use input::commands::{MediaKey, AppCommand};
+pub use input::keymap::{Keymap, KeymapChanged, InputLocaleChanged};
+pub use input::keyboard::{WidgetReceivedCharacter, KeyboardInputEvent, KeyboardInput, UnhandledKeyboardInput, ScrollIntoView};
 
 // Re-export macros
 pub use maplit::*;
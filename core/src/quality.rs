@@ -0,0 +1,105 @@
+//! Rendering quality presets, trading quality for power use with one setting.
+//!
+//! Apply a preset with `Ui::set_quality`, which can be called at any time, ex. from a settings
+//! screen. Each preset bundles the renderer options in `QualitySettings`. Multisampling is a
+//! property of the window's GL context, so it's only applied to windows created afterwards with
+//! `Window::with_quality`, the rest applies immediately. The chosen preset can be kept between
+//! runs with `save` and `load`.
+
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+use failure::Error;
+use webrender::api::FontRenderMode;
+
+use recent_files::data_dir;
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum QualityPreset {
+    /// Lowest power use, for laptops on battery
+    Battery,
+    Balanced,
+    /// Best looking, at the cost of power use
+    Quality,
+}
+
+/// The renderer options a preset bundles
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct QualitySettings {
+    /// Samples per pixel for GL content, ex. `GLCanvas`, 0 to disable multisampling
+    pub multisampling: u16,
+    /// Antialias text with subpixel antialiasing, rather than grayscale
+    pub subpixel_text: bool,
+    /// Cap on frames drawn per second, including animations
+    pub max_frame_rate: u32,
+}
+
+#[derive(Debug, Fail)]
+#[fail(display = "unknown quality preset {}", name)]
+struct UnknownPresetError {
+    name: String,
+}
+
+impl Default for QualityPreset {
+    fn default() -> Self {
+        QualityPreset::Balanced
+    }
+}
+
+impl QualityPreset {
+    pub fn settings(&self) -> QualitySettings {
+        match *self {
+            QualityPreset::Battery => QualitySettings { multisampling: 0, subpixel_text: false, max_frame_rate: 30 },
+            QualityPreset::Balanced => QualitySettings { multisampling: 0, subpixel_text: true, max_frame_rate: 60 },
+            QualityPreset::Quality => QualitySettings { multisampling: 4, subpixel_text: true, max_frame_rate: 60 },
+        }
+    }
+    pub(crate) fn font_render_mode(&self) -> FontRenderMode {
+        if self.settings().subpixel_text { FontRenderMode::Subpixel } else { FontRenderMode::Alpha }
+    }
+    pub fn name(&self) -> &'static str {
+        match *self {
+            QualityPreset::Battery => "battery",
+            QualityPreset::Balanced => "balanced",
+            QualityPreset::Quality => "quality",
+        }
+    }
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "battery" => Some(QualityPreset::Battery),
+            "balanced" => Some(QualityPreset::Balanced),
+            "quality" => Some(QualityPreset::Quality),
+            _ => None,
+        }
+    }
+
+    /// Load the preset saved in `store`, the default preset if it hasn't been saved
+    pub fn load(store: &Path) -> Result<Self, Error> {
+        match fs::read_to_string(store) {
+            Ok(contents) => {
+                let name = contents.trim();
+                QualityPreset::from_name(name).ok_or_else(|| UnknownPresetError { name: name.to_owned() }.into())
+            }
+            Err(ref err) if err.kind() == io::ErrorKind::NotFound => Ok(QualityPreset::default()),
+            Err(err) => Err(err.into()),
+        }
+    }
+    /// Save the preset to `store`, creating its directory if needed
+    pub fn save(&self, store: &Path) -> Result<(), Error> {
+        if let Some(dir) = store.parent() {
+            fs::create_dir_all(dir)?;
+        }
+        fs::write(store, self.name())?;
+        Ok(())
+    }
+    /// The usual place to keep the preset for an app named `app_name`, in the user's data directory
+    pub fn default_store(app_name: &str) -> Option<PathBuf> {
+        data_dir().map(|dir| dir.join(app_name).join("quality"))
+    }
+}
+
+/// Sent to the root widget when the quality preset is changed with `Ui::set_quality`,
+/// so widgets and apps can adapt effects of their own
+#[derive(Debug, Copy, Clone)]
+pub struct QualityChanged(pub QualityPreset);
@@ -0,0 +1,149 @@
+//! A list of recently opened files, most recent first, for an "Open Recent" menu.
+//!
+//! Paths are kept once each, adding a path that's already in the list moves it to the front, and
+//! the oldest paths are dropped past the capacity. With a store, the list is saved to a plain text
+//! file, one path per line, whenever it changes, so it's kept between runs.
+//!
+//! On Windows added paths are also passed to the shell, which shows them in the jump list of the
+//! taskbar button for file types registered to the app. Other platforms only keep the app's list.
+
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+use failure::Error;
+
+pub struct RecentFiles {
+    paths: Vec<PathBuf>,
+    capacity: usize,
+    store: Option<PathBuf>,
+}
+
+impl Default for RecentFiles {
+    fn default() -> Self {
+        RecentFiles {
+            paths: Vec::new(),
+            capacity: 10,
+            store: None,
+        }
+    }
+}
+
+impl RecentFiles {
+    /// An empty list, that isn't saved
+    pub fn new() -> Self {
+        Self::default()
+    }
+    /// Load the list saved in `store`, and save changes to it. Files that no longer exist are
+    /// dropped. A missing store is an empty list, it's created on the first change.
+    pub fn load(store: PathBuf) -> Result<Self, Error> {
+        let paths = match fs::read_to_string(&store) {
+            Ok(contents) => contents.lines()
+                .filter(|line| !line.is_empty())
+                .map(PathBuf::from)
+                .filter(|path| path.exists())
+                .collect(),
+            Err(ref err) if err.kind() == io::ErrorKind::NotFound => Vec::new(),
+            Err(err) => return Err(err.into()),
+        };
+        let mut recent_files = RecentFiles::default();
+        recent_files.paths = paths;
+        recent_files.store = Some(store);
+        recent_files.paths.truncate(recent_files.capacity);
+        Ok(recent_files)
+    }
+    /// The usual place to keep the list for an app named `app_name`, in the user's data directory
+    pub fn default_store(app_name: &str) -> Option<PathBuf> {
+        data_dir().map(|dir| dir.join(app_name).join("recent-files"))
+    }
+    /// The number of paths kept, 10 by default
+    pub fn set_capacity(&mut self, capacity: usize) -> &mut Self {
+        self.capacity = capacity;
+        self.paths.truncate(capacity);
+        self
+    }
+
+    /// The recent paths, most recent first
+    pub fn paths(&self) -> &[PathBuf] {
+        &self.paths
+    }
+
+    /// Add a path to the front of the list. The list is updated even if it can't be saved.
+    pub fn add(&mut self, path: &Path) -> Result<(), Error> {
+        let path = path.canonicalize().unwrap_or_else(|_| path.to_owned());
+        self.paths.retain(|recent| *recent != path);
+        self.paths.insert(0, path.clone());
+        self.paths.truncate(self.capacity);
+        add_to_shell(&path);
+        self.save()
+    }
+    /// Remove a path, ex. after failing to open it
+    pub fn remove(&mut self, path: &Path) -> Result<(), Error> {
+        let path = path.canonicalize().unwrap_or_else(|_| path.to_owned());
+        self.paths.retain(|recent| *recent != path);
+        self.save()
+    }
+    pub fn clear(&mut self) -> Result<(), Error> {
+        self.paths.clear();
+        self.save()
+    }
+
+    fn save(&self) -> Result<(), Error> {
+        if let Some(ref store) = self.store {
+            if let Some(dir) = store.parent() {
+                fs::create_dir_all(dir)?;
+            }
+            let mut contents = String::new();
+            for path in &self.paths {
+                contents.push_str(&path.to_string_lossy());
+                contents.push('\n');
+            }
+            fs::write(store, contents)?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(windows)]
+pub(crate) fn data_dir() -> Option<PathBuf> {
+    ::std::env::var_os("APPDATA").map(PathBuf::from)
+}
+
+#[cfg(target_os = "macos")]
+pub(crate) fn data_dir() -> Option<PathBuf> {
+    ::std::env::var_os("HOME").map(|home| PathBuf::from(home).join("Library/Application Support"))
+}
+
+#[cfg(all(unix, not(target_os = "macos")))]
+pub(crate) fn data_dir() -> Option<PathBuf> {
+    ::std::env::var_os("XDG_DATA_HOME").map(PathBuf::from)
+        .filter(|dir| dir.is_absolute())
+        .or_else(|| ::std::env::var_os("HOME").map(|home| PathBuf::from(home).join(".local/share")))
+}
+
+#[cfg(not(any(windows, unix)))]
+pub(crate) fn data_dir() -> Option<PathBuf> {
+    None
+}
+
+#[cfg(windows)]
+fn add_to_shell(path: &Path) {
+    use std::ffi::OsStr;
+    use std::os::raw::{c_uint, c_void};
+    use std::os::windows::ffi::OsStrExt;
+
+    const SHARD_PATHW: c_uint = 0x3;
+
+    #[link(name = "shell32")]
+    extern "system" {
+        fn SHAddToRecentDocs(flags: c_uint, path: *const c_void);
+    }
+
+    let path: Vec<u16> = OsStr::new(path).encode_wide().chain(Some(0)).collect();
+    unsafe {
+        SHAddToRecentDocs(SHARD_PATHW, path.as_ptr() as *const c_void);
+    }
+}
+
+#[cfg(not(windows))]
+fn add_to_shell(_: &Path) {}
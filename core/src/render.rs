@@ -1,5 +1,15 @@
 //! Helper functions and useful types for interacting with WebRender
+//!
+//! The display list for a frame is built on the UI thread, by drawing the widget tree in order.
+//! Building independent subtrees on a thread pool and stitching the results together isn't
+//! possible with the current design: widgets and their draw states are reference counted and not
+//! `Send`, draw states read fonts and images through the global `resources()` lock, which panics
+//! rather than waits when it's contended, and webrender's `DisplayListBuilder` has no way to
+//! append a list built separately. Frames that are slow to build are more cheaply sped up by
+//! caching derived data with `DrawCache`, and finding the slow widgets with the watchdog's draw
+//! timings.
 
+use std::rc::Rc;
 use std::sync::Arc;
 use std::sync::atomic::{self, AtomicBool};
 
@@ -11,16 +21,24 @@ use webrender::api::*;
 use window::Window;
 use euclid::TypedPoint2D;
 use resources;
+use watchdog::WidgetTiming;
+use widget::draw::DrawCache;
 use geometry::{Rect, Point, Size};
+use color::{Color, GRAY_80};
+
+/// The window is cleared to this color before the root widget is drawn
+pub const WINDOW_BACKGROUND: Color = GRAY_80;
 
 // Provides access to the WebRender context and API
 pub(super) struct WebRenderContext {
+    pub gl: Rc<gl::Gl>,
     pub renderer: webrender::Renderer,
     pub render_api: RenderApi,
     pub epoch: Epoch,
     pub pipeline_id: PipelineId,
     pub document_id: DocumentId,
     pub device_pixel_ratio: f32,
+    pub gpu_info: GpuInfo,
     pub root_background_color: ColorF,
     // store frame ready event in case it is received after
     // update but before the event queue is waiting, otherwise
@@ -28,10 +46,65 @@ pub(super) struct WebRenderContext {
     pub frame_ready: Arc<AtomicBool>,
 }
 
+/// How capable the GPU is, from what the OpenGL context reports at startup
+#[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord)]
+pub enum GpuTier {
+    /// Software rendering, or a GPU with small textures, where optional effects are left out
+    Low,
+    /// Integrated and mobile GPUs
+    Medium,
+    High,
+}
+
+/// The GPU detected at startup, see `Ui::gpu_info`
+#[derive(Debug, Clone)]
+pub struct GpuInfo {
+    pub vendor: String,
+    pub renderer: String,
+    pub version: String,
+    /// Largest width and height of a texture, larger images are scaled down to fit when loaded
+    pub max_texture_size: u32,
+    pub tier: GpuTier,
+}
+
+impl GpuInfo {
+    fn probe(gl: &gl::Gl) -> Self {
+        let vendor = gl.get_string(gl::VENDOR);
+        let renderer = gl.get_string(gl::RENDERER);
+        let version = gl.get_string(gl::VERSION);
+        let max_texture_size = gl.get_integer_v(gl::MAX_TEXTURE_SIZE).max(0) as u32;
+        let name = format!("{} {}", vendor, renderer).to_lowercase();
+        let matches = |names: &[&str]| names.iter().any(|part| name.contains(part));
+        let tier = if max_texture_size < 4096 || matches(&["llvmpipe", "softpipe", "swiftshader", "software", "basic render"]) {
+            GpuTier::Low
+        } else if gl.get_type() == gl::GlType::Gles || matches(&["intel", "mali", "adreno", "powervr", "videocore"]) {
+            GpuTier::Medium
+        } else {
+            GpuTier::High
+        };
+        GpuInfo {
+            vendor: vendor,
+            renderer: renderer,
+            version: version,
+            max_texture_size: max_texture_size,
+            tier: tier,
+        }
+    }
+}
+
 // Context needed for widgets to draw or update resources in a particular frame
 pub struct RenderBuilder {
     pub builder: DisplayListBuilder,
     pub resources: Vec<ResourceUpdate>,
+    /// Time taken to draw each widget, if the watchdog is enabled
+    pub(crate) draw_timings: Option<Vec<WidgetTiming>>,
+    /// Cache of the widget being drawn
+    pub cache: DrawCache,
+    /// Device pixels per `DensityIndependentPixel`, the bounds passed to `Draw::draw` are
+    /// snapped to device pixels at this scale
+    pub device_pixel_ratio: f32,
+    /// Tier of the GPU, draw states should leave out optional effects on `GpuTier::Low`
+    pub gpu_tier: GpuTier,
 }
 
 impl WebRenderContext {
@@ -39,6 +112,8 @@ impl WebRenderContext {
         let gl = window.gl();
         println!("OpenGL version {}", gl.get_string(gl::VERSION));
         println!("HiDPI factor {}", window.hidpi_factor());
+        let gpu_info = GpuInfo::probe(&*gl);
+        info!("GPU {} {}, {:?} tier", gpu_info.vendor, gpu_info.renderer, gpu_info.tier);
 
         let opts = webrender::RendererOptions {
             resource_override_path: None,
@@ -51,15 +126,16 @@ impl WebRenderContext {
         let frame_ready = Arc::new(AtomicBool::new(false));
         let notifier = Box::new(Notifier::new(events_loop.create_proxy(), Arc::clone(&frame_ready)));
 
-        let (mut renderer, sender) = webrender::Renderer::new(gl, notifier, opts).unwrap();
+        let (mut renderer, sender) = webrender::Renderer::new(Rc::clone(&gl), notifier, opts).unwrap();
         let api = sender.create_api();
         resources::init_resources(sender);
+        resources::resources().image_loader.max_texture_size = Some(gpu_info.max_texture_size);
         let document_id = api.add_document(window.size_px(), 0);
 
         renderer.set_external_image_handler(Box::new(LimnExternalImageHandler));
 
         let epoch = Epoch(0);
-        let root_background_color = ColorF::new(0.8, 0.8, 0.8, 1.0);
+        let root_background_color = WINDOW_BACKGROUND.into();
 
         let pipeline_id = PipelineId(0, 0);
         let mut txn = Transaction::new();
@@ -67,12 +143,14 @@ impl WebRenderContext {
         api.send_transaction(document_id, txn);
 
         WebRenderContext {
+            gl: gl,
             renderer: renderer,
             render_api: api,
             epoch: epoch,
             pipeline_id: pipeline_id,
             document_id: document_id,
             device_pixel_ratio: window.hidpi_factor(),
+            gpu_info: gpu_info,
             root_background_color: root_background_color,
             frame_ready: frame_ready,
         }
@@ -85,6 +163,10 @@ impl WebRenderContext {
         RenderBuilder {
             builder: builder,
             resources: vec![],
+            draw_timings: None,
+            cache: DrawCache::default(),
+            device_pixel_ratio: self.device_pixel_ratio,
+            gpu_tier: self.gpu_info.tier,
         }
     }
     pub fn set_display_list(&mut self, builder: DisplayListBuilder, resources: Vec<ResourceUpdate>, window_size: LayoutSize) {
@@ -122,6 +204,23 @@ impl WebRenderContext {
         let window_rect = DeviceUintRect::new(TypedPoint2D::zero(), size);
         self.render_api.set_window_parameters(self.document_id, size, window_rect, self.device_pixel_ratio);
     }
+    /// Render at a different scale than the window's, `size` is the framebuffer size in pixels
+    pub fn set_device_pixel_ratio(&mut self, device_pixel_ratio: f32, size: DeviceUintSize) {
+        self.device_pixel_ratio = device_pixel_ratio;
+        self.window_resized(size);
+    }
+    /// Read back the last rendered frame as RGBA, top row first
+    pub fn read_pixels(&self, size: DeviceUintSize) -> Vec<u8> {
+        let (width, height) = (size.width as usize, size.height as usize);
+        let pixels = self.gl.read_pixels(0, 0, size.width as i32, size.height as i32, gl::RGBA, gl::UNSIGNED_BYTE);
+        // OpenGL rows start from the bottom
+        let row_length = width * 4;
+        let mut flipped = Vec::with_capacity(pixels.len());
+        for row in (0..height).rev() {
+            flipped.extend_from_slice(&pixels[row * row_length..(row + 1) * row_length]);
+        }
+        flipped
+    }
 }
 
 struct Notifier {
@@ -167,6 +266,23 @@ pub fn draw_horizontal_line<C: Into<ColorF>>(baseline: f32, start: f32, end: f32
     draw_rect_outline(Rect::new(Point::new(start, baseline), Size::new(end - start, 0.0)), color, renderer);
 }
 
+/// Rotate everything drawn until `pop_rotation` by `degrees` clockwise around `center`.
+/// Positions are relative to `center` until then.
+pub fn push_rotation(center: Point, degrees: f32, renderer: &mut RenderBuilder) {
+    let (sin, cos) = degrees.to_radians().sin_cos();
+    let transform = LayoutTransform::row_major(
+        cos, sin, 0.0, 0.0,
+        -sin, cos, 0.0, 0.0,
+        0.0, 0.0, 1.0, 0.0,
+        0.0, 0.0, 0.0, 1.0);
+    let info = PrimitiveInfo::new(Rect::new(center, Size::zero()));
+    renderer.builder.push_reference_frame(&info, Some(PropertyBinding::Value(transform)), None);
+}
+
+pub fn pop_rotation(renderer: &mut RenderBuilder) {
+    renderer.builder.pop_reference_frame();
+}
+
 // This weird thing is required just to pass a texture's id to WebRender
 struct LimnExternalImageHandler;
 
@@ -0,0 +1,76 @@
+//! Color spaces of images, and conversion to the color space of the window.
+//!
+//! Windows are assumed to display sRGB, the only output space available with 8 bit OpenGL
+//! surfaces, so images in wider gamut spaces are converted to sRGB when they're loaded, with
+//! colors outside sRGB clipped. Without a tag images are assumed to be sRGB already, since
+//! embedded ICC profiles can't be read, so photos from cameras or editors set to other spaces
+//! should be tagged with `ImageLoader::set_color_space` before they're first drawn.
+
+/// The color space of an image's pixels
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum ColorSpace {
+    Srgb,
+    /// sRGB primaries without the sRGB transfer curve, ex. images rendered by a 3D renderer
+    LinearSrgb,
+    /// The wider gamut of recent phone cameras and displays
+    DisplayP3,
+    /// Adobe RGB (1998), common for photos from cameras and editors
+    AdobeRgb,
+}
+
+impl Default for ColorSpace {
+    fn default() -> Self {
+        ColorSpace::Srgb
+    }
+}
+
+impl ColorSpace {
+    /// Linear RGB in this space to linear sRGB, rows are the output channels
+    fn to_srgb_matrix(&self) -> [[f32; 3]; 3] {
+        match *self {
+            ColorSpace::Srgb | ColorSpace::LinearSrgb => [[1.0, 0.0, 0.0], [0.0, 1.0, 0.0], [0.0, 0.0, 1.0]],
+            ColorSpace::DisplayP3 => [
+                [1.224_940, -0.224_940, 0.0],
+                [-0.042_057, 1.042_057, 0.0],
+                [-0.019_638, -0.078_636, 1.098_274],
+            ],
+            ColorSpace::AdobeRgb => [
+                [1.398_283, -0.398_283, 0.0],
+                [0.0, 1.0, 0.0],
+                [0.0, -0.042_929, 1.042_929],
+            ],
+        }
+    }
+    /// Decode an encoded channel value, from 0 to 1, to linear light
+    fn to_linear(&self, value: f32) -> f32 {
+        match *self {
+            ColorSpace::Srgb | ColorSpace::DisplayP3 => srgb_to_linear(value),
+            ColorSpace::LinearSrgb => value,
+            ColorSpace::AdobeRgb => value.powf(563.0 / 256.0),
+        }
+    }
+    /// Convert non-premultiplied RGBA8 pixels in this space to sRGB, in place. Alpha is unchanged.
+    pub fn convert_to_srgb(&self, data: &mut [u8]) {
+        if *self == ColorSpace::Srgb {
+            return;
+        }
+        let decode: Vec<f32> = (0..256).map(|value| self.to_linear(value as f32 / 255.0)).collect();
+        let matrix = self.to_srgb_matrix();
+        for pixel in data.chunks_mut(4) {
+            let rgb = [decode[pixel[0] as usize], decode[pixel[1] as usize], decode[pixel[2] as usize]];
+            for (channel, row) in matrix.iter().enumerate() {
+                let linear = row[0] * rgb[0] + row[1] * rgb[1] + row[2] * rgb[2];
+                let encoded = linear_to_srgb(linear.max(0.0).min(1.0));
+                pixel[channel] = (encoded * 255.0).round() as u8;
+            }
+        }
+    }
+}
+
+fn srgb_to_linear(value: f32) -> f32 {
+    if value <= 0.040_45 { value / 12.92 } else { ((value + 0.055) / 1.055).powf(2.4) }
+}
+
+fn linear_to_srgb(value: f32) -> f32 {
+    if value <= 0.003_130_8 { value * 12.92 } else { 1.055 * value.powf(1.0 / 2.4) - 0.055 }
+}
@@ -1,11 +1,12 @@
 use std::collections::HashMap;
 use std::io;
+use std::sync::Arc;
 
 use failure::Error;
 use rusttype;
 use font_loader::system_fonts::{self, FontProperty, FontPropertyBuilder};
 use app_units;
-use webrender::api::{RenderApi, ResourceUpdate, AddFont, AddFontInstance, FontKey, FontInstanceKey};
+use webrender::api::{RenderApi, ResourceUpdate, AddFont, AddFontInstance, FontKey, FontInstanceKey, FontInstanceOptions, FontRenderMode};
 
 use text_layout;
 
@@ -14,6 +15,8 @@ pub type Font = rusttype::Font<'static>;
 pub struct FontInfo {
     pub key: FontKey,
     pub info: Font,
+    /// The font file, for text layout implementations that load fonts themselves
+    pub data: Arc<Vec<u8>>,
 }
 
 /// Set of properties used to specify a font
@@ -43,12 +46,51 @@ impl FontDescriptor {
     }
 }
 
+/// OpenType feature settings, such as `liga` for ligatures or `tnum` for tabular numbers.
+/// Features are identified by their 4 letter tag, and most are either enabled (1) or disabled (0).
+/// Features not set use the font or text layout's default.
+#[derive(Default, PartialEq, Eq, Hash, Clone, Debug)]
+pub struct FontFeatures {
+    features: Vec<(String, u32)>,
+}
+
+impl FontFeatures {
+    pub fn new() -> Self {
+        FontFeatures::default()
+    }
+    pub fn enable(self, tag: &str) -> Self {
+        self.set(tag, 1)
+    }
+    pub fn disable(self, tag: &str) -> Self {
+        self.set(tag, 0)
+    }
+    /// Set the value of a feature, for features with more than one alternative, like `salt`
+    pub fn set(mut self, tag: &str, value: u32) -> Self {
+        self.features.retain(|&(ref existing, _)| existing != tag);
+        self.features.push((tag.to_owned(), value));
+        self
+    }
+    pub fn get(&self, tag: &str) -> Option<u32> {
+        self.features.iter().find(|&&(ref existing, _)| existing == tag).map(|&(_, value)| value)
+    }
+    /// Is the feature enabled, or `default` if it isn't set
+    pub fn is_enabled(&self, tag: &str, default: bool) -> bool {
+        self.get(tag).map_or(default, |value| value != 0)
+    }
+    /// Every feature that's been set, in the order they were set
+    pub fn iter(&self) -> ::std::slice::Iter<(String, u32)> {
+        self.features.iter()
+    }
+}
+
 #[derive(Default)]
 pub struct FontLoader {
     pub render: Option<RenderApi>,
     pub font_info: HashMap<FontDescriptor, FontInfo>,
     pub bundled_font_info: HashMap<FontDescriptor, FontInfo>,
     pub font_instances: HashMap<(FontDescriptor, app_units::Au), FontInstanceKey>,
+    /// How glyphs are antialiased, webrender's default if `None`
+    pub render_mode: Option<FontRenderMode>,
 }
 
 impl FontLoader {
@@ -77,16 +119,26 @@ impl FontLoader {
         let size = app_units::Au::from_f32_px(text_layout::px_to_pt(font_size));
         let key = (descriptor.clone(), size);
         if !self.font_instances.contains_key(&key) {
-            let instance_key = webrender_load_font_instance(self.render_api(), font_key, size);
+            let instance_key = webrender_load_font_instance(self.render_api(), font_key, size, self.render_mode);
             self.font_instances.insert(key.clone(), instance_key);
         }
         Ok(&self.font_instances[&key])
     }
 
+    /// Antialias glyphs with `render_mode`, ex. `FontRenderMode::Alpha` rather than subpixel
+    /// antialiasing to save power. Text is rasterized again the next time it's drawn.
+    pub fn set_render_mode(&mut self, render_mode: Option<FontRenderMode>) {
+        if self.render_mode != render_mode {
+            self.render_mode = render_mode;
+            let updates = self.font_instances.drain().map(|(_, key)| ResourceUpdate::DeleteFontInstance(key)).collect();
+            self.render_api().update_resources(updates);
+        }
+    }
+
     fn load_font(&mut self, data: Vec<u8>) -> Result<FontInfo, Error> {
         let font_info = rusttype_load_font_info(data.clone())?;
-        let key = webrender_load_font(self.render_api(), data)?;
-        Ok(FontInfo { key: key, info: font_info })
+        let key = webrender_load_font(self.render_api(), data.clone())?;
+        Ok(FontInfo { key: key, info: font_info, data: Arc::new(data) })
     }
 
     pub fn register_font_data(&mut self, descriptor: FontDescriptor, data: Vec<u8>) -> Result<(), Error> {
@@ -107,14 +159,15 @@ fn webrender_load_font(render_api: &RenderApi, data: Vec<u8>) -> Result<FontKey,
     Ok(key)
 }
 
-fn webrender_load_font_instance(render_api: &RenderApi, font_key: FontKey, size: app_units::Au) -> FontInstanceKey {
+fn webrender_load_font_instance(render_api: &RenderApi, font_key: FontKey, size: app_units::Au, render_mode: Option<FontRenderMode>) -> FontInstanceKey {
     let instance_key = render_api.generate_font_instance_key();
+    let options = render_mode.map(|render_mode| FontInstanceOptions { render_mode: render_mode, ..FontInstanceOptions::default() });
     let update = ResourceUpdate::AddFontInstance(
         AddFontInstance {
             key: instance_key,
             font_key: font_key,
             glyph_size: size,
-            options: None,
+            options: options,
             platform_options: None,
             variations: vec![]
         }
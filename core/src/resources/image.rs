@@ -3,9 +3,11 @@ use std::path::PathBuf;
 
 use failure::Error;
 
-use webrender::api::{RenderApi, ResourceUpdate, AddImage, UpdateImage, ExternalImageId, ExternalImageData, ImageKey, ImageFormat, ImageData, ImageDescriptor};
+use webrender::api::{RenderApi, ResourceUpdate, AddImage, UpdateImage, ExternalImageId, ExternalImageData, ImageKey, ImageFormat, ImageData, ImageDescriptor, DeviceUintRect};
 use image::{self, ImageError, DynamicImage, GenericImage};
 
+use resources::color_space::ColorSpace;
+
 #[derive(PartialEq, Eq, Hash, Debug, Clone)]
 pub enum ImageSource {
     AbsolutePath(PathBuf),
@@ -44,6 +46,10 @@ pub struct ImageLoader {
     pub bundled_images: HashMap<ImageSource, ImageInfo>,
     pub images: HashMap<ImageSource, ImageInfo>,
     pub texture_descriptors: HashMap<u64, ImageDescriptor>,
+    /// Color spaces of images that aren't sRGB
+    pub color_spaces: HashMap<ImageSource, ColorSpace>,
+    /// Largest texture the GPU supports, images loaded from files or memory are scaled down to fit
+    pub max_texture_size: Option<u32>,
 }
 
 impl ImageLoader {
@@ -51,6 +57,17 @@ impl ImageLoader {
         ImageLoader::default()
     }
 
+    /// Tag the image from `source` as being in `color_space`, so it's converted to the window's
+    /// color space when it's loaded. Images that are already loaded aren't affected, so tag an
+    /// image before it's first drawn, or remove it so it's reloaded.
+    pub fn set_color_space(&mut self, source: &ImageSource, color_space: ColorSpace) {
+        if color_space == ColorSpace::Srgb {
+            self.color_spaces.remove(source);
+        } else {
+            self.color_spaces.insert(source.clone(), color_space);
+        }
+    }
+
     pub fn get_image(&mut self, source: &ImageSource) -> Result<&ImageInfo, Error> {
         let image = self.get_image_internal(source);
         if let Err(ref error) = image {
@@ -65,12 +82,12 @@ impl ImageLoader {
         } else {
             let (data, descriptor) = match *source {
                 ImageSource::AbsolutePath(ref path) => {
-                    prepare_image(image::open(&path)?)?
+                    prepare_image(self.fit_texture_size(self.convert_color_space(source, image::open(&path)?)))?
                 },
                 ImageSource::AssetPath(ref relative_path) => {
                     let mut path = PathBuf::from(&self.assets_path);
                     path.push(relative_path);
-                    prepare_image(image::open(&path)?)?
+                    prepare_image(self.fit_texture_size(self.convert_color_space(source, image::open(&path)?)))?
                 },
                 ImageSource::Bundled(ref name) => {
                     return Err(BundledImageMissingError { name: name.to_owned() }.into())
@@ -81,6 +98,30 @@ impl ImageLoader {
         }
     }
 
+    /// Convert an image to sRGB, if its source is tagged with another color space
+    fn convert_color_space(&self, source: &ImageSource, image: DynamicImage) -> DynamicImage {
+        match self.color_spaces.get(source) {
+            Some(color_space) => {
+                let mut rgba = image.to_rgba();
+                color_space.convert_to_srgb(&mut rgba);
+                DynamicImage::ImageRgba8(rgba)
+            }
+            None => image,
+        }
+    }
+
+    /// Scale an image down to fit in the largest texture the GPU supports, if it doesn't
+    fn fit_texture_size(&self, image: DynamicImage) -> DynamicImage {
+        let (width, height) = image.dimensions();
+        match self.max_texture_size {
+            Some(max) if width > max || height > max => {
+                warn!("Scaling {}x{} image down to fit in the GPU's largest texture, {}x{}", width, height, max, max);
+                image.resize(max, max, image::FilterType::Triangle)
+            }
+            _ => image,
+        }
+    }
+
     fn put_image(&mut self, source: &ImageSource, data: ImageData, descriptor: ImageDescriptor) -> &ImageInfo {
         let image_info = self.create_image_resource(data, descriptor);
         self.images.insert(source.clone(), image_info);
@@ -115,12 +156,68 @@ impl ImageLoader {
     }
 
     fn load_image_internal(&mut self, name: &str, data: Vec<u8>) -> Result<(), Error> {
-        let (data, descriptor) = prepare_image(image::load_from_memory(&data)?)?;
+        let image = self.convert_color_space(&ImageSource::bundled(name), image::load_from_memory(&data)?);
+        let (data, descriptor) = prepare_image(self.fit_texture_size(image))?;
         let image_info = self.create_image_resource(data, descriptor);
         self.images.insert(ImageSource::bundled(name), image_info);
         Ok(())
     }
 
+    /// Load an image from raw, non-premultiplied RGBA8 pixels, ex. generated at runtime
+    pub fn load_raw_image(&mut self, name: &str, width: u32, height: u32, mut data: Vec<u8>) {
+        assert_eq!(data.len(), (width * height * 4) as usize, "image data doesn't match dimensions");
+        if let Some(color_space) = self.color_spaces.get(&ImageSource::bundled(name)) {
+            color_space.convert_to_srgb(&mut data);
+        }
+        for pixel in data.chunks_mut(4) {
+            pixel.swap(0, 2);
+        }
+        premultiply(data.as_mut_slice());
+        let opaque = is_image_opaque(ImageFormat::BGRA8, &data[..]);
+        let descriptor = ImageDescriptor::new(width, height, ImageFormat::BGRA8, opaque, false);
+        let image_info = self.create_image_resource(ImageData::new(data), descriptor);
+        if let Some(old_info) = self.images.insert(ImageSource::bundled(name), image_info) {
+            self.render_api().update_resources(vec![ResourceUpdate::DeleteImage(old_info.key)]);
+        }
+    }
+
+    /// Replace the pixels of an image added with `load_raw_image`, without allocating a new image.
+    /// `data` holds the whole image, but only the area in `dirty_rect` is uploaded, if given.
+    pub fn update_raw_image(&mut self, name: &str, mut data: Vec<u8>, dirty_rect: Option<DeviceUintRect>) {
+        let image_info = match self.images.get(&ImageSource::bundled(name)) {
+            Some(image_info) => image_info.clone(),
+            None => {
+                error!("Failed to update image {}, it hasn't been loaded", name);
+                return;
+            }
+        };
+        let descriptor = image_info.descriptor;
+        assert_eq!(data.len(), (descriptor.size.width * descriptor.size.height * 4) as usize, "image data doesn't match dimensions");
+        if let Some(color_space) = self.color_spaces.get(&ImageSource::bundled(name)) {
+            color_space.convert_to_srgb(&mut data);
+        }
+        for pixel in data.chunks_mut(4) {
+            pixel.swap(0, 2);
+        }
+        premultiply(data.as_mut_slice());
+        let update = ResourceUpdate::UpdateImage(
+            UpdateImage {
+                key: image_info.key,
+                descriptor: descriptor,
+                data: ImageData::new(data),
+                dirty_rect: dirty_rect,
+            }
+        );
+        self.render_api().update_resources(vec![update]);
+    }
+
+    /// Free an image that is no longer needed, it will be reloaded if used again
+    pub fn remove_image(&mut self, source: &ImageSource) {
+        if let Some(image_info) = self.images.remove(source) {
+            self.render_api().update_resources(vec![ResourceUpdate::DeleteImage(image_info.key)]);
+        }
+    }
+
     fn render_api(&self) -> &RenderApi {
         self.render.as_ref().unwrap()
     }
@@ -2,6 +2,8 @@
 pub mod id;
 pub mod font;
 pub mod image;
+pub mod color_space;
+pub mod text;
 
 use std::sync::{Mutex, MutexGuard};
 use std::collections::HashMap;
@@ -12,11 +14,15 @@ use webrender::api::RenderApiSender;
 use self::id::{Id, IdGen};
 use self::font::FontLoader;
 use self::image::ImageLoader;
+use self::text::{TextLayout, RustTypeLayout};
 
 use style::Theme;
 
 lazy_static! {
     static ref RES: Mutex<Resources> = Mutex::new(Resources::new());
+    /// Kept apart from the other resources, since ids are freed whenever a widget is dropped,
+    /// which can happen while the resources are in use
+    static ref WIDGET_IDS: Mutex<WidgetIdGen> = Mutex::new(WidgetIdGen::default());
 }
 
 pub fn init_resources(render_api: RenderApiSender) {
@@ -29,6 +35,66 @@ pub fn resources() -> MutexGuard<'static, Resources> {
 
 named_id!(WidgetId);
 
+/// Bits of a `WidgetId` holding the index, the rest hold the generation
+const WIDGET_INDEX_BITS: usize = 24;
+
+impl WidgetId {
+    /// Slot of the widget in the `Ui`'s widget storage, reused once the widget is dropped
+    pub fn index(&self) -> usize {
+        self.0 & ((1 << WIDGET_INDEX_BITS) - 1)
+    }
+    /// Incremented each time the index is reused, so an id kept after its widget is dropped never
+    /// refers to a later widget with the same index
+    pub fn generation(&self) -> usize {
+        self.0 >> WIDGET_INDEX_BITS
+    }
+    fn from_parts(index: usize, generation: usize) -> Self {
+        WidgetId(generation << WIDGET_INDEX_BITS | index)
+    }
+}
+
+/// Generates `WidgetId`s, reusing the indices of dropped widgets with the next generation
+#[derive(Default)]
+struct WidgetIdGen {
+    generations: Vec<usize>,
+    free: Vec<usize>,
+}
+
+impl WidgetIdGen {
+    fn next(&mut self) -> WidgetId {
+        let index = match self.free.pop() {
+            Some(index) => index,
+            None => {
+                self.generations.push(0);
+                self.generations.len() - 1
+            }
+        };
+        WidgetId::from_parts(index, self.generations[index])
+    }
+    fn free(&mut self, id: WidgetId) {
+        let index = id.index();
+        if self.generations.get(index) == Some(&id.generation()) {
+            // only 8 bits are left for generations on 32 bit platforms, rather than wrapping,
+            // an index that used its last generation is never reused
+            let max_generation = ::std::usize::MAX >> WIDGET_INDEX_BITS;
+            if id.generation() < max_generation {
+                self.generations[index] = id.generation() + 1;
+                self.free.push(index);
+            }
+        }
+    }
+}
+
+/// Create an id for a new widget
+pub(crate) fn next_widget_id() -> WidgetId {
+    WIDGET_IDS.lock().unwrap().next()
+}
+
+/// Free the id of a dropped widget
+pub(crate) fn free_widget_id(id: WidgetId) {
+    WIDGET_IDS.lock().unwrap().free(id);
+}
+
 /// Map for a given `Id` and resource type.
 pub struct Map<I, T> {
     id_gen: IdGen<I>,
@@ -65,7 +131,8 @@ impl<I: Id, T> Map<I, T> {
 pub struct Resources {
     pub font_loader: FontLoader,
     pub image_loader: ImageLoader,
-    pub widget_id: IdGen<WidgetId>,
+    /// Measures and positions text, see the `text` module
+    pub text_layout: Box<TextLayout>,
     pub theme: Theme,
 }
 
@@ -74,7 +141,7 @@ impl Default for Resources {
         Resources {
             font_loader: FontLoader::new(),
             image_loader: ImageLoader::new(),
-            widget_id: IdGen::new(),
+            text_layout: Box::new(RustTypeLayout),
             theme: Theme::new(),
         }
     }
@@ -86,8 +153,9 @@ impl Resources {
         Self::default()
     }
 
-    pub fn widget_id(&mut self) -> WidgetId {
-        self.widget_id.next_id()
+    /// Replace the default text layout implementation
+    pub fn set_text_layout<T: TextLayout + 'static>(&mut self, text_layout: T) {
+        self.text_layout = Box::new(text_layout);
     }
 
     fn set_render_api(&mut self, render: RenderApiSender) {
@@ -95,3 +163,32 @@ impl Resources {
         self.image_loader.render = Some(render.create_api());
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn widget_ids_reused_with_next_generation() {
+        let mut ids = WidgetIdGen::default();
+        let first = ids.next();
+        ids.free(first);
+        let second = ids.next();
+        assert_eq!(second.index(), first.index());
+        assert_eq!(second.generation(), first.generation() + 1);
+        assert_ne!(second, first);
+        // freeing a stale id again doesn't free the slot of the widget now using it
+        ids.free(first);
+        assert_ne!(ids.next().index(), second.index());
+    }
+
+    #[test]
+    fn widget_ids_retired_after_last_generation() {
+        let mut ids = WidgetIdGen::default();
+        let first = ids.next();
+        let max_generation = ::std::usize::MAX >> WIDGET_INDEX_BITS;
+        ids.generations[first.index()] = max_generation;
+        ids.free(WidgetId::from_parts(first.index(), max_generation));
+        assert_ne!(ids.next().index(), first.index());
+    }
+}
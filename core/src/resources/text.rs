@@ -0,0 +1,146 @@
+//! Text measurement and shaping, behind the `TextLayout` trait so the default rusttype based
+//! implementation can be replaced, for example by one using cosmic-text, pango or a custom shaper.
+//!
+//! Install a replacement with `resources().set_text_layout(..)`, before any text is measured.
+//! Glyphs are drawn by webrender using the font data loaded by the `FontLoader`, so glyph indices
+//! returned by a replacement must refer to that same data, available as `FontInfo::data`.
+//!
+//! OpenType features are applied here rather than by webrender, which only rasterizes the glyphs it's
+//! given. The default implementation supports `kern` and `tnum`, and never forms ligatures, so
+//! disabling `liga` has no effect. Replacements using a full shaper can support any feature.
+
+use std::ops::Range;
+
+use rusttype::Scale;
+
+use text_layout::{self, Wrap, Align, Features};
+use resources::font::{FontLoader, FontDescriptor, FontFeatures};
+use geometry::{Point, Rect, Size};
+
+/// Everything about a piece of text that affects how it's laid out
+#[derive(Debug, Clone)]
+pub struct TextParams<'a> {
+    pub text: &'a str,
+    pub font: &'a FontDescriptor,
+    pub font_size: f32,
+    pub features: &'a FontFeatures,
+    pub wrap: Wrap,
+    pub align: Align,
+}
+
+/// Vertical metrics of a font at a given size
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct LineMetrics {
+    /// Distance between the tops of consecutive lines
+    pub line_height: f32,
+    /// Distance from the baseline to the bottom of the lowest glyph, negative below the baseline
+    pub descent: f32,
+}
+
+/// A glyph from the font, positioned so its baseline origin is at `position`
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct Glyph {
+    pub index: u32,
+    pub position: Point,
+}
+
+pub trait TextLayout: Send {
+    fn line_metrics(&mut self, fonts: &mut FontLoader, font: &FontDescriptor, font_size: f32) -> LineMetrics;
+    /// Size of the text, wrapped only at line breaks
+    fn measure(&mut self, fonts: &mut FontLoader, params: &TextParams) -> Size;
+    /// Height of the text when wrapped to `width`
+    fn height(&mut self, fonts: &mut FontLoader, params: &TextParams, width: f32) -> f32;
+    /// Byte range in the text of each line, when wrapped to `width`
+    fn line_ranges(&mut self, fonts: &mut FontLoader, params: &TextParams, width: f32) -> Vec<Range<usize>>;
+    /// Bounds of each line of the text, when laid out in `bounds`
+    fn line_rects(&mut self, fonts: &mut FontLoader, params: &TextParams, bounds: Rect) -> Vec<Rect>;
+    /// Position each glyph of the text, when laid out in `bounds`
+    fn glyphs(&mut self, fonts: &mut FontLoader, params: &TextParams, bounds: Rect) -> Vec<Glyph>;
+}
+
+/// The default `TextLayout`, using the `text_layout` crate, which positions glyphs with rusttype
+/// and applies kerning but no other shaping.
+pub(crate) struct RustTypeLayout;
+
+fn features(params: &TextParams) -> Features {
+    Features {
+        kerning: params.features.is_enabled("kern", true),
+        tabular_numbers: params.features.is_enabled("tnum", false),
+    }
+}
+
+impl TextLayout for RustTypeLayout {
+    fn line_metrics(&mut self, fonts: &mut FontLoader, font: &FontDescriptor, font_size: f32) -> LineMetrics {
+        let font = fonts.get_font(font).unwrap();
+        let v_metrics = font.info.v_metrics(Scale::uniform(font_size));
+        LineMetrics {
+            line_height: font_size + v_metrics.line_gap,
+            descent: v_metrics.descent,
+        }
+    }
+    fn measure(&mut self, fonts: &mut FontLoader, params: &TextParams) -> Size {
+        let line_height = self.line_metrics(fonts, params.font, params.font_size).line_height;
+        let font = fonts.get_font(params.font).unwrap();
+        Size::from_untyped(&text_layout::get_text_size(
+            params.text,
+            &font.info,
+            params.font_size,
+            line_height,
+            params.wrap,
+            features(params)))
+    }
+    fn height(&mut self, fonts: &mut FontLoader, params: &TextParams, width: f32) -> f32 {
+        let line_height = self.line_metrics(fonts, params.font, params.font_size).line_height;
+        let font = fonts.get_font(params.font).unwrap();
+        text_layout::get_text_height(
+            params.text,
+            &font.info,
+            params.font_size,
+            line_height,
+            params.wrap,
+            features(params),
+            width)
+    }
+    fn line_ranges(&mut self, fonts: &mut FontLoader, params: &TextParams, width: f32) -> Vec<Range<usize>> {
+        let font = fonts.get_font(params.font).unwrap();
+        text_layout::get_line_ranges(
+            params.text,
+            &font.info,
+            params.font_size,
+            params.wrap,
+            features(params),
+            width)
+    }
+    fn line_rects(&mut self, fonts: &mut FontLoader, params: &TextParams, bounds: Rect) -> Vec<Rect> {
+        let line_height = self.line_metrics(fonts, params.font, params.font_size).line_height;
+        let font = fonts.get_font(params.font).unwrap();
+        text_layout::get_line_rects(
+            params.text,
+            bounds.to_untyped(),
+            &font.info,
+            params.font_size,
+            line_height,
+            params.wrap,
+            features(params),
+            params.align).iter().map(|rect| Rect::from_untyped(rect)).collect()
+    }
+    fn glyphs(&mut self, fonts: &mut FontLoader, params: &TextParams, bounds: Rect) -> Vec<Glyph> {
+        let line_height = self.line_metrics(fonts, params.font, params.font_size).line_height;
+        let font = fonts.get_font(params.font).unwrap();
+        text_layout::get_positioned_glyphs(
+            params.text,
+            bounds.to_untyped(),
+            &font.info,
+            params.font_size,
+            line_height,
+            params.wrap,
+            features(params),
+            params.align).iter().map(|glyph| {
+                let position = glyph.position();
+                Glyph {
+                    index: glyph.id().0,
+                    position: Point::new(position.x, position.y),
+                }
+            }).collect()
+    }
+}
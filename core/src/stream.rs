@@ -0,0 +1,190 @@
+//! Feeding widgets from unbounded background streams, ex. log lines or metrics for a monitoring
+//! dashboard, without flooding the event queue.
+//!
+//! Rather than sending an event per item, the background thread pushes items into a bounded
+//! buffer shared with the widget with a `StreamSender`. Only the first item pushed after the
+//! buffer was drained sends an event, waking the UI, and the widget takes everything buffered
+//! once per frame, as a single `StreamBatch`. If the producer gets ahead of the UI by more than
+//! the capacity of the buffer, items are dropped or merged into the newest buffered item, as set
+//! by the `Overflow` policy, and the batch reports how many were dropped and how far behind the
+//! widget is, so it can show that it's lagging.
+
+use std::collections::VecDeque;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use clock;
+use app::FrameEvent;
+use event::{EventArgs, EventSender};
+use resources::WidgetId;
+use widget::Widget;
+
+/// What a `StreamSender` does with items pushed while the buffer is full
+pub enum Overflow<T> {
+    /// Drop the oldest buffered item, so the widget always shows the latest items
+    DropOldest,
+    /// Drop the item being pushed, so the widget shows a contiguous run of items
+    DropNewest,
+    /// Merge the item being pushed into the newest buffered item, ex. summing counts or
+    /// keeping a maximum, so nothing is lost, only the detail
+    Merge(Box<Fn(&mut T, T) + Send>),
+}
+
+/// Sent to a widget receiving a stream when there are items waiting
+#[derive(Debug, Copy, Clone)]
+pub struct StreamReady;
+
+/// The items pushed to a stream since the last frame
+#[derive(Debug, Clone)]
+pub struct StreamBatch<T> {
+    pub items: Vec<T>,
+    /// Number of items dropped since the last batch because the buffer was full, merged items
+    /// aren't counted
+    pub dropped: usize,
+    /// Time the oldest item in the batch spent waiting for the widget
+    pub lag: Duration,
+}
+
+struct StreamBuffer<T> {
+    items: VecDeque<(Instant, T)>,
+    capacity: usize,
+    overflow: Overflow<T>,
+    dropped: usize,
+    /// Set once the widget has been told there are items waiting, until they're taken
+    notified: bool,
+    widget: Option<WidgetId>,
+}
+
+/// Pushes items to a stream from any thread
+pub struct StreamSender<T> {
+    buffer: Arc<Mutex<StreamBuffer<T>>>,
+}
+
+impl<T> Clone for StreamSender<T> {
+    fn clone(&self) -> Self {
+        StreamSender { buffer: Arc::clone(&self.buffer) }
+    }
+}
+
+impl<T: Send + 'static> StreamSender<T> {
+    /// Push an item, never blocks on the UI. Items pushed before the stream is attached to a
+    /// widget wait in the buffer.
+    pub fn send(&self, item: T) {
+        let mut guard = self.buffer.lock().unwrap();
+        let buffer = &mut *guard;
+        if buffer.items.len() >= buffer.capacity {
+            match buffer.overflow {
+                Overflow::DropOldest => {
+                    buffer.items.pop_front();
+                    buffer.dropped += 1;
+                }
+                Overflow::DropNewest => {
+                    buffer.dropped += 1;
+                    return;
+                }
+                Overflow::Merge(ref merge) => {
+                    if let Some(&mut (_, ref mut newest)) = buffer.items.back_mut() {
+                        merge(newest, item);
+                    }
+                    return;
+                }
+            }
+        }
+        buffer.items.push_back((clock::now(), item));
+        if !buffer.notified {
+            if let Some(widget) = buffer.widget {
+                buffer.notified = true;
+                EventSender.widget_event(widget, StreamReady);
+            }
+        }
+    }
+}
+
+/// The widget end of a stream, see `Widget::receive_stream`
+pub struct StreamReceiver<T> {
+    buffer: Arc<Mutex<StreamBuffer<T>>>,
+}
+
+impl<T> StreamReceiver<T> {
+    fn take(&self) -> Option<StreamBatch<T>> {
+        let mut buffer = self.buffer.lock().unwrap();
+        buffer.notified = false;
+        if buffer.items.is_empty() && buffer.dropped == 0 {
+            return None;
+        }
+        let lag = buffer.items.front().map_or(Duration::from_secs(0), |&(sent, _)| {
+            let now = clock::now();
+            if now > sent { now.duration_since(sent) } else { Duration::from_secs(0) }
+        });
+        let items = buffer.items.drain(..).map(|(_, item)| item).collect();
+        let dropped = buffer.dropped;
+        buffer.dropped = 0;
+        Some(StreamBatch { items: items, dropped: dropped, lag: lag })
+    }
+}
+
+/// Create a stream buffering up to `capacity` items between frames
+pub fn stream<T>(capacity: usize, overflow: Overflow<T>) -> (StreamSender<T>, StreamReceiver<T>) {
+    let buffer = Arc::new(Mutex::new(StreamBuffer {
+        items: VecDeque::new(),
+        capacity: capacity.max(1),
+        overflow: overflow,
+        dropped: 0,
+        notified: false,
+        widget: None,
+    }));
+    (StreamSender { buffer: Arc::clone(&buffer) }, StreamReceiver { buffer: buffer })
+}
+
+impl Widget {
+    /// Call `handler` with the items pushed to the stream, at most once per frame
+    pub fn receive_stream<T, F>(&mut self, receiver: StreamReceiver<T>, mut handler: F) -> &mut Self
+        where T: Send + 'static,
+              F: FnMut(StreamBatch<T>, EventArgs) + 'static
+    {
+        let notify = {
+            let mut buffer = receiver.buffer.lock().unwrap();
+            buffer.widget = Some(self.id());
+            buffer.notified = !buffer.items.is_empty();
+            buffer.notified
+        };
+        self.add_handler(|_: &StreamReady, args: EventArgs| {
+            // keep the event loop running until the next frame takes the items
+            args.ui.redraw();
+        });
+        self.add_handler(move |_: &FrameEvent, args: EventArgs| {
+            if let Some(batch) = receiver.take() {
+                handler(batch, args);
+            }
+        });
+        self.receive_frame_events();
+        if notify {
+            self.event(StreamReady);
+        }
+        self
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use event::run_on_ui_thread;
+
+    #[test]
+    fn lag_follows_the_clock() {
+        run_on_ui_thread(|| {
+            clock::set_deterministic(true);
+            let (sender, receiver) = stream(2, Overflow::DropOldest);
+            sender.send(1);
+            clock::advance(Duration::from_millis(100));
+            sender.send(2);
+            sender.send(3);
+            clock::advance(Duration::from_millis(150));
+            let batch = receiver.take().unwrap();
+            assert_eq!(batch.items, vec![2, 3]);
+            assert_eq!(batch.dropped, 1);
+            assert_eq!(batch.lag, Duration::from_millis(150));
+            assert!(receiver.take().is_none());
+        });
+    }
+}
@@ -51,17 +51,23 @@ The theme will prioritize which values to use by (currently) simple specificity
 -Styles registered in the theme for named style classes that can be applied to widgets, eg. "alert_button"
 -The base style for the type, ie. `ButtonStyle`, registered in the theme
 -In values are found no where else, the default values specified in `component_style!`, in this example, `RectStyle::default()` and `None`
+
+Composite widgets can isolate their internal widgets from the application's class styles with `Widget::set_style_scope`.
+Widgets inside a scope take their class styles from the `StyleScope` registered in the theme under the scope's name,
+still inheriting the application's base type styles. Only classes the scope exports can be restyled by the application,
+by registering class styles named `"<scope>::<class>"`, eg. `"tour::tour_title"`.
 */
 
 use std::fmt::{self, Debug};
 use std::any::{Any, TypeId};
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 
 use widget::Widget;
 use widget::draw::Draw;
 use widget::property::PropSet;
 
 use resources::resources;
+use color::{Color, WHITE, BLACK};
 
 use linked_hash_map::LinkedHashMap;
 
@@ -71,16 +77,20 @@ pub struct Theme {
     class_style_selectors: HashMap<(TypeId, String), LinkedHashMap<PropSet, Box<DrawComponentStyle>>>,
     modifier_type_styles: HashMap<TypeId, Box<ModifierComponentStyle>>,
     modifier_class_styles: HashMap<(TypeId, String), Box<ModifierComponentStyle>>,
+    scopes: HashMap<String, StyleScope>,
+    contrast_colors: (Color, Color),
 }
 
 impl Theme {
     pub fn new() -> Self {
         Theme {
+            contrast_colors: (WHITE, BLACK),
             type_styles: HashMap::new(),
             class_styles: HashMap::new(),
             class_style_selectors: HashMap::new(),
             modifier_type_styles: HashMap::new(),
             modifier_class_styles: HashMap::new(),
+            scopes: HashMap::new(),
         }
     }
 
@@ -94,15 +104,38 @@ impl Theme {
         self.class_style_selectors.entry((TypeId::of::<T>(), class.to_owned())).or_insert_with(LinkedHashMap::new).insert(props, Box::new(style));
     }
 
+    /// The light and dark colors `Widget::contrasting_color` picks between, white and black by default
+    pub fn set_contrast_colors(&mut self, light: Color, dark: Color) {
+        self.contrast_colors = (light, dark);
+    }
+    pub fn contrast_colors(&self) -> (Color, Color) {
+        self.contrast_colors
+    }
+
+    /// The styles for the scope `name`, created if it doesn't exist yet
+    pub fn scope(&mut self, name: &str) -> &mut StyleScope {
+        self.scopes.entry(name.to_owned()).or_insert_with(StyleScope::new)
+    }
+
     pub fn get_style(&self, widget_style: &DrawStyle, props: PropSet) -> Box<DrawComponentStyle> {
+        self.get_scoped_style(widget_style, None, props)
+    }
+
+    /// Resolve the style of a widget inside the style scope `scope`, if any.
+    /// Class styles come from the scope rather than the application, unless the class is exported.
+    pub fn get_scoped_style(&self, widget_style: &DrawStyle, scope: Option<&str>, props: PropSet) -> Box<DrawComponentStyle> {
         let type_id = widget_style.type_id;
         let mut style = self.type_styles.get(&type_id).expect("Missing default style for type").clone();
         if let Some(ref class) = widget_style.class {
-            if let Some(class_style) = self.class_styles.get(&(type_id, class.clone())) {
-                style = class_style.clone().box_merge(style.clone());
-            }
-            if let Some(selector) = self.class_style_selectors.get(&(type_id, class.clone())) {
-                style = selector.select(style, &props);
+            if let Some(scope) = scope {
+                if let Some(style_scope) = self.scopes.get(scope) {
+                    style = style_scope.styles.class_style(type_id, class, style, &props);
+                    if style_scope.exports.contains(class) {
+                        style = self.class_style(type_id, &format!("{}::{}", scope, class), style, &props);
+                    }
+                }
+            } else {
+                style = self.class_style(type_id, class, style, &props);
             }
         }
         if let Some(widget_style) = widget_style.style.as_ref() {
@@ -114,6 +147,17 @@ impl Theme {
         style
     }
 
+    fn class_style(&self, type_id: TypeId, class: &str, mut style: Box<DrawComponentStyle>, props: &PropSet) -> Box<DrawComponentStyle> {
+        let key = (type_id, class.to_owned());
+        if let Some(class_style) = self.class_styles.get(&key) {
+            style = class_style.clone().box_merge(style.clone());
+        }
+        if let Some(selector) = self.class_style_selectors.get(&key) {
+            style = selector.select(style, props);
+        }
+        style
+    }
+
     pub fn register_modifier_type_style<C: Component + WidgetModifier + 'static, T: ComponentStyle<Component = C> + Debug + Send>(&mut self, style: T) {
         self.modifier_type_styles.insert(TypeId::of::<T>(), Box::new(style));
     }
@@ -136,6 +180,35 @@ impl Theme {
     }
 }
 
+/// Class styles private to the widgets of a composite widget, see `Widget::set_style_scope`
+pub struct StyleScope {
+    styles: Theme,
+    exports: HashSet<String>,
+}
+
+impl StyleScope {
+    fn new() -> Self {
+        StyleScope {
+            styles: Theme::new(),
+            exports: HashSet::new(),
+        }
+    }
+    pub fn register_class_style<S: Draw + Component + 'static, T: ComponentStyle<Component = S> + Debug + Send + 'static>(&mut self, class: &str, style: T) -> &mut Self {
+        self.styles.register_class_style(class, style);
+        self
+    }
+    pub fn register_class_prop_style<S: Draw + Component + 'static, T: ComponentStyle<Component = S> + Debug + Send + 'static>(&mut self, class: &str, props: PropSet, style: T) -> &mut Self {
+        self.styles.register_class_prop_style(class, props, style);
+        self
+    }
+    /// Allow the application to override styles of `class` inside this scope,
+    /// by registering class styles named `"<scope>::<class>"` in the theme
+    pub fn export_class(&mut self, class: &str) -> &mut Self {
+        self.exports.insert(class.to_owned());
+        self
+    }
+}
+
 trait Selector {
     fn select(&self, style: Box<DrawComponentStyle>, props: &PropSet) -> Box<DrawComponentStyle>;
 }
@@ -295,6 +368,7 @@ pub struct DrawState {
     pub style: Option<DrawStyle>,
     pub state: Option<Box<Draw>>,
 
+    scope: Option<String>,
     style_updated: bool,
 }
 
@@ -312,11 +386,22 @@ impl DrawState {
 
     pub fn update(&mut self, props: PropSet) {
         if let Some(style) = self.style.as_ref() {
-            self.state = Some(style.resolve(props).box_component());
+            let style = resources().theme.get_scoped_style(style, self.scope.as_ref().map(|scope| scope.as_str()), props);
+            self.state = Some(style.box_component());
             self.style_updated = false;
         }
     }
 
+    pub fn scope(&self) -> Option<&str> {
+        self.scope.as_ref().map(|scope| scope.as_str())
+    }
+    pub fn set_scope(&mut self, scope: Option<String>) {
+        if self.scope != scope {
+            self.scope = scope;
+            self.style_updated();
+        }
+    }
+
     pub fn needs_update(&self) -> bool {
         self.style_updated
     }
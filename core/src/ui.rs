@@ -1,14 +1,17 @@
 //! Contains `Ui`, which contains application global state and is accessible to every event handler.
 
-use std::collections::{HashSet, HashMap, VecDeque};
+use std::collections::{HashMap, HashSet, VecDeque};
 use std::any::{Any, TypeId};
 use std::rc::Rc;
 use std::cell::RefCell;
+use std::fmt;
+use std::time::{Duration, Instant};
 
 use cassowary::Constraint;
 use cassowary::strength::*;
 
 use glutin;
+use webrender::api::PrimitiveInfo;
 
 use window::Window;
 use app::App;
@@ -16,26 +19,48 @@ use widget::Widget;
 use layout::{LimnSolver, LayoutChanged, LayoutVars, ExactFrame};
 use layout::constraint::*;
 use geometry::{Point, Rect, Size};
-use resources::WidgetId;
-use event::{Target, EventArgs};
+use resources::{resources, WidgetId};
+use event::{Target, EventArgs, EventSender};
+use widget::draw::Draw;
 use render::WebRenderContext;
+use watchdog::{Watchdog, FramePhase};
+use audit::{self, AuditFinding};
+use quality::{QualityPreset, QualityChanged};
+use insets::{WindowInsets, Insets, InsetKind, InsetsChanged};
+use input::keymap::{Keymap, KeymapChanged};
+use render::{RenderBuilder, GpuInfo, GpuTier};
+use color::Color;
+use clock;
 
 /// If true, the constraint that matches the root layout size to the window size
 /// is required. This can be useful for debugging but can result in panics from resizing the window.
 const WINDOW_CONSTRAINT_REQUIRED: bool = false;
 
+/// How long the highlight over a repainted widget takes to fade, with paint flashing enabled
+const PAINT_FLASH_MILLIS: u64 = 300;
+
 /// The core of a limn application, holds the root of the widget tree and other application global state.
 /// `Ui` is accessible to every event handler, so features helper methods that can be accessed at any time.
 pub struct Ui {
     pub(crate) root: Widget,
-    widget_map: HashMap<WidgetId, Widget>,
+    /// Widgets attached to the ui, by the index of their id
+    widgets: Vec<Option<Widget>>,
     pub(crate) solver: LimnSolver,
     pub(crate) render: WebRenderContext,
     needs_redraw: bool,
     should_close: bool,
+    confirm_close: bool,
     debug_draw_bounds: bool,
+    debug_draw_frame_times: bool,
+    debug_paint_flashing: bool,
+    /// Widgets repainted recently, and when, with paint flashing enabled
+    paint_flashes: HashMap<WidgetId, Instant>,
+    pub(crate) watchdog: Option<Watchdog>,
     pub window: Rc<RefCell<Window>>,
     window_constraints: Vec<Constraint>,
+    insets: WindowInsets,
+    pub(crate) keymap: Keymap,
+    quality: QualityPreset,
 }
 
 impl Ui {
@@ -45,22 +70,37 @@ impl Ui {
         root.layout().add(top_left(Point::zero()));
         // x will crash if window size set to (0, 0)
         root.layout().add(min_size(Size::new(1.0, 1.0)));
+        let insets = WindowInsets::new(&mut root.layout());
         let render = WebRenderContext::new(&mut window, events_loop);
         Ui {
-            widget_map: HashMap::new(),
+            widgets: Vec::new(),
             root: root.into(),
             solver: LimnSolver::new(),
             render: render,
             needs_redraw: true,
             should_close: false,
+            confirm_close: false,
             debug_draw_bounds: false,
+            debug_draw_frame_times: false,
+            debug_paint_flashing: false,
+            paint_flashes: HashMap::new(),
+            watchdog: None,
             window: Rc::new(RefCell::new(window)),
             window_constraints: Vec::new(),
+            insets: insets,
+            keymap: Keymap::default(),
+            quality: QualityPreset::default(),
         }
     }
 
+    /// The widget with the given id, if it's attached. The slots of dropped widgets are reused
+    /// with a new generation, and retired once out of generations, so a stale id returns `None`
+    /// rather than a later widget in the same slot.
     pub fn get_widget(&self, widget_id: WidgetId) -> Option<Widget> {
-        self.widget_map.get(&widget_id).cloned()
+        match self.widgets.get(widget_id.index()) {
+            Some(&Some(ref widget)) if widget.id() == widget_id => Some(widget.clone()),
+            _ => None,
+        }
     }
 
     pub fn get_root(&self) -> Widget {
@@ -71,10 +111,45 @@ impl Ui {
         self.get_root().event(data);
     }
 
+    /// Get a handle that can be moved to other threads to send events back to the UI.
+    pub fn event_sender(&self) -> EventSender {
+        EventSender
+    }
+
+    /// Update the draw state of a widget by id, if it still exists.
+    /// To update a widget from another thread, use `EventSender::update_widget`.
+    pub fn update_widget<T, F>(&mut self, widget_id: WidgetId, f: F)
+        where T: Draw + 'static,
+              F: FnOnce(&mut T)
+    {
+        if let Some(mut widget) = self.get_widget(widget_id) {
+            widget.update(f);
+        }
+    }
+
+    /// Change the mouse cursor shown over the window, until it's changed again
+    pub fn set_cursor(&mut self, cursor: glutin::MouseCursor) {
+        self.window.borrow().set_cursor(cursor);
+    }
+
+    pub fn set_title(&mut self, title: &str) {
+        self.window.borrow().set_title(title);
+    }
+
     pub fn close(&mut self) {
         self.should_close = true;
     }
 
+    /// While set, closing the window sends `WindowCloseRequested` to the root widget rather than
+    /// closing it, so the app can ask to save changes first, then call `close`.
+    pub fn set_confirm_close(&mut self, confirm_close: bool) {
+        self.confirm_close = confirm_close;
+    }
+
+    pub fn confirm_close(&self) -> bool {
+        self.confirm_close
+    }
+
     pub(super) fn should_close(&self) -> bool {
         self.should_close
     }
@@ -109,6 +184,15 @@ impl Ui {
         self.needs_redraw = true;
     }
 
+    /// Render at the window's new scale, ex. after it moves to a monitor with a different one.
+    /// Layout is in `DensityIndependentPixel`s so is unaffected, but text and edges are
+    /// rasterized and snapped at the new scale.
+    pub(super) fn hidpi_factor_changed(&mut self, hidpi_factor: f32) {
+        let window_size = self.window.borrow_mut().size_px();
+        self.render.set_device_pixel_ratio(hidpi_factor, window_size);
+        self.needs_redraw = true;
+    }
+
     pub fn check_layout_changes(&mut self) {
 
         let changes = self.solver.fetch_changes();
@@ -129,7 +213,8 @@ impl Ui {
     pub(super) fn draw_if_needed(&mut self) {
         if self.needs_redraw {
             self.draw();
-            self.needs_redraw = false;
+            // keep drawing until the paint flashes have faded
+            self.needs_redraw = !self.paint_flashes.is_empty();
         }
     }
 
@@ -138,7 +223,17 @@ impl Ui {
         let (builder, resources) = {
             let mut renderer = self.render.render_builder(window_size);
             let crop_to = Rect::new(Point::zero(), Size::new(::std::f32::MAX, ::std::f32::MAX));
+            if self.watchdog.is_some() {
+                renderer.draw_timings = Some(Vec::new());
+            }
             self.root.draw(crop_to, &mut renderer, self.debug_draw_bounds);
+            self.draw_paint_flashes(&mut renderer);
+            if let Some(ref mut watchdog) = self.watchdog {
+                watchdog.record_draws(renderer.draw_timings.take().unwrap_or_default());
+                if self.debug_draw_frame_times {
+                    watchdog.draw_graph(Rect::new(Point::zero(), window_size), &mut renderer);
+                }
+            }
             (renderer.builder, renderer.resources)
         };
         self.render.set_display_list(builder, resources, window_size);
@@ -147,7 +242,11 @@ impl Ui {
 
     // Call after drawing
     pub(super) fn update(&mut self) {
+        let start = Instant::now();
         self.render.update(self.window.borrow_mut().size_px());
+        if let Some(ref mut watchdog) = self.watchdog {
+            watchdog.record_phase(FramePhase::Render, start.elapsed());
+        }
         let window = self.window.borrow_mut();
         window.swap_buffers();
     }
@@ -169,6 +268,7 @@ impl Ui {
         let handled = widget_ref.trigger_event(self, type_id, data);
         if widget_ref.has_updated() {
             self.needs_redraw = true;
+            self.flash_repaint(widget_ref.id());
             widget_ref.set_updated(false);
         }
         handled
@@ -206,11 +306,117 @@ impl Ui {
         }
     }
 
+    /// Report the insets of one kind, ex. when the on-screen keyboard is shown or hidden.
+    /// See the [`insets`](../insets/index.html) module.
+    pub fn set_insets(&mut self, kind: InsetKind, insets: Insets) {
+        let mut root = self.get_root();
+        if self.insets.set(&mut root.layout(), kind, insets) {
+            root.event_subtree(InsetsChanged {
+                kind: kind,
+                insets: insets,
+                total: self.insets.total(),
+            });
+        }
+    }
+    /// The insets that apply, the largest of each kind on each edge
+    pub fn insets(&self) -> Insets {
+        self.insets.total()
+    }
+    /// The insets of one kind
+    pub fn insets_of(&self, kind: InsetKind) -> Insets {
+        self.insets.get(kind)
+    }
+
+    /// The keyboard layout of the window, see the [`keymap`](../input/keymap/index.html) module
+    pub fn keymap(&self) -> &Keymap {
+        &self.keymap
+    }
+    /// Report the name of the keyboard layout, ex. from a platform integration
+    pub fn set_keyboard_layout(&mut self, layout: &str) {
+        if self.keymap.set_layout(layout) {
+            self.event(KeymapChanged { layout: Some(layout.to_owned()) });
+        }
+    }
+
     pub fn set_debug_draw_bounds(&mut self, debug_draw_bounds: bool) {
         self.debug_draw_bounds = debug_draw_bounds;
         self.redraw();
     }
 
+    /// Draw a graph of recent frame times over the UI, if the watchdog is enabled
+    pub fn set_debug_draw_frame_times(&mut self, debug_draw_frame_times: bool) {
+        self.debug_draw_frame_times = debug_draw_frame_times;
+        self.redraw();
+    }
+
+    /// Flash a translucent highlight over every widget that repaints, because its draw state
+    /// changed or it moved, fading over a few frames. Widgets that flash constantly are
+    /// repainting every frame, and widgets that change without flashing were missed.
+    pub fn set_debug_paint_flashing(&mut self, debug_paint_flashing: bool) {
+        self.debug_paint_flashing = debug_paint_flashing;
+        self.paint_flashes.clear();
+        self.redraw();
+    }
+
+    pub(crate) fn flash_repaint(&mut self, widget_id: WidgetId) {
+        if self.debug_paint_flashing {
+            self.paint_flashes.insert(widget_id, clock::now());
+        }
+    }
+
+    fn draw_paint_flashes(&mut self, renderer: &mut RenderBuilder) {
+        let now = clock::now();
+        let duration = Duration::from_millis(PAINT_FLASH_MILLIS);
+        self.paint_flashes.retain(|_, start| now.duration_since(*start) < duration);
+        for (&widget_id, &start) in &self.paint_flashes {
+            if let Some(widget) = self.get_widget(widget_id) {
+                let elapsed = now.duration_since(start);
+                let elapsed = elapsed.as_secs() as f32 * 1000.0 + elapsed.subsec_nanos() as f32 * 1e-6;
+                let alpha = ((1.0 - elapsed / PAINT_FLASH_MILLIS as f32) * 96.0) as u32;
+                let color = Color(0xFF00FF00 | alpha);
+                renderer.builder.push_rect(&PrimitiveInfo::new(widget.bounds()), color.into());
+            }
+        }
+    }
+
+    /// Apply a quality preset, see the [`quality`](../quality/index.html) module
+    pub fn set_quality(&mut self, quality: QualityPreset) {
+        if quality == self.quality {
+            return;
+        }
+        self.quality = quality;
+        clock::set_max_frame_rate(quality.settings().max_frame_rate);
+        resources().font_loader.set_render_mode(Some(quality.font_render_mode()));
+        self.redraw();
+        self.event(QualityChanged(quality));
+    }
+
+    pub fn quality(&self) -> QualityPreset {
+        self.quality
+    }
+
+    /// The GPU detected at startup. Apps can check `tier` to leave out expensive content of their
+    /// own on weak GPUs.
+    pub fn gpu_info(&self) -> &GpuInfo {
+        &self.render.gpu_info
+    }
+
+    /// Override the detected GPU tier, ex. from a setting, or to test how the app degrades
+    pub fn set_gpu_tier(&mut self, tier: GpuTier) {
+        self.render.gpu_info.tier = tier;
+        self.redraw();
+    }
+
+    /// Time every frame, and report frames that take longer than `budget`.
+    /// See the [`watchdog`](../watchdog/index.html) module.
+    pub fn enable_watchdog(&mut self, budget: Duration) {
+        self.watchdog = Some(Watchdog::new(budget));
+    }
+
+    pub fn disable_watchdog(&mut self) {
+        self.watchdog = None;
+    }
+
     pub fn debug_widget_positions(&self) {
         println!("WIDGET POSITIONS");
         for widget_ref in self.widgets_bfs() {
@@ -220,6 +426,12 @@ impl Ui {
         }
     }
 
+    /// Audit the widget tree for common accessibility problems,
+    /// see the [`audit`](../audit/index.html) module
+    pub fn audit_accessibility(&self) -> Vec<AuditFinding> {
+        audit::audit(&self.get_root())
+    }
+
     pub fn print_widgets(&self) {
         for widget_ref in self.widgets_bfs() {
             let draw_state = &widget_ref.widget().draw_state;
@@ -233,23 +445,81 @@ pub struct RegisterWidget(pub Widget);
 #[derive(Clone)]
 pub struct RemoveWidget(pub Widget);
 
+/// A draw state update or event for a single widget, marshalled onto the UI thread from any thread.
+/// Created by `EventSender::update_widget` or `EventSender::widget_event`.
+pub struct UpdateWidget {
+    widget_id: WidgetId,
+    update: RefCell<Box<FnMut(&mut Widget) + Send>>,
+}
+
+impl UpdateWidget {
+    pub fn new<T, F>(widget_id: WidgetId, f: F) -> Self
+        where T: Draw + 'static,
+              F: FnOnce(&mut T) + Send + 'static
+    {
+        let mut f = Some(f);
+        Self::from_fn(widget_id, move |widget: &mut Widget| {
+            if let Some(f) = f.take() {
+                widget.update(f);
+            }
+        })
+    }
+    /// Deliver an event to the widget, rather than updating its draw state
+    pub fn event<T: Send + 'static>(widget_id: WidgetId, data: T) -> Self {
+        let mut data = Some(data);
+        Self::from_fn(widget_id, move |widget: &mut Widget| {
+            if let Some(data) = data.take() {
+                widget.event(data);
+            }
+        })
+    }
+    fn from_fn<F: FnMut(&mut Widget) + Send + 'static>(widget_id: WidgetId, update: F) -> Self {
+        UpdateWidget {
+            widget_id: widget_id,
+            update: RefCell::new(Box::new(update)),
+        }
+    }
+}
+
+impl fmt::Debug for UpdateWidget {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "UpdateWidget({:?})", self.widget_id)
+    }
+}
+
 impl App {
     pub fn add_ui_handlers(&mut self) {
         self.add_handler(|event: &RegisterWidget, args: EventArgs| {
             let event = event.clone();
             let RegisterWidget(widget_ref) = event;
-            args.ui.widget_map.insert(widget_ref.id(), widget_ref.clone());
+            let index = widget_ref.id().index();
+            if args.ui.widgets.len() <= index {
+                args.ui.widgets.resize(index + 1, None);
+            }
+            args.ui.widgets[index] = Some(widget_ref);
         });
         self.add_handler(|event: &RemoveWidget, args: EventArgs| {
             let event = event.clone();
             let RemoveWidget(widget_ref) = event;
             args.ui.solver.remove_layout(widget_ref.id().0);
             args.ui.check_layout_changes();
-            args.ui.widget_map.remove(&widget_ref.id());
+            if args.ui.get_widget(widget_ref.id()).is_some() {
+                args.ui.widgets[widget_ref.id().index()] = None;
+            }
+        });
+        self.add_handler(|event: &UpdateWidget, args: EventArgs| {
+            if let Some(mut widget) = args.ui.get_widget(event.widget_id) {
+                let mut update = event.update.borrow_mut();
+                (&mut **update)(&mut widget);
+            }
         });
     }
 }
 
+/// Sent to the root widget when the window is asked to close while `Ui::set_confirm_close` is set
+#[derive(Debug, Copy, Clone)]
+pub struct WindowCloseRequested;
+
 #[derive(Debug, Copy, Clone)]
 pub struct WidgetAttachedEvent;
 #[derive(Debug, Copy, Clone)]
@@ -0,0 +1,214 @@
+//! Opt-in watchdog that reports frames taking longer than a time budget.
+//!
+//! Enable with `App::enable_watchdog` or `Ui::enable_watchdog`. Each frame is timed by phase, and the
+//! time taken by each event handler, layout update and widget draw is recorded. When a frame goes over
+//! budget a `JankReport` listing the slowest of each is logged as a warning and sent to the root widget.
+//! Recent frame times can also be drawn over the UI as a graph, toggled with F7 by `DebugSettingsHandler`.
+//!
+//! Times are measured with the system clock, even in deterministic mode. Waiting for input and for
+//! vsync isn't counted.
+
+use std::collections::VecDeque;
+use std::fmt;
+use std::time::Duration;
+
+use webrender::api::PrimitiveInfo;
+
+use widget::Widget;
+use resources::WidgetId;
+use render::RenderBuilder;
+use geometry::{Rect, RectExt, Point, Size};
+use color::*;
+
+/// Number of slowest handlers, layouts and draws included in a report
+const REPORT_LENGTH: usize = 5;
+/// Number of frames shown in the frame time graph
+const GRAPH_FRAMES: usize = 120;
+/// Height in the frame time graph of a millisecond
+const GRAPH_PIXELS_PER_MS: f32 = 3.0;
+
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum FramePhase {
+    /// Handling events, including layout updates
+    Events,
+    /// Building the display list
+    Draw,
+    /// Rendering the display list, not including waiting for vsync
+    Render,
+}
+
+/// Time taken by one widget's layout update or draw
+#[derive(Debug, Clone)]
+pub struct WidgetTiming {
+    pub widget: WidgetId,
+    pub name: String,
+    pub duration: Duration,
+}
+
+/// Time taken by one event handler
+#[derive(Debug, Clone)]
+pub struct HandlerTiming {
+    pub widget: WidgetId,
+    pub name: String,
+    /// Types of the handler and the event handled, only available on nightly
+    pub handler: &'static str,
+    pub event: &'static str,
+    pub duration: Duration,
+}
+
+/// Sent to the root widget when a frame goes over budget. The timings are the slowest of the frame, slowest first.
+#[derive(Debug, Clone)]
+pub struct JankReport {
+    pub budget: Duration,
+    pub total: Duration,
+    pub events: Duration,
+    /// Time spent updating the layout, included in `events`
+    pub layout: Duration,
+    pub draw: Duration,
+    pub render: Duration,
+    pub handlers: Vec<HandlerTiming>,
+    pub layouts: Vec<WidgetTiming>,
+    pub draws: Vec<WidgetTiming>,
+}
+
+fn ms(duration: Duration) -> f32 {
+    duration.as_secs() as f32 * 1000.0 + duration.subsec_nanos() as f32 / 1_000_000.0
+}
+
+impl fmt::Display for JankReport {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        writeln!(f, "frame took {:.1}ms, budget {:.1}ms: events {:.1}ms (layout {:.1}ms), draw {:.1}ms, render {:.1}ms",
+            ms(self.total), ms(self.budget), ms(self.events), ms(self.layout), ms(self.draw), ms(self.render))?;
+        for handler in &self.handlers {
+            writeln!(f, "  handler {} for {} on {:?} {:?}: {:.1}ms", handler.handler, handler.event, handler.name, handler.widget, ms(handler.duration))?;
+        }
+        for layout in &self.layouts {
+            writeln!(f, "  layout of {:?} {:?}: {:.1}ms", layout.name, layout.widget, ms(layout.duration))?;
+        }
+        for draw in &self.draws {
+            writeln!(f, "  draw of {:?} {:?}: {:.1}ms", draw.name, draw.widget, ms(draw.duration))?;
+        }
+        Ok(())
+    }
+}
+
+#[derive(Debug, Copy, Clone, Default)]
+struct FrameTimes {
+    events: Duration,
+    draw: Duration,
+    render: Duration,
+}
+
+impl FrameTimes {
+    fn total(&self) -> Duration {
+        self.events + self.draw + self.render
+    }
+}
+
+pub(crate) struct Watchdog {
+    budget: Duration,
+    frame: FrameTimes,
+    layout: Duration,
+    handlers: Vec<HandlerTiming>,
+    layouts: Vec<WidgetTiming>,
+    draws: Vec<WidgetTiming>,
+    history: VecDeque<FrameTimes>,
+}
+
+fn slowest<T, F: Fn(&T) -> Duration>(timings: &mut Vec<T>, duration: F) -> Vec<T> {
+    timings.sort_by(|a, b| duration(b).cmp(&duration(a)));
+    timings.truncate(REPORT_LENGTH);
+    timings.drain(..).collect()
+}
+
+impl Watchdog {
+    pub fn new(budget: Duration) -> Self {
+        Watchdog {
+            budget: budget,
+            frame: FrameTimes::default(),
+            layout: Duration::new(0, 0),
+            handlers: Vec::new(),
+            layouts: Vec::new(),
+            draws: Vec::new(),
+            history: VecDeque::new(),
+        }
+    }
+    pub fn record_phase(&mut self, phase: FramePhase, duration: Duration) {
+        match phase {
+            FramePhase::Events => self.frame.events += duration,
+            FramePhase::Draw => self.frame.draw += duration,
+            FramePhase::Render => self.frame.render += duration,
+        }
+    }
+    pub fn record_handler(&mut self, widget: &Widget, handler: &'static str, event: &'static str, duration: Duration) {
+        self.handlers.push(HandlerTiming {
+            widget: widget.id(),
+            name: widget.name(),
+            handler: handler,
+            event: event,
+            duration: duration,
+        });
+    }
+    pub fn record_layout(&mut self, widget: WidgetId, name: String, duration: Duration) {
+        self.layout += duration;
+        self.layouts.push(WidgetTiming { widget: widget, name: name, duration: duration });
+    }
+    pub fn record_draws(&mut self, draws: Vec<WidgetTiming>) {
+        self.draws.extend(draws);
+    }
+
+    /// Finish timing the current frame, returns a report if it went over budget
+    pub fn end_frame(&mut self) -> Option<JankReport> {
+        let frame = self.frame;
+        self.history.push_back(frame);
+        if self.history.len() > GRAPH_FRAMES {
+            self.history.pop_front();
+        }
+        let report = if frame.total() > self.budget {
+            Some(JankReport {
+                budget: self.budget,
+                total: frame.total(),
+                events: frame.events,
+                layout: self.layout,
+                draw: frame.draw,
+                render: frame.render,
+                handlers: slowest(&mut self.handlers, |timing| timing.duration),
+                layouts: slowest(&mut self.layouts, |timing| timing.duration),
+                draws: slowest(&mut self.draws, |timing| timing.duration),
+            })
+        } else {
+            None
+        };
+        self.frame = FrameTimes::default();
+        self.layout = Duration::new(0, 0);
+        self.handlers.clear();
+        self.layouts.clear();
+        self.draws.clear();
+        report
+    }
+
+    /// Draw recent frame times as a bar graph in the top right of `bounds`, with a line marking the budget
+    pub fn draw_graph(&self, bounds: Rect, renderer: &mut RenderBuilder) {
+        let bar_width = 2.0;
+        let height = ms(self.budget) * GRAPH_PIXELS_PER_MS * 2.0;
+        let graph = Rect::new(
+            Point::new(bounds.right() - GRAPH_FRAMES as f32 * bar_width - 10.0, bounds.top() + 10.0),
+            Size::new(GRAPH_FRAMES as f32 * bar_width, height));
+        renderer.builder.push_rect(&PrimitiveInfo::new(graph), Color(0x000000AA).into());
+        for (index, frame) in self.history.iter().enumerate() {
+            let left = graph.left() + index as f32 * bar_width;
+            let mut bottom = graph.bottom();
+            for &(duration, color) in &[(frame.events, BLUE), (frame.draw, GREEN), (frame.render, YELLOW)] {
+                let bar_height = (ms(duration) * GRAPH_PIXELS_PER_MS).min(bottom - graph.top());
+                if bar_height > 0.0 {
+                    let bar = Rect::new(Point::new(left, bottom - bar_height), Size::new(bar_width, bar_height));
+                    renderer.builder.push_rect(&PrimitiveInfo::new(bar), color.into());
+                    bottom -= bar_height;
+                }
+            }
+        }
+        let budget_y = graph.bottom() - ms(self.budget) * GRAPH_PIXELS_PER_MS;
+        let budget_line = Rect::new(Point::new(graph.left(), budget_y), Size::new(graph.width(), 1.0));
+        renderer.builder.push_rect(&PrimitiveInfo::new(budget_line), RED.into());
+    }
+}
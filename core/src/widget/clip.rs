@@ -0,0 +1,144 @@
+//! Clipping widgets to arbitrary shapes, see `Widget::set_clip_path` and `Widget::set_mask_image`.
+//!
+//! WebRender only clips to rectangles, rounded rectangles and image masks, so a `ClipPath` is
+//! rasterized into a mask image the first time it's drawn. The mask is stretched to the widget's
+//! bounds, so the path only needs to be rasterized once, whatever size the widget is.
+
+use std::f32;
+
+use webrender::api::ImageMask;
+
+use resources::resources;
+use resources::image::ImageSource;
+use resources::WidgetId;
+use geometry::{Point, Rect, RectExt};
+
+/// Width and height of the mask images generated for clip paths, in pixels
+const MASK_SIZE: usize = 256;
+/// Samples per pixel in each direction when rasterizing clip paths, for antialiasing
+const SUBSAMPLES: usize = 4;
+
+/// A closed polygon, with points relative to the widget's bounds, (0.0, 0.0) being the top left
+/// and (1.0, 1.0) the bottom right. Overlapping areas are clipped using the even-odd rule.
+#[derive(Clone, Debug, PartialEq)]
+pub struct ClipPath {
+    pub points: Vec<Point>,
+}
+
+impl ClipPath {
+    pub fn polygon(points: Vec<Point>) -> Self {
+        ClipPath { points: points }
+    }
+    /// A polygon with equal sides filling the bounds, with a point at the top, ex. 6 for a hexagon
+    pub fn regular_polygon(sides: usize) -> Self {
+        let points = (0..sides).map(|side| {
+            let angle = (side as f32 / sides as f32 * 360.0 - 90.0).to_radians();
+            Point::new(0.5 + 0.5 * angle.cos(), 0.5 + 0.5 * angle.sin())
+        }).collect();
+        ClipPath { points: points }
+    }
+
+    /// Is `point`, relative to the bounds the path is stretched to, inside the path
+    pub fn contains(&self, bounds: Rect, point: Point) -> bool {
+        if bounds.width() <= 0.0 || bounds.height() <= 0.0 {
+            return false;
+        }
+        let point = Point::new(
+            (point.x - bounds.left()) / bounds.width(),
+            (point.y - bounds.top()) / bounds.height());
+        crossings(&self.points, point.y).iter().filter(|&&x| x < point.x).count() % 2 == 1
+    }
+
+    /// Rasterize the path to a white image with the path's coverage in the alpha channel
+    fn rasterize(&self) -> Vec<u8> {
+        let samples_across = MASK_SIZE * SUBSAMPLES;
+        let mut coverage = vec![0u16; MASK_SIZE * MASK_SIZE];
+        for sample_row in 0..samples_across {
+            let y = (sample_row as f32 + 0.5) / samples_across as f32;
+            let mut xs = crossings(&self.points, y);
+            xs.sort_by(|a, b| a.partial_cmp(b).unwrap());
+            for span in xs.chunks(2) {
+                if span.len() < 2 {
+                    break;
+                }
+                // samples whose centers are inside the span
+                let start = (span[0] * samples_across as f32 - 0.5).ceil().max(0.0) as usize;
+                let end = (span[1] * samples_across as f32 - 0.5).floor().min(samples_across as f32 - 1.0);
+                if end < 0.0 {
+                    continue;
+                }
+                for sample_column in start..(end as usize + 1) {
+                    coverage[(sample_row / SUBSAMPLES) * MASK_SIZE + sample_column / SUBSAMPLES] += 1;
+                }
+            }
+        }
+        let max_coverage = (SUBSAMPLES * SUBSAMPLES) as u32;
+        let mut data = Vec::with_capacity(MASK_SIZE * MASK_SIZE * 4);
+        for &samples in &coverage {
+            let alpha = (u32::from(samples) * 255 / max_coverage) as u8;
+            data.extend_from_slice(&[255, 255, 255, alpha]);
+        }
+        data
+    }
+}
+
+/// Horizontal positions where a horizontal line at `y` crosses the edges of the polygon
+fn crossings(points: &[Point], y: f32) -> Vec<f32> {
+    let mut xs = Vec::new();
+    for (index, start) in points.iter().enumerate() {
+        let end = points[(index + 1) % points.len()];
+        if (start.y <= y && end.y > y) || (end.y <= y && start.y > y) {
+            xs.push(start.x + (y - start.y) / (end.y - start.y) * (end.x - start.x));
+        }
+    }
+    xs
+}
+
+pub(super) enum ClipMask {
+    Path {
+        path: ClipPath,
+        /// Name of the generated mask image
+        image: String,
+        loaded: bool,
+    },
+    Image(ImageSource),
+}
+
+impl ClipMask {
+    pub fn path(path: ClipPath, widget_id: WidgetId) -> Self {
+        ClipMask::Path {
+            path: path,
+            image: format!("clip_path_{}", widget_id.0),
+            loaded: false,
+        }
+    }
+
+    /// The mask to clip a widget with `bounds` to, generating it first if needed
+    pub fn image_mask(&mut self, bounds: Rect) -> Option<ImageMask> {
+        let mut resources = resources();
+        let source = match *self {
+            ClipMask::Path { ref path, ref image, ref mut loaded } => {
+                if !*loaded {
+                    resources.image_loader.load_raw_image(image, MASK_SIZE as u32, MASK_SIZE as u32, path.rasterize());
+                    *loaded = true;
+                }
+                ImageSource::bundled(image.as_str())
+            }
+            ClipMask::Image(ref source) => source.clone(),
+        };
+        resources.image_loader.get_image(&source).ok().map(|info| {
+            ImageMask {
+                image: info.key,
+                rect: bounds,
+                repeat: false,
+            }
+        })
+    }
+
+    /// Free the generated mask image, if any
+    pub fn remove(&self) {
+        if let ClipMask::Path { ref image, loaded: true, .. } = *self {
+            resources().image_loader.remove_image(&ImageSource::bundled(image.as_str()));
+        }
+    }
+}
@@ -1,3 +1,5 @@
+use std::any::{Any, TypeId};
+use std::collections::HashMap;
 use std::marker::PhantomData;
 
 use mopa;
@@ -5,15 +7,74 @@ use mopa;
 use render::RenderBuilder;
 use event::{EventHandler, EventArgs};
 
-use geometry::Rect;
+use geometry::{Rect, Size};
+use color::Color;
 
 
 pub trait Draw: ::std::fmt::Debug + mopa::Any {
     fn draw(&mut self, bounds: Rect, crop_to: Rect, renderer: &mut RenderBuilder);
+    /// What the draw state shows, for the accessibility audit. Draw states that show text, or fill
+    /// their bounds with a color, should describe it.
+    fn describe(&self) -> DrawDescription {
+        DrawDescription::default()
+    }
+}
+
+/// What a draw state shows, as far as assistive technology is concerned,
+/// see the [`audit`](../../audit/index.html) module
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct DrawDescription {
+    /// Text shown, ex. the label of a button
+    pub text: Option<String>,
+    pub text_color: Option<Color>,
+    /// Color the bounds are filled with, under anything drawn by descendants
+    pub background_color: Option<Color>,
 }
 
 mopafy!(Draw);
 
+/// Data a draw state derives from its fields while drawing, ex. glyph positions or tessellated
+/// paths, kept between frames so static widgets don't redo the work every frame.
+///
+/// Each widget has its own cache, available as `renderer.cache` while its draw state is drawn.
+/// The cache is cleared whenever the widget's draw state is updated or replaced, its style or props
+/// change, or its size changes, so cached data should be relative to the origin of the bounds.
+/// Anything else the data depends on, ex. the time in an animation, should be part of the key, as
+/// should the fields of draw states that can be drawn by another draw state, which share its cache.
+#[derive(Default)]
+pub struct DrawCache {
+    size: Option<Size>,
+    entries: HashMap<TypeId, Box<Any>>,
+}
+
+impl DrawCache {
+    /// The cached value of type `V` for `key`, computed with `compute` if there isn't one, or it
+    /// was computed for a different key. Use `&()` if there's nothing to key on.
+    pub fn get<K, V, F>(&mut self, key: &K, compute: F) -> &V
+        where K: PartialEq + Clone + 'static, V: 'static, F: FnOnce() -> V
+    {
+        let type_id = TypeId::of::<(K, V)>();
+        let stale = self.entries.get(&type_id)
+            .and_then(|entry| entry.downcast_ref::<(K, V)>())
+            .map_or(true, |&(ref cached_key, _)| cached_key != key);
+        if stale {
+            self.entries.insert(type_id, Box::new((key.clone(), compute())));
+        }
+        let &(_, ref value) = self.entries[&type_id].downcast_ref::<(K, V)>().unwrap();
+        value
+    }
+    pub fn clear(&mut self) {
+        self.entries.clear();
+    }
+    /// Clear the cache if the size of the widget changed since it was last drawn
+    pub(crate) fn set_size(&mut self, size: Size) {
+        if self.size != Some(size) {
+            self.size = Some(size);
+            self.entries.clear();
+        }
+    }
+}
+
 pub struct DrawEventHandler<T, E> {
     draw_callback: Box<Fn(&mut T)>,
     phantom: PhantomData<E>,
@@ -2,7 +2,7 @@ use mopa;
 use webrender::api::*;
 
 use render::RenderBuilder;
-use geometry::Rect;
+use geometry::{Rect, Point};
 
 pub trait Filter: mopa::Any {
     fn push(&self, renderer: &mut RenderBuilder);
@@ -42,3 +42,38 @@ impl Filter for OpacityFilter {
         }
     }
 }
+
+/// Scales everything the widget and its children draw by `scale` around `origin`.
+/// Only drawing is scaled, layout and cursor hit testing still use the unscaled bounds.
+pub struct ScaleFilter {
+    pub scale: f32,
+    pub origin: Point,
+}
+
+impl Default for ScaleFilter {
+    fn default() -> Self {
+        ScaleFilter {
+            scale: 1.0,
+            origin: Point::zero(),
+        }
+    }
+}
+
+impl Filter for ScaleFilter {
+    fn push(&self, renderer: &mut RenderBuilder) {
+        if self.scale != 1.0 {
+            let (scale, origin) = (self.scale, self.origin);
+            let transform = LayoutTransform::row_major(
+                scale, 0.0, 0.0, 0.0,
+                0.0, scale, 0.0, 0.0,
+                0.0, 0.0, 1.0, 0.0,
+                origin.x * (1.0 - scale), origin.y * (1.0 - scale), 0.0, 1.0);
+            renderer.builder.push_reference_frame(&PrimitiveInfo::new(Rect::zero()), Some(PropertyBinding::Value(transform)), None);
+        }
+    }
+    fn pop(&self, renderer: &mut RenderBuilder) {
+        if self.scale != 1.0 {
+            renderer.builder.pop_reference_frame();
+        }
+    }
+}
@@ -13,6 +13,7 @@
 pub mod property;
 pub mod draw;
 pub mod filter;
+pub mod clip;
 
 use std::any::{TypeId, Any};
 use std::collections::HashMap;
@@ -22,20 +23,24 @@ use std::hash::{Hash, Hasher};
 use std::ops::{Deref, DerefMut};
 use std::fmt;
 use std::fmt::Debug;
+use std::time::Instant;
 
 use render::RenderBuilder;
-use event::{self, EventHandler, EventArgs, EventHandlerWrapper};
+use event::{self, EventHandler, EventArgs, EventHandlerWrapper, Priority};
 use layout::{Layout, LayoutVars, LayoutRef, LayoutUpdated, VarType};
 use ui::Ui;
-use resources::{resources, WidgetId};
-use geometry::{Point, Rect};
+use resources::{resources, WidgetId, next_widget_id, free_widget_id};
+use geometry::{Point, Rect, RectExt};
 use render;
 use color::Color;
+use watchdog::WidgetTiming;
 use event::Target;
 use layout::UpdateLayout;
 use style::*;
 
 use widget::filter::Filter;
+use widget::clip::{ClipPath, ClipMask};
+use resources::image::ImageSource;
 
 use self::property::{PropSet, Property};
 use self::draw::*;
@@ -80,6 +85,7 @@ impl Widget {
 
     pub fn set_draw_state<T: Draw + Component + 'static>(&mut self, draw_state: T) -> &mut Self {
         self.widget_mut().draw_state.set_draw_state(draw_state);
+        self.widget_mut().draw_cache.clear();
         self
     }
 
@@ -90,6 +96,26 @@ impl Widget {
         self
     }
 
+    /// Isolate this widget and its descendants from the application's class styles, so a composite
+    /// widget looks the same in any app. Their class styles are taken from `theme.scope(scope)` instead,
+    /// apart from classes the scope exports. Descendants with their own scope, ie. nested composite widgets, keep it.
+    /// Widget modifier styles are resolved when a widget is created, so aren't affected.
+    pub fn set_style_scope(&mut self, scope: &str) -> &mut Self {
+        self.widget_mut().owns_style_scope = true;
+        self.apply_style_scope(Some(scope.to_owned()));
+        self
+    }
+
+    fn apply_style_scope(&mut self, scope: Option<String>) {
+        self.widget_mut().draw_state.set_scope(scope.clone());
+        self.update_draw_state();
+        for mut child in self.children() {
+            if !child.widget().owns_style_scope {
+                child.apply_style_scope(scope.clone());
+            }
+        }
+    }
+
     pub fn set_cursor_hit_fn<F: Fn(Rect, Point) -> bool + 'static>(&mut self, cursor_hit_fn: F) -> &mut Self {
         self.widget_mut().cursor_hit_fn = Some(Box::new(cursor_hit_fn));
         self
@@ -100,6 +126,40 @@ impl Widget {
         self
     }
 
+    /// Clip the widget and its children to `path`, stretched to the widget's bounds.
+    /// Unless the widget has its own cursor hit function, only points inside the path are under the cursor.
+    pub fn set_clip_path(&mut self, path: ClipPath) -> &mut Self {
+        let id = self.id();
+        if self.widget().cursor_hit_fn.is_none() {
+            let hit_path = path.clone();
+            self.set_cursor_hit_fn(move |bounds, cursor| hit_path.contains(bounds, cursor));
+        }
+        self.set_clip_mask(Some(ClipMask::path(path, id)))
+    }
+
+    /// Mask the widget and its children with an image stretched to the widget's bounds.
+    /// Transparent or black areas of the image are hidden.
+    pub fn set_mask_image(&mut self, source: ImageSource) -> &mut Self {
+        self.set_clip_mask(Some(ClipMask::Image(source)))
+    }
+
+    /// Remove any clip path or mask image
+    pub fn remove_clip(&mut self) -> &mut Self {
+        self.set_clip_mask(None)
+    }
+
+    fn set_clip_mask(&mut self, clip_mask: Option<ClipMask>) -> &mut Self {
+        let old_mask = {
+            let mut widget = self.widget_mut();
+            widget.has_updated = true;
+            ::std::mem::replace(&mut widget.clip_mask, clip_mask)
+        };
+        if let Some(old_mask) = old_mask {
+            old_mask.remove();
+        }
+        self
+    }
+
     fn new_inner(widget: WidgetInner) -> Self {
         let widget_ref = Widget(Rc::new(RefCell::new(widget)));
         event::event(Target::Root, ::ui::RegisterWidget(widget_ref.clone()));
@@ -143,6 +203,13 @@ impl Widget {
         }
         self.event(LayoutUpdated);
     }
+    /// Set the bounds of a widget placed without the solver
+    pub(crate) fn set_bounds(&mut self, bounds: Rect) {
+        if self.0.borrow().bounds != bounds {
+            self.0.borrow_mut().bounds = bounds;
+            self.event(LayoutUpdated);
+        }
+    }
     pub fn props(&self) -> PropsGuard {
         PropsGuard { guard: self.0.borrow() }
     }
@@ -186,6 +253,50 @@ impl Widget {
     pub fn name(&self) -> String {
         self.0.borrow().name.clone()
     }
+    /// Name the widget for assistive technology, ex. a button with an icon rather than a label.
+    /// Widgets that show text are named by it, so don't need one.
+    pub fn set_accessible_name(&mut self, name: &str) -> &mut Self {
+        self.widget_mut().accessible_name = Some(name.to_owned());
+        self
+    }
+    pub fn accessible_name(&self) -> Option<String> {
+        self.0.borrow().accessible_name.clone()
+    }
+    /// The color the widget is drawn over: its own background and those of its ancestors,
+    /// translucent ones blended over the nearest opaque one, or the window.
+    /// Backgrounds are found with `Draw::describe`.
+    pub fn effective_background_color(&self) -> Color {
+        let mut layers = Vec::new();
+        let mut next = Some(self.clone());
+        while let Some(mut ancestor) = next {
+            if let Some(color) = ancestor.draw_state().describe().background_color {
+                layers.push(color);
+                if color.is_opaque() {
+                    break;
+                }
+            }
+            next = ancestor.parent();
+        }
+        let start = match layers.last() {
+            Some(&color) if color.is_opaque() => color,
+            _ => render::WINDOW_BACKGROUND,
+        };
+        layers.iter().rev().fold(start, |background, &layer| {
+            let alpha = (layer.0 & 0xFF) as f32 / 255.0;
+            background.mix(Color(layer.0 | 0xFF), alpha)
+        })
+    }
+    /// Whichever of the theme's contrast colors, black and white by default, is more readable on
+    /// the widget's effective background, for its text or icons
+    pub fn contrasting_color(&self) -> Color {
+        let background = self.effective_background_color();
+        let (light, dark) = resources().theme.contrast_colors();
+        background.contrasting(light, dark)
+    }
+    /// Whether the widget has a handler for events of type `E`
+    pub fn has_handler<E: 'static>(&self) -> bool {
+        self.0.borrow().handlers.get(&TypeId::of::<E>()).map_or(false, |handlers| !handlers.is_empty())
+    }
     pub fn debug_color(&self) -> Option<Color> {
         self.0.borrow().debug_color
     }
@@ -217,6 +328,10 @@ impl Widget {
         event::event(Target::Root, ::layout::UpdateLayout(child.clone()));
         child.widget_mut().parent = Some(self.downgrade());
         child.widget_mut().props.extend(self.props().iter().cloned());
+        let scope = self.widget().draw_state.scope().map(String::from);
+        if !child.widget().owns_style_scope && child.widget().draw_state.scope() != scope.as_ref().map(|scope| scope.as_str()) {
+            child.apply_style_scope(scope);
+        }
         self.widget_mut().children.push(child.clone());
         self.layout().add_child(child.layout().deref_mut());
         self.event(::ui::WidgetAttachedEvent);
@@ -237,6 +352,23 @@ impl Widget {
         event::event(Target::Root, ::ui::RemoveWidget(child.clone()));
     }
 
+    /// Moves an existing child to `index` among this widgets children,
+    /// this affects both draw order and the order used by the layout container.
+    pub fn move_child(&mut self, child: &Widget, index: usize) {
+        let child_id = child.id();
+        {
+            let mut widget = self.widget_mut();
+            if let Some(pos) = widget.children.iter().position(|widget| widget.id() == child_id) {
+                let child = widget.children.remove(pos);
+                let index = ::std::cmp::min(index, widget.children.len());
+                widget.children.insert(index, child);
+            } else {
+                return;
+            }
+        }
+        self.layout().move_child(child_id.0, index);
+    }
+
     pub fn remove_widget(&mut self) {
         if let Some(mut parent) = self.parent() {
             parent.remove_child(self.clone());
@@ -255,9 +387,27 @@ impl Widget {
         self.children().iter().find(|child| child.name() == name).cloned()
     }
 
+    /// Find the first widget named `name` below this one, searching depth first.
+    pub fn find_descendant(&self, name: &str) -> Option<Widget> {
+        for child in self.children() {
+            if child.name() == name {
+                return Some(child);
+            }
+            if let Some(widget) = child.find_descendant(name) {
+                return Some(widget);
+            }
+        }
+        None
+    }
+
     pub fn event<T: 'static>(&self, data: T) {
         event::event(Target::Widget(self.clone()), data);
     }
+    /// Send an event to this widget in the given lane of the event queue, rather than the lane
+    /// of the event being handled
+    pub fn event_with_priority<T: 'static>(&self, priority: Priority, data: T) {
+        event::event_with_priority(Target::Widget(self.clone()), priority, data);
+    }
     pub fn event_subtree<T: 'static>(&self, data: T) {
         event::event(Target::SubTree(self.clone()), data);
     }
@@ -280,12 +430,18 @@ impl Widget {
         for event_handler in handlers {
             // will panic in the case of circular handler calls
             let mut handler = event_handler.borrow_mut();
-            let event_args = EventArgs {
-                widget: self.clone(),
-                ui: ui,
-                handled: &mut handled,
-            };
-            handler.handle(event, event_args);
+            let start = Instant::now();
+            {
+                let event_args = EventArgs {
+                    widget: self.clone(),
+                    ui: ui,
+                    handled: &mut handled,
+                };
+                handler.handle(event, event_args);
+            }
+            if let Some(ref mut watchdog) = ui.watchdog {
+                watchdog.record_handler(self, handler.handler_name, handler.event_name, start.elapsed());
+            }
         }
         handled
     }
@@ -305,14 +461,30 @@ impl Widget {
     }
 
     fn draw_widget(&mut self, crop_to: Rect, renderer: &mut RenderBuilder) {
-        let bounds = self.bounds();
-        let clip_id = renderer.builder.define_clip(bounds, vec![], None);
+        let bounds = self.bounds().snap_to_device(renderer.device_pixel_ratio);
+        let image_mask = self.widget_mut().clip_mask.as_mut().and_then(|clip_mask| clip_mask.image_mask(bounds));
+        let clip_id = renderer.builder.define_clip(bounds, vec![], image_mask);
         renderer.builder.push_clip_id(clip_id);
         for (_, filter) in &self.widget().filters {
             filter.push(renderer);
         }
-        if let Some(draw_state) = self.widget_mut().draw_state.state.as_mut() {
-            draw_state.draw(bounds, crop_to, renderer);
+        let start = Instant::now();
+        {
+            let mut widget = self.widget_mut();
+            let widget = &mut *widget;
+            if let Some(draw_state) = widget.draw_state.state.as_mut() {
+                widget.draw_cache.set_size(bounds.size);
+                ::std::mem::swap(&mut renderer.cache, &mut widget.draw_cache);
+                draw_state.draw(bounds, crop_to, renderer);
+                ::std::mem::swap(&mut renderer.cache, &mut widget.draw_cache);
+            }
+        }
+        if let Some(ref mut draw_timings) = renderer.draw_timings {
+            draw_timings.push(WidgetTiming {
+                widget: self.id(),
+                name: self.name(),
+                duration: start.elapsed(),
+            });
         }
         if let Some(crop_to) = crop_to.intersection(&bounds) {
             for child in &mut self.children() {
@@ -338,6 +510,7 @@ impl Widget {
         if self.widget().has_updated | self.widget().props_updated | self.widget().draw_state.needs_update() {
             let props = (*self.props()).clone();
             self.widget_mut().draw_state.update(props);
+            self.widget_mut().draw_cache.clear();
             self.event(StyleUpdated);
             self.event(StateUpdated);
             self.widget_mut().has_updated = true;
@@ -423,6 +596,10 @@ impl<'a> DrawStateGuard<'a> {
             None
         }
     }
+    /// Describe the draw state, see `Draw::describe`
+    pub fn describe(&self) -> DrawDescription {
+        self.guard.draw_state.state.as_ref().map(|draw_state| draw_state.describe()).unwrap_or_default()
+    }
     pub fn style(&mut self) -> Option<&mut DrawStyle> {
         if let Some(ref mut style) = self.guard.draw_state.style {
             Some(style)
@@ -449,7 +626,10 @@ impl WidgetWeak {
 pub(super) struct WidgetInner {
     id: WidgetId,
     pub(super) draw_state: DrawState,
+    draw_cache: DrawCache,
     filters: HashMap<TypeId, Box<Filter>>,
+    clip_mask: Option<ClipMask>,
+    owns_style_scope: bool,
     cursor_hit_fn: Option<Box<Fn(Rect, Point) -> bool>>,
     props: PropSet,
     has_updated: bool,
@@ -457,20 +637,30 @@ pub(super) struct WidgetInner {
     pub(super) layout: Layout,
     pub(super) bounds: Rect,
     name: String,
+    accessible_name: Option<String>,
     debug_color: Option<Color>,
     children: Vec<Widget>,
     parent: Option<WidgetWeak>,
     handlers: HashMap<TypeId, Vec<Rc<RefCell<EventHandlerWrapper>>>>,
 }
 
+impl Drop for WidgetInner {
+    fn drop(&mut self) {
+        free_widget_id(self.id);
+    }
+}
+
 impl WidgetInner {
     fn new<S: Into<String>>(name: S) -> Self {
-        let id = resources().widget_id();
+        let id = next_widget_id();
         let name: String = name.into();
         WidgetInner {
             id: id,
             draw_state: DrawState::default(),
+            draw_cache: DrawCache::default(),
             filters: HashMap::new(),
+            clip_mask: None,
+            owns_style_scope: false,
             cursor_hit_fn: None,
             props: PropSet::new(),
             layout: Layout::new(id.0, Some(name.clone())),
@@ -478,6 +668,7 @@ impl WidgetInner {
             props_updated: true,
             bounds: Rect::zero(),
             name: name,
+            accessible_name: None,
             debug_color: None,
             children: Vec::new(),
             parent: None,
@@ -489,6 +680,7 @@ impl WidgetInner {
     {
         if let Some(ref mut draw_state) = self.draw_state.state {
             self.has_updated = true;
+            self.draw_cache.clear();
             let state = draw_state.downcast_mut::<T>().expect("Called update on widget with wrong draw_state type");
             f(state);
         }
@@ -4,15 +4,21 @@ use glutin::GlContext;
 use glutin::dpi::LogicalSize;
 use webrender::api::DeviceUintSize;
 use geometry::Size;
+use quality::QualityPreset;
 
 /// A simple wrapper around a `glutin::GlWindow`.
 pub struct Window {
     pub window: glutin::GlWindow
 }
 impl Window {
-    pub fn new(mut window_builder: glutin::WindowBuilder, events_loop: &glutin::EventsLoop) -> Self {
+    pub fn new(window_builder: glutin::WindowBuilder, events_loop: &glutin::EventsLoop) -> Self {
+        Self::with_quality(window_builder, events_loop, QualityPreset::default())
+    }
+    /// Create a window with the multisampling of `quality`, which can't be changed later
+    pub fn with_quality(mut window_builder: glutin::WindowBuilder, events_loop: &glutin::EventsLoop, quality: QualityPreset) -> Self {
         let context = glutin::ContextBuilder::new()
             .with_vsync(true)
+            .with_multisampling(quality.settings().multisampling)
             .with_gl(glutin::GlRequest::GlThenGles {
                 opengl_version: (3, 2),
                 opengles_version: (3, 0)
@@ -45,8 +51,8 @@ impl Window {
     /// This is the size of the framebuffer
     pub fn size_px(&self) -> DeviceUintSize {
         let LogicalSize{width, height} = self.window.get_inner_size().unwrap();
-        let hidpi = self.hidpi_factor();
-        DeviceUintSize::new(width as u32 * hidpi as u32, height as u32 * hidpi as u32)
+        let hidpi = self.window.get_hidpi_factor();
+        DeviceUintSize::new((width * hidpi).round() as u32, (height * hidpi).round() as u32)
     }
     /// Get the size of the client area of the window in density independent pixels.
     pub fn size_dp(&self) -> Size {
@@ -56,4 +62,10 @@ impl Window {
     pub fn show(&self) {
         self.window.show()
     }
+    pub fn set_cursor(&self, cursor: glutin::MouseCursor) {
+        self.window.set_cursor(cursor)
+    }
+    pub fn set_title(&self, title: &str) {
+        self.window.set_title(title)
+    }
 }
@@ -0,0 +1,42 @@
+#[allow(unused_imports)]
+#[macro_use]
+extern crate limn;
+
+mod util;
+
+use limn::prelude::*;
+
+static BODY: &'static str = "Rust is a systems programming language that runs blazingly fast, \
+prevents segfaults, and guarantees thread safety. Its ownership model lets the compiler check \
+that references never outlive the data they point to, and that data shared between threads is \
+never mutated without synchronization. Zero cost abstractions mean high level code compiles \
+down to the same machine code you would write by hand, and a minimal runtime makes it a good \
+fit for embedding in other languages.";
+
+fn main() {
+    let window_builder = glutin::WindowBuilder::new()
+        .with_title("Limn article demo")
+        .with_min_dimensions(glutin::dpi::LogicalSize{width: 500.0, height: 400.0});
+    let app = util::init(window_builder);
+    resources().image_loader.load_image("rust", include_bytes!("../assets/images/rust.png").to_vec());
+    let mut root = Widget::new("root");
+
+    let mut body = Widget::new("body");
+    body.set_draw_style(style!(TextStyle {
+        text: BODY.to_owned(),
+        font_size: 18.0,
+    }));
+    body.layout().add(constraints![
+        bound_by(&root).padding(30.0),
+        match_layout(&root).padding(30.0).strength(STRONG),
+    ]);
+
+    let mut figure = FigureBuilder::new(ImageSource::bundled("rust"));
+    figure
+        .set_caption("The Rust logo")
+        .set_width(120.0);
+    figure.float_in(&mut body, FloatSide::Left, 12.0);
+    root.add_child(body);
+
+    app.main_loop(root);
+}
@@ -0,0 +1,24 @@
+#[allow(unused_imports)]
+#[macro_use]
+extern crate limn;
+
+mod util;
+
+use limn::prelude::*;
+
+/// 7GUIs task 7, cells.
+/// Select a cell and type in the formula bar, ex. `=sum(A1:A5)` or `=B2 * 2`.
+fn main() {
+    let window_builder = glutin::WindowBuilder::new()
+        .with_title("Limn cells")
+        .with_min_dimensions(glutin::dpi::LogicalSize{width: 600.0, height: 400.0});
+    let mut app = util::init(window_builder);
+    let mut root = Widget::new("root");
+    root.layout().add(min_size(Size::new(600.0, 400.0)));
+
+    let mut cells = CellsBuilder::new(10, 30);
+    cells.layout().add(bound_by(&root).padding(10.0));
+    root.add_child(cells);
+
+    app.main_loop(root);
+}
@@ -0,0 +1,51 @@
+#[allow(unused_imports)]
+#[macro_use]
+extern crate limn;
+
+mod util;
+
+use limn::prelude::*;
+
+fn main() {
+    let window_builder = glutin::WindowBuilder::new()
+        .with_title("Limn chart demo")
+        .with_min_dimensions(glutin::dpi::LogicalSize{width: 600.0, height: 400.0});
+    let mut app = util::init(window_builder);
+    let mut root = Widget::new("root");
+
+    let points = |f: fn(f32) -> f32| -> Vec<(f32, f32)> { (0..1000).map(|i| i as f32 * 0.02).map(|x| (x, f(x))).collect() };
+    let mut chart = ChartBuilder::new()
+        .add_series(Series::new("sin", points(f32::sin)).color(BLUE))
+        .add_series(Series::new("damped", points(|x| (x * 2.0).cos() * (-x * 0.1).exp())).color(RED))
+        .set_brushing(true)
+        .set_axes(ChartAxes::Both)
+        .set_legend(true)
+        .add_annotation(Annotation::Region { axis: Axis::X, range: (5.0, 7.5), color: Color(0xFFCC0033) })
+        .add_annotation(Annotation::Line { axis: Axis::Y, value: 0.8, color: GRAY_50 })
+        .add_annotation(Annotation::Callout { point: (1.57, 1.0), text: "peak".to_owned(), color: BLACK })
+        .build();
+
+    let mut selection = Widget::new("selection");
+    StaticTextStyle::from_text("Drag across the chart to select a range").component().apply(&mut selection);
+    selection.layout().add(constraints![
+        align_left(&root).padding(20.0),
+        align_bottom(&root).padding(20.0),
+    ]);
+    chart.layout().add(constraints![
+        align_left(&root).padding(20.0),
+        align_right(&root).padding(20.0),
+        align_top(&root).padding(20.0),
+        above(&selection).padding(20.0),
+    ]);
+    let selection_text = selection.clone();
+    chart.add_handler(move |event: &ChartBrushed, _: EventArgs| {
+        let text = match event.0 {
+            Some((start, end)) => format!("Selected {:.2} to {:.2}", start, end),
+            None => "Nothing selected".to_owned(),
+        };
+        selection_text.event(TextUpdated(text));
+    });
+    root.add_child(chart);
+    root.add_child(selection);
+    app.main_loop(root);
+}
@@ -0,0 +1,86 @@
+#[allow(unused_imports)]
+#[macro_use]
+extern crate limn;
+
+mod util;
+
+use std::{thread, time};
+use std::rc::Rc;
+use std::cell::RefCell;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use limn::prelude::*;
+
+const REPLIES: &[&str] = &[
+    "Sounds good!",
+    "I'll have a look at it after lunch, the layout code is a bit tangled in that area so it might take a while.",
+    "Did you try it on the latest master?",
+    "Nice one",
+];
+
+fn now() -> u64 {
+    SystemTime::now().duration_since(UNIX_EPOCH).unwrap().as_secs()
+}
+
+fn main() {
+    let window_builder = glutin::WindowBuilder::new()
+        .with_title("Limn chat demo")
+        .with_min_dimensions(glutin::dpi::LogicalSize{width: 300.0, height: 300.0});
+    let mut app = util::init(window_builder);
+    let mut root = Widget::new("root");
+
+    let yesterday = now() - 24 * 60 * 60;
+    let messages = vec![
+        ChatMessage::new("Alex", "Is anyone looking at the scroll bug?", yesterday),
+        ChatMessage::new("Sam", "Not yet, I can take it tomorrow", yesterday + 120).outgoing(),
+        ChatMessage::new("Alex", "Great, thanks. It only shows up with very long lists, \
+            so try the list example with a few thousand items.", yesterday + 300),
+        ChatMessage::new("Sam", "Found it, it was an off by one in the clipping", now() - 60).outgoing(),
+    ];
+    let mut chat = ChatViewBuilder::new(messages);
+
+    let mut input = Widget::from_modifier(EditText::default());
+    let mut send_button = Widget::from_modifier_style(ButtonStyle::from_text("Send"));
+    chat.layout().add(constraints![
+        align_top(&root),
+        align_left(&root),
+        align_right(&root),
+        above(&input).padding(10.0),
+    ]);
+    input.layout().add(constraints![
+        align_left(&root).padding(10.0),
+        align_bottom(&root).padding(10.0),
+        to_left_of(&send_button).padding(10.0),
+    ]);
+    send_button.layout().add(constraints![
+        align_right(&root).padding(10.0),
+        align_bottom(&root).padding(10.0),
+    ]);
+    let text = Rc::new(RefCell::new(String::new()));
+    let text_ref = text.clone();
+    input.add_handler(move |event: &TextUpdated, _: EventArgs| {
+        *text_ref.borrow_mut() = event.0.clone();
+    });
+    let chat_ref = chat.clone();
+    let input_ref = input.clone();
+    send_button.add_handler(move |_: &ClickEvent, _: EventArgs| {
+        let text = text.borrow().clone();
+        if !text.is_empty() {
+            chat_ref.event(ChatEvent::AddMessage(ChatMessage::new("Sam", &text, now()).outgoing()));
+            input_ref.event(TextUpdated(String::new()));
+        }
+    });
+
+    // someone else replying now and then
+    let chat_id = chat.id();
+    thread::spawn(move || {
+        let sender = EventSender;
+        for reply in REPLIES.iter().cycle() {
+            thread::sleep(time::Duration::from_millis(4000));
+            sender.widget_event(chat_id, ChatEvent::AddMessage(ChatMessage::new("Alex", reply, now())));
+        }
+    });
+
+    root.add_child(chat).add_child(input).add_child(send_button);
+    app.main_loop(root);
+}
@@ -18,8 +18,6 @@ use limn::draw::ellipse::EllipseState;
 
 type Angle = euclid::Angle<f32>;
 
-struct ClockTick;
-
 #[derive(Debug, Clone)]
 pub struct ClockHand {
     color: Color,
@@ -93,7 +91,19 @@ fn rotation(fraction: f32) -> Angle {
 
 struct ClockBuilder {
     widget: Widget,
+    hands: Vec<(WidgetId, fn() -> Angle)>,
+}
+
+fn hour_angle() -> Angle {
+    rotation((Local::now().hour() % 12) as f32 / 12.0)
+}
+fn minute_angle() -> Angle {
+    rotation(Local::now().minute() as f32 / 60.0)
 }
+fn second_angle() -> Angle {
+    rotation(Local::now().second() as f32 / 60.0)
+}
+
 impl ClockBuilder {
     fn new() -> Self {
 
@@ -105,34 +115,24 @@ impl ClockBuilder {
         widget.set_draw_state(ellipse);
         widget.layout().add(size(Size::new(200.0, 200.0)));
 
-        let hour_angle = || rotation((Local::now().hour() % 12) as f32 / 12.0);
-        let minute_angle = || rotation(Local::now().minute() as f32 / 60.0);
-        let second_angle = || rotation(Local::now().second() as f32 / 60.0);
         let mut hour_widget = Widget::new("hours");
-        hour_widget
-            .set_draw_state(ClockHand::new(BLACK, 4.0, 60.0, hour_angle()))
-            .add_handler(DrawEventHandler::new(ClockTick, move |state: &mut ClockHand| {
-                state.rotation = hour_angle()
-            }));
+        hour_widget.set_draw_state(ClockHand::new(BLACK, 4.0, 60.0, hour_angle()));
         let mut minute_widget = Widget::new("minutes");
-        minute_widget
-            .set_draw_state(ClockHand::new(BLACK, 3.0, 90.0, minute_angle()))
-            .add_handler(DrawEventHandler::new(ClockTick, move |state: &mut ClockHand| {
-                state.rotation = minute_angle()
-            }));
+        minute_widget.set_draw_state(ClockHand::new(BLACK, 3.0, 90.0, minute_angle()));
         let mut second_widget = Widget::new("seconds");
-        second_widget
-            .set_draw_state(ClockHand::new(RED, 2.0, 80.0, second_angle()))
-            .add_handler(DrawEventHandler::new(ClockTick, move |state: &mut ClockHand| {
-                state.rotation = second_angle()
-            }));
+        second_widget.set_draw_state(ClockHand::new(RED, 2.0, 80.0, second_angle()));
+        let hands: Vec<(WidgetId, fn() -> Angle)> = vec![
+            (hour_widget.id(), hour_angle),
+            (minute_widget.id(), minute_angle),
+            (second_widget.id(), second_angle),
+        ];
 
         widget
             .add_child(hour_widget)
             .add_child(minute_widget)
             .add_child(second_widget);
 
-        ClockBuilder { widget: widget }
+        ClockBuilder { widget: widget, hands: hands }
     }
 }
 
@@ -140,23 +140,24 @@ fn main() {
     let window_builder = glutin::WindowBuilder::new()
         .with_title("Limn clock demo")
         .with_min_dimensions(100, 100);
-    let mut app = util::init(window_builder);
+    let app = util::init(window_builder);
     let mut root = Widget::new("root");
 
-    let mut clock = ClockBuilder::new().widget;
+    let ClockBuilder { widget: mut clock, hands } = ClockBuilder::new();
     clock.layout().add(constraints![
         center(&root),
         bound_by(&root).padding(50.0),
     ]);
-    let clock_ref = clock.clone();
     root.add_child(clock);
 
+    let sender = EventSender;
     thread::spawn(move || loop {
         thread::sleep(time::Duration::from_millis(1000));
-        event_global(ClockTick);
-    });
-    app.add_handler(move |_: &ClockTick, _: EventArgs| {
-        clock_ref.event_subtree(ClockTick);
+        for &(hand, angle) in &hands {
+            sender.update_widget(hand, move |state: &mut ClockHand| {
+                state.rotation = angle()
+            });
+        }
     });
     app.main_loop(root);
 }
@@ -0,0 +1,44 @@
+#[allow(unused_imports)]
+#[macro_use]
+extern crate limn;
+
+mod util;
+
+use limn::prelude::*;
+use limn::resources::font::FontDescriptor;
+
+static CODE: &'static str = "fn main() {
+    let numbers = vec![1, 2, 3];
+    for number in &numbers {
+        println!(\"{}\", number * 2);
+    }
+}
+";
+
+fn main() {
+    let window_builder = glutin::WindowBuilder::new()
+        .with_title("Limn code view demo")
+        .with_min_dimensions(glutin::dpi::LogicalSize{width: 500.0, height: 400.0});
+    let app = util::init(window_builder);
+    resources().font_loader.register_font_data(FontDescriptor::from_family("Hack"), include_bytes!("../assets/fonts/Hack/Hack-Regular.ttf").to_vec()).unwrap();
+    let mut root = Widget::new("root");
+
+    let mut code_view = CodeViewBuilder::new(CODE);
+    code_view.set_editable(true);
+    let mut code_view = code_view.build();
+    code_view.add_handler(|event: &CodeChanged, _: EventArgs| {
+        println!("{} lines", event.0.lines().count());
+    });
+    code_view.add_handler(|event: &FoldChanged, _: EventArgs| {
+        println!("line {} folded {}", event.line + 1, event.folded);
+    });
+
+    let mut scroll_widget = ScrollContainer::default();
+    scroll_widget.add_scrollbar();
+    scroll_widget.add_content(code_view);
+    let mut scroll_widget = Widget::from_modifier(scroll_widget);
+    scroll_widget.layout().add(match_layout(&root).padding(20.0));
+    root.add_child(scroll_widget);
+
+    app.main_loop(root);
+}
@@ -85,31 +85,12 @@ impl PeopleHandler {
     fn add_person(&mut self) {
         let id = self.id_gen.next_id();
         self.people.insert(id, self.person.clone());
-        let list_item_widget = {
-            let text_style = style!(TextStyle {
-                text: self.person.name(),
+        let mut list_item_widget = ListItemBuilder::new(&self.person.name()).build(&self.widgets.list_widget);
+        list_item_widget
+            .add_handler(ListItemHandler::new(self.widgets.list_widget.clone()))
+            .add_handler(move |_: &ItemSelected, args: EventArgs| {
+                args.ui.event(PeopleEvent::PersonSelected(Some(id)));
             });
-            let text_style = StaticTextStyle {
-                style: Some(text_style),
-            };
-            let mut list_item_widget = Widget::new("list_item");
-            list_item_widget
-                .set_draw_style(DrawStyle::from_class::<RectStyle>("list_item_rect"))
-                .add_handler(ListItemHandler::new(self.widgets.list_widget.clone()))
-                .add_handler(move |_: &ItemSelected, args: EventArgs| {
-                    args.ui.event(PeopleEvent::PersonSelected(Some(id)));
-                })
-                .enable_hover();
-
-            let mut list_text_widget = Widget::from_modifier_style(text_style);
-            list_text_widget
-                .set_draw_style(DrawStyle::from_class::<RectStyle>("list_item_text"));
-            list_text_widget.layout().add(constraints![
-                match_height(&list_item_widget),
-                align_left(&list_item_widget)]);
-            list_item_widget.add_child(list_text_widget);
-            list_item_widget
-        };
         self.people_widgets.insert(id, list_item_widget.clone());
         self.widgets.list_widget.add_child(list_item_widget);
     }
@@ -0,0 +1,32 @@
+#[allow(unused_imports)]
+#[macro_use]
+extern crate limn;
+
+mod util;
+
+use std::env;
+
+use limn::prelude::*;
+
+fn main() {
+    let window_builder = glutin::WindowBuilder::new()
+        .with_title("Limn file browser demo")
+        .with_min_dimensions(glutin::dpi::LogicalSize{width: 700.0, height: 500.0});
+    let mut app = util::init(window_builder);
+    let mut root = Widget::new("root");
+    root.layout().add(min_size(Size::new(700.0, 500.0)));
+
+    let path = env::current_dir().unwrap();
+    let mut file_browser = FileBrowserBuilder::new(path);
+    file_browser.layout().add(bound_by(&root).padding(10.0));
+    file_browser
+        .add_handler(|event: &FileActivated, _: EventArgs| {
+            println!("activated {}", event.0.display());
+        })
+        .add_handler(|event: &FilesSelected, _: EventArgs| {
+            println!("{} files selected", event.0.len());
+        });
+    root.add_child(file_browser);
+
+    app.main_loop(root);
+}
@@ -0,0 +1,162 @@
+#[allow(unused_imports)]
+#[macro_use]
+extern crate limn;
+
+mod util;
+
+use limn::prelude::*;
+
+/// 7GUIs task 3, flight booker.
+/// The return date is only editable for return flights, and booking is only possible
+/// when the dates are valid and the return date isn't before the departure date.
+#[derive(Clone)]
+enum BookingEvent {
+    SetReturn(bool),
+    Depart(String),
+    Return(String),
+    Book,
+}
+
+struct Widgets {
+    return_box: Widget,
+    book_button: Widget,
+    message: Widget,
+}
+
+struct BookingHandler {
+    widgets: Widgets,
+    is_return: bool,
+    depart: String,
+    return_: String,
+}
+
+/// Parse a date in the form dd.mm.yyyy, returning a value that sorts chronologically
+fn parse_date(text: &str) -> Option<(u32, u32, u32)> {
+    let parts: Vec<&str> = text.trim().split('.').collect();
+    if parts.len() != 3 {
+        return None;
+    }
+    let day = parts[0].parse::<u32>().ok()?;
+    let month = parts[1].parse::<u32>().ok()?;
+    let year = parts[2].parse::<u32>().ok()?;
+    if month < 1 || month > 12 || day < 1 || day > days_in_month(year, month) {
+        return None;
+    }
+    Some((year, month, day))
+}
+
+fn days_in_month(year: u32, month: u32) -> u32 {
+    match month {
+        2 if year % 4 == 0 && (year % 100 != 0 || year % 400 == 0) => 29,
+        2 => 28,
+        4 | 6 | 9 | 11 => 30,
+        _ => 31,
+    }
+}
+
+impl BookingHandler {
+    fn is_valid(&self) -> bool {
+        match (parse_date(&self.depart), self.is_return) {
+            (Some(_), false) => true,
+            (Some(depart), true) => parse_date(&self.return_).map_or(false, |ret| ret >= depart),
+            (None, _) => false,
+        }
+    }
+    fn update(&mut self) {
+        if self.is_return {
+            self.widgets.return_box.remove_prop(Property::Inactive);
+        } else {
+            self.widgets.return_box.add_prop(Property::Inactive);
+        }
+        if self.is_valid() {
+            self.widgets.book_button.remove_prop(Property::Inactive);
+        } else {
+            self.widgets.book_button.add_prop(Property::Inactive);
+        }
+    }
+}
+
+impl EventHandler<BookingEvent> for BookingHandler {
+    fn handle(&mut self, event: &BookingEvent, _: EventArgs) {
+        match *event {
+            BookingEvent::SetReturn(is_return) => self.is_return = is_return,
+            BookingEvent::Depart(ref text) => self.depart = text.clone(),
+            BookingEvent::Return(ref text) => self.return_ = text.clone(),
+            BookingEvent::Book => {
+                if !self.is_valid() {
+                    return;
+                }
+                let message = if self.is_return {
+                    format!("Booked a return flight, {} to {}", self.depart.trim(), self.return_.trim())
+                } else {
+                    format!("Booked a one-way flight on {}", self.depart.trim())
+                };
+                self.widgets.message.event(TextUpdated(message));
+            }
+        }
+        self.update();
+    }
+}
+
+fn main() {
+    let window_builder = glutin::WindowBuilder::new()
+        .with_title("Limn flight booker")
+        .with_min_dimensions(glutin::dpi::LogicalSize{width: 100.0, height: 100.0});
+    let mut app = util::init(window_builder);
+    let mut root = Widget::new("root");
+    let mut layout_settings = LinearLayoutSettings::new(Orientation::Vertical);
+    layout_settings.padding = 10.0;
+    layout_settings.item_align = ItemAlignment::Fill;
+    root.linear_layout(layout_settings);
+    root.layout().add(min_width(300.0));
+
+    let mut return_toggle = ToggleButtonStyle::default();
+    return_toggle.toggle_text("return flight", "one-way flight");
+    let mut return_toggle = Widget::from_modifier_style(return_toggle);
+    return_toggle.add_handler(|event: &ToggleEvent, args: EventArgs| {
+        let is_return = match *event {
+            ToggleEvent::On => true,
+            ToggleEvent::Off => false,
+        };
+        args.ui.event(BookingEvent::SetReturn(is_return));
+    });
+
+    let mut depart_box = Widget::from_modifier(EditText::default());
+    depart_box.add_handler(|event: &TextUpdated, args: EventArgs| {
+        args.ui.event(BookingEvent::Depart(event.0.clone()));
+    });
+    depart_box.event_subtree(TextUpdated("27.03.2014".to_owned()));
+
+    let mut return_box = Widget::from_modifier(EditText::default());
+    return_box.add_handler(|event: &TextUpdated, args: EventArgs| {
+        args.ui.event(BookingEvent::Return(event.0.clone()));
+    });
+    return_box.event_subtree(TextUpdated("27.03.2014".to_owned()));
+    return_box.add_prop(Property::Inactive);
+
+    let mut book_button = Widget::from_modifier_style(ButtonStyle::from_text("Book"));
+    book_button.add_handler(|_: &ClickEvent, args: EventArgs| {
+        args.ui.event(BookingEvent::Book);
+    });
+
+    let message = Widget::from_modifier_style(StaticTextStyle::from_text(""));
+
+    app.add_handler(BookingHandler {
+        widgets: Widgets {
+            return_box: return_box.clone(),
+            book_button: book_button.clone(),
+            message: message.clone(),
+        },
+        is_return: false,
+        depart: String::new(),
+        return_: String::new(),
+    });
+    root
+        .add_child(return_toggle)
+        .add_child(depart_box)
+        .add_child(return_box)
+        .add_child(book_button)
+        .add_child(message);
+
+    app.main_loop(root);
+}
@@ -0,0 +1,348 @@
+#[allow(unused_imports)]
+#[macro_use]
+extern crate limn;
+extern crate lipsum;
+extern crate limn_contrib;
+
+mod util;
+
+use lipsum::lipsum;
+
+use limn::prelude::*;
+use limn::widgets::list;
+use limn::extension::register_library;
+use limn_contrib::{Contrib, RatingBuilder, RatingChanged};
+
+/// The pages shown in the gallery, one per widget or feature.
+/// New widgets should get a page here, so there is somewhere to try them out by hand.
+#[derive(Debug, Copy, Clone, PartialEq)]
+enum Page {
+    Buttons,
+    Sliders,
+    EditText,
+    List,
+    Scroll,
+    Image,
+    Shapes,
+    Text,
+    ThirdParty,
+}
+
+const PAGES: [Page; 9] = [
+    Page::Buttons,
+    Page::Sliders,
+    Page::EditText,
+    Page::List,
+    Page::Scroll,
+    Page::Image,
+    Page::Shapes,
+    Page::Text,
+    Page::ThirdParty,
+];
+
+impl Page {
+    fn title(&self) -> &'static str {
+        match *self {
+            Page::Buttons => "Buttons",
+            Page::Sliders => "Sliders",
+            Page::EditText => "Edit Text",
+            Page::List => "List",
+            Page::Scroll => "Scroll",
+            Page::Image => "Image",
+            Page::Shapes => "Shapes",
+            Page::Text => "Text",
+            Page::ThirdParty => "Third Party",
+        }
+    }
+
+    fn build(&self) -> Widget {
+        let mut page = Widget::new(format!("page_{:?}", self).to_lowercase());
+        let mut layout_settings = LinearLayoutSettings::new(Orientation::Vertical);
+        layout_settings.padding = 20.0;
+        layout_settings.item_align = ItemAlignment::Left;
+        page.linear_layout(layout_settings);
+        match *self {
+            Page::Buttons => {
+                let button = Widget::from_modifier_style(ButtonStyle::from_text("Button"));
+                let mut inactive_button = Widget::from_modifier_style(ButtonStyle::from_text("Inactive"));
+                inactive_button.add_prop(Property::Inactive);
+                let mut toggle_button = ToggleButtonStyle::default();
+                toggle_button.toggle_text("ON", "OFF");
+                let toggle_button = Widget::from_modifier_style(toggle_button);
+                page.add_child(button)
+                    .add_child(inactive_button)
+                    .add_child(toggle_button);
+            }
+            Page::Sliders => {
+                let mut value_text = Widget::from_modifier_style(StaticTextStyle::from_text("0.00"));
+                let value_text_ref = value_text.clone();
+                let mut slider = Widget::from_modifier(Slider::default());
+                slider.layout().add(min_width(300.0));
+                slider.add_handler(move |event: &SliderEvent, _: EventArgs| {
+                    value_text_ref.event(TextUpdated(format!("{:.2}", event.value)));
+                });
+                let mut inactive_slider = Slider::default();
+                inactive_slider.set_value(0.5);
+                let mut inactive_slider = Widget::from_modifier(inactive_slider);
+                inactive_slider.layout().add(min_width(300.0));
+                inactive_slider.add_prop(Property::Inactive);
+                value_text.layout().add(min_width(100.0));
+                page.add_child(slider)
+                    .add_child(value_text)
+                    .add_child(inactive_slider);
+            }
+            Page::EditText => {
+                let mut edit_text = Widget::from_modifier(EditText::default());
+                edit_text.layout().add(constraints![
+                    min_width(300.0),
+                    min_height(100.0),
+                ]);
+                page.add_child(edit_text);
+            }
+            Page::List => {
+                let mut list_widget = Widget::from_modifier(List::default());
+                list_widget.layout().add(min_width(300.0));
+                let list_data = (1..8).map(|count| lipsum(count));
+                list::add_contents_to_list(&mut list_widget, list_data, list::default_text_adapter);
+                page.add_child(list_widget);
+            }
+            Page::Scroll => {
+                let mut scroll_widget = ScrollContainer::default();
+                scroll_widget.add_scrollbar();
+                let mut content = Widget::new("scroll_content");
+                content.grid(3);
+                content.layout().add(size(Size::new(600.0, 600.0)));
+                for &color in &[RED, GREEN, BLUE, YELLOW, CYAN, FUSCHIA, WHITE, GRAY_50, BLACK] {
+                    let mut rect = Widget::new("scroll_rect");
+                    rect.set_draw_style(style!(RectStyle {
+                        background_color: color,
+                    }));
+                    content.add_child(rect);
+                }
+                scroll_widget.add_content(content);
+                let mut scroll_widget = Widget::from_modifier(scroll_widget);
+                scroll_widget.layout().add(size(Size::new(300.0, 300.0)));
+                page.add_child(scroll_widget);
+            }
+            Page::Image => {
+                let image = Widget::from_modifier(Image::new(ImageSource::bundled("rust")));
+                let mut hexagon = Widget::from_modifier(Image::new(ImageSource::bundled("rust")));
+                hexagon.set_clip_path(ClipPath::regular_polygon(6));
+                page.add_child(image)
+                    .add_child(hexagon);
+            }
+            Page::Shapes => {
+                let mut rect = Widget::new("rect");
+                rect.set_draw_style(style!(RectStyle {
+                    background_color: BLUE_HIGHLIGHT,
+                    corner_radius: Some(10.0),
+                    border: Some((2.0, GRAY_30)),
+                }));
+                rect.layout().add(size(Size::new(150.0, 100.0)));
+                let mut ellipse = Widget::new("ellipse");
+                ellipse.set_draw_style(style!(EllipseStyle {
+                    background_color: YELLOW,
+                    border: Some((2.0, GRAY_30)),
+                }));
+                ellipse.layout().add(size(Size::new(150.0, 100.0)));
+                page.add_child(rect)
+                    .add_child(ellipse);
+            }
+            Page::Text => {
+                let mut rotated = Widget::from_modifier_style(StaticTextStyle::from_style(style!(TextStyle {
+                    text: String::from("Rotated"),
+                    rotation: -30.0,
+                })));
+                rotated.set_name("rotated_text");
+                let mut vertical = Widget::from_modifier_style(StaticTextStyle::from_style(style!(TextStyle {
+                    text: String::from("TAB"),
+                    vertical: true,
+                })));
+                vertical.set_name("vertical_text");
+
+                // a badge with text around the top and bottom edges, each path starts opposite
+                // where the text is centered
+                let mut badge = Widget::new("badge");
+                badge.set_draw_style(style!(EllipseStyle {
+                    background_color: BLUE_HIGHLIGHT,
+                    border: Some((2.0, GRAY_30)),
+                }));
+                badge.layout().add(size(Size::new(160.0, 160.0)));
+                let center = Point::new(80.0, 80.0);
+                let mut top = Widget::new("badge_top");
+                top.set_draw_style(style!(TextPathStyle {
+                    text: String::from("TEXT ON A PATH"),
+                    path: TextPath::Circle { center: center, radius: 60.0, start_angle: 180.0 },
+                    font_size: 16.0,
+                    align: Align::Middle,
+                }));
+                top.layout().add(match_layout(&badge));
+                let mut bottom = Widget::new("badge_bottom");
+                bottom.set_draw_style(style!(TextPathStyle {
+                    text: String::from("limn"),
+                    path: TextPath::CircleReversed { center: center, radius: 72.0, start_angle: 180.0 },
+                    font_size: 16.0,
+                    align: Align::Middle,
+                }));
+                bottom.layout().add(match_layout(&badge));
+                badge.add_child(top).add_child(bottom);
+                page.add_child(rotated)
+                    .add_child(vertical)
+                    .add_child(badge);
+            }
+            Page::ThirdParty => {
+                // a widget from limn-contrib, styled by its own library rather than the app theme
+                let mut value_text = Widget::from_modifier_style(StaticTextStyle::from_text("3 / 5"));
+                value_text.layout().add(min_width(100.0));
+                let value_text_ref = value_text.clone();
+                let mut rating = RatingBuilder::new().set_value(3).build();
+                rating.layout().add(size(Size::new(150.0, 24.0)));
+                rating.add_handler(move |event: &RatingChanged, _: EventArgs| {
+                    value_text_ref.event(TextUpdated(format!("{} / 5", event.0)));
+                });
+                page.add_child(rating)
+                    .add_child(value_text);
+            }
+        }
+        page
+    }
+}
+
+#[derive(Debug, Copy, Clone)]
+struct ShowPage(Page);
+
+struct GalleryHandler {
+    content: Widget,
+    page: Option<Widget>,
+}
+
+impl EventHandler<ShowPage> for GalleryHandler {
+    fn handle(&mut self, event: &ShowPage, _: EventArgs) {
+        if let Some(mut page) = self.page.take() {
+            page.remove_widget();
+        }
+        let mut page = event.0.build();
+        page.layout().add(bound_by(&self.content).padding(20.0));
+        self.content.add_child(page.clone());
+        self.page = Some(page);
+    }
+}
+
+#[cfg(feature = "theme-preview")]
+fn dark_theme(theme: &mut limn::style::Theme) {
+    util::default_theme(theme);
+    theme.register_type_style(style!(TextStyle {
+        font: limn::resources::font::FontDescriptor::from_family("NotoSans"),
+        font_size: 24.0,
+        text_color: GRAY_90,
+        background_color: TRANSPARENT,
+        wrap: Wrap::Whitespace,
+        align: Align::Start,
+    }));
+    theme.register_class_style("gallery_background", style!(RectStyle {
+        background_color: GRAY_10,
+    }));
+    theme.register_class_style("list_item_rect", style!(RectStyle {
+        background_color: GRAY_20,
+    }));
+    theme.register_class_style("button_rect", style!(RectStyle {
+        background_color: GRAY_30,
+        corner_radius: Some(5.0),
+        border: Some((1.0, GRAY_50)),
+    }));
+    theme.register_class_style("button_text", style!(TextStyle {
+        text_color: WHITE,
+    }));
+}
+
+/// Renders every page under each theme at 1x and 2x, and writes a contact sheet to
+/// `target/theme_preview`. Captures are compared with the previous run's, to catch changes to a theme.
+/// Run with `cargo run --example gallery --features theme-preview -- --theme-preview`
+#[cfg(feature = "theme-preview")]
+fn theme_preview(mut app: App) {
+    use std::path::Path;
+    use limn::theme_preview::ThemePreview;
+    use limn::extension::WidgetLibrary;
+
+    let mut preview = ThemePreview::new();
+    preview
+        .add_theme("light", |theme| {
+            util::default_theme(theme);
+            Contrib.register_styles(theme);
+        })
+        .add_theme("dark", |theme| {
+            dark_theme(theme);
+            Contrib.register_styles(theme);
+        })
+        .set_hidpi_factors(&[1.0, 2.0]);
+    let captures = preview.capture(&mut app, || {
+        let mut sheet = Widget::new("theme_preview");
+        sheet.set_draw_style(DrawStyle::from_class::<RectStyle>("gallery_background"));
+        sheet.grid(4);
+        for page in PAGES.iter() {
+            sheet.add_child(page.build());
+        }
+        sheet
+    });
+    let dir = Path::new("target/theme_preview");
+    for regression in preview.compare(&captures, dir, 0.001) {
+        println!("{} changed, {:.2}% of pixels differ", regression.file_name, regression.difference * 100.0);
+    }
+    preview.save(&captures, dir).unwrap();
+    preview.contact_sheet(&captures).save(dir.join("contact_sheet.png")).unwrap();
+}
+
+fn main() {
+    let window_builder = glutin::WindowBuilder::new()
+        .with_title("Limn gallery")
+        .with_min_dimensions(glutin::dpi::LogicalSize{width: 700.0, height: 500.0});
+    let mut app = util::init(window_builder);
+    register_library(&Contrib);
+    resources().image_loader.load_image("rust", include_bytes!("../assets/images/rust.png").to_vec());
+
+    #[cfg(feature = "theme-preview")]
+    {
+        if ::std::env::args().any(|arg| arg == "--theme-preview") {
+            theme_preview(app);
+            return;
+        }
+    }
+
+    let mut root = Widget::new("root");
+    root.layout().add(min_size(Size::new(700.0, 500.0)));
+
+    let mut sidebar = Widget::from_modifier(List::default());
+    sidebar.set_name("sidebar");
+    sidebar.layout().add(constraints![
+        align_left(&root),
+        align_top(&root),
+        width(180.0),
+    ]);
+    for &page in PAGES.iter() {
+        let mut item = list::default_text_adapter(page.title().to_owned(), &mut sidebar);
+        item.set_name("list_item")
+            .add_handler(ListItemHandler::new(sidebar.clone()))
+            .add_handler(move |_: &ItemSelected, args: EventArgs| {
+                args.ui.event(ShowPage(page));
+            });
+        sidebar.add_child(item);
+    }
+
+    let mut content = Widget::new("content");
+    content.layout().add(constraints![
+        to_right_of(&sidebar),
+        align_top(&root),
+        align_right(&root),
+        align_bottom(&root),
+    ]);
+
+    app.add_handler(GalleryHandler {
+        content: content.clone(),
+        page: None,
+    });
+    root.add_child(sidebar)
+        .add_child(content);
+    app.get_root().event(ShowPage(Page::Buttons));
+
+    app.main_loop(root);
+}
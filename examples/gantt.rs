@@ -0,0 +1,63 @@
+#[allow(unused_imports)]
+#[macro_use]
+extern crate limn;
+
+mod util;
+
+use limn::prelude::*;
+
+fn main() {
+    let window_builder = glutin::WindowBuilder::new()
+        .with_title("Limn gantt demo")
+        .with_min_dimensions(glutin::dpi::LogicalSize{width: 700.0, height: 300.0});
+    let mut app = util::init(window_builder);
+    let mut root = Widget::new("root");
+    root.layout().add(min_size(Size::new(700.0, 300.0)));
+
+    let mut builder = GanttBuilder::new();
+    let design = builder.add_task(Task::new("Design", 0.0, 5.0).progress(1.0));
+    let backend = builder.add_task(Task::new("Backend", 5.0, 10.0).progress(0.6));
+    let frontend = builder.add_task(Task::new("Frontend", 7.0, 8.0).progress(0.3));
+    let testing = builder.add_task(Task::new("Testing", 15.0, 5.0));
+    let release = builder.add_task(Task::new("Release", 20.0, 1.0));
+    builder
+        .add_dependency(design, backend)
+        .add_dependency(design, frontend)
+        .add_dependency(backend, testing)
+        .add_dependency(frontend, testing)
+        .add_dependency(testing, release)
+        .set_today(9.0);
+    let mut gantt = builder.build();
+    gantt.add_handler(|event: &TaskChanged, _: EventArgs| {
+        println!("task {} now starts on day {} and takes {} days", event.id, event.start, event.duration);
+    });
+
+    let mut controls = Widget::new("controls");
+    controls.linear_layout(LinearLayoutSettings::new(Orientation::Horizontal));
+    controls.layout().add(constraints![
+        align_top(&root).padding(10.0),
+        align_left(&root).padding(10.0),
+    ]);
+    let buttons = [
+        ("Days", GanttEvent::SetZoom(40.0)),
+        ("Weeks", GanttEvent::SetZoom(10.0)),
+    ];
+    for &(title, event) in buttons.iter() {
+        let mut button = Widget::from_modifier_style(ButtonStyle::from_text(title));
+        let gantt = gantt.clone();
+        button.add_handler(move |_: &ClickEvent, _: EventArgs| {
+            gantt.event(event);
+        });
+        controls.add_child(button);
+    }
+
+    gantt.layout().add(constraints![
+        below(&controls).padding(10.0),
+        align_left(&root).padding(10.0),
+        align_right(&root).padding(10.0),
+        align_bottom(&root).padding(10.0),
+    ]);
+    root.add_child(controls).add_child(gantt);
+
+    app.main_loop(root);
+}
@@ -0,0 +1,58 @@
+#[allow(unused_imports)]
+#[macro_use]
+extern crate limn;
+
+mod util;
+
+use std::{thread, time};
+
+use limn::prelude::*;
+
+const DAYS: [&str; 7] = ["Mon", "Tue", "Wed", "Thu", "Fri", "Sat", "Sun"];
+
+/// Made up activity level for an hour of the day
+fn activity(day: usize, hour: usize, time: f32) -> f32 {
+    let daytime = (-((hour as f32 - 14.0) / 4.0).powi(2)).exp();
+    let weekday = if day < 5 { 1.0 } else { 0.6 };
+    (daytime * weekday * 100.0 * (1.0 + 0.2 * (time + day as f32).sin())).round()
+}
+
+fn main() {
+    let window_builder = glutin::WindowBuilder::new()
+        .with_title("Limn heatmap demo")
+        .with_min_dimensions(glutin::dpi::LogicalSize{width: 600.0, height: 300.0});
+    let mut app = util::init(window_builder);
+    let mut root = Widget::new("root");
+
+    let mut values = Vec::new();
+    for day in 0..DAYS.len() {
+        for hour in 0..24 {
+            values.push(activity(day, hour, 0.0));
+        }
+    }
+    let mut heatmap = HeatmapBuilder::new(24, DAYS.len())
+        .set_values(values)
+        .set_range(0.0, 120.0)
+        .set_cell_size(20.0)
+        .set_row_labels(DAYS.iter().map(|day| day.to_string()).collect())
+        .set_column_labels((0..24).map(|hour| format!("{:02}:00", hour)).collect())
+        .set_column_label_rotation(-90.0)
+        .build();
+    heatmap.layout().add(match_layout(&root).padding(20.0));
+    let heatmap_id = heatmap.id();
+    root.add_child(heatmap);
+
+    // update one day at a time, only that row of the image is uploaded
+    let sender = EventSender;
+    thread::spawn(move || {
+        let mut tick = 0;
+        loop {
+            thread::sleep(time::Duration::from_millis(250));
+            tick += 1;
+            let day = tick % DAYS.len();
+            let values = (0..24).map(|hour| activity(day, hour, tick as f32 * 0.1)).collect();
+            sender.widget_event(heatmap_id, HeatmapEvent::SetRegion { column: 0, row: day, columns: 24, values: values });
+        }
+    });
+    app.main_loop(root);
+}
@@ -0,0 +1,106 @@
+#[allow(unused_imports)]
+#[macro_use]
+extern crate limn;
+
+mod util;
+
+use limn::prelude::*;
+
+fn main() {
+    let window_builder = glutin::WindowBuilder::new()
+        .with_title("Limn image viewer demo")
+        .with_min_dimensions(glutin::dpi::LogicalSize{width: 500.0, height: 400.0});
+    let mut app = util::init(window_builder);
+    resources().image_loader.load_image("rust", include_bytes!("../assets/images/rust.png").to_vec());
+    let mut root = Widget::new("root");
+
+    let mut viewer = ImageViewerBuilder::new(ImageSource::bundled("rust")).build();
+
+    let mut rotate_button = Widget::from_modifier_style(ButtonStyle::from_text("Rotate"));
+    rotate_button.layout().add(constraints![
+        align_left(&root).padding(20.0),
+        align_bottom(&root).padding(20.0),
+    ]);
+    let mut fit_button = Widget::from_modifier_style(ButtonStyle::from_text("Fit"));
+    fit_button.layout().add(constraints![
+        to_right_of(&rotate_button).padding(10.0),
+        align_bottom(&root).padding(20.0),
+    ]);
+    let mut actual_size_button = Widget::from_modifier_style(ButtonStyle::from_text("1:1"));
+    actual_size_button.layout().add(constraints![
+        to_right_of(&fit_button).padding(10.0),
+        align_bottom(&root).padding(20.0),
+    ]);
+    let mut crop_button = ToggleButtonStyle::default();
+    crop_button.toggle_text("Done", "Crop");
+    let mut crop_button = Widget::from_modifier_style(crop_button);
+    crop_button.layout().add(constraints![
+        to_right_of(&actual_size_button).padding(10.0),
+        align_bottom(&root).padding(20.0),
+    ]);
+    let mut crop = CropOverlayBuilder::new().build();
+    crop.layout().add(match_layout(&viewer));
+    crop.layout().hide();
+    let crop_ref = crop.clone();
+    viewer.add_handler(move |event: &ImageViewChanged, _: EventArgs| {
+        crop_ref.event(CropEvent::from(*event));
+    });
+    let mut crop_ref = crop.clone();
+    crop_button.add_handler(move |event: &ToggleEvent, _: EventArgs| {
+        match *event {
+            ToggleEvent::On => crop_ref.layout().show(),
+            ToggleEvent::Off => crop_ref.layout().hide(),
+        }
+    });
+
+    let viewer_ref = viewer.clone();
+    rotate_button.add_handler(move |_: &ClickEvent, _: EventArgs| {
+        viewer_ref.event(ImageViewerEvent::Rotate(1));
+    });
+    let viewer_ref = viewer.clone();
+    fit_button.add_handler(move |_: &ClickEvent, _: EventArgs| {
+        viewer_ref.event(ImageViewerEvent::SetMode(ImageViewerMode::Fit));
+    });
+    let viewer_ref = viewer.clone();
+    actual_size_button.add_handler(move |_: &ClickEvent, _: EventArgs| {
+        viewer_ref.event(ImageViewerEvent::SetMode(ImageViewerMode::ActualSize));
+    });
+
+    let mut readout = Widget::new("readout");
+    StaticTextStyle::from_text("Scroll to zoom, drag to pan").component().apply(&mut readout);
+    readout.layout().add(constraints![
+        align_right(&root).padding(20.0),
+        center_vertical(&fit_button),
+    ]);
+    viewer.layout().add(constraints![
+        align_left(&root).padding(20.0),
+        align_right(&root).padding(20.0),
+        align_top(&root).padding(20.0),
+        above(&rotate_button).padding(20.0),
+    ]);
+    let readout_text = readout.clone();
+    viewer.add_handler(move |event: &ImagePixelHovered, _: EventArgs| {
+        if let Some((x, y)) = event.0 {
+            readout_text.event(TextUpdated(format!("x {} y {}", x, y)));
+        }
+    });
+    let readout_text = readout.clone();
+    crop.add_handler(move |event: &CropChanged, _: EventArgs| {
+        let region = event.0;
+        readout_text.event(TextUpdated(format!("crop {:.0}x{:.0} at {:.0}, {:.0}",
+            region.size.width, region.size.height, region.origin.x, region.origin.y)));
+    });
+    let readout_text = readout.clone();
+    viewer.add_handler(move |event: &ImageZoomChanged, _: EventArgs| {
+        readout_text.event(TextUpdated(format!("{:.0}%", event.0 * 100.0)));
+    });
+    root
+        .add_child(viewer)
+        .add_child(rotate_button)
+        .add_child(fit_button)
+        .add_child(actual_size_button)
+        .add_child(crop_button)
+        .add_child(crop)
+        .add_child(readout);
+    app.main_loop(root);
+}
@@ -0,0 +1,95 @@
+#[allow(unused_imports)]
+#[macro_use]
+extern crate limn;
+
+mod util;
+
+use limn::prelude::*;
+
+const HEADER_HEIGHT: f32 = 40.0;
+
+fn column(title: &str) -> Widget {
+    let mut column = Widget::new("kanban_column");
+    column.set_draw_style(style!(RectStyle {
+        background_color: GRAY_80,
+        corner_radius: Some(5.0),
+    }));
+    let mut header = Widget::from_modifier_style(StaticTextStyle::from_style(style!(TextStyle {
+        text: title.to_owned(),
+        font_size: 20.0,
+    })));
+    header.layout().add(constraints![
+        align_left(&column).padding(10.0),
+        align_top(&column).padding(10.0),
+    ]);
+    column.add_child(header);
+    column
+}
+
+fn card(text: &str) -> Widget {
+    let mut card = Widget::new("kanban_card");
+    card.set_draw_style(style!(RectStyle {
+        background_color: WHITE,
+        corner_radius: Some(3.0),
+        border: Some((1.0, GRAY_60)),
+    }));
+    let mut label = Widget::from_modifier_style(StaticTextStyle::from_style(style!(TextStyle {
+        text: text.to_owned(),
+        font_size: 16.0,
+    })));
+    label.layout().add(constraints![
+        align_left(&card).padding(8.0),
+        align_top(&card).padding(8.0),
+        align_bottom(&card).padding(8.0),
+    ]);
+    card.add_child(label);
+    card
+}
+
+fn main() {
+    let window_builder = glutin::WindowBuilder::new()
+        .with_title("Limn kanban demo")
+        .with_min_dimensions(glutin::dpi::LogicalSize{width: 700.0, height: 400.0});
+    let mut app = util::init(window_builder);
+    let mut root = Widget::new("root");
+
+    let mut builder = ReorderableBuilder::new();
+    builder.set_header_height(HEADER_HEIGHT);
+    let todo = builder.add_column(column("To do"));
+    let doing = builder.add_column(column("Doing"));
+    let done = builder.add_column(column("Done"));
+    builder
+        .add_item(todo, card("Write release notes"))
+        .add_item(todo, card("Update screenshots"))
+        .add_item(todo, card("Fix scroll bug"))
+        .add_item(doing, card("Kanban example"))
+        .add_item(doing, card("Review layout PR"))
+        .add_item(done, card("Set up CI"));
+    let mut board = builder.build();
+    board.add_handler(|event: &ItemMoved, _: EventArgs| {
+        println!("card moved from column {} row {} to column {} row {}",
+            event.from_column, event.from_index, event.to_column, event.to_index);
+    });
+    board.layout().add(constraints![
+        align_top(&root).padding(20.0),
+        align_left(&root).padding(20.0),
+        align_bottom(&root).padding(20.0),
+    ]);
+
+    // new cards go in the first column
+    let mut add_button = Widget::from_modifier_style(ButtonStyle::from_text("Add card"));
+    add_button.layout().add(constraints![
+        align_top(&root).padding(20.0),
+        to_right_of(&board).padding(20.0),
+        align_right(&root).padding(20.0),
+    ]);
+    let board_ref = board.clone();
+    let mut count = 0;
+    add_button.add_handler(move |_: &ClickEvent, _: EventArgs| {
+        count += 1;
+        board_ref.event(AddItem(todo, card(&format!("New card {}", count))));
+    });
+    root.add_child(board).add_child(add_button);
+
+    app.main_loop(root);
+}
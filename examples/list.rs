@@ -20,11 +20,16 @@ fn main() {
     let mut root = Widget::new("root");
 
     let mut list_widget = Widget::from_modifier(List::default());
-    let list_data = (0..15).map(|_| {
-        let rand = rand::thread_rng().gen_range(1, 6);
-        lipsum(rand)
+    let list_data = (0..15).map(|index| {
+        let rand = rand::thread_rng().gen_range(3, 8);
+        (format!("Item {}", index + 1), lipsum(rand))
+    });
+    list::add_contents_to_list(&mut list_widget, list_data, |(title, subtitle), list| {
+        ListItemBuilder::new(&title)
+            .set_subtitle(&subtitle)
+            .set_divider(true)
+            .build(list)
     });
-    list::add_contents_to_list(&mut list_widget, list_data, list::default_text_adapter);
 
     let mut scroll_widget = ScrollContainer::default();
     scroll_widget.add_content(list_widget.clone());
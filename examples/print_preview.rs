@@ -0,0 +1,27 @@
+#[allow(unused_imports)]
+#[macro_use]
+extern crate limn;
+extern crate lipsum;
+
+mod util;
+
+use limn::prelude::*;
+use lipsum::lipsum;
+
+fn main() {
+    let window_builder = glutin::WindowBuilder::new()
+        .with_title("Limn print preview demo")
+        .with_min_dimensions(glutin::dpi::LogicalSize{width: 700.0, height: 600.0});
+    let app = util::init(window_builder);
+    let mut root = Widget::new("root");
+
+    let text = (0..12).map(|_| lipsum(150)).collect::<Vec<_>>().join("\n\n");
+    let mut preview = PrintPreviewBuilder::new(&text).build();
+    preview.add_handler(|event: &PreviewPageChanged, _: EventArgs| {
+        println!("page {} of {}", event.page + 1, event.page_count);
+    });
+    preview.layout().add(match_layout(&root));
+    root.add_child(preview);
+
+    app.main_loop(root);
+}
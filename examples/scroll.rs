@@ -14,7 +14,7 @@ fn main() {
     let mut root = Widget::new("root");
 
     let mut scroll_widget = ScrollContainer::default();
-    scroll_widget.add_scrollbar();
+    scroll_widget.add_scrollbar().enable_zoom(0.5, 4.0);
     let mut rect_container = Widget::new("rect_container");
     rect_container.grid(3);
     rect_container.layout().add(size(Size::new(400.0, 400.0)));
@@ -41,7 +41,21 @@ fn main() {
     scroll_widget.add_content(rect_container);
     let mut scroll_widget = Widget::from_modifier(scroll_widget);
     scroll_widget.layout().add(match_layout(&root).padding(50.0));
-    root.add_child(scroll_widget);
+
+    // reading progress, grows as the content scrolls down
+    let mut progress = Widget::new("scroll_progress");
+    progress.set_draw_style(style!(RectStyle {
+        background_color: BLUE_HIGHLIGHT,
+    }));
+    progress.layout().add(constraints![
+        align_top(&root).padding(20.0),
+        align_left(&root).padding(50.0),
+        height(10.0),
+    ]);
+    bind_to_scroll(&mut scroll_widget, &progress, |event, progress| {
+        progress.layout().edit_width().set(event.progress().y * 200.0);
+    });
+    root.add_child(scroll_widget).add_child(progress);
 
     app.main_loop(root);
 }
@@ -0,0 +1,53 @@
+#[allow(unused_imports)]
+#[macro_use]
+extern crate limn;
+
+mod util;
+
+use limn::prelude::*;
+
+fn main() {
+    let window_builder = glutin::WindowBuilder::new()
+        .with_title("Limn sidebar demo")
+        .with_min_dimensions(glutin::dpi::LogicalSize{width: 700.0, height: 400.0});
+    let mut app = util::init(window_builder);
+    let mut root = Widget::new("root");
+
+    let mut folders = Widget::new("folders");
+    folders.linear_layout(LinearLayoutSettings::new(Orientation::Vertical));
+    for name in &["Inbox", "Drafts", "Sent", "Archive"] {
+        let mut folder = Widget::from_modifier_style(StaticTextStyle::from_text(name));
+        folder.layout().add(align_left(&folders).padding(10.0));
+        folders.add_child(folder);
+    }
+    let mut rail = Widget::new("folders_rail");
+    rail.linear_layout(LinearLayoutSettings::new(Orientation::Vertical));
+    for initial in &["I", "D", "S", "A"] {
+        let mut folder = Widget::from_modifier_style(StaticTextStyle::from_text(initial));
+        folder.layout().add(center_horizontal(&rail));
+        rail.add_child(folder);
+    }
+
+    let mut builder = SidebarBuilder::new(SidebarEdge::Left);
+    builder
+        .set_content(folders)
+        .set_rail(rail)
+        .set_width_range(120.0, 400.0);
+    let mut sidebar = builder.build(&mut root);
+    sidebar.add_handler(|event: &SidebarChanged, _: EventArgs| {
+        println!("sidebar width {} collapsed {}", event.width, event.collapsed);
+    });
+
+    let mut toggle_button = Widget::from_modifier_style(ButtonStyle::from_text("Toggle sidebar"));
+    toggle_button.layout().add(constraints![
+        to_right_of(&sidebar).padding(20.0),
+        align_top(&root).padding(20.0),
+        align_right(&root).padding(20.0).strength(WEAK),
+    ]);
+    toggle_button.add_handler(move |_: &ClickEvent, _: EventArgs| {
+        sidebar.event(SidebarEvent::Toggle);
+    });
+    root.add_child(toggle_button);
+
+    app.main_loop(root);
+}
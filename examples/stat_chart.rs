@@ -0,0 +1,52 @@
+#[allow(unused_imports)]
+#[macro_use]
+extern crate limn;
+extern crate rand;
+
+mod util;
+
+use rand::distributions::{Normal, IndependentSample};
+
+use limn::prelude::*;
+
+fn main() {
+    let window_builder = glutin::WindowBuilder::new()
+        .with_title("Limn histogram and box plot demo")
+        .with_min_dimensions(glutin::dpi::LogicalSize{width: 800.0, height: 400.0});
+    let mut app = util::init(window_builder);
+    let mut root = Widget::new("root");
+
+    let mut rng = rand::thread_rng();
+    let mut sample = |mean: f64, deviation: f64| -> Vec<f32> {
+        let normal = Normal::new(mean, deviation);
+        (0..500).map(|_| normal.ind_sample(&mut rng) as f32).collect()
+    };
+    let datasets = vec![
+        Dataset::new("control", sample(10.0, 2.0)).color(BLUE),
+        Dataset::new("treatment", sample(12.0, 3.0)).color(RED),
+    ];
+
+    let mut histogram = HistogramBuilder::new();
+    let mut box_plot = BoxPlotBuilder::new();
+    for dataset in &datasets {
+        histogram.add_dataset(dataset.clone());
+        box_plot.add_dataset(dataset.clone());
+    }
+    let mut histogram = histogram.set_legend(true).build();
+    let mut box_plot = box_plot.build();
+    histogram.layout().add(constraints![
+        align_left(&root).padding(20.0),
+        align_top(&root).padding(20.0),
+        align_bottom(&root).padding(20.0),
+    ]);
+    box_plot.layout().add(constraints![
+        to_right_of(&histogram).padding(20.0),
+        align_right(&root).padding(20.0),
+        align_top(&root).padding(20.0),
+        align_bottom(&root).padding(20.0),
+        match_width(&histogram),
+    ]);
+    root.add_child(histogram);
+    root.add_child(box_plot);
+    app.main_loop(root);
+}
@@ -0,0 +1,60 @@
+#[allow(unused_imports)]
+#[macro_use]
+extern crate limn;
+
+mod util;
+
+use limn::prelude::*;
+
+fn document(text: &str) -> Widget {
+    let mut document = Widget::new("document");
+    document.set_draw_style(style!(RectStyle {
+        background_color: WHITE,
+    }));
+    let mut text_widget = Widget::from_modifier_style(StaticTextStyle::from_text(text));
+    text_widget.layout().add(constraints![
+        align_top(&document).padding(20.0),
+        align_left(&document).padding(20.0),
+    ]);
+    document.add_child(text_widget);
+    document
+}
+
+fn main() {
+    let window_builder = glutin::WindowBuilder::new()
+        .with_title("Limn tabs demo")
+        .with_min_dimensions(glutin::dpi::LogicalSize{width: 600.0, height: 400.0});
+    let app = util::init(window_builder);
+    let mut root = Widget::new("root");
+
+    let mut builder = TabsBuilder::new();
+    builder
+        .add_tab("Notes", document("Shopping list"))
+        .add_tab("Draft", document("Dear Sir or Madam"));
+    let mut tabs = builder.build();
+    tabs.add_handler(|event: &TabMoved, _: EventArgs| {
+        println!("moved tab {} to {}", event.from, event.to);
+    });
+    tabs.layout().add(constraints![
+        align_top(&root),
+        align_left(&root),
+        align_right(&root),
+    ]);
+
+    let mut new_button = Widget::from_modifier_style(ButtonStyle::from_text("New tab"));
+    new_button.layout().add(constraints![
+        below(&tabs).padding(10.0),
+        align_bottom(&root).padding(10.0),
+        align_right(&root).padding(10.0),
+    ]);
+    let tabs_ref = tabs.clone();
+    let mut count = 0;
+    new_button.add_handler(move |_: &ClickEvent, _: EventArgs| {
+        count += 1;
+        let title = format!("Untitled {}", count);
+        tabs_ref.event(TabsEvent::Add(title.clone(), document(&title)));
+    });
+    root.add_child(tabs).add_child(new_button);
+
+    app.main_loop(root);
+}
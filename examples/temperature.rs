@@ -0,0 +1,90 @@
+#[allow(unused_imports)]
+#[macro_use]
+extern crate limn;
+
+mod util;
+
+use limn::prelude::*;
+
+/// 7GUIs task 2, temperature converter.
+/// Editing either field updates the other, if the input is a valid number.
+#[derive(Clone)]
+enum TemperatureEvent {
+    Celsius(String),
+    Fahrenheit(String),
+}
+
+struct TemperatureHandler {
+    celsius_box: Widget,
+    fahrenheit_box: Widget,
+    // text last sent to each box, so the resulting `TextUpdated` isn't converted back again
+    celsius_text: String,
+    fahrenheit_text: String,
+}
+
+impl EventHandler<TemperatureEvent> for TemperatureHandler {
+    fn handle(&mut self, event: &TemperatureEvent, _: EventArgs) {
+        match *event {
+            TemperatureEvent::Celsius(ref text) => {
+                if *text == self.celsius_text {
+                    return;
+                }
+                self.celsius_text = text.clone();
+                if let Ok(celsius) = text.trim().parse::<f32>() {
+                    self.fahrenheit_text = format!("{:.1}", celsius * 9.0 / 5.0 + 32.0);
+                    self.fahrenheit_box.event_subtree(TextUpdated(self.fahrenheit_text.clone()));
+                }
+            }
+            TemperatureEvent::Fahrenheit(ref text) => {
+                if *text == self.fahrenheit_text {
+                    return;
+                }
+                self.fahrenheit_text = text.clone();
+                if let Ok(fahrenheit) = text.trim().parse::<f32>() {
+                    self.celsius_text = format!("{:.1}", (fahrenheit - 32.0) * 5.0 / 9.0);
+                    self.celsius_box.event_subtree(TextUpdated(self.celsius_text.clone()));
+                }
+            }
+        }
+    }
+}
+
+fn main() {
+    let window_builder = glutin::WindowBuilder::new()
+        .with_title("Limn temperature converter")
+        .with_min_dimensions(glutin::dpi::LogicalSize{width: 100.0, height: 100.0});
+    let mut app = util::init(window_builder);
+    let mut root = Widget::new("root");
+    let mut layout_settings = LinearLayoutSettings::new(Orientation::Horizontal);
+    layout_settings.padding = 20.0;
+    layout_settings.item_align = ItemAlignment::Center;
+    root.linear_layout(layout_settings);
+
+    let mut celsius_box = Widget::from_modifier(EditText::default());
+    celsius_box.layout().add(min_width(100.0));
+    celsius_box.add_handler(|event: &TextUpdated, args: EventArgs| {
+        args.ui.event(TemperatureEvent::Celsius(event.0.clone()));
+    });
+    let celsius_text = Widget::from_modifier_style(StaticTextStyle::from_text("Celsius ="));
+
+    let mut fahrenheit_box = Widget::from_modifier(EditText::default());
+    fahrenheit_box.layout().add(min_width(100.0));
+    fahrenheit_box.add_handler(|event: &TextUpdated, args: EventArgs| {
+        args.ui.event(TemperatureEvent::Fahrenheit(event.0.clone()));
+    });
+    let fahrenheit_text = Widget::from_modifier_style(StaticTextStyle::from_text("Fahrenheit"));
+
+    app.add_handler(TemperatureHandler {
+        celsius_box: celsius_box.clone(),
+        fahrenheit_box: fahrenheit_box.clone(),
+        celsius_text: String::new(),
+        fahrenheit_text: String::new(),
+    });
+    root
+        .add_child(celsius_box)
+        .add_child(celsius_text)
+        .add_child(fahrenheit_box)
+        .add_child(fahrenheit_text);
+
+    app.main_loop(root);
+}
@@ -0,0 +1,45 @@
+#[allow(unused_imports)]
+#[macro_use]
+extern crate limn;
+
+mod util;
+
+use std::env;
+use std::fs;
+use std::path::PathBuf;
+
+use limn::prelude::*;
+
+fn main() {
+    let window_builder = glutin::WindowBuilder::new()
+        .with_title("Limn thumbnail grid demo")
+        .with_min_dimensions(glutin::dpi::LogicalSize{width: 500.0, height: 400.0});
+    let mut app = util::init(window_builder);
+    let mut root = Widget::new("root");
+
+    // the images in the directory given as an argument, or the bundled assets
+    let dir = env::args().nth(1).map_or_else(|| PathBuf::from(concat!(env!("CARGO_MANIFEST_DIR"), "/assets/images")), PathBuf::from);
+    let mut images: Vec<PathBuf> = fs::read_dir(&dir).unwrap()
+        .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+        .filter(|path| path.extension().map_or(false, |ext| ext == "png" || ext == "jpg" || ext == "jpeg"))
+        .collect();
+    images.sort();
+
+    let mut grid = ThumbnailGridBuilder::new()
+        .add_images(images)
+        .set_thumbnail_size(96.0)
+        .set_cache_dir(env::temp_dir().join("limn_thumbnails"))
+        .build();
+    grid.add_handler(|event: &ThumbnailsSelected, _: EventArgs| {
+        println!("{} selected", event.0.len());
+    });
+    grid.add_handler(|event: &ThumbnailActivated, _: EventArgs| {
+        println!("opened {}", event.0.display());
+    });
+    let mut scroll = ScrollContainer::default();
+    scroll.add_scrollbar().add_content(grid);
+    let mut scroll = Widget::from_modifier(scroll);
+    scroll.layout().add(match_layout(&root).padding(20.0));
+    root.add_child(scroll);
+    app.main_loop(root);
+}
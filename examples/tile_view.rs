@@ -0,0 +1,54 @@
+#[allow(unused_imports)]
+#[macro_use]
+extern crate limn;
+
+mod util;
+
+use std::{thread, time};
+
+use limn::prelude::*;
+
+const TILE_SIZE: u32 = 256;
+
+/// Stands in for a tile server, draws a checkerboard tinted by zoom level, with some latency
+struct CheckerProvider;
+
+impl TileProvider for CheckerProvider {
+    fn fetch(&self, tile: TileCoord) -> Result<TileData, String> {
+        thread::sleep(time::Duration::from_millis(100));
+        let tint = (tile.zoom * 37 % 255) as u8;
+        let mut data = Vec::with_capacity((TILE_SIZE * TILE_SIZE * 4) as usize);
+        for y in 0..TILE_SIZE {
+            for x in 0..TILE_SIZE {
+                let border = x == 0 || y == 0;
+                let checker = (x / 32 + y / 32) % 2 == 0;
+                let value = if border { 80 } else if checker { 230 } else { 200 };
+                data.extend_from_slice(&[value, value, value.saturating_sub(tint / 2), 255]);
+            }
+        }
+        Ok(TileData::Rgba(data))
+    }
+    fn tile_size(&self) -> u32 {
+        TILE_SIZE
+    }
+}
+
+fn main() {
+    let window_builder = glutin::WindowBuilder::new()
+        .with_title("Limn tile view demo")
+        .with_min_dimensions(glutin::dpi::LogicalSize{width: 500.0, height: 400.0});
+    let mut app = util::init(window_builder);
+    let mut root = Widget::new("root");
+    root.layout().add(min_size(Size::new(500.0, 400.0)));
+
+    let mut tile_view = TileViewBuilder::new(CheckerProvider)
+        .set_center(51.5, -0.1)
+        .set_zoom(3.0)
+        .build();
+    tile_view.layout().add(match_layout(&root).padding(20.0));
+    tile_view.add_handler(|event: &TileViewChanged, _: EventArgs| {
+        println!("center {:.4}, {:.4} zoom {:.2}", event.center.0, event.center.1, event.zoom);
+    });
+    root.add_child(tile_view);
+    app.main_loop(root);
+}
@@ -0,0 +1,65 @@
+#[allow(unused_imports)]
+#[macro_use]
+extern crate limn;
+
+mod util;
+
+use limn::prelude::*;
+
+fn main() {
+    let window_builder = glutin::WindowBuilder::new()
+        .with_title("Limn timeline demo")
+        .with_min_dimensions(glutin::dpi::LogicalSize{width: 700.0, height: 300.0});
+    let mut app = util::init(window_builder);
+    let mut root = Widget::new("root");
+    root.layout().add(min_size(Size::new(700.0, 300.0)));
+
+    let mut builder = TimelineBuilder::new();
+    let video = builder.add_track("Video");
+    let audio = builder.add_track("Audio");
+    let titles = builder.add_track("Titles");
+    builder.add_clip(Clip::new(video, 0.0, 4.0, "Intro"));
+    builder.add_clip(Clip::new(video, 4.0, 6.5, "Interview"));
+    builder.add_clip(Clip::new(audio, 0.0, 10.5, "Music"));
+    builder.add_clip(Clip::new(titles, 1.0, 2.0, "Title"));
+    builder.set_length(30.0);
+    let mut timeline = builder.build();
+    timeline
+        .add_handler(|event: &ClipMoved, _: EventArgs| {
+            println!("clip {} moved to track {} at {:.2}s", event.id, event.track, event.start);
+        })
+        .add_handler(|event: &ClipTrimmed, _: EventArgs| {
+            println!("clip {} trimmed to {:.2}s - {:.2}s", event.id, event.start, event.start + event.duration);
+        });
+
+    let mut controls = Widget::new("controls");
+    controls.linear_layout(LinearLayoutSettings::new(Orientation::Horizontal));
+    controls.layout().add(constraints![
+        align_top(&root).padding(10.0),
+        align_left(&root).padding(10.0),
+    ]);
+    let buttons = [
+        ("Play", TimelineEvent::Play),
+        ("Pause", TimelineEvent::Pause),
+        ("Zoom In", TimelineEvent::SetZoom(100.0)),
+        ("Zoom Out", TimelineEvent::SetZoom(25.0)),
+    ];
+    for &(title, event) in buttons.iter() {
+        let mut button = Widget::from_modifier_style(ButtonStyle::from_text(title));
+        let timeline = timeline.clone();
+        button.add_handler(move |_: &ClickEvent, _: EventArgs| {
+            timeline.event(event);
+        });
+        controls.add_child(button);
+    }
+
+    timeline.layout().add(constraints![
+        below(&controls).padding(10.0),
+        align_left(&root).padding(10.0),
+        align_right(&root).padding(10.0),
+        align_bottom(&root).padding(10.0),
+    ]);
+    root.add_child(controls).add_child(timeline);
+
+    app.main_loop(root);
+}
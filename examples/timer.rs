@@ -0,0 +1,113 @@
+#[allow(unused_imports)]
+#[macro_use]
+extern crate limn;
+
+mod util;
+
+use std::time::{Duration, Instant};
+
+use limn::prelude::*;
+use limn::clock;
+
+/// 7GUIs task 4, timer.
+/// The gauge fills up until the elapsed time reaches the chosen duration. Changing the
+/// duration takes effect immediately, and reset starts counting from zero again.
+#[derive(Clone)]
+enum TimerEvent {
+    Tick,
+    SetDuration(f32),
+    Reset,
+}
+
+struct TimerHandler {
+    gauge: Widget,
+    elapsed_text: Widget,
+    duration_text: Widget,
+    start: Instant,
+    duration: f32,
+    finished: bool,
+}
+
+fn as_secs(duration: Duration) -> f32 {
+    duration.as_secs() as f32 + duration.subsec_nanos() as f32 / 1_000_000_000.0
+}
+
+impl TimerHandler {
+    fn update(&mut self) {
+        let elapsed = as_secs(clock::now() - self.start).min(self.duration);
+        self.finished = elapsed >= self.duration;
+        self.gauge.event(SetSliderValue(elapsed / self.duration));
+        self.elapsed_text.event(TextUpdated(format!("{:.1}s", elapsed)));
+    }
+}
+
+impl EventHandler<TimerEvent> for TimerHandler {
+    fn handle(&mut self, event: &TimerEvent, args: EventArgs) {
+        match *event {
+            TimerEvent::Tick => {
+                if self.finished {
+                    return;
+                }
+            }
+            TimerEvent::SetDuration(duration) => {
+                self.duration = duration;
+                self.duration_text.event(TextUpdated(format!("{:.1}s", duration)));
+            }
+            TimerEvent::Reset => self.start = clock::now(),
+        }
+        self.update();
+        args.ui.redraw();
+    }
+}
+
+fn main() {
+    let window_builder = glutin::WindowBuilder::new()
+        .with_title("Limn timer")
+        .with_min_dimensions(glutin::dpi::LogicalSize{width: 100.0, height: 100.0});
+    let mut app = util::init(window_builder);
+    let mut root = Widget::new("root");
+    let mut layout_settings = LinearLayoutSettings::new(Orientation::Vertical);
+    layout_settings.padding = 10.0;
+    layout_settings.item_align = ItemAlignment::Fill;
+    root.linear_layout(layout_settings);
+    root.layout().add(min_width(300.0));
+
+    let mut gauge = Widget::from_modifier(Slider::default());
+    gauge.add_prop(Property::Inactive);
+    let mut elapsed_text = Widget::from_modifier_style(StaticTextStyle::from_text("0.0s"));
+    elapsed_text.set_name("elapsed_text");
+
+    let mut duration_slider = Slider::default();
+    duration_slider.set_range(1.0..30.0).set_value(10.0);
+    let mut duration_slider = Widget::from_modifier(duration_slider);
+    duration_slider.add_handler(|event: &SliderEvent, args: EventArgs| {
+        args.ui.event(TimerEvent::SetDuration(event.value));
+    });
+    let mut duration_text = Widget::from_modifier_style(StaticTextStyle::from_text("10.0s"));
+    duration_text.set_name("duration_text");
+
+    let mut reset_button = Widget::from_modifier_style(ButtonStyle::from_text("Reset"));
+    reset_button.add_handler(|_: &ClickEvent, args: EventArgs| {
+        args.ui.event(TimerEvent::Reset);
+    });
+
+    app.add_handler(TimerHandler {
+        gauge: gauge.clone(),
+        elapsed_text: elapsed_text.clone(),
+        duration_text: duration_text.clone(),
+        start: clock::now(),
+        duration: 10.0,
+        finished: false,
+    });
+    app.add_handler(|_: &FrameEvent, args: EventArgs| {
+        args.ui.event(TimerEvent::Tick);
+    });
+    root
+        .add_child(gauge)
+        .add_child(elapsed_text)
+        .add_child(duration_slider)
+        .add_child(duration_text)
+        .add_child(reset_button);
+
+    app.main_loop(root);
+}
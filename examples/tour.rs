@@ -0,0 +1,61 @@
+#[allow(unused_imports)]
+#[macro_use]
+extern crate limn;
+
+mod util;
+
+use limn::prelude::*;
+
+fn main() {
+    let window_builder = glutin::WindowBuilder::new()
+        .with_title("Limn tour demo")
+        .with_min_dimensions(glutin::dpi::LogicalSize{width: 500.0, height: 400.0});
+    let mut app = util::init(window_builder);
+    let mut root = Widget::new("root");
+
+    let mut toolbar = Widget::new("toolbar");
+    let mut layout_settings = LinearLayoutSettings::new(Orientation::Horizontal);
+    layout_settings.spacing = Spacing::Between;
+    toolbar.linear_layout(layout_settings);
+    toolbar.layout().add(constraints![
+        align_top(&root).padding(20.0),
+        align_left(&root).padding(20.0),
+        align_right(&root).padding(20.0),
+    ]);
+    let mut new_button = Widget::from_modifier_style(ButtonStyle::from_text("New"));
+    new_button.set_name("new_button");
+    let mut open_button = Widget::from_modifier_style(ButtonStyle::from_text("Open"));
+    open_button.set_name("open_button");
+    let mut tour_button = Widget::from_modifier_style(ButtonStyle::from_text("Take the tour"));
+    toolbar.add_child(new_button).add_child(open_button).add_child(tour_button.clone());
+
+    let mut search = Widget::from_modifier(EditText::default());
+    search.set_name("search");
+    search.layout().add(constraints![
+        align_left(&root).padding(20.0),
+        align_right(&root).padding(20.0),
+        align_bottom(&root).padding(20.0),
+    ]);
+
+    let mut builder = TourBuilder::new();
+    builder
+        .add_step(TourStep::new("new_button", "Start something new",
+            "Creates an empty document. Unsaved changes in the current document are kept in a separate tab."))
+        .add_step(TourStep::new("open_button", "Pick up where you left off",
+            "Opens a document from disk, recently opened documents are listed first."))
+        .add_step(TourStep::new("search", "Find anything",
+            "Type here to search every open document."));
+    let mut tour = builder.build();
+    tour.layout().add(match_layout(&root));
+    tour.add_handler(|event: &TourFinished, _: EventArgs| {
+        println!("tour {}", if event.skipped { "skipped" } else { "finished" });
+    });
+    let tour_ref = tour.clone();
+    tour_button.add_handler(move |_: &ClickEvent, _: EventArgs| {
+        tour_ref.event(TourEvent::Start);
+    });
+
+    root.add_child(toolbar).add_child(search).add_child(tour.clone());
+    tour.event(TourEvent::Start);
+    app.main_loop(root);
+}
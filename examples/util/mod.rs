@@ -3,6 +3,7 @@ extern crate env_logger;
 use limn::prelude::*;
 use limn::input::{EscKeyCloseHandler, DebugSettingsHandler};
 use limn::resources;
+use limn::style::Theme;
 use limn::resources::font::FontDescriptor;
 use limn::draw::rect::RectStyle;
 use limn::draw::text::TextStyle;
@@ -13,10 +14,14 @@ pub fn default_style() {
     let mut res = resources::resources();
 
     res.font_loader.register_font_data(FontDescriptor::from_family("NotoSans"), include_bytes!("../../assets/fonts/NotoSans/NotoSans-Regular.ttf").to_vec()).unwrap();
+    default_theme(&mut res.theme);
+}
 
-    res.theme.register_type_style(EllipseStyle::default());
-    res.theme.register_type_style(RectStyle::default());
-    res.theme.register_type_style(style!(TextStyle {
+/// Register the styles used by the examples
+pub fn default_theme(theme: &mut Theme) {
+    theme.register_type_style(EllipseStyle::default());
+    theme.register_type_style(RectStyle::default());
+    theme.register_type_style(style!(TextStyle {
         font: FontDescriptor::from_family("NotoSans"),
         font_size: 24.0,
         text_color: BLACK,
@@ -24,43 +29,50 @@ pub fn default_style() {
         wrap: Wrap::Whitespace,
         align: Align::Start,
     }));
-    res.theme.register_class_prop_style("static_text", INACTIVE.clone(), style!(TextStyle {
+    theme.register_class_prop_style("static_text", INACTIVE.clone(), style!(TextStyle {
         text_color: GRAY_50,
     }));
-    res.theme.register_class_style("list_item_rect", style!(RectStyle {
+    theme.register_class_style("list_item_rect", style!(RectStyle {
         background_color: GRAY_30,
     }));
-    res.theme.register_class_prop_style("list_item_rect", SELECTED.clone(), style!(RectStyle {
+    theme.register_class_prop_style("list_item_rect", SELECTED.clone(), style!(RectStyle {
         background_color: BLUE_HIGHLIGHT,
     }));
-    res.theme.register_class_prop_style("list_item_rect", MOUSEOVER.clone(), style!(RectStyle {
+    theme.register_class_prop_style("list_item_rect", MOUSEOVER.clone(), style!(RectStyle {
         background_color: GRAY_60,
     }));
-    res.theme.register_class_style("list_item_text", style!(TextStyle {
+    theme.register_class_style("list_item_text", style!(TextStyle {
         text_color: WHITE,
     }));
-    res.theme.register_class_style("button_rect", style!(RectStyle {
+    theme.register_class_style("list_item_subtitle", style!(TextStyle {
+        text_color: GRAY_70,
+        font_size: 14.0,
+    }));
+    theme.register_class_style("list_item_divider", style!(RectStyle {
+        background_color: GRAY_50,
+    }));
+    theme.register_class_style("button_rect", style!(RectStyle {
         background_color: GRAY_80,
         corner_radius: Some(5.0),
         border: Some((1.0, GRAY_40)),
     }));
-    res.theme.register_class_prop_style("button_rect", INACTIVE.clone(), style!(RectStyle {
+    theme.register_class_prop_style("button_rect", INACTIVE.clone(), style!(RectStyle {
         background_color: GRAY_90,
         border: Some((1.0, GRAY_70)),
     }));
-    res.theme.register_class_prop_style("button_rect", ACTIVATED_PRESSED.clone(), style!(RectStyle {
+    theme.register_class_prop_style("button_rect", ACTIVATED_PRESSED.clone(), style!(RectStyle {
         background_color: GRAY_30,
     }));
-    res.theme.register_class_prop_style("button_rect", ACTIVATED.clone(), style!(RectStyle {
+    theme.register_class_prop_style("button_rect", ACTIVATED.clone(), style!(RectStyle {
         background_color: GRAY_40,
     }));
-    res.theme.register_class_prop_style("button_rect", PRESSED.clone(), style!(RectStyle {
+    theme.register_class_prop_style("button_rect", PRESSED.clone(), style!(RectStyle {
         background_color: GRAY_60,
     }));
-    res.theme.register_class_prop_style("button_rect", MOUSEOVER.clone(), style!(RectStyle {
+    theme.register_class_prop_style("button_rect", MOUSEOVER.clone(), style!(RectStyle {
         background_color: GRAY_90,
     }));
-    res.theme.register_modifier_class_style("scrollbar_slider", style!(SliderStyle {
+    theme.register_modifier_class_style("scrollbar_slider", style!(SliderStyle {
         variable_handle_size: true,
         handle_style: HandleStyle::Square,
         bar_style: BarStyle::Wide,
@@ -0,0 +1,67 @@
+#[allow(unused_imports)]
+#[macro_use]
+extern crate limn;
+
+mod util;
+
+use std::f32::consts::PI;
+use std::{thread, time};
+
+use limn::prelude::*;
+
+const SAMPLE_RATE: usize = 48_000;
+const CHUNK_MILLIS: usize = 20;
+
+/// Stands in for an audio callback, a tone that swells and fades every few seconds
+fn synthesize(start: usize, len: usize) -> Vec<f32> {
+    (start..start + len).map(|index| {
+        let time = index as f32 / SAMPLE_RATE as f32;
+        let envelope = (time * PI / 4.0).sin().abs();
+        envelope * (time * 220.0 * 2.0 * PI).sin() * (0.8 + 0.2 * (time * 3.0 * 2.0 * PI).sin())
+    }).collect()
+}
+
+fn main() {
+    let window_builder = glutin::WindowBuilder::new()
+        .with_title("Limn waveform demo")
+        .with_min_dimensions(glutin::dpi::LogicalSize{width: 500.0, height: 300.0});
+    let mut app = util::init(window_builder);
+    let mut root = Widget::new("root");
+    root.layout().add(min_size(Size::new(500.0, 300.0)));
+
+    let mut waveform = WaveformBuilder::new(Vec::new());
+    let mut level_meter = LevelMeterBuilder::new();
+    level_meter.layout().add(constraints![
+        align_top(&root).padding(20.0),
+        align_bottom(&root).padding(20.0),
+        align_right(&root).padding(20.0),
+        width(20.0),
+    ]);
+    waveform.layout().add(constraints![
+        align_top(&root).padding(20.0),
+        align_bottom(&root).padding(20.0),
+        align_left(&root).padding(20.0),
+        to_left_of(&level_meter).padding(20.0),
+    ]);
+    let (waveform_id, level_meter_id) = (waveform.id(), level_meter.id());
+    root.add_child(waveform).add_child(level_meter);
+
+    let sender = EventSender;
+    thread::spawn(move || {
+        let chunk_len = SAMPLE_RATE * CHUNK_MILLIS / 1000;
+        let mut position = 0;
+        loop {
+            thread::sleep(time::Duration::from_millis(CHUNK_MILLIS as u64));
+            let chunk = synthesize(position, chunk_len);
+            position += chunk_len;
+            let peak = chunk.iter().fold(0.0f32, |peak, sample| peak.max(sample.abs()));
+            sender.update_widget(waveform_id, move |state: &mut WaveformState| {
+                state.push_samples(&chunk);
+            });
+            sender.update_widget(level_meter_id, move |state: &mut LevelMeterState| {
+                state.set_level(peak);
+            });
+        }
+    });
+    app.main_loop(root);
+}
@@ -0,0 +1,134 @@
+//! A higher level way of implementing `LayoutContainer`.
+//!
+//! Rather than editing the parent layout directly as children come and go, an `Arrange`
+//! implementation describes the constraints for a single child, given it's position among
+//! its siblings. `ArrangeContainer` keeps track of which constraints were created for which
+//! child, and rebuilds the constraints of any neighbouring children when a child is added,
+//! removed or moved, so the solver never sees stale constraints.
+
+use cassowary::Constraint;
+
+use super::{LayoutId, LayoutVars, Layout, LayoutContainer};
+use super::constraint::ConstraintBuilder;
+
+/// Describes the position of a child within an `ArrangeContainer`.
+#[derive(Debug, Copy, Clone)]
+pub struct ChildSlot {
+    /// The layout of the container
+    pub parent: LayoutVars,
+    /// The layout of the child being arranged
+    pub vars: LayoutVars,
+    /// Position of the child among its siblings
+    pub index: usize,
+    /// Total number of children in the container
+    pub count: usize,
+    /// The sibling before this child, if any
+    pub prev: Option<LayoutVars>,
+    /// The sibling after this child, if any
+    pub next: Option<LayoutVars>,
+}
+
+impl ChildSlot {
+    pub fn is_first(&self) -> bool {
+        self.prev.is_none()
+    }
+    pub fn is_last(&self) -> bool {
+        self.next.is_none()
+    }
+}
+
+/// Defines how an `ArrangeContainer` positions its children.
+///
+/// The returned builders are applied to the child, so the usual constraint functions
+/// can be used, ex. `constraints![align_left(&slot.parent), align_below(&prev)]`.
+pub trait Arrange {
+    fn arrange(&mut self, slot: &ChildSlot) -> Vec<Box<ConstraintBuilder>>;
+}
+
+impl<F> Arrange for F where F: FnMut(&ChildSlot) -> Vec<Box<ConstraintBuilder>> {
+    fn arrange(&mut self, slot: &ChildSlot) -> Vec<Box<ConstraintBuilder>> {
+        self(slot)
+    }
+}
+
+#[derive(Debug, Clone)]
+struct ArrangedChild {
+    id: LayoutId,
+    vars: LayoutVars,
+    constraints: Vec<Constraint>,
+}
+
+/// `LayoutContainer` that delegates the placement of each child to an `Arrange` implementation.
+pub struct ArrangeContainer<A> {
+    arrange: A,
+    children: Vec<ArrangedChild>,
+}
+
+impl<A: Arrange> ArrangeContainer<A> {
+    pub fn new(arrange: A) -> Self {
+        ArrangeContainer {
+            arrange: arrange,
+            children: Vec::new(),
+        }
+    }
+
+    fn slot(&self, parent: &Layout, index: usize) -> ChildSlot {
+        ChildSlot {
+            parent: parent.vars,
+            vars: self.children[index].vars,
+            index: index,
+            count: self.children.len(),
+            prev: if index > 0 { Some(self.children[index - 1].vars) } else { None },
+            next: self.children.get(index + 1).map(|child| child.vars),
+        }
+    }
+
+    /// Replace the constraints of the child at `index` with freshly arranged ones.
+    fn rearrange(&mut self, parent: &mut Layout, index: usize) {
+        if index >= self.children.len() {
+            return;
+        }
+        let slot = self.slot(parent, index);
+        let constraints = self.arrange.arrange(&slot).build(&slot.vars);
+        let child = &mut self.children[index];
+        parent.remove_constraints(::std::mem::replace(&mut child.constraints, constraints.clone()));
+        parent.add(constraints);
+    }
+}
+
+impl<A: Arrange> LayoutContainer for ArrangeContainer<A> {
+    fn add_child(&mut self, parent: &mut Layout, child: &mut Layout) {
+        self.children.push(ArrangedChild {
+            id: child.id,
+            vars: child.vars,
+            constraints: Vec::new(),
+        });
+        let index = self.children.len() - 1;
+        if index > 0 {
+            self.rearrange(parent, index - 1);
+        }
+        self.rearrange(parent, index);
+    }
+
+    fn remove_child(&mut self, parent: &mut Layout, child: &mut Layout) {
+        if let Some(index) = self.children.iter().position(|arranged| arranged.id == child.id) {
+            let removed = self.children.remove(index);
+            parent.remove_constraints(removed.constraints);
+            if index > 0 {
+                self.rearrange(parent, index - 1);
+            }
+            self.rearrange(parent, index);
+        }
+    }
+
+    fn move_child(&mut self, parent: &mut Layout, child: LayoutId, index: usize) {
+        if let Some(old_index) = self.children.iter().position(|arranged| arranged.id == child) {
+            let moved = self.children.remove(old_index);
+            let index = ::std::cmp::min(index, self.children.len());
+            self.children.insert(index, moved);
+            for index in 0..self.children.len() {
+                self.rearrange(parent, index);
+            }
+        }
+    }
+}
@@ -6,6 +6,46 @@ use euclid::{TypedPoint2D, TypedSize2D};
 
 use super::{LAYOUT, LayoutRef, LayoutVars, Size, Point};
 
+/// Named strengths for constraints, in place of raw cassowary strengths. Each level other than
+/// `Required` can be split into tiers with `tier`, ordered by the app.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum Strength {
+    Required,
+    Strong,
+    Medium,
+    Weak,
+}
+
+/// Highest tier within a level, higher tiers would reach the next level
+pub const MAX_STRENGTH_TIER: u16 = 998;
+
+impl Strength {
+    pub fn value(self) -> f64 {
+        match self {
+            Strength::Required => REQUIRED,
+            Strength::Strong => STRONG,
+            Strength::Medium => MEDIUM,
+            Strength::Weak => WEAK,
+        }
+    }
+    /// A strength above this level by `tier`, but still below the next level, so a constraint
+    /// in a higher tier wins over a lower tier of the same level, ex. `Strength::Weak.tier(1)`
+    /// for a preferred size that should give way to anything stronger than weak.
+    /// Tier 0 is the level itself, required constraints have no tiers.
+    pub fn tier(self, tier: u16) -> f64 {
+        match self {
+            Strength::Required => REQUIRED,
+            _ => self.value() * (1.0 + f64::from(tier.min(MAX_STRENGTH_TIER))),
+        }
+    }
+}
+
+impl From<Strength> for f64 {
+    fn from(strength: Strength) -> f64 {
+        strength.value()
+    }
+}
+
 pub fn width(width: f32) -> WidgetConstraintBuilder {
     WidgetConstraint::Width(width).builder(REQUIRED)
 }
@@ -205,8 +245,8 @@ pub struct WidgetConstraintBuilder {
 }
 
 impl WidgetConstraintBuilder {
-    pub fn strength(mut self, strength: f64) -> Self {
-        self.strength = strength;
+    pub fn strength<S: Into<f64>>(mut self, strength: S) -> Self {
+        self.strength = strength.into();
         self
     }
 }
@@ -219,8 +259,8 @@ pub struct PaddableConstraintBuilder {
 }
 
 impl PaddableConstraintBuilder {
-    pub fn strength(mut self, strength: f64) -> Self {
-        self.strength = strength;
+    pub fn strength<S: Into<f64>>(mut self, strength: S) -> Self {
+        self.strength = strength.into();
         self
     }
     pub fn padding(mut self, padding: f32) -> Self {
@@ -231,6 +271,34 @@ impl PaddableConstraintBuilder {
 
 pub trait ConstraintBuilder {
     fn build(&self, widget: &LayoutVars) -> Vec<Constraint>;
+    /// Like `build`, with the name of each constraint, if it has one
+    fn build_named(&self, widget: &LayoutVars) -> Vec<(Constraint, Option<String>)> {
+        self.build(widget).into_iter().map(|constraint| (constraint, None)).collect()
+    }
+    /// Name the constraints, ex. `width(200.0).named("sidebar_width")`. The name is shown in
+    /// place of the constraint in solver diagnostics, ex. when the constraint conflicts with others.
+    fn named(self, name: &str) -> Named<Self> where Self: Sized {
+        Named {
+            builder: self,
+            name: name.to_owned(),
+        }
+    }
+}
+
+/// Constraints with a name, see `ConstraintBuilder::named`
+#[derive(Debug, Clone)]
+pub struct Named<B> {
+    builder: B,
+    name: String,
+}
+
+impl<B: ConstraintBuilder> ConstraintBuilder for Named<B> {
+    fn build(&self, widget: &LayoutVars) -> Vec<Constraint> {
+        self.builder.build(widget)
+    }
+    fn build_named(&self, widget: &LayoutVars) -> Vec<(Constraint, Option<String>)> {
+        self.builder.build(widget).into_iter().map(|constraint| (constraint, Some(self.name.clone()))).collect()
+    }
 }
 
 impl ConstraintBuilder for Constraint {
@@ -425,10 +493,20 @@ impl <C: ConstraintBuilder> ConstraintBuilder for Vec<C> {
         }
         constraints
     }
+    fn build_named(&self, widget: &LayoutVars) -> Vec<(Constraint, Option<String>)> {
+        let mut constraints = Vec::new();
+        for builder in self {
+            constraints.extend(builder.build_named(widget));
+        }
+        constraints
+    }
 }
 
 impl ConstraintBuilder for Box<ConstraintBuilder> {
     fn build(&self, widget: &LayoutVars) -> Vec<Constraint> {
         self.as_ref().build(widget)
     }
+    fn build_named(&self, widget: &LayoutVars) -> Vec<(Constraint, Option<String>)> {
+        self.as_ref().build_named(widget)
+    }
 }
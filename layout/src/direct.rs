@@ -0,0 +1,191 @@
+//! Layouts computed directly from the bounds of the parent, without the solver.
+//!
+//! Stacks, flex rows and grids of equally sized cells don't need a constraint solver, the bounds
+//! of each child follow from the bounds of the parent and the position of the child. A layout
+//! whose container is a `DirectLayout` doesn't add any constraints to its children, and children
+//! that don't need the solver never get registered with it, which keeps large, regular trees
+//! cheap to lay out.
+//!
+//! A directly placed child still goes through the solver when it has constraints of its own, or
+//! has children placed by constraints. Its bounds are then suggested to the solver as edit
+//! variables, so its own constraints can refine them. Constraints relating a directly placed
+//! child to its parent or siblings can't be satisfied, the parent isn't in the solver on their
+//! behalf.
+
+use super::{Rect, Point, Size};
+use super::linear_layout::Orientation;
+
+/// Computes the bounds of the visible children of a layout from the bounds of the layout
+pub trait DirectLayout {
+    /// The bounds of each of `count` children, in order
+    fn arrange(&mut self, bounds: Rect, count: usize) -> Vec<Rect>;
+}
+
+/// Children placed one after another along an axis, filling the other axis. The space along the
+/// axis is shared by weight, children without a weight have a weight of one.
+#[derive(Debug, Clone)]
+pub struct DirectStack {
+    orientation: Orientation,
+    spacing: f32,
+    weights: Vec<f32>,
+}
+
+impl DirectStack {
+    pub fn new(orientation: Orientation) -> Self {
+        DirectStack {
+            orientation: orientation,
+            spacing: 0.0,
+            weights: Vec::new(),
+        }
+    }
+    /// Space between children
+    pub fn set_spacing(&mut self, spacing: f32) -> &mut Self {
+        self.spacing = spacing;
+        self
+    }
+    /// Weights of the children, in order, for a flex layout
+    pub fn set_weights(&mut self, weights: Vec<f32>) -> &mut Self {
+        self.weights = weights;
+        self
+    }
+}
+
+impl DirectLayout for DirectStack {
+    fn arrange(&mut self, bounds: Rect, count: usize) -> Vec<Rect> {
+        if count == 0 {
+            return Vec::new();
+        }
+        let weight = |index: usize| self.weights.get(index).cloned().unwrap_or(1.0).max(0.0);
+        let total_weight: f32 = (0..count).map(&weight).sum();
+        let length = match self.orientation {
+            Orientation::Horizontal => bounds.size.width,
+            Orientation::Vertical => bounds.size.height,
+        };
+        let available = (length - self.spacing * (count - 1) as f32).max(0.0);
+        let mut offset = 0.0;
+        (0..count).map(|index| {
+            let size = if total_weight > 0.0 { available * weight(index) / total_weight } else { 0.0 };
+            let rect = match self.orientation {
+                Orientation::Horizontal => Rect::new(
+                    Point::new(bounds.origin.x + offset, bounds.origin.y),
+                    Size::new(size, bounds.size.height)),
+                Orientation::Vertical => Rect::new(
+                    Point::new(bounds.origin.x, bounds.origin.y + offset),
+                    Size::new(bounds.size.width, size)),
+            };
+            offset += size + self.spacing;
+            rect
+        }).collect()
+    }
+}
+
+/// Children placed in a row while the parent is wide, and in a column while it's tall, ex. for
+/// toolbars and split views that follow the shape of the window
+#[derive(Debug, Clone)]
+pub struct DirectFlipStack {
+    threshold: f32,
+    stack: DirectStack,
+}
+
+impl Default for DirectFlipStack {
+    fn default() -> Self {
+        DirectFlipStack {
+            threshold: 1.0,
+            stack: DirectStack::new(Orientation::Horizontal),
+        }
+    }
+}
+
+impl DirectFlipStack {
+    pub fn new() -> Self {
+        Self::default()
+    }
+    /// Ratio of width to height at and above which children are placed in a row, 1 by default
+    pub fn set_threshold(&mut self, threshold: f32) -> &mut Self {
+        self.threshold = threshold;
+        self
+    }
+    /// Space between children
+    pub fn set_spacing(&mut self, spacing: f32) -> &mut Self {
+        self.stack.set_spacing(spacing);
+        self
+    }
+    /// Weights of the children, in order, along whichever axis they're placed on
+    pub fn set_weights(&mut self, weights: Vec<f32>) -> &mut Self {
+        self.stack.set_weights(weights);
+        self
+    }
+    /// The axis children are placed along within `bounds`
+    pub fn orientation(&self, bounds: Rect) -> Orientation {
+        if bounds.size.width >= bounds.size.height * self.threshold {
+            Orientation::Horizontal
+        } else {
+            Orientation::Vertical
+        }
+    }
+}
+
+impl DirectLayout for DirectFlipStack {
+    fn arrange(&mut self, bounds: Rect, count: usize) -> Vec<Rect> {
+        self.stack.orientation = self.orientation(bounds);
+        self.stack.arrange(bounds, count)
+    }
+}
+
+/// Children placed in rows of equally sized cells, left to right then top to bottom
+#[derive(Debug, Clone)]
+pub struct DirectGrid {
+    columns: usize,
+    spacing: f32,
+    row_height: Option<f32>,
+}
+
+impl DirectGrid {
+    pub fn new(columns: usize) -> Self {
+        DirectGrid {
+            columns: columns.max(1),
+            spacing: 0.0,
+            row_height: None,
+        }
+    }
+    /// Space between rows and columns
+    pub fn set_spacing(&mut self, spacing: f32) -> &mut Self {
+        self.spacing = spacing;
+        self
+    }
+    /// Fix the height of the rows, otherwise the rows share the height of the parent
+    pub fn set_row_height(&mut self, row_height: f32) -> &mut Self {
+        self.row_height = Some(row_height);
+        self
+    }
+}
+
+impl DirectLayout for DirectGrid {
+    fn arrange(&mut self, bounds: Rect, count: usize) -> Vec<Rect> {
+        if count == 0 {
+            return Vec::new();
+        }
+        let rows = (count + self.columns - 1) / self.columns;
+        let cell_width = ((bounds.size.width - self.spacing * (self.columns - 1) as f32) / self.columns as f32).max(0.0);
+        let cell_height = self.row_height.unwrap_or_else(|| {
+            ((bounds.size.height - self.spacing * (rows - 1) as f32) / rows as f32).max(0.0)
+        });
+        (0..count).map(|index| {
+            let (row, column) = (index / self.columns, index % self.columns);
+            Rect::new(
+                Point::new(bounds.origin.x + column as f32 * (cell_width + self.spacing),
+                           bounds.origin.y + row as f32 * (cell_height + self.spacing)),
+                Size::new(cell_width, cell_height))
+        }).collect()
+    }
+}
+
+/// Every child fills the parent, ex. for layers drawn over each other
+#[derive(Debug, Default, Copy, Clone)]
+pub struct DirectOverlay;
+
+impl DirectLayout for DirectOverlay {
+    fn arrange(&mut self, bounds: Rect, count: usize) -> Vec<Rect> {
+        vec![bounds; count]
+    }
+}
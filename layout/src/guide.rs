@@ -0,0 +1,164 @@
+//! Invisible guides that widgets can be aligned to, in place of dummy spacer widgets.
+//!
+//! A `Guideline` is a vertical or horizontal line and an `Anchor` is a point, each positioned
+//! relative to the bounds of the layout it's attached to, ex. a vertical line at 30% of the
+//! width of a panel, or a point 20 pixels in from its bottom right corner. Guides are variables
+//! in the solver, associated with the layout they're attached to, so any number of widgets can
+//! be constrained to the same guide, and the guide moves with the layout.
+
+use cassowary::{Variable, Constraint};
+use cassowary::WeightedRelation::*;
+use cassowary::strength::*;
+
+use super::{Layout, LayoutVars};
+use super::linear_layout::Orientation;
+use super::constraint::*;
+
+/// The position of a guide along one axis of the layout it's attached to
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum GuidePosition {
+    /// A fraction of the length of the layout, from the start
+    Fraction(f32),
+    /// An offset from the start, left or top, of the layout
+    FromStart(f32),
+    /// An offset from the end, right or bottom, of the layout
+    FromEnd(f32),
+}
+
+impl GuidePosition {
+    fn constraint(&self, var: Variable, start: Variable, end: Variable, length: Variable) -> Constraint {
+        match *self {
+            GuidePosition::Fraction(fraction) => var - start | EQ(REQUIRED) | f64::from(fraction) * length,
+            GuidePosition::FromStart(offset) => var - start | EQ(REQUIRED) | offset,
+            GuidePosition::FromEnd(offset) => end - var | EQ(REQUIRED) | offset,
+        }
+    }
+}
+
+/// A vertical or horizontal line widgets can be aligned to
+#[derive(Debug, Copy, Clone)]
+pub struct Guideline {
+    pub var: Variable,
+    orientation: Orientation,
+    position: GuidePosition,
+}
+
+impl Guideline {
+    /// A vertical line, positioned along the width of the layout it's attached to
+    pub fn vertical(position: GuidePosition) -> Self {
+        Guideline {
+            var: Variable::new(),
+            orientation: Orientation::Vertical,
+            position: position,
+        }
+    }
+    /// A horizontal line, positioned along the height of the layout it's attached to
+    pub fn horizontal(position: GuidePosition) -> Self {
+        Guideline {
+            var: Variable::new(),
+            orientation: Orientation::Horizontal,
+            position: position,
+        }
+    }
+    /// Position the guide relative to `layout`, `name` is shown in solver diagnostics
+    pub fn attach(&self, layout: &mut Layout, name: &str) {
+        let vars = layout.vars;
+        let constraint = match self.orientation {
+            Orientation::Vertical => self.position.constraint(self.var, vars.left, vars.right, vars.width),
+            Orientation::Horizontal => self.position.constraint(self.var, vars.top, vars.bottom, vars.height),
+        };
+        layout.add_associated_var(self.var, name);
+        layout.add(constraint);
+    }
+
+    /// Align the left edge of a widget to a vertical guide
+    pub fn align_left(&self) -> PaddableConstraintBuilder {
+        PaddableConstraint::AlignLeft(self.var).builder(REQUIRED)
+    }
+    /// Align the right edge of a widget to a vertical guide
+    pub fn align_right(&self) -> PaddableConstraintBuilder {
+        PaddableConstraint::AlignRight(self.var).builder(REQUIRED)
+    }
+    /// Align the top edge of a widget to a horizontal guide
+    pub fn align_top(&self) -> PaddableConstraintBuilder {
+        PaddableConstraint::AlignTop(self.var).builder(REQUIRED)
+    }
+    /// Align the bottom edge of a widget to a horizontal guide
+    pub fn align_bottom(&self) -> PaddableConstraintBuilder {
+        PaddableConstraint::AlignBottom(self.var).builder(REQUIRED)
+    }
+    /// Keep a widget left of, or above, the guide
+    pub fn before(&self) -> PaddableConstraintBuilder {
+        match self.orientation {
+            Orientation::Vertical => PaddableConstraint::ToLeftOf(self.var).builder(REQUIRED),
+            Orientation::Horizontal => PaddableConstraint::Above(self.var).builder(REQUIRED),
+        }
+    }
+    /// Keep a widget right of, or below, the guide
+    pub fn after(&self) -> PaddableConstraintBuilder {
+        match self.orientation {
+            Orientation::Vertical => PaddableConstraint::ToRightOf(self.var).builder(REQUIRED),
+            Orientation::Horizontal => PaddableConstraint::Below(self.var).builder(REQUIRED),
+        }
+    }
+    /// Center a widget on the guide
+    pub fn center(&self) -> WidgetConstraintBuilder {
+        match self.orientation {
+            Orientation::Vertical => WidgetConstraint::CenterHorizontal(self.var, self.var).builder(REQUIRED),
+            Orientation::Horizontal => WidgetConstraint::CenterVertical(self.var, self.var).builder(REQUIRED),
+        }
+    }
+}
+
+/// A point widgets can be aligned to
+#[derive(Debug, Copy, Clone)]
+pub struct Anchor {
+    pub x: Variable,
+    pub y: Variable,
+    horizontal: GuidePosition,
+    vertical: GuidePosition,
+}
+
+impl Anchor {
+    /// A point positioned along the width and height of the layout it's attached to
+    pub fn new(horizontal: GuidePosition, vertical: GuidePosition) -> Self {
+        Anchor {
+            x: Variable::new(),
+            y: Variable::new(),
+            horizontal: horizontal,
+            vertical: vertical,
+        }
+    }
+    /// Position the anchor relative to `layout`, `name` is shown in solver diagnostics
+    pub fn attach(&self, layout: &mut Layout, name: &str) {
+        let LayoutVars { left, top, right, bottom, width, height } = layout.vars;
+        layout.add_associated_var(self.x, &format!("{}.x", name));
+        layout.add_associated_var(self.y, &format!("{}.y", name));
+        layout.add(vec![
+            self.horizontal.constraint(self.x, left, right, width),
+            self.vertical.constraint(self.y, top, bottom, height),
+        ]);
+    }
+
+    /// Center a widget on the anchor
+    pub fn center(&self) -> Vec<WidgetConstraintBuilder> {
+        vec![
+            WidgetConstraint::CenterHorizontal(self.x, self.x).builder(REQUIRED),
+            WidgetConstraint::CenterVertical(self.y, self.y).builder(REQUIRED),
+        ]
+    }
+    /// Place the top left corner of a widget on the anchor
+    pub fn top_left(&self) -> Vec<PaddableConstraintBuilder> {
+        vec![
+            PaddableConstraint::AlignLeft(self.x).builder(REQUIRED),
+            PaddableConstraint::AlignTop(self.y).builder(REQUIRED),
+        ]
+    }
+    /// Place the bottom right corner of a widget on the anchor
+    pub fn bottom_right(&self) -> Vec<PaddableConstraintBuilder> {
+        vec![
+            PaddableConstraint::AlignRight(self.x).builder(REQUIRED),
+            PaddableConstraint::AlignBottom(self.y).builder(REQUIRED),
+        ]
+    }
+}
@@ -54,6 +54,7 @@ use euclid::{Point2D, Size2D, UnknownUnit};
 
 use self::constraint::ConstraintBuilder;
 use self::constraint::*;
+use self::linear_layout::AxisSize;
 
 pub type Length = euclid::Length<f32, UnknownUnit>;
 pub type Size = Size2D<f32>;
@@ -159,7 +160,17 @@ pub struct Layout {
     removed_constraints: Vec<Constraint>,
     removed_children: Vec<LayoutId>,
     associated_vars: Vec<(Variable, String)>,
+    /// Names of constraints added since the last update
+    constraint_names: Vec<(Constraint, String)>,
     pub hidden: bool,
+    /// Placed by the `DirectLayout` of the parent
+    direct: bool,
+    /// Children are placed by a `DirectLayout`
+    direct_container: bool,
+    /// Constraints or edit variables have been added
+    constrained: bool,
+    /// Size along the axis of a parent `LinearLayout`
+    axis_size: Option<AxisSize>,
 }
 
 impl Layout {
@@ -185,7 +196,12 @@ impl Layout {
             removed_constraints: Vec::new(),
             removed_children: Vec::new(),
             associated_vars: Vec::new(),
+            constraint_names: Vec::new(),
             hidden: false,
+            direct: false,
+            direct_container: false,
+            constrained: false,
+            axis_size: None,
         }
     }
 
@@ -203,6 +219,24 @@ impl Layout {
     /// and it's children, as they are added, if any.
     pub fn set_container<T>(&mut self, container: T) where T: LayoutContainer + 'static {
         self.container = Some(Rc::new(RefCell::new(container)));
+        self.direct_container = false;
+    }
+    /// Clears the container, children added from now on are placed by a `DirectLayout`
+    /// rather than by constraints.
+    pub fn set_direct_container(&mut self) {
+        self.container = None;
+        self.direct_container = true;
+    }
+    /// True if this layout is placed by the `DirectLayout` of its parent and doesn't need
+    /// the solver, that is it has no constraints of its own and its children, if any, are
+    /// placed directly too.
+    pub fn bypasses_solver(&self) -> bool {
+        self.direct && !self.constrained && (self.children.is_empty() || self.direct_container)
+    }
+    /// Fix or flex the size of this layout along the axis of a parent `LinearLayout`, ex. to
+    /// push groups of siblings apart. Takes effect when the layout is added to the parent.
+    pub fn set_axis_size(&mut self, axis_size: AxisSize) {
+        self.axis_size = Some(axis_size);
     }
     pub fn edit_left(&mut self) -> VariableEditable {
         let var = self.vars.left;
@@ -232,19 +266,24 @@ impl Layout {
         builder.build(&self.vars)
     }
     pub fn add<B: ConstraintBuilder>(&mut self, builder: B) {
-        let new_constraints = builder.build(&self.vars);
-        self.new_constraints.extend(new_constraints);
+        for (constraint, name) in builder.build_named(&self.vars) {
+            if let Some(name) = name {
+                self.constraint_names.push((constraint.clone(), name));
+            }
+            self.new_constraints.insert(constraint);
+        }
+        self.constrained = true;
     }
     pub fn remove_constraint(&mut self, constraint: Constraint) {
-        if !self.new_constraints.remove(&constraint) {
+        if self.new_constraints.remove(&constraint) {
+            self.constraint_names.retain(|&(ref named, _)| *named != constraint);
+        } else {
             self.removed_constraints.push(constraint);
         }
     }
     pub fn remove_constraints(&mut self, constraints: Vec<Constraint>) {
         for constraint in constraints {
-            if !self.new_constraints.remove(&constraint) {
-                self.removed_constraints.push(constraint);
-            }
+            self.remove_constraint(constraint);
         }
     }
     pub fn has_constraint(&mut self, constraints: &Vec<Constraint>) -> bool {
@@ -269,11 +308,18 @@ impl Layout {
         }
         removed_constraints
     }
+    /// True if there are changes the solver hasn't seen yet, other than showing or hiding
+    pub fn has_changes(&self) -> bool {
+        !self.new_constraints.is_empty() || !self.removed_constraints.is_empty() ||
+            !self.edit_vars.is_empty() || !self.removed_children.is_empty() ||
+            !self.associated_vars.is_empty()
+    }
     pub fn get_edit_vars(&mut self) -> Vec<EditVariable> {
         mem::replace(&mut self.edit_vars, Vec::new())
     }
     pub fn add_child(&mut self, child: &mut Layout) {
         child.parent = Some(self.id);
+        child.direct = self.direct_container;
         self.children.push(child.id);
         if let Some(container) = self.container.clone() {
             container.borrow_mut().add_child(self, child);
@@ -287,6 +333,19 @@ impl Layout {
             self.children.remove(pos);
         }
         self.removed_children.push(child.id);
+        child.direct = false;
+    }
+    /// Moves an existing child to `index` within this layouts children,
+    /// giving the container a chance to update any order dependent constraints.
+    pub fn move_child(&mut self, child: LayoutId, index: usize) {
+        if let Some(pos) = self.children.iter().position(|id| child == *id) {
+            self.children.remove(pos);
+            let index = ::std::cmp::min(index, self.children.len());
+            self.children.insert(index, child);
+            if let Some(container) = self.container.clone() {
+                container.borrow_mut().move_child(self, child, index);
+            }
+        }
     }
     pub fn get_removed_children(&mut self) -> Vec<LayoutId> {
         mem::replace(&mut self.removed_children, Vec::new())
@@ -306,6 +365,9 @@ impl Layout {
     pub fn get_associated_vars(&mut self) -> Vec<(Variable, String)> {
         mem::replace(&mut self.associated_vars, Vec::new())
     }
+    pub fn get_constraint_names(&mut self) -> Vec<(Constraint, String)> {
+        mem::replace(&mut self.constraint_names, Vec::new())
+    }
     pub fn hide(&mut self) {
         self.hidden = true;
     }
@@ -330,8 +392,8 @@ impl<'a> VariableEditable<'a> {
             strength: STRONG,
         }
     }
-    pub fn strength(mut self, strength: f64) -> Self {
-        self.strength = strength;
+    pub fn strength<S: Into<f64>>(mut self, strength: S) -> Self {
+        self.strength = strength.into();
         self
     }
     pub fn set(mut self, val: f32) -> Self {
@@ -344,6 +406,7 @@ impl<'a> Drop for VariableEditable<'a> {
     fn drop(&mut self) {
         let edit_var = EditVariable::new(&self);
         self.builder.edit_vars.push(edit_var);
+        self.builder.constrained = true;
     }
 }
 
@@ -388,6 +451,8 @@ macro_rules! constraints {
 pub trait LayoutContainer {
     fn add_child(&mut self, parent: &mut Layout, child: &mut Layout);
     fn remove_child(&mut self, _: &mut Layout, _: &mut Layout) {}
+    /// Called when an existing child is moved to a new position among its siblings
+    fn move_child(&mut self, _: &mut Layout, _: LayoutId, _: usize) {}
 }
 
 #[derive(Debug, Default, Copy, Clone)]
@@ -419,6 +484,9 @@ pub mod solver;
 pub mod constraint;
 pub mod linear_layout;
 pub mod grid_layout;
+pub mod arrange;
+pub mod direct;
+pub mod guide;
 
 pub use self::solver::LimnSolver;
 
@@ -70,7 +70,23 @@ impl LinearLayoutSettings {
     }
 }
 
-#[derive(Debug, Copy, Clone)]
+/// Size of a child along the axis of a linear layout, see `Layout::set_axis_size`
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum AxisSize {
+    /// A fixed length along the axis
+    Fixed(f32),
+    /// A share of the space left over along the axis, by weight relative to the other
+    /// flexible children
+    Flex(f32),
+}
+
+/// Flexible children only take space nothing else wants, ex. the preferred size of a sibling
+const FLEX_STRENGTH: f64 = WEAK / 100.0;
+
+/// Flexible children are pushed towards sharing this much space, limited by the parent
+const FLEX_TARGET: f64 = 100_000.0;
+
+#[derive(Debug, Copy, Clone, PartialEq)]
 pub enum Orientation {
     Horizontal,
     Vertical,
@@ -93,6 +109,8 @@ pub struct LinearLayout {
     size: Option<Variable>,
     widgets: HashMap<LayoutId, WidgetData>,
     last_widget: Option<LayoutId>,
+    /// Length of one unit of weight of the flexible children, if there are any
+    flex_unit: Option<Variable>,
 }
 
 impl LinearLayout {
@@ -141,8 +159,23 @@ impl LinearLayout {
             size: size,
             widgets: HashMap::new(),
             last_widget: None,
+            flex_unit: None,
         }
     }
+
+    fn flex_unit(&mut self, parent: &mut Layout) -> Variable {
+        if let Some(flex_unit) = self.flex_unit {
+            return flex_unit;
+        }
+        let flex_unit = Variable::new();
+        parent.add_associated_var(flex_unit, "linear_layout_flex_unit");
+        parent.add(constraints![
+            flex_unit | GE(REQUIRED) | 0.0,
+            flex_unit | EQ(FLEX_STRENGTH) | FLEX_TARGET,
+        ]);
+        self.flex_unit = Some(flex_unit);
+        flex_unit
+    }
 }
 
 impl LayoutContainer for LinearLayout {
@@ -189,6 +222,18 @@ impl LayoutContainer for LinearLayout {
             let child_size = axis_length(self.settings.orientation, &child.vars);
             parent.add(child_size | EQ(REQUIRED) | self.size.unwrap());
         }
+        match child.axis_size {
+            Some(AxisSize::Fixed(length)) => {
+                let child_size = axis_length(self.settings.orientation, &child.vars);
+                child.add(child_size | EQ(REQUIRED) | length);
+            }
+            Some(AxisSize::Flex(grow)) => {
+                let child_size = axis_length(self.settings.orientation, &child.vars);
+                let flex_unit = self.flex_unit(parent);
+                child.add(child_size | EQ(REQUIRED) | f64::from(grow.max(0.0)) * flex_unit);
+            }
+            None => (),
+        }
         match self.settings.orientation {
             Orientation::Horizontal => {
                 match self.settings.item_align {
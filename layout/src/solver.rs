@@ -1,5 +1,6 @@
 use std::collections::{HashMap, HashSet, VecDeque};
 use std::fmt::Write;
+use std::mem;
 
 use cassowary;
 use cassowary::strength::*;
@@ -8,10 +9,60 @@ use cassowary::WeightedRelation::*;
 
 use super::{LayoutId, Layout, VarType, LayoutVars, EditVariable, Rect, Point, Size};
 
+/// Number of available sizes the layout of a hidden subtree is kept for
+const LAYOUT_CACHE_SIZES: usize = 4;
+
+/// The values of every variable in a subtree, solved for one available size. Horizontal
+/// positions are relative to the left of the parent, vertical positions to the top.
+#[derive(Clone)]
+struct CachedLayout {
+    /// Width and height of the parent when the subtree was solved
+    available: (f64, f64),
+    values: Vec<(LayoutId, Variable, VarType, f64)>,
+}
+
+/// A subtree shown with cached values, rather than its constraints. Only the root of the subtree
+/// is in the solver, the values of the rest come from the cache, relative to the root.
+struct FrozenLayout {
+    parent: LayoutId,
+    layout: CachedLayout,
+    /// Suggest the cached size of the root
+    constraints: Vec<Constraint>,
+}
+
+impl FrozenLayout {
+    /// The cached size of the root
+    fn size(&self) -> (f64, f64) {
+        let mut size = (0.0, 0.0);
+        // the root comes first in the cached values
+        for &(_, _, var_type, value) in &self.layout.values[..6] {
+            match var_type {
+                VarType::Width => size.0 = value,
+                VarType::Height => size.1 = value,
+                _ => (),
+            }
+        }
+        size
+    }
+}
+
+fn same_size(a: (f64, f64), b: (f64, f64)) -> bool {
+    (a.0 - b.0).abs() < 0.001 && (a.1 - b.1).abs() < 0.001
+}
+
 pub struct LimnSolver {
     pub solver: cassowary::Solver,
     pub strict: bool,
     layouts: LayoutManager,
+    /// Solved layouts of hidden subtrees, by the root of the subtree, most recent first
+    layout_cache: HashMap<LayoutId, Vec<CachedLayout>>,
+    /// The roots of the cached subtrees each layout is in
+    cache_members: HashMap<LayoutId, HashSet<LayoutId>>,
+    frozen: HashMap<LayoutId, FrozenLayout>,
+    /// The root of the frozen subtree each layout is in
+    frozen_members: HashMap<LayoutId, LayoutId>,
+    /// Changes from frozen subtrees, not yet fetched
+    pending_changes: Vec<(LayoutId, VarType, f64)>,
 }
 
 impl LimnSolver {
@@ -20,14 +71,37 @@ impl LimnSolver {
             solver: cassowary::Solver::new(),
             strict: false,
             layouts: LayoutManager::new(),
+            layout_cache: HashMap::new(),
+            cache_members: HashMap::new(),
+            frozen: HashMap::new(),
+            frozen_members: HashMap::new(),
+            pending_changes: Vec::new(),
         }
     }
 
     pub fn update_layout(&mut self, layout: &mut Layout) {
 
         let registered = self.layouts.layouts.contains_key(&layout.id);
+        if !registered && layout.bypasses_solver() {
+            // placed by the parent, changes are kept in the layout until it needs the solver,
+            // only children that were in the solver need removing
+            for child in layout.get_removed_children() {
+                self.remove_layout(child);
+            }
+            return;
+        }
         if !registered {
             self.layouts.register_layout(layout);
+            if let Some(parent) = layout.parent {
+                self.invalidate_cache(parent);
+            }
+        }
+        if layout.has_changes() {
+            self.invalidate_cache(layout.id);
+        } else if registered && layout.hidden != self.layouts.layout_hidden(layout.id) {
+            // showing or hiding a subtree changes the layout of any subtree it's in,
+            // but not its own cached layout
+            self.invalidate_ancestor_caches(layout.id);
         }
         self.layouts.update_layout(layout);
 
@@ -91,9 +165,22 @@ impl LimnSolver {
     }
 
     pub fn remove_layout(&mut self, id: LayoutId) {
+        // subtrees shown from the cache are solved again when their root, a member or the parent
+        // they were cached for is removed, so no frozen layout refers to a removed one
+        let frozen: Vec<LayoutId> = self.frozen.iter()
+            .filter(|&(&root, frozen)| root == id || frozen.parent == id || self.frozen_members.get(&id) == Some(&root))
+            .map(|(&root, _)| root)
+            .collect();
+        for root in frozen {
+            self.thaw_layout(root);
+        }
+        self.invalidate_cache(id);
+        self.layout_cache.remove(&id);
+        self.cache_members.remove(&id);
         if let Some(layout) = self.layouts.layouts.remove(&id) {
             for constraint in layout.constraints {
                 self.remove_constraint(&constraint);
+                self.layouts.constraint_names.remove(&constraint);
             }
             for var in layout.vars.array().iter() {
                 self.layouts.var_ids.remove(&var);
@@ -102,6 +189,26 @@ impl LimnSolver {
     }
 
     pub fn hide_layout(&mut self, id: LayoutId) {
+        if !self.layouts.layouts.contains_key(&id) {
+            // placed directly by the parent, hidden when it's arranged
+            return;
+        }
+        if !self.layouts.layout_hidden(id) {
+            self.cache_layout(id);
+            // frozen subtrees are hidden as they are, without adding their constraints back first
+            let frozen: Vec<LayoutId> = self.subtree(id).into_iter()
+                .filter(|layout_id| self.frozen.contains_key(layout_id))
+                .collect();
+            for root in frozen {
+                self.unfreeze_layout(root, false);
+            }
+        }
+        self.hide_subtree(id);
+    }
+    fn hide_subtree(&mut self, id: LayoutId) {
+        if !self.layouts.layouts.contains_key(&id) {
+            return;
+        }
         if !self.layouts.layout_hidden(id) {
             for constraint in self.layouts.layouts[&id].constraints.clone() {
                 self.remove_constraint(&constraint);
@@ -123,34 +230,239 @@ impl LimnSolver {
         }
         let children = self.layouts.layouts[&id].children.clone();
         for child in children {
-            self.hide_layout(child);
+            self.hide_subtree(child);
         }
     }
     pub fn unhide_layout(&mut self, id: LayoutId) {
+        if !self.layouts.layouts.contains_key(&id) {
+            // placed directly by the parent, hidden when it's arranged
+            return;
+        }
+        if self.layouts.layout_hidden(id) && self.unhide_cached(id) {
+            return;
+        }
+        self.unhide_subtree(id);
+    }
+    fn unhide_subtree(&mut self, id: LayoutId) {
+        if !self.layouts.layouts.contains_key(&id) {
+            return;
+        }
         if self.layouts.layout_hidden(id) {
             for constraint in self.layouts.layouts[&id].hidden_constraints.clone() {
                 self.remove_constraint(&constraint);
             }
-            for constraint in self.layouts.layouts[&id].constraints.clone() {
-                if !self.solver.has_constraint(&constraint) {
-                    let mut hidden = false;
-                    for layout_id in self.layouts.dependent_layouts(&constraint) {
-                        if layout_id != id && self.layouts.layout_hidden(layout_id) {
-                            hidden = true;
-                            break;
-                        }
-                    }
-                    if !hidden {
-                        self.add_constraint(constraint.clone());
-                    }
-                }
-            }
+            self.restore_constraints(id);
             let layout = self.layouts.layouts.get_mut(&id).unwrap();
             layout.hidden = false;
         }
         let children = self.layouts.layouts[&id].children.clone();
         for child in children {
-            self.unhide_layout(child);
+            self.unhide_subtree(child);
+        }
+    }
+    /// Add back the constraints of a layout that aren't in the solver, unless they depend on
+    /// another layout that's hidden
+    fn restore_constraints(&mut self, id: LayoutId) {
+        for constraint in self.layouts.layouts[&id].constraints.clone() {
+            if !self.solver.has_constraint(&constraint) {
+                let mut hidden = false;
+                for layout_id in self.layouts.dependent_layouts(&constraint) {
+                    if layout_id != id && self.layouts.layout_hidden(layout_id) {
+                        hidden = true;
+                        break;
+                    }
+                }
+                if !hidden {
+                    self.add_constraint(constraint.clone());
+                }
+            }
+        }
+    }
+
+    /// Drop the cached layouts of every subtree `id` is in, ex. when its constraints change,
+    /// and solve any of those subtrees that are shown from the cache.
+    pub fn invalidate_cache(&mut self, id: LayoutId) {
+        if let Some(roots) = self.cache_members.remove(&id) {
+            for root in roots {
+                self.layout_cache.remove(&root);
+                self.thaw_layout(root);
+            }
+        }
+    }
+    fn invalidate_ancestor_caches(&mut self, id: LayoutId) {
+        let ancestors: Vec<LayoutId> = match self.cache_members.get_mut(&id) {
+            Some(roots) => {
+                let ancestors = roots.iter().cloned().filter(|root| *root != id).collect();
+                roots.retain(|root| *root == id);
+                ancestors
+            }
+            None => return,
+        };
+        for root in ancestors {
+            self.layout_cache.remove(&root);
+            self.thaw_layout(root);
+        }
+    }
+
+    /// The registered subtree rooted at `id`, parents before children
+    fn subtree(&self, id: LayoutId) -> Vec<LayoutId> {
+        let mut layouts = vec![id];
+        let mut index = 0;
+        while index < layouts.len() {
+            let children = self.layouts.children(layouts[index]);
+            layouts.extend(children);
+            index += 1;
+        }
+        layouts
+    }
+    /// The parent of `id` and the size available to it
+    fn available_size(&self, id: LayoutId) -> Option<(LayoutId, (f64, f64))> {
+        let parent = self.layouts.layouts[&id].parent?;
+        let vars = self.layouts.layouts.get(&parent)?.vars;
+        Some((parent, (self.solver.get_value(vars.width), self.solver.get_value(vars.height))))
+    }
+    fn origin(&self, id: LayoutId) -> (f64, f64) {
+        let vars = self.layouts.layouts[&id].vars;
+        (self.solver.get_value(vars.left), self.solver.get_value(vars.top))
+    }
+    /// Keep the solved layout of a subtree that's about to be hidden
+    fn cache_layout(&mut self, id: LayoutId) {
+        if self.frozen.contains_key(&id) {
+            // shown from the cache, the cached layout is still valid
+            return;
+        }
+        let available = match self.available_size(id) {
+            Some((_, available)) => available,
+            None => return,
+        };
+        let layouts = self.subtree(id);
+        if layouts.iter().any(|layout| self.frozen_members.contains_key(layout)) {
+            // part of the subtree isn't solved
+            return;
+        }
+        // only the root can be related to layouts outside the subtree, the rest aren't in the
+        // solver when shown from the cache
+        let members: HashSet<LayoutId> = layouts.iter().cloned().collect();
+        for &layout_id in &layouts[1..] {
+            for constraint in &self.layouts.layouts[&layout_id].constraints {
+                let outside = constraint.expr().terms.iter()
+                    .any(|term| self.layouts.var_ids.get(&term.variable).map_or(true, |id| !members.contains(id)));
+                if outside {
+                    return;
+                }
+            }
+        }
+        let (left, top) = self.origin(id);
+        let mut values = Vec::new();
+        for &layout_id in &layouts {
+            let vars = self.layouts.layouts[&layout_id].vars;
+            for &var in vars.array().iter() {
+                let var_type = vars.var_type(var);
+                let value = self.solver.get_value(var) - match var_type {
+                    VarType::Left | VarType::Right => left,
+                    VarType::Top | VarType::Bottom => top,
+                    _ => 0.0,
+                };
+                values.push((layout_id, var, var_type, value));
+            }
+        }
+        {
+            let entries = self.layout_cache.entry(id).or_insert_with(Vec::new);
+            entries.retain(|entry| !same_size(entry.available, available));
+            entries.insert(0, CachedLayout { available: available, values: values });
+            entries.truncate(LAYOUT_CACHE_SIZES);
+        }
+        for layout_id in layouts {
+            self.cache_members.entry(layout_id).or_insert_with(HashSet::new).insert(id);
+        }
+    }
+    /// Show a hidden subtree with a layout cached for the available size. Only the constraints
+    /// relating the root to layouts outside the subtree are added back, with its cached size
+    /// suggested. Returns false if there's no such layout.
+    fn unhide_cached(&mut self, id: LayoutId) -> bool {
+        let (parent, available) = match self.available_size(id) {
+            Some(available) => available,
+            None => return false,
+        };
+        if self.layouts.layout_hidden(parent) {
+            return false;
+        }
+        let cached = match self.layout_cache.get(&id)
+            .and_then(|entries| entries.iter().find(|entry| same_size(entry.available, available))) {
+            Some(cached) => cached.clone(),
+            None => return false,
+        };
+        let layouts = self.subtree(id);
+        for &layout_id in &layouts {
+            for constraint in self.layouts.layouts[&layout_id].hidden_constraints.clone() {
+                self.remove_constraint(&constraint);
+            }
+            self.layouts.layouts.get_mut(&layout_id).unwrap().hidden = false;
+            self.frozen_members.insert(layout_id, id);
+        }
+        for constraint in self.layouts.layouts[&id].constraints.clone() {
+            let outside = self.layouts.dependent_layouts(&constraint).iter()
+                .all(|layout_id| *layout_id == id || !self.frozen_members.contains_key(layout_id));
+            if outside && !self.solver.has_constraint(&constraint) {
+                self.add_constraint(constraint);
+            }
+        }
+        let vars = self.layouts.layouts[&id].vars;
+        let constraints: Vec<Constraint> = cached.values.iter()
+            .filter(|&&(layout_id, _, _, _)| layout_id == id)
+            .filter_map(|&(_, var, var_type, value)| match var_type {
+                VarType::Width | VarType::Height => Some(var | EQ(STRONG) | value),
+                _ => None,
+            }).collect();
+        for constraint in &constraints {
+            self.add_constraint(constraint.clone());
+        }
+        let frozen = FrozenLayout { parent: parent, layout: cached, constraints: constraints };
+        if same_size(frozen.size(), (self.solver.get_value(vars.width), self.solver.get_value(vars.height))) {
+            self.report_frozen(id, &frozen);
+            self.frozen.insert(id, frozen);
+        } else {
+            // the root is constrained to another size, the subtree needs solving
+            self.frozen.insert(id, frozen);
+            self.thaw_layout(id);
+        }
+        true
+    }
+    /// Queue the cached values of a frozen subtree, relative to the root which is in the solver
+    fn report_frozen(&mut self, id: LayoutId, frozen: &FrozenLayout) {
+        let (left, top) = self.origin(id);
+        for &(layout_id, _, var_type, value) in &frozen.layout.values {
+            if layout_id != id {
+                let value = value + match var_type {
+                    VarType::Left | VarType::Right => left,
+                    VarType::Top | VarType::Bottom => top,
+                    _ => 0.0,
+                };
+                self.pending_changes.push((layout_id, var_type, value));
+            }
+        }
+    }
+    /// Put the constraints of a frozen subtree back in the solver
+    fn thaw_layout(&mut self, id: LayoutId) {
+        self.unfreeze_layout(id, true);
+    }
+    fn unfreeze_layout(&mut self, id: LayoutId, restore: bool) {
+        if let Some(frozen) = self.frozen.remove(&id) {
+            for constraint in &frozen.constraints {
+                self.remove_constraint(constraint);
+            }
+            let layouts: Vec<LayoutId> = self.frozen_members.iter()
+                .filter(|&(_, root)| *root == id)
+                .map(|(layout_id, _)| *layout_id)
+                .collect();
+            for layout_id in &layouts {
+                self.frozen_members.remove(layout_id);
+            }
+            for layout_id in layouts {
+                if restore && self.layouts.layouts.contains_key(&layout_id) {
+                    self.restore_constraints(layout_id);
+                }
+            }
         }
     }
     pub fn update_solver<F>(&mut self, f: F)
@@ -167,12 +479,44 @@ impl LimnSolver {
     }
 
     pub fn fetch_changes(&mut self) -> Vec<(LayoutId, VarType, f64)> {
-        let mut changes = Vec::new();
-        for &(var, val) in self.solver.fetch_changes() {
+        let mut solver_changes = self.solver.fetch_changes().to_vec();
+        if !self.frozen.is_empty() {
+            // subtrees whose size or available size changed are solved again, moved ones are moved
+            let mut resized = Vec::new();
+            let mut moved = Vec::new();
+            for (&id, frozen) in &self.frozen {
+                let (vars, parent_vars) = match (self.layouts.layouts.get(&id), self.layouts.layouts.get(&frozen.parent)) {
+                    (Some(layout), Some(parent)) => (layout.vars, parent.vars),
+                    _ => continue,
+                };
+                let available = (self.solver.get_value(parent_vars.width), self.solver.get_value(parent_vars.height));
+                let size = (self.solver.get_value(vars.width), self.solver.get_value(vars.height));
+                if !same_size(available, frozen.layout.available) || !same_size(size, frozen.size()) {
+                    resized.push(id);
+                } else if solver_changes.iter().any(|&(var, _)| var == vars.left || var == vars.top) {
+                    moved.push(id);
+                }
+            }
+            for id in resized {
+                self.thaw_layout(id);
+            }
+            for id in moved {
+                let frozen = self.frozen.remove(&id).unwrap();
+                self.report_frozen(id, &frozen);
+                self.frozen.insert(id, frozen);
+            }
+            solver_changes.extend_from_slice(self.solver.fetch_changes());
+        }
+        let mut changes = mem::replace(&mut self.pending_changes, Vec::new());
+        for (var, val) in solver_changes {
             debug!("solver {} = {}", self.layouts.fmt_variable(var), val);
-            if let Some(layout_id) = self.layouts.var_ids.get(&var) {
+            if let Some(&layout_id) = self.layouts.var_ids.get(&var) {
+                if self.frozen_members.get(&layout_id).map_or(false, |root| *root != layout_id) {
+                    // not in the solver, the value comes from the cache
+                    continue;
+                }
                 let var_type = self.layouts.layouts[&layout_id].vars.var_type(var);
-                changes.push((*layout_id, var_type, val));
+                changes.push((layout_id, var_type, val));
             }
         }
         changes
@@ -206,6 +550,11 @@ impl LimnSolver {
         }
     }
 
+    /// The name given to a constraint with `ConstraintBuilder::named`
+    pub fn constraint_name(&self, constraint: &Constraint) -> Option<&str> {
+        self.layouts.constraint_name(constraint)
+    }
+
     pub fn debug_constraint(&self, constraint: &Constraint) {
         println!("{}", self.layouts.fmt_constraint(constraint));
     }
@@ -272,6 +621,7 @@ struct LayoutInternal {
     edit_vars: HashMap<Variable, EditVariable>,
     constraints: HashSet<Constraint>,
     children: Vec<LayoutId>,
+    parent: Option<LayoutId>,
     hidden: bool,
     hidden_constraints: Vec<Constraint>,
 }
@@ -283,6 +633,8 @@ pub struct LayoutManager {
 
     pending_constraints: HashMap<Variable, Vec<Constraint>>,
     missing_vars: HashMap<Constraint, usize>,
+    /// Names given to constraints with `ConstraintBuilder::named`
+    constraint_names: HashMap<Constraint, String>,
 }
 
 impl LayoutManager {
@@ -295,6 +647,7 @@ impl LayoutManager {
             constraints: HashMap::new(),
             pending_constraints: HashMap::new(),
             missing_vars: HashMap::new(),
+            constraint_names: HashMap::new(),
         }
     }
 
@@ -311,6 +664,7 @@ impl LayoutManager {
             edit_vars: HashMap::new(),
             constraints: HashSet::new(),
             children: layout.children.clone(),
+            parent: layout.parent,
             hidden: false,
             hidden_constraints: Vec::new(),
         };
@@ -323,7 +677,10 @@ impl LayoutManager {
             self.var_ids.insert(var, layout.id);
             internal_layout.associated_vars.insert(var, name);
         }
+        self.constraint_names.extend(layout.get_constraint_names());
         internal_layout.name = layout.name.clone();
+        internal_layout.children = layout.children.clone();
+        internal_layout.parent = layout.parent;
     }
 
     pub fn add_constraint(&mut self, constraint: &Constraint) -> bool {
@@ -342,6 +699,7 @@ impl LayoutManager {
     }
 
     pub fn remove_constraint(&mut self, constraint: &Constraint) {
+        self.constraint_names.remove(constraint);
         for term in &constraint.expr().terms {
             self.constraints.entry(term.variable).or_insert_with(HashSet::new).remove(constraint);
             if self.var_ids.contains_key(&term.variable) {
@@ -404,8 +762,9 @@ impl LayoutManager {
         constraint.expr().terms.iter().map(|term| self.var_ids[&term.variable]).collect()
     }
 
+    /// Children registered with the solver, directly placed children may not be
     pub fn children(&self, id: LayoutId) -> Vec<LayoutId> {
-        self.layouts[&id].children.clone()
+        self.layouts[&id].children.iter().cloned().filter(|child| self.layouts.contains_key(child)).collect()
     }
     pub fn layout_vars(&self, id: LayoutId) -> Vec<Variable> {
         let layout = &self.layouts[&id];
@@ -429,8 +788,17 @@ impl LayoutManager {
         format!("{} {} == {}", strength_desc(edit_var.strength), self.fmt_variable(edit_var.var), edit_var.val)
     }
 
+    /// The name given to a constraint with `ConstraintBuilder::named`
+    pub fn constraint_name(&self, constraint: &Constraint) -> Option<&str> {
+        self.constraint_names.get(constraint).map(|name| name.as_str())
+    }
+
     pub fn fmt_constraint(&self, constraint: &Constraint) -> String {
-        format!("{} {}", strength_desc(constraint.strength()), self.fmt_expression(&constraint.expr(), constraint.op()))
+        let expression = self.fmt_expression(&constraint.expr(), constraint.op());
+        match self.constraint_name(constraint) {
+            Some(name) => format!("{} {} ({})", strength_desc(constraint.strength()), name, expression),
+            None => format!("{} {}", strength_desc(constraint.strength()), expression),
+        }
     }
 
     fn fmt_expression(&self, expression: &Expression, op: cassowary::RelationalOperator) -> String {
@@ -473,15 +841,20 @@ impl LayoutManager {
     }
 }
 
-/// Creates a printable string value for a given strength
-fn strength_desc(strength: f64) -> &'static str {
-    if strength < WEAK { "WEAK-" }
-    else if strength == WEAK { "WEAK " }
-    else if strength < MEDIUM { "WEAK+" }
-    else if strength == MEDIUM { "MED  " }
-    else if strength < STRONG { "MED+ " }
-    else if strength == STRONG { "STR  " }
-    else if strength < REQUIRED { "STR+ " }
-    else if strength == REQUIRED { "REQD " }
-    else { "REQD+" }
+/// Creates a printable string value for a given strength, including the tier of strengths
+/// created with `Strength::tier`
+fn strength_desc(strength: f64) -> String {
+    let tier = |level: f64| {
+        let tier = strength / level - 1.0;
+        if tier.fract() == 0.0 { tier.to_string() } else { String::new() }
+    };
+    if strength < WEAK { "WEAK-".to_owned() }
+    else if strength == WEAK { "WEAK ".to_owned() }
+    else if strength < MEDIUM { format!("WEAK+{}", tier(WEAK)) }
+    else if strength == MEDIUM { "MED  ".to_owned() }
+    else if strength < STRONG { format!("MED+ {}", tier(MEDIUM)) }
+    else if strength == STRONG { "STR  ".to_owned() }
+    else if strength < REQUIRED { format!("STR+ {}", tier(STRONG)) }
+    else if strength == REQUIRED { "REQD ".to_owned() }
+    else { "REQD+".to_owned() }
 }
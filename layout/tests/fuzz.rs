@@ -0,0 +1,221 @@
+//! Property tests that build random layout trees with random constraints, then apply random
+//! sequences of resizes, visibility changes and new constraints, checking that the solver never
+//! panics and always produces sane layouts.
+//! The number of cases can be raised with the `PROPTEST_CASES` environment variable.
+
+extern crate cassowary;
+extern crate limn_layout as layout;
+#[macro_use]
+extern crate proptest;
+
+mod util;
+
+use cassowary::strength::*;
+use proptest::prelude::*;
+
+use layout::{Point, Rect};
+use layout::constraint::*;
+
+use util::{TestLayout, SharedLayout};
+
+/// Allowed rounding error when comparing bounds
+const EPSILON: f32 = 0.5;
+
+/// A constraint relative to the widget itself, or another widget in the tree, by index.
+/// Never required, so any combination is satisfiable alongside the parent bounds.
+#[derive(Debug, Clone)]
+enum Rule {
+    Width(f32),
+    Height(f32),
+    MinWidth(f32),
+    MinHeight(f32),
+    AspectRatio(f32),
+    Shrink,
+    AlignLeft(usize, f32),
+    AlignTop(usize, f32),
+    Below(usize, f32),
+    ToRightOf(usize, f32),
+    MatchWidth(usize),
+    MatchHeight(usize),
+    Center(usize),
+}
+
+#[derive(Debug, Clone)]
+enum Action {
+    Resize(f32, f32),
+    Hide(usize),
+    Show(usize),
+    AddRule(usize, Rule, f64),
+}
+
+/// Each widget is a child of an earlier widget, chosen by the parent seed, widget 0 is the root
+#[derive(Debug, Clone)]
+struct Tree {
+    parent_seeds: Vec<usize>,
+    rules: Vec<(usize, Rule, f64)>,
+}
+
+fn length() -> BoxedStrategy<f32> {
+    prop_oneof![Just(0.0), -50.0f32..50.0, 0.0f32..2000.0].boxed()
+}
+
+fn strength() -> BoxedStrategy<f64> {
+    prop_oneof![Just(STRONG), Just(MEDIUM), Just(WEAK)].boxed()
+}
+
+fn rule() -> BoxedStrategy<Rule> {
+    prop_oneof![
+        length().prop_map(Rule::Width),
+        length().prop_map(Rule::Height),
+        length().prop_map(Rule::MinWidth),
+        length().prop_map(Rule::MinHeight),
+        (0.1f32..10.0).prop_map(Rule::AspectRatio),
+        Just(Rule::Shrink),
+        (any::<usize>(), length()).prop_map(|(other, padding)| Rule::AlignLeft(other, padding)),
+        (any::<usize>(), length()).prop_map(|(other, padding)| Rule::AlignTop(other, padding)),
+        (any::<usize>(), length()).prop_map(|(other, padding)| Rule::Below(other, padding)),
+        (any::<usize>(), length()).prop_map(|(other, padding)| Rule::ToRightOf(other, padding)),
+        any::<usize>().prop_map(Rule::MatchWidth),
+        any::<usize>().prop_map(Rule::MatchHeight),
+        any::<usize>().prop_map(Rule::Center),
+    ].boxed()
+}
+
+fn arb_tree() -> BoxedStrategy<Tree> {
+    (prop::collection::vec(any::<usize>(), 0..20),
+     prop::collection::vec((any::<usize>(), rule(), strength()), 0..40))
+        .prop_map(|(parent_seeds, rules)| Tree { parent_seeds: parent_seeds, rules: rules })
+        .boxed()
+}
+
+fn arb_action() -> BoxedStrategy<Action> {
+    prop_oneof![
+        (0.0f32..3000.0, 0.0f32..3000.0).prop_map(|(width, height)| Action::Resize(width, height)),
+        any::<usize>().prop_map(Action::Hide),
+        any::<usize>().prop_map(Action::Show),
+        (any::<usize>(), rule(), strength()).prop_map(|(widget, rule, strength)| Action::AddRule(widget, rule, strength)),
+    ].boxed()
+}
+
+struct Fuzz {
+    layout: TestLayout,
+    widgets: Vec<SharedLayout>,
+    parents: Vec<Option<usize>>,
+}
+
+impl Fuzz {
+    fn new(tree: &Tree) -> Self {
+        let mut layout = TestLayout::new();
+        // the solver used by the ui skips unsatisfiable constraints rather than panicking
+        layout.solver.strict = false;
+        let mut root = layout.new_widget("root");
+        root.add(top_left(Point::new(0.0, 0.0)));
+        root.edit_right().set(800.0).strength(REQUIRED - 1.0);
+        root.edit_bottom().set(600.0).strength(REQUIRED - 1.0);
+        let mut widgets = vec![root.clone()];
+        let mut parents = vec![None];
+        for (index, seed) in tree.parent_seeds.iter().enumerate() {
+            let parent = seed % (index + 1);
+            let mut child = layout.new_widget(&format!("widget_{}", index + 1));
+            widgets[parent].add_child(&mut *child);
+            widgets.push(child);
+            parents.push(Some(parent));
+        }
+        layout.add_root(root);
+        let mut fuzz = Fuzz {
+            layout: layout,
+            widgets: widgets,
+            parents: parents,
+        };
+        for &(widget, ref rule, strength) in &tree.rules {
+            fuzz.add_rule(widget, rule, strength);
+        }
+        fuzz
+    }
+
+    fn add_rule(&mut self, widget: usize, rule: &Rule, strength: f64) {
+        let count = self.widgets.len();
+        let builder: Box<ConstraintBuilder> = {
+            let other = |index: usize| self.widgets[index % count].clone();
+            match *rule {
+                Rule::Width(value) => Box::new(width(value).strength(strength)),
+                Rule::Height(value) => Box::new(height(value).strength(strength)),
+                Rule::MinWidth(value) => Box::new(min_width(value).strength(strength)),
+                Rule::MinHeight(value) => Box::new(min_height(value).strength(strength)),
+                Rule::AspectRatio(ratio) => Box::new(aspect_ratio(ratio).strength(strength)),
+                Rule::Shrink => Box::new(shrink().strength(strength)),
+                Rule::AlignLeft(index, padding) => Box::new(align_left(&other(index)).padding(padding).strength(strength)),
+                Rule::AlignTop(index, padding) => Box::new(align_top(&other(index)).padding(padding).strength(strength)),
+                Rule::Below(index, padding) => Box::new(below(&other(index)).padding(padding).strength(strength)),
+                Rule::ToRightOf(index, padding) => Box::new(to_right_of(&other(index)).padding(padding).strength(strength)),
+                Rule::MatchWidth(index) => Box::new(match_width(&other(index)).strength(strength)),
+                Rule::MatchHeight(index) => Box::new(match_height(&other(index)).strength(strength)),
+                Rule::Center(index) => Box::new(center(&other(index)).strength(strength)),
+            }
+        };
+        self.widgets[widget % count].add(vec![builder]);
+    }
+
+    fn apply(&mut self, action: &Action) {
+        let count = self.widgets.len();
+        match *action {
+            Action::Resize(width, height) => {
+                let root = &mut self.widgets[0];
+                root.edit_right().set(width).strength(REQUIRED - 1.0);
+                root.edit_bottom().set(height).strength(REQUIRED - 1.0);
+            }
+            Action::Hide(widget) => self.widgets[widget % count].hide(),
+            Action::Show(widget) => self.widgets[widget % count].show(),
+            Action::AddRule(widget, ref rule, strength) => self.add_rule(widget, rule, strength),
+        }
+    }
+
+    fn visible(&self, index: usize) -> bool {
+        !self.widgets[index].hidden && self.parents[index].map_or(true, |parent| self.visible(parent))
+    }
+
+    fn check(&self) -> Result<(), TestCaseError> {
+        for (id, rect) in &self.layout.layout_rects {
+            prop_assert!(rect.origin.x.is_finite() && rect.origin.y.is_finite()
+                && rect.size.width.is_finite() && rect.size.height.is_finite(),
+                "widget {} has bounds {:?}", id, rect);
+        }
+        for (index, parent) in self.parents.iter().enumerate() {
+            let parent = match *parent {
+                Some(parent) => parent,
+                None => continue,
+            };
+            if !self.visible(index) {
+                continue;
+            }
+            let bounds = self.bounds(index);
+            let parent_bounds = self.bounds(parent);
+            prop_assert!(bounds.size.width >= -EPSILON && bounds.size.height >= -EPSILON,
+                "widget {} has negative size {:?}", index, bounds);
+            prop_assert!(bounds.origin.x >= parent_bounds.origin.x - EPSILON
+                && bounds.origin.y >= parent_bounds.origin.y - EPSILON
+                && bounds.max_x() <= parent_bounds.max_x() + EPSILON
+                && bounds.max_y() <= parent_bounds.max_y() + EPSILON,
+                "widget {} at {:?} is outside its parent at {:?}", index, bounds, parent_bounds);
+        }
+        Ok(())
+    }
+
+    fn bounds(&self, index: usize) -> Rect {
+        self.layout.layout_rects.get(&self.widgets[index].id).cloned().unwrap_or(Rect::zero())
+    }
+}
+
+proptest! {
+    #[test]
+    fn random_layouts(ref tree in arb_tree(), ref actions in prop::collection::vec(arb_action(), 0..20)) {
+        let mut fuzz = Fuzz::new(tree);
+        fuzz.layout.update();
+        fuzz.check()?;
+        for action in actions {
+            fuzz.apply(action);
+            fuzz.layout.update();
+            fuzz.check()?;
+        }
+    }
+}
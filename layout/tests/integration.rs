@@ -4,16 +4,16 @@ extern crate limn_layout as layout;
 #[macro_use]
 extern crate maplit;
 
-use std::rc::Rc;
-use std::cell::RefCell;
-use std::collections::HashMap;
-use std::ops::{Deref, DerefMut};
+mod util;
 
 use cassowary::strength::*;
+use cassowary::WeightedRelation::*;
 
-use layout::{LimnSolver, LayoutId, Layout, VarType, LayoutRef, LayoutVars};
-use layout::{Size, Point, Rect};
+use layout::{Size, Point, Rect, LayoutRef};
 use layout::constraint::*;
+use layout::guide::{Guideline, GuidePosition, Anchor};
+
+use util::TestLayout;
 
 #[test]
 fn one_widget() {
@@ -381,117 +381,317 @@ fn linear_layout_remove() {
     }));
 }
 
-#[derive(Clone)]
-struct SharedLayout(Rc<RefCell<Layout>>);
-impl SharedLayout {
-    fn new(layout: Layout) -> Self {
-        SharedLayout(Rc::new(RefCell::new(layout)))
-    }
+#[test]
+fn arrange_remove() {
+    use layout::arrange::{ArrangeContainer, ChildSlot};
+    use layout::constraint::ConstraintBuilder;
+
+    let mut layout = TestLayout::new();
+
+    let mut root = layout.new_widget("root");
+    let mut item_1 = layout.new_widget("item_1");
+    let mut item_2 = layout.new_widget("item_2");
+    let mut item_3 = layout.new_widget("item_3");
+
+    root.add(constraints![
+        top_left(Point::new(0.0, 0.0)),
+        size(Size::new(100.0, 100.0))
+    ]);
+    item_1.add(height(20.0));
+    item_2.add(height(20.0));
+    item_3.add(height(20.0));
+    let column = |slot: &ChildSlot| {
+        let mut constraints = constraints![
+            align_left(&slot.parent),
+            align_right(&slot.parent),
+        ];
+        if let Some(ref prev) = slot.prev {
+            constraints.push(Box::new(align_below(prev)));
+        } else {
+            constraints.push(Box::new(align_top(&slot.parent)));
+        }
+        constraints
+    };
+    root.set_container(ArrangeContainer::new(column));
+
+    root.add_child(&mut *item_1);
+    root.add_child(&mut *item_2);
+    root.add_child(&mut *item_3);
+
+    root.remove_child(&mut *item_2);
+
+    layout.add_root(root.clone());
+    layout.update();
+    assert!(layout.match_layouts(hashmap!{
+        root.id => Rect::new(Point::new(0.0, 0.0), Size::new(100.0, 100.0)),
+        item_1.id => Rect::new(Point::new(0.0, 0.0), Size::new(100.0, 20.0)),
+        item_3.id => Rect::new(Point::new(0.0, 20.0), Size::new(100.0, 20.0)),
+    }));
 }
-impl LayoutRef for SharedLayout {
-    fn layout_ref(&self) -> LayoutVars {
-        self.0.borrow().vars.clone()
-    }
+
+#[test]
+fn direct_stack() {
+    use layout::direct::{DirectLayout, DirectStack};
+    use layout::linear_layout::Orientation;
+
+    let mut stack = DirectStack::new(Orientation::Horizontal);
+    stack.set_spacing(10.0).set_weights(vec![1.0, 2.0]);
+    let bounds = Rect::new(Point::new(5.0, 5.0), Size::new(130.0, 40.0));
+    assert!(stack.arrange(bounds, 3) == vec![
+        Rect::new(Point::new(5.0, 5.0), Size::new(27.5, 40.0)),
+        Rect::new(Point::new(42.5, 5.0), Size::new(55.0, 40.0)),
+        Rect::new(Point::new(107.5, 5.0), Size::new(27.5, 40.0)),
+    ]);
 }
-impl <'a> Deref for SharedLayout {
-    type Target = Layout;
-    #[inline]
-    fn deref(&self) -> &Layout {
-        unsafe {self.0.as_ptr().as_ref().unwrap()}
-    }
+
+#[test]
+fn direct_flip_stack() {
+    use layout::direct::{DirectLayout, DirectFlipStack};
+
+    let mut stack = DirectFlipStack::new();
+    stack.set_threshold(1.5);
+    let wide = Rect::new(Point::new(0.0, 0.0), Size::new(200.0, 100.0));
+    assert!(stack.arrange(wide, 2) == vec![
+        Rect::new(Point::new(0.0, 0.0), Size::new(100.0, 100.0)),
+        Rect::new(Point::new(100.0, 0.0), Size::new(100.0, 100.0)),
+    ]);
+    let square = Rect::new(Point::new(0.0, 0.0), Size::new(100.0, 100.0));
+    assert!(stack.arrange(square, 2) == vec![
+        Rect::new(Point::new(0.0, 0.0), Size::new(100.0, 50.0)),
+        Rect::new(Point::new(0.0, 50.0), Size::new(100.0, 50.0)),
+    ]);
 }
-impl <'a> DerefMut for SharedLayout
-{   #[inline]
-    fn deref_mut(&mut self) -> &mut Layout {
-        unsafe {self.0.as_ptr().as_mut().unwrap()}
-    }
+
+#[test]
+fn direct_children_bypass_solver() {
+    let mut layout = TestLayout::new();
+
+    let mut root = layout.new_widget("root");
+    let mut leaf = layout.new_widget("leaf");
+    let mut constrained = layout.new_widget("constrained");
+
+    root.add(constraints![
+        top_left(Point::new(0.0, 0.0)),
+        size(Size::new(100.0, 100.0))
+    ]);
+    constrained.add(width(20.0));
+    root.set_direct_container();
+    root.add_child(&mut *leaf);
+    root.add_child(&mut *constrained);
+    assert!(leaf.bypasses_solver());
+    assert!(!constrained.bypasses_solver());
+
+    layout.add_root(root.clone());
+    layout.update();
+    assert!(!layout.layout_rects.contains_key(&leaf.id));
+    assert!(layout.match_layouts(hashmap!{
+        root.id => Rect::new(Point::new(0.0, 0.0), Size::new(100.0, 100.0)),
+        constrained.id => Rect::new(Point::new(0.0, 0.0), Size::new(20.0, 0.0)),
+    }));
 }
 
-// code below is used to create a test harness for creating layouts outside of the widget graph
-struct TestLayout {
-    id_gen: IdGen,
-    solver: LimnSolver,
-    layout_rects: HashMap<LayoutId, Rect>,
-    layouts: HashMap<LayoutId, SharedLayout>,
-    roots: Vec<SharedLayout>,
+#[test]
+fn cached_layout_shown_again() {
+    let mut layout = TestLayout::new();
+
+    let mut root = layout.new_widget("root");
+    let mut panel = layout.new_widget("panel");
+    let mut item = layout.new_widget("item");
+
+    root.add(constraints![
+        top_left(Point::new(0.0, 0.0)),
+        size(Size::new(100.0, 100.0))
+    ]);
+    panel.add(match_layout(&root));
+    let item_size = item.create_constraint(size(Size::new(20.0, 20.0)));
+    item.add(item_size.clone());
+    item.add(center(&panel));
+    root.add_child(&mut *panel);
+    panel.add_child(&mut *item);
+
+    layout.add_root(root.clone());
+    layout.update();
+    panel.hide();
+    layout.update();
+    assert!(layout.match_layouts(hashmap!{
+        item.id => Rect::new(Point::new(0.0, 0.0), Size::new(0.0, 0.0)),
+    }));
+    panel.show();
+    layout.update();
+    // shown from the cache, without solving the subtree again
+    assert!(!layout.solver.has_constraint(&item_size[0]));
+    assert!(layout.match_layouts(hashmap!{
+        panel.id => Rect::new(Point::new(0.0, 0.0), Size::new(100.0, 100.0)),
+        item.id => Rect::new(Point::new(40.0, 40.0), Size::new(20.0, 20.0)),
+    }));
+
+    // changing the subtree solves it again
+    item.add(width(30.0).strength(STRONG));
+    layout.update();
+    assert!(layout.solver.has_constraint(&item_size[0]));
+    assert!(layout.match_layouts(hashmap!{
+        item.id => Rect::new(Point::new(40.0, 40.0), Size::new(20.0, 20.0)),
+    }));
 }
-impl TestLayout {
-    fn new() -> Self {
-        let mut solver = LimnSolver::new();
-        solver.strict = true;
-        TestLayout {
-            id_gen: IdGen::new(),
-            solver: solver,
-            layout_rects: HashMap::new(),
-            layouts: HashMap::new(),
-            roots: Vec::new(),
-        }
-    }
-    fn new_widget(&mut self, name: &str) -> SharedLayout {
-        let id = self.id_gen.next();
-        let layout = Layout::new(id, Some(name.to_owned()));
-        let layout = SharedLayout::new(layout);
-        self.layouts.insert(id, layout.clone());
-        layout
-    }
-    fn add_root(&mut self, layout: SharedLayout) {
-        self.roots.push(layout);
-    }
-    fn update_layout(&mut self, mut layout: SharedLayout) {
-        self.solver.update_layout(layout.deref_mut());
-        for child in layout.get_children() {
-            let layout = self.layouts[child].clone();
-            self.update_layout(layout);
-        }
-    }
-    fn update(&mut self) {
-        for layout in self.roots.clone() {
-            self.update_layout(layout);
-        }
-        for (id, var, value) in self.solver.fetch_changes() {
-            let rect = self.layout_rects.entry(id).or_insert(Rect::zero());
-            match var {
-                VarType::Left => rect.origin.x = value as f32,
-                VarType::Top => rect.origin.y = value as f32,
-                VarType::Width => rect.size.width = value as f32,
-                VarType::Height => rect.size.height = value as f32,
-                _ => (),
-            }
-        }
-    }
-    fn match_layouts(&self, layouts: HashMap<LayoutId, Rect>) -> bool {
-        for (match_layout_id, match_layout_rect) in layouts {
-            let layout_rect = self.layout_rects[&match_layout_id];
-            if layout_rect != match_layout_rect {
-                self.solver.debug_layouts();
-                return false;
-            }
-        }
-        true
-    }
+
+#[test]
+fn cached_layout_parent_removed() {
+    let mut layout = TestLayout::new();
+
+    let mut root = layout.new_widget("root");
+    let mut panel = layout.new_widget("panel");
+    let mut item = layout.new_widget("item");
+
+    root.add(constraints![
+        top_left(Point::new(0.0, 0.0)),
+        size(Size::new(100.0, 100.0))
+    ]);
+    panel.add(constraints![
+        top_left(Point::new(10.0, 10.0)),
+        size(Size::new(60.0, 60.0))
+    ]);
+    let item_size = item.create_constraint(size(Size::new(20.0, 20.0)));
+    item.add(item_size.clone());
+    item.add(center(&panel));
+    root.add_child(&mut *panel);
+    panel.add_child(&mut *item);
+
+    layout.add_root(root.clone());
+    layout.update();
+    panel.hide();
+    layout.update();
+    panel.show();
+    layout.update();
+    assert!(!layout.solver.has_constraint(&item_size[0]));
+
+    // the subtree is shown from the cache, removing the parent it was cached for solves it again
+    layout.solver.remove_layout(root.id);
+    assert!(layout.solver.has_constraint(&item_size[0]));
+    layout.fetch_changes();
+    assert!(layout.match_layouts(hashmap!{
+        panel.id => Rect::new(Point::new(10.0, 10.0), Size::new(60.0, 60.0)),
+        item.id => Rect::new(Point::new(30.0, 30.0), Size::new(20.0, 20.0)),
+    }));
+
+    layout.solver.remove_layout(panel.id);
+    layout.solver.remove_layout(item.id);
+    assert!(!layout.solver.has_constraint(&item_size[0]));
+    layout.fetch_changes();
 }
 
-#[derive(Debug, Copy, Clone, PartialEq, Eq)]
-struct IdGen {
-    id: usize,
+#[test]
+fn strength_tiers() {
+    let mut layout = TestLayout::new();
+
+    let mut widget = layout.new_widget("widget");
+    let preferred_width = widget.vars.width | EQ(Strength::Weak.tier(2)) | 200.0;
+    widget.add(constraints![
+        top_left(Point::new(0.0, 0.0)),
+        height(100.0),
+        width(50.0).strength(Strength::Weak),
+        width(100.0).strength(Strength::Weak.tier(1)),
+        preferred_width.clone().named("preferred_width"),
+    ]);
+    assert!(Strength::Weak.tier(MAX_STRENGTH_TIER) < MEDIUM);
+    assert!(Strength::Strong.tier(MAX_STRENGTH_TIER) < REQUIRED);
+
+    layout.add_root(widget.clone());
+    layout.update();
+    assert_eq!(layout.solver.constraint_name(&preferred_width), Some("preferred_width"));
+    assert!(layout.layout_rects == hashmap!{
+        widget.id => Rect::new(Point::new(0.0, 0.0), Size::new(200.0, 100.0)),
+    });
 }
 
-impl Default for IdGen {
-    fn default() -> Self {
-        IdGen {
-            id: 0,
-        }
-    }
+#[test]
+fn guides() {
+    let mut layout = TestLayout::new();
+
+    let mut root = layout.new_widget("root");
+    let mut sidebar = layout.new_widget("sidebar");
+    let mut content = layout.new_widget("content");
+    let mut badge = layout.new_widget("badge");
+    root.add(constraints![
+        top_left(Point::new(0.0, 0.0)),
+        size(Size::new(200.0, 100.0))
+    ]);
+    let split = Guideline::vertical(GuidePosition::Fraction(0.3));
+    split.attach(&mut *root, "split");
+    let corner = Anchor::new(GuidePosition::FromEnd(10.0), GuidePosition::FromEnd(10.0));
+    corner.attach(&mut *root, "corner");
+    sidebar.add(constraints![
+        align_left(&root),
+        split.align_right(),
+        align_top(&root),
+        align_bottom(&root),
+    ]);
+    content.add(constraints![
+        split.align_left(),
+        align_right(&root),
+        align_top(&root),
+        align_bottom(&root),
+    ]);
+    badge.add(constraints![
+        size(Size::new(20.0, 20.0)),
+        corner.bottom_right(),
+    ]);
+    root.add_child(&mut *sidebar);
+    root.add_child(&mut *content);
+    root.add_child(&mut *badge);
+
+    layout.add_root(root.clone());
+    layout.update();
+    assert!(layout.match_layouts(hashmap!{
+        sidebar.id => Rect::new(Point::new(0.0, 0.0), Size::new(60.0, 100.0)),
+        content.id => Rect::new(Point::new(60.0, 0.0), Size::new(140.0, 100.0)),
+        badge.id => Rect::new(Point::new(170.0, 70.0), Size::new(20.0, 20.0)),
+    }));
 }
-impl IdGen {
 
-    fn new() -> Self {
-        Self::default()
-    }
+#[test]
+fn linear_layout_spacers() {
+    use layout::linear_layout::{LinearLayout, LinearLayoutSettings, Orientation, ItemAlignment, AxisSize};
+
+    let mut layout = TestLayout::new();
+
+    let mut root = layout.new_widget("root");
+    let mut item_1 = layout.new_widget("item_1");
+    let mut fixed = layout.new_widget("fixed");
+    let mut item_2 = layout.new_widget("item_2");
+    let mut flex_1 = layout.new_widget("flex_1");
+    let mut item_3 = layout.new_widget("item_3");
+    let mut flex_2 = layout.new_widget("flex_2");
+
+    root.add(constraints![
+        top_left(Point::new(0.0, 0.0)),
+        size(Size::new(300.0, 10.0))
+    ]);
+    let mut settings = LinearLayoutSettings::new(Orientation::Horizontal);
+    settings.item_align = ItemAlignment::Fill;
+    let linear_layout = LinearLayout::new(&mut *root, settings);
+    root.set_container(linear_layout);
+
+    item_1.add(width(50.0));
+    fixed.set_axis_size(AxisSize::Fixed(10.0));
+    item_2.add(width(50.0));
+    flex_1.set_axis_size(AxisSize::Flex(1.0));
+    item_3.add(width(40.0));
+    flex_2.set_axis_size(AxisSize::Flex(2.0));
+    root.add_child(&mut *item_1);
+    root.add_child(&mut *fixed);
+    root.add_child(&mut *item_2);
+    root.add_child(&mut *flex_1);
+    root.add_child(&mut *item_3);
+    root.add_child(&mut *flex_2);
 
-    fn next(&mut self) -> LayoutId {
-        let next = self.id;
-        self.id += 1;
-        next
-    }
+    layout.add_root(root.clone());
+    layout.update();
+    assert!(layout.match_layouts(hashmap!{
+        item_1.id => Rect::new(Point::new(0.0, 0.0), Size::new(50.0, 10.0)),
+        fixed.id => Rect::new(Point::new(50.0, 0.0), Size::new(10.0, 10.0)),
+        item_2.id => Rect::new(Point::new(60.0, 0.0), Size::new(50.0, 10.0)),
+        flex_1.id => Rect::new(Point::new(110.0, 0.0), Size::new(50.0, 10.0)),
+        item_3.id => Rect::new(Point::new(160.0, 0.0), Size::new(40.0, 10.0)),
+        flex_2.id => Rect::new(Point::new(200.0, 0.0), Size::new(100.0, 10.0)),
+    }));
 }
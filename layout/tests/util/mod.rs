@@ -0,0 +1,131 @@
+//! Helpers for testing layouts outside of the widget graph, shared by the layout tests
+
+#![allow(dead_code)]
+
+use std::rc::Rc;
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::ops::{Deref, DerefMut};
+
+use layout::{LimnSolver, LayoutId, Layout, VarType, LayoutRef, LayoutVars};
+use layout::Rect;
+
+#[derive(Clone)]
+pub struct SharedLayout(Rc<RefCell<Layout>>);
+impl SharedLayout {
+    pub fn new(layout: Layout) -> Self {
+        SharedLayout(Rc::new(RefCell::new(layout)))
+    }
+}
+impl LayoutRef for SharedLayout {
+    fn layout_ref(&self) -> LayoutVars {
+        self.0.borrow().vars.clone()
+    }
+}
+impl <'a> Deref for SharedLayout {
+    type Target = Layout;
+    #[inline]
+    fn deref(&self) -> &Layout {
+        unsafe {self.0.as_ptr().as_ref().unwrap()}
+    }
+}
+impl <'a> DerefMut for SharedLayout
+{   #[inline]
+    fn deref_mut(&mut self) -> &mut Layout {
+        unsafe {self.0.as_ptr().as_mut().unwrap()}
+    }
+}
+
+// code below is used to create a test harness for creating layouts outside of the widget graph
+pub struct TestLayout {
+    id_gen: IdGen,
+    pub solver: LimnSolver,
+    pub layout_rects: HashMap<LayoutId, Rect>,
+    layouts: HashMap<LayoutId, SharedLayout>,
+    roots: Vec<SharedLayout>,
+}
+impl TestLayout {
+    pub fn new() -> Self {
+        let mut solver = LimnSolver::new();
+        solver.strict = true;
+        TestLayout {
+            id_gen: IdGen::new(),
+            solver: solver,
+            layout_rects: HashMap::new(),
+            layouts: HashMap::new(),
+            roots: Vec::new(),
+        }
+    }
+    pub fn new_widget(&mut self, name: &str) -> SharedLayout {
+        let id = self.id_gen.next();
+        let layout = Layout::new(id, Some(name.to_owned()));
+        let layout = SharedLayout::new(layout);
+        self.layouts.insert(id, layout.clone());
+        layout
+    }
+    pub fn add_root(&mut self, layout: SharedLayout) {
+        self.roots.push(layout);
+    }
+    fn update_layout(&mut self, mut layout: SharedLayout) {
+        self.solver.update_layout(layout.deref_mut());
+        for child in layout.get_children() {
+            let layout = self.layouts[child].clone();
+            self.update_layout(layout);
+        }
+    }
+    pub fn update(&mut self) {
+        for layout in self.roots.clone() {
+            self.update_layout(layout);
+        }
+        self.fetch_changes();
+    }
+    /// Apply the solver's changes to `layout_rects`, without updating the layouts first,
+    /// ex. after removing layouts from the solver directly
+    pub fn fetch_changes(&mut self) {
+        for (id, var, value) in self.solver.fetch_changes() {
+            let rect = self.layout_rects.entry(id).or_insert(Rect::zero());
+            match var {
+                VarType::Left => rect.origin.x = value as f32,
+                VarType::Top => rect.origin.y = value as f32,
+                VarType::Width => rect.size.width = value as f32,
+                VarType::Height => rect.size.height = value as f32,
+                _ => (),
+            }
+        }
+    }
+    pub fn match_layouts(&self, layouts: HashMap<LayoutId, Rect>) -> bool {
+        for (match_layout_id, match_layout_rect) in layouts {
+            let layout_rect = self.layout_rects[&match_layout_id];
+            if layout_rect != match_layout_rect {
+                self.solver.debug_layouts();
+                return false;
+            }
+        }
+        true
+    }
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+struct IdGen {
+    id: usize,
+}
+
+impl Default for IdGen {
+    fn default() -> Self {
+        IdGen {
+            id: 0,
+        }
+    }
+}
+impl IdGen {
+
+    fn new() -> Self {
+        Self::default()
+    }
+
+    fn next(&mut self) -> LayoutId {
+        let next = self.id;
+        self.id += 1;
+        next
+    }
+}
@@ -1,15 +0,0 @@
-//! Contains common `Draw` state, basic drawing primitives
-
-pub mod rect;
-pub mod ellipse;
-pub mod text;
-pub mod image;
-pub mod glcanvas;
-
-pub mod prelude {
-    pub use super::ellipse::{EllipseState, EllipseStyle};
-    pub use super::glcanvas::GLCanvasState;
-    pub use super::image::ImageState;
-    pub use super::rect::{RectState, RectStyle};
-    pub use super::text::{TextState, TextStyle};
-}
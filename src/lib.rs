@@ -1,54 +1,16 @@
 //! Limn is a cross platform, event driven, component based GUI library.
-
-// ---- START CLIPPY CONFIG
-
-#![cfg_attr(all(not(test), feature="clippy"), warn(result_unwrap_used))]
-#![cfg_attr(feature="clippy", warn(unseparated_literal_suffix))]
-#![cfg_attr(feature="clippy", warn(wrong_pub_self_convention))]
-
-// Enable clippy if our Cargo.toml file asked us to do so.
-#![cfg_attr(feature="clippy", feature(plugin))]
-#![cfg_attr(feature="clippy", plugin(clippy))]
-
-#![warn(trivial_numeric_casts,
-        trivial_casts,
-        unused_extern_crates,
-        unused_import_braces,
-        unused_qualifications)]
-#![cfg_attr(feature="clippy", warn(cast_possible_truncation))]
-#![cfg_attr(feature="clippy", warn(cast_possible_wrap))]
-#![cfg_attr(feature="clippy", warn(cast_precision_loss))]
-#![cfg_attr(feature="clippy", warn(cast_sign_loss))]
-#![cfg_attr(feature="clippy", warn(missing_docs_in_private_items))]
-#![cfg_attr(feature="clippy", warn(mut_mut))]
-
-// Disallow `println!`. Use `debug!` for debug output
-// (which is provided by the `log` crate).
-#![cfg_attr(feature="clippy", warn(print_stdout))]
-
-// This allows us to use `unwrap` on `Option` values (because doing makes
-// working with Regex matches much nicer) and when compiling in test mode
-// (because using it in tests is idiomatic).
-#![cfg_attr(all(not(test), feature="clippy"), warn(result_unwrap_used))]
-#![cfg_attr(feature="clippy", warn(unseparated_literal_suffix))]
-#![cfg_attr(feature="clippy", warn(wrong_pub_self_convention))]
-
-// ---- END CLIPPY CONFIG
-
-// uncomment to debug macros
-//#![feature(trace_macros)]
-//trace_macros!(true);
-
-#[macro_use]
-extern crate limn_core as core;
-
-pub use core::*;
-
-pub mod draw;
-pub mod widgets;
-
-pub mod prelude {
-    pub use core::prelude::*;
-    pub use draw::prelude::*;
-    pub use widgets::prelude::*;
-}
+//!
+//! This crate combines `limn-core`, the window, rendering, layout, style and event system, with the
+//! standard widgets from `limn-widgets`. Minimal embedders or alternative widget sets can depend on `limn-core` alone.
+//!
+//! Optional parts of the library are enabled with cargo features, to keep the build of a basic app small:
+//!
+//! - `charts`: line chart, histogram and box plot, sparkline, heatmap, waveform and level meter, timeline and gantt chart widgets
+//! - `maps`: slippy map tile view
+//! - `png`, `jpeg`, `gif`, `bmp`, `ico`, `tiff`, `webp`: image formats that can be loaded, `png` and `jpeg` are enabled by default
+//! - `global-hotkeys`: system-wide hotkeys, see `hotkeys`
+//! - `theme-preview`: development tool rendering widgets under every theme, see `theme_preview`
+
+extern crate limn_widgets;
+
+pub use limn_widgets::*;
@@ -0,0 +1,179 @@
+//! Property tests that build random trees of standard widgets, then send them random sequences of
+//! input through the input simulator, checking that handling the events never panics and the
+//! layout stays sane. These open a hidden window, so they're ignored without a display, run them
+//! with RUST_TEST_THREADS=1 cargo test -- --ignored.
+//! The number of cases can be raised with the `PROPTEST_CASES` environment variable.
+
+#[macro_use]
+extern crate limn;
+#[macro_use]
+extern crate proptest;
+
+mod util;
+
+use proptest::prelude::*;
+
+use limn::prelude::*;
+use limn::input::simulate::{NO_MODIFIERS, CTRL, SHIFT};
+
+use util::app;
+
+const WINDOW_SIZE: (f32, f32) = (800.0, 600.0);
+
+#[derive(Debug, Clone)]
+enum Kind {
+    Container,
+    ToggleButton,
+    EditText,
+    Slider,
+}
+
+/// A widget, the container it's in, by seed, and where in the container it prefers to be
+#[derive(Debug, Clone)]
+struct Node {
+    kind: Kind,
+    parent_seed: usize,
+    rect: (f32, f32, f32, f32),
+}
+
+#[derive(Debug, Clone)]
+enum Action {
+    MoveMouse(f32, f32),
+    Click(f32, f32),
+    ClickWidget(usize),
+    Drag(usize, f32, f32),
+    Scroll(usize, f32),
+    Type(String),
+    Press(usize, usize),
+}
+
+const KEYS: [glutin::VirtualKeyCode; 10] = [
+    glutin::VirtualKeyCode::Tab, glutin::VirtualKeyCode::Return, glutin::VirtualKeyCode::Back,
+    glutin::VirtualKeyCode::Escape, glutin::VirtualKeyCode::Left, glutin::VirtualKeyCode::Right,
+    glutin::VirtualKeyCode::Up, glutin::VirtualKeyCode::Down, glutin::VirtualKeyCode::A,
+    glutin::VirtualKeyCode::Key1,
+];
+const MODIFIERS: [glutin::ModifiersState; 3] = [NO_MODIFIERS, CTRL, SHIFT];
+
+fn arb_node() -> BoxedStrategy<Node> {
+    let kind = prop_oneof![Just(Kind::Container), Just(Kind::ToggleButton), Just(Kind::EditText), Just(Kind::Slider)];
+    (kind, any::<usize>(), (0.0f32..400.0, 0.0f32..300.0, 0.0f32..400.0, 0.0f32..300.0))
+        .prop_map(|(kind, parent_seed, rect)| Node { kind: kind, parent_seed: parent_seed, rect: rect })
+        .boxed()
+}
+
+fn coordinate() -> BoxedStrategy<f32> {
+    prop_oneof![-100.0f32..1000.0, Just(0.0), Just(WINDOW_SIZE.0)].boxed()
+}
+
+fn arb_action() -> BoxedStrategy<Action> {
+    prop_oneof![
+        (coordinate(), coordinate()).prop_map(|(x, y)| Action::MoveMouse(x, y)),
+        (coordinate(), coordinate()).prop_map(|(x, y)| Action::Click(x, y)),
+        any::<usize>().prop_map(Action::ClickWidget),
+        (any::<usize>(), coordinate(), coordinate()).prop_map(|(widget, x, y)| Action::Drag(widget, x, y)),
+        (any::<usize>(), -20.0f32..20.0).prop_map(|(widget, dy)| Action::Scroll(widget, dy)),
+        "[a-zA-Z0-9 \t\r\u{8}]{0,8}".prop_map(Action::Type),
+        (any::<usize>(), any::<usize>()).prop_map(|(key, modifiers)| Action::Press(key, modifiers)),
+    ].boxed()
+}
+
+struct Fuzz {
+    app: App,
+    widgets: Vec<Widget>,
+}
+
+impl Fuzz {
+    fn new(nodes: &[Node]) -> Self {
+        let mut app = app();
+        let mut root = Widget::new("root");
+        root.layout().add(size(Size::new(WINDOW_SIZE.0, WINDOW_SIZE.1)));
+        let mut widgets = vec![root.clone()];
+        let mut containers = vec![0];
+        for (index, node) in nodes.iter().enumerate() {
+            let mut widget = match node.kind {
+                Kind::Container => Widget::new("container"),
+                Kind::ToggleButton => {
+                    let mut button = ToggleButtonStyle::default();
+                    button.toggle_text("ON", "OFF");
+                    Widget::from_modifier_style(button)
+                }
+                Kind::EditText => Widget::from_modifier(EditText::default()),
+                Kind::Slider => Widget::from_modifier(Slider::default()),
+            };
+            let mut parent = widgets[containers[node.parent_seed % containers.len()]].clone();
+            let (left, top, preferred_width, preferred_height) = node.rect;
+            widget.layout().add(constraints![
+                bound_by(&parent),
+                align_left(&parent).padding(left).strength(WEAK),
+                align_top(&parent).padding(top).strength(WEAK),
+                width(preferred_width).strength(WEAK),
+                height(preferred_height).strength(WEAK),
+            ]);
+            parent.add_child(widget.clone());
+            if let Kind::Container = node.kind {
+                containers.push(index + 1);
+            }
+            widgets.push(widget);
+        }
+        app.capture(root, 1.0);
+        Fuzz { app: app, widgets: widgets }
+    }
+
+    fn widget(&self, index: usize) -> Widget {
+        self.widgets[index % self.widgets.len()].clone()
+    }
+
+    fn apply(&mut self, action: &Action) {
+        match *action {
+            Action::MoveMouse(x, y) => {
+                self.app.simulate().move_mouse(Point::new(x, y));
+            }
+            Action::Click(x, y) => {
+                self.app.simulate().move_mouse(Point::new(x, y)).click_here();
+            }
+            Action::ClickWidget(widget) => {
+                let widget = self.widget(widget);
+                self.app.simulate().click(&widget);
+            }
+            Action::Drag(widget, x, y) => {
+                let widget = self.widget(widget);
+                self.app.simulate().drag(&widget, Point::new(x, y));
+            }
+            Action::Scroll(widget, dy) => {
+                let widget = self.widget(widget);
+                self.app.simulate().scroll(&widget, dy);
+            }
+            Action::Type(ref text) => {
+                self.app.simulate().type_text(text);
+            }
+            Action::Press(key, modifiers) => {
+                self.app.simulate().press(KEYS[key % KEYS.len()], MODIFIERS[modifiers % MODIFIERS.len()]);
+            }
+        }
+    }
+
+    fn check(&self) -> Result<(), TestCaseError> {
+        for widget in &self.widgets {
+            let bounds = widget.bounds();
+            prop_assert!(bounds.origin.x.is_finite() && bounds.origin.y.is_finite()
+                && bounds.size.width.is_finite() && bounds.size.height.is_finite(),
+                "{} has bounds {:?}", widget.name(), bounds);
+        }
+        Ok(())
+    }
+}
+
+proptest! {
+    #![proptest_config(ProptestConfig::with_cases(32))]
+    #[test]
+    #[ignore]
+    fn random_input(ref nodes in prop::collection::vec(arb_node(), 0..12), ref actions in prop::collection::vec(arb_action(), 0..30)) {
+        let mut fuzz = Fuzz::new(nodes);
+        fuzz.check()?;
+        for action in actions {
+            fuzz.apply(action);
+            fuzz.check()?;
+        }
+    }
+}
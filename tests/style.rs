@@ -49,3 +49,47 @@ fn style_instance() {
     assert!(state.text_a == "test");
     assert!(state.text_b == "default");
 }
+
+#[test]
+fn style_scope() {
+    setup();
+
+    resources().theme.register_class_style("scoped", style!(TestStyle {
+        text_a: "app".to_owned(),
+        text_b: "app".to_owned(),
+    }));
+    resources().theme.scope("widget").register_class_style("scoped", style!(TestStyle {
+        text_a: "scope".to_owned(),
+    }));
+
+    let mut state = DrawState::default();
+    state.set_draw_style(DrawStyle::from_class::<TestStyle>("scoped"));
+    state.set_scope(Some("widget".to_owned()));
+    state.update(btreeset!{});
+
+    let state = state.get_state::<TestState>();
+    assert!(state.text_a == "scope");
+    assert!(state.text_b == "default");
+}
+
+#[test]
+fn style_scope_export() {
+    setup();
+
+    resources().theme.scope("exporting").register_class_style("exported", style!(TestStyle {
+        text_a: "scope".to_owned(),
+        text_b: "scope".to_owned(),
+    })).export_class("exported");
+    resources().theme.register_class_style("exporting::exported", style!(TestStyle {
+        text_b: "app".to_owned(),
+    }));
+
+    let mut state = DrawState::default();
+    state.set_draw_style(DrawStyle::from_class::<TestStyle>("exported"));
+    state.set_scope(Some("exporting".to_owned()));
+    state.update(btreeset!{});
+
+    let state = state.get_state::<TestState>();
+    assert!(state.text_a == "scope");
+    assert!(state.text_b == "app");
+}
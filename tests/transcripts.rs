@@ -0,0 +1,112 @@
+//! Golden transcripts of the events widgets send for scripted input, see `limn::input::transcript`.
+//! These open a hidden window, so they're ignored without a display, run them with
+//! RUST_TEST_THREADS=1 cargo test -- --ignored, and LIMN_UPDATE_GOLDEN=1 to accept changes
+
+#[macro_use]
+extern crate limn;
+
+mod util;
+
+use std::path::PathBuf;
+
+use limn::prelude::*;
+use limn::input::simulate::NO_MODIFIERS;
+
+use util::app;
+
+fn golden(name: &str) -> PathBuf {
+    PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("tests").join("golden").join(name)
+}
+
+/// Lay out `widget` at the top left of the window, `widget_size` points large
+fn lay_out(app: &mut App, mut widget: Widget, widget_size: Size) {
+    let mut root = Widget::new("root");
+    root.layout().add(shrink());
+    widget.layout().add(constraints![
+        align_top(&root),
+        align_left(&root),
+        size(widget_size),
+        bound_by(&root),
+    ]);
+    root.add_child(widget);
+    app.capture(root, 1.0);
+}
+
+#[test]
+#[ignore]
+fn toggle_button_clicks() {
+    let mut app = app();
+    let mut button = ToggleButtonStyle::default();
+    button.toggle_text("ON", "OFF");
+    let mut button = Widget::from_modifier_style(button);
+    button.set_name("toggle_button");
+    lay_out(&mut app, button.clone(), Size::new(100.0, 40.0));
+
+    let transcript = Transcript::new();
+    transcript.record::<ToggleEvent>(&mut button);
+    transcript.note("click");
+    app.simulate().click(&button);
+    transcript.note("click again");
+    app.simulate().click(&button);
+    transcript.assert_golden(&golden("toggle_button_clicks.txt"));
+}
+
+#[test]
+#[ignore]
+fn edit_text_typing() {
+    let mut app = app();
+    let mut edit_text = Widget::from_modifier(EditText::default());
+    edit_text.set_name("edit_text");
+    lay_out(&mut app, edit_text.clone(), Size::new(200.0, 40.0));
+
+    let transcript = Transcript::new();
+    transcript.record::<TextUpdated>(&mut edit_text);
+    transcript.note("focus and type");
+    app.simulate().click(&edit_text).type_text("hi");
+    transcript.note("backspace");
+    app.simulate().press(glutin::VirtualKeyCode::Back, NO_MODIFIERS);
+    transcript.assert_golden(&golden("edit_text_typing.txt"));
+}
+
+#[test]
+#[ignore]
+fn slider_drag() {
+    let mut app = app();
+    // 30 point handle, leaving 200 points for it to move
+    let mut slider = Widget::from_modifier(Slider::default());
+    slider.set_name("slider");
+    lay_out(&mut app, slider.clone(), Size::new(230.0, 30.0));
+    let handle = slider.child("slider_handle").unwrap();
+
+    let transcript = Transcript::new();
+    transcript.record::<SliderEvent>(&mut slider);
+    transcript.note("drag the handle to the end");
+    app.simulate().drag(&handle, Point::new(215.0, 15.0));
+    transcript.assert_golden(&golden("slider_drag.txt"));
+}
+
+#[test]
+#[ignore]
+fn scroll_wheel_and_keys() {
+    let mut app = app();
+    // 800 points of content below the fold, nothing to scroll horizontally
+    let mut content = Widget::new("content");
+    content.layout().add(size(Size::new(200.0, 1000.0)));
+    let mut scroll = ScrollContainer::default();
+    scroll.add_content(content);
+    let mut scroll = Widget::from_modifier(scroll);
+    scroll.set_name("scroll");
+    lay_out(&mut app, scroll.clone(), Size::new(200.0, 200.0));
+
+    let transcript = Transcript::new();
+    transcript.record::<ScrollChanged>(&mut scroll);
+    transcript.note("wheel down three lines");
+    app.simulate().scroll(&scroll, -3.0);
+    transcript.note("focus and arrow down");
+    app.simulate().click(&scroll).press(glutin::VirtualKeyCode::Down, NO_MODIFIERS);
+    transcript.note("end");
+    app.simulate().press(glutin::VirtualKeyCode::End, NO_MODIFIERS);
+    transcript.note("wheel past the top");
+    app.simulate().scroll(&scroll, 100.0);
+    transcript.assert_golden(&golden("scroll_wheel_and_keys.txt"));
+}
@@ -0,0 +1,12 @@
+//! Helpers for tests that need an `App`, shared by the integration tests
+
+#![allow(dead_code)]
+
+use limn::prelude::*;
+
+/// An app with a hidden window, tests using it need a display
+pub fn app() -> App {
+    let events_loop = glutin::EventsLoop::new();
+    let window = Window::new(glutin::WindowBuilder::new(), &events_loop);
+    App::new(window, events_loop)
+}
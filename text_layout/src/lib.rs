@@ -55,7 +55,7 @@ pub type PositionedGlyph = rusttype::PositionedGlyph<'static>;
 
 pub type Font = rusttype::Font<'static>;
 
-pub use types::Align;
+pub use types::{Align, Features};
 
 /// The way in which text should wrap around the width.
 #[derive(Copy, Clone, Debug, PartialEq)]
@@ -77,9 +77,10 @@ pub fn get_text_size(text: &str,
                      font: &Font,
                      font_size: f32,
                      line_height: f32,
-                     wrap: Wrap) -> Size {
+                     wrap: Wrap,
+                     features: Features) -> Size {
 
-    let line_infos = LineInfos::new(text, font, font_size, wrap, f32::MAX);
+    let line_infos = LineInfos::new(text, font, font_size, wrap, f32::MAX).with_features(features);
     let max_width = line_infos.fold(0.0, |max, line_info| f32::max(max, line_info.width));
     Size::new(max_width, line_infos.count() as f32 * line_height)
 }
@@ -89,22 +90,39 @@ pub fn get_text_height(text: &str,
                         font_size: f32,
                         line_height: f32,
                         wrap: Wrap,
+                        features: Features,
                         width: f32)
                         -> f32 {
-    let line_infos = LineInfos::new(text, font, font_size, wrap, width);
+    let line_infos = LineInfos::new(text, font, font_size, wrap, width).with_features(features);
     line_infos.count() as f32 * line_height
 }
 
+/// Byte range of each line of the text when wrapped to `width`
+pub fn get_line_ranges(text: &str,
+                       font: &Font,
+                       font_size: f32,
+                       wrap: Wrap,
+                       features: Features,
+                       width: f32)
+                       -> Vec<std::ops::Range<usize>> {
+    LineInfos::new(text, font, font_size, wrap, width)
+        .with_features(features)
+        .map(|info| info.start_byte..info.end_byte())
+        .collect()
+}
+
 pub fn get_line_rects(text: &str,
                       rect: Rect,
                       font: &Font,
                       font_size: f32,
                       line_height: f32,
                       line_wrap: Wrap,
+                      features: Features,
                       align: Align)
                       -> Vec<Rect> {
 
     let line_infos: Vec<LineInfo> = LineInfos::new(text, font, font_size, line_wrap, rect.width())
+        .with_features(features)
         .collect();
     let line_infos = line_infos.iter().cloned();
     let line_rects = LineRects::new(line_infos, font_size, rect, align, line_height);
@@ -117,15 +135,18 @@ pub fn get_positioned_glyphs(text: &str,
                              font_size: f32,
                              line_height: f32,
                              line_wrap: Wrap,
+                             features: Features,
                              align: Align)
                              -> Vec<PositionedGlyph>
 {
     let line_infos: Vec<LineInfo> = LineInfos::new(text, font, font_size, line_wrap, rect.width())
+        .with_features(features)
         .collect();
     let line_infos = line_infos.iter().cloned();
     let line_texts = line_infos.clone().map(|info| &text[info.byte_range()]);
     let line_rects = LineRects::new(line_infos, font_size, rect, align, line_height);
     let scale = Scale::uniform(font_size);
+    let tabular_width = line::tabular_advance_width(font, scale);
 
     let mut positioned_glyphs = Vec::new();
     for (line_text, line_rect) in line_texts.zip(line_rects) {
@@ -135,14 +156,23 @@ pub fn get_positioned_glyphs(text: &str,
             y: line_rect.top() + font_size,
         };
 
-        positioned_glyphs.extend(font.glyphs_for(line_text.chars())
-            .scan((None, 0.0), |state, g| {
+        positioned_glyphs.extend(line_text.chars().map(|ch| (ch, font.glyph(ch).unwrap()))
+            .scan((None, 0.0), |state, (ch, g)| {
                 let &mut (last, x) = state;
                 let g = g.scaled(scale);
-
-                let kern = last.map(|last| font.pair_kerning(scale, last, g.id())).unwrap_or(0.0);
                 let width = g.h_metrics().advance_width;
 
+                if features.tabular_numbers && ch.is_ascii_digit() {
+                    // center the digit in a cell the width of the widest digit
+                    let next = g.positioned(point + rusttype::vector(x + (tabular_width - width) / 2.0, 0.0));
+                    *state = (None, x + tabular_width);
+                    return Some(next.standalone());
+                }
+                let kern = match last {
+                    Some(last) if features.kerning => font.pair_kerning(scale, last, g.id()),
+                    _ => 0.0,
+                };
+
                 let next = g.positioned(point + rusttype::vector(x, 0.0));
                 *state = (Some(next.id()), x + width + kern);
                 Some(next.standalone())
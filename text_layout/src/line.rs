@@ -5,7 +5,7 @@
 use rusttype;
 use super::Font;
 use rusttype::Scale;
-use types::{Range, Align, Rect, RectExt};
+use types::{Range, Align, Rect, RectExt, Features};
 use std;
 use rusttype::GlyphId;
 use std::str::CharIndices;
@@ -102,6 +102,7 @@ pub struct LineInfos<'a> {
     font_size: f32,
     max_width: f32,
     line_wrap: Wrap,
+    features: Features,
     /// The index that indicates the start of the next line to be yielded.
     start_byte: usize,
     /// The character index that indicates the start of the next line to be yielded.
@@ -123,11 +124,17 @@ impl<'a> LineInfos<'a> {
             font_size: font_size,
             max_width: max_width,
             line_wrap: line_wrap,
+            features: Features::default(),
             start_byte: 0,
             start_char: 0,
             last_break: None,
         }
     }
+    /// Measure the text with the given font features, rather than the defaults
+    pub fn with_features(mut self, features: Features) -> Self {
+        self.features = features;
+        self
+    }
 }
 
 impl<'a> Iterator for LineInfos<'a> {
@@ -138,15 +145,16 @@ impl<'a> Iterator for LineInfos<'a> {
                         font_size,
                         max_width,
                         line_wrap,
+                        features,
                         ref mut start_byte,
                         ref mut start_char,
                         ref mut last_break } = *self;
 
         let text_line = &text[*start_byte..];
         let (next, width) = match line_wrap {
-            Wrap::NoWrap => next_break(text_line, font, font_size),
-            Wrap::Character => next_break_by_character(text_line, font, font_size, max_width),
-            Wrap::Whitespace => next_break_by_whitespace(text_line, font, font_size, max_width),
+            Wrap::NoWrap => next_break(text_line, font, font_size, features),
+            Wrap::Character => next_break_by_character(text_line, font, font_size, features, max_width),
+            Wrap::Whitespace => next_break_by_whitespace(text_line, font, font_size, features, max_width),
         };
         match next.break_type {
             BreakType::Newline { len_bytes } |
@@ -344,15 +352,28 @@ impl<'a, I> Iterator for SelectedLineRects<'a, I>
 /// This is primarily for use within the `next_break` functions below.
 ///
 /// The following code is adapted from the `rusttype::LayoutIter::next` src.
-fn advance_width(ch: char, font: &Font, scale: Scale, last_glyph: &mut Option<GlyphId>) -> f32 {
+fn advance_width(ch: char, font: &Font, scale: Scale, features: Features, last_glyph: &mut Option<GlyphId>) -> f32 {
+    if features.tabular_numbers && ch.is_ascii_digit() {
+        *last_glyph = None;
+        return tabular_advance_width(font, scale);
+    }
     let g = font.glyph(ch).unwrap().scaled(scale);
-    let kern = last_glyph.map(|last| font.pair_kerning(scale, last, g.id()))
-        .unwrap_or(0.0);
+    let kern = match *last_glyph {
+        Some(last) if features.kerning => font.pair_kerning(scale, last, g.id()),
+        _ => 0.0,
+    };
     let advance_width = g.h_metrics().advance_width;
     *last_glyph = Some(g.id());
     (kern + advance_width)
 }
 
+/// The advance width of every digit when using tabular numbers, the width of the widest digit.
+pub fn tabular_advance_width(font: &Font, scale: Scale) -> f32 {
+    "0123456789".chars()
+        .map(|ch| font.glyph(ch).unwrap().scaled(scale).h_metrics().advance_width)
+        .fold(0.0, f32::max)
+}
+
 fn peek_next_char(char_indices: &mut Peekable<CharIndices>, next_char_expected: char) -> bool {
     if let Some(&(_, next_char)) = char_indices.peek() {
         next_char == next_char_expected
@@ -363,7 +384,7 @@ fn peek_next_char(char_indices: &mut Peekable<CharIndices>, next_char_expected:
 
 /// Returns the next index at which the text naturally breaks via a newline character,
 /// along with the width of the line.
-fn next_break(text: &str, font: &Font, font_size: f32) -> (Break, f32) {
+fn next_break(text: &str, font: &Font, font_size: f32, features: Features) -> (Break, f32) {
     let scale = super::pt_to_scale(font_size);
     let mut width = 0.0;
     let mut char_i = 0;
@@ -380,7 +401,7 @@ fn next_break(text: &str, font: &Font, font_size: f32) -> (Break, f32) {
         }
 
         // Update the width.
-        width += advance_width(ch, font, scale, &mut last_glyph);
+        width += advance_width(ch, font, scale, features, &mut last_glyph);
         char_i += 1;
     }
     let break_ = Break::new(text.len(), char_i, BreakType::End);
@@ -394,6 +415,7 @@ fn next_break(text: &str, font: &Font, font_size: f32) -> (Break, f32) {
 fn next_break_by_character(text: &str,
                            font: &Font,
                            font_size: f32,
+                           features: Features,
                            max_width: f32)
                            -> (Break, f32) {
     let scale = super::pt_to_scale(font_size);
@@ -412,7 +434,7 @@ fn next_break_by_character(text: &str,
         }
 
         // Add the character's width to the width so far.
-        let new_width = width + advance_width(ch, font, scale, &mut last_glyph);
+        let new_width = width + advance_width(ch, font, scale, features, &mut last_glyph);
 
         // Check for a line wrap.
         if new_width > max_width {
@@ -439,6 +461,7 @@ fn next_break_by_character(text: &str,
 fn next_break_by_whitespace(text: &str,
                             font: &Font,
                             font_size: f32,
+                            features: Features,
                             max_width: f32)
                             -> (Break, f32) {
     struct Last {
@@ -464,7 +487,7 @@ fn next_break_by_whitespace(text: &str,
         }
 
         // Add the character's width to the width so far.
-        let new_width = width + advance_width(ch, font, scale, &mut last_glyph);
+        let new_width = width + advance_width(ch, font, scale, features, &mut last_glyph);
 
         // Check for a line wrap.
         if new_width > max_width {
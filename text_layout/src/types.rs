@@ -47,6 +47,24 @@ impl RectExt<f32> for Rect {
     }
 }
 
+/// Optional font features applied when laying out text
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct Features {
+    /// Adjust the spacing between pairs of glyphs using the font's kerning table
+    pub kerning: bool,
+    /// Give every digit the same advance width, so columns of numbers line up
+    pub tabular_numbers: bool,
+}
+
+impl Default for Features {
+    fn default() -> Self {
+        Features {
+            kerning: true,
+            tabular_numbers: false,
+        }
+    }
+}
+
 /// The orientation of **Align**ment along some **Axis**.
 #[derive(Copy, Clone, Debug, PartialEq, Eq)]
 pub enum Align {
@@ -0,0 +1,673 @@
+use std::collections::{BTreeMap, VecDeque};
+
+use webrender::api::PrimitiveInfo;
+
+use render::RenderBuilder;
+use widget::draw::Draw;
+use geometry::{Rect, RectExt, Point, Size};
+use style::Component;
+use format::NumberFormat;
+use color::*;
+use draw::text::TextState;
+use draw::conditional::ConditionalFormat;
+
+/// A series is drawn from its decimation once it has more than this many points per pixel column
+const DECIMATE_POINTS_PER_COLUMN: usize = 2;
+/// Minimum space between ticks on the x axis, wide enough for a label
+const X_TICK_SPACING: f32 = 80.0;
+/// Minimum space between ticks on the y axis
+const Y_TICK_SPACING: f32 = 40.0;
+const LABEL_FONT_SIZE: f32 = 12.0;
+
+/// A line through a list of points in data coordinates, sorted by x.
+///
+/// The points are a ring buffer, appending points beyond `max_points` drops the oldest, so a
+/// series can be fed indefinitely, ex. from a monitoring thread.
+#[derive(Clone, Debug)]
+pub struct Series {
+    pub name: String,
+    pub color: Color,
+    pub visible: bool,
+    points: VecDeque<(f32, f32)>,
+    max_points: usize,
+    /// Number of points appended since the points were last replaced
+    appended: u64,
+    decimation: Option<Decimation>,
+    format: Option<ConditionalFormat>,
+    /// The color given to each point by the format, if it has one
+    point_colors: VecDeque<Option<Color>>,
+}
+
+impl Series {
+    pub fn new(name: &str, points: Vec<(f32, f32)>) -> Self {
+        Series {
+            name: name.to_owned(),
+            color: BLUE,
+            visible: true,
+            appended: points.len() as u64,
+            points: points.into_iter().collect(),
+            max_points: ::std::usize::MAX,
+            decimation: None,
+            format: None,
+            point_colors: VecDeque::new(),
+        }
+    }
+    pub fn color(mut self, color: Color) -> Self {
+        self.color = color;
+        self
+    }
+    /// Keep at most `max_points`, dropping the oldest as points are appended
+    pub fn max_points(mut self, max_points: usize) -> Self {
+        self.max_points = max_points.max(1);
+        self.drop_oldest();
+        self
+    }
+    /// Color the line to each point by the color `format` gives its value, ex. red above a
+    /// threshold. Gradients and icon sets without a range span the points the series has now.
+    /// Decimated lines are drawn in the series color.
+    pub fn set_format(&mut self, format: ConditionalFormat) {
+        self.format = Some(format);
+        self.color_points();
+    }
+    fn color_points(&mut self) {
+        self.point_colors.clear();
+        if let Some(ref mut format) = self.format {
+            format.fit(self.points.iter().map(|&(_, y)| f64::from(y)));
+            let format = &*format;
+            self.point_colors.extend(self.points.iter().map(|&(_, y)| format.style(f64::from(y)).color));
+        }
+    }
+    pub fn points(&self) -> &VecDeque<(f32, f32)> {
+        &self.points
+    }
+    /// The last point, ex. to scroll a streaming chart to
+    pub fn last(&self) -> Option<(f32, f32)> {
+        self.points.back().cloned()
+    }
+    pub fn set_points(&mut self, points: Vec<(f32, f32)>) {
+        self.appended = points.len() as u64;
+        self.points = points.into_iter().collect();
+        self.decimation = None;
+        self.drop_oldest();
+        self.color_points();
+    }
+    /// Append points, with x at or after the x of the last point
+    pub fn extend(&mut self, points: &[(f32, f32)]) {
+        self.points.extend(points.iter().cloned());
+        self.appended += points.len() as u64;
+        if let Some(ref format) = self.format {
+            self.point_colors.extend(points.iter().map(|&(_, y)| format.style(f64::from(y)).color));
+        }
+        self.drop_oldest();
+    }
+    fn drop_oldest(&mut self) {
+        while self.points.len() > self.max_points {
+            self.points.pop_front();
+            self.point_colors.pop_front();
+        }
+    }
+    /// The value of the series at `x`, interpolated between the points either side of it,
+    /// or `None` if `x` is outside the series
+    pub fn value_at(&self, x: f32) -> Option<f32> {
+        let after = self.points.iter().position(|&(point_x, _)| point_x >= x)?;
+        let (x1, y1) = self.points[after];
+        if after == 0 {
+            return if x1 == x { Some(y1) } else { None };
+        }
+        let (x0, y0) = self.points[after - 1];
+        Some(if x1 > x0 { y0 + (y1 - y0) * (x - x0) / (x1 - x0) } else { y1 })
+    }
+
+    /// Draw the line through the points in `view`, decimated if there are too many to draw each one
+    pub(crate) fn draw(&mut self, view: ChartView, line_width: f32, bounds: Rect, renderer: &mut RenderBuilder) {
+        // include the points either side of the view, so lines run to the edges
+        let start = self.points.iter().position(|&(x, _)| x >= view.x.0).unwrap_or(self.points.len()).saturating_sub(1);
+        let end = self.points.iter().position(|&(x, _)| x > view.x.1).map_or(self.points.len(), |end| end + 1);
+        if end - start > bounds.width() as usize * DECIMATE_POINTS_PER_COLUMN {
+            self.draw_decimated(view, line_width, bounds, renderer);
+            return;
+        }
+        self.decimation = None;
+        let mut previous = None;
+        for (index, &(x, y)) in self.points.iter().enumerate().skip(start).take(end - start) {
+            let point = view.to_point(bounds, x, y);
+            let color = self.point_colors.get(index).and_then(|color| *color).unwrap_or(self.color);
+            push_segment(previous.unwrap_or(point), point, line_width, color, bounds, renderer);
+            previous = Some(point);
+        }
+    }
+
+    /// Draw the min and max of the points in each pixel column, only decimating the points
+    /// appended since the last draw while the scale of the x axis stays the same
+    fn draw_decimated(&mut self, view: ChartView, line_width: f32, bounds: Rect, renderer: &mut RenderBuilder) {
+        let width = (view.x.1 - view.x.0) / bounds.width();
+        let mut decimation = match self.decimation.take() {
+            Some(decimation) if decimation.width == width && decimation.added <= self.appended => decimation,
+            _ => Decimation::new(width),
+        };
+        let new_points = ((self.appended - decimation.added) as usize).min(self.points.len());
+        for &(x, y) in self.points.iter().skip(self.points.len() - new_points) {
+            decimation.add(x, y);
+        }
+        decimation.added = self.appended;
+        if let Some(&(first_x, _)) = self.points.front() {
+            decimation.drop_before(first_x);
+        }
+        let half = line_width / 2.0;
+        let mut previous_last = None;
+        // from a column before the view, to join the first column in the view to it
+        for (x, span) in decimation.spans(view.x.0 - width, view.x.1) {
+            let column = view.to_point(bounds, x, 0.0).x.floor();
+            if column + 1.0 >= bounds.left() {
+                // join the column to the last point of the column before
+                let (min, max) = previous_last.map_or((span.min, span.max), |last: f32| (span.min.min(last), span.max.max(last)));
+                let top = (view.to_point(bounds, x, max).y - half).max(bounds.top());
+                let bottom = (view.to_point(bounds, x, min).y + half).min(bounds.bottom());
+                if bottom > top {
+                    let rect = Rect::new(Point::new(column, top), Size::new(1.0, bottom - top));
+                    renderer.builder.push_rect(&PrimitiveInfo::new(rect), self.color.into());
+                }
+            }
+            previous_last = Some(span.last);
+        }
+        self.decimation = Some(decimation);
+    }
+}
+
+/// The range of the points in a column, and the first and last point, to join it to its neighbours
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct ColumnSpan {
+    pub min: f32,
+    pub max: f32,
+    pub first: f32,
+    pub last: f32,
+}
+
+impl ColumnSpan {
+    fn new(y: f32) -> Self {
+        ColumnSpan { min: y, max: y, first: y, last: y }
+    }
+    fn add(&mut self, y: f32) {
+        self.min = self.min.min(y);
+        self.max = self.max.max(y);
+        self.last = y;
+    }
+}
+
+/// Min/max decimation of points into columns of x, for drawing more points than there are
+/// pixels. Columns are aligned to multiples of their width rather than to the first point, so
+/// points can be appended, and old ones dropped, without recomputing the other columns.
+#[derive(Debug, Clone)]
+pub struct Decimation {
+    /// Width of each column, in data coordinates
+    pub width: f32,
+    /// By index, the x of the start of the column divided by the width
+    columns: BTreeMap<i64, ColumnSpan>,
+    /// Number of points the series had appended when last updated
+    added: u64,
+}
+
+impl Decimation {
+    pub fn new(width: f32) -> Self {
+        Decimation {
+            width: width,
+            columns: BTreeMap::new(),
+            added: 0,
+        }
+    }
+    fn column(&self, x: f32) -> i64 {
+        (x / self.width).floor() as i64
+    }
+    pub fn add(&mut self, x: f32, y: f32) {
+        let column = self.column(x);
+        if let Some(span) = self.columns.get_mut(&column) {
+            span.add(y);
+            return;
+        }
+        self.columns.insert(column, ColumnSpan::new(y));
+    }
+    /// Drop the columns entirely before `x`
+    pub fn drop_before(&mut self, x: f32) {
+        let column = self.column(x);
+        self.columns = self.columns.split_off(&column);
+    }
+    /// The x of the start of each column with points between `start` and `end`, and the range of
+    /// its points
+    pub fn spans<'a>(&'a self, start: f32, end: f32) -> Box<Iterator<Item = (f32, ColumnSpan)> + 'a> {
+        let width = self.width;
+        Box::new(self.columns.range(self.column(start)..self.column(end) + 1)
+            .map(move |(&column, &span)| (column as f32 * width, span)))
+    }
+}
+
+/// Decimate `points`, sorted by x, into columns `width` wide
+pub fn decimate<I: IntoIterator<Item = (f32, f32)>>(points: I, width: f32) -> Decimation {
+    let mut decimation = Decimation::new(width);
+    for (x, y) in points {
+        decimation.add(x, y);
+    }
+    decimation
+}
+
+/// An axis of a chart
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum Axis {
+    X,
+    Y,
+}
+
+/// A "nice" distance between ticks, 1, 2 or 5 times a power of ten, giving at most `max_ticks`
+/// ticks over `span`
+pub fn nice_step(span: f32, max_ticks: usize) -> f32 {
+    let rough = span.abs() / max_ticks.max(1) as f32;
+    if rough <= 0.0 || !rough.is_finite() {
+        return 1.0;
+    }
+    let magnitude = 10f32.powf(rough.log10().floor());
+    let step = [1.0, 2.0, 5.0, 10.0].iter().map(|factor| factor * magnitude).find(|&step| step >= rough);
+    step.unwrap_or(10.0 * magnitude)
+}
+
+/// Ticks at multiples of a nice step within `range`
+pub fn nice_ticks(range: (f32, f32), max_ticks: usize) -> Vec<f32> {
+    let step = nice_step(range.1 - range.0, max_ticks);
+    let first = (range.0 / step).ceil() as i64;
+    let last = (range.1 / step).floor() as i64;
+    (first..last + 1).map(|tick| tick as f32 * step).collect()
+}
+
+/// `range` widened to the nearest multiples of a nice step, ex. to fit a view to data so the
+/// axis starts and ends on a tick
+pub fn nice_range(range: (f32, f32), max_ticks: usize) -> (f32, f32) {
+    let step = nice_step(range.1 - range.0, max_ticks);
+    ((range.0 / step).floor() * step, (range.1 / step).ceil() * step)
+}
+
+/// The ticks of an axis `length` pixels long, showing `range`
+pub fn axis_ticks(axis: Axis, range: (f32, f32), length: f32) -> Vec<f32> {
+    let spacing = if axis == Axis::X { X_TICK_SPACING } else { Y_TICK_SPACING };
+    nice_ticks(range, (length / spacing).max(2.0) as usize)
+}
+
+fn label_text_state(text: &str, color: Color) -> TextState {
+    TextState {
+        text: text.to_owned(),
+        font_size: LABEL_FONT_SIZE,
+        text_color: color,
+        ..TextState::default()
+    }
+}
+
+/// Something marked on a chart, in data coordinates
+#[derive(Debug, Clone, PartialEq)]
+pub enum Annotation {
+    /// A line across the chart at a value of x or y, ex. an alert threshold
+    Line {
+        axis: Axis,
+        value: f32,
+        color: Color,
+    },
+    /// A band across the chart between two values of x or y, ex. an incident or a normal range
+    Region {
+        axis: Axis,
+        range: (f32, f32),
+        color: Color,
+    },
+    /// A label marking a point
+    Callout {
+        point: (f32, f32),
+        text: String,
+        color: Color,
+    },
+}
+
+impl Annotation {
+    fn draw(&self, view: ChartView, bounds: Rect, renderer: &mut RenderBuilder) {
+        let band = |axis: Axis, start: f32, end: f32| {
+            let rect = match axis {
+                Axis::X => {
+                    let (left, right) = (view.to_point(bounds, start, 0.0).x, view.to_point(bounds, end, 0.0).x);
+                    Rect::new(Point::new(left.min(right), bounds.top()), Size::new((right - left).abs(), bounds.height()))
+                }
+                Axis::Y => {
+                    let (top, bottom) = (view.to_point(bounds, 0.0, start).y, view.to_point(bounds, 0.0, end).y);
+                    Rect::new(Point::new(bounds.left(), top.min(bottom)), Size::new(bounds.width(), (bottom - top).abs()))
+                }
+            };
+            rect.intersection(&bounds)
+        };
+        match *self {
+            Annotation::Line { axis, value, color } => {
+                let point = view.to_point(bounds, value, value);
+                let rect = match axis {
+                    Axis::X => Rect::new(Point::new(point.x.floor(), bounds.top()), Size::new(1.0, bounds.height())),
+                    Axis::Y => Rect::new(Point::new(bounds.left(), point.y.floor()), Size::new(bounds.width(), 1.0)),
+                };
+                if let Some(rect) = rect.intersection(&bounds) {
+                    renderer.builder.push_rect(&PrimitiveInfo::new(rect), color.into());
+                }
+            }
+            Annotation::Region { axis, range, color } => {
+                if let Some(rect) = band(axis, range.0, range.1) {
+                    renderer.builder.push_rect(&PrimitiveInfo::new(rect), color.into());
+                }
+            }
+            Annotation::Callout { point, ref text, color } => {
+                let point = view.to_point(bounds, point.0, point.1);
+                if !bounds.contains(&point) {
+                    return;
+                }
+                let marker = Rect::new(Point::new(point.x - 2.0, point.y - 2.0), Size::new(5.0, 5.0));
+                renderer.builder.push_rect(&PrimitiveInfo::new(marker), color.into());
+                let mut label = label_text_state(text, color);
+                label.background_color = WHITE;
+                let size = label.measure();
+                // above and to the right of the point, unless that's outside the chart
+                let x = if point.x + 6.0 + size.width > bounds.right() { point.x - 6.0 - size.width } else { point.x + 6.0 };
+                let y = if point.y - 6.0 - size.height < bounds.top() { point.y + 6.0 } else { point.y - 6.0 - size.height };
+                label.draw(Rect::new(Point::new(x, y), size), bounds, renderer);
+            }
+        }
+    }
+}
+
+/// Which axes of a chart an interaction or setting applies to
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum ChartAxes {
+    X,
+    Y,
+    Both,
+}
+
+impl ChartAxes {
+    pub fn x(&self) -> bool {
+        *self != ChartAxes::Y
+    }
+    pub fn y(&self) -> bool {
+        *self != ChartAxes::X
+    }
+}
+
+/// The ranges of x and y shown by a chart, in data coordinates
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct ChartView {
+    pub x: (f32, f32),
+    pub y: (f32, f32),
+}
+
+impl Default for ChartView {
+    fn default() -> Self {
+        ChartView {
+            x: (0.0, 1.0),
+            y: (0.0, 1.0),
+        }
+    }
+}
+
+impl ChartView {
+    pub fn new(x: (f32, f32), y: (f32, f32)) -> Self {
+        ChartView { x: x, y: y }
+    }
+    /// The view showing every point of the visible `series`, with a margin above and below
+    pub fn fit(series: &[Series]) -> Self {
+        let points = || series.iter().filter(|series| series.visible).flat_map(|series| series.points().iter());
+        let mut view = ChartView {
+            x: points().fold((::std::f32::INFINITY, ::std::f32::NEG_INFINITY), |(min, max), &(x, _)| (min.min(x), max.max(x))),
+            y: points().fold((::std::f32::INFINITY, ::std::f32::NEG_INFINITY), |(min, max), &(_, y)| (min.min(y), max.max(y))),
+        };
+        if view.x.0 > view.x.1 {
+            return ChartView::default();
+        }
+        let margin = (view.y.1 - view.y.0) * 0.05;
+        view.y = (view.y.0 - margin, view.y.1 + margin);
+        // a single point, or a flat line, still needs a range to be drawn in
+        for range in &mut [&mut view.x, &mut view.y] {
+            if range.1 <= range.0 {
+                range.0 -= 0.5;
+                range.1 += 0.5;
+            }
+        }
+        view
+    }
+    /// The position of a point in data coordinates, within `bounds`
+    pub fn to_point(&self, bounds: Rect, x: f32, y: f32) -> Point {
+        Point::new(
+            bounds.left() + (x - self.x.0) / (self.x.1 - self.x.0) * bounds.width(),
+            bounds.bottom() - (y - self.y.0) / (self.y.1 - self.y.0) * bounds.height())
+    }
+    /// The data coordinates of a position within `bounds`
+    pub fn to_data(&self, bounds: Rect, point: Point) -> (f32, f32) {
+        (self.x.0 + (point.x - bounds.left()) / bounds.width() * (self.x.1 - self.x.0),
+         self.y.0 + (bounds.bottom() - point.y) / bounds.height() * (self.y.1 - self.y.0))
+    }
+    /// Zoom in by `factor`, or out if it's less than one, keeping `anchor` in place
+    pub fn zoom(&mut self, factor: f32, anchor: (f32, f32), axes: ChartAxes) {
+        let zoom_range = |range: (f32, f32), anchor: f32| {
+            (anchor - (anchor - range.0) / factor, anchor + (range.1 - anchor) / factor)
+        };
+        if axes.x() {
+            self.x = zoom_range(self.x, anchor.0);
+        }
+        if axes.y() {
+            self.y = zoom_range(self.y, anchor.1);
+        }
+    }
+    /// Move the view by `x` and `y`, in data coordinates
+    pub fn pan(&mut self, x: f32, y: f32) {
+        self.x = (self.x.0 + x, self.x.1 + x);
+        self.y = (self.y.0 + y, self.y.1 + y);
+    }
+}
+
+/// Draws lines through series of points, with an optional crosshair and selected range of x.
+#[derive(Clone, Debug)]
+pub struct ChartState {
+    pub series: Vec<Series>,
+    pub view: ChartView,
+    /// Position of the crosshair, in data coordinates
+    pub crosshair: Option<(f32, f32)>,
+    /// Range of x highlighted by brushing, in data coordinates
+    pub brush: Option<(f32, f32)>,
+    pub annotations: Vec<Annotation>,
+    /// Color of lines across the chart at the ticks of each axis, if any
+    pub grid_color: Option<Color>,
+    pub line_width: f32,
+    pub background_color: Color,
+    pub crosshair_color: Color,
+    pub brush_color: Color,
+}
+
+impl Component for ChartState {
+    fn name() -> String {
+        String::from("chart")
+    }
+}
+
+impl Default for ChartState {
+    fn default() -> Self {
+        ChartState {
+            series: Vec::new(),
+            view: ChartView::default(),
+            crosshair: None,
+            brush: None,
+            annotations: Vec::new(),
+            grid_color: Some(GRAY_90),
+            line_width: 1.5,
+            background_color: WHITE,
+            crosshair_color: GRAY_50,
+            brush_color: Color(0x3366E633),
+        }
+    }
+}
+
+impl ChartState {
+    pub fn new(series: Vec<Series>) -> Self {
+        ChartState {
+            view: ChartView::fit(&series),
+            series: series,
+            ..ChartState::default()
+        }
+    }
+}
+
+impl Draw for ChartState {
+    fn draw(&mut self, bounds: Rect, _: Rect, renderer: &mut RenderBuilder) {
+        renderer.builder.push_rect(&PrimitiveInfo::new(bounds), self.background_color.into());
+        if let Some(grid_color) = self.grid_color {
+            draw_grid(self.view, ChartAxes::Both, grid_color, bounds, renderer);
+        }
+        if let Some((start, end)) = self.brush {
+            let left = self.view.to_point(bounds, start, 0.0).x.max(bounds.left());
+            let right = self.view.to_point(bounds, end, 0.0).x.min(bounds.right());
+            if right > left {
+                let rect = Rect::new(Point::new(left, bounds.top()), Size::new(right - left, bounds.height()));
+                renderer.builder.push_rect(&PrimitiveInfo::new(rect), self.brush_color.into());
+            }
+        }
+        // regions and lines under the series, callouts over them
+        let (view, line_width) = (self.view, self.line_width);
+        let is_callout = |annotation: &&Annotation| if let Annotation::Callout { .. } = **annotation { true } else { false };
+        for annotation in self.annotations.iter().filter(|annotation| !is_callout(annotation)) {
+            annotation.draw(view, bounds, renderer);
+        }
+        for series in self.series.iter_mut().filter(|series| series.visible) {
+            series.draw(view, line_width, bounds, renderer);
+        }
+        for annotation in self.annotations.iter().filter(is_callout) {
+            annotation.draw(view, bounds, renderer);
+        }
+        if let Some((x, y)) = self.crosshair {
+            let point = self.view.to_point(bounds, x, y);
+            if bounds.contains(&point) {
+                let vertical = Rect::new(Point::new(point.x.floor(), bounds.top()), Size::new(1.0, bounds.height()));
+                let horizontal = Rect::new(Point::new(bounds.left(), point.y.floor()), Size::new(bounds.width(), 1.0));
+                renderer.builder.push_rect(&PrimitiveInfo::new(vertical), self.crosshair_color.into());
+                renderer.builder.push_rect(&PrimitiveInfo::new(horizontal), self.crosshair_color.into());
+            }
+        }
+    }
+}
+
+/// Draws lines across a chart at the ticks of `axes`
+pub(crate) fn draw_grid(view: ChartView, axes: ChartAxes, color: Color, bounds: Rect, renderer: &mut RenderBuilder) {
+    if axes.x() {
+        for x in axis_ticks(Axis::X, view.x, bounds.width()) {
+            let left = view.to_point(bounds, x, 0.0).x.floor();
+            let rect = Rect::new(Point::new(left, bounds.top()), Size::new(1.0, bounds.height()));
+            renderer.builder.push_rect(&PrimitiveInfo::new(rect), color.into());
+        }
+    }
+    if axes.y() {
+        for y in axis_ticks(Axis::Y, view.y, bounds.height()) {
+            let top = view.to_point(bounds, 0.0, y).y.floor();
+            let rect = Rect::new(Point::new(bounds.left(), top), Size::new(bounds.width(), 1.0));
+            renderer.builder.push_rect(&PrimitiveInfo::new(rect), color.into());
+        }
+    }
+}
+
+/// Draws the ticks and labels of an axis of a chart, beside the plot: the x axis below it, and
+/// the y axis to its left.
+#[derive(Clone, Debug)]
+pub struct AxisState {
+    pub axis: Axis,
+    /// The range of the axis shown, in data coordinates
+    pub range: (f32, f32),
+    pub format: NumberFormat,
+    /// Labels at fixed positions, ex. the names of categories, shown instead of numbered ticks
+    pub labels: Vec<(f32, String)>,
+    pub color: Color,
+    pub tick_length: f32,
+}
+
+impl Component for AxisState {
+    fn name() -> String {
+        String::from("chart_axis")
+    }
+}
+
+impl Default for AxisState {
+    fn default() -> Self {
+        AxisState {
+            axis: Axis::X,
+            range: (0.0, 1.0),
+            format: NumberFormat::new(),
+            labels: Vec::new(),
+            color: GRAY_30,
+            tick_length: 4.0,
+        }
+    }
+}
+
+impl AxisState {
+    pub fn new(axis: Axis) -> Self {
+        AxisState {
+            axis: axis,
+            ..AxisState::default()
+        }
+    }
+}
+
+impl Draw for AxisState {
+    fn draw(&mut self, bounds: Rect, crop_to: Rect, renderer: &mut RenderBuilder) {
+        let ticks: Vec<(f32, String)> = if self.labels.is_empty() {
+            let length = if self.axis == Axis::X { bounds.width() } else { bounds.height() };
+            let ticks = axis_ticks(self.axis, self.range, length);
+            // enough decimals to show the step between ticks
+            let step = nice_step(self.range.1 - self.range.0, ticks.len().max(1));
+            let decimals = (-step.log10().floor()).max(0.0) as usize;
+            self.format.set_decimals(0, decimals);
+            ticks.into_iter().map(|tick| (tick, self.format.format(f64::from(tick)))).collect()
+        } else {
+            self.labels.clone()
+        };
+        let view = ChartView::new(self.range, self.range);
+        for (tick, text) in ticks {
+            let mut label = label_text_state(&text, self.color);
+            let size = label.measure();
+            let (tick_rect, label_rect) = match self.axis {
+                Axis::X => {
+                    let x = view.to_point(bounds, tick, 0.0).x.floor();
+                    (Rect::new(Point::new(x, bounds.top()), Size::new(1.0, self.tick_length)),
+                     Rect::new(Point::new(x - size.width / 2.0, bounds.top() + self.tick_length + 2.0), size))
+                }
+                Axis::Y => {
+                    let y = view.to_point(bounds, 0.0, tick).y.floor();
+                    (Rect::new(Point::new(bounds.right() - self.tick_length, y), Size::new(self.tick_length, 1.0)),
+                     Rect::new(Point::new(bounds.right() - self.tick_length - 4.0 - size.width, y - size.height / 2.0), size))
+                }
+            };
+            renderer.builder.push_rect(&PrimitiveInfo::new(tick_rect), self.color.into());
+            label.draw(label_rect, crop_to, renderer);
+        }
+    }
+}
+
+/// Draws a segment as one rect per pixel column it crosses, spanning the part of the segment
+/// in that column, so any slope can be drawn with rects
+fn push_segment(start: Point, end: Point, line_width: f32, color: Color, bounds: Rect, renderer: &mut RenderBuilder) {
+    let half = line_width / 2.0;
+    let left = start.x.max(bounds.left());
+    let right = end.x.min(bounds.right());
+    if right < left {
+        return;
+    }
+    let y_at = |x: f32| {
+        if end.x > start.x { start.y + (end.y - start.y) * (x - start.x) / (end.x - start.x) } else { end.y }
+    };
+    let mut column = left.floor();
+    loop {
+        let (from, to) = (column.max(left), (column + 1.0).min(right));
+        let (y0, y1) = if end.x > start.x { (y_at(from), y_at(to)) } else { (start.y, end.y) };
+        let top = (y0.min(y1) - half).max(bounds.top());
+        let bottom = (y0.max(y1) + half).min(bounds.bottom());
+        if bottom > top {
+            let rect = Rect::new(Point::new(column, top), Size::new(1.0, bottom - top));
+            renderer.builder.push_rect(&PrimitiveInfo::new(rect), color.into());
+        }
+        column += 1.0;
+        if column >= right {
+            break;
+        }
+    }
+}
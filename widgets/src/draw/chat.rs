@@ -0,0 +1,282 @@
+use webrender::api::PrimitiveInfo;
+
+use render::RenderBuilder;
+use widget::draw::Draw;
+use geometry::{Rect, RectExt, Point, Size};
+use style::Component;
+use color::*;
+use draw::rect::RectState;
+use draw::text::TextState;
+use text_layout::Align;
+use resources::font::FontDescriptor;
+
+const BUBBLE_PADDING: f32 = 8.0;
+const BUBBLE_MARGIN: f32 = 6.0;
+const SEPARATOR_HEIGHT: f32 = 30.0;
+const SECONDS_PER_DAY: i64 = 24 * 60 * 60;
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct ChatMessage {
+    pub author: String,
+    pub text: String,
+    /// Seconds since the unix epoch, in UTC, shown in the view's `utc_offset` time zone
+    pub timestamp: u64,
+    /// Sent by the local user, drawn on the right
+    pub outgoing: bool,
+}
+
+impl ChatMessage {
+    pub fn new(author: &str, text: &str, timestamp: u64) -> Self {
+        ChatMessage {
+            author: author.to_owned(),
+            text: text.to_owned(),
+            timestamp: timestamp,
+            outgoing: false,
+        }
+    }
+    pub fn outgoing(mut self) -> Self {
+        self.outgoing = true;
+        self
+    }
+    /// Seconds since the unix epoch, in the time zone `utc_offset` seconds ahead of UTC
+    fn local_time(&self, utc_offset: i32) -> i64 {
+        self.timestamp as i64 + i64::from(utc_offset)
+    }
+    /// Days since the unix epoch, in the time zone `utc_offset` seconds ahead of UTC
+    fn day(&self, utc_offset: i32) -> i64 {
+        let time = self.local_time(utc_offset);
+        if time >= 0 { time / SECONDS_PER_DAY } else { (time + 1) / SECONDS_PER_DAY - 1 }
+    }
+}
+
+/// Size of a message as laid out, including the day separator above it, if any
+#[derive(Debug, Copy, Clone)]
+struct MessageLayout {
+    separator: bool,
+    bubble: Size,
+}
+
+impl MessageLayout {
+    fn height(&self) -> f32 {
+        let separator = if self.separator { SEPARATOR_HEIGHT } else { 0.0 };
+        separator + self.bubble.height + BUBBLE_MARGIN
+    }
+}
+
+/// Draws a list of chat messages as bubbles, newest at the bottom, with a separator between days.
+/// Only the messages in view are drawn, and message sizes are only measured when the width changes,
+/// so long histories stay cheap.
+#[derive(Debug, Clone)]
+pub struct ChatViewState {
+    pub messages: Vec<ChatMessage>,
+    /// Distance scrolled up from the latest message, in pixels
+    scroll: f32,
+    pub font: FontDescriptor,
+    pub font_size: f32,
+    /// Widest a bubble can be, as a fraction of the width of the view
+    pub max_bubble_width: f32,
+    pub bubble_color: Color,
+    pub outgoing_color: Color,
+    pub text_color: Color,
+    pub author_color: Color,
+    pub separator_color: Color,
+    /// Seconds the time zone messages are shown in is ahead of UTC, used for the times and the
+    /// days messages are grouped by, ex. `-5 * 3600` for UTC-05:00
+    pub utc_offset: i32,
+    layouts: Vec<MessageLayout>,
+    bounds: Rect,
+}
+
+impl Component for ChatViewState {
+    fn name() -> String {
+        String::from("chat_view")
+    }
+}
+
+impl Default for ChatViewState {
+    fn default() -> Self {
+        ChatViewState {
+            messages: Vec::new(),
+            scroll: 0.0,
+            font: TextState::default().font,
+            font_size: 16.0,
+            max_bubble_width: 0.7,
+            bubble_color: WHITE,
+            outgoing_color: Color(0xD0E8FFFF),
+            text_color: BLACK,
+            author_color: BLUE_HIGHLIGHT,
+            separator_color: GRAY_40,
+            utc_offset: 0,
+            layouts: Vec::new(),
+            bounds: Rect::zero(),
+        }
+    }
+}
+
+impl ChatViewState {
+    /// Add a message at the bottom. If scrolled up the view stays where it is, otherwise it
+    /// sticks to the latest message.
+    pub fn push_message(&mut self, message: ChatMessage) {
+        self.messages.push(message);
+        // until the view has been drawn the width isn't known, all messages are measured on the first draw
+        if self.layouts.len() + 1 == self.messages.len() && self.bounds.width() > 0.0 {
+            let layout = self.layout_message(self.messages.len() - 1);
+            if self.scroll > 0.0 {
+                self.scroll += layout.height();
+            }
+            self.layouts.push(layout);
+        }
+    }
+    pub fn scroll(&self) -> f32 {
+        self.scroll
+    }
+    /// True unless scrolled up from the latest message
+    pub fn at_latest(&self) -> bool {
+        self.scroll <= 0.0
+    }
+    /// Scroll up by `amount` pixels, or down if negative
+    pub fn scroll_by(&mut self, amount: f32) {
+        let max_scroll = (self.content_height() - self.bounds.height()).max(0.0);
+        self.scroll = (self.scroll + amount).max(0.0).min(max_scroll);
+    }
+    pub fn scroll_to_latest(&mut self) {
+        self.scroll = 0.0;
+    }
+    pub fn content_height(&self) -> f32 {
+        self.layouts.iter().map(MessageLayout::height).sum()
+    }
+
+    fn text_state(&self, text: &str, font_size: f32, color: Color) -> TextState {
+        TextState {
+            text: text.to_owned(),
+            font: self.font.clone(),
+            font_size: font_size,
+            text_color: color,
+            align: Align::Start,
+            ..TextState::default()
+        }
+    }
+    fn author_line(&self, message: &ChatMessage) -> String {
+        let seconds = message.local_time(self.utc_offset) - message.day(self.utc_offset) * SECONDS_PER_DAY;
+        format!("{}  {:02}:{:02}", message.author, seconds / 3600, seconds / 60 % 60)
+    }
+    fn max_text_width(&self) -> f32 {
+        (self.bounds.width() * self.max_bubble_width - BUBBLE_PADDING * 2.0).max(1.0)
+    }
+    fn layout_message(&self, index: usize) -> MessageLayout {
+        let message = &self.messages[index];
+        let max_width = self.max_text_width();
+        let author = self.text_state(&self.author_line(message), self.font_size * 0.8, self.author_color);
+        let body = self.text_state(&message.text, self.font_size, self.text_color);
+        let width = author.measure().width.max(body.measure().width).min(max_width);
+        let height = author.line_height() + body.wrapped_height(max_width);
+        MessageLayout {
+            separator: index == 0 || self.messages[index - 1].day(self.utc_offset) != message.day(self.utc_offset),
+            bubble: Size::new(width + BUBBLE_PADDING * 2.0, height + BUBBLE_PADDING * 2.0),
+        }
+    }
+    fn layout_messages(&mut self) {
+        let layouts = (0..self.messages.len()).map(|index| self.layout_message(index)).collect();
+        self.layouts = layouts;
+    }
+
+    fn draw_separator(&self, day: i64, top: f32, renderer: &mut RenderBuilder) {
+        let bounds = self.bounds;
+        let center = top + SEPARATOR_HEIGHT / 2.0;
+        let line = Rect::new(Point::new(bounds.left() + 10.0, center), Size::new(bounds.width() - 20.0, 1.0));
+        renderer.builder.push_rect(&PrimitiveInfo::new(line), self.separator_color.into());
+        let mut label = self.text_state(&format_day(day), self.font_size * 0.8, self.separator_color);
+        label.background_color = WHITE;
+        let size = label.measure();
+        let label_bounds = Rect::new(
+            Point::new(bounds.center().x - size.width / 2.0 - 5.0, center - size.height / 2.0),
+            Size::new(size.width + 10.0, size.height));
+        label.draw(label_bounds, label_bounds, renderer);
+    }
+}
+
+impl Draw for ChatViewState {
+    fn draw(&mut self, bounds: Rect, crop_to: Rect, renderer: &mut RenderBuilder) {
+        if bounds.size != self.bounds.size || self.layouts.len() != self.messages.len() {
+            self.bounds = bounds;
+            self.layout_messages();
+            let scroll = self.scroll;
+            self.scroll = 0.0;
+            self.scroll_by(scroll);
+        }
+        self.bounds = bounds;
+        let mut bottom = bounds.bottom() + self.scroll;
+        for index in (0..self.messages.len()).rev() {
+            if bottom < bounds.top() {
+                break;
+            }
+            let layout = self.layouts[index];
+            let top = bottom - layout.height();
+            bottom = top;
+            if top > bounds.bottom() {
+                continue;
+            }
+            let message = &self.messages[index];
+            let mut y = top;
+            if layout.separator {
+                self.draw_separator(message.day(self.utc_offset), y, renderer);
+                y += SEPARATOR_HEIGHT;
+            }
+            let x = if message.outgoing {
+                bounds.right() - layout.bubble.width - BUBBLE_MARGIN * 2.0
+            } else {
+                bounds.left() + BUBBLE_MARGIN * 2.0
+            };
+            let bubble = Rect::new(Point::new(x, y), layout.bubble);
+            let mut bubble_state = RectState {
+                background_color: if message.outgoing { self.outgoing_color } else { self.bubble_color },
+                corner_radius: Some(8.0),
+                border: None,
+            };
+            bubble_state.draw(bubble, crop_to, renderer);
+
+            let mut author = self.text_state(&self.author_line(message), self.font_size * 0.8, self.author_color);
+            let text_left = bubble.left() + BUBBLE_PADDING;
+            let text_width = bubble.width() - BUBBLE_PADDING * 2.0;
+            let author_height = author.line_height();
+            let author_bounds = Rect::new(Point::new(text_left, bubble.top() + BUBBLE_PADDING), Size::new(text_width, author_height));
+            author.draw(author_bounds, crop_to, renderer);
+            let mut body = self.text_state(&message.text, self.font_size, self.text_color);
+            let body_bounds = Rect::new(
+                Point::new(text_left, author_bounds.bottom()),
+                Size::new(text_width, bubble.bottom() - BUBBLE_PADDING - author_bounds.bottom()));
+            body.draw(body_bounds, crop_to, renderer);
+        }
+    }
+}
+
+/// Format a count of days since the unix epoch as a date, ex. "2018-03-14"
+fn format_day(day: i64) -> String {
+    // from Howard Hinnant's civil_from_days
+    let z = day + 719_468;
+    let era = z / 146_097;
+    let day_of_era = z - era * 146_097;
+    let year_of_era = (day_of_era - day_of_era / 1460 + day_of_era / 36_524 - day_of_era / 146_096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let month_index = (5 * day_of_year + 2) / 153;
+    let day_of_month = day_of_year - (153 * month_index + 2) / 5 + 1;
+    let month = if month_index < 10 { month_index + 3 } else { month_index - 9 };
+    let year = year_of_era + era * 400 + if month <= 2 { 1 } else { 0 };
+    format!("{}-{:02}-{:02}", year, month, day_of_month)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn days_in_time_zone() {
+        // 2018-03-14 23:30 UTC
+        let message = ChatMessage::new("Alex", "hi", 1_521_070_200);
+        assert_eq!(format_day(message.day(0)), "2018-03-14");
+        assert_eq!(format_day(message.day(3600)), "2018-03-15");
+        assert_eq!(format_day(message.day(-5 * 3600)), "2018-03-14");
+        let before_epoch = ChatMessage::new("Alex", "hi", 0);
+        assert_eq!(format_day(before_epoch.day(-3600)), "1969-12-31");
+    }
+}
@@ -0,0 +1,1087 @@
+use std::cmp::{min, max};
+use std::ops::Range;
+
+use webrender::api::PrimitiveInfo;
+
+use render::{self, RenderBuilder};
+use widget::draw::Draw;
+use geometry::{Rect, RectExt, Point, Size};
+use style::Component;
+use color::*;
+use draw::text::TextState;
+use text_layout::{Align, Wrap};
+use resources::font::FontDescriptor;
+
+const GUTTER_PADDING: f32 = 8.0;
+const TEXT_PADDING: f32 = 6.0;
+const CARET_WIDTH: f32 = 2.0;
+/// Width of the gutter column holding the fold arrows
+const FOLD_WIDTH: f32 = 14.0;
+/// Fewest columns a wrapped line is wrapped to, however narrow the view
+const MIN_WRAP_COLUMNS: usize = 10;
+
+/// A caret and the text it has selected. Both ends are byte offsets into the text, `head` is
+/// where the caret is drawn and moves to, `anchor` is where the selection started.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct Caret {
+    pub anchor: usize,
+    pub head: usize,
+    /// Column to return to when moving up or down through shorter lines
+    goal_column: Option<usize>,
+}
+
+impl Caret {
+    pub fn new(offset: usize) -> Self {
+        Caret::select(offset, offset)
+    }
+    pub fn select(anchor: usize, head: usize) -> Self {
+        Caret {
+            anchor: anchor,
+            head: head,
+            goal_column: None,
+        }
+    }
+    pub fn range(&self) -> Range<usize> {
+        min(self.anchor, self.head)..max(self.anchor, self.head)
+    }
+    pub fn is_empty(&self) -> bool {
+        self.anchor == self.head
+    }
+}
+
+/// Line and column of a position in the text, both counted from zero, columns in characters
+#[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord)]
+pub struct TextPosition {
+    pub line: usize,
+    pub column: usize,
+}
+
+impl TextPosition {
+    pub fn new(line: usize, column: usize) -> Self {
+        TextPosition {
+            line: line,
+            column: column,
+        }
+    }
+}
+
+/// Ways to move the carets
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum CaretMotion {
+    Left,
+    Right,
+    Up,
+    Down,
+    /// First non-whitespace character of the line, or the start of the line if already there
+    LineStart,
+    LineEnd,
+    TextStart,
+    TextEnd,
+}
+
+/// A replacement of `range` by `text`, leaving the caret `caret` bytes into `text`
+struct Edit {
+    range: Range<usize>,
+    text: String,
+    caret: usize,
+}
+
+/// Lines that can be folded, from a line ending with an opening bracket to the line with the
+/// closing bracket. Folding hides the lines between the two.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct FoldRegion {
+    pub start_line: usize,
+    pub end_line: usize,
+    /// Offset of the opening bracket
+    bracket: usize,
+}
+
+impl FoldRegion {
+    /// Lines hidden while folded
+    pub fn hidden_lines(&self) -> Range<usize> {
+        self.start_line + 1..self.end_line
+    }
+    pub fn hides(&self, line: usize) -> bool {
+        line > self.start_line && line < self.end_line
+    }
+}
+
+/// A row of the view, either part of a line or a placeholder for folded lines
+#[derive(Debug, Copy, Clone, PartialEq)]
+struct Row {
+    line: usize,
+    /// Byte range of the text shown in the row
+    start: usize,
+    end: usize,
+    /// Columns the text is indented by, continuation rows of wrapped lines line up with the
+    /// indentation of the line
+    indent: usize,
+    /// Number of lines folded away, for placeholder rows
+    folded: usize,
+}
+
+fn opening_bracket(ch: char) -> Option<char> {
+    match ch {
+        ')' => Some('('),
+        ']' => Some('['),
+        '}' => Some('{'),
+        _ => None,
+    }
+}
+
+fn closing_bracket(ch: char) -> Option<char> {
+    match ch {
+        '(' => Some(')'),
+        '[' => Some(']'),
+        '{' => Some('}'),
+        _ => None,
+    }
+}
+
+/// Draws monospaced text with line numbers, carets and selections, and edits it.
+/// Any number of carets can be placed, edits apply at each of them. Brackets next to a caret are
+/// highlighted along with the bracket they match.
+#[derive(Debug, Clone)]
+pub struct CodeViewState {
+    text: String,
+    /// Byte offset of the start of each line
+    line_starts: Vec<usize>,
+    /// Sorted, non-overlapping carets
+    carets: Vec<Caret>,
+    /// Index of the primary caret, the one most recently placed
+    primary: usize,
+    /// Offsets of the highlighted matching brackets
+    brackets: Vec<usize>,
+    fold_regions: Vec<FoldRegion>,
+    /// Offsets of the opening brackets of folded regions
+    folded: Vec<usize>,
+    /// First line of each region folded or unfolded since the last `take_fold_changes`
+    fold_changes: Vec<(usize, bool)>,
+    /// Rows as drawn, after wrapping and folding
+    rows: Vec<Row>,
+    /// Wrap long lines to the width of the view
+    wrap: bool,
+    /// Width the rows were laid out for
+    width: f32,
+    /// If false, the text can be selected but not changed
+    pub editable: bool,
+    /// Inserted for each level of indentation
+    pub indent: String,
+    pub font: FontDescriptor,
+    pub font_size: f32,
+    pub show_line_numbers: bool,
+    /// Show fold arrows in the gutter
+    pub folding: bool,
+    pub text_color: Color,
+    pub background_color: Color,
+    pub gutter_color: Color,
+    pub line_number_color: Color,
+    pub selection_color: Color,
+    pub caret_color: Color,
+    pub bracket_color: Color,
+    bounds: Rect,
+}
+
+impl Component for CodeViewState {
+    fn name() -> String {
+        String::from("code_view")
+    }
+}
+
+impl Default for CodeViewState {
+    fn default() -> Self {
+        CodeViewState {
+            text: String::new(),
+            line_starts: vec![0],
+            carets: vec![Caret::new(0)],
+            primary: 0,
+            brackets: Vec::new(),
+            fold_regions: Vec::new(),
+            folded: Vec::new(),
+            fold_changes: Vec::new(),
+            rows: Vec::new(),
+            wrap: false,
+            width: 0.0,
+            editable: false,
+            indent: "    ".to_owned(),
+            font: FontDescriptor::from_family("Hack"),
+            font_size: 16.0,
+            show_line_numbers: true,
+            folding: true,
+            text_color: GRAY_10,
+            background_color: WHITE,
+            gutter_color: GRAY_90,
+            line_number_color: GRAY_50,
+            selection_color: Color(0xB4D5FEFF),
+            caret_color: BLACK,
+            bracket_color: Color(0xFFE08AFF),
+            bounds: Rect::zero(),
+        }
+    }
+}
+
+impl CodeViewState {
+    pub fn new(text: &str) -> Self {
+        let mut state = CodeViewState::default();
+        state.set_text(text);
+        state
+    }
+    pub fn text(&self) -> &str {
+        &self.text
+    }
+    /// Replace the text, leaving a single caret at the start
+    pub fn set_text(&mut self, text: &str) {
+        self.unfold_all();
+        self.text = text.to_owned();
+        self.update_lines();
+        self.carets = vec![Caret::new(0)];
+        self.primary = 0;
+        self.carets_changed();
+    }
+    /// Wrap lines too long for the view, continuation rows are indented like the start of the line
+    pub fn set_wrap(&mut self, wrap: bool) {
+        self.wrap = wrap;
+        self.update_rows();
+    }
+    pub fn wrap(&self) -> bool {
+        self.wrap
+    }
+    /// Set the width of the view, which long lines are wrapped to
+    pub fn set_width(&mut self, width: f32) {
+        if width != self.width {
+            self.width = width;
+            self.update_rows();
+        }
+    }
+    pub fn carets(&self) -> &[Caret] {
+        &self.carets
+    }
+    pub fn primary_caret(&self) -> Caret {
+        self.carets[self.primary]
+    }
+    pub fn line_count(&self) -> usize {
+        self.line_starts.len()
+    }
+    /// Byte range of line `line`, without its line break
+    pub fn line_range(&self, line: usize) -> Range<usize> {
+        let start = self.line_starts[line];
+        let end = self.line_starts.get(line + 1).map_or(self.text.len(), |next| next - 1);
+        start..end
+    }
+    pub fn line(&self, line: usize) -> &str {
+        &self.text[self.line_range(line)]
+    }
+    pub fn position(&self, offset: usize) -> TextPosition {
+        let line = match self.line_starts.binary_search(&offset) {
+            Ok(line) => line,
+            Err(next) => next - 1,
+        };
+        let column = self.text[self.line_starts[line]..offset].chars().count();
+        TextPosition::new(line, column)
+    }
+    /// Offset of `position`, columns past the end of the line are moved to the end of the line
+    pub fn offset(&self, position: TextPosition) -> usize {
+        let line = min(position.line, self.line_count() - 1);
+        let range = self.line_range(line);
+        self.text[range.clone()].char_indices().nth(position.column).map_or(range.end, |(index, _)| range.start + index)
+    }
+
+    pub fn line_height(&self) -> f32 {
+        self.text_state("", BLACK).line_height()
+    }
+    /// Advance of each character, the font is expected to be monospaced
+    pub fn char_width(&self) -> f32 {
+        self.text_state("0000000000", BLACK).measure().width / 10.0
+    }
+    fn line_numbers_width(&self) -> f32 {
+        if self.show_line_numbers {
+            let digits = self.line_count().to_string().len().max(2);
+            digits as f32 * self.char_width() + GUTTER_PADDING * 2.0
+        } else {
+            0.0
+        }
+    }
+    fn gutter_width(&self) -> f32 {
+        self.line_numbers_width() + if self.folding { FOLD_WIDTH } else { 0.0 }
+    }
+    /// Size needed to show all the text. Wrapped text only needs room for a few columns.
+    pub fn content_size(&self) -> Size {
+        let columns = if self.wrap {
+            MIN_WRAP_COLUMNS
+        } else {
+            (0..self.line_count()).map(|line| self.line(line).chars().count()).max().unwrap_or(0)
+        };
+        Size::new(
+            self.gutter_width() + TEXT_PADDING * 2.0 + (columns + 1) as f32 * self.char_width(),
+            self.rows.len() as f32 * self.line_height())
+    }
+    /// Position of the character boundary nearest `point`, in the bounds the view was last drawn in.
+    /// The column can be past the end of the line.
+    pub fn position_at(&self, point: Point) -> TextPosition {
+        let row = ((point.y - self.bounds.top()) / self.line_height()).floor().max(0.0) as usize;
+        let row = self.rows[min(row, self.rows.len() - 1)];
+        if row.folded > 0 {
+            return self.position(self.line_range(row.line - 1).end);
+        }
+        let text_left = self.bounds.left() + self.gutter_width() + TEXT_PADDING;
+        let visual_column = ((point.x - text_left) / self.char_width()).round().max(0.0) as usize;
+        let offset = self.row_offset(&row, visual_column);
+        let mut position = self.position(offset);
+        let row_columns = self.text[row.start..row.end].chars().count();
+        let column = visual_column.saturating_sub(row.indent);
+        if column > row_columns && row.end == self.line_range(row.line).end {
+            position.column += column - row_columns;
+        }
+        position
+    }
+    /// The fold region whose arrow is at `point`, if any
+    pub fn fold_arrow_at(&self, point: Point) -> Option<FoldRegion> {
+        let left = self.bounds.left() + self.line_numbers_width();
+        if !self.folding || point.x < left || point.x > left + FOLD_WIDTH {
+            return None;
+        }
+        let row = ((point.y - self.bounds.top()) / self.line_height()).floor();
+        if row < 0.0 {
+            return None;
+        }
+        let row = self.rows.get(row as usize)?;
+        if row.folded > 0 || row.start != self.line_starts[row.line] {
+            return None;
+        }
+        self.fold_region(row.line)
+    }
+    pub fn offset_at(&self, point: Point) -> usize {
+        self.offset(self.position_at(point))
+    }
+
+    fn update_lines(&mut self) {
+        self.line_starts = ::std::iter::once(0)
+            .chain(self.text.match_indices('\n').map(|(index, _)| index + 1))
+            .collect();
+        self.update_fold_regions();
+        let lost: Vec<usize> = self.folded.iter().cloned()
+            .filter(|&bracket| !self.fold_regions.iter().any(|region| region.bracket == bracket))
+            .collect();
+        for bracket in lost {
+            let line = self.position(bracket).line;
+            self.fold_changes.push((line, false));
+            self.folded.retain(|&folded| folded != bracket);
+        }
+        self.update_rows();
+    }
+    fn update_fold_regions(&mut self) {
+        let mut regions = Vec::new();
+        let mut open = Vec::new();
+        let mut line = 0;
+        for (offset, ch) in self.text.char_indices() {
+            if ch == '\n' {
+                line += 1;
+            } else if closing_bracket(ch).is_some() {
+                open.push((offset, ch, line));
+            } else if let Some(opening) = opening_bracket(ch) {
+                if let Some((bracket, start_ch, start_line)) = open.pop() {
+                    let line_end = self.line_range(start_line).end;
+                    let ends_line = self.text[bracket + 1..line_end].trim().is_empty();
+                    if start_ch == opening && ends_line && line > start_line + 1 {
+                        regions.push(FoldRegion { start_line: start_line, end_line: line, bracket: bracket });
+                    }
+                }
+            }
+        }
+        regions.sort_by_key(|region| region.start_line);
+        self.fold_regions = regions;
+    }
+    /// The outermost fold region starting on `line`
+    fn fold_region(&self, line: usize) -> Option<FoldRegion> {
+        self.fold_regions.iter().filter(|region| region.start_line == line)
+            .max_by_key(|region| region.end_line).cloned()
+    }
+    fn is_region_folded(&self, region: &FoldRegion) -> bool {
+        self.folded.contains(&region.bracket)
+    }
+    fn wrap_columns(&self) -> usize {
+        let width = self.width - self.gutter_width() - TEXT_PADDING * 2.0;
+        max((width / self.char_width()).floor() as usize, MIN_WRAP_COLUMNS)
+    }
+    fn update_rows(&mut self) {
+        let columns = if self.wrap { Some(self.wrap_columns()) } else { None };
+        let mut rows = Vec::new();
+        let mut line = 0;
+        while line < self.line_count() {
+            self.push_line_rows(line, columns, &mut rows);
+            let folded = self.fold_regions.iter()
+                .filter(|region| region.start_line == line && self.is_region_folded(region))
+                .max_by_key(|region| region.end_line).cloned();
+            match folded {
+                Some(region) => {
+                    let hidden = region.hidden_lines();
+                    let start = self.line_starts[hidden.start];
+                    rows.push(Row { line: hidden.start, start: start, end: start, indent: 0, folded: hidden.len() });
+                    line = region.end_line;
+                }
+                None => line += 1,
+            }
+        }
+        self.rows = rows;
+    }
+    /// Add the rows showing `line`, wrapped to `columns` if given
+    fn push_line_rows(&self, line: usize, columns: Option<usize>, rows: &mut Vec<Row>) {
+        let range = self.line_range(line);
+        let mut row = Row { line: line, start: range.start, end: range.end, indent: 0, folded: 0 };
+        let columns = match columns {
+            Some(columns) if self.text[range.clone()].chars().count() > columns => columns,
+            _ => {
+                rows.push(row);
+                return;
+            }
+        };
+        let indent = self.leading_whitespace(line).chars().count();
+        let continuation_indent = if indent <= columns / 2 { indent } else { 0 };
+        loop {
+            let width = columns - row.indent;
+            let limit = match self.text[row.start..range.end].char_indices().nth(width) {
+                Some((index, _)) => row.start + index,
+                None => {
+                    rows.push(row);
+                    return;
+                }
+            };
+            // break after the last space that fits, or mid word if there isn't one
+            let end = match self.text[row.start..limit].rfind(' ') {
+                Some(index) if index > 0 => row.start + index + 1,
+                _ => limit,
+            };
+            rows.push(Row { end: end, ..row });
+            row = Row { start: end, indent: continuation_indent, ..row };
+        }
+    }
+    /// Index of the row showing `offset`
+    fn row_of(&self, offset: usize) -> usize {
+        match self.rows.binary_search_by_key(&offset, |row| row.start) {
+            Ok(index) => index,
+            Err(next) => next.saturating_sub(1),
+        }
+    }
+    /// Column `offset` is drawn at, counting the row's indent
+    fn visual_column(&self, row: &Row, offset: usize) -> usize {
+        row.indent + self.text[row.start..offset].chars().count()
+    }
+    /// Offset drawn nearest `visual_column` in `row`
+    fn row_offset(&self, row: &Row, visual_column: usize) -> usize {
+        let column = visual_column.saturating_sub(row.indent);
+        self.text[row.start..row.end].char_indices().nth(column).map_or(row.end, |(index, _)| row.start + index)
+    }
+    /// Offset reached by moving a row up or down from `offset`, keeping to `visual_column`
+    fn vertical_offset(&self, offset: usize, visual_column: usize, down: bool) -> usize {
+        let mut index = self.row_of(offset);
+        loop {
+            if down {
+                if index + 1 == self.rows.len() {
+                    return self.text.len();
+                }
+                index += 1;
+            } else {
+                if index == 0 {
+                    return 0;
+                }
+                index -= 1;
+            }
+            let row = self.rows[index];
+            if row.folded == 0 {
+                return self.row_offset(&row, visual_column);
+            }
+        }
+    }
+    fn prev_boundary(&self, offset: usize) -> usize {
+        self.text[..offset].char_indices().next_back().map_or(0, |(index, _)| index)
+    }
+    fn next_boundary(&self, offset: usize) -> usize {
+        self.text[offset..].chars().next().map_or(offset, |ch| offset + ch.len_utf8())
+    }
+    fn char_before(&self, offset: usize) -> Option<char> {
+        self.text[..offset].chars().next_back()
+    }
+    fn char_after(&self, offset: usize) -> Option<char> {
+        self.text[offset..].chars().next()
+    }
+    fn leading_whitespace(&self, line: usize) -> &str {
+        let text = self.line(line);
+        let end = text.find(|ch: char| !ch.is_whitespace()).unwrap_or(text.len());
+        &text[..end]
+    }
+
+    /// Sort the carets and merge any that overlap, keeping track of the primary caret
+    fn normalize_carets(&mut self) {
+        let primary = self.carets[self.primary];
+        self.carets.sort_by_key(|caret| caret.range().start);
+        let mut merged: Vec<Caret> = Vec::new();
+        let mut primary_index = 0;
+        for caret in self.carets.drain(..) {
+            let overlaps = merged.last().map_or(false, |last| {
+                let last = last.range();
+                let range = caret.range();
+                range.start < last.end || (range.start == last.end && (caret.is_empty() || last.start == last.end))
+            });
+            if overlaps {
+                let last = merged.last_mut().unwrap();
+                let end = max(last.range().end, caret.range().end);
+                let start = last.range().start;
+                *last = if last.head < last.anchor { Caret::select(end, start) } else { Caret::select(start, end) };
+            } else {
+                merged.push(caret);
+            }
+            if caret == primary {
+                primary_index = merged.len() - 1;
+            }
+        }
+        self.carets = merged;
+        self.primary = primary_index;
+        self.carets_changed();
+    }
+
+    fn carets_changed(&mut self) {
+        self.reveal_carets();
+        self.update_brackets();
+    }
+    /// Unfold regions hiding a caret
+    fn reveal_carets(&mut self) {
+        let hiding: Vec<FoldRegion> = self.fold_regions.iter().filter(|region| {
+            self.is_region_folded(region) && self.carets.iter().any(|caret| {
+                region.hides(self.position(caret.head).line)
+            })
+        }).cloned().collect();
+        if !hiding.is_empty() {
+            for region in hiding {
+                self.folded.retain(|&bracket| bracket != region.bracket);
+                self.fold_changes.push((region.start_line, false));
+            }
+            self.update_rows();
+        }
+    }
+    /// Highlight brackets next to each caret, along with their matches
+    fn update_brackets(&mut self) {
+        let mut brackets = Vec::new();
+        for caret in &self.carets {
+            let after = self.char_after(caret.head).map(|ch| (caret.head, ch));
+            let before = self.char_before(caret.head).map(|ch| (self.prev_boundary(caret.head), ch));
+            for &(offset, ch) in after.iter().chain(before.iter()) {
+                if let Some(matching) = self.matching_bracket(offset, ch) {
+                    brackets.push(offset);
+                    brackets.push(matching);
+                    break;
+                }
+            }
+        }
+        self.brackets = brackets;
+    }
+    /// Offset of the bracket matching the bracket `ch` at `offset`, if it's a bracket and has a match
+    fn matching_bracket(&self, offset: usize, ch: char) -> Option<usize> {
+        let mut depth = 0;
+        if let Some(close) = closing_bracket(ch) {
+            for (index, next) in self.text[offset..].char_indices() {
+                if next == ch {
+                    depth += 1;
+                } else if next == close {
+                    depth -= 1;
+                    if depth == 0 {
+                        return Some(offset + index);
+                    }
+                }
+            }
+        } else if let Some(open) = opening_bracket(ch) {
+            for (index, prev) in self.text[..offset + ch.len_utf8()].char_indices().rev() {
+                if prev == ch {
+                    depth += 1;
+                } else if prev == open {
+                    depth -= 1;
+                    if depth == 0 {
+                        return Some(index);
+                    }
+                }
+            }
+        }
+        None
+    }
+
+    /// Apply one edit per caret, in order, moving each caret to the end of its edit
+    fn apply_edits(&mut self, edits: Vec<Edit>) {
+        // folded regions move with the text, and unfold if their opening bracket is edited
+        let mut unfolded = Vec::new();
+        let folded = self.folded.iter().filter_map(|&bracket| {
+            let mut shift = 0isize;
+            for edit in &edits {
+                if bracket >= edit.range.start && bracket < edit.range.end {
+                    unfolded.push(self.position(bracket).line);
+                    return None;
+                }
+                if bracket >= edit.range.end {
+                    shift += edit.text.len() as isize - (edit.range.end - edit.range.start) as isize;
+                }
+            }
+            Some((bracket as isize + shift) as usize)
+        }).collect();
+        self.folded = folded;
+        self.fold_changes.extend(unfolded.into_iter().map(|line| (line, false)));
+        let mut delta = 0isize;
+        let mut carets = Vec::new();
+        for edit in edits {
+            let start = (edit.range.start as isize + delta) as usize;
+            let end = (edit.range.end as isize + delta) as usize;
+            self.text.drain(start..end);
+            self.text.insert_str(start, &edit.text);
+            carets.push(Caret::new(start + edit.caret));
+            delta += edit.text.len() as isize - (end - start) as isize;
+        }
+        self.carets = carets;
+        self.update_lines();
+        self.normalize_carets();
+    }
+
+    /// Type `text` at each caret, replacing any selections
+    pub fn insert(&mut self, text: &str) {
+        if !self.editable {
+            return;
+        }
+        let edits = self.carets.iter().map(|caret| Edit {
+            range: caret.range(),
+            text: text.to_owned(),
+            caret: text.len(),
+        }).collect();
+        self.apply_edits(edits);
+    }
+    /// Break the line at each caret, indenting the new line like the line it was split from.
+    /// After an opening bracket the new line is indented one more level, and a matching closing
+    /// bracket right after the caret moves to a line of its own.
+    pub fn newline(&mut self) {
+        if !self.editable {
+            return;
+        }
+        let edits = self.carets.iter().map(|caret| {
+            let range = caret.range();
+            let line = self.position(range.start).line;
+            let mut indent = self.leading_whitespace(line).to_owned();
+            // only the whitespace before the caret carries over
+            let before_caret = range.start - self.line_starts[line];
+            if before_caret < indent.len() && indent.is_char_boundary(before_caret) {
+                indent.truncate(before_caret);
+            }
+            let opened = self.char_before(range.start).and_then(closing_bracket);
+            match opened {
+                Some(close) => {
+                    let inner = format!("\n{}{}", indent, self.indent);
+                    let text = if self.char_after(range.end) == Some(close) {
+                        format!("{}\n{}", inner, indent)
+                    } else {
+                        inner.clone()
+                    };
+                    Edit { range: range, text: text, caret: inner.len() }
+                }
+                None => {
+                    let text = format!("\n{}", indent);
+                    let caret = text.len();
+                    Edit { range: range, text: text, caret: caret }
+                }
+            }
+        }).collect();
+        self.apply_edits(edits);
+    }
+    /// Delete the selection or the character before each caret
+    pub fn backspace(&mut self) {
+        if !self.editable {
+            return;
+        }
+        let edits = self.carets.iter().map(|caret| {
+            let range = if caret.is_empty() { self.prev_boundary(caret.head)..caret.head } else { caret.range() };
+            Edit { range: range, text: String::new(), caret: 0 }
+        }).collect();
+        self.apply_edits(edits);
+    }
+    /// Delete the selection or the character after each caret
+    pub fn delete(&mut self) {
+        if !self.editable {
+            return;
+        }
+        let edits = self.carets.iter().map(|caret| {
+            let range = if caret.is_empty() { caret.head..self.next_boundary(caret.head) } else { caret.range() };
+            Edit { range: range, text: String::new(), caret: 0 }
+        }).collect();
+        self.apply_edits(edits);
+    }
+
+    /// Move every caret, extending their selections if `extend` is true
+    pub fn move_carets(&mut self, motion: CaretMotion, extend: bool) {
+        let carets: Vec<Caret> = self.carets.iter().map(|&caret| {
+            let mut goal_column = None;
+            let head = match motion {
+                CaretMotion::Left if !extend && !caret.is_empty() => caret.range().start,
+                CaretMotion::Right if !extend && !caret.is_empty() => caret.range().end,
+                CaretMotion::Left => self.prev_boundary(caret.head),
+                CaretMotion::Right => self.next_boundary(caret.head),
+                CaretMotion::Up | CaretMotion::Down => {
+                    let row = self.rows[self.row_of(caret.head)];
+                    let column = caret.goal_column.unwrap_or_else(|| self.visual_column(&row, caret.head));
+                    goal_column = Some(column);
+                    self.vertical_offset(caret.head, column, motion == CaretMotion::Down)
+                }
+                CaretMotion::LineStart => {
+                    let line = self.position(caret.head).line;
+                    let start = self.line_starts[line];
+                    let indented = start + self.leading_whitespace(line).len();
+                    if caret.head == indented { start } else { indented }
+                }
+                CaretMotion::LineEnd => self.line_range(self.position(caret.head).line).end,
+                CaretMotion::TextStart => 0,
+                CaretMotion::TextEnd => self.text.len(),
+            };
+            Caret {
+                anchor: if extend { caret.anchor } else { head },
+                head: head,
+                goal_column: goal_column,
+            }
+        }).collect();
+        self.carets = carets;
+        self.normalize_carets();
+    }
+    /// Replace all the carets with one at `offset`
+    pub fn set_caret(&mut self, offset: usize) {
+        self.carets = vec![Caret::new(offset)];
+        self.primary = 0;
+        self.carets_changed();
+    }
+    /// Add a caret at `offset`, it becomes the primary caret
+    pub fn add_caret(&mut self, offset: usize) {
+        self.carets.push(Caret::new(offset));
+        self.primary = self.carets.len() - 1;
+        self.normalize_carets();
+    }
+    /// Add a caret on the line above the topmost caret, or below the bottommost one
+    pub fn add_caret_vertical(&mut self, down: bool) {
+        let caret = if down { *self.carets.last().unwrap() } else { self.carets[0] };
+        let position = self.position(caret.head);
+        let line = if down { position.line + 1 } else { position.line.wrapping_sub(1) };
+        if line < self.line_count() {
+            let column = caret.goal_column.unwrap_or(position.column);
+            let offset = self.offset(TextPosition::new(line, column));
+            self.add_caret(offset);
+            self.carets[self.primary].goal_column = Some(column);
+        }
+    }
+    /// Move the head of the primary caret to `offset`, extending its selection
+    pub fn select_to(&mut self, offset: usize) {
+        let anchor = self.carets[self.primary].anchor;
+        self.carets[self.primary] = Caret::select(anchor, offset);
+        self.normalize_carets();
+    }
+    /// Replace the carets with a rectangular selection between two positions, one caret per line
+    pub fn select_block(&mut self, from: TextPosition, to: TextPosition) {
+        let lines = min(from.line, to.line)..max(from.line, to.line) + 1;
+        self.carets = lines.map(|line| {
+            let mut caret = Caret::select(
+                self.offset(TextPosition::new(line, from.column)),
+                self.offset(TextPosition::new(line, to.column)));
+            caret.goal_column = Some(to.column);
+            caret
+        }).collect();
+        self.primary = if to.line >= from.line { self.carets.len() - 1 } else { 0 };
+        self.carets_changed();
+    }
+    /// Remove all but the primary caret
+    pub fn single_caret(&mut self) {
+        let caret = self.carets[self.primary];
+        self.carets = vec![caret];
+        self.primary = 0;
+        self.carets_changed();
+    }
+    pub fn select_all(&mut self) {
+        self.carets = vec![Caret::select(0, self.text.len())];
+        self.primary = 0;
+        self.carets_changed();
+    }
+    pub fn fold_regions(&self) -> &[FoldRegion] {
+        &self.fold_regions
+    }
+    /// First lines of the folded regions
+    pub fn folded_lines(&self) -> Vec<usize> {
+        self.fold_regions.iter().filter(|region| self.is_region_folded(region))
+            .map(|region| region.start_line).collect()
+    }
+    pub fn is_folded(&self, line: usize) -> bool {
+        self.fold_region(line).map_or(false, |region| self.is_region_folded(&region))
+    }
+    /// Fold or unfold the region starting on `line`, if there is one. Carets in the folded lines
+    /// move to the end of `line`.
+    pub fn set_folded(&mut self, line: usize, folded: bool) {
+        let region = match self.fold_region(line) {
+            Some(region) => region,
+            None => return,
+        };
+        if folded == self.is_region_folded(&region) {
+            return;
+        }
+        self.fold_changes.push((line, folded));
+        if folded {
+            self.folded.push(region.bracket);
+            let line_end = self.line_range(line).end;
+            let hidden = self.line_starts[region.start_line + 1]..self.line_starts[region.end_line];
+            for caret in &mut self.carets {
+                let range = caret.range();
+                if range.end > hidden.start && range.start < hidden.end {
+                    *caret = Caret::new(line_end);
+                }
+            }
+            self.update_rows();
+            self.normalize_carets();
+        } else {
+            self.folded.retain(|&bracket| bracket != region.bracket);
+            self.update_rows();
+        }
+    }
+    pub fn toggle_folded(&mut self, line: usize) {
+        let folded = self.is_folded(line);
+        self.set_folded(line, !folded);
+    }
+    /// Fold every region that isn't inside another region
+    pub fn fold_all(&mut self) {
+        let outer: Vec<usize> = self.fold_regions.iter().filter(|region| {
+            !self.fold_regions.iter().any(|outer| outer.hides(region.start_line))
+        }).map(|region| region.start_line).collect();
+        for line in outer {
+            self.set_folded(line, true);
+        }
+    }
+    pub fn unfold_all(&mut self) {
+        for line in self.folded_lines() {
+            self.fold_changes.push((line, false));
+        }
+        self.folded.clear();
+        self.update_rows();
+    }
+    /// First line of each region folded or unfolded since the last call, and whether it's folded
+    pub fn take_fold_changes(&mut self) -> Vec<(usize, bool)> {
+        ::std::mem::replace(&mut self.fold_changes, Vec::new())
+    }
+    /// Text of each selection, one per line
+    pub fn selected_text(&self) -> String {
+        self.carets.iter().filter(|caret| !caret.is_empty())
+            .map(|caret| &self.text[caret.range()])
+            .collect::<Vec<_>>().join("\n")
+    }
+
+    fn text_state(&self, text: &str, color: Color) -> TextState {
+        TextState {
+            text: text.to_owned(),
+            font: self.font.clone(),
+            font_size: self.font_size,
+            text_color: color,
+            wrap: Wrap::NoWrap,
+            align: Align::Start,
+            ..TextState::default()
+        }
+    }
+}
+
+/// Bounds of the characters in `columns`, on the line at `top`
+fn column_rect(text_left: f32, top: f32, columns: Range<usize>, char_width: f32, line_height: f32) -> Rect {
+    Rect::new(
+        Point::new(text_left + columns.start as f32 * char_width, top),
+        Size::new((columns.end - columns.start) as f32 * char_width, line_height))
+}
+
+impl Draw for CodeViewState {
+    fn draw(&mut self, bounds: Rect, crop_to: Rect, renderer: &mut RenderBuilder) {
+        self.bounds = bounds;
+        self.set_width(bounds.width());
+        let line_height = self.line_height();
+        let char_width = self.char_width();
+        let numbers_width = self.line_numbers_width();
+        let gutter_width = self.gutter_width();
+        let text_left = bounds.left() + gutter_width + TEXT_PADDING;
+        renderer.builder.push_rect(&PrimitiveInfo::new(bounds), self.background_color.into());
+        if gutter_width > 0.0 {
+            let gutter = Rect::new(bounds.origin, Size::new(gutter_width, bounds.height()));
+            renderer.builder.push_rect(&PrimitiveInfo::new(gutter), self.gutter_color.into());
+        }
+
+        let first = ((crop_to.top() - bounds.top()) / line_height).floor().max(0.0) as usize;
+        let last = min(((crop_to.bottom() - bounds.top()) / line_height).ceil().max(0.0) as usize, self.rows.len());
+        for index in first..last {
+            let row = self.rows[index];
+            let top = bounds.top() + index as f32 * line_height;
+            if row.folded > 0 {
+                self.draw_placeholder(&row, Point::new(text_left, top), renderer);
+                continue;
+            }
+            let line_end = self.line_range(row.line).end;
+
+            for caret in &self.carets {
+                let selection = caret.range();
+                if selection.start == selection.end || selection.start > row.end || selection.end < row.start {
+                    continue;
+                }
+                let start = self.visual_column(&row, max(selection.start, row.start));
+                let end = self.visual_column(&row, min(selection.end, row.end));
+                // selections continuing onto the next line include the line break
+                let end = if selection.end > row.end && row.end == line_end { end + 1 } else { end };
+                let rect = column_rect(text_left, top, start..end, char_width, line_height);
+                renderer.builder.push_rect(&PrimitiveInfo::new(rect), self.selection_color.into());
+            }
+            for &bracket in &self.brackets {
+                if bracket >= row.start && bracket < row.end {
+                    let column = self.visual_column(&row, bracket);
+                    let rect = column_rect(text_left, top, column..column + 1, char_width, line_height);
+                    renderer.builder.push_rect(&PrimitiveInfo::new(rect), self.bracket_color.into());
+                }
+            }
+
+            if row.start == self.line_starts[row.line] {
+                if self.show_line_numbers {
+                    let mut number = self.text_state(&(row.line + 1).to_string(), self.line_number_color);
+                    number.align = Align::End;
+                    let number_bounds = Rect::new(
+                        Point::new(bounds.left(), top),
+                        Size::new(numbers_width - GUTTER_PADDING, line_height));
+                    number.draw(number_bounds, crop_to, renderer);
+                }
+                if self.folding {
+                    if let Some(region) = self.fold_region(row.line) {
+                        let center = Point::new(bounds.left() + numbers_width + FOLD_WIDTH / 2.0, top + line_height / 2.0);
+                        push_fold_arrow(center, self.is_region_folded(&region), self.line_number_color, renderer);
+                    }
+                }
+            } else {
+                // continuation marker, a hook leading into the wrapped text
+                let marker_right = text_left + row.indent as f32 * char_width - 2.0;
+                let marker_left = marker_right - char_width;
+                let middle = top + line_height / 2.0;
+                let stem = Rect::new(Point::new(marker_left, top + line_height * 0.2), Size::new(1.0, middle - top - line_height * 0.2));
+                renderer.builder.push_rect(&PrimitiveInfo::new(stem), self.line_number_color.into());
+                render::draw_horizontal_line(middle, marker_left, marker_right, self.line_number_color, renderer);
+            }
+
+            let text = &self.text[row.start..row.end];
+            if !text.is_empty() {
+                let mut text = self.text_state(text, self.text_color);
+                let text_x = text_left + row.indent as f32 * char_width;
+                let text_bounds = Rect::new(Point::new(text_x, top), Size::new((bounds.right() - text_x).max(0.0), line_height));
+                text.draw(text_bounds, crop_to, renderer);
+            }
+
+            if self.editable {
+                for caret in &self.carets {
+                    if caret.head >= row.start && caret.head <= row.end && self.row_of(caret.head) == index {
+                        let column = self.visual_column(&row, caret.head);
+                        let caret_rect = Rect::new(
+                            Point::new(text_left + column as f32 * char_width - CARET_WIDTH / 2.0, top),
+                            Size::new(CARET_WIDTH, line_height));
+                        renderer.builder.push_rect(&PrimitiveInfo::new(caret_rect), self.caret_color.into());
+                    }
+                }
+            }
+        }
+    }
+}
+
+impl CodeViewState {
+    /// Draw the row standing in for folded lines, at the indentation of the first hidden line
+    fn draw_placeholder(&self, row: &Row, origin: Point, renderer: &mut RenderBuilder) {
+        let indent = self.leading_whitespace(row.line).chars().count();
+        let label = format!("... {} lines", row.folded);
+        let mut text = self.text_state(&label, self.line_number_color);
+        let size = Size::new(text.measure().width + TEXT_PADDING * 2.0, self.line_height());
+        let bounds = Rect::new(Point::new(origin.x + indent as f32 * self.char_width(), origin.y), size);
+        renderer.builder.push_rect(&PrimitiveInfo::new(bounds), self.gutter_color.into());
+        let text_bounds = Rect::new(Point::new(bounds.left() + TEXT_PADDING, bounds.top()), size);
+        text.draw(text_bounds, bounds, renderer);
+    }
+}
+
+/// Draw a small triangle centered on `center`, pointing right if `folded`, otherwise down
+fn push_fold_arrow(center: Point, folded: bool, color: Color, renderer: &mut RenderBuilder) {
+    let size = 4;
+    for strip in 0..size {
+        let half_width = (size - strip) as f32;
+        let offset = strip as f32 - size as f32 / 2.0;
+        let rect = if folded {
+            Rect::new(Point::new(center.x + offset, center.y - half_width), Size::new(1.0, half_width * 2.0))
+        } else {
+            Rect::new(Point::new(center.x - half_width, center.y + offset), Size::new(half_width * 2.0, 1.0))
+        };
+        renderer.builder.push_rect(&PrimitiveInfo::new(rect), color.into());
+    }
+}
+
+/// Bounds of the characters in `columns`, on the line at `top`
+fn column_rect(text_left: f32, top: f32, columns: Range<usize>, char_width: f32, line_height: f32) -> Rect {
+    Rect::new(
+        Point::new(text_left + columns.start as f32 * char_width, top),
+        Size::new((columns.end - columns.start) as f32 * char_width, line_height))
+}
+
+impl Draw for CodeViewState {
+    fn draw(&mut self, bounds: Rect, crop_to: Rect, renderer: &mut RenderBuilder) {
+        self.bounds = bounds;
+        let line_height = self.line_height();
+        let char_width = self.char_width();
+        let gutter_width = self.gutter_width();
+        let text_left = bounds.left() + gutter_width + TEXT_PADDING;
+        renderer.builder.push_rect(&PrimitiveInfo::new(bounds), self.background_color.into());
+        if self.show_line_numbers {
+            let gutter = Rect::new(bounds.origin, Size::new(gutter_width, bounds.height()));
+            renderer.builder.push_rect(&PrimitiveInfo::new(gutter), self.gutter_color.into());
+        }
+
+        let first = ((crop_to.top() - bounds.top()) / line_height).floor().max(0.0) as usize;
+        let last = min(((crop_to.bottom() - bounds.top()) / line_height).ceil().max(0.0) as usize, self.line_count());
+        for line in first..last {
+            let top = bounds.top() + line as f32 * line_height;
+            let range = self.line_range(line);
+
+            for caret in &self.carets {
+                let selection = caret.range();
+                if selection.start == selection.end || selection.start > range.end || selection.end < range.start {
+                    continue;
+                }
+                let start = self.position(max(selection.start, range.start)).column;
+                let end = self.position(min(selection.end, range.end)).column;
+                // selections continuing onto the next line include the line break
+                let end = if selection.end > range.end { end + 1 } else { end };
+                let rect = column_rect(text_left, top, start..end, char_width, line_height);
+                renderer.builder.push_rect(&PrimitiveInfo::new(rect), self.selection_color.into());
+            }
+            for &bracket in &self.brackets {
+                if bracket >= range.start && bracket < range.end {
+                    let column = self.position(bracket).column;
+                    let rect = column_rect(text_left, top, column..column + 1, char_width, line_height);
+                    renderer.builder.push_rect(&PrimitiveInfo::new(rect), self.bracket_color.into());
+                }
+            }
+
+            if self.show_line_numbers {
+                let mut number = self.text_state(&(line + 1).to_string(), self.line_number_color);
+                number.align = Align::End;
+                let number_bounds = Rect::new(
+                    Point::new(bounds.left(), top),
+                    Size::new(gutter_width - GUTTER_PADDING, line_height));
+                number.draw(number_bounds, crop_to, renderer);
+            }
+            let text = self.line(line);
+            if !text.is_empty() {
+                let mut text = self.text_state(text, self.text_color);
+                let text_bounds = Rect::new(Point::new(text_left, top), Size::new(bounds.right() - text_left, line_height));
+                text.draw(text_bounds, crop_to, renderer);
+            }
+
+            if self.editable {
+                for caret in &self.carets {
+                    if caret.head >= range.start && caret.head <= range.end {
+                        let column = self.position(caret.head).column;
+                        let caret_rect = Rect::new(
+                            Point::new(text_left + column as f32 * char_width - CARET_WIDTH / 2.0, top),
+                            Size::new(CARET_WIDTH, line_height));
+                        renderer.builder.push_rect(&PrimitiveInfo::new(caret_rect), self.caret_color.into());
+                    }
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,205 @@
+//! Conditional formatting, styles derived from data values by rules, ex. negative numbers in red,
+//! a background shading from white to green with the size of a value, or an arrow showing whether
+//! a value is up or down.
+//!
+//! A `ConditionalFormat` is a list of rules, evaluated when a value is bound to a widget, ex. when
+//! a cell's value changes or a list item is built, never while drawing. Rules are in priority
+//! order: each part of the style, the background, color and icon, comes from the first rule that
+//! sets it. Gradients and icon sets can span a fixed range, or the range of the data, found by
+//! `ConditionalFormat::fit`.
+//!
+//! Cells are formatted by sending them `SetConditionalFormat`, list items are given the style of
+//! their value with `ListItemBuilder::set_value_style`, and line chart series color their points
+//! with `Series::set_format`.
+
+use color::Color;
+use draw::rect::RectStyle;
+use draw::text::TextStyle;
+
+/// The style given to a value by a `ConditionalFormat`, parts not set by any rule are left as
+/// they are
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ValueStyle {
+    pub background: Option<Color>,
+    /// Color of the text showing the value, or of its point on a chart
+    pub color: Option<Color>,
+    /// Written before the value, ex. "▲"
+    pub icon: Option<String>,
+}
+
+impl ValueStyle {
+    pub fn new() -> Self {
+        Self::default()
+    }
+    pub fn background(mut self, color: Color) -> Self {
+        self.background = Some(color);
+        self
+    }
+    pub fn color(mut self, color: Color) -> Self {
+        self.color = Some(color);
+        self
+    }
+    pub fn icon(mut self, icon: &str) -> Self {
+        self.icon = Some(icon.to_owned());
+        self
+    }
+    /// Fill in the parts not yet set from `other`
+    fn or(&mut self, other: ValueStyle) {
+        self.background = self.background.or(other.background);
+        self.color = self.color.or(other.color);
+        self.icon = self.icon.take().or(other.icon);
+    }
+    /// The background as a rect style, to merge with the style of the widget showing the value.
+    /// Styles are used rather than updating the draw state, so the background survives the
+    /// widget's props changing.
+    pub fn rect_style(&self) -> RectStyle {
+        RectStyle {
+            background_color: self.background,
+            ..RectStyle::default()
+        }
+    }
+    /// The color as a text style, to merge with the style of the text showing the value
+    pub fn text_style(&self) -> TextStyle {
+        TextStyle {
+            text_color: self.color,
+            ..TextStyle::default()
+        }
+    }
+    /// `text` with the icon, if any, before it
+    pub fn decorate(&self, text: &str) -> String {
+        match self.icon {
+            Some(ref icon) => format!("{}\u{a0}{}", icon, text),
+            None => text.to_owned(),
+        }
+    }
+}
+
+/// Which values a threshold rule applies to
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum Condition {
+    Above(f64),
+    Below(f64),
+    /// Between two values, inclusive
+    Between(f64, f64),
+    Equal(f64),
+}
+
+impl Condition {
+    pub fn matches(&self, value: f64) -> bool {
+        match *self {
+            Condition::Above(threshold) => value > threshold,
+            Condition::Below(threshold) => value < threshold,
+            Condition::Between(low, high) => value >= low && value <= high,
+            Condition::Equal(other) => value == other,
+        }
+    }
+}
+
+/// A rule of a `ConditionalFormat`
+#[derive(Debug, Clone, PartialEq)]
+pub enum FormatRule {
+    /// A style for the values meeting a condition
+    Threshold(Condition, ValueStyle),
+    /// A background shading through `colors`, evenly spaced over `range`, or the range of the data
+    /// if it's `None`
+    Gradient {
+        range: Option<(f64, f64)>,
+        colors: Vec<Color>,
+    },
+    /// Icons for equal parts of `range`, or of the range of the data if it's `None`, from the
+    /// lowest values to the highest
+    Icons {
+        range: Option<(f64, f64)>,
+        icons: Vec<String>,
+    },
+}
+
+impl FormatRule {
+    /// Red, yellow and green, for low to high values
+    pub fn traffic_light() -> Self {
+        FormatRule::Gradient {
+            range: None,
+            colors: vec![Color(0xF8696BFF), Color(0xFFEB84FF), Color(0x63BE7BFF)],
+        }
+    }
+    /// Down, sideways and up arrows, for low to high values
+    pub fn arrows() -> Self {
+        FormatRule::Icons {
+            range: None,
+            icons: vec!["▼".to_owned(), "►".to_owned(), "▲".to_owned()],
+        }
+    }
+
+    fn style(&self, value: f64, data_range: Option<(f64, f64)>) -> ValueStyle {
+        // where the value falls in the rule's range, from 0.0 to 1.0
+        let position = |range: Option<(f64, f64)>| {
+            range.or(data_range).map(|(low, high)| {
+                if high > low { ((value - low) / (high - low)).max(0.0).min(1.0) } else { 0.5 }
+            })
+        };
+        match *self {
+            FormatRule::Threshold(condition, ref style) => {
+                if condition.matches(value) { style.clone() } else { ValueStyle::default() }
+            }
+            FormatRule::Gradient { range, ref colors } => {
+                let background = position(range).and_then(|position| {
+                    let last = colors.len().checked_sub(1)?;
+                    let scaled = position as f32 * last as f32;
+                    let index = (scaled.floor() as usize).min(last.saturating_sub(1));
+                    let next = (index + 1).min(last);
+                    Some(colors[index].mix(colors[next], scaled - index as f32))
+                });
+                ValueStyle { background: background, ..ValueStyle::default() }
+            }
+            FormatRule::Icons { range, ref icons } => {
+                let icon = position(range).and_then(|position| {
+                    let index = ((position * icons.len() as f64) as usize).min(icons.len().checked_sub(1)?);
+                    Some(icons[index].clone())
+                });
+                ValueStyle { icon: icon, ..ValueStyle::default() }
+            }
+        }
+    }
+}
+
+/// Rules deriving the style of values, see the [module docs](index.html)
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ConditionalFormat {
+    rules: Vec<FormatRule>,
+    /// The range of the data, for rules without a range of their own
+    data_range: Option<(f64, f64)>,
+}
+
+impl ConditionalFormat {
+    pub fn new() -> Self {
+        Self::default()
+    }
+    /// Add a rule, after the rules already added in priority
+    pub fn add_rule(&mut self, rule: FormatRule) -> &mut Self {
+        self.rules.push(rule);
+        self
+    }
+    /// Span gradients and icon sets without a range of their own over the range of `values`
+    pub fn fit<I: IntoIterator<Item = f64>>(&mut self, values: I) -> &mut Self {
+        self.data_range = values.into_iter().filter(|value| value.is_finite()).fold(None, |range, value| {
+            Some(range.map_or((value, value), |(low, high): (f64, f64)| (low.min(value), high.max(value))))
+        });
+        self
+    }
+    /// The style of `value`
+    pub fn style(&self, value: f64) -> ValueStyle {
+        let mut style = ValueStyle::default();
+        if value.is_nan() {
+            return style;
+        }
+        for rule in &self.rules {
+            style.or(rule.style(value, self.data_range));
+        }
+        style
+    }
+}
+
+/// Sent to a widget showing values, ex. cells, to format them with a `ConditionalFormat`, or
+/// `None` to stop formatting them
+#[derive(Debug, Clone)]
+pub struct SetConditionalFormat(pub Option<ConditionalFormat>);
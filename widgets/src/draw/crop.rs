@@ -0,0 +1,266 @@
+use webrender::api::{PrimitiveInfo, BorderWidths, BorderSide, BorderStyle, NormalBorder, BorderRadius, BorderDetails};
+
+use render::{self, RenderBuilder};
+use widget::draw::Draw;
+use geometry::{Rect, RectExt, Point, Size, Vector};
+use style::Component;
+use color::*;
+
+/// Smallest the selection can be resized to, in window coordinates
+const MIN_SELECTION: f32 = 8.0;
+
+/// Part of a crop selection being dragged
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum CropHandle {
+    Move,
+    Left,
+    Right,
+    Top,
+    Bottom,
+    TopLeft,
+    TopRight,
+    BottomLeft,
+    BottomRight,
+}
+
+impl CropHandle {
+    /// Which horizontal edge the handle moves, -1 for the left, 1 for the right, 0 for neither
+    fn horizontal(&self) -> i8 {
+        match *self {
+            CropHandle::Left | CropHandle::TopLeft | CropHandle::BottomLeft => -1,
+            CropHandle::Right | CropHandle::TopRight | CropHandle::BottomRight => 1,
+            _ => 0,
+        }
+    }
+    /// Which vertical edge the handle moves, -1 for the top, 1 for the bottom, 0 for neither
+    fn vertical(&self) -> i8 {
+        match *self {
+            CropHandle::Top | CropHandle::TopLeft | CropHandle::TopRight => -1,
+            CropHandle::Bottom | CropHandle::BottomLeft | CropHandle::BottomRight => 1,
+            _ => 0,
+        }
+    }
+}
+
+/// Draws a crop selection over content: the area outside it dimmed, an outline with handles on
+/// its corners and edges, and rule of thirds guides.
+///
+/// The selection is kept in content coordinates, ex. pixels of an image, so it stays over the same
+/// part of the content when the content is moved or scaled. The content is drawn in `content_rect`
+/// and is `content_size` units across.
+#[derive(Debug, Clone)]
+pub struct CropState {
+    /// The selected region, in content coordinates
+    pub selection: Rect,
+    /// Where the content is drawn, in window coordinates, `None` if it fills the bounds
+    pub content_rect: Option<Rect>,
+    /// Size of the content in its own units, `None` to use the size it's drawn at
+    pub content_size: Option<Size>,
+    /// Width divided by height the selection is locked to, if any
+    pub aspect_ratio: Option<f32>,
+    /// Draw lines dividing the selection into thirds
+    pub guides: bool,
+    pub dim_color: Color,
+    pub border: (f32, Color),
+    /// Width and height of the handles drawn on the corners and edges
+    pub handle_size: f32,
+}
+
+impl Default for CropState {
+    fn default() -> Self {
+        CropState {
+            selection: Rect::zero(),
+            content_rect: None,
+            content_size: None,
+            aspect_ratio: None,
+            guides: true,
+            dim_color: Color(0x00000099),
+            border: (1.0, WHITE),
+            handle_size: 8.0,
+        }
+    }
+}
+
+impl Component for CropState {
+    fn name() -> String {
+        String::from("crop")
+    }
+}
+
+impl CropState {
+    pub fn new() -> Self {
+        Self::default()
+    }
+    /// Where the content is drawn within `bounds`
+    pub fn content_rect(&self, bounds: Rect) -> Rect {
+        self.content_rect.unwrap_or(bounds)
+    }
+    /// Size of the content in its own units
+    pub fn content_size(&self, bounds: Rect) -> Size {
+        self.content_size.unwrap_or_else(|| self.content_rect(bounds).size)
+    }
+    /// Window coordinates per content unit, along each axis
+    fn scale(&self, bounds: Rect) -> Vector {
+        let (rect, size) = (self.content_rect(bounds), self.content_size(bounds));
+        let scale = |shown: f32, size: f32| if size > 0.0 { shown / size } else { 1.0 };
+        Vector::new(scale(rect.width(), size.width), scale(rect.height(), size.height))
+    }
+    /// Convert a point in window coordinates to content coordinates
+    pub fn to_content(&self, point: Point, bounds: Rect) -> Point {
+        let (origin, scale) = (self.content_rect(bounds).origin, self.scale(bounds));
+        Point::new((point.x - origin.x) / scale.x, (point.y - origin.y) / scale.y)
+    }
+    /// The selection in window coordinates
+    pub fn selection_rect(&self, bounds: Rect) -> Rect {
+        let (origin, scale) = (self.content_rect(bounds).origin, self.scale(bounds));
+        Rect::new(
+            Point::new(origin.x + self.selection.left() * scale.x, origin.y + self.selection.top() * scale.y),
+            Size::new(self.selection.width() * scale.x, self.selection.height() * scale.y))
+    }
+    /// The part of the selection under `point`, if any. Handles extend a little outside the selection.
+    pub fn handle_at(&self, point: Point, bounds: Rect) -> Option<CropHandle> {
+        let rect = self.selection_rect(bounds);
+        let reach = self.handle_size;
+        if !rect.inflate(reach / 2.0, reach / 2.0).contains(&point) {
+            return None;
+        }
+        let near = |edge: f32, value: f32| (edge - value).abs() <= reach / 2.0;
+        let horizontal = if near(rect.left(), point.x) { -1 } else if near(rect.right(), point.x) { 1 } else { 0 };
+        let vertical = if near(rect.top(), point.y) { -1 } else if near(rect.bottom(), point.y) { 1 } else { 0 };
+        Some(match (horizontal, vertical) {
+            (-1, -1) => CropHandle::TopLeft,
+            (1, -1) => CropHandle::TopRight,
+            (-1, 1) => CropHandle::BottomLeft,
+            (1, 1) => CropHandle::BottomRight,
+            (-1, _) => CropHandle::Left,
+            (1, _) => CropHandle::Right,
+            (_, -1) => CropHandle::Top,
+            (_, 1) => CropHandle::Bottom,
+            _ => CropHandle::Move,
+        })
+    }
+    /// Move the selection, or the edges `handle` moves, by `change` in window coordinates,
+    /// keeping the selection within the content and at its aspect ratio
+    pub fn drag(&mut self, handle: CropHandle, change: Vector, bounds: Rect) {
+        let scale = self.scale(bounds);
+        let change = Vector::new(change.x / scale.x, change.y / scale.y);
+        let content = self.content_size(bounds);
+        let selection = self.selection;
+        if handle == CropHandle::Move {
+            let x = (selection.left() + change.x).max(0.0).min(content.width - selection.width());
+            let y = (selection.top() + change.y).max(0.0).min(content.height - selection.height());
+            self.selection.origin = Point::new(x, y);
+            return;
+        }
+        let min = Size::new(MIN_SELECTION / scale.x, MIN_SELECTION / scale.y);
+        let (mut left, mut top, mut right, mut bottom) = (selection.left(), selection.top(), selection.right(), selection.bottom());
+        match handle.horizontal() {
+            -1 => left = (left + change.x).max(0.0).min(right - min.width),
+            1 => right = (right + change.x).min(content.width).max(left + min.width),
+            _ => (),
+        }
+        match handle.vertical() {
+            -1 => top = (top + change.y).max(0.0).min(bottom - min.height),
+            1 => bottom = (bottom + change.y).min(content.height).max(top + min.height),
+            _ => (),
+        }
+        self.selection = Rect::new(Point::new(left, top), Size::new(right - left, bottom - top));
+        if let Some(ratio) = self.aspect_ratio {
+            self.fit_aspect_ratio(handle, ratio, content);
+        }
+    }
+    /// Resize the selection to `ratio`, keeping the edges opposite `handle` in place, or the
+    /// center along an axis the handle doesn't move
+    fn fit_aspect_ratio(&mut self, handle: CropHandle, ratio: f32, content: Size) {
+        let rect = self.selection;
+        let (width, height) = (rect.width(), rect.height());
+        let width = match (handle.horizontal(), handle.vertical()) {
+            (0, _) => height * ratio,
+            (_, 0) => width,
+            _ => width.max(height * ratio),
+        };
+        // the most room there is from the fixed edge, or around the center
+        let room = |fixed: i8, start: f32, end: f32, center: f32, size: f32| match fixed {
+            -1 => end,
+            1 => size - start,
+            _ => 2.0 * center.min(size - center),
+        };
+        let center = rect.center();
+        let max_width = room(handle.horizontal(), rect.left(), rect.right(), center.x, content.width);
+        let max_height = room(handle.vertical(), rect.top(), rect.bottom(), center.y, content.height);
+        let width = width.min(max_width).min(max_height * ratio);
+        let height = width / ratio;
+        let left = match handle.horizontal() {
+            -1 => rect.right() - width,
+            1 => rect.left(),
+            _ => center.x - width / 2.0,
+        };
+        let top = match handle.vertical() {
+            -1 => rect.bottom() - height,
+            1 => rect.top(),
+            _ => center.y - height / 2.0,
+        };
+        self.selection = Rect::new(Point::new(left, top), Size::new(width, height));
+    }
+    /// Lock the selection to `ratio`, width divided by height, shrinking it around its center
+    pub fn set_aspect_ratio(&mut self, ratio: Option<f32>, bounds: Rect) {
+        self.aspect_ratio = ratio;
+        if let Some(ratio) = ratio {
+            let rect = self.selection;
+            let size = if rect.width() > rect.height() * ratio {
+                Size::new(rect.height() * ratio, rect.height())
+            } else {
+                Size::new(rect.width(), rect.width() / ratio)
+            };
+            let center = rect.center();
+            self.selection = Rect::new(Point::new(center.x - size.width / 2.0, center.y - size.height / 2.0), size);
+            let content = self.content_size(bounds);
+            self.fit_aspect_ratio(CropHandle::Move, ratio, content);
+        }
+    }
+}
+
+impl Draw for CropState {
+    fn draw(&mut self, bounds: Rect, _: Rect, renderer: &mut RenderBuilder) {
+        let content = self.content_rect(bounds);
+        let selection = self.selection_rect(bounds);
+        // everything above and below the selection, then either side of it
+        let dimmed = [
+            Rect::new(content.origin, Size::new(content.width(), selection.top() - content.top())),
+            Rect::new(Point::new(content.left(), selection.bottom()), Size::new(content.width(), content.bottom() - selection.bottom())),
+            Rect::new(Point::new(content.left(), selection.top()), Size::new(selection.left() - content.left(), selection.height())),
+            Rect::new(Point::new(selection.right(), selection.top()), Size::new(content.right() - selection.right(), selection.height())),
+        ];
+        for rect in dimmed.iter().filter(|rect| rect.width() > 0.0 && rect.height() > 0.0) {
+            renderer.builder.push_rect(&PrimitiveInfo::new(*rect), self.dim_color.into());
+        }
+        let (width, color) = self.border;
+        if self.guides {
+            let guide_color = Color(color.0 & 0xFFFF_FF00 | 0x80);
+            for third in 1..3 {
+                let x = selection.left() + selection.width() * third as f32 / 3.0;
+                let y = selection.top() + selection.height() * third as f32 / 3.0;
+                let vertical = Rect::new(Point::new(x, selection.top()), Size::new(width, selection.height()));
+                let horizontal = Rect::new(Point::new(selection.left(), y), Size::new(selection.width(), width));
+                renderer.builder.push_rect(&PrimitiveInfo::new(vertical), guide_color.into());
+                renderer.builder.push_rect(&PrimitiveInfo::new(horizontal), guide_color.into());
+            }
+        }
+        let widths = BorderWidths { left: width, right: width, top: width, bottom: width };
+        let side = BorderSide { color: color.into(), style: BorderStyle::Solid };
+        let border = NormalBorder { left: side, right: side, top: side, bottom: side, radius: BorderRadius::zero() };
+        renderer.builder.push_border(&PrimitiveInfo::new(selection), widths, BorderDetails::Normal(border));
+        let handle = Size::new(self.handle_size, self.handle_size);
+        let (center, half) = (selection.center(), self.handle_size / 2.0);
+        for &x in &[selection.left(), center.x, selection.right()] {
+            for &y in &[selection.top(), center.y, selection.bottom()] {
+                if x == center.x && y == center.y {
+                    continue;
+                }
+                let rect = Rect::new(Point::new(x - half, y - half), handle);
+                renderer.builder.push_rect(&PrimitiveInfo::new(rect), color.into());
+                render::draw_rect_outline(rect, GRAY_30, renderer);
+            }
+        }
+    }
+}
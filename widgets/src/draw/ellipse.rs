@@ -12,8 +12,8 @@ component_style!{pub struct EllipseState<name="ellipse", style=EllipseStyle> {
 
 impl Draw for EllipseState {
     fn draw(&mut self, bounds: Rect, _: Rect, renderer: &mut RenderBuilder) {
-        // rounding is a hack to prevent bug in webrender that produces artifacts around the corners
-        let bounds = bounds.round();
+        // bounds are snapped to device pixels, which also prevents a bug in webrender that
+        // produces artifacts around the corners
         if let Some((width, color)) = self.border {
             let width = if width < 2.0 { 2.0 } else { width };
             push_ellipse(renderer, bounds, bounds, color);
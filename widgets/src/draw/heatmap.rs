@@ -0,0 +1,134 @@
+use webrender::api::*;
+
+use render::RenderBuilder;
+use widget::draw::Draw;
+use resources::resources;
+use resources::image::ImageSource;
+use geometry::{Rect, Point, Size};
+use style::Component;
+use color::*;
+
+/// Maps values in the range `0.0..1.0` to colors, interpolating between color stops.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ColorScale {
+    /// Positions in `0.0..1.0` and their colors, sorted by position
+    pub stops: Vec<(f32, Color)>,
+}
+
+impl Default for ColorScale {
+    /// Dark blue through teal and green to yellow, readable in grayscale and by most color blind viewers
+    fn default() -> Self {
+        ColorScale::new(vec![
+            (0.0, Color(0x440154FF)),
+            (0.25, Color(0x3B528BFF)),
+            (0.5, Color(0x21918CFF)),
+            (0.75, Color(0x5EC962FF)),
+            (1.0, Color(0xFDE725FF)),
+        ])
+    }
+}
+
+impl ColorScale {
+    pub fn new(mut stops: Vec<(f32, Color)>) -> Self {
+        assert!(!stops.is_empty(), "color scale needs at least one stop");
+        stops.sort_by(|a, b| a.0.partial_cmp(&b.0).unwrap());
+        ColorScale { stops: stops }
+    }
+    /// Two color scale, from `low` at `0.0` to `high` at `1.0`
+    pub fn linear(low: Color, high: Color) -> Self {
+        ColorScale::new(vec![(0.0, low), (1.0, high)])
+    }
+    /// Color for `value`, values outside `0.0..1.0` get the first or last color
+    pub fn color_at(&self, value: f32) -> Color {
+        let first = self.stops[0];
+        if value.is_nan() || value <= first.0 {
+            return first.1;
+        }
+        for pair in self.stops.windows(2) {
+            let ((start, low), (end, high)) = (pair[0], pair[1]);
+            if value <= end {
+                let amount = if end > start { (value - start) / (end - start) } else { 1.0 };
+                return low.mix(high, amount);
+            }
+        }
+        self.stops[self.stops.len() - 1].1
+    }
+    /// RGBA8 bytes for `value`, as used for raw image data
+    pub fn rgba_at(&self, value: f32) -> [u8; 4] {
+        let Color(color) = self.color_at(value);
+        [(color >> 24) as u8, (color >> 16) as u8, (color >> 8) as u8, color as u8]
+    }
+}
+
+/// Draws an image generated from a matrix, one pixel per cell, stretched without smoothing to fill the bounds.
+#[derive(Debug, Clone)]
+pub struct HeatmapState {
+    pub image: ImageSource,
+}
+
+impl Component for HeatmapState {
+    fn name() -> String {
+        String::from("heatmap")
+    }
+}
+
+impl HeatmapState {
+    pub fn new(image: ImageSource) -> Self {
+        HeatmapState {
+            image: image,
+        }
+    }
+}
+
+impl Draw for HeatmapState {
+    fn draw(&mut self, bounds: Rect, _: Rect, renderer: &mut RenderBuilder) {
+        if let Ok(info) = resources().image_loader.get_image(&self.image) {
+            renderer.builder.push_image(
+                &PrimitiveInfo::new(bounds),
+                bounds.size,
+                LayoutSize::zero(),
+                ImageRendering::Pixelated,
+                AlphaType::Alpha,
+                info.key,
+            );
+        }
+    }
+}
+
+/// A vertical bar showing a `ColorScale`, with the high end at the top.
+#[derive(Debug, Clone)]
+pub struct ColorScaleState {
+    pub scale: ColorScale,
+    /// Number of bands the bar is drawn with
+    pub steps: usize,
+}
+
+impl Component for ColorScaleState {
+    fn name() -> String {
+        String::from("color_scale")
+    }
+}
+
+impl ColorScaleState {
+    pub fn new(scale: ColorScale) -> Self {
+        ColorScaleState {
+            scale: scale,
+            steps: 64,
+        }
+    }
+}
+
+impl Draw for ColorScaleState {
+    fn draw(&mut self, bounds: Rect, _: Rect, renderer: &mut RenderBuilder) {
+        let band_height = bounds.size.height / self.steps as f32;
+        for step in 0..self.steps {
+            let value = 1.0 - (step as f32 + 0.5) / self.steps as f32;
+            // overlap bands slightly so no gaps show between them
+            let overlap = if step + 1 < self.steps { 0.5 } else { 0.0 };
+            let band = Rect::new(
+                Point::new(bounds.origin.x, bounds.origin.y + step as f32 * band_height),
+                Size::new(bounds.size.width, band_height + overlap));
+            renderer.builder.push_rect(&PrimitiveInfo::new(band), self.scale.color_at(value).into());
+        }
+    }
+}
@@ -0,0 +1,198 @@
+use webrender::api::*;
+
+use render::{self, RenderBuilder};
+use widget::draw::Draw;
+use resources::resources;
+use resources::image::ImageSource;
+use geometry::{Rect, RectExt, Point, Size, Vector};
+use style::Component;
+use color::*;
+
+/// Name of the raw image the checkerboard is drawn from, two squares by two
+const CHECKERBOARD_IMAGE: &str = "limn_checkerboard";
+
+/// How an image viewer scales its image
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum ImageViewerMode {
+    /// Scaled to fit the viewer, refitting as it's resized
+    Fit,
+    /// One image pixel to each pixel
+    ActualSize,
+    /// Scaled by a factor
+    Zoom(f32),
+}
+
+/// Draws an image panned, zoomed and rotated in quarter turns within its bounds, over a
+/// checkerboard where it's transparent.
+#[derive(Clone, Debug)]
+pub struct ImageViewerState {
+    pub image: ImageSource,
+    pub mode: ImageViewerMode,
+    /// Offset of the center of the image from the center of the bounds
+    pub offset: Vector,
+    /// Quarter turns clockwise, from 0 to 3
+    pub rotation: u8,
+    /// Size of the squares of the checkerboard, `None` to not draw it
+    pub checkerboard: Option<f32>,
+    pub background_color: Color,
+}
+
+impl Component for ImageViewerState {
+    fn name() -> String {
+        String::from("image_viewer")
+    }
+}
+
+impl ImageViewerState {
+    pub fn new(image: ImageSource) -> Self {
+        load_checkerboard();
+        ImageViewerState {
+            image: image,
+            mode: ImageViewerMode::Fit,
+            offset: Vector::zero(),
+            rotation: 0,
+            checkerboard: Some(8.0),
+            background_color: GRAY_30,
+        }
+    }
+    /// Size of the image in pixels, zero if it can't be loaded
+    pub fn image_size(&self) -> Size {
+        resources().image_loader.get_image(&self.image).map(|info| {
+            Size::new(info.descriptor.size.width as f32, info.descriptor.size.height as f32)
+        }).unwrap_or_else(|_| Size::zero())
+    }
+    /// Size of the image as shown, before scaling, swapping width and height when turned sideways
+    pub fn rotated_size(&self) -> Size {
+        let size = self.image_size();
+        if self.rotation % 2 == 1 { Size::new(size.height, size.width) } else { size }
+    }
+    /// The scale the image is shown at in `bounds`
+    pub fn zoom(&self, bounds: Rect) -> f32 {
+        match self.mode {
+            ImageViewerMode::Fit => {
+                let size = self.rotated_size();
+                if size.width <= 0.0 || size.height <= 0.0 {
+                    1.0
+                } else {
+                    (bounds.width() / size.width).min(bounds.height() / size.height)
+                }
+            }
+            ImageViewerMode::ActualSize => 1.0,
+            ImageViewerMode::Zoom(zoom) => zoom,
+        }
+    }
+    /// Center of the image as shown in `bounds`
+    fn center(&self, bounds: Rect) -> Point {
+        if self.mode == ImageViewerMode::Fit { bounds.center() } else { bounds.center() + self.offset }
+    }
+    /// Where the image is shown in `bounds`, after turning it
+    pub fn image_rect(&self, bounds: Rect) -> Rect {
+        let size = self.rotated_size() * self.zoom(bounds);
+        let center = self.center(bounds);
+        Rect::new(Point::new(center.x - size.width / 2.0, center.y - size.height / 2.0), size)
+    }
+    /// Scale by `factor`, keeping the image under `anchor` where it is
+    pub fn zoom_by(&mut self, factor: f32, anchor: Point, bounds: Rect) {
+        let center = self.center(bounds);
+        let zoom = self.zoom(bounds);
+        let new_zoom = (zoom * factor).max(0.01).min(64.0);
+        let factor = new_zoom / zoom;
+        // the vector from the anchor to the center scales with the image
+        let new_center = anchor + (center - anchor) * factor;
+        self.offset = new_center - bounds.center();
+        self.mode = ImageViewerMode::Zoom(new_zoom);
+    }
+    /// Move the image by `change`, switching from fitting to zooming so it stays where it's put
+    pub fn pan(&mut self, change: Vector, bounds: Rect) {
+        if self.mode == ImageViewerMode::Fit {
+            self.mode = ImageViewerMode::Zoom(self.zoom(bounds));
+            self.offset = Vector::zero();
+        }
+        self.offset = self.offset + change;
+    }
+    /// Turn by `quarter_turns` clockwise, negative to turn counterclockwise
+    pub fn rotate(&mut self, quarter_turns: i32) {
+        self.rotation = ((i32::from(self.rotation) + quarter_turns) % 4 + 4) as u8 % 4;
+    }
+    /// The pixel of the image under `point` in `bounds`, if it's over the image
+    pub fn pixel_at(&self, point: Point, bounds: Rect) -> Option<(u32, u32)> {
+        let size = self.image_size();
+        let zoom = self.zoom(bounds);
+        let relative = (point - self.center(bounds)) / zoom;
+        // undo the rotation
+        let (x, y) = match self.rotation {
+            1 => (relative.y, -relative.x),
+            2 => (-relative.x, -relative.y),
+            3 => (-relative.y, relative.x),
+            _ => (relative.x, relative.y),
+        };
+        let (x, y) = (x + size.width / 2.0, y + size.height / 2.0);
+        if x >= 0.0 && y >= 0.0 && x < size.width && y < size.height {
+            Some((x as u32, y as u32))
+        } else {
+            None
+        }
+    }
+}
+
+/// Load the image the checkerboard is tiled from, if it isn't already
+fn load_checkerboard() {
+    let mut resources = resources();
+    if !resources.image_loader.images.contains_key(&ImageSource::bundled(CHECKERBOARD_IMAGE)) {
+        let (light, dark) = ([0xFF, 0xFF, 0xFF, 0xFF], [0xCC, 0xCC, 0xCC, 0xFF]);
+        let data = [light, dark, dark, light].iter().flat_map(|pixel| pixel.iter().cloned()).collect();
+        resources.image_loader.load_raw_image(CHECKERBOARD_IMAGE, 2, 2, data);
+    }
+}
+
+impl Draw for ImageViewerState {
+    fn draw(&mut self, bounds: Rect, _: Rect, renderer: &mut RenderBuilder) {
+        renderer.builder.push_rect(&PrimitiveInfo::new(bounds), self.background_color.into());
+        let key = match resources().image_loader.get_image(&self.image) {
+            Ok(info) => info.key,
+            Err(_) => return,
+        };
+        let image_size = self.image_size();
+        let size = image_size * self.zoom(bounds);
+        let center = self.center(bounds);
+        // drawn turned around its center, in coordinates relative to it
+        let degrees = f32::from(self.rotation) * 90.0;
+        render::push_rotation(center, degrees, renderer);
+        let image_rect = Rect::new(Point::new(-size.width / 2.0, -size.height / 2.0), size);
+        let clip = {
+            let relative = bounds.translate(&(Point::zero() - center));
+            // the bounds, turned back by the rotation
+            let (left, top, right, bottom) = (relative.left(), relative.top(), relative.right(), relative.bottom());
+            let (left, top, right, bottom) = match self.rotation {
+                1 => (top, -right, bottom, -left),
+                2 => (-right, -bottom, -left, -top),
+                3 => (-bottom, left, -top, right),
+                _ => (left, top, right, bottom),
+            };
+            Rect::new(Point::new(left, top), Size::new(right - left, bottom - top))
+        };
+        if let Some(visible) = image_rect.intersection(&clip) {
+            if let (Some(square), Ok(checkerboard)) = (self.checkerboard, resources().image_loader.get_image(&ImageSource::bundled(CHECKERBOARD_IMAGE))) {
+                renderer.builder.push_image(
+                    &PrimitiveInfo::with_clip_rect(image_rect, visible),
+                    Size::new(square * 2.0, square * 2.0),
+                    LayoutSize::zero(),
+                    ImageRendering::Pixelated,
+                    AlphaType::Alpha,
+                    checkerboard.key,
+                );
+            }
+            // pixels stay sharp when zoomed in far enough to see them
+            let rendering = if size.width > image_size.width * 2.0 { ImageRendering::Pixelated } else { ImageRendering::Auto };
+            renderer.builder.push_image(
+                &PrimitiveInfo::with_clip_rect(image_rect, visible),
+                size,
+                LayoutSize::zero(),
+                rendering,
+                AlphaType::Alpha,
+                key,
+            );
+        }
+        render::pop_rotation(renderer);
+    }
+}
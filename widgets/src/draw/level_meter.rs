@@ -0,0 +1,91 @@
+use std::time::{Duration, Instant};
+
+use webrender::api::PrimitiveInfo;
+
+use clock;
+use render::RenderBuilder;
+use widget::draw::Draw;
+use geometry::{Rect, RectExt, Point, Size};
+use style::Component;
+use color::*;
+
+/// A vertical level bar with peak hold, levels are in the range `0.0..1.0`.
+#[derive(Clone, Debug)]
+pub struct LevelMeterState {
+    pub level: f32,
+    pub peak: f32,
+    peak_time: Instant,
+    /// How long the peak marker stays at the highest level before falling back
+    pub peak_hold: Duration,
+    /// Levels above this are drawn in `warning_color`
+    pub warning_level: f32,
+    /// Levels above this are drawn in `clip_color`
+    pub clip_level: f32,
+    pub color: Color,
+    pub warning_color: Color,
+    pub clip_color: Color,
+    pub background_color: Color,
+}
+
+impl Component for LevelMeterState {
+    fn name() -> String {
+        String::from("level_meter")
+    }
+}
+
+impl Default for LevelMeterState {
+    fn default() -> Self {
+        LevelMeterState {
+            level: 0.0,
+            peak: 0.0,
+            peak_time: clock::now(),
+            peak_hold: Duration::from_millis(1500),
+            warning_level: 0.7,
+            clip_level: 0.9,
+            color: GREEN,
+            warning_color: YELLOW,
+            clip_color: RED,
+            background_color: GRAY_20,
+        }
+    }
+}
+
+impl LevelMeterState {
+    /// Set the current level, updating the held peak
+    pub fn set_level(&mut self, level: f32) {
+        self.level = level.max(0.0).min(1.0);
+        if self.level >= self.peak || clock::now().duration_since(self.peak_time) > self.peak_hold {
+            self.peak = self.level;
+            self.peak_time = clock::now();
+        }
+    }
+}
+
+impl Draw for LevelMeterState {
+    fn draw(&mut self, bounds: Rect, _: Rect, renderer: &mut RenderBuilder) {
+        renderer.builder.push_rect(&PrimitiveInfo::new(bounds), self.background_color.into());
+        let height = bounds.height();
+        let segments = [
+            (0.0, self.warning_level, self.color),
+            (self.warning_level, self.clip_level, self.warning_color),
+            (self.clip_level, 1.0, self.clip_color),
+        ];
+        for &(from, to, color) in &segments {
+            let to = to.min(self.level);
+            if to <= from {
+                continue;
+            }
+            let rect = Rect::new(
+                Point::new(bounds.left(), bounds.bottom() - to * height),
+                Size::new(bounds.width(), (to - from) * height));
+            renderer.builder.push_rect(&PrimitiveInfo::new(rect), color.into());
+        }
+        if self.peak > 0.0 {
+            let color = if self.peak >= self.clip_level { self.clip_color } else { WHITE };
+            let rect = Rect::new(
+                Point::new(bounds.left(), bounds.bottom() - self.peak * height),
+                Size::new(bounds.width(), 2.0));
+            renderer.builder.push_rect(&PrimitiveInfo::new(rect), color.into());
+        }
+    }
+}
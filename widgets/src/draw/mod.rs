@@ -0,0 +1,68 @@
+//! Contains common `Draw` state, basic drawing primitives
+
+pub mod rect;
+pub mod ellipse;
+pub mod text;
+pub mod text_path;
+pub mod image;
+pub mod image_viewer;
+pub mod thumbnail_grid;
+pub mod glcanvas;
+#[cfg(feature = "charts")]
+pub mod waveform;
+#[cfg(feature = "charts")]
+pub mod level_meter;
+#[cfg(feature = "charts")]
+pub mod ruler;
+#[cfg(feature = "maps")]
+pub mod tile_view;
+#[cfg(feature = "charts")]
+pub mod heatmap;
+#[cfg(feature = "charts")]
+pub mod chart;
+#[cfg(feature = "charts")]
+pub mod stat_chart;
+#[cfg(feature = "charts")]
+pub mod sparkline;
+pub mod path;
+pub mod conditional;
+pub mod chat;
+pub mod spotlight;
+pub mod crop;
+pub mod code_view;
+pub mod skeleton;
+
+pub mod prelude {
+    #[cfg(feature = "charts")]
+    pub use super::chart::{ChartState, ChartView, ChartAxes, Series, Decimation, ColumnSpan, decimate,
+                           Axis, AxisState, Annotation, nice_step, nice_ticks, nice_range, axis_ticks};
+    #[cfg(feature = "charts")]
+    pub use super::stat_chart::{HistogramState, BoxPlotState, Dataset, Binning, Bins, BoxStats, quantile};
+    #[cfg(feature = "charts")]
+    pub use super::sparkline::{SparklineState, SparklineStyle};
+    pub use super::chat::{ChatViewState, ChatMessage};
+    pub use super::conditional::{ConditionalFormat, FormatRule, Condition, ValueStyle, SetConditionalFormat};
+    pub use super::crop::{CropState, CropHandle};
+    pub use super::code_view::{CodeViewState, Caret, CaretMotion, TextPosition, FoldRegion};
+    pub use super::ellipse::{EllipseState, EllipseStyle};
+    pub use super::glcanvas::GLCanvasState;
+    #[cfg(feature = "charts")]
+    pub use super::heatmap::{HeatmapState, ColorScale, ColorScaleState};
+    pub use super::image::ImageState;
+    pub use super::image_viewer::{ImageViewerState, ImageViewerMode};
+    #[cfg(feature = "charts")]
+    pub use super::level_meter::LevelMeterState;
+    pub use super::path::{PathState, Path};
+    pub use super::rect::{RectState, RectStyle};
+    #[cfg(feature = "charts")]
+    pub use super::ruler::RulerState;
+    pub use super::skeleton::SkeletonState;
+    pub use super::spotlight::SpotlightState;
+    pub use super::text::{TextState, TextStyle, TextColumns, ColumnWidth, TextFloat, FloatSide};
+    pub use super::thumbnail_grid::{ThumbnailGridState, Thumbnail};
+    pub use super::text_path::{TextPathState, TextPathStyle, TextPath};
+    #[cfg(feature = "maps")]
+    pub use super::tile_view::{TileViewState, TileCoord};
+    #[cfg(feature = "charts")]
+    pub use super::waveform::WaveformState;
+}
@@ -0,0 +1,101 @@
+use webrender::api::PrimitiveInfo;
+
+use render::RenderBuilder;
+use widget::draw::Draw;
+use geometry::{Rect, Point, Size};
+use style::Component;
+use color::*;
+
+/// A line through a list of points, relative to the top left of the widget.
+/// Only horizontal and vertical segments are supported, as used for connectors between boxes.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Path {
+    pub points: Vec<Point>,
+    /// Draw an arrow head at the last point
+    pub arrow: bool,
+}
+
+impl Path {
+    pub fn new(points: Vec<Point>) -> Self {
+        Path {
+            points: points,
+            arrow: false,
+        }
+    }
+    pub fn arrow(points: Vec<Point>) -> Self {
+        Path {
+            points: points,
+            arrow: true,
+        }
+    }
+}
+
+/// Draws any number of `Path`s with the same width and color.
+#[derive(Clone, Debug)]
+pub struct PathState {
+    pub paths: Vec<Path>,
+    pub width: f32,
+    pub color: Color,
+}
+
+impl Component for PathState {
+    fn name() -> String {
+        String::from("path")
+    }
+}
+
+impl Default for PathState {
+    fn default() -> Self {
+        PathState {
+            paths: Vec::new(),
+            width: 1.0,
+            color: GRAY_20,
+        }
+    }
+}
+
+impl PathState {
+    fn push_segment(&self, start: Point, end: Point, renderer: &mut RenderBuilder) {
+        let half = self.width / 2.0;
+        let (min_x, max_x) = (start.x.min(end.x), start.x.max(end.x));
+        let (min_y, max_y) = (start.y.min(end.y), start.y.max(end.y));
+        // extend each segment by half the width so corners join
+        let rect = Rect::new(
+            Point::new(min_x - half, min_y - half),
+            Size::new(max_x - min_x + self.width, max_y - min_y + self.width));
+        renderer.builder.push_rect(&PrimitiveInfo::new(rect), self.color.into());
+    }
+    /// Arrow head at `tip`, pointing away from `from`, built from one pixel strips widening away from the tip
+    fn push_arrow_head(&self, from: Point, tip: Point, renderer: &mut RenderBuilder) {
+        let length = 3.0 + self.width * 2.0;
+        let horizontal = (tip.x - from.x).abs() >= (tip.y - from.y).abs();
+        let direction = if horizontal { (tip.x - from.x).signum() } else { (tip.y - from.y).signum() };
+        for strip in 0..length as u32 {
+            let distance = strip as f32;
+            let half_width = (distance + 1.0) * 0.6;
+            let rect = if horizontal {
+                let x = tip.x - direction * (distance + 1.0);
+                Rect::new(Point::new(x.min(x + direction), tip.y - half_width), Size::new(1.0, half_width * 2.0))
+            } else {
+                let y = tip.y - direction * (distance + 1.0);
+                Rect::new(Point::new(tip.x - half_width, y.min(y + direction)), Size::new(half_width * 2.0, 1.0))
+            };
+            renderer.builder.push_rect(&PrimitiveInfo::new(rect), self.color.into());
+        }
+    }
+}
+
+impl Draw for PathState {
+    fn draw(&mut self, bounds: Rect, _: Rect, renderer: &mut RenderBuilder) {
+        let offset = bounds.origin.to_vector();
+        for path in &self.paths {
+            let points: Vec<Point> = path.points.iter().map(|point| *point + offset).collect();
+            for segment in points.windows(2) {
+                self.push_segment(segment[0], segment[1], renderer);
+            }
+            if path.arrow && points.len() >= 2 {
+                self.push_arrow_head(points[points.len() - 2], points[points.len() - 1], renderer);
+            }
+        }
+    }
+}
@@ -1,7 +1,7 @@
 use webrender::api::{LocalClip, BorderRadius, ComplexClipRegion, PrimitiveInfo, ClipMode};
 
 use render::RenderBuilder;
-use widget::draw::Draw;
+use widget::draw::{Draw, DrawDescription};
 use geometry::{Rect, RectExt};
 use color::*;
 
@@ -13,9 +13,8 @@ component_style!{pub struct RectState<name="rect", style=RectStyle> {
 
 impl Draw for RectState {
     fn draw(&mut self, bounds: Rect, _: Rect, renderer: &mut RenderBuilder) {
-        // rounding is a hack to prevent bug in webrender that produces artifacts around the corners
-        //let bounds = Rect::new(bounds.origin.round(), bounds.size.round());
-        let bounds = bounds.round();
+        // bounds are snapped to device pixels, which also prevents a bug in webrender that
+        // produces artifacts around the corners
         if let Some((width, color)) = self.border {
             let width = if width < 2.0 { 2.0 } else { width };
             push_rect(renderer, bounds, color, bounds, self.corner_radius);
@@ -24,6 +23,12 @@ impl Draw for RectState {
             push_rect(renderer, bounds, self.background_color, bounds, self.corner_radius);
         };
     }
+    fn describe(&self) -> DrawDescription {
+        DrawDescription {
+            background_color: Some(self.background_color),
+            ..DrawDescription::default()
+        }
+    }
 }
 
 fn clip_rounded(rect: Rect, radius: f32) -> LocalClip {
@@ -0,0 +1,58 @@
+use webrender::api::PrimitiveInfo;
+
+use render::RenderBuilder;
+use widget::draw::Draw;
+use geometry::{Rect, RectExt, Point, Size};
+use style::Component;
+use color::*;
+
+/// Tick marks along a horizontal time axis, with a major tick every `major_interval` seconds.
+#[derive(Clone, Debug)]
+pub struct RulerState {
+    pub pixels_per_second: f32,
+    pub major_interval: f32,
+    /// Number of minor ticks between each major tick
+    pub subdivisions: u32,
+    pub color: Color,
+    pub background_color: Color,
+}
+
+impl Component for RulerState {
+    fn name() -> String {
+        String::from("ruler")
+    }
+}
+
+impl Default for RulerState {
+    fn default() -> Self {
+        RulerState {
+            pixels_per_second: 50.0,
+            major_interval: 1.0,
+            subdivisions: 4,
+            color: GRAY_30,
+            background_color: GRAY_90,
+        }
+    }
+}
+
+impl Draw for RulerState {
+    fn draw(&mut self, bounds: Rect, crop_to: Rect, renderer: &mut RenderBuilder) {
+        renderer.builder.push_rect(&PrimitiveInfo::new(bounds), self.background_color.into());
+        let spacing = self.pixels_per_second * self.major_interval / self.subdivisions as f32;
+        if spacing < 2.0 {
+            return;
+        }
+        // only draw the ticks that are visible
+        let first = ((crop_to.left() - bounds.left()) / spacing).floor().max(0.0) as u32;
+        let last = ((crop_to.right() - bounds.left()) / spacing).ceil() as u32;
+        for tick in first..last + 1 {
+            let x = bounds.left() + tick as f32 * spacing;
+            if x > bounds.right() {
+                break;
+            }
+            let height = if tick % self.subdivisions == 0 { bounds.height() } else { bounds.height() / 3.0 };
+            let rect = Rect::new(Point::new(x, bounds.bottom() - height), Size::new(1.0, height));
+            renderer.builder.push_rect(&PrimitiveInfo::new(rect), self.color.into());
+        }
+    }
+}
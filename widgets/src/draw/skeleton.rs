@@ -0,0 +1,82 @@
+use std::time::{Duration, Instant};
+
+use webrender::api::{PrimitiveInfo, GradientStop, ExtendMode, LayoutSize, ColorF};
+
+use clock;
+use render::{RenderBuilder, GpuTier};
+use widget::draw::Draw;
+use geometry::{Rect, RectExt, Point, Size};
+use style::Component;
+use color::*;
+
+/// A grey block standing in for content that's still loading, with a lighter band sweeping across
+/// it. Blocks created at the same time sweep together.
+#[derive(Clone, Debug)]
+pub struct SkeletonState {
+    pub color: Color,
+    pub shimmer_color: Color,
+    /// Width of the shimmer band
+    pub shimmer_width: f32,
+    /// Time for the band to cross the block, followed by the same time before the next sweep
+    pub period: Duration,
+    start: Instant,
+}
+
+impl Component for SkeletonState {
+    fn name() -> String {
+        String::from("skeleton")
+    }
+}
+
+impl Default for SkeletonState {
+    fn default() -> Self {
+        SkeletonState {
+            color: GRAY_80,
+            shimmer_color: GRAY_90,
+            shimmer_width: 120.0,
+            period: Duration::from_millis(1200),
+            start: clock::now(),
+        }
+    }
+}
+
+impl SkeletonState {
+    /// How far through the current sweep the band is, in the range `0.0..2.0`, past `1.0` the band
+    /// is waiting off the right edge
+    fn phase(&self) -> f32 {
+        let elapsed = clock::now().duration_since(self.start);
+        let elapsed = elapsed.as_secs() as f64 + elapsed.subsec_nanos() as f64 * 1e-9;
+        let period = self.period.as_secs() as f64 + self.period.subsec_nanos() as f64 * 1e-9;
+        ((elapsed / period) % 2.0) as f32
+    }
+}
+
+impl Draw for SkeletonState {
+    fn draw(&mut self, bounds: Rect, _: Rect, renderer: &mut RenderBuilder) {
+        renderer.builder.push_rect(&PrimitiveInfo::new(bounds), self.color.into());
+        let phase = self.phase();
+        // the shimmer is redrawn every frame, too often for a weak GPU
+        if phase > 1.0 || renderer.gpu_tier == GpuTier::Low {
+            return;
+        }
+        // the band starts fully off the left edge and ends fully off the right edge
+        let band_left = bounds.left() - self.shimmer_width + phase * (bounds.width() + self.shimmer_width);
+        let left = band_left.max(bounds.left());
+        let right = (band_left + self.shimmer_width).min(bounds.right());
+        if right <= left {
+            return;
+        }
+        let band = Rect::new(Point::new(left, bounds.top()), Size::new(right - left, bounds.height()));
+        let edge: ColorF = self.shimmer_color.into();
+        let stops = vec![
+            GradientStop { offset: 0.0, color: ColorF { a: 0.0, ..edge } },
+            GradientStop { offset: 0.5, color: edge },
+            GradientStop { offset: 1.0, color: ColorF { a: 0.0, ..edge } },
+        ];
+        // gradient points are relative to the primitive, which is the visible part of the band
+        let start = Point::new(band_left - left, 0.0);
+        let end = Point::new(band_left - left + self.shimmer_width, 0.0);
+        let gradient = renderer.builder.create_gradient(start, end, stops, ExtendMode::Clamp);
+        renderer.builder.push_gradient(&PrimitiveInfo::new(band), gradient, band.size, LayoutSize::zero());
+    }
+}
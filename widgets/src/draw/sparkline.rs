@@ -0,0 +1,142 @@
+use webrender::api::PrimitiveInfo;
+
+use render::RenderBuilder;
+use widget::draw::Draw;
+use geometry::{Rect, RectExt, Point, Size};
+use style::Component;
+use color::*;
+use draw::chart::{Series, ChartView, decimate};
+
+/// How a sparkline shows its values
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum SparklineStyle {
+    Line,
+    /// A bar from zero to each value
+    Bar,
+}
+
+/// Draws a small chart of a list of values, without axes or labels, scaled to fit its bounds.
+/// Lines with more values than pixels are decimated like the series of a line chart, bars are
+/// merged into one for each pixel column.
+#[derive(Clone, Debug)]
+pub struct SparklineState {
+    series: Series,
+    pub style: SparklineStyle,
+    pub color: Color,
+    pub line_width: f32,
+    /// Color of the dots marking the lowest and highest values, if they're marked
+    pub markers: Option<Color>,
+}
+
+impl Component for SparklineState {
+    fn name() -> String {
+        String::from("sparkline")
+    }
+}
+
+impl Default for SparklineState {
+    fn default() -> Self {
+        SparklineState {
+            series: Series::new("", Vec::new()),
+            style: SparklineStyle::Line,
+            color: GRAY_30,
+            line_width: 1.0,
+            markers: None,
+        }
+    }
+}
+
+impl SparklineState {
+    pub fn new(values: Vec<f32>) -> Self {
+        let mut state = SparklineState::default();
+        state.set_values(values);
+        state
+    }
+    /// Keep at most `max_values`, dropping the oldest as values are pushed
+    pub fn set_max_values(&mut self, max_values: usize) {
+        let series = ::std::mem::replace(&mut self.series, Series::new("", Vec::new()));
+        self.series = series.max_points(max_values);
+    }
+    pub fn set_values(&mut self, values: Vec<f32>) {
+        self.series.set_points(values.into_iter().enumerate().map(|(index, value)| (index as f32, value)).collect());
+    }
+    /// Append values after the last
+    pub fn push(&mut self, values: &[f32]) {
+        let next = self.series.last().map_or(0.0, |(x, _)| x + 1.0);
+        let points: Vec<(f32, f32)> = values.iter().enumerate().map(|(index, &value)| (next + index as f32, value)).collect();
+        self.series.extend(&points);
+    }
+    pub fn values<'a>(&'a self) -> Box<Iterator<Item = f32> + 'a> {
+        Box::new(self.series.points().iter().map(|&(_, value)| value))
+    }
+
+    /// The points of the lowest and highest values, the first of each if they're repeated
+    fn extremes(&self) -> Option<((f32, f32), (f32, f32))> {
+        let points = self.series.points();
+        let first = *points.front()?;
+        Some(points.iter().fold((first, first), |(low, high), &point| {
+            (if point.1 < low.1 { point } else { low }, if point.1 > high.1 { point } else { high })
+        }))
+    }
+
+    fn draw_bars(&self, view: ChartView, bounds: Rect, renderer: &mut RenderBuilder) {
+        let baseline = view.to_point(bounds, 0.0, 0.0).y;
+        let mut push_bar = |left: f32, width: f32, value: f32| {
+            let top = view.to_point(bounds, 0.0, value).y;
+            let rect = Rect::new(Point::new(left, top.min(baseline)), Size::new(width, (top - baseline).abs().max(1.0)));
+            if let Some(rect) = rect.intersection(&bounds) {
+                renderer.builder.push_rect(&PrimitiveInfo::new(rect), self.color.into());
+            }
+        };
+        let points = self.series.points();
+        let column_width = (view.x.1 - view.x.0) / bounds.width();
+        if column_width > 1.0 {
+            for (x, span) in decimate(points.iter().cloned(), column_width).spans(view.x.0, view.x.1) {
+                // the value furthest from the baseline
+                let value = if span.max.abs() >= span.min.abs() { span.max } else { span.min };
+                push_bar(view.to_point(bounds, x, 0.0).x.floor(), 1.0, value);
+            }
+        } else {
+            let bar_width = bounds.width() / points.len() as f32;
+            for &(x, value) in points {
+                let left = view.to_point(bounds, x - 0.5, 0.0).x;
+                push_bar(left + bar_width * 0.1, (bar_width * 0.8).max(1.0), value);
+            }
+        }
+    }
+}
+
+impl Draw for SparklineState {
+    fn draw(&mut self, bounds: Rect, _: Rect, renderer: &mut RenderBuilder) {
+        let (low, high) = match self.extremes() {
+            Some(extremes) => extremes,
+            None => return,
+        };
+        let first = self.series.points().front().map_or(0.0, |&(x, _)| x);
+        let last = self.series.last().map_or(0.0, |(x, _)| x);
+        // room for the line and markers at the top and bottom
+        let margin = self.line_width.max(if self.markers.is_some() { 3.0 } else { 0.0 });
+        let bounds = Rect::new(Point::new(bounds.left(), bounds.top() + margin),
+                               Size::new(bounds.width(), (bounds.height() - margin * 2.0).max(1.0)));
+        let y = if high.1 > low.1 { (low.1, high.1) } else { (low.1 - 1.0, high.1 + 1.0) };
+        let view = match self.style {
+            SparklineStyle::Line => ChartView::new((first, last.max(first + 1.0)), y),
+            // bars are centered on their values, and reach zero
+            SparklineStyle::Bar => ChartView::new((first - 0.5, last + 0.5), (y.0.min(0.0), y.1.max(0.0))),
+        };
+        match self.style {
+            SparklineStyle::Line => {
+                self.series.color = self.color;
+                self.series.draw(view, self.line_width, bounds, renderer);
+            }
+            SparklineStyle::Bar => self.draw_bars(view, bounds, renderer),
+        }
+        if let Some(marker_color) = self.markers {
+            for &(x, value) in &[low, high] {
+                let point = view.to_point(bounds, x, value);
+                let rect = Rect::new(Point::new(point.x - 1.5, point.y - 1.5), Size::new(3.0, 3.0));
+                renderer.builder.push_rect(&PrimitiveInfo::new(rect), marker_color.into());
+            }
+        }
+    }
+}
@@ -0,0 +1,73 @@
+use webrender::api::{PrimitiveInfo, BorderWidths, BorderSide, BorderStyle, NormalBorder, BorderRadius, BorderDetails};
+
+use render::RenderBuilder;
+use widget::draw::Draw;
+use geometry::{Rect, RectExt, Point, Size};
+use style::Component;
+use color::*;
+
+/// Dims everything within its bounds except for a cutout, used to draw attention to one widget.
+#[derive(Debug, Clone)]
+pub struct SpotlightState {
+    /// Area left undimmed, in window coordinates. If `None` the whole area is dimmed.
+    pub cutout: Option<Rect>,
+    /// Space left around the cutout
+    pub padding: f32,
+    pub dim_color: Color,
+    /// Outline drawn around the cutout
+    pub border: Option<(f32, Color)>,
+}
+
+impl Component for SpotlightState {
+    fn name() -> String {
+        String::from("spotlight")
+    }
+}
+
+impl Default for SpotlightState {
+    fn default() -> Self {
+        SpotlightState {
+            cutout: None,
+            padding: 4.0,
+            dim_color: Color(0x00000099),
+            border: Some((2.0, WHITE)),
+        }
+    }
+}
+
+impl Draw for SpotlightState {
+    fn draw(&mut self, bounds: Rect, _: Rect, renderer: &mut RenderBuilder) {
+        let cutout = match self.cutout {
+            Some(cutout) => {
+                let padding = self.padding;
+                Rect::new(
+                    Point::new(cutout.left() - padding, cutout.top() - padding),
+                    Size::new(cutout.width() + padding * 2.0, cutout.height() + padding * 2.0))
+            }
+            None => {
+                renderer.builder.push_rect(&PrimitiveInfo::new(bounds), self.dim_color.into());
+                return;
+            }
+        };
+        let left = cutout.left().max(bounds.left());
+        let right = cutout.right().min(bounds.right());
+        let top = cutout.top().max(bounds.top());
+        let bottom = cutout.bottom().min(bounds.bottom());
+        // everything above and below the cutout, then either side of it
+        let dimmed = [
+            Rect::new(bounds.origin, Size::new(bounds.width(), top - bounds.top())),
+            Rect::new(Point::new(bounds.left(), bottom), Size::new(bounds.width(), bounds.bottom() - bottom)),
+            Rect::new(Point::new(bounds.left(), top), Size::new(left - bounds.left(), bottom - top)),
+            Rect::new(Point::new(right, top), Size::new(bounds.right() - right, bottom - top)),
+        ];
+        for rect in dimmed.iter().filter(|rect| rect.width() > 0.0 && rect.height() > 0.0) {
+            renderer.builder.push_rect(&PrimitiveInfo::new(*rect), self.dim_color.into());
+        }
+        if let Some((width, color)) = self.border {
+            let widths = BorderWidths { left: width, right: width, top: width, bottom: width };
+            let side = BorderSide { color: color.into(), style: BorderStyle::Solid };
+            let border = NormalBorder { left: side, right: side, top: side, bottom: side, radius: BorderRadius::zero() };
+            renderer.builder.push_border(&PrimitiveInfo::new(cutout), widths, BorderDetails::Normal(border));
+        }
+    }
+}
@@ -0,0 +1,386 @@
+use webrender::api::PrimitiveInfo;
+
+use render::RenderBuilder;
+use widget::draw::Draw;
+use geometry::{Rect, RectExt, Point, Size};
+use style::Component;
+use color::*;
+use draw::chart::{ChartView, ChartAxes, nice_step, nice_range, draw_grid};
+
+/// Histograms never have more bins than this, however narrow they're asked to be
+const MAX_BINS: usize = 1000;
+/// Values further than this many interquartile ranges outside the box of a box plot are outliers
+const WHISKER_IQR: f32 = 1.5;
+
+/// A named set of values, shown by a histogram or box plot
+#[derive(Clone, Debug)]
+pub struct Dataset {
+    pub name: String,
+    pub color: Color,
+    pub visible: bool,
+    values: Vec<f32>,
+}
+
+impl Dataset {
+    /// Values that aren't finite are left out
+    pub fn new(name: &str, values: Vec<f32>) -> Self {
+        let mut dataset = Dataset {
+            name: name.to_owned(),
+            color: BLUE,
+            visible: true,
+            values: Vec::new(),
+        };
+        dataset.set_values(values);
+        dataset
+    }
+    pub fn color(mut self, color: Color) -> Self {
+        self.color = color;
+        self
+    }
+    pub fn values(&self) -> &[f32] {
+        &self.values
+    }
+    pub fn set_values(&mut self, values: Vec<f32>) {
+        self.values = values.into_iter().filter(|value| value.is_finite()).collect();
+    }
+}
+
+/// How the values of a histogram are split into bins
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum Binning {
+    /// Bins of a nice width chosen from the spread and number of values, by the Freedman-Diaconis
+    /// rule, or Sturges' rule for values that are mostly the same
+    Auto,
+    /// About this many bins, of a nice width
+    Count(usize),
+    Width(f32),
+}
+
+/// The bins of a histogram, shared by all its datasets so their bars line up
+#[derive(Clone, Debug, PartialEq)]
+pub struct Bins {
+    pub start: f32,
+    pub width: f32,
+    /// The number of values of each dataset in each bin
+    pub counts: Vec<Vec<usize>>,
+}
+
+impl Bins {
+    pub fn new(datasets: &[Dataset], binning: Binning) -> Self {
+        let mut values: Vec<f32> = datasets.iter().flat_map(|dataset| dataset.values.iter().cloned()).collect();
+        values.sort_by(|a, b| a.partial_cmp(b).unwrap());
+        let (min, max) = match (values.first(), values.last()) {
+            (Some(&min), Some(&max)) => (min, max),
+            _ => return Bins { start: 0.0, width: 1.0, counts: vec![Vec::new(); datasets.len()] },
+        };
+        let span = (max - min).max(::std::f32::EPSILON);
+        let width = match binning {
+            Binning::Auto => {
+                let iqr = quantile(&values, 0.75) - quantile(&values, 0.25);
+                let n = values.len() as f32;
+                if iqr > 0.0 {
+                    nice_step(2.0 * iqr / n.cbrt(), 1)
+                } else {
+                    nice_step(span, n.log2().ceil() as usize + 1)
+                }
+            }
+            Binning::Count(count) => nice_step(span, count),
+            Binning::Width(width) => width,
+        };
+        let width = width.max(span / MAX_BINS as f32);
+        let start = (min / width).floor() * width;
+        let len = (((max - start) / width).floor() as usize + 1).min(MAX_BINS);
+        let counts = datasets.iter().map(|dataset| {
+            let mut counts = vec![0; len];
+            for value in &dataset.values {
+                let bin = ((value - start) / width).floor().max(0.0) as usize;
+                counts[bin.min(len - 1)] += 1;
+            }
+            counts
+        }).collect();
+        Bins { start: start, width: width, counts: counts }
+    }
+    pub fn len(&self) -> usize {
+        self.counts.first().map_or(0, |counts| counts.len())
+    }
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+    pub fn end(&self) -> f32 {
+        self.start + self.len() as f32 * self.width
+    }
+}
+
+/// The value below which `fraction` of sorted `values` lie, interpolating between values
+pub fn quantile(sorted: &[f32], fraction: f32) -> f32 {
+    if sorted.is_empty() {
+        return ::std::f32::NAN;
+    }
+    let position = fraction.max(0.0).min(1.0) * (sorted.len() - 1) as f32;
+    let (below, above) = (position.floor() as usize, position.ceil() as usize);
+    sorted[below] + (sorted[above] - sorted[below]) * (position - below as f32)
+}
+
+/// The summary of a dataset drawn by a box plot
+#[derive(Clone, Debug, PartialEq)]
+pub struct BoxStats {
+    pub q1: f32,
+    pub median: f32,
+    pub q3: f32,
+    /// The ends of the whiskers, the most extreme values that aren't outliers
+    pub whiskers: (f32, f32),
+    pub outliers: Vec<f32>,
+}
+
+impl BoxStats {
+    /// `None` if there are no values
+    pub fn new(values: &[f32]) -> Option<Self> {
+        let mut sorted = values.to_vec();
+        sorted.sort_by(|a, b| a.partial_cmp(b).unwrap());
+        if sorted.is_empty() {
+            return None;
+        }
+        let (q1, median, q3) = (quantile(&sorted, 0.25), quantile(&sorted, 0.5), quantile(&sorted, 0.75));
+        let reach = (q3 - q1) * WHISKER_IQR;
+        let inside = |value: &&f32| **value >= q1 - reach && **value <= q3 + reach;
+        let low = sorted.iter().find(&inside).cloned().unwrap_or(q1);
+        let high = sorted.iter().rev().find(&inside).cloned().unwrap_or(q3);
+        Some(BoxStats {
+            q1: q1,
+            median: median,
+            q3: q3,
+            whiskers: (low, high),
+            outliers: sorted.iter().filter(|value| !inside(value)).cloned().collect(),
+        })
+    }
+}
+
+/// A chart of datasets, for the widgets of the statistical chart types to share
+pub(crate) trait StatChart: Draw + 'static {
+    fn datasets_mut(&mut self) -> &mut Vec<Dataset>;
+    /// Recalculate after the datasets change, and fit the view to them
+    fn refresh(&mut self);
+    fn view(&self) -> ChartView;
+    /// Change how values are binned, for charts that bin them
+    fn set_binning(&mut self, _: Binning) {}
+    /// Labels for the x axis, if it shows categories rather than values
+    fn categories(&self) -> Vec<(f32, String)> {
+        Vec::new()
+    }
+}
+
+fn push_rect(rect: Rect, color: Color, renderer: &mut RenderBuilder) {
+    renderer.builder.push_rect(&PrimitiveInfo::new(rect), color.into());
+}
+
+/// `color` at about a third of its opacity
+fn translucent(color: Color) -> Color {
+    Color(color.0 & 0xFFFF_FF00 | (color.0 & 0xFF) / 3)
+}
+
+/// Draws the bars of a histogram, the bars of each bin side by side, one for each visible dataset
+#[derive(Clone, Debug)]
+pub struct HistogramState {
+    datasets: Vec<Dataset>,
+    binning: Binning,
+    bins: Bins,
+    pub view: ChartView,
+    pub grid_color: Option<Color>,
+    pub background_color: Color,
+    /// Space between the bars of neighbouring bins, in pixels
+    pub bar_gap: f32,
+}
+
+impl Component for HistogramState {
+    fn name() -> String {
+        String::from("histogram")
+    }
+}
+
+impl HistogramState {
+    pub fn new(datasets: Vec<Dataset>, binning: Binning) -> Self {
+        let mut state = HistogramState {
+            bins: Bins::new(&datasets, binning),
+            datasets: datasets,
+            binning: binning,
+            view: ChartView::default(),
+            grid_color: Some(GRAY_90),
+            background_color: WHITE,
+            bar_gap: 1.0,
+        };
+        state.view = state.fit();
+        state
+    }
+    pub fn datasets(&self) -> &[Dataset] {
+        &self.datasets
+    }
+    pub fn bins(&self) -> &Bins {
+        &self.bins
+    }
+    pub fn set_binning(&mut self, binning: Binning) {
+        self.binning = binning;
+        self.refresh();
+    }
+    /// The view showing every bin, from zero to a little over the tallest visible bar
+    pub fn fit(&self) -> ChartView {
+        let tallest = self.bins.counts.iter().zip(&self.datasets)
+            .filter(|&(_, dataset)| dataset.visible)
+            .flat_map(|(counts, _)| counts.iter())
+            .cloned().max().unwrap_or(0);
+        ChartView::new((self.bins.start, self.bins.end()), nice_range((0.0, tallest.max(1) as f32), 5))
+    }
+}
+
+impl StatChart for HistogramState {
+    fn datasets_mut(&mut self) -> &mut Vec<Dataset> {
+        &mut self.datasets
+    }
+    fn refresh(&mut self) {
+        self.bins = Bins::new(&self.datasets, self.binning);
+        self.view = self.fit();
+    }
+    fn view(&self) -> ChartView {
+        self.view
+    }
+    fn set_binning(&mut self, binning: Binning) {
+        self.binning = binning;
+    }
+}
+
+impl Draw for HistogramState {
+    fn draw(&mut self, bounds: Rect, _: Rect, renderer: &mut RenderBuilder) {
+        push_rect(bounds, self.background_color, renderer);
+        if let Some(grid_color) = self.grid_color {
+            draw_grid(self.view, ChartAxes::Y, grid_color, bounds, renderer);
+        }
+        let visible: Vec<usize> = (0..self.datasets.len()).filter(|&index| self.datasets[index].visible).collect();
+        for bin in 0..self.bins.len() {
+            let left = self.bins.start + bin as f32 * self.bins.width;
+            let (left, right) = (self.view.to_point(bounds, left, 0.0).x, self.view.to_point(bounds, left + self.bins.width, 0.0).x);
+            let bar_width = (right - left - self.bar_gap).max(1.0) / visible.len().max(1) as f32;
+            for (slot, &index) in visible.iter().enumerate() {
+                let count = self.bins.counts[index][bin];
+                if count == 0 {
+                    continue;
+                }
+                let (top, bottom) = (self.view.to_point(bounds, 0.0, count as f32).y, self.view.to_point(bounds, 0.0, 0.0).y);
+                let bar = Rect::new(Point::new(left + slot as f32 * bar_width, top), Size::new(bar_width, bottom - top));
+                if let Some(bar) = bar.intersection(&bounds) {
+                    push_rect(bar, self.datasets[index].color, renderer);
+                }
+            }
+        }
+    }
+}
+
+/// Draws a box plot, a box from the first to the third quartile with a line at the median for each
+/// visible dataset, whiskers to the most extreme values within 1.5 interquartile ranges of the box,
+/// and a dot for each value outside them. Dataset `i` is drawn centered at `x = i`.
+#[derive(Clone, Debug)]
+pub struct BoxPlotState {
+    datasets: Vec<Dataset>,
+    stats: Vec<Option<BoxStats>>,
+    pub view: ChartView,
+    pub grid_color: Option<Color>,
+    pub background_color: Color,
+    /// Width of each box, as a fraction of the space for it
+    pub box_width: f32,
+}
+
+impl Component for BoxPlotState {
+    fn name() -> String {
+        String::from("box_plot")
+    }
+}
+
+impl BoxPlotState {
+    pub fn new(datasets: Vec<Dataset>) -> Self {
+        let mut state = BoxPlotState {
+            stats: Vec::new(),
+            datasets: datasets,
+            view: ChartView::default(),
+            grid_color: Some(GRAY_90),
+            background_color: WHITE,
+            box_width: 0.6,
+        };
+        state.refresh();
+        state
+    }
+    pub fn datasets(&self) -> &[Dataset] {
+        &self.datasets
+    }
+    /// The summary of each dataset, `None` for datasets without values
+    pub fn stats(&self) -> &[Option<BoxStats>] {
+        &self.stats
+    }
+    /// The view showing every dataset, with the y axis covering the visible ones and their outliers
+    pub fn fit(&self) -> ChartView {
+        let (low, high) = self.stats.iter().zip(&self.datasets)
+            .filter(|&(_, dataset)| dataset.visible)
+            .filter_map(|(stats, _)| stats.as_ref())
+            .fold((::std::f32::INFINITY, ::std::f32::NEG_INFINITY), |(low, high), stats| {
+                let low = stats.outliers.iter().fold(low.min(stats.whiskers.0), |low, &value| low.min(value));
+                let high = stats.outliers.iter().fold(high.max(stats.whiskers.1), |high, &value| high.max(value));
+                (low, high)
+            });
+        let y = if low <= high { nice_range((low, high.max(low + ::std::f32::EPSILON)), 5) } else { (0.0, 1.0) };
+        ChartView::new((-0.5, self.datasets.len().max(1) as f32 - 0.5), y)
+    }
+}
+
+impl StatChart for BoxPlotState {
+    fn datasets_mut(&mut self) -> &mut Vec<Dataset> {
+        &mut self.datasets
+    }
+    fn refresh(&mut self) {
+        self.stats = self.datasets.iter().map(|dataset| BoxStats::new(&dataset.values)).collect();
+        self.view = self.fit();
+    }
+    fn view(&self) -> ChartView {
+        self.view
+    }
+    fn categories(&self) -> Vec<(f32, String)> {
+        self.datasets.iter().enumerate().map(|(index, dataset)| (index as f32, dataset.name.clone())).collect()
+    }
+}
+
+impl Draw for BoxPlotState {
+    fn draw(&mut self, bounds: Rect, _: Rect, renderer: &mut RenderBuilder) {
+        push_rect(bounds, self.background_color, renderer);
+        if let Some(grid_color) = self.grid_color {
+            draw_grid(self.view, ChartAxes::Y, grid_color, bounds, renderer);
+        }
+        let view = self.view;
+        let y = |value: f32| view.to_point(bounds, 0.0, value).y;
+        for (index, (dataset, stats)) in self.datasets.iter().zip(&self.stats).enumerate() {
+            let stats = match *stats {
+                Some(ref stats) if dataset.visible => stats,
+                _ => continue,
+            };
+            let center = view.to_point(bounds, index as f32, 0.0).x.floor();
+            let half_width = (view.to_point(bounds, index as f32 + self.box_width / 2.0, 0.0).x - center).max(2.0);
+            let color = dataset.color;
+            let mut shapes = vec![
+                // whiskers and their caps
+                (Rect::new(Point::new(center, y(stats.whiskers.1)), Size::new(1.0, y(stats.whiskers.0) - y(stats.whiskers.1))), color),
+                (Rect::new(Point::new(center - half_width / 2.0, y(stats.whiskers.0)), Size::new(half_width, 1.0)), color),
+                (Rect::new(Point::new(center - half_width / 2.0, y(stats.whiskers.1)), Size::new(half_width, 1.0)), color),
+            ];
+            let box_rect = Rect::new(Point::new(center - half_width, y(stats.q3)), Size::new(half_width * 2.0, y(stats.q1) - y(stats.q3)));
+            shapes.push((box_rect, color));
+            let inner = Rect::new(Point::new(box_rect.left() + 1.0, box_rect.top() + 1.0),
+                                  Size::new((box_rect.width() - 2.0).max(0.0), (box_rect.height() - 2.0).max(0.0)));
+            shapes.push((inner, self.background_color));
+            shapes.push((box_rect, translucent(color)));
+            shapes.push((Rect::new(Point::new(center - half_width, y(stats.median) - 1.0), Size::new(half_width * 2.0, 2.0)), color));
+            for &value in &stats.outliers {
+                shapes.push((Rect::new(Point::new(center - 2.0, y(value) - 2.0), Size::new(4.0, 4.0)), color));
+            }
+            for (rect, color) in shapes {
+                if let Some(rect) = rect.intersection(&bounds) {
+                    push_rect(rect, color, renderer);
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,488 @@
+use std::borrow::Cow;
+use std::ops::Range;
+
+use webrender::api::{LayoutPoint, GlyphInstance, PrimitiveInfo, FontInstanceKey};
+use rusttype::{Scale, GlyphId};
+
+use render::RenderBuilder;
+use text_layout::{Wrap, Align};
+use resources::resources;
+use resources::font::{FontDescriptor, FontFeatures, FontLoader};
+use resources::text::{TextLayout, TextParams, LineMetrics};
+use geometry::{Size, Rect, RectExt, Point, Vector};
+use render;
+use widget::draw::{Draw, DrawDescription};
+use color::*;
+
+const DEBUG_LINE_BOUNDS: bool = false;
+
+/// Everything the glyph positions depend on. Draw states that draw several texts share their
+/// widget's draw cache, so the positions are only reused for the same text laid out the same way.
+#[derive(Clone, PartialEq)]
+struct GlyphKey {
+    text: String,
+    font: FontDescriptor,
+    font_size: f32,
+    font_features: FontFeatures,
+    wrap: Wrap,
+    align: Align,
+    vertical: bool,
+    columns: Option<TextColumns>,
+    float: Option<TextFloat>,
+    size: Size,
+}
+
+component_style!{pub struct TextState<name="text", style=TextStyle> {
+    text: String = String::from(""),
+    font: FontDescriptor = FontDescriptor::from_family("Verdana"),
+    font_size: f32 = 24.0,
+    font_features: FontFeatures = FontFeatures::new(),
+    text_color: Color = BLACK,
+    background_color: Color = TRANSPARENT,
+    wrap: Wrap = Wrap::Whitespace,
+    align: Align = Align::Start,
+    rotation: f32 = 0.0,
+    vertical: bool = false,
+    columns: Option<TextColumns> = None,
+    float: Option<TextFloat> = None,
+}}
+
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum FloatSide {
+    Left,
+    Right,
+}
+
+/// Space kept clear in the top left or right corner of the text, for a widget the text wraps
+/// around, ex. a figure.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct TextFloat {
+    pub side: FloatSide,
+    pub size: Size,
+    /// Space between the float and the text beside and below it
+    pub margin: f32,
+}
+
+/// How wide the columns of multi-column text are
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum ColumnWidth {
+    /// A fixed number of columns sharing the width
+    Count(usize),
+    /// As many columns as fit, each at least this wide
+    Min(f32),
+}
+
+/// Flows text into side by side columns, filled so they end up as close to the same length as
+/// possible, ex. for body text in reader style apps.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct TextColumns {
+    pub width: ColumnWidth,
+    /// Space between adjacent columns
+    pub gap: f32,
+}
+
+impl TextColumns {
+    pub fn count(count: usize, gap: f32) -> Self {
+        TextColumns { width: ColumnWidth::Count(count), gap: gap }
+    }
+    pub fn min_width(width: f32, gap: f32) -> Self {
+        TextColumns { width: ColumnWidth::Min(width), gap: gap }
+    }
+    /// Number of columns and the width of each when laid out in `width`
+    pub fn layout(&self, width: f32) -> (usize, f32) {
+        let count = match self.width {
+            ColumnWidth::Count(count) => count.max(1),
+            ColumnWidth::Min(min_width) => (((width + self.gap) / (min_width + self.gap)).floor() as usize).max(1),
+        };
+        let column_width = (width - self.gap * (count - 1) as f32) / count as f32;
+        (count, column_width.max(0.0))
+    }
+}
+
+/// Where each column of multi-column text goes, for text laid out as one long column
+#[derive(Debug, Copy, Clone)]
+struct ColumnFlow {
+    column_width: f32,
+    gap: f32,
+    lines_per_column: usize,
+    line_height: f32,
+    font_size: f32,
+}
+
+impl ColumnFlow {
+    fn line_top(&self, line: usize) -> f32 {
+        line_top(line, self.font_size, self.line_height)
+    }
+    /// The line at `y` below the top of the text
+    fn line_at(&self, y: f32) -> usize {
+        if y < self.font_size / 2.0 {
+            0
+        } else {
+            ((y - self.font_size) / self.line_height).round().max(0.0) as usize + 1
+        }
+    }
+    /// Offset moving line `line` of the single long column into place
+    fn offset(&self, line: usize) -> Vector {
+        let column = line / self.lines_per_column;
+        Vector::new(
+            column as f32 * (self.column_width + self.gap),
+            -self.line_top(column * self.lines_per_column))
+    }
+    fn height(&self) -> f32 {
+        self.lines_per_column as f32 * self.line_height
+    }
+}
+
+/// Distance from the top of the text to the top of line `line`, the first line is only one font
+/// size tall
+fn line_top(line: usize, font_size: f32, line_height: f32) -> f32 {
+    if line == 0 { 0.0 } else { font_size + (line - 1) as f32 * line_height }
+}
+
+impl TextStyle {
+    pub fn from_text(text: &str) -> Self {
+        Self {
+            text: Some(String::from(text)),
+            ..Self::default()
+        }
+    }
+}
+
+/// Text can be rotated by `rotation` degrees clockwise around the center of its bounds, for
+/// example -90.0 for labels reading bottom to top. Quarter turns are laid out in the bounds with
+/// width and height swapped, other angles at the text's natural size.
+///
+/// `vertical` text stacks each character upright below the previous one, for simple vertical
+/// writing such as tabs on a window edge. Vertical text doesn't wrap, line breaks leave a gap.
+///
+/// Text with `columns` is wrapped to the column width and split between the columns in reading
+/// order. Columns only apply to horizontal, unrotated text, and `measure` ignores them, since the
+/// number of columns depends on the width the text is given.
+///
+/// A `float` keeps a corner of the text clear, lines beside it are shortened and the lines below
+/// it use the full width. Floats only apply to horizontal, unrotated text without columns.
+impl TextState {
+    pub fn measure(&self) -> Size {
+        let mut size = self.with_layout(|layout, fonts, params| layout.measure(fonts, params));
+        if let Some(float) = self.applied_float() {
+            size.width += float.size.width + float.margin;
+            size.height = size.height.max(float.size.height);
+        }
+        self.rotated_size(size)
+    }
+    pub fn min_height(&self) -> f32 {
+        self.line_height()
+    }
+    pub fn line_height(&self) -> f32 {
+        self.line_metrics().line_height
+    }
+    pub fn text_fits(&self, text: &str, bounds: Rect) -> bool {
+        let mut resources = resources();
+        let resources = &mut *resources;
+        let text = self.layout_text(text);
+        let params = self.params(&text);
+        let bounds = self.unrotated_bounds(bounds.size, bounds.size);
+        let height = self.text_height(&mut *resources.text_layout, &mut resources.font_loader, &params, bounds.width);
+        height <= bounds.height
+    }
+    /// Height of the text when wrapped to `width`
+    pub fn wrapped_height(&self, width: f32) -> f32 {
+        if self.is_rotated() {
+            return self.measure().height;
+        }
+        self.with_layout(|layout, fonts, params| self.text_height(layout, fonts, params, width))
+    }
+    /// Bounds of each line of the text when drawn in `bounds`, in reading order, for hit testing
+    /// and for highlighting selections or search matches. Lines of multi-column text are in
+    /// their columns.
+    pub fn line_rects(&self, bounds: Rect) -> Vec<Rect> {
+        self.get_line_rects(bounds)
+    }
+    /// Split the text into pages of `page_size`, as many whole lines on each page as fit.
+    /// Lines are broken where the text wraps, so each page can be drawn without wrapping at any
+    /// scale and keep the same lines. Columns, floats, rotation and vertical text are ignored.
+    pub fn paginate(&self, page_size: Size) -> Vec<String> {
+        let text = &self.text;
+        let mut resources = resources();
+        let resources = &mut *resources;
+        let (layout, fonts) = (&mut *resources.text_layout, &mut resources.font_loader);
+        let params = TextParams { wrap: self.wrap, align: self.align, ..self.params(text) };
+        let line_height = layout.line_metrics(fonts, params.font, params.font_size).line_height;
+        let lines_per_page = if page_size.height > self.font_size {
+            ((page_size.height - self.font_size) / line_height).floor() as usize + 1
+        } else {
+            1
+        };
+        let ranges = layout.line_ranges(fonts, &params, page_size.width);
+        ranges.chunks(lines_per_page).map(|page| {
+            page.iter().map(|range| text[range.clone()].trim_right()).collect::<Vec<_>>().join("\n")
+        }).collect()
+    }
+    fn text_height(&self, layout: &mut TextLayout, fonts: &mut FontLoader, params: &TextParams, width: f32) -> f32 {
+        if let Some(flow) = self.column_flow_for(layout, fonts, params, width) {
+            return flow.height();
+        }
+        let bounds = Rect::new(Point::zero(), Size::new(width, ::std::f32::MAX));
+        if let Some(blocks) = self.float_blocks(layout, fonts, params, bounds) {
+            let float_height = self.float.map_or(0.0, |float| float.size.height);
+            let &(ref range, last) = blocks.last().unwrap();
+            let last_params = TextParams { text: &params.text[range.clone()], ..params.clone() };
+            let height = last.origin.y + layout.height(fonts, &last_params, last.width());
+            return height.max(float_height);
+        }
+        layout.height(fonts, params, width)
+    }
+    fn applied_float(&self) -> Option<TextFloat> {
+        if self.columns.is_none() && !self.vertical && !self.is_rotated() { self.float } else { None }
+    }
+    /// Split the text into the part beside the float and the part below it, each with the
+    /// bounds it's laid out in
+    fn float_blocks(&self, layout: &mut TextLayout, fonts: &mut FontLoader, params: &TextParams, bounds: Rect) -> Option<Vec<(Range<usize>, Rect)>> {
+        let float = self.applied_float()?;
+        let float_width = float.size.width + float.margin;
+        let beside_size = Size::new((bounds.width() - float_width).max(0.0), bounds.height());
+        let beside_bounds = match float.side {
+            FloatSide::Left => Rect::new(Point::new(bounds.origin.x + float_width, bounds.origin.y), beside_size),
+            FloatSide::Right => Rect::new(bounds.origin, beside_size),
+        };
+        let line_height = layout.line_metrics(fonts, params.font, params.font_size).line_height;
+        let float_bottom = float.size.height + float.margin;
+        let ranges = layout.line_ranges(fonts, params, beside_bounds.width());
+        let beside = (0..ranges.len())
+            .take_while(|&line| line_top(line, params.font_size, line_height) < float_bottom)
+            .count();
+        if beside >= ranges.len() {
+            return Some(vec![(0..params.text.len(), beside_bounds)]);
+        }
+        let split = ranges[beside].start;
+        let top = line_top(beside, params.font_size, line_height);
+        let below_bounds = Rect::new(
+            Point::new(bounds.origin.x, bounds.origin.y + top),
+            Size::new(bounds.width(), (bounds.height() - top).max(0.0)));
+        Some(vec![(0..split, beside_bounds), (split..params.text.len(), below_bounds)])
+    }
+    /// Call `f` to lay out each block of the text in `bounds`, there's more than one when the
+    /// text wraps around a float
+    fn with_blocks<F, T>(&self, bounds: Rect, mut f: F) -> Vec<T>
+        where F: FnMut(&mut TextLayout, &mut FontLoader, &TextParams, Rect) -> Vec<T>
+    {
+        self.with_layout(|layout, fonts, params| {
+            match self.float_blocks(layout, fonts, params, bounds) {
+                Some(blocks) => {
+                    let mut items = Vec::new();
+                    for (range, block_bounds) in blocks {
+                        let block_params = TextParams { text: &params.text[range], ..params.clone() };
+                        items.extend(f(layout, fonts, &block_params, block_bounds));
+                    }
+                    items
+                }
+                None => f(layout, fonts, params, bounds),
+            }
+        })
+    }
+    fn column_flow(&self, width: f32) -> Option<ColumnFlow> {
+        let text = self.layout_text(&self.text);
+        let mut resources = resources();
+        let resources = &mut *resources;
+        self.column_flow_for(&mut *resources.text_layout, &mut resources.font_loader, &self.params(&text), width)
+    }
+    fn column_flow_for(&self, layout: &mut TextLayout, fonts: &mut FontLoader, params: &TextParams, width: f32) -> Option<ColumnFlow> {
+        let columns = match self.columns {
+            Some(columns) if !self.vertical && !self.is_rotated() => columns,
+            _ => return None,
+        };
+        let (count, column_width) = columns.layout(width);
+        if count == 1 {
+            return None;
+        }
+        let line_height = layout.line_metrics(fonts, params.font, params.font_size).line_height;
+        let lines = (layout.height(fonts, params, column_width) / line_height).round() as usize;
+        // balanced fill, the last column is never longer than the others
+        let lines_per_column = ((lines + count - 1) / count).max(1);
+        Some(ColumnFlow {
+            column_width: column_width,
+            gap: columns.gap,
+            lines_per_column: lines_per_column,
+            line_height: line_height,
+            font_size: params.font_size,
+        })
+    }
+    /// Bounds of the single long column multi-column text is first laid out in
+    fn column_bounds(bounds: Rect, flow: &ColumnFlow) -> Rect {
+        Rect::new(bounds.origin, Size::new(flow.column_width, ::std::f32::MAX))
+    }
+    fn is_rotated(&self) -> bool {
+        self.rotation % 360.0 != 0.0
+    }
+    fn is_quarter_turn(&self) -> bool {
+        (self.rotation % 180.0).abs() == 90.0
+    }
+    /// Size of the bounding box of text of `size` once rotated
+    fn rotated_size(&self, size: Size) -> Size {
+        if self.is_quarter_turn() {
+            Size::new(size.height, size.width)
+        } else if self.is_rotated() {
+            let (sin, cos) = self.rotation.to_radians().sin_cos();
+            Size::new(
+                (size.width * cos).abs() + (size.height * sin).abs(),
+                (size.width * sin).abs() + (size.height * cos).abs())
+        } else {
+            size
+        }
+    }
+    /// Size to lay the text out in, before it's rotated to fit `bounds`
+    fn unrotated_bounds(&self, bounds: Size, natural_size: Size) -> Size {
+        if self.is_quarter_turn() {
+            Size::new(bounds.height, bounds.width)
+        } else if self.is_rotated() && (self.rotation % 180.0) != 0.0 {
+            natural_size
+        } else {
+            bounds
+        }
+    }
+    /// Vertical text is laid out as one character per line
+    fn layout_text<'a>(&self, text: &'a str) -> Cow<'a, str> {
+        if self.vertical {
+            let columns: Vec<String> = text.lines().map(|line| {
+                line.chars().map(|ch| ch.to_string()).collect::<Vec<_>>().join("\n")
+            }).collect();
+            Cow::Owned(columns.join("\n\n"))
+        } else {
+            Cow::Borrowed(text)
+        }
+    }
+    fn params<'a>(&'a self, text: &'a str) -> TextParams<'a> {
+        TextParams {
+            text: text,
+            font: &self.font,
+            font_size: self.font_size,
+            features: &self.font_features,
+            wrap: if self.vertical { Wrap::NoWrap } else { self.wrap },
+            align: if self.vertical { Align::Middle } else { self.align },
+        }
+    }
+    /// Call `f` with the text layout implementation and the parameters of this text
+    fn with_layout<F, T>(&self, f: F) -> T
+        where F: FnOnce(&mut TextLayout, &mut FontLoader, &TextParams) -> T
+    {
+        let text = self.layout_text(&self.text);
+        let mut resources = resources();
+        let resources = &mut *resources;
+        f(resources.text_layout.as_mut(), &mut resources.font_loader, &self.params(&text))
+    }
+    fn get_line_rects(&self, bounds: Rect) -> Vec<Rect> {
+        if let Some(flow) = self.column_flow(bounds.width()) {
+            let column_bounds = Self::column_bounds(bounds, &flow);
+            let rects = self.with_layout(|layout, fonts, params| layout.line_rects(fonts, params, column_bounds));
+            return rects.iter().enumerate().map(|(line, rect)| rect.translate(&flow.offset(line))).collect();
+        }
+        self.with_blocks(bounds, |layout, fonts, params, bounds| layout.line_rects(fonts, params, bounds))
+    }
+    fn position_glyphs(&self, bounds: Rect) -> Vec<GlyphInstance> {
+        let descent = self.line_metrics().descent;
+        let flow = self.column_flow(bounds.width());
+        let layout_bounds = flow.as_ref().map_or(bounds, |flow| Self::column_bounds(bounds, flow));
+        self.with_blocks(layout_bounds, |layout, fonts, params, bounds| layout.glyphs(fonts, params, bounds)).iter().map(|glyph| {
+            let mut position = glyph.position;
+            if let Some(ref flow) = flow {
+                // glyph positions are on the baseline, one font size below the top of the line
+                let line = flow.line_at(position.y - self.font_size - bounds.origin.y);
+                position = position + flow.offset(line);
+            }
+            GlyphInstance {
+                index: glyph.index,
+                point: LayoutPoint::new(position.x, position.y + descent),
+            }
+        }).collect()
+    }
+    fn glyph_key(&self, size: Size) -> GlyphKey {
+        GlyphKey {
+            text: self.text.clone(),
+            font: self.font.clone(),
+            font_size: self.font_size,
+            font_features: self.font_features.clone(),
+            wrap: self.wrap,
+            align: self.align,
+            vertical: self.vertical,
+            columns: self.columns,
+            float: self.float,
+            size: size,
+        }
+    }
+    fn font_instance_key(&self) -> FontInstanceKey {
+        *resources().font_loader.get_font_instance(&self.font, self.font_size).unwrap()
+    }
+    fn line_metrics(&self) -> LineMetrics {
+        let mut resources = resources();
+        let resources = &mut *resources;
+        resources.text_layout.line_metrics(&mut resources.font_loader, &self.font, self.font_size)
+    }
+}
+
+impl Draw for TextState {
+    fn draw(&mut self, bounds: Rect, _: Rect, renderer: &mut RenderBuilder) {
+        if self.is_rotated() {
+            // lay out unrotated around the origin, then rotate into place
+            let natural_size = self.with_layout(|layout, fonts, params| layout.measure(fonts, params));
+            let size = self.unrotated_bounds(bounds.size, natural_size);
+            let local_bounds = Rect::new(Point::new(-size.width / 2.0, -size.height / 2.0), size);
+            render::push_rotation(bounds.center(), self.rotation, renderer);
+            self.draw_text(local_bounds, renderer);
+            render::pop_rotation(renderer);
+        } else {
+            self.draw_text(bounds, renderer);
+        }
+    }
+    fn describe(&self) -> DrawDescription {
+        DrawDescription {
+            text: Some(self.text.clone()),
+            text_color: Some(self.text_color),
+            background_color: Some(self.background_color),
+        }
+    }
+}
+
+impl TextState {
+    fn draw_text(&self, bounds: Rect, renderer: &mut RenderBuilder) {
+        // glyphs are laid out at the origin once, then moved to wherever the text is drawn
+        let local_bounds = Rect::new(Point::zero(), bounds.size);
+        let offset = bounds.origin.to_vector();
+        let glyphs: Vec<GlyphInstance> = renderer.cache.get(&self.glyph_key(bounds.size), || self.position_glyphs(local_bounds))
+            .iter()
+            .map(|glyph| GlyphInstance { index: glyph.index, point: glyph.point + offset })
+            .collect();
+        if DEBUG_LINE_BOUNDS {
+            let line_rects = self.get_line_rects(bounds);
+            let descent = self.line_metrics().descent;
+            let mut resources = resources();
+            let font = resources.font_loader.get_font(&self.font).unwrap();
+            for mut rect in line_rects {
+                render::draw_rect_outline(rect, CYAN, renderer);
+                rect.origin.y = rect.bottom() + descent;
+                rect.size.height = 1.0;
+                render::draw_rect_outline(rect, RED, renderer);
+            }
+            let scale = Scale::uniform(self.font_size);
+            for glyph in &glyphs {
+                let scaled_glyph = font.info.glyph(GlyphId(glyph.index)).unwrap().scaled(scale);
+                if let Some(rect) = scaled_glyph.exact_bounding_box() {
+                    let origin = glyph.point.to_vector() + Vector::new(0.0, -1.0);
+                    let rect = Rect::from_rusttype(rect).translate(&origin);
+                    render::draw_rect_outline(rect, BLUE, renderer);
+                }
+            }
+        }
+        if self.background_color != TRANSPARENT {
+            renderer.builder.push_rect(&PrimitiveInfo::new(bounds.clone()), self.background_color.into());
+        }
+        let key = self.font_instance_key();
+        let info = PrimitiveInfo::new(bounds);
+        renderer.builder.push_text(
+            &info,
+            &glyphs,
+            key,
+            self.text_color.into(),
+            None,
+        );
+    }
+}
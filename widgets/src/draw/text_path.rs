@@ -0,0 +1,179 @@
+use std::f32;
+
+use webrender::api::{LayoutPoint, GlyphInstance, PrimitiveInfo};
+
+use render::{self, RenderBuilder};
+use text_layout::{Wrap, Align};
+use resources::resources;
+use resources::font::{FontDescriptor, FontFeatures};
+use resources::text::TextParams;
+use geometry::{Rect, Point, Size};
+use widget::draw::Draw;
+use draw::text::TextState;
+use color::*;
+
+/// Number of straight segments curves are divided into when laying out text
+const CURVE_SEGMENTS: usize = 96;
+
+/// A curve to lay text along, relative to the top left of the widget.
+/// Text reads in the direction the path is traced, with glyphs on the left side of the path.
+#[derive(Clone, Debug, PartialEq)]
+pub enum TextPath {
+    /// Clockwise around a circle, starting `start_angle` degrees clockwise from the top
+    Circle { center: Point, radius: f32, start_angle: f32 },
+    /// Counter clockwise around a circle, starting `start_angle` degrees clockwise from the bottom,
+    /// so text along the bottom of a badge reads left to right
+    CircleReversed { center: Point, radius: f32, start_angle: f32 },
+    /// A cubic bezier curve
+    Bezier { start: Point, control_1: Point, control_2: Point, end: Point },
+    /// Straight lines through each point
+    Polyline(Vec<Point>),
+}
+
+impl TextPath {
+    /// Divide the path into straight segments
+    fn points(&self) -> Vec<Point> {
+        match *self {
+            TextPath::Circle { center, radius, start_angle } => {
+                arc_points(center, radius, start_angle - 90.0, 1.0)
+            }
+            TextPath::CircleReversed { center, radius, start_angle } => {
+                arc_points(center, radius, start_angle + 90.0, -1.0)
+            }
+            TextPath::Bezier { start, control_1, control_2, end } => {
+                (0..CURVE_SEGMENTS + 1).map(|step| {
+                    let t = step as f32 / CURVE_SEGMENTS as f32;
+                    let u = 1.0 - t;
+                    let x = u * u * u * start.x + 3.0 * u * u * t * control_1.x + 3.0 * u * t * t * control_2.x + t * t * t * end.x;
+                    let y = u * u * u * start.y + 3.0 * u * u * t * control_1.y + 3.0 * u * t * t * control_2.y + t * t * t * end.y;
+                    Point::new(x, y)
+                }).collect()
+            }
+            TextPath::Polyline(ref points) => points.clone(),
+        }
+    }
+}
+
+/// Points around a full circle, starting at `start` degrees clockwise from the positive x axis
+fn arc_points(center: Point, radius: f32, start: f32, direction: f32) -> Vec<Point> {
+    (0..CURVE_SEGMENTS + 1).map(|step| {
+        let angle = (start + direction * 360.0 * step as f32 / CURVE_SEGMENTS as f32).to_radians();
+        Point::new(center.x + radius * angle.cos(), center.y + radius * angle.sin())
+    }).collect()
+}
+
+/// A path divided into straight segments, measured so positions can be found by distance along it
+struct MeasuredPath {
+    points: Vec<Point>,
+    /// Distance along the path of each point
+    distances: Vec<f32>,
+}
+
+impl MeasuredPath {
+    fn new(points: Vec<Point>) -> Self {
+        let mut distances = Vec::with_capacity(points.len());
+        let mut total = 0.0;
+        for (index, point) in points.iter().enumerate() {
+            if index > 0 {
+                total += (*point - points[index - 1]).length();
+            }
+            distances.push(total);
+        }
+        MeasuredPath {
+            points: points,
+            distances: distances,
+        }
+    }
+    fn length(&self) -> f32 {
+        self.distances.last().cloned().unwrap_or(0.0)
+    }
+    /// Position and direction in degrees clockwise from the positive x axis, `distance` along the path.
+    /// Past either end the path continues in a straight line.
+    fn position(&self, distance: f32) -> Option<(Point, f32)> {
+        if self.points.len() < 2 {
+            return None;
+        }
+        let segment = match self.distances.iter().position(|&end| end >= distance) {
+            Some(0) => 1,
+            Some(index) => index,
+            None => self.points.len() - 1,
+        };
+        let (start, end) = (self.points[segment - 1], self.points[segment]);
+        let direction = end - start;
+        let length = direction.length();
+        if length == 0.0 {
+            return Some((start, 0.0));
+        }
+        let along = distance - self.distances[segment - 1];
+        let point = start + direction * (along / length);
+        Some((point, direction.y.atan2(direction.x).to_degrees()))
+    }
+}
+
+component_style!{pub struct TextPathState<name="text_path", style=TextPathStyle> {
+    text: String = String::from(""),
+    path: TextPath = TextPath::Polyline(Vec::new()),
+    font: FontDescriptor = TextState::default().font,
+    font_size: f32 = 24.0,
+    font_features: FontFeatures = FontFeatures::new(),
+    text_color: Color = BLACK,
+    align: Align = Align::Start,
+    offset: f32 = 0.0,
+    letter_spacing: f32 = 0.0,
+}}
+
+/// Draws a single line of text along a curve, such as around the edge of a badge or gauge.
+/// Each glyph is rotated to follow the curve, its baseline centered on the path.
+/// `align` places the text at the start, middle or end of the path, offset by `offset`.
+impl TextPathState {
+    /// Position of each glyph along the line of text, and the width of the line.
+    fn layout_glyphs(&self) -> (Vec<(u32, f32)>, f32) {
+        let params = TextParams {
+            text: &self.text,
+            font: &self.font,
+            font_size: self.font_size,
+            features: &self.font_features,
+            wrap: Wrap::NoWrap,
+            align: Align::Start,
+        };
+        let mut resources = resources();
+        let resources = &mut *resources;
+        let width = resources.text_layout.measure(&mut resources.font_loader, &params).width;
+        let bounds = Rect::new(Point::zero(), Size::new(f32::MAX, self.font_size * 2.0));
+        let glyphs = resources.text_layout.glyphs(&mut resources.font_loader, &params, bounds);
+        let glyphs: Vec<(u32, f32)> = glyphs.iter().enumerate()
+            .map(|(index, glyph)| (glyph.index, glyph.position.x + self.letter_spacing * index as f32))
+            .collect();
+        let width = width + self.letter_spacing * glyphs.len().saturating_sub(1) as f32;
+        (glyphs, width)
+    }
+}
+
+impl Draw for TextPathState {
+    fn draw(&mut self, bounds: Rect, _: Rect, renderer: &mut RenderBuilder) {
+        let offset = bounds.origin.to_vector();
+        let path = MeasuredPath::new(self.path.points().iter().map(|point| *point + offset).collect());
+        let (glyphs, width) = self.layout_glyphs();
+        let start = self.offset + match self.align {
+            Align::Start => 0.0,
+            Align::Middle => (path.length() - width) / 2.0,
+            Align::End => path.length() - width,
+        };
+        let key = *resources().font_loader.get_font_instance(&self.font, self.font_size).unwrap();
+        for (index, &(glyph, x)) in glyphs.iter().enumerate() {
+            let next_x = glyphs.get(index + 1).map_or(width, |&(_, next_x)| next_x - self.letter_spacing);
+            let advance = (next_x - x).max(0.0);
+            // rotate each glyph around the middle of its baseline
+            if let Some((point, angle)) = path.position(start + x + advance / 2.0) {
+                render::push_rotation(point, angle, renderer);
+                let rect = Rect::new(Point::new(-advance / 2.0, -self.font_size), Size::new(advance.max(1.0), self.font_size * 1.5));
+                let instance = GlyphInstance {
+                    index: glyph,
+                    point: LayoutPoint::new(-advance / 2.0, 0.0),
+                };
+                renderer.builder.push_text(&PrimitiveInfo::new(rect), &[instance], key, self.text_color.into(), None);
+                render::pop_rotation(renderer);
+            }
+        }
+    }
+}
@@ -0,0 +1,164 @@
+use std::cmp::min;
+use std::collections::BTreeSet;
+use std::ops::Range;
+use std::path::PathBuf;
+
+use webrender::api::*;
+
+use render::{self, RenderBuilder};
+use widget::draw::Draw;
+use resources::resources;
+use resources::image::ImageSource;
+use geometry::{Rect, RectExt, Point, Size};
+use style::Component;
+use color::*;
+
+/// An image in a thumbnail grid
+#[derive(Debug, Clone)]
+pub struct Thumbnail {
+    pub path: PathBuf,
+    /// The generated thumbnail, `None` until it has been loaded
+    pub image: Option<ImageSource>,
+}
+
+impl Thumbnail {
+    pub fn new(path: PathBuf) -> Self {
+        Thumbnail {
+            path: path,
+            image: None,
+        }
+    }
+}
+
+/// Draws thumbnails in rows that wrap to the width of the bounds, only drawing the rows that are
+/// visible. Columns are spread evenly across the width.
+#[derive(Clone, Debug)]
+pub struct ThumbnailGridState {
+    pub thumbnails: Vec<Thumbnail>,
+    pub selected: BTreeSet<usize>,
+    /// Width and height of the square each thumbnail is fit into
+    pub thumbnail_size: f32,
+    /// Least space between thumbnails, and between rows
+    pub spacing: f32,
+    /// Area being selected by dragging, relative to the top left of the grid
+    pub rubber_band: Option<Rect>,
+    /// Drawn in place of thumbnails that haven't been loaded yet
+    pub placeholder_color: Color,
+    pub selection_color: Color,
+}
+
+impl Default for ThumbnailGridState {
+    fn default() -> Self {
+        ThumbnailGridState {
+            thumbnails: Vec::new(),
+            selected: BTreeSet::new(),
+            thumbnail_size: 128.0,
+            spacing: 8.0,
+            rubber_band: None,
+            placeholder_color: GRAY_90,
+            selection_color: Color(0xB4D5FEFF),
+        }
+    }
+}
+
+impl Component for ThumbnailGridState {
+    fn name() -> String {
+        String::from("thumbnail_grid")
+    }
+}
+
+impl ThumbnailGridState {
+    pub fn new() -> Self {
+        Self::default()
+    }
+    /// Number of thumbnails in each row of a grid `width` wide, at least one
+    pub fn columns(&self, width: f32) -> usize {
+        let columns = ((width - self.spacing) / (self.thumbnail_size + self.spacing)).floor();
+        if columns >= 1.0 { columns as usize } else { 1 }
+    }
+    pub fn rows(&self, width: f32) -> usize {
+        let columns = self.columns(width);
+        (self.thumbnails.len() + columns - 1) / columns
+    }
+    /// Height of the grid when it's `width` wide
+    pub fn content_height(&self, width: f32) -> f32 {
+        self.rows(width) as f32 * (self.thumbnail_size + self.spacing) + self.spacing
+    }
+    /// Horizontal space between columns, the leftover width is shared between the gaps
+    fn column_gap(&self, width: f32) -> f32 {
+        let columns = self.columns(width) as f32;
+        ((width - columns * self.thumbnail_size) / (columns + 1.0)).max(self.spacing)
+    }
+    /// The cell the thumbnail at `index` is fit into
+    pub fn cell_rect(&self, index: usize, bounds: Rect) -> Rect {
+        let columns = self.columns(bounds.width());
+        let gap = self.column_gap(bounds.width());
+        let (row, column) = (index / columns, index % columns);
+        let origin = Point::new(
+            bounds.left() + gap + column as f32 * (self.thumbnail_size + gap),
+            bounds.top() + self.spacing + row as f32 * (self.thumbnail_size + self.spacing));
+        Rect::new(origin, Size::new(self.thumbnail_size, self.thumbnail_size))
+    }
+    /// The thumbnails in rows overlapping `visible`
+    pub fn visible_range(&self, bounds: Rect, visible: Rect) -> Range<usize> {
+        let row_height = self.thumbnail_size + self.spacing;
+        let columns = self.columns(bounds.width());
+        let first = ((visible.top() - bounds.top() - self.spacing) / row_height).floor().max(0.0) as usize;
+        let last = ((visible.bottom() - bounds.top()) / row_height).ceil().max(0.0) as usize;
+        let len = self.thumbnails.len();
+        min(first * columns, len)..min(last * columns, len)
+    }
+    /// The thumbnail under `point`, if any
+    pub fn index_at(&self, point: Point, bounds: Rect) -> Option<usize> {
+        let area = Rect::new(point, Size::new(1.0, 1.0));
+        self.visible_range(bounds, area).find(|&index| self.cell_rect(index, bounds).contains(&point))
+    }
+    /// The thumbnails overlapping `area`, ex. selected by a rubber band
+    pub fn indices_in(&self, area: Rect, bounds: Rect) -> BTreeSet<usize> {
+        self.visible_range(bounds, area).filter(|&index| self.cell_rect(index, bounds).intersects(&area)).collect()
+    }
+}
+
+impl Draw for ThumbnailGridState {
+    fn draw(&mut self, bounds: Rect, crop_to: Rect, renderer: &mut RenderBuilder) {
+        let visible = match bounds.intersection(&crop_to) {
+            Some(visible) => visible,
+            None => return,
+        };
+        for index in self.visible_range(bounds, visible) {
+            let cell = self.cell_rect(index, bounds);
+            if self.selected.contains(&index) {
+                renderer.builder.push_rect(&PrimitiveInfo::new(cell.inflate(4.0, 4.0)), self.selection_color.into());
+            }
+            let image = self.thumbnails[index].image.as_ref().and_then(|image| {
+                resources().image_loader.images.get(image).map(|info| {
+                    (info.key, Size::new(info.descriptor.size.width as f32, info.descriptor.size.height as f32))
+                })
+            });
+            match image {
+                Some((key, size)) => {
+                    // fit in the cell, keeping the aspect ratio
+                    let scale = (cell.width() / size.width).min(cell.height() / size.height);
+                    let size = size * scale;
+                    let origin = Point::new(cell.center().x - size.width / 2.0, cell.center().y - size.height / 2.0);
+                    let rect = Rect::new(origin, size);
+                    renderer.builder.push_image(
+                        &PrimitiveInfo::new(rect),
+                        size,
+                        LayoutSize::zero(),
+                        ImageRendering::Auto,
+                        AlphaType::Alpha,
+                        key,
+                    );
+                }
+                None => renderer.builder.push_rect(&PrimitiveInfo::new(cell), self.placeholder_color.into()),
+            }
+        }
+        if let Some(rubber_band) = self.rubber_band {
+            let rect = Rect::new(bounds.origin + rubber_band.origin.to_vector(), rubber_band.size);
+            let fill = Color(self.selection_color.0 & 0xFFFF_FF00 | 0x4C);
+            renderer.builder.push_rect(&PrimitiveInfo::new(rect), fill.into());
+            render::draw_rect_outline(rect, self.selection_color, renderer);
+        }
+    }
+}
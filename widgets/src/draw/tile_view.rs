@@ -0,0 +1,167 @@
+use std::collections::HashMap;
+use std::time::{Duration, Instant};
+
+use webrender::api::*;
+
+use clock;
+use render::RenderBuilder;
+use widget::draw::Draw;
+use resources::resources;
+use resources::image::ImageSource;
+use geometry::{Rect, RectExt, Point, Size};
+use style::Component;
+use color::*;
+
+/// Identifies a tile in a slippy map style tile pyramid.
+/// At zoom level `zoom` the world is `2^zoom` tiles wide and high.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub struct TileCoord {
+    pub x: u32,
+    pub y: u32,
+    pub zoom: u32,
+}
+
+#[derive(Debug, Clone)]
+pub struct LoadedTile {
+    pub image: ImageSource,
+    pub loaded: Instant,
+}
+
+/// Draws the loaded tiles visible at the current center and zoom.
+/// World coordinates range from `0.0` to `1.0` in both axes, with `(0, 0)` at the top left.
+#[derive(Debug, Clone)]
+pub struct TileViewState {
+    /// Center of the view in world coordinates
+    pub center: (f64, f64),
+    /// Fractional zoom level, tiles from the level below are scaled up between levels
+    pub zoom: f32,
+    pub tile_size: u32,
+    pub max_zoom: u32,
+    /// How long newly loaded tiles take to fade in
+    pub fade_duration: Duration,
+    pub background_color: Color,
+    pub tiles: HashMap<TileCoord, LoadedTile>,
+}
+
+impl Component for TileViewState {
+    fn name() -> String {
+        String::from("tile_view")
+    }
+}
+
+impl Default for TileViewState {
+    fn default() -> Self {
+        TileViewState {
+            center: (0.5, 0.5),
+            zoom: 1.0,
+            tile_size: 256,
+            max_zoom: 18,
+            fade_duration: Duration::from_millis(300),
+            background_color: GRAY_90,
+            tiles: HashMap::new(),
+        }
+    }
+}
+
+impl TileViewState {
+    /// The zoom level tiles are fetched at
+    pub fn tile_zoom(&self) -> u32 {
+        ::std::cmp::min(self.zoom.max(0.0).floor() as u32, self.max_zoom)
+    }
+    /// Width of the whole world in pixels, at the current zoom
+    pub fn world_size(&self) -> f64 {
+        self.tile_size as f64 * 2f64.powf(self.zoom as f64)
+    }
+    pub fn world_to_screen(&self, bounds: Rect, world: (f64, f64)) -> Point {
+        let world_size = self.world_size();
+        let center = bounds.center();
+        Point::new(
+            center.x + ((world.0 - self.center.0) * world_size) as f32,
+            center.y + ((world.1 - self.center.1) * world_size) as f32)
+    }
+    pub fn screen_to_world(&self, bounds: Rect, point: Point) -> (f64, f64) {
+        let world_size = self.world_size();
+        let center = bounds.center();
+        (self.center.0 + (point.x - center.x) as f64 / world_size,
+         self.center.1 + (point.y - center.y) as f64 / world_size)
+    }
+    /// Screen rect covered by a tile
+    pub fn tile_rect(&self, bounds: Rect, tile: TileCoord) -> Rect {
+        let tiles = 2f64.powi(tile.zoom as i32);
+        let top_left = self.world_to_screen(bounds, (tile.x as f64 / tiles, tile.y as f64 / tiles));
+        let bottom_right = self.world_to_screen(bounds, ((tile.x + 1) as f64 / tiles, (tile.y + 1) as f64 / tiles));
+        Rect::new(top_left, Size::new(bottom_right.x - top_left.x, bottom_right.y - top_left.y))
+    }
+    /// Tiles at the current tile zoom level that overlap `bounds`
+    pub fn visible_tiles(&self, bounds: Rect) -> Vec<TileCoord> {
+        let zoom = self.tile_zoom();
+        let tiles = 2f64.powi(zoom as i32);
+        let top_left = self.screen_to_world(bounds, bounds.origin);
+        let bottom_right = self.screen_to_world(bounds, Point::new(bounds.right(), bounds.bottom()));
+        let clamp = |value: f64| value.max(0.0).min(tiles - 1.0) as u32;
+        let (min_x, max_x) = (clamp((top_left.0 * tiles).floor()), clamp((bottom_right.0 * tiles).floor()));
+        let (min_y, max_y) = (clamp((top_left.1 * tiles).floor()), clamp((bottom_right.1 * tiles).floor()));
+        let mut visible = Vec::new();
+        for y in min_y..max_y + 1 {
+            for x in min_x..max_x + 1 {
+                visible.push(TileCoord { x: x, y: y, zoom: zoom });
+            }
+        }
+        visible
+    }
+    /// True while any visible tile is still fading in, so more frames are needed
+    pub fn is_fading(&self, bounds: Rect) -> bool {
+        self.visible_tiles(bounds).iter().any(|tile| {
+            self.tiles.get(tile).map_or(false, |loaded| clock::now().duration_since(loaded.loaded) < self.fade_duration)
+        })
+    }
+    fn opacity(&self, loaded: &LoadedTile) -> f32 {
+        let elapsed = clock::now().duration_since(loaded.loaded);
+        if elapsed >= self.fade_duration {
+            1.0
+        } else {
+            let elapsed = elapsed.subsec_nanos() as f32 / 1_000_000_000.0 + elapsed.as_secs() as f32;
+            let fade = self.fade_duration.subsec_nanos() as f32 / 1_000_000_000.0 + self.fade_duration.as_secs() as f32;
+            elapsed / fade
+        }
+    }
+}
+
+impl Draw for TileViewState {
+    fn draw(&mut self, bounds: Rect, _: Rect, renderer: &mut RenderBuilder) {
+        renderer.builder.push_rect(&PrimitiveInfo::new(bounds), self.background_color.into());
+        for tile in self.visible_tiles(bounds) {
+            let loaded = match self.tiles.get(&tile) {
+                Some(loaded) => loaded,
+                None => continue,
+            };
+            let key = match resources().image_loader.get_image(&loaded.image) {
+                Ok(info) => info.key,
+                Err(_) => continue,
+            };
+            let rect = self.tile_rect(bounds, tile);
+            let alpha = self.opacity(loaded);
+            if alpha < 1.0 {
+                renderer.builder.push_stacking_context(
+                    &PrimitiveInfo::new(Rect::zero()),
+                    None,
+                    TransformStyle::Flat,
+                    MixBlendMode::Normal,
+                    vec![FilterOp::Opacity(PropertyBinding::Value(alpha), alpha)],
+                    GlyphRasterSpace::Screen,
+                );
+            }
+            renderer.builder.push_image(
+                &PrimitiveInfo::new(rect),
+                rect.size,
+                LayoutSize::zero(),
+                ImageRendering::Auto,
+                AlphaType::Alpha,
+                key,
+            );
+            if alpha < 1.0 {
+                renderer.builder.pop_stacking_context();
+            }
+        }
+    }
+}
@@ -0,0 +1,114 @@
+use std::collections::VecDeque;
+
+use webrender::api::PrimitiveInfo;
+
+use render::RenderBuilder;
+use widget::draw::Draw;
+use geometry::{Rect, RectExt, Point, Size};
+use style::Component;
+use color::*;
+
+/// Draws the min/max peaks of a buffer of samples, each sample in the range `-1.0..1.0`.
+#[derive(Clone, Debug)]
+pub struct WaveformState {
+    samples: VecDeque<f32>,
+    /// Samples beyond this are dropped from the start of the buffer as new samples are pushed
+    pub max_samples: usize,
+    /// Horizontal zoom, 1.0 fits the whole buffer in the bounds
+    pub zoom: f32,
+    /// Start of the visible range, as a fraction of the buffer length
+    pub offset: f32,
+    pub color: Color,
+    pub background_color: Color,
+}
+
+impl Component for WaveformState {
+    fn name() -> String {
+        String::from("waveform")
+    }
+}
+
+impl Default for WaveformState {
+    fn default() -> Self {
+        WaveformState {
+            samples: VecDeque::new(),
+            max_samples: 48_000 * 10,
+            zoom: 1.0,
+            offset: 0.0,
+            color: BLUE,
+            background_color: BLACK,
+        }
+    }
+}
+
+impl WaveformState {
+    pub fn new(samples: Vec<f32>) -> Self {
+        let mut state = WaveformState::default();
+        state.max_samples = ::std::cmp::max(state.max_samples, samples.len());
+        state.samples = samples.into_iter().collect();
+        state
+    }
+    /// Append samples, for streaming audio into the waveform
+    pub fn push_samples(&mut self, samples: &[f32]) {
+        self.samples.extend(samples.iter().cloned());
+        while self.samples.len() > self.max_samples {
+            self.samples.pop_front();
+        }
+    }
+    pub fn clear(&mut self) {
+        self.samples.clear();
+    }
+    pub fn len(&self) -> usize {
+        self.samples.len()
+    }
+    pub fn is_empty(&self) -> bool {
+        self.samples.is_empty()
+    }
+    /// Zoom by `factor`, keeping the sample at `anchor` (a fraction of the bounds width) in place
+    pub fn zoom_by(&mut self, factor: f32, anchor: f32) {
+        let old_visible = 1.0 / self.zoom;
+        self.zoom = (self.zoom * factor).max(1.0);
+        let visible = 1.0 / self.zoom;
+        self.offset += (old_visible - visible) * anchor;
+        self.clamp_offset();
+    }
+    /// Scroll by `amount`, a fraction of the visible range
+    pub fn scroll_by(&mut self, amount: f32) {
+        self.offset += amount / self.zoom;
+        self.clamp_offset();
+    }
+    fn clamp_offset(&mut self) {
+        self.offset = self.offset.max(0.0).min(1.0 - 1.0 / self.zoom);
+    }
+}
+
+impl Draw for WaveformState {
+    fn draw(&mut self, bounds: Rect, _: Rect, renderer: &mut RenderBuilder) {
+        renderer.builder.push_rect(&PrimitiveInfo::new(bounds), self.background_color.into());
+        let columns = bounds.width().floor() as usize;
+        if self.samples.is_empty() || columns == 0 {
+            return;
+        }
+        let center = bounds.center().y;
+        let half_height = bounds.height() / 2.0;
+        let len = self.samples.len() as f32;
+        let start = self.offset * len;
+        let samples_per_column = len / self.zoom / columns as f32;
+        for column in 0..columns {
+            let from = (start + column as f32 * samples_per_column) as usize;
+            let to = ::std::cmp::max(from + 1, (start + (column + 1) as f32 * samples_per_column) as usize);
+            let to = ::std::cmp::min(to, self.samples.len());
+            let (mut min, mut max) = (0.0f32, 0.0f32);
+            for index in from..to {
+                min = min.min(self.samples[index]);
+                max = max.max(self.samples[index]);
+            }
+            let top = center - max.min(1.0) * half_height;
+            let bottom = center - min.max(-1.0) * half_height;
+            let rect = Rect::new(
+                Point::new(bounds.left() + column as f32, top),
+                Size::new(1.0, (bottom - top).max(1.0)));
+            renderer.builder.push_rect(&PrimitiveInfo::new(rect), self.color.into());
+        }
+    }
+}
@@ -0,0 +1,61 @@
+//! The standard widget set for Limn, and the `Draw` states used to draw them.
+//!
+//! Only depends on the public API of `limn-core`, so alternative widget sets can be built the same way.
+//! Everything in `limn-core` is re-exported, `limn` re-exports this crate.
+
+// ---- START CLIPPY CONFIG
+
+#![cfg_attr(all(not(test), feature="clippy"), warn(result_unwrap_used))]
+#![cfg_attr(feature="clippy", warn(unseparated_literal_suffix))]
+#![cfg_attr(feature="clippy", warn(wrong_pub_self_convention))]
+
+// Enable clippy if our Cargo.toml file asked us to do so.
+#![cfg_attr(feature="clippy", feature(plugin))]
+#![cfg_attr(feature="clippy", plugin(clippy))]
+
+#![warn(trivial_numeric_casts,
+        trivial_casts,
+        unused_extern_crates,
+        unused_import_braces,
+        unused_qualifications)]
+#![cfg_attr(feature="clippy", warn(cast_possible_truncation))]
+#![cfg_attr(feature="clippy", warn(cast_possible_wrap))]
+#![cfg_attr(feature="clippy", warn(cast_precision_loss))]
+#![cfg_attr(feature="clippy", warn(cast_sign_loss))]
+#![cfg_attr(feature="clippy", warn(missing_docs_in_private_items))]
+#![cfg_attr(feature="clippy", warn(mut_mut))]
+
+// Disallow `println!`. Use `debug!` for debug output
+// (which is provided by the `log` crate).
+#![cfg_attr(feature="clippy", warn(print_stdout))]
+
+// This allows us to use `unwrap` on `Option` values (because doing makes
+// working with Regex matches much nicer) and when compiling in test mode
+// (because using it in tests is idiomatic).
+#![cfg_attr(all(not(test), feature="clippy"), warn(result_unwrap_used))]
+#![cfg_attr(feature="clippy", warn(unseparated_literal_suffix))]
+#![cfg_attr(feature="clippy", warn(wrong_pub_self_convention))]
+
+// ---- END CLIPPY CONFIG
+
+// uncomment to debug macros
+//#![feature(trace_macros)]
+//trace_macros!(true);
+
+#[macro_use]
+extern crate limn_core as core;
+#[macro_use]
+extern crate log;
+
+pub use core::*;
+
+pub mod draw;
+pub mod widgets;
+#[cfg(feature = "theme-preview")]
+pub mod theme_preview;
+
+pub mod prelude {
+    pub use core::prelude::*;
+    pub use draw::prelude::*;
+    pub use widgets::prelude::*;
+}
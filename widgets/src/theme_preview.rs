@@ -0,0 +1,155 @@
+//! Development tool for previewing themes, enabled with the `theme-preview` feature.
+//!
+//! Renders the same widgets under every registered theme at several hidpi factors, and lays the
+//! results out on a contact sheet, one row per theme and one column per hidpi factor.
+//! Captures can be saved as reference images and later captures compared against them,
+//! to catch unintended changes to a theme.
+
+use std::fs;
+use std::io;
+use std::path::Path;
+
+use image::{self, Rgba, RgbaImage};
+
+use app::App;
+use widget::Widget;
+use style::Theme;
+use resources::resources;
+
+/// Space between images on the contact sheet, in pixels
+const SHEET_SPACING: u32 = 10;
+
+/// Widgets rendered under one theme at one hidpi factor
+pub struct Capture {
+    pub theme: String,
+    pub hidpi_factor: f32,
+    pub image: RgbaImage,
+}
+
+impl Capture {
+    /// File name used when saving or comparing, ex. "dark@2x.png"
+    pub fn file_name(&self) -> String {
+        format!("{}@{}x.png", self.theme, self.hidpi_factor)
+    }
+}
+
+/// A capture that differs from its reference image
+#[derive(Debug, Clone)]
+pub struct Regression {
+    pub file_name: String,
+    /// Fraction of pixels that differ, 1.0 if the image size changed
+    pub difference: f32,
+}
+
+pub struct ThemePreview {
+    themes: Vec<(String, Box<Fn(&mut Theme)>)>,
+    hidpi_factors: Vec<f32>,
+}
+
+impl Default for ThemePreview {
+    fn default() -> Self {
+        ThemePreview {
+            themes: Vec::new(),
+            hidpi_factors: vec![1.0, 2.0],
+        }
+    }
+}
+
+impl ThemePreview {
+    pub fn new() -> Self {
+        Self::default()
+    }
+    /// Add a theme, `register` is called on an empty theme to register its styles
+    pub fn add_theme<F: Fn(&mut Theme) + 'static>(&mut self, name: &str, register: F) -> &mut Self {
+        self.themes.push((name.to_owned(), Box::new(register)));
+        self
+    }
+    pub fn set_hidpi_factors(&mut self, hidpi_factors: &[f32]) -> &mut Self {
+        self.hidpi_factors = hidpi_factors.to_vec();
+        self
+    }
+
+    /// Render the widgets created by `build` under every theme at every hidpi factor.
+    /// The widgets are rebuilt for each capture, so they pick up the current theme.
+    /// The last theme is left registered afterwards.
+    pub fn capture<F: Fn() -> Widget>(&self, app: &mut App, build: F) -> Vec<Capture> {
+        let mut captures = Vec::new();
+        for &(ref name, ref register) in &self.themes {
+            {
+                let mut resources = resources();
+                resources.theme = Theme::new();
+                register(&mut resources.theme);
+            }
+            for &hidpi_factor in &self.hidpi_factors {
+                captures.push(Capture {
+                    theme: name.clone(),
+                    hidpi_factor: hidpi_factor,
+                    image: app.capture(build(), hidpi_factor),
+                });
+            }
+        }
+        captures
+    }
+
+    /// Lay out captures in a grid, one row per theme and one column per hidpi factor
+    pub fn contact_sheet(&self, captures: &[Capture]) -> RgbaImage {
+        let columns = self.hidpi_factors.len().max(1) as u32;
+        let rows = (captures.len() as u32 + columns - 1) / columns;
+        let column_width = captures.iter().map(|capture| capture.image.width()).max().unwrap_or(0);
+        let row_height = captures.iter().map(|capture| capture.image.height()).max().unwrap_or(0);
+        let mut sheet = RgbaImage::from_pixel(
+            SHEET_SPACING + columns * (column_width + SHEET_SPACING),
+            SHEET_SPACING + rows * (row_height + SHEET_SPACING),
+            Rgba([255, 255, 255, 255]));
+        for (index, capture) in captures.iter().enumerate() {
+            let index = index as u32;
+            let left = SHEET_SPACING + (index % columns) * (column_width + SHEET_SPACING);
+            let top = SHEET_SPACING + (index / columns) * (row_height + SHEET_SPACING);
+            for (x, y, pixel) in capture.image.enumerate_pixels() {
+                sheet.put_pixel(left + x, top + y, *pixel);
+            }
+        }
+        sheet
+    }
+
+    /// Save each capture in `dir` as a reference for `compare`
+    pub fn save(&self, captures: &[Capture], dir: &Path) -> io::Result<()> {
+        fs::create_dir_all(dir)?;
+        for capture in captures {
+            capture.image.save(dir.join(capture.file_name()))?;
+        }
+        Ok(())
+    }
+
+    /// Compare captures with the reference images saved in `dir`, returns the captures where more than
+    /// `tolerance` of the pixels differ. Captures without a reference image are ignored.
+    pub fn compare(&self, captures: &[Capture], dir: &Path, tolerance: f32) -> Vec<Regression> {
+        let mut regressions = Vec::new();
+        for capture in captures {
+            let file_name = capture.file_name();
+            let reference = match image::open(dir.join(&file_name)) {
+                Ok(reference) => reference.to_rgba(),
+                Err(_) => continue,
+            };
+            let difference = difference(&capture.image, &reference);
+            if difference > tolerance {
+                regressions.push(Regression {
+                    file_name: file_name,
+                    difference: difference,
+                });
+            }
+        }
+        regressions
+    }
+}
+
+/// Fraction of pixels that differ between two images, ignoring small differences from antialiasing
+fn difference(a: &RgbaImage, b: &RgbaImage) -> f32 {
+    if a.dimensions() != b.dimensions() {
+        return 1.0;
+    }
+    let differing = a.pixels().zip(b.pixels()).filter(|&(a, b)| {
+        a.data.iter().zip(b.data.iter()).any(|(&a, &b)| (a as i32 - b as i32).abs() > 8)
+    }).count();
+    differing as f32 / (a.width() * a.height()).max(1) as f32
+}
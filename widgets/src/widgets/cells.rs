@@ -0,0 +1,886 @@
+//! A spreadsheet style grid of cells, as in the 7GUIs Cells task.
+//!
+//! Each cell holds either plain text, a number, or a formula starting with `=`,
+//! ex. `=A1 * 2`, `=sum(B1:B10) / count(B1:B10)`. Selecting a cell shows its contents
+//! in the formula bar, where it can be edited. When a cell changes, only the cells that
+//! depend on it, directly or indirectly, are recomputed, and only the cells whose value
+//! actually changed are sent new text.
+//!
+//! Once a cell is selected the arrow keys move the selection, home and end move it to the first
+//! and last cell of the row. Each time the selection moves a `CellAnnounced` event is sent to the
+//! cells widget, describing the cell as a screen reader would announce it: its value, and the
+//! headers of the column or row it moved into.
+//!
+//! Numbers can be formatted conditionally by sending the cells widget a `SetConditionalFormat`.
+//! Cells are restyled when their value changes, or all of them when the range of the values
+//! changes, for formats with gradients or icon sets spanning it.
+
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::fmt;
+
+use format;
+use prelude::*;
+use draw::prelude::*;
+use widgets::edit_text::{EditText, TextUpdated};
+use widgets::scroll::ScrollContainer;
+use widgets::text::StaticTextStyle;
+use input::keyboard::{WidgetKeyboardInput, UnhandledKeyboardInput};
+
+const CELL_WIDTH: f32 = 80.0;
+const CELL_HEIGHT: f32 = 24.0;
+
+/// The location of a cell, ex. `B3` is column 1, row 2.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct CellRef {
+    pub column: usize,
+    pub row: usize,
+}
+
+impl CellRef {
+    pub fn new(column: usize, row: usize) -> Self {
+        CellRef { column: column, row: row }
+    }
+    /// Parse a reference in the form `A1`, column letters are case insensitive.
+    pub fn parse(text: &str) -> Option<Self> {
+        let letters = text.chars().take_while(|c| c.is_ascii_alphabetic()).count();
+        if letters == 0 || letters == text.len() {
+            return None;
+        }
+        let mut column: usize = 0;
+        for c in text[..letters].chars() {
+            let digit = c.to_ascii_uppercase() as usize - 'A' as usize + 1;
+            column = column.checked_mul(26)?.checked_add(digit)?;
+        }
+        // rows too large for a usize fail to parse
+        let row = text[letters..].parse::<usize>().ok()?;
+        if row == 0 {
+            return None;
+        }
+        Some(CellRef::new(column - 1, row - 1))
+    }
+    pub fn column_name(column: usize) -> String {
+        let mut name = Vec::new();
+        let mut column = column + 1;
+        while column > 0 {
+            name.push((b'A' + ((column - 1) % 26) as u8) as char);
+            column = (column - 1) / 26;
+        }
+        name.iter().rev().collect()
+    }
+}
+
+impl fmt::Display for CellRef {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}{}", CellRef::column_name(self.column), self.row + 1)
+    }
+}
+
+/// The computed contents of a cell.
+#[derive(Debug, Clone, PartialEq)]
+pub enum CellValue {
+    Empty,
+    Number(f64),
+    Text(String),
+    Error(String),
+}
+
+impl CellValue {
+    fn number(&self) -> Result<f64, String> {
+        match *self {
+            CellValue::Empty => Ok(0.0),
+            CellValue::Number(value) => Ok(value),
+            CellValue::Text(_) => Err("#VALUE".to_owned()),
+            CellValue::Error(ref error) => Err(error.clone()),
+        }
+    }
+}
+
+impl fmt::Display for CellValue {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            CellValue::Empty => Ok(()),
+            CellValue::Number(value) => write!(f, "{}", NumberFormat::new().set_grouping(false).set_decimals(0, 10).format(value)),
+            CellValue::Text(ref text) => write!(f, "{}", text),
+            CellValue::Error(ref error) => write!(f, "{}", error),
+        }
+    }
+}
+
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum Operator {
+    Add,
+    Sub,
+    Mul,
+    Div,
+}
+
+/// The parsed contents of a cell.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Formula {
+    Empty,
+    Text(String),
+    Number(f64),
+    Ref(CellRef),
+    Range(CellRef, CellRef),
+    Negate(Box<Formula>),
+    Binary(Operator, Box<Formula>, Box<Formula>),
+    Call(String, Vec<Formula>),
+}
+
+impl Formula {
+    /// Parse the contents of a cell. Anything not starting with `=` is a number, written as the
+    /// locale writes numbers, or text.
+    pub fn parse(text: &str) -> Result<Formula, String> {
+        let trimmed = text.trim();
+        if trimmed.is_empty() {
+            return Ok(Formula::Empty);
+        }
+        if !trimmed.starts_with('=') {
+            return Ok(match format::parse_number(trimmed) {
+                Some(value) => Formula::Number(value),
+                None => Formula::Text(text.to_owned()),
+            });
+        }
+        let mut parser = Parser { chars: trimmed[1..].chars().collect(), pos: 0 };
+        let formula = parser.expr()?;
+        parser.skip_whitespace();
+        if parser.pos < parser.chars.len() {
+            return Err(format!("unexpected '{}'", parser.chars[parser.pos]));
+        }
+        Ok(formula)
+    }
+
+    /// All the cells this formula reads from, with ranges expanded and clipped to a sheet of
+    /// `size` columns and rows
+    pub fn references(&self, refs: &mut Vec<CellRef>, size: (usize, usize)) {
+        match *self {
+            Formula::Ref(cell) => refs.push(cell),
+            Formula::Range(start, end) => refs.extend(range_cells(start, end, size)),
+            Formula::Negate(ref formula) => formula.references(refs, size),
+            Formula::Binary(_, ref left, ref right) => {
+                left.references(refs, size);
+                right.references(refs, size);
+            }
+            Formula::Call(_, ref args) => {
+                for arg in args {
+                    arg.references(refs, size);
+                }
+            }
+            _ => (),
+        }
+    }
+
+    fn eval<F: Fn(CellRef) -> CellValue>(&self, lookup: &F, size: (usize, usize)) -> CellValue {
+        match *self {
+            Formula::Empty => CellValue::Empty,
+            Formula::Text(ref text) => CellValue::Text(text.clone()),
+            Formula::Number(value) => CellValue::Number(value),
+            Formula::Ref(cell) => lookup(cell),
+            Formula::Range(..) => CellValue::Error("#RANGE".to_owned()),
+            _ => match self.eval_number(lookup, size) {
+                Ok(value) => CellValue::Number(value),
+                Err(error) => CellValue::Error(error),
+            }
+        }
+    }
+
+    fn eval_number<F: Fn(CellRef) -> CellValue>(&self, lookup: &F, size: (usize, usize)) -> Result<f64, String> {
+        match *self {
+            Formula::Negate(ref formula) => Ok(-formula.eval_number(lookup, size)?),
+            Formula::Binary(op, ref left, ref right) => {
+                let left = left.eval_number(lookup, size)?;
+                let right = right.eval_number(lookup, size)?;
+                match op {
+                    Operator::Add => Ok(left + right),
+                    Operator::Sub => Ok(left - right),
+                    Operator::Mul => Ok(left * right),
+                    Operator::Div if right == 0.0 => Err("#DIV/0".to_owned()),
+                    Operator::Div => Ok(left / right),
+                }
+            }
+            Formula::Call(ref name, ref args) => {
+                let mut values = Vec::new();
+                for arg in args {
+                    if let Formula::Range(start, end) = *arg {
+                        for cell in range_cells(start, end, size) {
+                            match lookup(cell) {
+                                CellValue::Empty => (),
+                                value => values.push(value.number()?),
+                            }
+                        }
+                    } else {
+                        values.push(arg.eval_number(lookup, size)?);
+                    }
+                }
+                call(name, &values)
+            }
+            _ => self.eval(lookup, size).number(),
+        }
+    }
+}
+
+fn call(name: &str, values: &[f64]) -> Result<f64, String> {
+    match name {
+        "sum" => Ok(values.iter().sum()),
+        "prod" => Ok(values.iter().product()),
+        "count" => Ok(values.len() as f64),
+        "avg" if values.is_empty() => Err("#DIV/0".to_owned()),
+        "avg" => Ok(values.iter().sum::<f64>() / values.len() as f64),
+        "min" => Ok(values.iter().cloned().fold(::std::f64::INFINITY, f64::min)),
+        "max" => Ok(values.iter().cloned().fold(::std::f64::NEG_INFINITY, f64::max)),
+        _ => Err("#NAME".to_owned()),
+    }
+}
+
+/// The cells in a range, leaving out those outside a sheet of `size` columns and rows, so ranges
+/// typed past the edge of the sheet don't expand to cells that can't exist
+fn range_cells(start: CellRef, end: CellRef, size: (usize, usize)) -> Vec<CellRef> {
+    let (columns, rows) = size;
+    let mut cells = Vec::new();
+    for column in start.column.min(end.column)..(start.column.max(end.column) + 1).min(columns) {
+        for row in start.row.min(end.row)..(start.row.max(end.row) + 1).min(rows) {
+            cells.push(CellRef::new(column, row));
+        }
+    }
+    cells
+}
+
+/// Recursive descent parser for the expression following `=`
+struct Parser {
+    chars: Vec<char>,
+    pos: usize,
+}
+
+impl Parser {
+    fn skip_whitespace(&mut self) {
+        while self.pos < self.chars.len() && self.chars[self.pos].is_whitespace() {
+            self.pos += 1;
+        }
+    }
+    fn peek(&mut self) -> Option<char> {
+        self.skip_whitespace();
+        self.chars.get(self.pos).cloned()
+    }
+    fn expect(&mut self, expected: char) -> Result<(), String> {
+        if self.peek() == Some(expected) {
+            self.pos += 1;
+            Ok(())
+        } else {
+            Err(format!("expected '{}'", expected))
+        }
+    }
+    fn expr(&mut self) -> Result<Formula, String> {
+        let mut formula = self.term()?;
+        loop {
+            let op = match self.peek() {
+                Some('+') => Operator::Add,
+                Some('-') => Operator::Sub,
+                _ => return Ok(formula),
+            };
+            self.pos += 1;
+            formula = Formula::Binary(op, Box::new(formula), Box::new(self.term()?));
+        }
+    }
+    fn term(&mut self) -> Result<Formula, String> {
+        let mut formula = self.factor()?;
+        loop {
+            let op = match self.peek() {
+                Some('*') => Operator::Mul,
+                Some('/') => Operator::Div,
+                _ => return Ok(formula),
+            };
+            self.pos += 1;
+            formula = Formula::Binary(op, Box::new(formula), Box::new(self.factor()?));
+        }
+    }
+    fn factor(&mut self) -> Result<Formula, String> {
+        match self.peek() {
+            Some('-') => {
+                self.pos += 1;
+                Ok(Formula::Negate(Box::new(self.factor()?)))
+            }
+            Some('(') => {
+                self.pos += 1;
+                let formula = self.expr()?;
+                self.expect(')')?;
+                Ok(formula)
+            }
+            Some(c) if c.is_ascii_digit() || c == '.' => {
+                let number = self.word(|c| c.is_ascii_digit() || c == '.');
+                number.parse::<f64>().map(Formula::Number).map_err(|_| format!("invalid number '{}'", number))
+            }
+            Some(c) if c.is_ascii_alphabetic() => {
+                let word = self.word(|c| c.is_ascii_alphanumeric());
+                if self.peek() == Some('(') {
+                    self.pos += 1;
+                    let mut args = Vec::new();
+                    if self.peek() != Some(')') {
+                        loop {
+                            args.push(self.expr()?);
+                            if self.peek() != Some(',') {
+                                break;
+                            }
+                            self.pos += 1;
+                        }
+                    }
+                    self.expect(')')?;
+                    return Ok(Formula::Call(word.to_lowercase(), args));
+                }
+                let start = CellRef::parse(&word).ok_or_else(|| format!("invalid cell '{}'", word))?;
+                if self.peek() == Some(':') {
+                    self.pos += 1;
+                    self.skip_whitespace();
+                    let word = self.word(|c| c.is_ascii_alphanumeric());
+                    let end = CellRef::parse(&word).ok_or_else(|| format!("invalid cell '{}'", word))?;
+                    Ok(Formula::Range(start, end))
+                } else {
+                    Ok(Formula::Ref(start))
+                }
+            }
+            Some(c) => Err(format!("unexpected '{}'", c)),
+            None => Err("unexpected end of formula".to_owned()),
+        }
+    }
+    fn word<F: Fn(char) -> bool>(&mut self, accept: F) -> String {
+        let start = self.pos;
+        while self.pos < self.chars.len() && accept(self.chars[self.pos]) {
+            self.pos += 1;
+        }
+        self.chars[start..self.pos].iter().collect()
+    }
+}
+
+/// The cell contents and computed values, with the dependencies between cells
+/// tracked so that edits only recompute what they affect.
+#[derive(Debug)]
+pub struct Sheet {
+    /// Columns and rows in the sheet, ranges are clipped to them
+    size: (usize, usize),
+    sources: HashMap<CellRef, String>,
+    formulas: HashMap<CellRef, Result<Formula, String>>,
+    values: HashMap<CellRef, CellValue>,
+    dependencies: HashMap<CellRef, Vec<CellRef>>,
+    dependents: HashMap<CellRef, HashSet<CellRef>>,
+}
+
+impl Sheet {
+    pub fn new(columns: usize, rows: usize) -> Self {
+        Sheet {
+            size: (columns, rows),
+            sources: HashMap::new(),
+            formulas: HashMap::new(),
+            values: HashMap::new(),
+            dependencies: HashMap::new(),
+            dependents: HashMap::new(),
+        }
+    }
+    /// The text the cell was last set to
+    pub fn source(&self, cell: CellRef) -> &str {
+        self.sources.get(&cell).map_or("", |source| source.as_str())
+    }
+    pub fn value(&self, cell: CellRef) -> CellValue {
+        self.values.get(&cell).cloned().unwrap_or(CellValue::Empty)
+    }
+    /// Set the contents of a cell, returning every cell whose value changed as a result.
+    pub fn set(&mut self, cell: CellRef, text: &str) -> Vec<CellRef> {
+        for dependency in self.dependencies.remove(&cell).unwrap_or_default() {
+            if let Some(dependents) = self.dependents.get_mut(&dependency) {
+                dependents.remove(&cell);
+            }
+        }
+        let formula = Formula::parse(text);
+        let mut dependencies = Vec::new();
+        if let Ok(ref formula) = formula {
+            formula.references(&mut dependencies, self.size);
+        }
+        // a cell referenced twice is still only one dependency when recomputing in order
+        dependencies.sort();
+        dependencies.dedup();
+        for &dependency in &dependencies {
+            self.dependents.entry(dependency).or_insert_with(HashSet::new).insert(cell);
+        }
+        self.dependencies.insert(cell, dependencies);
+        self.sources.insert(cell, text.to_owned());
+        self.formulas.insert(cell, formula);
+        self.recompute(cell)
+    }
+
+    /// Recompute `cell` and everything depending on it, in dependency order.
+    /// Cells that are part of a cycle can't be ordered, and get an error value instead.
+    fn recompute(&mut self, cell: CellRef) -> Vec<CellRef> {
+        let mut affected = HashSet::new();
+        let mut queue = VecDeque::new();
+        queue.push_back(cell);
+        while let Some(next) = queue.pop_front() {
+            if affected.insert(next) {
+                if let Some(dependents) = self.dependents.get(&next) {
+                    queue.extend(dependents.iter().cloned());
+                }
+            }
+        }
+        let mut pending: HashMap<CellRef, usize> = affected.iter().map(|&cell| {
+            let count = self.dependencies.get(&cell).map_or(0, |dependencies| {
+                dependencies.iter().filter(|dependency| affected.contains(dependency)).count()
+            });
+            (cell, count)
+        }).collect();
+        let mut ready: VecDeque<CellRef> = pending.iter()
+            .filter(|&(_, &count)| count == 0)
+            .map(|(&cell, _)| cell)
+            .collect();
+        let mut changed = Vec::new();
+        while let Some(next) = ready.pop_front() {
+            pending.remove(&next);
+            let value = match self.formulas.get(&next) {
+                Some(&Ok(ref formula)) => formula.eval(&|cell| self.value(cell), self.size),
+                Some(&Err(_)) => CellValue::Error("#PARSE".to_owned()),
+                None => CellValue::Empty,
+            };
+            self.update_value(next, value, &mut changed);
+            if let Some(dependents) = self.dependents.get(&next) {
+                for dependent in dependents {
+                    if let Some(count) = pending.get_mut(dependent) {
+                        *count -= 1;
+                        if *count == 0 {
+                            ready.push_back(*dependent);
+                        }
+                    }
+                }
+            }
+        }
+        let cyclic: Vec<CellRef> = pending.keys().cloned().collect();
+        for cell in cyclic {
+            self.update_value(cell, CellValue::Error("#CYCLE".to_owned()), &mut changed);
+        }
+        changed
+    }
+
+    fn update_value(&mut self, cell: CellRef, value: CellValue, changed: &mut Vec<CellRef>) {
+        if self.value(cell) != value {
+            self.values.insert(cell, value);
+            changed.push(cell);
+        }
+    }
+}
+
+/// Sent to the cells widget when the selection moves, describing the selected cell for assistive
+/// technology, ex. to be spoken by a screen reader
+#[derive(Debug, Clone, PartialEq)]
+pub struct CellAnnounced {
+    pub cell: CellRef,
+    /// Header of the cell's column, ex. `B`
+    pub column_header: String,
+    /// Header of the cell's row, ex. `3`
+    pub row_header: String,
+    /// What to announce: the headers of the column and row, if the selection moved into a
+    /// different one, followed by the value, ex. "column B, 42"
+    pub text: String,
+}
+
+#[derive(Debug, Clone)]
+enum CellsEvent {
+    Select(CellRef),
+    /// Move the selection by a number of columns and rows
+    Move(isize, isize),
+    /// Move the selection to the first or last cell of its row
+    RowEdge(bool),
+    Edit(String),
+    SetFormat(Option<ConditionalFormat>),
+}
+
+struct CellsHandler {
+    columns: usize,
+    rows: usize,
+    sheet: Sheet,
+    formula_bar: Widget,
+    /// The widget of each cell, and of its text
+    cells: HashMap<CellRef, (Widget, Widget)>,
+    selected: Option<CellRef>,
+    format: Option<ConditionalFormat>,
+    /// The style each formatted cell was last given
+    styles: HashMap<CellRef, ValueStyle>,
+}
+
+impl CellsHandler {
+    /// Fit the format to the values of the sheet, returns true if that changed the format
+    fn fit_format(&mut self) -> bool {
+        let sheet = &self.sheet;
+        let values = self.cells.keys().filter_map(|&cell| match sheet.value(cell) {
+            CellValue::Number(value) => Some(value),
+            _ => None,
+        });
+        match self.format {
+            Some(ref mut format) => {
+                let before = format.clone();
+                format.fit(values);
+                *format != before
+            }
+            None => false,
+        }
+    }
+
+    /// Show the value of `cell`, in the style the format gives it
+    fn show_value(&mut self, cell: CellRef) {
+        let value = self.sheet.value(cell);
+        let style = match (self.format.as_ref(), &value) {
+            (Some(format), &CellValue::Number(number)) => format.style(number),
+            _ => ValueStyle::default(),
+        };
+        let (ref mut cell_widget, ref mut text_widget) = *self.cells.get_mut(&cell).unwrap();
+        text_widget.event_subtree(TextUpdated(style.decorate(&value.to_string())));
+        if self.styles.get(&cell).unwrap_or(&ValueStyle::default()) != &style {
+            // styles merge, so parts the format doesn't set are reset explicitly
+            cell_widget.set_draw_style(style!(RectStyle {
+                background_color: style.background.unwrap_or(WHITE),
+            }));
+            text_widget.set_draw_style(style!(TextStyle {
+                text_color: style.color.unwrap_or(BLACK),
+            }));
+            self.styles.insert(cell, style);
+        }
+    }
+
+    /// Select `cell`, scroll it into view and announce it
+    fn select(&mut self, cell: CellRef, widget: &Widget) {
+        let previous = self.selected.take();
+        if let Some(selected) = previous {
+            self.cells.get_mut(&selected).unwrap().0.remove_prop(Property::Selected);
+        }
+        let mut cell_widget = self.cells[&cell].0.clone();
+        cell_widget.add_prop(Property::Selected);
+        self.selected = Some(cell);
+        let source = self.sheet.source(cell).to_owned();
+        self.formula_bar.event_subtree(TextUpdated(source));
+        cell_widget.event_bubble_up(ScrollIntoView {
+            area: cell_widget.bounds(),
+            visible: None,
+        });
+        widget.event(self.announcement(cell, previous));
+    }
+
+    /// Describe `cell`, naming the headers of the column and row if they differ from `previous`
+    fn announcement(&self, cell: CellRef, previous: Option<CellRef>) -> CellAnnounced {
+        let column_header = CellRef::column_name(cell.column);
+        let row_header = (cell.row + 1).to_string();
+        let mut parts = Vec::new();
+        if previous.map_or(true, |previous| previous.column != cell.column) {
+            parts.push(format!("column {}", column_header));
+        }
+        if previous.map_or(true, |previous| previous.row != cell.row) {
+            parts.push(format!("row {}", row_header));
+        }
+        let value = self.sheet.value(cell);
+        parts.push(if value == CellValue::Empty { "blank".to_owned() } else { value.to_string() });
+        CellAnnounced {
+            cell: cell,
+            column_header: column_header,
+            row_header: row_header,
+            text: parts.join(", "),
+        }
+    }
+}
+
+impl EventHandler<CellsEvent> for CellsHandler {
+    fn handle(&mut self, event: &CellsEvent, args: EventArgs) {
+        match *event {
+            CellsEvent::Select(cell) => self.select(cell, &args.widget),
+            CellsEvent::Move(columns, rows) => {
+                if let Some(selected) = self.selected {
+                    let step = |index: usize, by: isize, len: usize| {
+                        (index as isize + by).max(0).min(len as isize - 1) as usize
+                    };
+                    let cell = CellRef::new(step(selected.column, columns, self.columns), step(selected.row, rows, self.rows));
+                    if cell != selected {
+                        self.select(cell, &args.widget);
+                    }
+                }
+            }
+            CellsEvent::RowEdge(end) => {
+                if let Some(selected) = self.selected {
+                    let column = if end { self.columns - 1 } else { 0 };
+                    if column != selected.column {
+                        self.select(CellRef::new(column, selected.row), &args.widget);
+                    }
+                }
+            }
+            CellsEvent::Edit(ref text) => {
+                if let Some(selected) = self.selected {
+                    if self.sheet.source(selected) == text.as_str() {
+                        return;
+                    }
+                    let changed = self.sheet.set(selected, text);
+                    let changed = if self.fit_format() { self.cells.keys().cloned().collect() } else { changed };
+                    for cell in changed {
+                        self.show_value(cell);
+                    }
+                }
+            }
+            CellsEvent::SetFormat(ref format) => {
+                self.format = format.clone();
+                self.fit_format();
+                let cells: Vec<CellRef> = self.cells.keys().cloned().collect();
+                for cell in cells {
+                    self.show_value(cell);
+                }
+            }
+        }
+    }
+}
+
+/// Creates an editable grid of cells, with a formula bar above it
+#[derive(Debug, Copy, Clone)]
+pub struct CellsBuilder;
+
+impl CellsBuilder {
+    /// Creates a new `CellsBuilder` with the given number of columns and rows, returns it in form of a `Widget`
+    pub fn new(columns: usize, rows: usize) -> Widget {
+        let mut widget = Widget::new("cells");
+
+        let mut formula_bar = Widget::from_modifier(EditText::default());
+        formula_bar.set_name("formula_bar");
+        let cells_widget = widget.clone();
+        formula_bar.add_handler(move |event: &TextUpdated, _: EventArgs| {
+            cells_widget.event(CellsEvent::Edit(event.0.clone()));
+        });
+        formula_bar.layout().add(constraints![
+            align_top(&widget),
+            align_left(&widget),
+            align_right(&widget),
+        ]);
+
+        let mut grid = Widget::new("cells_grid");
+        grid.grid(columns + 1);
+        grid.layout().add(size(Size::new(
+            (columns + 1) as f32 * CELL_WIDTH,
+            (rows + 1) as f32 * CELL_HEIGHT)));
+
+        let mut cells = HashMap::new();
+        for row in 0..rows + 1 {
+            for column in 0..columns + 1 {
+                let header = match (column, row) {
+                    (0, 0) => Some(String::new()),
+                    (0, row) => Some(row.to_string()),
+                    (column, 0) => Some(CellRef::column_name(column - 1)),
+                    _ => None,
+                };
+                let mut cell_widget = Widget::new("cell");
+                let text = header.clone().unwrap_or_default();
+                let mut text_widget = Widget::from_modifier_style(StaticTextStyle::from_style(style!(TextStyle {
+                    text: text,
+                    font_size: 16.0,
+                })));
+                text_widget.set_name("cell_text");
+                text_widget.layout().add(constraints![
+                    align_left(&cell_widget).padding(3.0),
+                    center_vertical(&cell_widget),
+                ]);
+                if header.is_some() {
+                    cell_widget.set_draw_style(style!(RectStyle {
+                        background_color: GRAY_80,
+                        border: Some((0.5, GRAY_50)),
+                    }));
+                } else {
+                    let cell = CellRef::new(column - 1, row - 1);
+                    let mut draw_style = DrawStyle::from(style!(RectStyle {
+                        background_color: WHITE,
+                        border: Some((0.5, GRAY_70)),
+                    }));
+                    draw_style.prop_style(SELECTED.clone(), style!(RectStyle {
+                        border: Some((2.0, BLUE)),
+                    }));
+                    cell_widget.set_draw_style(draw_style);
+                    let cells_widget = widget.clone();
+                    cell_widget.add_handler(move |_: &ClickEvent, args: EventArgs| {
+                        cells_widget.event(CellsEvent::Select(cell));
+                        args.ui.get_root().event(KeyboardInputEvent::FocusChange(Some(cells_widget.clone())));
+                        *args.handled = true;
+                    });
+                    cells.insert(cell, (cell_widget.clone(), text_widget.clone()));
+                }
+                cell_widget.add_child(text_widget);
+                grid.add_child(cell_widget);
+            }
+        }
+
+        let mut scroll_widget = ScrollContainer::default();
+        scroll_widget.add_scrollbar().add_content(grid);
+        let mut scroll_widget = Widget::from_modifier(scroll_widget);
+        scroll_widget.layout().add(constraints![
+            below(&formula_bar).padding(5.0),
+            align_left(&widget),
+            align_right(&widget),
+            align_bottom(&widget),
+        ]);
+
+        widget.add_handler(CellsHandler {
+            columns: columns,
+            rows: rows,
+            sheet: Sheet::new(columns, rows),
+            formula_bar: formula_bar.clone(),
+            cells: cells,
+            selected: None,
+            format: None,
+            styles: HashMap::new(),
+        });
+        let cells_widget = widget.clone();
+        widget.add_handler(move |event: &SetConditionalFormat, _: EventArgs| {
+            cells_widget.event(CellsEvent::SetFormat(event.0.clone()));
+        });
+        widget.add_handler(|event: &WidgetKeyboardInput, args: EventArgs| {
+            let input = event.0;
+            if input.state != glutin::ElementState::Pressed {
+                return;
+            }
+            if let Some(event) = navigation_key(input.virtual_keycode) {
+                args.widget.event(event);
+            }
+        });
+        // arrow keys move the selection rather than scrolling the sheet or a containing scroll view
+        widget.add_handler(|event: &UnhandledKeyboardInput, args: EventArgs| {
+            if navigation_key(event.0.virtual_keycode).is_some() {
+                *args.handled = true;
+            }
+        });
+        widget
+            .add_child(formula_bar)
+            .add_child(scroll_widget);
+        widget
+    }
+}
+
+/// The selection change for a key, if it's used to move the selection
+fn navigation_key(key: Option<glutin::VirtualKeyCode>) -> Option<CellsEvent> {
+    use glutin::VirtualKeyCode::*;
+    match key {
+        Some(Left) => Some(CellsEvent::Move(-1, 0)),
+        Some(Right) => Some(CellsEvent::Move(1, 0)),
+        Some(Up) => Some(CellsEvent::Move(0, -1)),
+        Some(Down) => Some(CellsEvent::Move(0, 1)),
+        Some(Home) => Some(CellsEvent::RowEdge(false)),
+        Some(End) => Some(CellsEvent::RowEdge(true)),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn cell(name: &str) -> CellRef {
+        CellRef::parse(name).unwrap()
+    }
+    fn number(value: f64) -> Box<Formula> {
+        Box::new(Formula::Number(value))
+    }
+
+    #[test]
+    fn parse_precedence() {
+        assert_eq!(Formula::parse("=1+2*3"), Ok(Formula::Binary(Operator::Add, number(1.0),
+            Box::new(Formula::Binary(Operator::Mul, number(2.0), number(3.0))))));
+        assert_eq!(Formula::parse("=(1+2)*3"), Ok(Formula::Binary(Operator::Mul,
+            Box::new(Formula::Binary(Operator::Add, number(1.0), number(2.0))), number(3.0))));
+        assert_eq!(Formula::parse("=8-2-1"), Ok(Formula::Binary(Operator::Sub,
+            Box::new(Formula::Binary(Operator::Sub, number(8.0), number(2.0))), number(1.0))));
+        assert_eq!(Formula::parse("=-A1*2"), Ok(Formula::Binary(Operator::Mul,
+            Box::new(Formula::Negate(Box::new(Formula::Ref(cell("A1"))))), number(2.0))));
+    }
+
+    #[test]
+    fn parse_ranges_and_calls() {
+        assert_eq!(Formula::parse("=SUM(a1 : B2, 3)"), Ok(Formula::Call("sum".to_owned(),
+            vec![Formula::Range(cell("A1"), cell("B2")), Formula::Number(3.0)])));
+        assert_eq!(Formula::parse("=count()"), Ok(Formula::Call("count".to_owned(), vec![])));
+    }
+
+    #[test]
+    fn parse_errors() {
+        assert!(Formula::parse("=A0").is_err());
+        assert!(Formula::parse("=1A").is_err());
+        assert!(Formula::parse("=A1:").is_err());
+        assert!(Formula::parse("=(1+2").is_err());
+        assert!(Formula::parse("=1+").is_err());
+        assert!(Formula::parse("=1 2").is_err());
+        assert!(Formula::parse("=1..2").is_err());
+        assert_eq!(Formula::parse("plain text"), Ok(Formula::Text("plain text".to_owned())));
+        assert_eq!(Formula::parse("  "), Ok(Formula::Empty));
+        // the symbols would be lost if these were numbers
+        assert_eq!(Formula::parse("50%"), Ok(Formula::Text("50%".to_owned())));
+        assert_eq!(Formula::parse("$5"), Ok(Formula::Text("$5".to_owned())));
+    }
+
+    #[test]
+    fn cell_ref_overflow() {
+        assert_eq!(CellRef::parse("ZZ10"), Some(CellRef::new(26 * 26 + 25, 9)));
+        assert_eq!(CellRef::parse(&format!("{}1", "Z".repeat(40))), None);
+        assert_eq!(CellRef::parse("A99999999999999999999999"), None);
+        assert_eq!(CellRef::column_name(26 * 26 + 25), "ZZ");
+    }
+
+    #[test]
+    fn range_clipped_to_sheet() {
+        assert_eq!(range_cells(cell("B2"), cell("A1"), (10, 10)),
+            vec![cell("A1"), cell("A2"), cell("B1"), cell("B2")]);
+        assert_eq!(range_cells(cell("A1"), cell("ZZ100000"), (2, 1)), vec![cell("A1"), cell("B1")]);
+        assert!(range_cells(cell("C3"), cell("D4"), (2, 2)).is_empty());
+
+        let mut sheet = Sheet::new(2, 2);
+        sheet.set(cell("B1"), "1");
+        sheet.set(cell("B2"), "2");
+        sheet.set(cell("A1"), "=sum(B1:ZZ100000)");
+        assert_eq!(sheet.value(cell("A1")), CellValue::Number(3.0));
+    }
+
+    #[test]
+    fn recompute_in_dependency_order() {
+        let mut sheet = Sheet::new(5, 5);
+        sheet.set(cell("A1"), "1");
+        sheet.set(cell("B1"), "=A1*2");
+        sheet.set(cell("C1"), "=A1+B1");
+        sheet.set(cell("D1"), "=C1+C1");
+        assert_eq!(sheet.value(cell("D1")), CellValue::Number(6.0));
+
+        let changed = sheet.set(cell("A1"), "2");
+        assert_eq!(sheet.value(cell("B1")), CellValue::Number(4.0));
+        assert_eq!(sheet.value(cell("C1")), CellValue::Number(6.0));
+        assert_eq!(sheet.value(cell("D1")), CellValue::Number(12.0));
+        let position = |name| changed.iter().position(|&changed| changed == cell(name)).unwrap();
+        assert!(position("A1") < position("B1"));
+        assert!(position("B1") < position("C1"));
+        assert!(position("C1") < position("D1"));
+
+        // unchanged values aren't reported
+        assert!(sheet.set(cell("A1"), "2").is_empty());
+    }
+
+    #[test]
+    fn cycles() {
+        let mut sheet = Sheet::new(5, 5);
+        sheet.set(cell("A1"), "=B1");
+        sheet.set(cell("B1"), "=A1+1");
+        sheet.set(cell("C1"), "=B1");
+        let cycle = CellValue::Error("#CYCLE".to_owned());
+        assert_eq!(sheet.value(cell("A1")), cycle);
+        assert_eq!(sheet.value(cell("B1")), cycle);
+        assert_eq!(sheet.value(cell("C1")), cycle);
+
+        sheet.set(cell("B1"), "5");
+        assert_eq!(sheet.value(cell("A1")), CellValue::Number(5.0));
+        assert_eq!(sheet.value(cell("C1")), CellValue::Number(5.0));
+
+        sheet.set(cell("D1"), "=D1");
+        assert_eq!(sheet.value(cell("D1")), cycle);
+    }
+
+    #[test]
+    fn errors_propagate() {
+        let mut sheet = Sheet::new(5, 5);
+        sheet.set(cell("A1"), "=1/0");
+        sheet.set(cell("A2"), "=A1+1");
+        sheet.set(cell("A3"), "=(");
+        sheet.set(cell("A4"), "text");
+        sheet.set(cell("A5"), "=A4*2");
+        assert_eq!(sheet.value(cell("A2")), CellValue::Error("#DIV/0".to_owned()));
+        assert_eq!(sheet.value(cell("A3")), CellValue::Error("#PARSE".to_owned()));
+        assert_eq!(sheet.value(cell("A5")), CellValue::Error("#VALUE".to_owned()));
+    }
+}
@@ -0,0 +1,756 @@
+//! An interactive line chart, for exploring series of data.
+//!
+//! The mouse wheel zooms in and out around the cursor, holding shift zooms the y axis instead.
+//! Dragging pans, or, if brushing is enabled, selects a range of x, sending `ChartBrushed` to the
+//! chart widget so the selection can filter other views of the data; dragging with the right or
+//! middle button still pans. A crosshair follows the cursor, with the value of each series at the
+//! cursor shown below the chart.
+//!
+//! In streaming mode the x axis scrolls to keep the latest points in view, for monitoring
+//! dashboards. Points are appended with `ChartEvent::AppendPoints`, or from background threads
+//! with `receive_points`, series drop their oldest points beyond `Series::max_points`, and long
+//! histories are drawn decimated to the min and max of each pixel column. Panning or zooming stops
+//! the scrolling, so the user can look back, `ChartEvent::FitView` resumes it.
+//!
+//! Axes with ticks at "nice" values, grid lines, and a legend that shows and hides series when
+//! clicked can be added with the builder. Annotations, lines and shaded regions marking values
+//! of x or y, and callouts labelling points, are drawn with the data and move with the view.
+//! The legend is its own widget, built by `LegendBuilder`, for other kinds of charts to use.
+
+use format::NumberFormat;
+use stream::StreamReceiver;
+use prelude::*;
+use draw::prelude::*;
+use widgets::text::StaticTextStyle;
+use widgets::edit_text::TextUpdated;
+use input::mouse::MouseOverEvent;
+
+/// Brushes narrower than this, in pixels, are taken as a click and clear the brush
+const MIN_BRUSH_WIDTH: f32 = 3.0;
+/// Room for the labels of the y axis
+const Y_AXIS_WIDTH: f32 = 50.0;
+const X_AXIS_HEIGHT: f32 = 20.0;
+const SWATCH_SIZE: f32 = 12.0;
+
+/// Changes the data or view of a chart, send to the chart widget
+#[derive(Debug, Clone)]
+pub enum ChartEvent {
+    AddSeries(Series),
+    /// Replace the points of the series at an index
+    SetPoints(usize, Vec<(f32, f32)>),
+    /// Append points to the series at an index, with x at or after its last point
+    AppendPoints(usize, Vec<(f32, f32)>),
+    SetView(ChartView),
+    /// Fit the view to the data, or in streaming mode, resume scrolling with the latest points
+    FitView,
+    ClearBrush,
+    /// Show or hide the series at an index
+    SetSeriesVisible(usize, bool),
+    AddAnnotation(Annotation),
+    ClearAnnotations,
+}
+
+/// Sent to the chart widget when the view is panned or zoomed
+#[derive(Debug, Copy, Clone)]
+pub struct ChartViewChanged(pub ChartView);
+
+/// Sent to the chart widget when a range of x is selected by brushing, or `None` when it's cleared
+#[derive(Debug, Copy, Clone)]
+pub struct ChartBrushed(pub Option<(f32, f32)>);
+
+/// Sent to a legend widget when an entry is clicked, with the index of the entry and whether it's
+/// now shown
+#[derive(Debug, Copy, Clone)]
+pub struct LegendToggled(pub usize, pub bool);
+
+/// Sent to the chart widget when the crosshair moves
+#[derive(Debug, Clone)]
+pub struct ChartHovered {
+    pub x: f32,
+    pub y: f32,
+    /// The value of each series at `x`, `None` for hidden series and those that don't reach `x`
+    pub values: Vec<Option<f32>>,
+}
+
+#[derive(Debug, Copy, Clone)]
+enum ChartInput {
+    Moved(Point),
+    Out,
+    Wheel(glutin::MouseScrollDelta, glutin::ModifiersState),
+    Pressed(glutin::MouseButton),
+    Drag(DragEvent),
+}
+
+#[derive(Debug, Copy, Clone)]
+enum ChartDrag {
+    Pan,
+    /// Brushing, from this x
+    Brush(f32),
+}
+
+struct ChartHandler {
+    plot: Widget,
+    readout: Widget,
+    x_axis: Option<Widget>,
+    y_axis: Option<Widget>,
+    legend: Option<Widget>,
+    zoom_axes: ChartAxes,
+    pan_axes: ChartAxes,
+    brushing: bool,
+    crosshair: bool,
+    mouse: Option<Point>,
+    drag: Option<ChartDrag>,
+    format: NumberFormat,
+    /// Width of the x axis in streaming mode
+    streaming: Option<f32>,
+    /// Whether the x axis is scrolling with the latest points
+    following: bool,
+}
+
+multi_event!{impl EventHandler<ChartHandlerEvent> for ChartHandler {
+    ChartEvent => chart_event,
+    ChartInput => input,
+    LegendToggled => legend_toggled,
+}}
+
+impl ChartHandler {
+    fn state<T, F: FnOnce(&ChartState) -> T>(&mut self, f: F) -> T {
+        let draw_state = self.plot.draw_state();
+        f(draw_state.downcast_ref::<ChartState>().unwrap())
+    }
+
+    /// Scroll the x axis to end at the latest point, in streaming mode
+    fn follow(&mut self) {
+        if let (Some(window), true) = (self.streaming, self.following) {
+            self.plot.update(|state: &mut ChartState| {
+                let last = state.series.iter().filter_map(|series| series.last()).map(|(x, _)| x).fold(::std::f32::NEG_INFINITY, f32::max);
+                if last.is_finite() {
+                    state.view.x = (last - window, last);
+                }
+            });
+            self.update_axes();
+        }
+    }
+
+    fn update_axes(&mut self) {
+        let view = self.state(|state| state.view);
+        update_axes(&mut self.x_axis, &mut self.y_axis, view);
+    }
+
+    fn set_series_visible(&mut self, index: usize, visible: bool) {
+        self.plot.update(|state: &mut ChartState| {
+            if let Some(series) = state.series.get_mut(index) {
+                series.visible = visible;
+            }
+        });
+        if let Some(ref legend) = self.legend {
+            legend.event(LegendEvent::SetVisible(index, visible));
+        }
+    }
+
+    /// Change the view in response to the user, stops streaming mode scrolling
+    fn move_view(&mut self, view: ChartView, chart: &Widget) {
+        self.following = false;
+        self.set_view(view, chart);
+    }
+
+    fn set_view(&mut self, view: ChartView, chart: &Widget) {
+        self.plot.update(|state: &mut ChartState| state.view = view);
+        self.update_axes();
+        chart.event(ChartViewChanged(view));
+        if let Some(mouse) = self.mouse {
+            self.hover(mouse, chart);
+        }
+    }
+
+    fn set_brush(&mut self, brush: Option<(f32, f32)>) {
+        self.plot.update(|state: &mut ChartState| state.brush = brush);
+    }
+
+    fn hover(&mut self, mouse: Point, chart: &Widget) {
+        let bounds = self.plot.bounds();
+        if !self.crosshair || !bounds.contains(&mouse) {
+            return;
+        }
+        let (x, y, view, values, names) = self.state(|state| {
+            let (x, y) = state.view.to_data(bounds, mouse);
+            let values: Vec<Option<f32>> = state.series.iter().map(|series| {
+                if series.visible { series.value_at(x) } else { None }
+            }).collect();
+            let names: Vec<String> = state.series.iter().map(|series| series.name.clone()).collect();
+            (x, y, state.view, values, names)
+        });
+        self.plot.update(|state: &mut ChartState| state.crosshair = Some((x, y)));
+        // enough decimals to tell apart values a pixel apart
+        let decimals = |range: (f32, f32), pixels: f32| {
+            let per_pixel = ((range.1 - range.0) / pixels.max(1.0)).abs();
+            (-per_pixel.log10().floor()).max(0.0).min(6.0) as usize
+        };
+        let x_decimals = decimals(view.x, bounds.width());
+        let y_decimals = decimals(view.y, bounds.height());
+        let mut text = format!("x: {}", self.format.set_decimals(0, x_decimals).format(f64::from(x)));
+        for (name, value) in names.iter().zip(values.iter()) {
+            if let Some(value) = *value {
+                text.push_str(&format!("   {}: {}", name, self.format.set_decimals(0, y_decimals).format(f64::from(value))));
+            }
+        }
+        self.readout.event(TextUpdated(text));
+        chart.event(ChartHovered { x: x, y: y, values: values });
+    }
+
+    fn chart_event(&mut self, event: &ChartEvent, args: EventArgs) {
+        match *event {
+            ChartEvent::AddSeries(ref series) => {
+                if let Some(ref legend) = self.legend {
+                    legend.event(LegendEvent::AddEntry(series.name.clone(), series.color));
+                }
+                let series = series.clone();
+                self.plot.update(|state: &mut ChartState| state.series.push(series));
+            }
+            ChartEvent::SetPoints(index, ref points) => {
+                let points = points.clone();
+                self.plot.update(|state: &mut ChartState| {
+                    if let Some(series) = state.series.get_mut(index) {
+                        series.set_points(points);
+                    }
+                });
+                self.follow();
+            }
+            ChartEvent::AppendPoints(index, ref points) => {
+                self.plot.update(|state: &mut ChartState| {
+                    if let Some(series) = state.series.get_mut(index) {
+                        series.extend(points);
+                    }
+                });
+                self.follow();
+            }
+            ChartEvent::SetView(view) => self.set_view(view, &args.widget),
+            ChartEvent::FitView => {
+                let view = self.state(|state| ChartView::fit(&state.series));
+                self.following = true;
+                self.set_view(view, &args.widget);
+                self.follow();
+            }
+            ChartEvent::ClearBrush => {
+                self.set_brush(None);
+                args.widget.event(ChartBrushed(None));
+            }
+            ChartEvent::SetSeriesVisible(index, visible) => self.set_series_visible(index, visible),
+            ChartEvent::AddAnnotation(ref annotation) => {
+                let annotation = annotation.clone();
+                self.plot.update(|state: &mut ChartState| state.annotations.push(annotation));
+            }
+            ChartEvent::ClearAnnotations => {
+                self.plot.update(|state: &mut ChartState| state.annotations.clear());
+            }
+        }
+    }
+
+    fn legend_toggled(&mut self, event: &LegendToggled, _: EventArgs) {
+        let LegendToggled(index, visible) = *event;
+        self.plot.update(|state: &mut ChartState| {
+            if let Some(series) = state.series.get_mut(index) {
+                series.visible = visible;
+            }
+        });
+    }
+
+    fn input(&mut self, event: &ChartInput, args: EventArgs) {
+        let bounds = self.plot.bounds();
+        if bounds.width() <= 0.0 || bounds.height() <= 0.0 {
+            return;
+        }
+        match *event {
+            ChartInput::Moved(mouse) => {
+                self.mouse = Some(mouse);
+                self.hover(mouse, &args.widget);
+            }
+            ChartInput::Out => {
+                self.mouse = None;
+                self.plot.update(|state: &mut ChartState| state.crosshair = None);
+                self.readout.event(TextUpdated(String::new()));
+            }
+            ChartInput::Wheel(delta, modifiers) => {
+                let amount = match delta {
+                    glutin::MouseScrollDelta::LineDelta(_, y) => y,
+                    glutin::MouseScrollDelta::PixelDelta(glutin::dpi::LogicalPosition{y, ..}) => y as f32 / 13.0,
+                };
+                let mut view = self.state(|state| state.view);
+                let anchor = view.to_data(bounds, self.mouse.unwrap_or_else(|| bounds.center()));
+                let axes = if modifiers.shift { ChartAxes::Y } else { self.zoom_axes };
+                view.zoom(1.2f32.powf(amount), anchor, axes);
+                self.move_view(view, &args.widget);
+            }
+            ChartInput::Pressed(button) => {
+                let drag = match (button, self.mouse) {
+                    (glutin::MouseButton::Left, Some(mouse)) if self.brushing => {
+                        ChartDrag::Brush(self.state(|state| state.view.to_data(bounds, mouse).0))
+                    }
+                    _ => ChartDrag::Pan,
+                };
+                self.drag = Some(drag);
+                self.plot.start_drag(args.ui);
+            }
+            ChartInput::Drag(ref drag_event) => {
+                let drag = match self.drag {
+                    Some(drag) => drag,
+                    None => return,
+                };
+                let mut view = self.state(|state| state.view);
+                match drag {
+                    ChartDrag::Pan => {
+                        let x = if self.pan_axes.x() { -drag_event.change.x / bounds.width() * (view.x.1 - view.x.0) } else { 0.0 };
+                        let y = if self.pan_axes.y() { drag_event.change.y / bounds.height() * (view.y.1 - view.y.0) } else { 0.0 };
+                        view.pan(x, y);
+                        self.move_view(view, &args.widget);
+                    }
+                    ChartDrag::Brush(start) => {
+                        let end = view.to_data(bounds, drag_event.position).0;
+                        let brush = (start.min(end), start.max(end));
+                        let width = (view.to_point(bounds, brush.1, 0.0).x - view.to_point(bounds, brush.0, 0.0).x).abs();
+                        let brush = if width < MIN_BRUSH_WIDTH { None } else { Some(brush) };
+                        self.set_brush(brush);
+                        if drag_event.state == DragState::End {
+                            args.widget.event(ChartBrushed(brush));
+                        }
+                    }
+                }
+                if drag_event.state == DragState::End {
+                    self.drag = None;
+                }
+            }
+        }
+    }
+}
+
+/// Builds an interactive line chart
+#[derive(Debug, Clone)]
+pub struct ChartBuilder {
+    series: Vec<Series>,
+    view: Option<ChartView>,
+    zoom_axes: ChartAxes,
+    pan_axes: ChartAxes,
+    brushing: bool,
+    crosshair: bool,
+    streaming: Option<f32>,
+    axes: ChartAxes,
+    axes_shown: bool,
+    grid: bool,
+    legend: bool,
+    annotations: Vec<Annotation>,
+}
+
+impl Default for ChartBuilder {
+    fn default() -> Self {
+        ChartBuilder {
+            series: Vec::new(),
+            view: None,
+            zoom_axes: ChartAxes::X,
+            pan_axes: ChartAxes::Both,
+            brushing: false,
+            crosshair: true,
+            streaming: None,
+            axes: ChartAxes::Both,
+            axes_shown: false,
+            grid: true,
+            legend: false,
+            annotations: Vec::new(),
+        }
+    }
+}
+
+impl ChartBuilder {
+    pub fn new() -> Self {
+        Self::default()
+    }
+    pub fn add_series(&mut self, series: Series) -> &mut Self {
+        self.series.push(series);
+        self
+    }
+    /// Set the initial view, by default it fits the data
+    pub fn set_view(&mut self, view: ChartView) -> &mut Self {
+        self.view = Some(view);
+        self
+    }
+    /// The axes zoomed by the mouse wheel, the x axis by default
+    pub fn set_zoom_axes(&mut self, axes: ChartAxes) -> &mut Self {
+        self.zoom_axes = axes;
+        self
+    }
+    /// The axes panned by dragging, both by default
+    pub fn set_pan_axes(&mut self, axes: ChartAxes) -> &mut Self {
+        self.pan_axes = axes;
+        self
+    }
+    /// Select ranges of x by dragging with the left button, rather than panning
+    pub fn set_brushing(&mut self, brushing: bool) -> &mut Self {
+        self.brushing = brushing;
+        self
+    }
+    /// Show a crosshair and the values under the cursor, on by default
+    pub fn set_crosshair(&mut self, crosshair: bool) -> &mut Self {
+        self.crosshair = crosshair;
+        self
+    }
+
+    /// Scroll the x axis to show the latest `window` of x as points are appended
+    pub fn set_streaming(&mut self, window: f32) -> &mut Self {
+        self.streaming = Some(window);
+        self
+    }
+    /// Show the ticks and labels of `axes`, beside the chart
+    pub fn set_axes(&mut self, axes: ChartAxes) -> &mut Self {
+        self.axes = axes;
+        self.axes_shown = true;
+        self
+    }
+    /// Draw grid lines at the ticks of each axis, on by default
+    pub fn set_grid(&mut self, grid: bool) -> &mut Self {
+        self.grid = grid;
+        self
+    }
+    /// Show a legend above the chart, clicking a series in it shows or hides the series
+    pub fn set_legend(&mut self, legend: bool) -> &mut Self {
+        self.legend = legend;
+        self
+    }
+    pub fn add_annotation(&mut self, annotation: Annotation) -> &mut Self {
+        self.annotations.push(annotation);
+        self
+    }
+
+    pub fn build(&self) -> Widget {
+        let mut widget = Widget::new("chart");
+
+        let mut readout = Widget::new("chart_readout");
+        StaticTextStyle::from_text("").component().apply(&mut readout);
+
+        let legend = if self.legend {
+            let mut legend = LegendBuilder::new();
+            for series in &self.series {
+                legend.add_entry(&series.name, series.color);
+            }
+            let mut legend = legend.build();
+            let chart = widget.clone();
+            legend.add_handler(move |event: &LegendToggled, _: EventArgs| {
+                chart.event(*event);
+            });
+            Some(legend)
+        } else {
+            None
+        };
+
+        let mut plot = Widget::new("chart_plot");
+        let mut state = ChartState::new(self.series.clone());
+        if let Some(view) = self.view {
+            state.view = view;
+        }
+        if !self.grid {
+            state.grid_color = None;
+        }
+        state.annotations = self.annotations.clone();
+        plot.set_draw_state(state);
+        let axes = if self.axes_shown { Some(self.axes) } else { None };
+        let (x_axis, y_axis) = layout_chart(&mut widget, &mut plot, legend.as_ref(), axes, Some(&readout));
+        let chart = widget.clone();
+        plot.add_handler(move |event: &WidgetMouseMoved, _: EventArgs| {
+            chart.event(ChartInput::Moved(event.0));
+        });
+        let chart = widget.clone();
+        plot.add_handler(move |event: &MouseOverEvent, _: EventArgs| {
+            if let MouseOverEvent::Out = *event {
+                chart.event(ChartInput::Out);
+            }
+        });
+        let chart = widget.clone();
+        plot.add_handler(move |event: &WidgetMouseWheel, _: EventArgs| {
+            chart.event(ChartInput::Wheel(event.0, event.1));
+        });
+        let chart = widget.clone();
+        plot.add_handler(move |event: &WidgetMouseButton, _: EventArgs| {
+            if let WidgetMouseButton(glutin::ElementState::Pressed, button) = *event {
+                chart.event(ChartInput::Pressed(button));
+            }
+        });
+        let chart = widget.clone();
+        plot.add_handler(move |event: &DragEvent, _: EventArgs| {
+            chart.event(ChartInput::Drag(*event));
+        });
+
+        let mut handler = ChartHandler {
+            plot: plot.clone(),
+            readout: readout.clone(),
+            x_axis: x_axis,
+            y_axis: y_axis,
+            legend: legend,
+            zoom_axes: self.zoom_axes,
+            pan_axes: self.pan_axes,
+            brushing: self.brushing,
+            crosshair: self.crosshair,
+            mouse: None,
+            drag: None,
+            format: NumberFormat::new(),
+            streaming: self.streaming,
+            following: self.view.is_none(),
+        };
+        if let Some(window) = self.streaming {
+            handler.plot.update(|state: &mut ChartState| state.view.x = (0.0, window));
+            handler.follow();
+        }
+        handler.update_axes();
+        widget
+            .add_child(plot)
+            .add_child(readout)
+            .add_handler(handler);
+        ChartHandler::add_adapters(&mut widget);
+        widget
+    }
+}
+
+/// Lays out the parts of a chart in `chart`: the legend, if any, along the top, the plot under it
+/// with the y axis to its left and the x axis below it, and `footer`, ex. a readout, if any, along
+/// the bottom. Adds the legend and the axes of `axes` to `chart`, returning the x and y axes, the
+/// caller adds the plot and footer.
+pub(crate) fn layout_chart(chart: &mut Widget, plot: &mut Widget, legend: Option<&Widget>, axes: Option<ChartAxes>, footer: Option<&Widget>) -> (Option<Widget>, Option<Widget>) {
+    plot.layout().add(align_right(chart));
+    match legend {
+        Some(legend) => {
+            let mut legend = legend.clone();
+            legend.layout().add(constraints![
+                align_left(chart),
+                align_top(chart),
+            ]);
+            plot.layout().add(below(&legend).padding(5.0));
+            chart.add_child(legend);
+        }
+        None => plot.layout().add(align_top(chart)),
+    }
+    let y_axis = match axes {
+        Some(axes) if axes.y() => {
+            let mut y_axis = Widget::new("chart_y_axis");
+            y_axis.set_draw_state(AxisState::new(Axis::Y));
+            y_axis.layout().add(constraints![
+                width(Y_AXIS_WIDTH),
+                align_left(chart),
+                align_top(plot),
+                align_bottom(plot),
+            ]);
+            plot.layout().add(to_right_of(&y_axis));
+            chart.add_child(y_axis.clone());
+            Some(y_axis)
+        }
+        _ => {
+            plot.layout().add(align_left(chart));
+            None
+        }
+    };
+    let x_axis = match axes {
+        Some(axes) if axes.x() => {
+            let mut x_axis = Widget::new("chart_x_axis");
+            x_axis.set_draw_state(AxisState::new(Axis::X));
+            x_axis.layout().add(constraints![
+                height(X_AXIS_HEIGHT),
+                align_left(plot),
+                align_right(plot),
+                below(plot),
+            ]);
+            chart.add_child(x_axis.clone());
+            Some(x_axis)
+        }
+        _ => None,
+    };
+    let mut bottom = x_axis.clone().unwrap_or_else(|| plot.clone());
+    match footer {
+        Some(footer) => {
+            let mut footer = footer.clone();
+            footer.layout().add(constraints![
+                align_left(chart),
+                align_bottom(chart),
+            ]);
+            bottom.layout().add(above(&footer).padding(5.0));
+        }
+        None => bottom.layout().add(align_bottom(chart)),
+    }
+    (x_axis, y_axis)
+}
+
+/// Set the ranges of the axes of a chart to those of `view`
+pub(crate) fn update_axes(x_axis: &mut Option<Widget>, y_axis: &mut Option<Widget>, view: ChartView) {
+    if let Some(ref mut x_axis) = *x_axis {
+        x_axis.update(|state: &mut AxisState| state.range = view.x);
+    }
+    if let Some(ref mut y_axis) = *y_axis {
+        y_axis.update(|state: &mut AxisState| state.range = view.y);
+    }
+}
+
+/// Changes a legend, send to the legend widget
+#[derive(Debug, Clone)]
+pub enum LegendEvent {
+    AddEntry(String, Color),
+    /// Show an entry as shown or hidden, without sending `LegendToggled`
+    SetVisible(usize, bool),
+}
+
+struct LegendHandler {
+    /// The swatch of each entry, and whether it's shown
+    entries: Vec<(Widget, Color, bool)>,
+    last_entry: Option<Widget>,
+    toggleable: bool,
+}
+
+impl LegendHandler {
+    fn add_entry(&mut self, name: &str, color: Color, legend: &mut Widget) {
+        let index = self.entries.len();
+        let mut entry = Widget::new("legend_entry");
+        entry.layout().add(constraints![
+            align_top(legend),
+            align_bottom(legend),
+        ]);
+        match self.last_entry {
+            Some(ref previous) => entry.layout().add(to_right_of(previous).padding(10.0)),
+            None => entry.layout().add(align_left(legend)),
+        }
+        let mut swatch = Widget::new("legend_swatch");
+        swatch.set_draw_state(swatch_state(color, true));
+        swatch.layout().add(constraints![
+            size(Size::new(SWATCH_SIZE, SWATCH_SIZE)),
+            align_left(&entry),
+            center_vertical(&entry),
+        ]);
+        let mut label = Widget::new("legend_label");
+        let mut style = TextStyle::default();
+        style.text = Some(name.to_owned());
+        style.font_size = Some(14.0);
+        StaticTextStyle::from_style(style).component().apply(&mut label);
+        label.layout().add(constraints![
+            to_right_of(&swatch).padding(4.0),
+            align_right(&entry),
+            align_top(&entry),
+            align_bottom(&entry),
+        ]);
+        if self.toggleable {
+            let legend = legend.clone();
+            entry.add_handler(move |_: &ClickEvent, _: EventArgs| {
+                legend.event(LegendEntryClicked(index));
+            });
+        }
+        entry.add_child(swatch.clone()).add_child(label);
+        legend.add_child(entry.clone());
+        self.last_entry = Some(entry);
+        self.entries.push((swatch, color, true));
+    }
+
+    fn set_visible(&mut self, index: usize, visible: bool) -> bool {
+        match self.entries.get_mut(index) {
+            Some(&mut (ref mut swatch, color, ref mut shown)) => {
+                *shown = visible;
+                swatch.update(|state: &mut RectState| *state = swatch_state(color, visible));
+                true
+            }
+            None => false,
+        }
+    }
+
+    fn legend_event(&mut self, event: &LegendEvent, mut args: EventArgs) {
+        match *event {
+            LegendEvent::AddEntry(ref name, color) => self.add_entry(name, color, &mut args.widget),
+            LegendEvent::SetVisible(index, visible) => {
+                self.set_visible(index, visible);
+            }
+        }
+    }
+
+    fn entry_clicked(&mut self, event: &LegendEntryClicked, args: EventArgs) {
+        let index = event.0;
+        let visible = !self.entries.get(index).map_or(true, |&(_, _, shown)| shown);
+        if self.set_visible(index, visible) {
+            args.widget.event(LegendToggled(index, visible));
+        }
+    }
+}
+
+#[derive(Debug, Copy, Clone)]
+struct LegendEntryClicked(usize);
+
+multi_event!{impl EventHandler<LegendHandlerEvent> for LegendHandler {
+    LegendEvent => legend_event,
+    LegendEntryClicked => entry_clicked,
+}}
+
+/// A hidden entry's swatch is just an outline
+fn swatch_state(color: Color, visible: bool) -> RectState {
+    RectState {
+        background_color: if visible { color } else { WHITE },
+        border: Some((2.0, color)),
+        ..RectState::default()
+    }
+}
+
+/// Builds a legend, a row of series names, each beside a swatch of the series color. Clicking an
+/// entry hides it, shown with just the outline of the swatch, or shows it again, sending
+/// `LegendToggled` to the legend widget.
+#[derive(Debug, Clone)]
+pub struct LegendBuilder {
+    entries: Vec<(String, Color)>,
+    toggleable: bool,
+}
+
+impl Default for LegendBuilder {
+    fn default() -> Self {
+        LegendBuilder {
+            entries: Vec::new(),
+            toggleable: true,
+        }
+    }
+}
+
+impl LegendBuilder {
+    pub fn new() -> Self {
+        Self::default()
+    }
+    pub fn add_entry(&mut self, name: &str, color: Color) -> &mut Self {
+        self.entries.push((name.to_owned(), color));
+        self
+    }
+    /// Whether clicking entries toggles them, on by default
+    pub fn set_toggleable(&mut self, toggleable: bool) -> &mut Self {
+        self.toggleable = toggleable;
+        self
+    }
+
+    pub fn build(&self) -> Widget {
+        let mut widget = Widget::new("legend");
+        widget.layout().add(height(SWATCH_SIZE + 8.0));
+        let mut handler = LegendHandler {
+            entries: Vec::new(),
+            last_entry: None,
+            toggleable: self.toggleable,
+        };
+        for &(ref name, color) in &self.entries {
+            handler.add_entry(name, color, &mut widget);
+        }
+        widget.add_handler(handler);
+        LegendHandler::add_adapters(&mut widget);
+        widget
+    }
+}
+
+/// Append points pushed to a stream from any thread to a chart, as `(series index, x, y)`.
+/// Points are appended once per frame, however fast they're pushed.
+pub fn receive_points(chart: &mut Widget, receiver: StreamReceiver<(usize, f32, f32)>) {
+    chart.receive_stream(receiver, |batch, args| {
+        let mut points: Vec<Vec<(f32, f32)>> = Vec::new();
+        for (series, x, y) in batch.items {
+            while points.len() <= series {
+                points.push(Vec::new());
+            }
+            points[series].push((x, y));
+        }
+        for (series, points) in points.into_iter().enumerate() {
+            if !points.is_empty() {
+                args.widget.event(ChartEvent::AppendPoints(series, points));
+            }
+        }
+    });
+}
@@ -0,0 +1,105 @@
+//! A chat transcript, newest messages at the bottom.
+//!
+//! The view sticks to the latest message as new ones arrive, unless the user has scrolled up
+//! to read older messages, in which case a "Jump to latest" button is shown instead.
+//! Messages are drawn directly by `ChatViewState` rather than as a widget each, so only the
+//! messages in view cost anything to draw.
+
+use prelude::*;
+use draw::prelude::*;
+use widgets::button::ButtonStyle;
+use resources::font::FontDescriptor;
+use widget::property::PropSet;
+use style::DrawComponentStyle;
+
+/// Send to the chat view widget, `ChatMessage` is `Send` so messages can be added from other threads
+/// with `EventSender::widget_event`.
+#[derive(Debug, Clone)]
+pub enum ChatEvent {
+    AddMessage(ChatMessage),
+    JumpToLatest,
+}
+
+fn scroll_amount(delta: glutin::MouseScrollDelta) -> f32 {
+    match delta {
+        glutin::MouseScrollDelta::LineDelta(_, y) => y * 40.0,
+        glutin::MouseScrollDelta::PixelDelta(glutin::dpi::LogicalPosition{y, ..}) => y as f32,
+    }
+}
+
+/// Font of the theme's text style for the "chat_message" class, so messages match the rest of the UI
+fn theme_font() -> FontDescriptor {
+    let style = DrawStyle::from_class::<TextStyle>("chat_message").resolve(PropSet::new());
+    let text = style.box_component();
+    text.downcast_ref::<TextState>().map_or_else(|| TextState::default().font, |text| text.font.clone())
+}
+
+pub struct ChatViewBuilder;
+
+impl ChatViewBuilder {
+    /// Creates a new `ChatViewBuilder` showing `messages`, returns it in form of a `Widget`.
+    pub fn new(messages: Vec<ChatMessage>) -> Widget {
+        ChatViewBuilder::with_utc_offset(messages, 0)
+    }
+    /// Like `new`, showing message times and grouping messages by day in the time zone
+    /// `utc_offset` seconds ahead of UTC, ex. the user's local time zone
+    pub fn with_utc_offset(messages: Vec<ChatMessage>, utc_offset: i32) -> Widget {
+        let mut widget = Widget::new("chat_view");
+        let mut state = ChatViewState::default();
+        state.font = theme_font();
+        state.utc_offset = utc_offset;
+        for message in messages {
+            state.push_message(message);
+        }
+        widget.set_draw_state(state);
+
+        let mut jump_button = Widget::from_modifier_style(ButtonStyle::from_text("Jump to latest"));
+        jump_button.set_name("jump_to_latest");
+        jump_button.layout().add(constraints![
+            center_horizontal(&widget),
+            align_bottom(&widget).padding(10.0),
+        ]);
+        jump_button.layout().hide();
+        let chat = widget.clone();
+        jump_button.add_handler(move |_: &ClickEvent, args: EventArgs| {
+            chat.event(ChatEvent::JumpToLatest);
+            *args.handled = true;
+        });
+
+        // show the jump button only while scrolled up
+        let update_jump_button = {
+            let jump_button = jump_button.clone();
+            move |widget: &mut Widget| {
+                let at_latest = widget.draw_state().downcast_ref::<ChatViewState>().unwrap().at_latest();
+                let mut jump_button = jump_button.clone();
+                let mut layout = jump_button.layout();
+                if at_latest && !layout.hidden {
+                    layout.hide();
+                } else if !at_latest && layout.hidden {
+                    layout.show();
+                }
+            }
+        };
+        let update = update_jump_button.clone();
+        widget.add_handler(move |event: &ChatEvent, mut args: EventArgs| {
+            match *event {
+                ChatEvent::AddMessage(ref message) => {
+                    let message = message.clone();
+                    args.widget.update(move |state: &mut ChatViewState| state.push_message(message));
+                }
+                ChatEvent::JumpToLatest => {
+                    args.widget.update(|state: &mut ChatViewState| state.scroll_to_latest());
+                }
+            }
+            update(&mut args.widget);
+        });
+        let update = update_jump_button;
+        widget.add_handler(move |event: &WidgetMouseWheel, mut args: EventArgs| {
+            let amount = scroll_amount(event.0);
+            args.widget.update(|state: &mut ChatViewState| state.scroll_by(amount));
+            update(&mut args.widget);
+        });
+        widget.add_child(jump_button);
+        widget
+    }
+}
@@ -0,0 +1,291 @@
+//! A code view, showing monospaced text with line numbers.
+//!
+//! The text can be selected but not changed, unless the view is made editable. Edits apply at
+//! every caret: ctrl+click adds a caret, ctrl+alt+up or down adds one on the line above or below,
+//! and alt+drag selects a rectangular block with a caret on each line. Enter keeps the indentation
+//! of the line, indenting one more level after an opening bracket. The bracket next to each caret
+//! is highlighted along with the bracket it matches.
+//!
+//! Long lines can be wrapped to the width of the view, continuing at the line's indentation after
+//! a marker. Blocks from a line ending with an opening bracket to its closing bracket can be folded
+//! with the arrows in the gutter or with ctrl+shift+[ and ctrl+shift+], leaving a placeholder row.
+//! Each fold and unfold is reported as a `FoldChanged` event.
+//!
+//! Modifier keys are only known while the view has focus, so the first click on an unfocused view
+//! always places a single caret.
+
+use prelude::*;
+use draw::prelude::*;
+use draw::code_view::{CaretMotion, TextPosition};
+use resources::font::FontDescriptor;
+use widgets::replace_constraints;
+
+const BACKSPACE: char = '\u{8}';
+
+/// Send to the code view widget
+#[derive(Debug, Clone)]
+pub enum CodeViewEvent {
+    SetText(String),
+    SetEditable(bool),
+    SetWrap(bool),
+    /// Fold or unfold the region starting on a line
+    SetFolded(usize, bool),
+    FoldAll,
+    UnfoldAll,
+}
+
+/// Sent to the code view widget after each edit, with the new text
+#[derive(Debug, Clone)]
+pub struct CodeChanged(pub String);
+
+/// Sent to the code view widget when a region is folded or unfolded, including regions unfolded
+/// to show a caret or because their text was edited. `line` is the first line of the region.
+#[derive(Debug, Copy, Clone)]
+pub struct FoldChanged {
+    pub line: usize,
+    pub folded: bool,
+}
+
+multi_event!{impl EventHandler<CodeViewInput> for CodeViewHandler {
+    CodeViewEvent => code_view_event,
+    WidgetReceivedCharacter => received_char,
+    WidgetKeyboardInput => keyboard_input,
+    WidgetMouseMoved => mouse_moved,
+    WidgetMouseButton => mouse_button,
+    DragEvent => drag,
+    LayoutUpdated => layout_updated,
+}}
+
+#[derive(Default)]
+struct CodeViewHandler {
+    mouse: Point,
+    modifiers: glutin::ModifiersState,
+    /// Where an alt+drag block selection started
+    block_start: Option<TextPosition>,
+    content_size: Option<Size>,
+    size_constraints: Vec<Constraint>,
+}
+
+impl CodeViewHandler {
+    fn update_size(&mut self, widget: &mut Widget) {
+        let content_size = widget.draw_state().downcast_ref::<CodeViewState>().unwrap().content_size();
+        if self.content_size != Some(content_size) {
+            let constraints = min_size(content_size).build(&widget.layout_vars());
+            replace_constraints(widget, &mut self.size_constraints, constraints);
+            self.content_size = Some(content_size);
+        }
+    }
+
+    /// Update the state, then resize the widget to fit and report any regions folded or unfolded
+    fn update<F: FnOnce(&mut CodeViewState)>(&mut self, widget: &mut Widget, f: F) {
+        let mut fold_changes = Vec::new();
+        widget.update(|state: &mut CodeViewState| {
+            f(state);
+            fold_changes = state.take_fold_changes();
+        });
+        self.update_size(widget);
+        for (line, folded) in fold_changes {
+            widget.event(FoldChanged { line: line, folded: folded });
+        }
+    }
+
+    /// Apply an edit to the text, if the view is editable
+    fn edit<F: FnOnce(&mut CodeViewState)>(&mut self, widget: &mut Widget, f: F) {
+        let editable = widget.draw_state().downcast_ref::<CodeViewState>().unwrap().editable;
+        if !editable {
+            return;
+        }
+        self.update(widget, f);
+        let text = widget.draw_state().downcast_ref::<CodeViewState>().unwrap().text().to_owned();
+        widget.event(CodeChanged(text));
+    }
+
+    fn code_view_event(&mut self, event: &CodeViewEvent, mut args: EventArgs) {
+        match *event {
+            CodeViewEvent::SetText(ref text) => self.update(&mut args.widget, |state| state.set_text(text)),
+            CodeViewEvent::SetEditable(editable) => self.update(&mut args.widget, |state| state.editable = editable),
+            CodeViewEvent::SetWrap(wrap) => self.update(&mut args.widget, |state| state.set_wrap(wrap)),
+            CodeViewEvent::SetFolded(line, folded) => self.update(&mut args.widget, |state| state.set_folded(line, folded)),
+            CodeViewEvent::FoldAll => self.update(&mut args.widget, |state| state.fold_all()),
+            CodeViewEvent::UnfoldAll => self.update(&mut args.widget, |state| state.unfold_all()),
+        }
+    }
+
+    fn layout_updated(&mut self, _: &LayoutUpdated, mut args: EventArgs) {
+        let width = args.widget.bounds().width();
+        self.update(&mut args.widget, |state| state.set_width(width));
+    }
+
+    fn received_char(&mut self, event: &WidgetReceivedCharacter, mut args: EventArgs) {
+        let &WidgetReceivedCharacter(ch) = event;
+        match ch {
+            BACKSPACE => self.edit(&mut args.widget, |state| state.backspace()),
+            '\r' | '\n' => self.edit(&mut args.widget, |state| state.newline()),
+            // delete and shortcuts like ctrl+a arrive as control characters, they're handled as keys
+            _ if ch.is_control() => (),
+            _ => self.edit(&mut args.widget, |state| state.insert(&ch.to_string())),
+        }
+    }
+
+    fn keyboard_input(&mut self, event: &WidgetKeyboardInput, mut args: EventArgs) {
+        use glutin::VirtualKeyCode::*;
+        let &WidgetKeyboardInput(input) = event;
+        self.modifiers = input.modifiers;
+        if input.state != glutin::ElementState::Pressed {
+            return;
+        }
+        let modifiers = input.modifiers;
+        let motion = match input.virtual_keycode {
+            Some(Up) | Some(Down) if modifiers.ctrl && modifiers.alt => {
+                let down = input.virtual_keycode == Some(Down);
+                self.update(&mut args.widget, |state| state.add_caret_vertical(down));
+                return;
+            }
+            Some(Left) => CaretMotion::Left,
+            Some(Right) => CaretMotion::Right,
+            Some(Up) => CaretMotion::Up,
+            Some(Down) => CaretMotion::Down,
+            Some(Home) => if modifiers.ctrl { CaretMotion::TextStart } else { CaretMotion::LineStart },
+            Some(End) => if modifiers.ctrl { CaretMotion::TextEnd } else { CaretMotion::LineEnd },
+            Some(Delete) => {
+                self.edit(&mut args.widget, |state| state.delete());
+                return;
+            }
+            Some(Escape) => {
+                self.update(&mut args.widget, |state| state.single_caret());
+                return;
+            }
+            Some(LBracket) | Some(RBracket) if modifiers.ctrl && modifiers.shift => {
+                let fold = input.virtual_keycode == Some(LBracket);
+                self.update(&mut args.widget, |state| {
+                    let line = state.position(state.primary_caret().head).line;
+                    state.set_folded(line, fold);
+                });
+                return;
+            }
+            Some(A) if modifiers.ctrl => {
+                self.update(&mut args.widget, |state| state.select_all());
+                return;
+            }
+            _ => return,
+        };
+        self.update(&mut args.widget, |state| state.move_carets(motion, modifiers.shift));
+    }
+
+    fn mouse_moved(&mut self, event: &WidgetMouseMoved, _: EventArgs) {
+        self.mouse = event.0;
+    }
+
+    fn mouse_button(&mut self, event: &WidgetMouseButton, mut args: EventArgs) {
+        if let WidgetMouseButton(glutin::ElementState::Pressed, glutin::MouseButton::Left) = *event {
+            let (modifiers, mouse) = (self.modifiers, self.mouse);
+            let fold_arrow = args.widget.draw_state().downcast_ref::<CodeViewState>().unwrap().fold_arrow_at(mouse);
+            if let Some(region) = fold_arrow {
+                self.update(&mut args.widget, |state| state.toggle_folded(region.start_line));
+                return;
+            }
+            let mut block_start = None;
+            self.update(&mut args.widget, |state| {
+                let position = state.position_at(mouse);
+                let offset = state.offset(position);
+                if modifiers.alt {
+                    block_start = Some(position);
+                    state.select_block(position, position);
+                } else if modifiers.ctrl {
+                    state.add_caret(offset);
+                } else if modifiers.shift {
+                    state.select_to(offset);
+                } else {
+                    state.set_caret(offset);
+                }
+            });
+            self.block_start = block_start;
+            args.widget.start_drag(args.ui);
+        }
+    }
+
+    fn drag(&mut self, event: &DragEvent, mut args: EventArgs) {
+        if event.state == DragState::Start {
+            return;
+        }
+        let block_start = self.block_start;
+        self.update(&mut args.widget, |state| {
+            let position = state.position_at(event.position);
+            match block_start {
+                Some(start) => state.select_block(start, position),
+                None => {
+                    let offset = state.offset(position);
+                    state.select_to(offset);
+                }
+            }
+        });
+        if event.state == DragState::End {
+            self.block_start = None;
+        }
+    }
+}
+
+pub struct CodeViewBuilder {
+    state: CodeViewState,
+}
+
+impl CodeViewBuilder {
+    pub fn new(text: &str) -> Self {
+        CodeViewBuilder {
+            state: CodeViewState::new(text),
+        }
+    }
+    /// Allow the text to be edited, it's read-only by default
+    pub fn set_editable(&mut self, editable: bool) -> &mut Self {
+        self.state.editable = editable;
+        self
+    }
+    /// Set the font, which should be monospaced. Defaults to Hack, which the app must load.
+    pub fn set_font(&mut self, font: FontDescriptor, font_size: f32) -> &mut Self {
+        self.state.font = font;
+        self.state.font_size = font_size;
+        self
+    }
+    /// Wrap lines too long for the view rather than widening it
+    pub fn set_wrap(&mut self, wrap: bool) -> &mut Self {
+        self.state.set_wrap(wrap);
+        self
+    }
+    /// Show fold arrows in the gutter, on by default
+    pub fn set_folding(&mut self, folding: bool) -> &mut Self {
+        self.state.folding = folding;
+        self
+    }
+    pub fn set_line_numbers(&mut self, show_line_numbers: bool) -> &mut Self {
+        self.state.show_line_numbers = show_line_numbers;
+        self
+    }
+    /// Set the text inserted for each level of indentation, four spaces by default
+    pub fn set_indent(&mut self, indent: &str) -> &mut Self {
+        self.state.indent = indent.to_owned();
+        self
+    }
+
+    /// Create the code view widget, at least as large as its content
+    pub fn build(&self) -> Widget {
+        let mut widget = Widget::new("code_view");
+        widget.set_draw_state(self.state.clone());
+        widget
+            .add_handler(|_: &WidgetAttachedEvent, args: EventArgs| {
+                args.ui.event(KeyboardInputEvent::AddFocusable(args.widget));
+            })
+            .add_handler(|_: &WidgetDetachedEvent, args: EventArgs| {
+                args.ui.event(KeyboardInputEvent::RemoveFocusable(args.widget));
+            })
+            // arrow keys move the carets rather than scrolling a containing scroll view
+            .add_handler(|_: &UnhandledKeyboardInput, args: EventArgs| {
+                *args.handled = true;
+            })
+            .make_focusable();
+        let mut handler = CodeViewHandler::default();
+        handler.update_size(&mut widget);
+        widget.add_handler(handler);
+        CodeViewHandler::add_adapters(&mut widget);
+        widget
+    }
+}
@@ -0,0 +1,197 @@
+//! A crop overlay, for selecting a region of the widget under it, ex. an image to crop.
+//!
+//! Place the overlay over the widget, the selection is dragged to move it, resized by its corners
+//! and edges, and dragging outside it starts a new one. The region is reported in content
+//! coordinates with `CropChanged`. By default the content is the area of the overlay, measured
+//! in window coordinates from its top left. Over an image viewer, forward `ImageViewChanged` to
+//! the overlay as `CropEvent::SetContent` to select a region in pixels of the image.
+
+use prelude::*;
+use draw::prelude::*;
+use input::mouse::MouseOverEvent;
+use widgets::image_viewer::ImageViewChanged;
+
+/// Changes a crop overlay, send to the overlay widget
+#[derive(Debug, Copy, Clone)]
+pub enum CropEvent {
+    /// Select a region, in content coordinates
+    SetSelection(Rect),
+    /// Lock the selection to a ratio of width to height, or unlock it
+    SetAspectRatio(Option<f32>),
+    /// Where the content is shown, in window coordinates, and its size in its own units
+    SetContent(Rect, Size),
+    SetGuides(bool),
+}
+
+/// Sent to the overlay widget when the selection changes, with the selected region in content coordinates
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct CropChanged(pub Rect);
+
+impl From<ImageViewChanged> for CropEvent {
+    fn from(view: ImageViewChanged) -> Self {
+        CropEvent::SetContent(view.rect, view.size)
+    }
+}
+
+multi_event!{impl EventHandler<CropInput> for CropHandler {
+    CropEvent => crop_event,
+    DragEvent => drag,
+    WidgetMouseMoved => mouse_moved,
+    MouseOverEvent => mouse_over,
+    LayoutUpdated => layout_updated,
+}}
+
+#[derive(Default)]
+struct CropHandler {
+    /// The handle being dragged, and the selection when the drag started
+    dragging: Option<(CropHandle, Rect)>,
+    selection: Option<Rect>,
+}
+
+impl CropHandler {
+    /// Change the state, then report the selection if it changed. Until there's a selection, all
+    /// of the content is selected.
+    fn update<F: FnOnce(&mut CropState, Rect)>(&mut self, widget: &mut Widget, f: F) {
+        let bounds = widget.bounds();
+        let mut selection = Rect::zero();
+        widget.update(|state: &mut CropState| {
+            f(state, bounds);
+            if state.selection.size == Size::zero() {
+                state.selection = Rect::new(Point::zero(), state.content_size(bounds));
+                let ratio = state.aspect_ratio;
+                state.set_aspect_ratio(ratio, bounds);
+            }
+            selection = state.selection;
+        });
+        if self.selection != Some(selection) {
+            self.selection = Some(selection);
+            widget.event(CropChanged(selection));
+        }
+    }
+
+    fn crop_event(&mut self, event: &CropEvent, mut args: EventArgs) {
+        self.update(&mut args.widget, |state, bounds| {
+            match *event {
+                CropEvent::SetSelection(selection) => state.selection = selection,
+                CropEvent::SetAspectRatio(ratio) => state.set_aspect_ratio(ratio, bounds),
+                CropEvent::SetContent(rect, size) => {
+                    // a selection of different content doesn't carry over
+                    if state.content_size(bounds) != size {
+                        state.selection = Rect::zero();
+                    }
+                    state.content_rect = Some(rect);
+                    state.content_size = Some(size);
+                }
+                CropEvent::SetGuides(guides) => state.guides = guides,
+            }
+        });
+    }
+
+    fn drag(&mut self, event: &DragEvent, mut args: EventArgs) {
+        match event.state {
+            DragState::Start => {
+                let bounds = args.widget.bounds();
+                let draw_state = args.widget.draw_state();
+                let state = draw_state.downcast_ref::<CropState>().unwrap();
+                self.dragging = Some(match state.handle_at(event.position, bounds) {
+                    Some(handle) => (handle, state.selection),
+                    // start a new selection from the corner pressed
+                    None => (CropHandle::BottomRight, Rect::new(state.to_content(event.position, bounds), Size::zero())),
+                });
+            }
+            DragState::Moved | DragState::End => {
+                if let Some((handle, start)) = self.dragging {
+                    let offset = event.offset;
+                    self.update(&mut args.widget, |state, bounds| {
+                        state.selection = start;
+                        state.drag(handle, offset, bounds);
+                    });
+                }
+                if event.state == DragState::End {
+                    self.dragging = None;
+                }
+            }
+        }
+    }
+
+    fn mouse_moved(&mut self, event: &WidgetMouseMoved, args: EventArgs) {
+        if self.dragging.is_some() {
+            return;
+        }
+        let bounds = args.widget.bounds();
+        let handle = args.widget.draw_state().downcast_ref::<CropState>().unwrap().handle_at(event.0, bounds);
+        let cursor = match handle {
+            Some(CropHandle::Move) => glutin::MouseCursor::Move,
+            Some(CropHandle::Left) | Some(CropHandle::Right) => glutin::MouseCursor::EwResize,
+            Some(CropHandle::Top) | Some(CropHandle::Bottom) => glutin::MouseCursor::NsResize,
+            Some(CropHandle::TopLeft) | Some(CropHandle::BottomRight) => glutin::MouseCursor::NwseResize,
+            Some(CropHandle::TopRight) | Some(CropHandle::BottomLeft) => glutin::MouseCursor::NeswResize,
+            None => glutin::MouseCursor::Crosshair,
+        };
+        args.ui.set_cursor(cursor);
+    }
+
+    fn mouse_over(&mut self, event: &MouseOverEvent, args: EventArgs) {
+        if let MouseOverEvent::Out = *event {
+            args.ui.set_cursor(glutin::MouseCursor::Default);
+        }
+    }
+
+    fn layout_updated(&mut self, _: &LayoutUpdated, mut args: EventArgs) {
+        self.update(&mut args.widget, |_, _| ());
+    }
+}
+
+/// Builds a crop overlay, see the [module docs](index.html)
+#[derive(Debug, Clone)]
+pub struct CropOverlayBuilder {
+    state: CropState,
+}
+
+impl Default for CropOverlayBuilder {
+    fn default() -> Self {
+        CropOverlayBuilder {
+            state: CropState::new(),
+        }
+    }
+}
+
+impl CropOverlayBuilder {
+    pub fn new() -> Self {
+        Self::default()
+    }
+    /// The region selected at first, in content coordinates. All of the content by default.
+    pub fn set_selection(&mut self, selection: Rect) -> &mut Self {
+        self.state.selection = selection;
+        self
+    }
+    /// Lock the selection to a ratio of width to height
+    pub fn set_aspect_ratio(&mut self, ratio: f32) -> &mut Self {
+        self.state.aspect_ratio = Some(ratio);
+        self
+    }
+    /// Size of the content in its own units, if it's not measured in window coordinates,
+    /// ex. the size of an image in pixels that fills the overlay
+    pub fn set_content_size(&mut self, size: Size) -> &mut Self {
+        self.state.content_size = Some(size);
+        self
+    }
+    /// Draw rule of thirds guides over the selection, on by default
+    pub fn set_guides(&mut self, guides: bool) -> &mut Self {
+        self.state.guides = guides;
+        self
+    }
+    pub fn set_dim_color(&mut self, color: Color) -> &mut Self {
+        self.state.dim_color = color;
+        self
+    }
+
+    pub fn build(&self) -> Widget {
+        let mut widget = Widget::new("crop_overlay");
+        widget.set_draw_state(self.state.clone());
+        widget.add_handler(CropHandler::default());
+        CropHandler::add_adapters(&mut widget);
+        widget.make_draggable();
+        widget
+    }
+}
@@ -77,6 +77,10 @@ impl WidgetModifier for EditText {
             .add_handler(|_: &WidgetDetachedEvent, args: EventArgs| {
                 args.ui.event(KeyboardInputEvent::RemoveFocusable(args.widget));
             })
+            // keys are for editing, not for containers like scroll areas
+            .add_handler(|_: &UnhandledKeyboardInput, args: EventArgs| {
+                *args.handled = true;
+            })
             .add_handler(EditTextHandler {
                 text_box: text_widget.clone(),
                 text: "".to_owned(),
@@ -0,0 +1,113 @@
+//! A figure, an image with an optional caption below it.
+//!
+//! A figure can be floated in the top left or right corner of a text widget, the lines beside it
+//! are shortened so the text wraps around it, see `TextState::float`.
+
+use prelude::*;
+use draw::prelude::*;
+
+/// Space between the image and its caption
+const CAPTION_GAP: f32 = 6.0;
+
+pub struct FigureBuilder {
+    image: ImageSource,
+    caption: Option<String>,
+    width: Option<f32>,
+    caption_style: TextStyle,
+}
+
+impl FigureBuilder {
+    pub fn new(image: ImageSource) -> Self {
+        FigureBuilder {
+            image: image,
+            caption: None,
+            width: None,
+            caption_style: style!(TextStyle {
+                font_size: 16.0,
+                text_color: GRAY_30,
+                align: Align::Middle,
+            }),
+        }
+    }
+    pub fn set_caption(&mut self, caption: &str) -> &mut Self {
+        self.caption = Some(caption.to_owned());
+        self
+    }
+    /// Scale the image to `width`, keeping its aspect ratio. The caption wraps to the same width.
+    /// Defaults to the width of the image.
+    pub fn set_width(&mut self, width: f32) -> &mut Self {
+        self.width = Some(width);
+        self
+    }
+    pub fn set_caption_style(&mut self, style: TextStyle) -> &mut Self {
+        self.caption_style = style;
+        self
+    }
+
+    pub fn build(&self) -> Widget {
+        self.build_sized().0
+    }
+
+    /// Build the figure, along with its size
+    fn build_sized(&self) -> (Widget, Size) {
+        let natural_size = ImageState::new(self.image.clone()).measure();
+        let image_size = match self.width {
+            Some(width) if natural_size.width > 0.0 => Size::new(width, natural_size.height * width / natural_size.width),
+            _ => natural_size,
+        };
+        let mut figure = Widget::new("figure");
+
+        let mut image = Widget::new("figure_image");
+        image.set_draw_state(ImageState::new(self.image.clone()));
+        image.layout().add(constraints![
+            size(image_size),
+            align_top(&figure),
+            align_left(&figure),
+        ]);
+        figure.add_child(image.clone());
+
+        let mut height = image_size.height;
+        if let Some(ref text) = self.caption {
+            let mut caption = Widget::new("figure_caption");
+            let mut caption_style = self.caption_style.clone();
+            caption_style.text = Some(text.clone());
+            caption.set_draw_style(caption_style);
+            let caption_height = caption.draw_state().downcast_ref::<TextState>()
+                .map_or(0.0, |caption| caption.wrapped_height(image_size.width));
+            caption.layout().add(constraints![
+                below(&image).padding(CAPTION_GAP),
+                align_left(&figure),
+                width(image_size.width),
+                height(caption_height),
+            ]);
+            figure.add_child(caption);
+            height += CAPTION_GAP + caption_height;
+        }
+        let figure_size = Size::new(image_size.width, height);
+        figure.layout().add(size(figure_size));
+        (figure, figure_size)
+    }
+
+    /// Build the figure and float it in the top `side` corner of `text`, a widget drawing
+    /// `TextState`, with `margin` between the figure and the text wrapping around it
+    pub fn float_in(&self, text: &mut Widget, side: FloatSide, margin: f32) -> Widget {
+        let (mut figure, figure_size) = self.build_sized();
+        let float = TextFloat {
+            side: side,
+            size: figure_size,
+            margin: margin,
+        };
+        figure.layout().add(align_top(&*text));
+        figure.layout().add(match side {
+            FloatSide::Left => align_left(&*text),
+            FloatSide::Right => align_right(&*text),
+        });
+        // restyling resets the draw state, so set the float again afterwards
+        text.add_handler(move |_: &StyleUpdated, mut args: EventArgs| {
+            args.widget.update(|state: &mut TextState| state.float = Some(float));
+        });
+        text.update(|state: &mut TextState| state.float = Some(float));
+        text.add_child(figure.clone());
+        figure
+    }
+}
@@ -0,0 +1,551 @@
+//! A file browser, combining a breadcrumb of the current path, a directory tree and a sortable file list.
+//!
+//! Directories are read on a background thread, the result is sent back to the browser widget with
+//! `EventSender::widget_event`, so large or slow directories never block the UI thread.
+//!
+//! Clicking selects a file, ctrl-click and shift-click extend the selection. Double clicking a
+//! directory opens it, double clicking a file sends a `FileActivated` event to the browser widget.
+//! Clicking an already selected file, or pressing F2, renames it in place, enter commits the new name.
+
+use std::cmp::Ordering;
+use std::collections::BTreeSet;
+use std::fs;
+use std::path::PathBuf;
+use std::thread;
+use std::time::{Duration, Instant};
+
+use clock;
+use prelude::*;
+use draw::prelude::*;
+use widgets::button::ButtonStyle;
+use widgets::edit_text::{EditText, TextUpdated};
+use widgets::scroll::ScrollContainer;
+use widgets::text::StaticTextStyle;
+use input::keyboard::{WidgetKeyboardInput, UnhandledKeyboardInput};
+
+const DOUBLE_CLICK_TIME: u64 = 400;
+
+/// Sent to the file browser widget when a file is double clicked.
+#[derive(Debug, Clone)]
+pub struct FileActivated(pub PathBuf);
+
+/// Sent to the file browser widget whenever the selection changes.
+#[derive(Debug, Clone)]
+pub struct FilesSelected(pub Vec<PathBuf>);
+
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum SortKey {
+    Name,
+    Size,
+}
+
+#[derive(Debug, Clone)]
+pub struct FileEntry {
+    pub path: PathBuf,
+    pub name: String,
+    pub is_dir: bool,
+    pub size: u64,
+}
+
+impl FileEntry {
+    fn compare(&self, other: &FileEntry, key: SortKey) -> Ordering {
+        // directories are always listed first
+        other.is_dir.cmp(&self.is_dir).then_with(|| match key {
+            SortKey::Name => self.name.to_lowercase().cmp(&other.name.to_lowercase()),
+            SortKey::Size => self.size.cmp(&other.size),
+        })
+    }
+}
+
+fn read_entries(path: &PathBuf) -> Result<Vec<FileEntry>, String> {
+    let mut entries = Vec::new();
+    for entry in fs::read_dir(path).map_err(|err| err.to_string())? {
+        let entry = entry.map_err(|err| err.to_string())?;
+        let metadata = match entry.metadata() {
+            Ok(metadata) => metadata,
+            Err(_) => continue,
+        };
+        entries.push(FileEntry {
+            path: entry.path(),
+            name: entry.file_name().to_string_lossy().into_owned(),
+            is_dir: metadata.is_dir(),
+            size: metadata.len(),
+        });
+    }
+    Ok(entries)
+}
+
+fn format_size(size: u64) -> String {
+    const UNITS: [&str; 4] = ["B", "KB", "MB", "GB"];
+    let mut size = size as f64;
+    let mut unit = 0;
+    while size >= 1024.0 && unit < UNITS.len() - 1 {
+        size /= 1024.0;
+        unit += 1;
+    }
+    let decimals = if unit == 0 { 0 } else { 1 };
+    format!("{} {}", NumberFormat::new().set_decimals(decimals, decimals).format(size), UNITS[unit])
+}
+
+enum FileBrowserEvent {
+    Navigate(PathBuf),
+    Listed(usize, Result<Vec<FileEntry>, String>),
+    Sort(SortKey),
+    Click(usize, glutin::ModifiersState),
+    StartRename,
+    Rename(String),
+}
+
+struct Widgets {
+    breadcrumb: Widget,
+    tree: Widget,
+    files: Widget,
+    status: Widget,
+}
+
+struct FileBrowserHandler {
+    widgets: Widgets,
+    path: PathBuf,
+    // incremented on every navigation, so results of stale directory reads can be ignored
+    generation: usize,
+    entries: Vec<FileEntry>,
+    rows: Vec<Widget>,
+    selected: BTreeSet<usize>,
+    anchor: Option<usize>,
+    last_click: Option<(usize, Instant)>,
+    sort: SortKey,
+    reverse: bool,
+    renaming: Option<Widget>,
+}
+
+impl FileBrowserHandler {
+    fn navigate(&mut self, path: PathBuf, browser: &Widget) {
+        self.path = path.clone();
+        self.generation += 1;
+        self.entries.clear();
+        self.clear_selection();
+        self.rebuild_breadcrumb(browser);
+        self.rebuild_files(browser);
+        self.widgets.status.event(TextUpdated("Loading...".to_owned()));
+
+        let generation = self.generation;
+        let browser_id = browser.id();
+        let sender = EventSender;
+        thread::spawn(move || {
+            let entries = read_entries(&path);
+            sender.widget_event(browser_id, FileBrowserEvent::Listed(generation, entries));
+        });
+    }
+
+    fn listed(&mut self, entries: Result<Vec<FileEntry>, String>, browser: &Widget) {
+        match entries {
+            Ok(entries) => {
+                self.entries = entries;
+                self.sort_entries();
+                let status = format!("{} items", self.entries.len());
+                self.widgets.status.event(TextUpdated(status));
+            }
+            Err(err) => {
+                self.entries.clear();
+                self.widgets.status.event(TextUpdated(format!("Can't read directory: {}", err)));
+            }
+        }
+        self.rebuild_tree(browser);
+        self.rebuild_files(browser);
+    }
+
+    fn sort_entries(&mut self) {
+        let (key, reverse) = (self.sort, self.reverse);
+        self.entries.sort_by(|a, b| {
+            let ordering = a.compare(b, key);
+            if reverse { ordering.reverse() } else { ordering }
+        });
+    }
+
+    fn rebuild_breadcrumb(&mut self, browser: &Widget) {
+        for child in self.widgets.breadcrumb.children() {
+            self.widgets.breadcrumb.remove_child(child);
+        }
+        let mut prefix = PathBuf::new();
+        for component in self.path.iter() {
+            prefix.push(component);
+            let name = component.to_string_lossy().into_owned();
+            let mut button = Widget::from_modifier_style(ButtonStyle::from_text(&name));
+            let (browser, path) = (browser.clone(), prefix.clone());
+            button.add_handler(move |_: &ClickEvent, _: EventArgs| {
+                browser.event(FileBrowserEvent::Navigate(path.clone()));
+            });
+            self.widgets.breadcrumb.add_child(button);
+        }
+    }
+
+    /// The tree shows the ancestors of the current directory, followed by its subdirectories
+    fn rebuild_tree(&mut self, browser: &Widget) {
+        for child in self.widgets.tree.children() {
+            self.widgets.tree.remove_child(child);
+        }
+        let mut ancestors = Vec::new();
+        let mut ancestor = Some(self.path.as_path());
+        while let Some(path) = ancestor {
+            ancestors.push(path.to_path_buf());
+            ancestor = path.parent();
+        }
+        ancestors.reverse();
+        let depth = ancestors.len();
+        let subdirs = self.entries.iter().filter(|entry| entry.is_dir).map(|entry| entry.path.clone());
+        let items = ancestors.into_iter().enumerate()
+            .chain(subdirs.map(|path| (depth, path)));
+        for (depth, path) in items {
+            let name = match path.file_name() {
+                Some(name) => name.to_string_lossy().into_owned(),
+                None => path.to_string_lossy().into_owned(),
+            };
+            let mut row = entry_row(&name, Some(true), depth as f32 * 15.0, &self.widgets.tree);
+            if path == self.path {
+                row.add_prop(Property::Selected);
+            }
+            let browser = browser.clone();
+            row.add_handler(move |_: &ClickEvent, _: EventArgs| {
+                browser.event(FileBrowserEvent::Navigate(path.clone()));
+            });
+            self.widgets.tree.add_child(row);
+        }
+    }
+
+    fn rebuild_files(&mut self, browser: &Widget) {
+        self.renaming = None;
+        for child in self.widgets.files.children() {
+            self.widgets.files.remove_child(child);
+        }
+        self.rows.clear();
+        for (index, entry) in self.entries.iter().enumerate() {
+            let mut row = entry_row(&entry.name, Some(entry.is_dir), 0.0, &self.widgets.files);
+            if !entry.is_dir {
+                let mut size_text = Widget::new("file_size");
+                size_text.set_draw_style(DrawStyle::from_class::<TextStyle>("list_item_text"));
+                StaticTextStyle::from_text(&format_size(entry.size)).component().apply(&mut size_text);
+                size_text.layout().add(constraints![
+                    align_right(&row).padding(10.0),
+                    center_vertical(&row),
+                ]);
+                row.add_child(size_text);
+            }
+            let browser = browser.clone();
+            row.add_handler(move |event: &ClickEvent, _: EventArgs| {
+                browser.event(FileBrowserEvent::Click(index, event.modifiers));
+            });
+            self.widgets.files.add_child(row.clone());
+            self.rows.push(row);
+        }
+    }
+
+    fn clear_selection(&mut self) {
+        for &index in &self.selected {
+            if let Some(row) = self.rows.get_mut(index) {
+                row.remove_prop(Property::Selected);
+            }
+        }
+        self.selected.clear();
+        self.anchor = None;
+    }
+
+    fn select(&mut self, indices: BTreeSet<usize>, browser: &Widget) {
+        self.clear_selection();
+        for &index in &indices {
+            self.rows[index].add_prop(Property::Selected);
+        }
+        self.selected = indices;
+        let paths = self.selected.iter().map(|&index| self.entries[index].path.clone()).collect();
+        browser.event(FilesSelected(paths));
+    }
+
+    fn click(&mut self, index: usize, modifiers: glutin::ModifiersState, browser: &Widget, ui: &mut Ui) {
+        self.cancel_rename();
+        let now = clock::now();
+        let last_click = self.last_click.take();
+        self.last_click = Some((index, now));
+        let same_item = last_click.map_or(false, |(last_index, _)| last_index == index);
+        let double_click = same_item && last_click.map_or(false, |(_, time)| {
+            now.duration_since(time) < Duration::from_millis(DOUBLE_CLICK_TIME)
+        });
+        let anchor = self.anchor;
+        if modifiers.ctrl || modifiers.logo {
+            let mut selected = self.selected.clone();
+            if !selected.remove(&index) {
+                selected.insert(index);
+            }
+            self.select(selected, browser);
+            self.anchor = Some(index);
+        } else if let (true, Some(anchor)) = (modifiers.shift, anchor) {
+            let range = if anchor < index { anchor..index + 1 } else { index..anchor + 1 };
+            self.select(range.collect(), browser);
+            self.anchor = Some(anchor);
+        } else if double_click {
+            self.last_click = None;
+            self.activate(index, browser);
+        } else if same_item && self.selected.len() == 1 && self.selected.contains(&index) {
+            self.start_rename(browser, ui);
+        } else {
+            self.select(Some(index).into_iter().collect(), browser);
+            self.anchor = Some(index);
+        }
+    }
+
+    fn activate(&mut self, index: usize, browser: &Widget) {
+        let entry = self.entries[index].clone();
+        if entry.is_dir {
+            self.navigate(entry.path, browser);
+        } else {
+            browser.event(FileActivated(entry.path));
+        }
+    }
+
+    fn start_rename(&mut self, browser: &Widget, ui: &mut Ui) {
+        if self.selected.len() != 1 || self.renaming.is_some() {
+            return;
+        }
+        let index = *self.selected.iter().next().unwrap();
+        let mut row = self.rows[index].clone();
+        let mut edit = Widget::from_modifier(EditText::default());
+        edit.set_name("rename_text");
+        edit.layout().add(constraints![
+            align_left(&row).padding(25.0),
+            align_right(&row).padding(80.0),
+            center_vertical(&row),
+        ]);
+        let browser = browser.clone();
+        // keep clicks inside the edit box from reaching the row, which would cancel the rename
+        edit.add_handler(|_: &ClickEvent, args: EventArgs| *args.handled = true);
+        edit.add_handler(move |event: &TextUpdated, _: EventArgs| {
+            if event.0.ends_with('\r') || event.0.ends_with('\n') {
+                let name = event.0.trim().to_owned();
+                browser.event(FileBrowserEvent::Rename(name));
+            }
+        });
+        edit.event_subtree(TextUpdated(self.entries[index].name.clone()));
+        row.add_child(edit.clone());
+        ui.get_root().event(KeyboardInputEvent::FocusChange(Some(edit.clone())));
+        self.renaming = Some(edit);
+    }
+
+    fn cancel_rename(&mut self) {
+        if let Some(mut edit) = self.renaming.take() {
+            edit.remove_widget();
+        }
+    }
+
+    fn rename(&mut self, name: String, browser: &Widget) {
+        self.cancel_rename();
+        let index = match self.selected.iter().next() {
+            Some(&index) if self.selected.len() == 1 => index,
+            _ => return,
+        };
+        if name.is_empty() {
+            return;
+        }
+        if !valid_file_name(&name) {
+            self.widgets.status.event(TextUpdated(format!("Can't rename {}: {} isn't a valid name", self.entries[index].name, name)));
+            return;
+        }
+        let old_path = self.entries[index].path.clone();
+        let new_path = old_path.with_file_name(&name);
+        if new_path == old_path {
+            return;
+        }
+        if let Err(err) = fs::rename(&old_path, &new_path) {
+            self.widgets.status.event(TextUpdated(format!("Can't rename {}: {}", self.entries[index].name, err)));
+            return;
+        }
+        let path = self.path.clone();
+        self.navigate(path, browser);
+    }
+}
+
+/// Whether `name` names a file in the same directory, rather than a path leading out of it
+fn valid_file_name(name: &str) -> bool {
+    name != "." && name != ".." && !name.contains(|c: char| c == '/' || c == '\\')
+}
+
+impl EventHandler<FileBrowserEvent> for FileBrowserHandler {
+    fn handle(&mut self, event: &FileBrowserEvent, args: EventArgs) {
+        let browser = args.widget;
+        match *event {
+            FileBrowserEvent::Navigate(ref path) => self.navigate(path.clone(), &browser),
+            FileBrowserEvent::Listed(generation, ref entries) => {
+                if generation == self.generation {
+                    self.listed(entries.clone(), &browser);
+                }
+            }
+            FileBrowserEvent::Sort(key) => {
+                self.reverse = key == self.sort && !self.reverse;
+                self.sort = key;
+                self.clear_selection();
+                self.sort_entries();
+                self.rebuild_files(&browser);
+            }
+            FileBrowserEvent::Click(index, modifiers) => self.click(index, modifiers, &browser, args.ui),
+            FileBrowserEvent::StartRename => self.start_rename(&browser, args.ui),
+            FileBrowserEvent::Rename(ref name) => self.rename(name.clone(), &browser),
+        }
+    }
+}
+
+/// A list row with an optional folder or file icon, and the text indented by `indent`
+fn entry_row(name: &str, is_dir: Option<bool>, indent: f32, list: &Widget) -> Widget {
+    let mut row = Widget::new("file_row");
+    row.set_draw_style(DrawStyle::from_class::<RectStyle>("list_item_rect"))
+        .enable_hover();
+    row.layout().add(match_width(list));
+
+    let mut text_indent = indent + 5.0;
+    if let Some(is_dir) = is_dir {
+        let mut icon = Widget::new("file_icon");
+        icon.set_draw_style(style!(RectStyle {
+            background_color: if is_dir { YELLOW } else { GRAY_80 },
+            corner_radius: Some(2.0),
+            border: Some((1.0, GRAY_50)),
+        }));
+        icon.layout().add(constraints![
+            size(Size::new(14.0, 12.0)),
+            align_left(&row).padding(indent + 5.0),
+            center_vertical(&row),
+        ]);
+        row.add_child(icon);
+        text_indent += 20.0;
+    }
+    let mut text = Widget::new("file_name");
+    text.set_draw_style(DrawStyle::from_class::<TextStyle>("list_item_text"));
+    StaticTextStyle::from_text(name).component().apply(&mut text);
+    text.layout().add(align_left(&row).padding(text_indent));
+    row.add_child(text);
+    row
+}
+
+/// Creates a file browser widget
+#[derive(Debug, Copy, Clone)]
+pub struct FileBrowserBuilder;
+
+impl FileBrowserBuilder {
+    /// Creates a new `FileBrowserBuilder` showing the directory `path`, returns it in form of a `Widget`
+    pub fn new(path: PathBuf) -> Widget {
+        let mut widget = Widget::new("file_browser");
+
+        let mut breadcrumb = Widget::new("breadcrumb");
+        let mut layout_settings = LinearLayoutSettings::new(Orientation::Horizontal);
+        layout_settings.spacing = Spacing::End;
+        breadcrumb.linear_layout(layout_settings);
+        breadcrumb.layout().add(constraints![
+            align_top(&widget),
+            align_left(&widget),
+            align_right(&widget),
+        ]);
+
+        let mut status = Widget::from_modifier_style(StaticTextStyle::from_text(""));
+        status.set_name("status");
+        status.layout().add(constraints![
+            align_left(&widget).padding(5.0),
+            align_bottom(&widget),
+        ]);
+
+        let list_settings = {
+            let mut layout_settings = LinearLayoutSettings::new(Orientation::Vertical);
+            layout_settings.item_align = ItemAlignment::Fill;
+            layout_settings
+        };
+        let mut tree = Widget::new("directory_tree");
+        tree.linear_layout(list_settings);
+        let mut tree_scroll = ScrollContainer::default();
+        tree_scroll.add_content(tree.clone());
+        let mut tree_scroll = Widget::from_modifier(tree_scroll);
+        tree_scroll.layout().add(constraints![
+            below(&breadcrumb).padding(5.0),
+            align_left(&widget),
+            above(&status).padding(5.0),
+            width(200.0),
+        ]);
+
+        let mut header = Widget::new("file_list_header");
+        header.linear_layout(LinearLayoutSettings::new(Orientation::Horizontal));
+        header.layout().add(constraints![
+            below(&breadcrumb).padding(5.0),
+            to_right_of(&tree_scroll).padding(5.0),
+            align_right(&widget),
+        ]);
+        for &(title, key) in &[("Name", SortKey::Name), ("Size", SortKey::Size)] {
+            let mut button = Widget::from_modifier_style(ButtonStyle::from_text(title));
+            let browser = widget.clone();
+            button.add_handler(move |_: &ClickEvent, _: EventArgs| {
+                browser.event(FileBrowserEvent::Sort(key));
+            });
+            header.add_child(button);
+        }
+
+        let mut files = Widget::new("file_list");
+        files.linear_layout(list_settings);
+        let mut files_scroll = ScrollContainer::default();
+        files_scroll.add_scrollbar().add_content(files.clone());
+        let mut files_scroll = Widget::from_modifier(files_scroll);
+        files_scroll.layout().add(constraints![
+            below(&header).padding(5.0),
+            to_right_of(&tree_scroll).padding(5.0),
+            align_right(&widget),
+            above(&status).padding(5.0),
+        ]);
+
+        widget
+            .add_handler(FileBrowserHandler {
+                widgets: Widgets {
+                    breadcrumb: breadcrumb.clone(),
+                    tree: tree,
+                    files: files,
+                    status: status.clone(),
+                },
+                path: PathBuf::new(),
+                generation: 0,
+                entries: Vec::new(),
+                rows: Vec::new(),
+                selected: BTreeSet::new(),
+                anchor: None,
+                last_click: None,
+                sort: SortKey::Name,
+                reverse: false,
+                renaming: None,
+            })
+            .add_handler(|event: &WidgetKeyboardInput, args: EventArgs| {
+                let input = event.0;
+                if input.state == glutin::ElementState::Released && input.virtual_keycode == Some(glutin::VirtualKeyCode::F2) {
+                    args.widget.event(FileBrowserEvent::StartRename);
+                }
+            })
+            .add_handler(|event: &UnhandledKeyboardInput, args: EventArgs| {
+                if event.0.virtual_keycode == Some(glutin::VirtualKeyCode::F2) {
+                    *args.handled = true;
+                }
+            })
+            .make_focusable();
+        widget
+            .add_child(breadcrumb)
+            .add_child(tree_scroll)
+            .add_child(header)
+            .add_child(files_scroll)
+            .add_child(status);
+        widget.event(FileBrowserEvent::Navigate(path));
+        widget
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn rename_stays_in_directory() {
+        assert!(valid_file_name("notes.txt"));
+        assert!(valid_file_name("..hidden"));
+        assert!(!valid_file_name(".."));
+        assert!(!valid_file_name("."));
+        assert!(!valid_file_name("../notes.txt"));
+        assert!(!valid_file_name("dir/notes.txt"));
+        assert!(!valid_file_name("dir\\notes.txt"));
+    }
+}
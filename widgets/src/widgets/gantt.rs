@@ -0,0 +1,486 @@
+//! A Gantt chart, for scheduling tasks along a time axis measured in days.
+//!
+//! Task bars can be dragged to move them, or resized by dragging either end, snapping to whole days.
+//! Dependencies between tasks are drawn as arrows from the end of one task to the start of another,
+//! and follow the bars as they move. The task names and the day ruler stay in sync with the
+//! scrolled chart. Changes are reported to the gantt widget as `TaskSelected` and `TaskChanged` events.
+
+use prelude::*;
+use draw::prelude::*;
+use widgets::scroll::ScrollContainer;
+use widgets::text::StaticTextStyle;
+use widgets::replace_constraints;
+
+const RULER_HEIGHT: f32 = 20.0;
+const ROW_HEIGHT: f32 = 30.0;
+const BAR_MARGIN: f32 = 6.0;
+const NAME_WIDTH: f32 = 150.0;
+/// Width of the area at each end of a bar that resizes rather than moves it
+const RESIZE_HANDLE_WIDTH: f32 = 6.0;
+/// Horizontal distance dependency arrows travel before turning
+const ARROW_STUB: f32 = 8.0;
+const MIN_TASK_DURATION: f32 = 1.0;
+
+pub type TaskId = usize;
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Task {
+    pub name: String,
+    /// Start, in days from the start of the chart
+    pub start: f32,
+    /// Duration, in days
+    pub duration: f32,
+    /// Fraction of the task completed, `0.0..1.0`
+    pub progress: f32,
+}
+
+impl Task {
+    pub fn new(name: &str, start: f32, duration: f32) -> Self {
+        Task {
+            name: name.to_owned(),
+            start: start,
+            duration: duration,
+            progress: 0.0,
+        }
+    }
+    pub fn progress(mut self, progress: f32) -> Self {
+        self.progress = progress;
+        self
+    }
+    pub fn end(&self) -> f32 {
+        self.start + self.duration
+    }
+}
+
+/// Controls the chart, send to the gantt widget.
+#[derive(Debug, Copy, Clone)]
+pub enum GanttEvent {
+    /// Set the horizontal scale, in pixels per day
+    SetZoom(f32),
+    /// Move the today marker, in days from the start of the chart
+    SetToday(f32),
+}
+
+#[derive(Debug, Copy, Clone)]
+pub struct TaskSelected(pub TaskId);
+/// Sent to the gantt widget when a task is moved or resized.
+#[derive(Debug, Copy, Clone)]
+pub struct TaskChanged {
+    pub id: TaskId,
+    pub start: f32,
+    pub duration: f32,
+}
+
+#[derive(Debug, Copy, Clone, PartialEq)]
+enum DragMode {
+    Move,
+    ResizeStart,
+    ResizeEnd,
+}
+
+#[derive(Debug, Copy, Clone)]
+enum TaskInput {
+    Drag(TaskId, DragEvent),
+    Click(TaskId),
+}
+
+struct TaskBar {
+    task: Task,
+    widget: Widget,
+    progress: Widget,
+    constraints: Vec<Constraint>,
+    progress_constraints: Vec<Constraint>,
+    drag: Option<(DragMode, Task)>,
+}
+
+struct GanttHandler {
+    content: Widget,
+    ruler: Widget,
+    today_line: Widget,
+    dependency_paths: Widget,
+    bars: Vec<TaskBar>,
+    dependencies: Vec<(TaskId, TaskId)>,
+    selected: Option<TaskId>,
+    length: f32,
+    pixels_per_day: f32,
+    today: f32,
+    content_constraints: Vec<Constraint>,
+    today_constraints: Vec<Constraint>,
+}
+
+multi_event!{impl EventHandler<GanttInput> for GanttHandler {
+    GanttEvent => gantt_event,
+    TaskInput => task_input,
+}}
+
+impl GanttHandler {
+    fn layout_content(&mut self) {
+        let vars = self.content.layout_vars();
+        let constraints = vec![
+            vars.width | EQ(REQUIRED) | self.length * self.pixels_per_day,
+            vars.height | EQ(REQUIRED) | self.bars.len() as f32 * ROW_HEIGHT,
+        ];
+        replace_constraints(&mut self.content, &mut self.content_constraints, constraints);
+        let pixels_per_day = self.pixels_per_day;
+        self.ruler.update(|state: &mut RulerState| state.pixels_per_second = pixels_per_day);
+        for id in 0..self.bars.len() {
+            self.layout_bar(id);
+        }
+        self.layout_today();
+        self.update_dependencies();
+    }
+
+    fn layout_bar(&mut self, id: TaskId) {
+        let content = self.content.layout_vars();
+        let bar = &mut self.bars[id];
+        let vars = bar.widget.layout_vars();
+        let constraints = vec![
+            vars.left - content.left | EQ(REQUIRED) | bar.task.start * self.pixels_per_day,
+            vars.width | EQ(REQUIRED) | bar.task.duration * self.pixels_per_day,
+            vars.top - content.top | EQ(REQUIRED) | id as f32 * ROW_HEIGHT + BAR_MARGIN,
+            vars.height | EQ(REQUIRED) | ROW_HEIGHT - BAR_MARGIN * 2.0,
+        ];
+        replace_constraints(&mut bar.widget, &mut bar.constraints, constraints);
+        let progress = bar.progress.layout_vars();
+        let constraints = vec![
+            progress.width | EQ(REQUIRED) | bar.task.duration * self.pixels_per_day * bar.task.progress.max(0.0).min(1.0),
+        ];
+        replace_constraints(&mut bar.progress, &mut bar.progress_constraints, constraints);
+    }
+
+    fn layout_today(&mut self) {
+        let content = self.content.layout_vars();
+        let vars = self.today_line.layout_vars();
+        let constraints = vec![
+            vars.left - content.left | EQ(REQUIRED) | self.today * self.pixels_per_day,
+        ];
+        replace_constraints(&mut self.today_line, &mut self.today_constraints, constraints);
+    }
+
+    /// Route an arrow from the end of each dependency to the start of the dependent task
+    fn update_dependencies(&mut self) {
+        let ppd = self.pixels_per_day;
+        let paths = self.dependencies.iter().map(|&(from, to)| {
+            let (from_task, to_task) = (&self.bars[from].task, &self.bars[to].task);
+            let start = Point::new(from_task.end() * ppd, (from as f32 + 0.5) * ROW_HEIGHT);
+            let end = Point::new(to_task.start * ppd, (to as f32 + 0.5) * ROW_HEIGHT);
+            let mut points = vec![start];
+            if end.x - start.x >= ARROW_STUB * 2.0 {
+                points.push(Point::new(start.x + ARROW_STUB, start.y));
+                points.push(Point::new(start.x + ARROW_STUB, end.y));
+            } else {
+                // the dependent task starts too early to go straight there, so loop back between the rows
+                let between = if to > from { (from as f32 + 1.0) * ROW_HEIGHT } else { from as f32 * ROW_HEIGHT };
+                points.push(Point::new(start.x + ARROW_STUB, start.y));
+                points.push(Point::new(start.x + ARROW_STUB, between));
+                points.push(Point::new(end.x - ARROW_STUB, between));
+                points.push(Point::new(end.x - ARROW_STUB, end.y));
+            }
+            points.push(end);
+            Path::arrow(points)
+        }).collect();
+        self.dependency_paths.update(|state: &mut PathState| state.paths = paths);
+    }
+
+    fn gantt_event(&mut self, event: &GanttEvent, _: EventArgs) {
+        match *event {
+            GanttEvent::SetZoom(pixels_per_day) => {
+                self.pixels_per_day = pixels_per_day.max(1.0);
+                self.layout_content();
+            }
+            GanttEvent::SetToday(today) => {
+                self.today = today;
+                self.layout_today();
+            }
+        }
+    }
+
+    fn select(&mut self, id: TaskId, gantt: &Widget) {
+        if self.selected == Some(id) {
+            return;
+        }
+        if let Some(selected) = self.selected {
+            self.bars[selected].widget.remove_prop(Property::Selected);
+        }
+        self.bars[id].widget.add_prop(Property::Selected);
+        self.selected = Some(id);
+        gantt.event(TaskSelected(id));
+    }
+
+    fn task_input(&mut self, event: &TaskInput, args: EventArgs) {
+        match *event {
+            TaskInput::Click(id) => self.select(id, &args.widget),
+            TaskInput::Drag(id, ref drag) => self.drag(id, drag, &args.widget),
+        }
+    }
+
+    fn drag(&mut self, id: TaskId, event: &DragEvent, gantt: &Widget) {
+        match event.state {
+            DragState::Start => {
+                self.select(id, gantt);
+                let bar = &mut self.bars[id];
+                let bounds = bar.widget.bounds();
+                let mode = if event.position.x < bounds.left() + RESIZE_HANDLE_WIDTH {
+                    DragMode::ResizeStart
+                } else if event.position.x > bounds.right() - RESIZE_HANDLE_WIDTH {
+                    DragMode::ResizeEnd
+                } else {
+                    DragMode::Move
+                };
+                bar.drag = Some((mode, bar.task.clone()));
+            }
+            DragState::Moved => {
+                let (mode, original) = match self.bars[id].drag.clone() {
+                    Some(drag) => drag,
+                    None => return,
+                };
+                let delta = (event.offset.x / self.pixels_per_day).round();
+                let mut task = original.clone();
+                match mode {
+                    DragMode::Move => {
+                        task.start = (original.start + delta).max(0.0).min(self.length - task.duration);
+                    }
+                    DragMode::ResizeStart => {
+                        task.start = (original.start + delta).max(0.0).min(original.end() - MIN_TASK_DURATION);
+                        task.duration = original.end() - task.start;
+                    }
+                    DragMode::ResizeEnd => {
+                        let end = (original.end() + delta).min(self.length);
+                        task.duration = (end - original.start).max(MIN_TASK_DURATION);
+                    }
+                }
+                if task != self.bars[id].task {
+                    self.bars[id].task = task;
+                    self.layout_bar(id);
+                    self.update_dependencies();
+                }
+            }
+            DragState::End => {
+                if let Some((_, original)) = self.bars[id].drag.take() {
+                    let task = &self.bars[id].task;
+                    if *task != original {
+                        gantt.event(TaskChanged { id: id, start: task.start, duration: task.duration });
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// Builds a gantt chart widget from a list of tasks and the dependencies between them.
+#[derive(Debug, Clone)]
+pub struct GanttBuilder {
+    tasks: Vec<Task>,
+    dependencies: Vec<(TaskId, TaskId)>,
+    length: Option<f32>,
+    pixels_per_day: f32,
+    today: f32,
+}
+
+impl Default for GanttBuilder {
+    fn default() -> Self {
+        GanttBuilder {
+            tasks: Vec::new(),
+            dependencies: Vec::new(),
+            length: None,
+            pixels_per_day: 20.0,
+            today: 0.0,
+        }
+    }
+}
+
+impl GanttBuilder {
+    pub fn new() -> Self {
+        Self::default()
+    }
+    /// Add a task, one per row, returns the `TaskId` used to identify it in events
+    pub fn add_task(&mut self, task: Task) -> TaskId {
+        self.tasks.push(task);
+        self.tasks.len() - 1
+    }
+    /// Add a dependency, `to` can't start until `from` has finished
+    pub fn add_dependency(&mut self, from: TaskId, to: TaskId) -> &mut Self {
+        assert!(from < self.tasks.len() && to < self.tasks.len(), "dependency on a task that doesn't exist");
+        self.dependencies.push((from, to));
+        self
+    }
+    /// Set the total length in days, by default this is a couple of weeks past the last task
+    pub fn set_length(&mut self, length: f32) -> &mut Self {
+        self.length = Some(length);
+        self
+    }
+    /// Set the initial horizontal scale
+    pub fn set_zoom(&mut self, pixels_per_day: f32) -> &mut Self {
+        self.pixels_per_day = pixels_per_day;
+        self
+    }
+    /// Set the position of the today marker, in days from the start of the chart
+    pub fn set_today(&mut self, today: f32) -> &mut Self {
+        self.today = today;
+        self
+    }
+
+    /// Create the gantt chart widget
+    pub fn build(&self) -> Widget {
+        let mut widget = Widget::new("gantt");
+        let length = self.length.unwrap_or_else(|| {
+            self.tasks.iter().map(Task::end).fold(self.today, f32::max) + 14.0
+        });
+
+        let mut content = Widget::new("gantt_content");
+        let mut bars = Vec::new();
+        for (id, task) in self.tasks.iter().enumerate() {
+            let mut row = Widget::new("gantt_row");
+            row.set_draw_style(style!(RectStyle {
+                background_color: if id % 2 == 0 { GRAY_90 } else { GRAY_80 },
+            }));
+            row.layout().add(constraints![
+                align_left(&content),
+                align_right(&content),
+                align_top(&content).padding(id as f32 * ROW_HEIGHT),
+                height(ROW_HEIGHT),
+            ]);
+            content.add_child(row);
+
+            let mut bar = Widget::new("task_bar");
+            let mut draw_style = DrawStyle::from(style!(RectStyle {
+                background_color: BLUE_HIGHLIGHT,
+                corner_radius: Some(3.0),
+                border: Some((1.0, GRAY_30)),
+            }));
+            draw_style.prop_style(SELECTED.clone(), style!(RectStyle {
+                border: Some((2.0, WHITE)),
+            }));
+            bar.set_draw_style(draw_style);
+            let mut progress = Widget::new("task_progress");
+            progress.set_draw_style(style!(RectStyle {
+                background_color: GRAY_30,
+                corner_radius: Some(3.0),
+            }));
+            progress.layout().add(constraints![
+                align_left(&bar),
+                align_top(&bar),
+                align_bottom(&bar),
+            ]);
+            bar.add_child(progress.clone());
+            let gantt = widget.clone();
+            bar.add_handler(move |event: &DragEvent, _: EventArgs| {
+                gantt.event(TaskInput::Drag(id, *event));
+            });
+            let gantt = widget.clone();
+            bar.add_handler(move |_: &ClickEvent, args: EventArgs| {
+                gantt.event(TaskInput::Click(id));
+                *args.handled = true;
+            });
+            bar.make_draggable();
+            bars.push(TaskBar {
+                task: task.clone(),
+                widget: bar,
+                progress: progress,
+                constraints: Vec::new(),
+                progress_constraints: Vec::new(),
+                drag: None,
+            });
+        }
+
+        let mut dependency_paths = Widget::new("gantt_dependencies");
+        dependency_paths.set_draw_state(PathState::default());
+        dependency_paths.layout().add(match_layout(&content));
+        content.add_child(dependency_paths.clone());
+        // bars go above the dependency arrows
+        for bar in &bars {
+            content.add_child(bar.widget.clone());
+        }
+
+        let mut today_line = Widget::new("today_line");
+        today_line.set_draw_style(style!(RectStyle {
+            background_color: RED,
+        }));
+        today_line.layout().add(constraints![
+            align_top(&content),
+            align_bottom(&content),
+            width(2.0),
+        ]);
+        content.add_child(today_line.clone());
+
+        let mut scroll_widget = ScrollContainer::default();
+        scroll_widget.add_scrollbar().add_content(content.clone());
+        let mut scroll_widget = Widget::from_modifier(scroll_widget);
+        scroll_widget.layout().add(constraints![
+            align_top(&widget).padding(RULER_HEIGHT),
+            align_left(&widget).padding(NAME_WIDTH),
+            align_right(&widget),
+            align_bottom(&widget),
+        ]);
+
+        // the ruler and task names are outside the scroll container, and kept in sync with the
+        // scrolled content by constraining them to it, they are clipped to their holders
+        let mut ruler_holder = Widget::new("gantt_ruler_holder");
+        ruler_holder.layout().no_container();
+        ruler_holder.layout().add(constraints![
+            align_top(&widget),
+            align_left(&scroll_widget),
+            align_right(&scroll_widget),
+            height(RULER_HEIGHT),
+        ]);
+        let mut ruler = Widget::new("gantt_ruler");
+        ruler.set_draw_state(RulerState {
+            major_interval: 7.0,
+            subdivisions: 7,
+            ..RulerState::default()
+        });
+        ruler.layout().add(constraints![
+            align_top(&ruler_holder),
+            align_bottom(&ruler_holder),
+            align_left(&content),
+            align_right(&content),
+        ]);
+        ruler_holder.add_child(ruler.clone());
+
+        let mut names = Widget::new("gantt_names");
+        names.layout().no_container();
+        names.layout().add(constraints![
+            align_top(&scroll_widget),
+            align_bottom(&scroll_widget),
+            align_left(&widget),
+            width(NAME_WIDTH),
+        ]);
+        for (id, task) in self.tasks.iter().enumerate() {
+            let mut name = Widget::from_modifier_style(StaticTextStyle::from_style(style!(TextStyle {
+                text: task.name.clone(),
+                font_size: 16.0,
+            })));
+            name.set_name("task_name");
+            let content_vars = content.layout_vars();
+            let vars = name.layout_vars();
+            name.layout().add(constraints![
+                align_left(&names).padding(5.0),
+                vars.top - content_vars.top | EQ(REQUIRED) | id as f32 * ROW_HEIGHT + BAR_MARGIN,
+            ]);
+            names.add_child(name);
+        }
+
+        let mut handler = GanttHandler {
+            content: content,
+            ruler: ruler,
+            today_line: today_line,
+            dependency_paths: dependency_paths,
+            bars: bars,
+            dependencies: self.dependencies.clone(),
+            selected: None,
+            length: length,
+            pixels_per_day: self.pixels_per_day,
+            today: self.today,
+            content_constraints: Vec::new(),
+            today_constraints: Vec::new(),
+        };
+        handler.layout_content();
+        widget.add_handler(handler);
+        GanttHandler::add_adapters(&mut widget);
+        widget
+            .add_child(ruler_holder)
+            .add_child(names)
+            .add_child(scroll_widget);
+        widget
+    }
+}
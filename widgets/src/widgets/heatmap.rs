@@ -0,0 +1,413 @@
+//! A dense 2D matrix drawn as colored cells, ex. for correlation matrices or activity grids.
+//!
+//! The matrix is rendered as a raw image with one pixel per cell, so large matrices are cheap
+//! to draw, and changing a region of the matrix only uploads the changed pixels.
+//! Hovering over a cell shows its value below the matrix, and sends `HeatmapCellHovered` to the heatmap widget.
+
+use format;
+use prelude::*;
+use draw::prelude::*;
+use widgets::text::StaticTextStyle;
+use widgets::edit_text::TextUpdated;
+use input::mouse::MouseOverEvent;
+use webrender::api::{DeviceUintRect, DeviceUintPoint, DeviceUintSize};
+
+const GUTTER_FONT_SIZE: f32 = 14.0;
+const ROW_GUTTER_WIDTH: f32 = 80.0;
+const COLUMN_GUTTER_HEIGHT: f32 = 24.0;
+const LEGEND_WIDTH: f32 = 20.0;
+
+/// Changes the values or range of the heatmap, send to the heatmap widget.
+#[derive(Debug, Clone)]
+pub enum HeatmapEvent {
+    /// Replace all values, in row major order. Ignored unless there's a value for every cell.
+    SetValues(Vec<f32>),
+    /// Replace a rectangular region of values, in row major order, starting at `column`, `row`.
+    /// Regions that don't fit in the matrix, or don't fill whole rows, are ignored.
+    SetRegion {
+        column: usize,
+        row: usize,
+        columns: usize,
+        values: Vec<f32>,
+    },
+    /// Set the values mapped to the low and high ends of the color scale
+    SetRange(f32, f32),
+}
+
+/// Sent to the heatmap widget when the mouse moves over a cell.
+#[derive(Debug, Copy, Clone)]
+pub struct HeatmapCellHovered {
+    pub column: usize,
+    pub row: usize,
+    pub value: f32,
+}
+
+#[derive(Debug, Copy, Clone)]
+enum HeatmapHover {
+    Moved(Point),
+    Out,
+}
+
+struct HeatmapHandler {
+    columns: usize,
+    rows: usize,
+    values: Vec<f32>,
+    pixels: Vec<u8>,
+    image_name: String,
+    range: (f32, f32),
+    scale: ColorScale,
+    row_labels: Vec<String>,
+    column_labels: Vec<String>,
+    hovered: Option<(usize, usize)>,
+    matrix: Widget,
+    readout: Widget,
+    min_label: Widget,
+    max_label: Widget,
+}
+
+impl Drop for HeatmapHandler {
+    fn drop(&mut self) {
+        // the handler lives as long as the heatmap widget, so the image is freed with it
+        resources().image_loader.remove_image(&ImageSource::bundled(self.image_name.clone()));
+    }
+}
+
+multi_event!{impl EventHandler<HeatmapInput> for HeatmapHandler {
+    HeatmapEvent => heatmap_event,
+    HeatmapHover => hover,
+}}
+
+impl HeatmapHandler {
+    fn color_cell(&mut self, index: usize) {
+        let (min, max) = self.range;
+        let amount = if max > min { (self.values[index] - min) / (max - min) } else { 0.5 };
+        let rgba = self.scale.rgba_at(amount);
+        self.pixels[index * 4..index * 4 + 4].copy_from_slice(&rgba);
+    }
+
+    /// Upload the pixels, only the region within `dirty_rect` if given
+    fn upload(&mut self, dirty_rect: Option<DeviceUintRect>) {
+        resources().image_loader.update_raw_image(&self.image_name, self.pixels.clone(), dirty_rect);
+        // the image key is unchanged, so the widget needs an explicit redraw
+        self.matrix.update(|_: &mut HeatmapState| {});
+    }
+
+    fn update_range_labels(&mut self) {
+        self.min_label.event(TextUpdated(format_value(self.range.0)));
+        self.max_label.event(TextUpdated(format_value(self.range.1)));
+    }
+
+    fn heatmap_event(&mut self, event: &HeatmapEvent, args: EventArgs) {
+        match *event {
+            HeatmapEvent::SetValues(ref values) => {
+                if values.len() != self.columns * self.rows {
+                    warn!("heatmap values ignored, {} values for {}x{} cells", values.len(), self.columns, self.rows);
+                    return;
+                }
+                self.values.copy_from_slice(values);
+                for index in 0..self.values.len() {
+                    self.color_cell(index);
+                }
+                self.upload(None);
+            }
+            HeatmapEvent::SetRegion { column, row, columns, ref values } => {
+                if values.is_empty() {
+                    return;
+                }
+                if columns == 0 || values.len() % columns != 0 {
+                    warn!("heatmap region ignored, {} values don't make rows of {}", values.len(), columns);
+                    return;
+                }
+                let rows = values.len() / columns;
+                if column + columns > self.columns || row + rows > self.rows {
+                    warn!("heatmap region ignored, {}x{} at {}, {} is outside {}x{} cells",
+                        columns, rows, column, row, self.columns, self.rows);
+                    return;
+                }
+                for (offset, value) in values.iter().enumerate() {
+                    let index = (row + offset / columns) * self.columns + column + offset % columns;
+                    self.values[index] = *value;
+                    self.color_cell(index);
+                }
+                self.upload(Some(DeviceUintRect::new(
+                    DeviceUintPoint::new(column as u32, row as u32),
+                    DeviceUintSize::new(columns as u32, rows as u32))));
+            }
+            HeatmapEvent::SetRange(min, max) => {
+                self.range = (min, max);
+                for index in 0..self.values.len() {
+                    self.color_cell(index);
+                }
+                self.upload(None);
+                self.update_range_labels();
+            }
+        }
+        // refresh the readout, the hovered value may have changed
+        if let Some((column, row)) = self.hovered.take() {
+            self.hover_cell(column, row, &args.widget);
+        }
+    }
+
+    fn hover_cell(&mut self, column: usize, row: usize, heatmap: &Widget) {
+        if self.hovered == Some((column, row)) {
+            return;
+        }
+        self.hovered = Some((column, row));
+        let value = self.values[row * self.columns + column];
+        let row_label = self.row_labels.get(row).cloned().unwrap_or_else(|| row.to_string());
+        let column_label = self.column_labels.get(column).cloned().unwrap_or_else(|| column.to_string());
+        self.readout.event(TextUpdated(format!("{}, {}: {}", row_label, column_label, format_value(value))));
+        heatmap.event(HeatmapCellHovered { column: column, row: row, value: value });
+    }
+
+    fn hover(&mut self, event: &HeatmapHover, args: EventArgs) {
+        match *event {
+            HeatmapHover::Moved(point) => {
+                let bounds = self.matrix.bounds();
+                if bounds.width() <= 0.0 || bounds.height() <= 0.0 {
+                    return;
+                }
+                let x = (point.x - bounds.left()) / bounds.width();
+                let y = (point.y - bounds.top()) / bounds.height();
+                if x < 0.0 || x >= 1.0 || y < 0.0 || y >= 1.0 {
+                    return;
+                }
+                let column = (x * self.columns as f32) as usize;
+                let row = (y * self.rows as f32) as usize;
+                self.hover_cell(column, row, &args.widget);
+            }
+            HeatmapHover::Out => {
+                self.hovered = None;
+                self.readout.event(TextUpdated(String::new()));
+            }
+        }
+    }
+}
+
+fn format_value(value: f32) -> String {
+    format::number(f64::from(value))
+}
+
+fn gutter_label(text: &str, align: Align, rotation: f32) -> Widget {
+    let mut label = Widget::new("heatmap_label");
+    StaticTextStyle::from_style(style!(TextStyle {
+        text: text.to_owned(),
+        font_size: GUTTER_FONT_SIZE,
+        align: align,
+        rotation: rotation,
+    })).component().apply(&mut label);
+    label
+}
+
+/// Builds a heatmap widget for a matrix of values.
+#[derive(Debug, Clone)]
+pub struct HeatmapBuilder {
+    columns: usize,
+    rows: usize,
+    values: Vec<f32>,
+    range: Option<(f32, f32)>,
+    scale: ColorScale,
+    row_labels: Vec<String>,
+    column_labels: Vec<String>,
+    column_label_rotation: f32,
+    cell_size: f32,
+}
+
+impl HeatmapBuilder {
+    /// Create a builder for a matrix with all values zero
+    pub fn new(columns: usize, rows: usize) -> Self {
+        assert!(columns > 0 && rows > 0, "heatmap needs at least one row and column");
+        HeatmapBuilder {
+            columns: columns,
+            rows: rows,
+            values: vec![0.0; columns * rows],
+            range: None,
+            scale: ColorScale::default(),
+            row_labels: Vec::new(),
+            column_labels: Vec::new(),
+            column_label_rotation: 0.0,
+            cell_size: 16.0,
+        }
+    }
+    /// Set the initial values, in row major order
+    pub fn set_values(&mut self, values: Vec<f32>) -> &mut Self {
+        assert_eq!(values.len(), self.columns * self.rows, "heatmap values don't match dimensions");
+        self.values = values;
+        self
+    }
+    /// Set the values mapped to the ends of the color scale, by default the range of the initial values
+    pub fn set_range(&mut self, min: f32, max: f32) -> &mut Self {
+        self.range = Some((min, max));
+        self
+    }
+    pub fn set_color_scale(&mut self, scale: ColorScale) -> &mut Self {
+        self.scale = scale;
+        self
+    }
+    pub fn set_row_labels(&mut self, labels: Vec<String>) -> &mut Self {
+        self.row_labels = labels;
+        self
+    }
+    pub fn set_column_labels(&mut self, labels: Vec<String>) -> &mut Self {
+        self.column_labels = labels;
+        self
+    }
+    /// Rotate column labels clockwise by `degrees`, ex. -90.0 to fit long labels over narrow columns,
+    /// reading bottom to top
+    pub fn set_column_label_rotation(&mut self, degrees: f32) -> &mut Self {
+        self.column_label_rotation = degrees;
+        self
+    }
+    /// Set the minimum size of each cell, in pixels
+    pub fn set_cell_size(&mut self, cell_size: f32) -> &mut Self {
+        self.cell_size = cell_size;
+        self
+    }
+
+    /// Create the heatmap widget
+    pub fn build(&self) -> Widget {
+        let mut widget = Widget::new("heatmap");
+        let image_name = format!("heatmap_{}", widget.id().0);
+        let range = self.range.unwrap_or_else(|| {
+            let min = self.values.iter().cloned().fold(::std::f32::INFINITY, f32::min);
+            let max = self.values.iter().cloned().fold(::std::f32::NEG_INFINITY, f32::max);
+            (min, max)
+        });
+
+        let row_gutter = if self.row_labels.is_empty() { 0.0 } else { ROW_GUTTER_WIDTH };
+        let column_gutter = if self.column_labels.is_empty() {
+            0.0
+        } else if self.column_label_rotation != 0.0 {
+            ROW_GUTTER_WIDTH
+        } else {
+            COLUMN_GUTTER_HEIGHT
+        };
+
+        let mut readout = Widget::new("heatmap_readout");
+        StaticTextStyle::from_text("").component().apply(&mut readout);
+        readout.layout().add(constraints![
+            align_left(&widget).padding(row_gutter),
+            align_bottom(&widget),
+        ]);
+
+        let mut matrix = Widget::new("heatmap_matrix");
+        matrix.set_draw_state(HeatmapState::new(ImageSource::bundled(image_name.clone())));
+        matrix.layout().add(constraints![
+            align_left(&widget).padding(row_gutter),
+            align_top(&widget).padding(column_gutter),
+            above(&readout).padding(5.0),
+            min_width(self.columns as f32 * self.cell_size),
+            min_height(self.rows as f32 * self.cell_size),
+        ]);
+        let heatmap = widget.clone();
+        matrix.add_handler(move |event: &WidgetMouseMoved, _: EventArgs| {
+            heatmap.event(HeatmapHover::Moved(event.0));
+        });
+        let heatmap = widget.clone();
+        matrix.add_handler(move |event: &MouseOverEvent, _: EventArgs| {
+            if let MouseOverEvent::Out = *event {
+                heatmap.event(HeatmapHover::Out);
+            }
+        });
+
+        // row and column labels, each centered on its row or column
+        for (row, label) in self.row_labels.iter().enumerate() {
+            let mut slot = Widget::new("heatmap_row_label");
+            let vars = slot.layout_vars();
+            let matrix_vars = matrix.layout_vars();
+            let (start, end) = (row as f32 / self.rows as f32, (row + 1) as f32 / self.rows as f32);
+            slot.layout().add(constraints![
+                align_left(&widget),
+                to_left_of(&matrix).padding(5.0),
+                vars.top - matrix_vars.top | EQ(REQUIRED) | matrix_vars.height * start,
+                vars.bottom - matrix_vars.top | EQ(REQUIRED) | matrix_vars.height * end,
+            ]);
+            let mut text = gutter_label(label, Align::End, 0.0);
+            text.layout().add(constraints![
+                align_right(&slot),
+                center_vertical(&slot),
+            ]);
+            slot.add_child(text);
+            widget.add_child(slot);
+        }
+        for (column, label) in self.column_labels.iter().enumerate() {
+            let mut slot = Widget::new("heatmap_column_label");
+            let vars = slot.layout_vars();
+            let matrix_vars = matrix.layout_vars();
+            let (start, end) = (column as f32 / self.columns as f32, (column + 1) as f32 / self.columns as f32);
+            slot.layout().add(constraints![
+                align_top(&widget),
+                above(&matrix),
+                vars.left - matrix_vars.left | EQ(REQUIRED) | matrix_vars.width * start,
+                vars.right - matrix_vars.left | EQ(REQUIRED) | matrix_vars.width * end,
+            ]);
+            let mut text = gutter_label(label, Align::Middle, self.column_label_rotation);
+            if self.column_label_rotation != 0.0 {
+                text.layout().add(constraints![
+                    center_horizontal(&slot),
+                    align_bottom(&slot).padding(5.0),
+                ]);
+            } else {
+                text.layout().add(constraints![
+                    center_horizontal(&slot),
+                    center_vertical(&slot),
+                ]);
+            }
+            slot.add_child(text);
+            widget.add_child(slot);
+        }
+
+        // color scale legend, with the range labelled at each end
+        let mut legend = Widget::new("heatmap_legend");
+        legend.set_draw_state(ColorScaleState::new(self.scale.clone()));
+        let mut max_label = gutter_label(&format_value(range.1), Align::Start, 0.0);
+        let mut min_label = gutter_label(&format_value(range.0), Align::Start, 0.0);
+        legend.layout().add(constraints![
+            to_right_of(&matrix).padding(10.0),
+            align_top(&matrix),
+            align_bottom(&matrix),
+            width(LEGEND_WIDTH),
+        ]);
+        max_label.layout().add(constraints![
+            to_right_of(&legend).padding(5.0),
+            align_top(&legend),
+            align_right(&widget),
+        ]);
+        min_label.layout().add(constraints![
+            to_right_of(&legend).padding(5.0),
+            align_bottom(&legend),
+            align_right(&widget),
+        ]);
+
+        let mut handler = HeatmapHandler {
+            columns: self.columns,
+            rows: self.rows,
+            values: self.values.clone(),
+            pixels: vec![0; self.columns * self.rows * 4],
+            image_name: image_name.clone(),
+            range: range,
+            scale: self.scale.clone(),
+            row_labels: self.row_labels.clone(),
+            column_labels: self.column_labels.clone(),
+            hovered: None,
+            matrix: matrix.clone(),
+            readout: readout.clone(),
+            min_label: min_label.clone(),
+            max_label: max_label.clone(),
+        };
+        for index in 0..handler.values.len() {
+            handler.color_cell(index);
+        }
+        resources().image_loader.load_raw_image(&image_name, self.columns as u32, self.rows as u32, handler.pixels.clone());
+
+        widget
+            .add_child(matrix)
+            .add_child(legend)
+            .add_child(max_label)
+            .add_child(min_label)
+            .add_child(readout)
+            .add_handler(handler);
+        HeatmapHandler::add_adapters(&mut widget);
+        widget
+    }
+}
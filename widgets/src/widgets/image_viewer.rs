@@ -0,0 +1,185 @@
+//! An image viewer, for inspecting an image: the mouse wheel zooms around the cursor, dragging
+//! pans, and the pixel under the cursor is reported with `ImagePixelHovered`, ex. for a readout
+//! of its coordinates. Transparent parts of the image show a checkerboard.
+
+use prelude::*;
+use draw::prelude::*;
+use input::mouse::MouseOverEvent;
+
+/// Changes an image viewer, send to the image viewer widget
+#[derive(Debug, Clone)]
+pub enum ImageViewerEvent {
+    SetImage(ImageSource),
+    SetMode(ImageViewerMode),
+    /// Turn the image by a number of quarter turns clockwise, negative to turn counterclockwise
+    Rotate(i32),
+    /// Zoom by a factor around the center of the viewer
+    ZoomBy(f32),
+}
+
+/// Sent to the image viewer widget when the cursor moves over a different pixel of the image,
+/// or off the image
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct ImagePixelHovered(pub Option<(u32, u32)>);
+
+/// Sent to the image viewer widget when the scale the image is shown at changes
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct ImageZoomChanged(pub f32);
+
+/// Sent to the image viewer widget when the image moves, is scaled or turned, ex. to keep an
+/// overlay over the image
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct ImageViewChanged {
+    /// Where the image is shown, in window coordinates
+    pub rect: Rect,
+    /// Size of the image in pixels, turned along with it
+    pub size: Size,
+}
+
+struct ImageViewerHandler {
+    /// Position of the mouse over the viewer
+    mouse: Option<Point>,
+    pixel: Option<(u32, u32)>,
+    zoom: f32,
+    view: Option<ImageViewChanged>,
+}
+
+multi_event!{impl EventHandler<ImageViewerInput> for ImageViewerHandler {
+    ImageViewerEvent => viewer_event,
+    WidgetMouseMoved => mouse_moved,
+    MouseOverEvent => mouse_over,
+    WidgetMouseWheel => mouse_wheel,
+    DragEvent => drag,
+    LayoutUpdated => layout_updated,
+}}
+
+impl ImageViewerHandler {
+    /// Change the state, then report the view, the zoom and the pixel under the mouse if they changed
+    fn update<F: FnOnce(&mut ImageViewerState, Rect)>(&mut self, widget: &mut Widget, f: F) {
+        let bounds = widget.bounds();
+        widget.update(|state: &mut ImageViewerState| f(state, bounds));
+        let (view, zoom, pixel) = {
+            let draw_state = widget.draw_state();
+            let state = draw_state.downcast_ref::<ImageViewerState>().unwrap();
+            let view = ImageViewChanged { rect: state.image_rect(bounds), size: state.rotated_size() };
+            (view, state.zoom(bounds), self.mouse.and_then(|mouse| state.pixel_at(mouse, bounds)))
+        };
+        if self.view != Some(view) {
+            self.view = Some(view);
+            widget.event(view);
+        }
+        if zoom != self.zoom {
+            self.zoom = zoom;
+            widget.event(ImageZoomChanged(zoom));
+        }
+        if pixel != self.pixel {
+            self.pixel = pixel;
+            widget.event(ImagePixelHovered(pixel));
+        }
+    }
+
+    fn viewer_event(&mut self, event: &ImageViewerEvent, mut args: EventArgs) {
+        self.update(&mut args.widget, |state, bounds| {
+            match *event {
+                ImageViewerEvent::SetImage(ref image) => {
+                    state.image = image.clone();
+                    state.offset = Vector::zero();
+                }
+                ImageViewerEvent::SetMode(mode) => {
+                    state.mode = mode;
+                    state.offset = Vector::zero();
+                }
+                ImageViewerEvent::Rotate(quarter_turns) => state.rotate(quarter_turns),
+                ImageViewerEvent::ZoomBy(factor) => state.zoom_by(factor, bounds.center(), bounds),
+            }
+        });
+    }
+
+    fn mouse_moved(&mut self, event: &WidgetMouseMoved, mut args: EventArgs) {
+        self.mouse = Some(event.0);
+        self.update(&mut args.widget, |_, _| ());
+    }
+
+    fn mouse_over(&mut self, event: &MouseOverEvent, mut args: EventArgs) {
+        if let MouseOverEvent::Out = *event {
+            self.mouse = None;
+            self.update(&mut args.widget, |_, _| ());
+        }
+    }
+
+    fn mouse_wheel(&mut self, event: &WidgetMouseWheel, mut args: EventArgs) {
+        let delta = match event.0 {
+            glutin::MouseScrollDelta::LineDelta(_, y) => y,
+            glutin::MouseScrollDelta::PixelDelta(glutin::dpi::LogicalPosition{y, ..}) => y as f32 / 20.0,
+        };
+        let mouse = self.mouse;
+        self.update(&mut args.widget, |state, bounds| {
+            state.zoom_by(1.25f32.powf(delta), mouse.unwrap_or_else(|| bounds.center()), bounds);
+        });
+    }
+
+    fn drag(&mut self, event: &DragEvent, mut args: EventArgs) {
+        let change = event.change;
+        self.update(&mut args.widget, |state, bounds| state.pan(change, bounds));
+    }
+
+    fn layout_updated(&mut self, _: &LayoutUpdated, mut args: EventArgs) {
+        // a fitted image is rescaled with the viewer
+        self.update(&mut args.widget, |_, _| ());
+    }
+}
+
+/// Builds an image viewer
+#[derive(Debug, Clone)]
+pub struct ImageViewerBuilder {
+    image: ImageSource,
+    mode: ImageViewerMode,
+    checkerboard: Option<f32>,
+    background_color: Option<Color>,
+}
+
+impl ImageViewerBuilder {
+    pub fn new(image: ImageSource) -> Self {
+        ImageViewerBuilder {
+            image: image,
+            mode: ImageViewerMode::Fit,
+            checkerboard: Some(8.0),
+            background_color: None,
+        }
+    }
+    /// How the image is scaled at first, fit to the viewer by default
+    pub fn set_mode(&mut self, mode: ImageViewerMode) -> &mut Self {
+        self.mode = mode;
+        self
+    }
+    /// Size of the squares of the checkerboard behind the image, `None` for no checkerboard
+    pub fn set_checkerboard(&mut self, square: Option<f32>) -> &mut Self {
+        self.checkerboard = square;
+        self
+    }
+    /// Color around the image
+    pub fn set_background_color(&mut self, color: Color) -> &mut Self {
+        self.background_color = Some(color);
+        self
+    }
+
+    pub fn build(&self) -> Widget {
+        let mut widget = Widget::new("image_viewer");
+        let mut state = ImageViewerState::new(self.image.clone());
+        state.mode = self.mode;
+        state.checkerboard = self.checkerboard;
+        if let Some(color) = self.background_color {
+            state.background_color = color;
+        }
+        widget.set_draw_state(state);
+        widget.add_handler(ImageViewerHandler {
+            mouse: None,
+            pixel: None,
+            zoom: 0.0,
+            view: None,
+        });
+        ImageViewerHandler::add_adapters(&mut widget);
+        widget.make_draggable();
+        widget
+    }
+}
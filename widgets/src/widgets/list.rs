@@ -0,0 +1,264 @@
+use prelude::*;
+use draw::prelude::*;
+use widgets::text::StaticTextStyle;
+use widgets::skeleton::{SkeletonBuilder, SKELETON_LINE_HEIGHT};
+
+pub struct ListItemSelected {
+    pub widget: Option<Widget>,
+}
+
+#[derive(Debug, Copy, Clone)]
+pub struct ItemSelected;
+
+#[derive(Default)]
+pub struct ListHandler {
+    selected: Option<Widget>,
+}
+
+impl EventHandler<ListItemSelected> for ListHandler {
+    fn handle(&mut self, event: &ListItemSelected, _: EventArgs) {
+        let selected = event.widget.clone();
+        if selected != self.selected {
+            if let Some(ref mut old_selected) = self.selected {
+                old_selected.remove_prop(Property::Selected);
+            }
+        }
+        self.selected = selected;
+    }
+}
+
+pub struct ListItemHandler {
+    list_widget: Widget,
+}
+
+impl ListItemHandler {
+    pub fn new(list_widget: Widget) -> Self {
+        ListItemHandler { list_widget: list_widget }
+    }
+}
+
+impl EventHandler<ClickEvent> for ListItemHandler {
+    fn handle(&mut self, _: &ClickEvent, mut args: EventArgs) {
+        if !args.widget.props().contains(&Property::Selected) {
+            args.widget.add_prop(Property::Selected);
+            let event = ListItemSelected { widget: Some(args.widget.clone()) };
+            self.list_widget.event(event);
+            args.widget.event(ItemSelected);
+            *args.handled = true;
+        }
+    }
+}
+
+component_style!{pub struct List<name="list", style=ListStyle> {
+    layout_settings: LinearLayoutSettings = {
+        let mut layout_settings = LinearLayoutSettings::new(Orientation::Vertical);
+        layout_settings.item_align = ItemAlignment::Fill;
+        layout_settings
+    },
+}}
+
+impl WidgetModifier for List {
+    fn apply(&self, widget: &mut Widget) {
+        widget
+            .add_handler(ListHandler::default())
+            .add_handler(|_: &ClickEvent, args: EventArgs| {
+                args.widget.event(ListItemSelected { widget: None });
+            })
+            .linear_layout(self.layout_settings);
+    }
+}
+
+pub fn add_contents_to_list<C, I, F>(list: &mut Widget, contents: C, build: F)
+    where C: Iterator<Item=I>,
+          F: Fn(I, &mut Widget) -> Widget,
+{
+    for item in contents {
+        let mut widget = build(item, list);
+        widget
+            .set_name("list_item")
+            .add_handler(ListItemHandler::new(list.clone()));
+        list.add_child(widget);
+    }
+}
+
+pub fn default_text_adapter(text: String, list: &mut Widget) -> Widget {
+    ListItemBuilder::new(&text).build(list)
+}
+
+const ITEM_PADDING: f32 = 8.0;
+
+/// Builds a list item: an optional leading icon, a title with an optional subtitle below it, and an
+/// optional trailing widget, ex. a toggle or a count. Items are drawn with the `list_item_rect`
+/// class, which should have styles for the `SELECTED` and `MOUSEOVER` props, and the title,
+/// subtitle and divider with the `list_item_text`, `list_item_subtitle` and `list_item_divider` classes.
+pub struct ListItemBuilder {
+    title: String,
+    subtitle: Option<String>,
+    icon: Option<Widget>,
+    trailing: Option<Widget>,
+    divider: bool,
+    value_style: Option<ValueStyle>,
+}
+
+impl ListItemBuilder {
+    pub fn new(title: &str) -> Self {
+        ListItemBuilder {
+            title: title.to_owned(),
+            subtitle: None,
+            icon: None,
+            trailing: None,
+            divider: false,
+            value_style: None,
+        }
+    }
+    pub fn set_subtitle(&mut self, subtitle: &str) -> &mut Self {
+        self.subtitle = Some(subtitle.to_owned());
+        self
+    }
+    /// Show a widget before the text, ex. an `Image`
+    pub fn set_icon(&mut self, icon: Widget) -> &mut Self {
+        self.icon = Some(icon);
+        self
+    }
+    /// Show a widget at the end of the item
+    pub fn set_trailing(&mut self, trailing: Widget) -> &mut Self {
+        self.trailing = Some(trailing);
+        self
+    }
+    /// Draw a line along the bottom of the item, starting below the text
+    pub fn set_divider(&mut self, divider: bool) -> &mut Self {
+        self.divider = divider;
+        self
+    }
+    /// Style the item by the value it shows, with the style a `ConditionalFormat` gives the value,
+    /// ex. `item.set_value_style(format.style(balance))` in the closure of `add_contents_to_list`.
+    /// The background is drawn over the `list_item_rect` class style, the color and icon apply
+    /// to the title.
+    pub fn set_value_style(&mut self, style: ValueStyle) -> &mut Self {
+        self.value_style = Some(style);
+        self
+    }
+
+    /// Create the item, filling the width of `list`. It still needs to be added to the list, with
+    /// a `ListItemHandler` to be selectable, `add_contents_to_list` does both.
+    pub fn build(&self, list: &Widget) -> Widget {
+        let mut item = Widget::new("list_item");
+        item.set_draw_style(DrawStyle::from_class::<RectStyle>("list_item_rect"))
+            .enable_hover();
+        item.layout().add(constraints![
+            match_width(list),
+            shrink_vertical(),
+        ]);
+
+        let mut title = Widget::new("list_item_title");
+        title.set_draw_style(DrawStyle::from_class::<TextStyle>("list_item_text"));
+        match self.value_style {
+            Some(ref value_style) => {
+                item.set_draw_style(value_style.rect_style());
+                title.set_draw_style(value_style.text_style());
+                StaticTextStyle::from_text(&value_style.decorate(&self.title)).component().apply(&mut title);
+            }
+            None => StaticTextStyle::from_text(&self.title).component().apply(&mut title),
+        }
+        title.layout().add(constraints![
+            bound_top(&item).padding(ITEM_PADDING / 2.0),
+            bound_bottom(&item).padding(ITEM_PADDING / 2.0),
+        ]);
+
+        if let Some(ref icon) = self.icon {
+            let mut icon = icon.clone();
+            icon.layout().add(constraints![
+                align_left(&item).padding(ITEM_PADDING),
+                center_vertical(&item),
+                bound_top(&item).padding(ITEM_PADDING / 2.0),
+                bound_bottom(&item).padding(ITEM_PADDING / 2.0),
+            ]);
+            title.layout().add(to_right_of(&icon).padding(ITEM_PADDING));
+            item.add_child(icon);
+        } else {
+            title.layout().add(align_left(&item).padding(ITEM_PADDING));
+        }
+
+        if let Some(ref trailing) = self.trailing {
+            let mut trailing = trailing.clone();
+            trailing.layout().add(constraints![
+                align_right(&item).padding(ITEM_PADDING),
+                center_vertical(&item),
+                bound_top(&item).padding(ITEM_PADDING / 2.0),
+                bound_bottom(&item).padding(ITEM_PADDING / 2.0),
+                to_right_of(&title).padding(ITEM_PADDING),
+            ]);
+            item.add_child(trailing);
+        }
+
+        if let Some(ref subtitle) = self.subtitle {
+            let mut subtitle_widget = Widget::new("list_item_subtitle");
+            subtitle_widget.set_draw_style(DrawStyle::from_class::<TextStyle>("list_item_subtitle"));
+            StaticTextStyle::from_text(subtitle).component().apply(&mut subtitle_widget);
+            subtitle_widget.layout().add(constraints![
+                below(&title),
+                align_left(&title),
+                bound_bottom(&item).padding(ITEM_PADDING / 2.0),
+            ]);
+            item.add_child(title.clone());
+            item.add_child(subtitle_widget);
+        } else {
+            title.layout().add(center_vertical(&item));
+            item.add_child(title.clone());
+        }
+
+        if self.divider {
+            let mut divider = Widget::new("list_item_divider");
+            divider.set_draw_style(DrawStyle::from_class::<RectStyle>("list_item_divider"));
+            divider.layout().add(constraints![
+                height(1.0),
+                align_bottom(&item),
+                align_left(&title),
+                align_right(&item),
+            ]);
+            item.add_child(divider);
+        }
+        item
+    }
+    /// Create a skeleton of the item, with blocks in place of the icon and text, to show while
+    /// the data for the list is loading. The trailing widget is left out.
+    pub fn build_skeleton(&self, list: &Widget) -> Widget {
+        let mut item = Widget::new("list_item_skeleton");
+        item.layout().add(constraints![
+            match_width(list),
+            shrink_vertical(),
+        ]);
+        let mut title = SkeletonBuilder::text_line(160.0).build();
+        title.layout().add(constraints![
+            bound_top(&item).padding(ITEM_PADDING),
+            bound_bottom(&item).padding(ITEM_PADDING),
+        ]);
+        if self.icon.is_some() {
+            let mut icon = SkeletonBuilder::new().build();
+            let icon_size = if self.subtitle.is_some() { 2.0 * SKELETON_LINE_HEIGHT + ITEM_PADDING / 2.0 } else { SKELETON_LINE_HEIGHT };
+            icon.layout().add(constraints![
+                size(Size::new(icon_size, icon_size)),
+                align_left(&item).padding(ITEM_PADDING),
+                center_vertical(&item),
+            ]);
+            title.layout().add(to_right_of(&icon).padding(ITEM_PADDING));
+            item.add_child(icon);
+        } else {
+            title.layout().add(align_left(&item).padding(ITEM_PADDING));
+        }
+        if self.subtitle.is_some() {
+            let mut subtitle = SkeletonBuilder::text_line(240.0).build();
+            subtitle.layout().add(constraints![
+                below(&title).padding(ITEM_PADDING / 2.0),
+                align_left(&title),
+                bound_bottom(&item).padding(ITEM_PADDING),
+            ]);
+            item.add_child(title);
+            item.add_child(subtitle);
+        } else {
+            title.layout().add(center_vertical(&item));
+            item.add_child(title);
+        }
+        item
+    }
+}
@@ -0,0 +1,103 @@
+//! Includes standard bundled widgets.
+
+use cassowary::Constraint;
+use widget::Widget;
+
+pub mod button;
+pub mod scroll;
+pub mod list;
+pub mod slider;
+pub mod edit_text;
+pub mod image;
+pub mod image_viewer;
+pub mod thumbnail_grid;
+pub mod crop;
+pub mod glcanvas;
+pub mod text;
+pub mod cells;
+pub mod file_browser;
+#[cfg(feature = "charts")]
+pub mod waveform;
+#[cfg(feature = "charts")]
+pub mod timeline;
+#[cfg(feature = "maps")]
+pub mod tile_view;
+#[cfg(feature = "charts")]
+pub mod heatmap;
+#[cfg(feature = "charts")]
+pub mod gantt;
+#[cfg(feature = "charts")]
+pub mod chart;
+#[cfg(feature = "charts")]
+pub mod stat_chart;
+#[cfg(feature = "charts")]
+pub mod sparkline;
+pub mod reorderable;
+pub mod chat;
+pub mod tour;
+pub mod sidebar;
+pub mod figure;
+pub mod code_view;
+pub mod print_preview;
+pub mod tabs;
+pub mod recent_files;
+pub mod placeholder;
+pub mod skeleton;
+pub mod spacer;
+pub mod relative_time;
+
+/// Remove `old` constraints from the widget's layout and add `new` ones in their place
+pub(crate) fn replace_constraints(widget: &mut Widget, old: &mut Vec<Constraint>, new: Vec<Constraint>) {
+    let mut layout = widget.layout();
+    for constraint in old.drain(..) {
+        layout.remove_constraint(constraint);
+    }
+    layout.add(new.clone());
+    *old = new;
+}
+
+pub mod prelude {
+    pub use super::text::{StaticTextStyle, ContrastingText, BackgroundChanged};
+    pub use super::button::{ButtonStyle, ToggleButtonStyle, ToggleEvent};
+    pub use super::edit_text::{EditText, TextUpdated};
+    pub use super::slider::{Slider, SetSliderValue, SliderEvent};
+    pub use super::list::{List, ListItemBuilder, ListItemSelected, ItemSelected, ListItemHandler};
+    pub use super::scroll::{ScrollContainer, ZoomBy, ZoomChanged, ScrollChanged, bind_to_scroll, SnapPoints, SnapStrictness};
+    pub use super::image::Image;
+    pub use super::image_viewer::{ImageViewerBuilder, ImageViewerEvent, ImagePixelHovered, ImageZoomChanged, ImageViewChanged};
+    pub use super::thumbnail_grid::{ThumbnailGridBuilder, ThumbnailGridEvent, ThumbnailsSelected, ThumbnailActivated};
+    pub use super::crop::{CropOverlayBuilder, CropEvent, CropChanged};
+    pub use super::glcanvas::{GLCanvasBuilder, GLCanvasState};
+    pub use super::chat::{ChatViewBuilder, ChatEvent};
+    pub use super::cells::{CellsBuilder, CellRef, CellValue, Sheet, CellAnnounced};
+    pub use super::file_browser::{FileBrowserBuilder, FileActivated, FilesSelected};
+    #[cfg(feature = "charts")]
+    pub use super::waveform::{WaveformBuilder, LevelMeterBuilder};
+    #[cfg(feature = "charts")]
+    pub use super::timeline::{TimelineBuilder, TimelineEvent, Clip, ClipId, ClipSelected, ClipMoved, ClipTrimmed, PlayheadMoved};
+    #[cfg(feature = "charts")]
+    pub use super::gantt::{GanttBuilder, GanttEvent, Task, TaskId, TaskSelected, TaskChanged};
+    #[cfg(feature = "charts")]
+    pub use super::chart::{ChartBuilder, ChartEvent, ChartViewChanged, ChartBrushed, ChartHovered, receive_points,
+                           LegendBuilder, LegendEvent, LegendToggled};
+    #[cfg(feature = "charts")]
+    pub use super::stat_chart::{HistogramBuilder, BoxPlotBuilder, StatChartEvent};
+    #[cfg(feature = "charts")]
+    pub use super::sparkline::{SparklineBuilder, SparklineEvent};
+    #[cfg(feature = "charts")]
+    pub use super::heatmap::{HeatmapBuilder, HeatmapEvent, HeatmapCellHovered};
+    pub use super::reorderable::{ReorderableBuilder, AddItem, ItemMoved};
+    pub use super::tour::{TourBuilder, TourStep, TourEvent, TourStepShown, TourFinished};
+    pub use super::sidebar::{SidebarBuilder, SidebarEdge, SidebarEvent, SidebarChanged};
+    pub use super::figure::FigureBuilder;
+    pub use super::code_view::{CodeViewBuilder, CodeViewEvent, CodeChanged, FoldChanged};
+    pub use super::print_preview::{PrintPreviewBuilder, PrintPreviewEvent, PreviewPageChanged};
+    pub use super::tabs::{TabsBuilder, TabsEvent, TabSelected, TabClosed, TabMoved};
+    pub use super::recent_files::{RecentFilesBuilder, RecentFilesEvent, RecentFileActivated};
+    pub use super::placeholder::{PlaceholderBuilder, ContentState};
+    pub use super::skeleton::{SkeletonBuilder, skeleton_list};
+    pub use super::spacer::Spacer;
+    pub use super::relative_time::{RelativeTimeBuilder, RelativeTimeFormat, SetRelativeTime};
+    #[cfg(feature = "maps")]
+    pub use super::tile_view::{TileViewBuilder, TileProvider, TileData, TileViewEvent, TileViewChanged, lat_lon_to_world, world_to_lat_lon};
+}
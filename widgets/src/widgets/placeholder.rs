@@ -0,0 +1,146 @@
+//! Placeholders shown in place of a widget's content while it has nothing to show, ex. a list
+//! that is empty, still loading, or failed to load.
+//!
+//! The content is wrapped in a container, and the app reports the state of the data behind it
+//! by sending `ContentState` events to the container. While the state isn't `Ready` the content
+//! is hidden and a placeholder for the state is shown over its area. Placeholders are built from
+//! templates each time they're shown, so the same builder can wrap any number of widgets.
+
+use std::rc::Rc;
+
+use prelude::*;
+use draw::prelude::*;
+use widgets::text::StaticTextStyle;
+
+/// Send to the placeholder container when the state of its content changes
+#[derive(Debug, Clone, PartialEq)]
+pub enum ContentState {
+    /// The content is shown
+    Ready,
+    Empty,
+    Loading,
+    /// Loading failed, with a message describing the error
+    Error(String),
+}
+
+type Template = Rc<Fn() -> Widget>;
+type ErrorTemplate = Rc<Fn(&str) -> Widget>;
+
+struct PlaceholderHandler {
+    container: Widget,
+    content: Widget,
+    placeholder: Option<Widget>,
+    state: ContentState,
+    empty: Template,
+    loading: Template,
+    error: ErrorTemplate,
+}
+
+impl PlaceholderHandler {
+    fn set_state(&mut self, state: ContentState) {
+        if let Some(placeholder) = self.placeholder.take() {
+            self.container.remove_child(placeholder);
+        }
+        let placeholder = match state {
+            ContentState::Ready => None,
+            ContentState::Empty => Some((self.empty)()),
+            ContentState::Loading => Some((self.loading)()),
+            ContentState::Error(ref message) => Some((self.error)(message)),
+        };
+        if let Some(mut placeholder) = placeholder {
+            self.content.layout().hide();
+            placeholder.layout().add(constraints![
+                center(&self.container),
+                bound_by(&self.container),
+            ]);
+            self.container.add_child(placeholder.clone());
+            self.placeholder = Some(placeholder);
+        } else {
+            self.content.layout().show();
+        }
+        self.state = state;
+    }
+}
+
+impl EventHandler<ContentState> for PlaceholderHandler {
+    fn handle(&mut self, state: &ContentState, _: EventArgs) {
+        if *state != self.state {
+            self.set_state(state.clone());
+        }
+    }
+}
+
+fn message(text: &str, color: Color) -> Widget {
+    let mut widget = Widget::from_modifier_style(StaticTextStyle::from_style(style!(TextStyle {
+        text: text.to_owned(),
+        text_color: color,
+        align: Align::Middle,
+    })));
+    widget.set_name("placeholder_message");
+    widget
+}
+
+/// Builds a container showing placeholders in place of its content
+pub struct PlaceholderBuilder {
+    state: ContentState,
+    empty: Template,
+    loading: Template,
+    error: ErrorTemplate,
+}
+
+impl Default for PlaceholderBuilder {
+    fn default() -> Self {
+        PlaceholderBuilder {
+            state: ContentState::Ready,
+            empty: Rc::new(|| message("Nothing here yet", GRAY_50)),
+            loading: Rc::new(|| message("Loading…", GRAY_50)),
+            error: Rc::new(|error| message(error, RED)),
+        }
+    }
+}
+
+impl PlaceholderBuilder {
+    pub fn new() -> Self {
+        Self::default()
+    }
+    /// The state the container starts in, `Ready` by default
+    pub fn set_state(&mut self, state: ContentState) -> &mut Self {
+        self.state = state;
+        self
+    }
+    /// Build the placeholder shown while the content is empty
+    pub fn set_empty<F: Fn() -> Widget + 'static>(&mut self, template: F) -> &mut Self {
+        self.empty = Rc::new(template);
+        self
+    }
+    /// Build the placeholder shown while the content is loading, ex. a `skeleton_list`
+    pub fn set_loading<F: Fn() -> Widget + 'static>(&mut self, template: F) -> &mut Self {
+        self.loading = Rc::new(template);
+        self
+    }
+    /// Build the placeholder shown when loading failed, from the error message
+    pub fn set_error<F: Fn(&str) -> Widget + 'static>(&mut self, template: F) -> &mut Self {
+        self.error = Rc::new(template);
+        self
+    }
+
+    /// Wrap `content` in a container that fills the same area. Constrain the container rather
+    /// than the content, and send it `ContentState` events.
+    pub fn build(&self, mut content: Widget) -> Widget {
+        let mut container = Widget::new("placeholder_container");
+        content.layout().add(match_layout(&container));
+        container.add_child(content.clone());
+        let mut handler = PlaceholderHandler {
+            container: container.clone(),
+            content: content,
+            placeholder: None,
+            state: ContentState::Ready,
+            empty: Rc::clone(&self.empty),
+            loading: Rc::clone(&self.loading),
+            error: Rc::clone(&self.error),
+        };
+        handler.set_state(self.state.clone());
+        container.add_handler(handler);
+        container
+    }
+}
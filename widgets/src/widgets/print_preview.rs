@@ -0,0 +1,363 @@
+//! A print preview, showing a text document split into pages, one page at a time.
+//!
+//! A toolbar steps between pages and zooms the page in and out, the page can also be zoomed with
+//! the mouse wheel while holding ctrl. A column of thumbnails beside the page jumps to the page
+//! clicked. Pages are laid out with `TextState::paginate`, so the preview shows the same lines on
+//! each page as an app printing or exporting the text page by page with the same style.
+
+use prelude::*;
+use draw::prelude::*;
+use widgets::button::ButtonStyle;
+use widgets::edit_text::TextUpdated;
+use widgets::scroll::{ScrollContainer, ZoomBy, ZoomChanged};
+use widgets::text::StaticTextStyle;
+
+/// Factor the zoom buttons zoom the page by
+const ZOOM_STEP: f32 = 1.25;
+const MIN_ZOOM: f32 = 0.25;
+const MAX_ZOOM: f32 = 4.0;
+const THUMBNAIL_WIDTH: f32 = 100.0;
+/// Space around the page, and between thumbnails
+const PAGE_GAP: f32 = 20.0;
+const TOOLBAR_PADDING: f32 = 8.0;
+
+/// Send to the print preview widget
+#[derive(Debug, Clone)]
+pub enum PrintPreviewEvent {
+    /// Replace the document, showing its first page
+    SetText(String),
+    /// Show a page, the first page is 0
+    GoToPage(usize),
+    NextPage,
+    PreviousPage,
+    ZoomIn,
+    ZoomOut,
+}
+
+/// Sent to the print preview widget when a different page is shown, or the number of pages changes
+#[derive(Debug, Copy, Clone)]
+pub struct PreviewPageChanged {
+    pub page: usize,
+    pub page_count: usize,
+}
+
+/// Layout of the pages, shared by the page shown and the thumbnails
+#[derive(Debug, Clone)]
+struct PageSetup {
+    style: TextState,
+    size: Size,
+    margin: f32,
+}
+
+impl PageSetup {
+    fn paginate(&self, text: &str) -> Vec<String> {
+        let mut state = self.style.clone();
+        state.text = text.to_owned();
+        let area = Size::new(self.size.width - self.margin * 2.0, self.size.height - self.margin * 2.0);
+        let mut pages = state.paginate(area);
+        if pages.is_empty() {
+            pages.push(String::new());
+        }
+        pages
+    }
+
+    /// Text state drawing `page` at `scale` times its size
+    fn page_text(&self, page: &str, scale: f32) -> TextState {
+        let mut state = self.style.clone();
+        state.text = page.to_owned();
+        state.font_size *= scale;
+        // lines are already broken where they wrap at full size
+        state.wrap = Wrap::NoWrap;
+        state
+    }
+
+    /// Build a page widget at `scale` times its size, along with the widget drawing its text
+    fn build_page(&self, page: &str, scale: f32) -> (Widget, Widget) {
+        let mut page_widget = Widget::new("print_preview_page");
+        page_widget.set_draw_state(RectState {
+            background_color: WHITE,
+            corner_radius: None,
+            border: Some((1.0, GRAY_60)),
+        });
+        page_widget.layout().add(size(self.size * scale));
+        let mut text = Widget::new("print_preview_page_text");
+        text.set_draw_state(self.page_text(page, scale));
+        text.layout().add(constraints![
+            match_layout(&page_widget).padding(self.margin * scale),
+        ]);
+        page_widget.add_child(text.clone());
+        (page_widget, text)
+    }
+}
+
+multi_event!{impl EventHandler<PrintPreviewInput> for PrintPreviewHandler {
+    PrintPreviewEvent => print_preview_event,
+    ZoomChanged => zoom_changed,
+}}
+
+struct PrintPreviewHandler {
+    preview: Widget,
+    setup: PageSetup,
+    pages: Vec<String>,
+    page: usize,
+    page_text: Widget,
+    page_scroll: Widget,
+    page_label: Widget,
+    zoom_label: Widget,
+    thumbnail_list: Widget,
+    thumbnails: Vec<Widget>,
+}
+
+impl PrintPreviewHandler {
+    fn print_preview_event(&mut self, event: &PrintPreviewEvent, _: EventArgs) {
+        match *event {
+            PrintPreviewEvent::SetText(ref text) => {
+                self.pages = self.setup.paginate(text);
+                self.build_thumbnails();
+                self.show_page(0);
+            }
+            PrintPreviewEvent::GoToPage(page) => self.show_page(page),
+            PrintPreviewEvent::NextPage => {
+                let page = self.page + 1;
+                self.show_page(page);
+            }
+            PrintPreviewEvent::PreviousPage => {
+                let page = self.page.saturating_sub(1);
+                self.show_page(page);
+            }
+            PrintPreviewEvent::ZoomIn => self.zoom_by(ZOOM_STEP),
+            PrintPreviewEvent::ZoomOut => self.zoom_by(1.0 / ZOOM_STEP),
+        }
+    }
+
+    fn zoom_changed(&mut self, event: &ZoomChanged, _: EventArgs) {
+        let &ZoomChanged(zoom) = event;
+        self.zoom_label.event(TextUpdated(format!("{}%", (zoom * 100.0).round())));
+    }
+
+    fn zoom_by(&mut self, factor: f32) {
+        let center = self.page_scroll.bounds().center();
+        self.page_scroll.event(ZoomBy { factor: factor, center: center });
+    }
+
+    fn show_page(&mut self, page: usize) {
+        let page = page.min(self.pages.len() - 1);
+        if let Some(thumbnail) = self.thumbnails.get_mut(self.page) {
+            thumbnail.update(|state: &mut RectState| state.border = Some((1.0, GRAY_60)));
+        }
+        self.page = page;
+        if let Some(thumbnail) = self.thumbnails.get_mut(page) {
+            thumbnail.update(|state: &mut RectState| state.border = Some((3.0, BLUE_HIGHLIGHT)));
+        }
+        let text = self.pages[page].clone();
+        self.page_text.update(|state: &mut TextState| state.text = text);
+        self.page_label.event(TextUpdated(format!("Page {} of {}", page + 1, self.pages.len())));
+        self.preview.event(PreviewPageChanged {
+            page: page,
+            page_count: self.pages.len(),
+        });
+    }
+
+    fn build_thumbnails(&mut self) {
+        for thumbnail in self.thumbnails.drain(..) {
+            self.thumbnail_list.remove_child(thumbnail);
+        }
+        let scale = THUMBNAIL_WIDTH / self.setup.size.width;
+        for (index, page) in self.pages.iter().enumerate() {
+            let (mut thumbnail, _) = self.setup.build_page(page, scale);
+            let preview = self.preview.clone();
+            thumbnail.add_handler(move |_: &ClickEvent, args: EventArgs| {
+                preview.event(PrintPreviewEvent::GoToPage(index));
+                *args.handled = true;
+            });
+            self.thumbnail_list.add_child(thumbnail.clone());
+            self.thumbnails.push(thumbnail);
+        }
+    }
+}
+
+/// Builds a print preview of a text document
+pub struct PrintPreviewBuilder {
+    text: String,
+    setup: PageSetup,
+    thumbnails: bool,
+}
+
+impl PrintPreviewBuilder {
+    pub fn new(text: &str) -> Self {
+        PrintPreviewBuilder {
+            text: text.to_owned(),
+            setup: PageSetup {
+                style: style!(TextStyle {
+                    font_size: 12.0,
+                }).component(),
+                // A4 at 72 points per inch
+                size: Size::new(595.0, 842.0),
+                margin: 56.0,
+            },
+            thumbnails: true,
+        }
+    }
+    /// Set the style of the document text, the text itself is ignored
+    pub fn set_text_style(&mut self, style: TextStyle) -> &mut Self {
+        self.setup.style = style.component();
+        self
+    }
+    /// Set the size of a page, A4 by default
+    pub fn set_page_size(&mut self, page_size: Size) -> &mut Self {
+        self.setup.size = page_size;
+        self
+    }
+    /// Set the space between the edges of a page and the text
+    pub fn set_margin(&mut self, margin: f32) -> &mut Self {
+        self.setup.margin = margin;
+        self
+    }
+    /// Show a column of page thumbnails, on by default
+    pub fn set_thumbnails(&mut self, thumbnails: bool) -> &mut Self {
+        self.thumbnails = thumbnails;
+        self
+    }
+
+    pub fn build(&self) -> Widget {
+        let mut preview = Widget::new("print_preview");
+        preview.set_draw_style(style!(RectStyle {
+            background_color: GRAY_80,
+        }));
+
+        let mut toolbar = Widget::new("print_preview_toolbar");
+        toolbar.set_draw_style(style!(RectStyle {
+            background_color: GRAY_90,
+        }));
+        toolbar.layout().add(constraints![
+            align_top(&preview),
+            align_left(&preview),
+            align_right(&preview),
+        ]);
+        let mut previous_button = Widget::from_modifier_style(ButtonStyle::from_text("Previous"));
+        let mut page_label = Widget::from_modifier_style(StaticTextStyle::from_style(style!(TextStyle {
+            font_size: 18.0,
+        })));
+        let mut next_button = Widget::from_modifier_style(ButtonStyle::from_text("Next"));
+        let mut zoom_out_button = Widget::from_modifier_style(ButtonStyle::from_text("-"));
+        let mut zoom_label = Widget::from_modifier_style(StaticTextStyle::from_style(style!(TextStyle {
+            text: "100%".to_owned(),
+            font_size: 18.0,
+        })));
+        let mut zoom_in_button = Widget::from_modifier_style(ButtonStyle::from_text("+"));
+        previous_button.layout().add(constraints![
+            align_left(&toolbar).padding(TOOLBAR_PADDING),
+            align_top(&toolbar).padding(TOOLBAR_PADDING),
+            align_bottom(&toolbar).padding(TOOLBAR_PADDING),
+        ]);
+        page_label.layout().add(constraints![
+            to_right_of(&previous_button).padding(TOOLBAR_PADDING),
+            center_vertical(&previous_button),
+        ]);
+        next_button.layout().add(constraints![
+            to_right_of(&page_label).padding(TOOLBAR_PADDING),
+            align_top(&previous_button),
+        ]);
+        zoom_in_button.layout().add(constraints![
+            align_right(&toolbar).padding(TOOLBAR_PADDING),
+            align_top(&previous_button),
+        ]);
+        zoom_label.layout().add(constraints![
+            to_left_of(&zoom_in_button).padding(TOOLBAR_PADDING),
+            center_vertical(&previous_button),
+        ]);
+        zoom_out_button.layout().add(constraints![
+            to_left_of(&zoom_label).padding(TOOLBAR_PADDING),
+            to_right_of(&next_button).padding(TOOLBAR_PADDING),
+            align_top(&previous_button),
+        ]);
+        let buttons = vec![
+            (&mut previous_button, PrintPreviewEvent::PreviousPage),
+            (&mut next_button, PrintPreviewEvent::NextPage),
+            (&mut zoom_out_button, PrintPreviewEvent::ZoomOut),
+            (&mut zoom_in_button, PrintPreviewEvent::ZoomIn),
+        ];
+        for (button, event) in buttons {
+            let preview = preview.clone();
+            button.add_handler(move |_: &ClickEvent, _: EventArgs| {
+                preview.event(event.clone());
+            });
+        }
+        toolbar
+            .add_child(previous_button)
+            .add_child(page_label.clone())
+            .add_child(next_button)
+            .add_child(zoom_out_button)
+            .add_child(zoom_label.clone())
+            .add_child(zoom_in_button);
+
+        let mut thumbnail_list = Widget::new("print_preview_thumbnails");
+        let mut layout_settings = LinearLayoutSettings::new(Orientation::Vertical);
+        layout_settings.item_align = ItemAlignment::Center;
+        layout_settings.padding = PAGE_GAP;
+        thumbnail_list.linear_layout(layout_settings);
+        thumbnail_list.layout().add(width(THUMBNAIL_WIDTH + PAGE_GAP * 2.0));
+        let mut thumbnail_scroll = ScrollContainer::default();
+        thumbnail_scroll.add_scrollbar();
+        thumbnail_scroll.add_content(thumbnail_list.clone());
+        let mut thumbnail_scroll = Widget::from_modifier(thumbnail_scroll);
+        thumbnail_scroll.layout().add(constraints![
+            below(&toolbar),
+            align_left(&preview),
+            align_bottom(&preview),
+            width(THUMBNAIL_WIDTH + PAGE_GAP * 2.0),
+        ]);
+        if !self.thumbnails {
+            thumbnail_scroll.layout().hide();
+        }
+
+        let mut page_area = Widget::new("print_preview_page_area");
+        let (mut page, page_text) = self.setup.build_page("", 1.0);
+        page.layout().add(constraints![
+            align_top(&page_area).padding(PAGE_GAP),
+            align_left(&page_area).padding(PAGE_GAP),
+        ]);
+        page_area.layout().add(size(self.setup.size + Size::new(PAGE_GAP * 2.0, PAGE_GAP * 2.0)));
+        page_area.add_child(page);
+        let mut page_scroll = ScrollContainer::default();
+        page_scroll.add_scrollbar();
+        page_scroll.enable_zoom(MIN_ZOOM, MAX_ZOOM);
+        page_scroll.enable_pan();
+        page_scroll.add_content(page_area);
+        let mut page_scroll = Widget::from_modifier(page_scroll);
+        page_scroll.layout().add(constraints![
+            below(&toolbar),
+            align_right(&preview),
+            align_bottom(&preview),
+        ]);
+        if self.thumbnails {
+            page_scroll.layout().add(to_right_of(&thumbnail_scroll));
+        } else {
+            page_scroll.layout().add(align_left(&preview));
+        }
+        forward_event!(ZoomChanged: |event, _| *event; page_scroll -> preview);
+
+        preview
+            .add_child(toolbar)
+            .add_child(thumbnail_scroll)
+            .add_child(page_scroll.clone());
+
+        let mut handler = PrintPreviewHandler {
+            preview: preview.clone(),
+            setup: self.setup.clone(),
+            pages: self.setup.paginate(&self.text),
+            page: 0,
+            page_text: page_text,
+            page_scroll: page_scroll,
+            page_label: page_label,
+            zoom_label: zoom_label,
+            thumbnail_list: thumbnail_list,
+            thumbnails: Vec::new(),
+        };
+        handler.build_thumbnails();
+        handler.show_page(0);
+        preview.add_handler(handler);
+        PrintPreviewHandler::add_adapters(&mut preview);
+        preview
+    }
+}
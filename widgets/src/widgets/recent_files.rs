@@ -0,0 +1,125 @@
+//! An "Open Recent" list, showing the paths in a `RecentFiles` list with an entry to clear it.
+//!
+//! Clicking a path sends `RecentFileActivated` to the recent files widget, the app opens the file
+//! and, if it can't be opened, sends `RecentFilesEvent::Remove`. With a `Document`,
+//! `add_document_handlers` adds each document opened or saved to the list.
+
+use std::path::PathBuf;
+
+use prelude::*;
+use draw::prelude::*;
+use widgets::text::StaticTextStyle;
+
+/// Send to the recent files widget
+#[derive(Debug, Clone)]
+pub enum RecentFilesEvent {
+    Add(PathBuf),
+    Remove(PathBuf),
+    Clear,
+}
+
+/// Sent to the recent files widget when a path is clicked
+#[derive(Debug, Clone)]
+pub struct RecentFileActivated(pub PathBuf);
+
+struct RecentFilesHandler {
+    recent_files: RecentFiles,
+}
+
+impl RecentFilesHandler {
+    fn rebuild(&self, widget: &mut Widget) {
+        for child in widget.children() {
+            widget.remove_child(child);
+        }
+        for path in self.recent_files.paths() {
+            let mut row = row(&entry_name(path), widget);
+            let (recent_files, path) = (widget.clone(), path.clone());
+            row.add_handler(move |_: &ClickEvent, _: EventArgs| {
+                recent_files.event(RecentFileActivated(path.clone()));
+            });
+            widget.add_child(row);
+        }
+        if self.recent_files.paths().is_empty() {
+            let mut empty = Widget::from_modifier_style(StaticTextStyle::from_style(style!(TextStyle {
+                text: "No Recent Files".to_owned(),
+                text_color: GRAY_50,
+            })));
+            empty.layout().add(align_left(widget).padding(5.0));
+            widget.add_child(empty);
+        } else {
+            let mut clear = row("Clear Menu", widget);
+            let recent_files = widget.clone();
+            clear.add_handler(move |_: &ClickEvent, _: EventArgs| {
+                recent_files.event(RecentFilesEvent::Clear);
+            });
+            widget.add_child(clear);
+        }
+    }
+}
+
+impl EventHandler<RecentFilesEvent> for RecentFilesHandler {
+    fn handle(&mut self, event: &RecentFilesEvent, mut args: EventArgs) {
+        // the list is kept in memory if it can't be saved, the next change tries again
+        let _ = match *event {
+            RecentFilesEvent::Add(ref path) => self.recent_files.add(path),
+            RecentFilesEvent::Remove(ref path) => self.recent_files.remove(path),
+            RecentFilesEvent::Clear => self.recent_files.clear(),
+        };
+        self.rebuild(&mut args.widget);
+    }
+}
+
+/// The file name followed by the directory it's in, so files with the same name can be told apart
+fn entry_name(path: &PathBuf) -> String {
+    match (path.file_name(), path.parent()) {
+        (Some(name), Some(dir)) => format!("{} — {}", name.to_string_lossy(), dir.display()),
+        _ => path.to_string_lossy().into_owned(),
+    }
+}
+
+fn row(text: &str, list: &Widget) -> Widget {
+    let mut row = Widget::new("recent_file");
+    row.set_draw_style(DrawStyle::from_class::<RectStyle>("list_item_rect"))
+        .enable_hover();
+    row.layout().add(match_width(list));
+    let mut text_widget = Widget::new("recent_file_name");
+    text_widget.set_draw_style(DrawStyle::from_class::<TextStyle>("list_item_text"));
+    StaticTextStyle::from_style(style!(TextStyle {
+        text: text.to_owned(),
+        wrap: Wrap::NoWrap,
+    })).component().apply(&mut text_widget);
+    text_widget.layout().add(align_left(&row).padding(5.0));
+    row.add_child(text_widget);
+    row
+}
+
+/// Creates a recent files widget
+#[derive(Debug, Copy, Clone)]
+pub struct RecentFilesBuilder;
+
+impl RecentFilesBuilder {
+    /// Creates a list of the paths in `recent_files`, kept up to date as `RecentFilesEvent`s
+    /// are received
+    pub fn new(recent_files: RecentFiles) -> Widget {
+        let mut widget = Widget::new("recent_files");
+        let mut layout_settings = LinearLayoutSettings::new(Orientation::Vertical);
+        layout_settings.item_align = ItemAlignment::Fill;
+        widget.linear_layout(layout_settings);
+        let handler = RecentFilesHandler { recent_files: recent_files };
+        handler.rebuild(&mut widget);
+        widget.add_handler(handler);
+        widget
+    }
+}
+
+/// Add the path of each `Document` opened or saved to the recent files widget
+pub fn add_document_handlers(app: &mut App, recent_files: &Widget) {
+    let widget = recent_files.clone();
+    app.add_handler(move |event: &DocumentOpened, _: EventArgs| {
+        widget.event(RecentFilesEvent::Add(event.0.clone()));
+    });
+    let widget = recent_files.clone();
+    app.add_handler(move |event: &DocumentSaved, _: EventArgs| {
+        widget.event(RecentFilesEvent::Add(event.0.clone()));
+    });
+}
@@ -0,0 +1,394 @@
+//! A label showing how long ago, or how far in the future, a time is, ex. "3 minutes ago" in a feed.
+//!
+//! The label updates itself when its text would change, so a label for a time a few seconds ago
+//! updates after the first minute, and one for last week only once a day. Times are read from
+//! `clock`, so labels follow the virtual clock in deterministic mode. Each label checks whether
+//! it's due on every frame, and a single timer thread shared by every label wakes the UI when one
+//! is due while no frames are drawn. The timer isn't used in deterministic mode, where time only
+//! moves with frames.
+//!
+//! The text is in the language of the locale from the `format` module, numbers are formatted by
+//! it too. English, German, French and Spanish are supported, other languages get English.
+
+use std::sync::mpsc::{self, Sender, RecvTimeoutError};
+use std::thread;
+use std::time::{Duration, Instant, SystemTime};
+
+use clock;
+use format;
+use prelude::*;
+use draw::prelude::*;
+use widgets::text::StaticTextStyle;
+use widgets::edit_text::TextUpdated;
+
+const SECOND: u64 = 1;
+const MINUTE: u64 = 60;
+const HOUR: u64 = 60 * MINUTE;
+const DAY: u64 = 24 * HOUR;
+const WEEK: u64 = 7 * DAY;
+/// The average month in the Gregorian calendar
+const MONTH: u64 = 2_629_746;
+const YEAR: u64 = 12 * MONTH;
+/// Times closer than this to now are shown as now
+const NOW_SECS: u64 = 45;
+
+/// Units from smallest to largest, relative times skip seconds and durations stop at days
+const UNITS: [u64; 7] = [SECOND, MINUTE, HOUR, DAY, WEEK, MONTH, YEAR];
+
+#[derive(Debug)]
+struct Words {
+    now: &'static str,
+    /// Written before and after a time in the past
+    past: (&'static str, &'static str),
+    /// Written before and after a time in the future
+    future: (&'static str, &'static str),
+    /// Singular and plural of each unit, as written in a relative time
+    units: [(&'static str, &'static str); 7],
+    /// Singular and plural of each unit, as written in a duration
+    durations: [(&'static str, &'static str); 7],
+}
+
+static ENGLISH: Words = Words {
+    now: "just now",
+    past: ("", " ago"),
+    future: ("in ", ""),
+    units: [("second", "seconds"), ("minute", "minutes"), ("hour", "hours"), ("day", "days"),
+            ("week", "weeks"), ("month", "months"), ("year", "years")],
+    durations: [("second", "seconds"), ("minute", "minutes"), ("hour", "hours"), ("day", "days"),
+                ("week", "weeks"), ("month", "months"), ("year", "years")],
+};
+
+static GERMAN: Words = Words {
+    now: "gerade eben",
+    past: ("vor ", ""),
+    future: ("in ", ""),
+    units: [("Sekunde", "Sekunden"), ("Minute", "Minuten"), ("Stunde", "Stunden"), ("Tag", "Tagen"),
+            ("Woche", "Wochen"), ("Monat", "Monaten"), ("Jahr", "Jahren")],
+    durations: [("Sekunde", "Sekunden"), ("Minute", "Minuten"), ("Stunde", "Stunden"), ("Tag", "Tage"),
+                ("Woche", "Wochen"), ("Monat", "Monate"), ("Jahr", "Jahre")],
+};
+
+static FRENCH: Words = Words {
+    now: "à l'instant",
+    past: ("il y a ", ""),
+    future: ("dans ", ""),
+    units: [("seconde", "secondes"), ("minute", "minutes"), ("heure", "heures"), ("jour", "jours"),
+            ("semaine", "semaines"), ("mois", "mois"), ("an", "ans")],
+    durations: [("seconde", "secondes"), ("minute", "minutes"), ("heure", "heures"), ("jour", "jours"),
+                ("semaine", "semaines"), ("mois", "mois"), ("an", "ans")],
+};
+
+static SPANISH: Words = Words {
+    now: "ahora mismo",
+    past: ("hace ", ""),
+    future: ("dentro de ", ""),
+    units: [("segundo", "segundos"), ("minuto", "minutos"), ("hora", "horas"), ("día", "días"),
+            ("semana", "semanas"), ("mes", "meses"), ("año", "años")],
+    durations: [("segundo", "segundos"), ("minuto", "minutos"), ("hora", "horas"), ("día", "días"),
+                ("semana", "semanas"), ("mes", "meses"), ("año", "años")],
+};
+
+/// Formats times relative to now, and durations, in a language
+#[derive(Debug, Copy, Clone)]
+pub struct RelativeTimeFormat {
+    words: &'static Words,
+}
+
+impl Default for RelativeTimeFormat {
+    fn default() -> Self {
+        RelativeTimeFormat::for_language(&format::locale().language)
+    }
+}
+
+impl RelativeTimeFormat {
+    /// Format in the language of the current locale
+    pub fn new() -> Self {
+        Self::default()
+    }
+    /// Format in `language`, ex. `de`
+    pub fn for_language(language: &str) -> Self {
+        let words = match language {
+            "de" => &GERMAN,
+            "fr" => &FRENCH,
+            "es" => &SPANISH,
+            _ => &ENGLISH,
+        };
+        RelativeTimeFormat { words: words }
+    }
+
+    fn count(&self, count: u64, unit: usize, durations: bool) -> String {
+        let (singular, plural) = if durations { self.words.durations[unit] } else { self.words.units[unit] };
+        format!("{}\u{a0}{}", format::number(count as f64), if count == 1 { singular } else { plural })
+    }
+
+    /// `time` relative to `now`, ex. "3 minutes ago" or "in 2 days"
+    pub fn relative(&self, time: SystemTime, now: SystemTime) -> String {
+        let (secs, past) = signed_secs(time, now);
+        if secs < NOW_SECS {
+            return self.words.now.to_owned();
+        }
+        let unit = relative_unit(secs);
+        let count = (secs / UNITS[unit]).max(1);
+        let (before, after) = if past { self.words.past } else { self.words.future };
+        format!("{}{}{}", before, self.count(count, unit, false), after)
+    }
+
+    /// A duration in its largest unit and the next one down, ex. "1 hour 5 minutes"
+    pub fn duration(&self, duration: Duration) -> String {
+        let secs = duration.as_secs();
+        let unit = (0..4).rev().find(|&unit| secs >= UNITS[unit]).unwrap_or(0);
+        let count = secs / UNITS[unit];
+        let mut text = self.count(count, unit, true);
+        if unit > 0 {
+            let rest = secs % UNITS[unit] / UNITS[unit - 1];
+            if rest > 0 {
+                text.push(' ');
+                text.push_str(&self.count(rest, unit - 1, true));
+            }
+        }
+        text
+    }
+}
+
+/// Seconds between `time` and `now`, and whether `time` is in the past
+fn signed_secs(time: SystemTime, now: SystemTime) -> (u64, bool) {
+    match now.duration_since(time) {
+        Ok(elapsed) => (elapsed.as_secs(), true),
+        Err(err) => (err.duration().as_secs(), false),
+    }
+}
+
+fn relative_unit(secs: u64) -> usize {
+    (1..UNITS.len()).rev().find(|&unit| secs >= UNITS[unit]).unwrap_or(1)
+}
+
+/// How long until the relative text of `time` changes
+fn next_change(time: SystemTime, now: SystemTime) -> Duration {
+    let (secs, past) = signed_secs(time, now);
+    let wait = if past {
+        if secs < NOW_SECS {
+            NOW_SECS - secs
+        } else {
+            let unit = UNITS[relative_unit(secs)];
+            unit - secs % unit
+        }
+    } else if secs < NOW_SECS {
+        secs + NOW_SECS
+    } else {
+        secs.saturating_sub(NOW_SECS).min(secs % UNITS[relative_unit(secs)]) + 1
+    };
+    Duration::from_secs(wait.max(1))
+}
+
+/// Sent to a relative time label by the shared timer when its text may be due to change
+#[derive(Debug, Copy, Clone)]
+struct RelativeTimeTick;
+
+thread_local! {
+    static TIMER: Sender<(Instant, WidgetId)> = start_timer();
+}
+
+/// Schedule a tick for `widget` at `at`, replacing any tick it already has scheduled
+fn schedule(widget: WidgetId, at: Instant) {
+    TIMER.with(|timer| {
+        timer.send((at, widget)).ok();
+    });
+}
+
+fn start_timer() -> Sender<(Instant, WidgetId)> {
+    let (sender, receiver) = mpsc::channel();
+    thread::spawn(move || {
+        let mut due: Vec<(Instant, WidgetId)> = Vec::new();
+        loop {
+            let received = match due.iter().map(|&(at, _)| at).min() {
+                Some(next) => {
+                    let now = Instant::now();
+                    receiver.recv_timeout(if next > now { next - now } else { Duration::from_secs(0) })
+                }
+                None => receiver.recv().map_err(|_| RecvTimeoutError::Disconnected),
+            };
+            match received {
+                Ok((at, widget)) => {
+                    due.retain(|&(_, scheduled)| scheduled != widget);
+                    due.push((at, widget));
+                }
+                Err(RecvTimeoutError::Timeout) => (),
+                Err(RecvTimeoutError::Disconnected) => return,
+            }
+            let now = Instant::now();
+            due.retain(|&(at, widget)| {
+                if at <= now {
+                    EventSender.widget_event(widget, RelativeTimeTick);
+                }
+                at > now
+            });
+        }
+    });
+    sender
+}
+
+/// Change the time shown by a relative time label, send to the label widget
+#[derive(Debug, Copy, Clone)]
+pub struct SetRelativeTime(pub SystemTime);
+
+multi_event!{impl EventHandler<RelativeTimeEvent> for RelativeTimeHandler {
+    SetRelativeTime => set_time,
+    RelativeTimeTick => tick,
+    FrameEvent => frame_event,
+}}
+
+struct RelativeTimeHandler {
+    time: SystemTime,
+    format: RelativeTimeFormat,
+    /// When the text is next due to change, by `clock::now`
+    due: Instant,
+}
+
+impl RelativeTimeHandler {
+    fn update(&mut self, widget: &Widget) {
+        widget.event(TextUpdated(self.format.relative(self.time, clock::system_now())));
+        self.due = due(widget.id(), self.time);
+    }
+    fn update_if_due(&mut self, widget: &Widget) {
+        if clock::now() >= self.due {
+            self.update(widget);
+        }
+    }
+    fn set_time(&mut self, event: &SetRelativeTime, args: EventArgs) {
+        self.time = event.0;
+        self.update(&args.widget);
+    }
+    fn tick(&mut self, _: &RelativeTimeTick, args: EventArgs) {
+        self.update_if_due(&args.widget);
+    }
+    fn frame_event(&mut self, _: &FrameEvent, args: EventArgs) {
+        self.update_if_due(&args.widget);
+    }
+}
+
+/// When the text of a label for `time` is next due to change, waking the UI then unless in
+/// deterministic mode
+fn due(widget: WidgetId, time: SystemTime) -> Instant {
+    let due = clock::now() + next_change(time, clock::system_now());
+    if !clock::is_deterministic() {
+        schedule(widget, due);
+    }
+    due
+}
+
+/// Builds a label showing a time relative to now
+pub struct RelativeTimeBuilder {
+    time: SystemTime,
+    format: Option<RelativeTimeFormat>,
+    text_style: TextStyle,
+}
+
+impl RelativeTimeBuilder {
+    pub fn new(time: SystemTime) -> Self {
+        RelativeTimeBuilder {
+            time: time,
+            format: None,
+            text_style: TextStyle::default(),
+        }
+    }
+    /// Use `format` rather than the language of the current locale
+    pub fn set_format(&mut self, format: RelativeTimeFormat) -> &mut Self {
+        self.format = Some(format);
+        self
+    }
+    pub fn set_text_style(&mut self, style: TextStyle) -> &mut Self {
+        self.text_style = style;
+        self
+    }
+
+    pub fn build(&self) -> Widget {
+        let mut widget = Widget::new("relative_time");
+        let format = self.format.unwrap_or_default();
+        let mut text_style = self.text_style.clone();
+        text_style.text = Some(format.relative(self.time, clock::system_now()));
+        StaticTextStyle::from_style(text_style).component().apply(&mut widget);
+        let due = due(widget.id(), self.time);
+        RelativeTimeHandler::add_adapters(&mut widget);
+        widget.add_handler(RelativeTimeHandler {
+            time: self.time,
+            format: format,
+            due: due,
+        });
+        widget.receive_frame_events();
+        widget
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+    use super::*;
+
+    fn now() -> SystemTime {
+        UNIX_EPOCH + Duration::from_secs(1_500_000_000)
+    }
+    fn ago(secs: u64) -> SystemTime {
+        now() - Duration::from_secs(secs)
+    }
+    fn ahead(secs: u64) -> SystemTime {
+        now() + Duration::from_secs(secs)
+    }
+    fn relative(time: SystemTime) -> String {
+        RelativeTimeFormat::for_language("en").relative(time, now()).replace('\u{a0}', " ")
+    }
+    fn duration(secs: u64) -> String {
+        RelativeTimeFormat::for_language("en").duration(Duration::from_secs(secs)).replace('\u{a0}', " ")
+    }
+
+    #[test]
+    fn relative_boundaries() {
+        assert_eq!(relative(ago(0)), "just now");
+        assert_eq!(relative(ago(44)), "just now");
+        assert_eq!(relative(ago(45)), "1 minute ago");
+        assert_eq!(relative(ago(119)), "1 minute ago");
+        assert_eq!(relative(ago(120)), "2 minutes ago");
+        assert_eq!(relative(ago(HOUR - 1)), "59 minutes ago");
+        assert_eq!(relative(ago(HOUR)), "1 hour ago");
+        assert_eq!(relative(ago(DAY - 1)), "23 hours ago");
+        assert_eq!(relative(ago(DAY)), "1 day ago");
+        assert_eq!(relative(ago(WEEK)), "1 week ago");
+    }
+
+    #[test]
+    fn relative_future() {
+        assert_eq!(relative(ahead(30)), "just now");
+        assert_eq!(relative(ahead(90)), "in 1 minute");
+        assert_eq!(relative(ahead(2 * HOUR)), "in 2 hours");
+        assert_eq!(relative(ahead(3 * DAY)), "in 3 days");
+    }
+
+    #[test]
+    fn duration_boundaries() {
+        assert_eq!(duration(59), "59 seconds");
+        assert_eq!(duration(60), "1 minute");
+        assert_eq!(duration(HOUR + 61), "1 hour 1 minute");
+        assert_eq!(duration(DAY), "1 day");
+        assert_eq!(duration(DAY + 2 * HOUR), "1 day 2 hours");
+    }
+
+    #[test]
+    fn next_change_in_past() {
+        // "just now" until 45 seconds have passed
+        assert_eq!(next_change(ago(10), now()), Duration::from_secs(35));
+        // minutes change on the minute
+        assert_eq!(next_change(ago(90), now()), Duration::from_secs(30));
+        assert_eq!(next_change(ago(HOUR), now()), Duration::from_secs(HOUR));
+        assert_eq!(next_change(ago(DAY + HOUR), now()), Duration::from_secs(DAY - HOUR));
+    }
+
+    #[test]
+    fn next_change_in_future() {
+        // "just now" until 45 seconds after the time
+        assert_eq!(next_change(ahead(30), now()), Duration::from_secs(75));
+        // an hour and a half ahead changes to minutes with an hour to go
+        assert_eq!(next_change(ahead(90 * MINUTE), now()), Duration::from_secs(30 * MINUTE + 1));
+        // never less than a second
+        assert_eq!(next_change(ahead(2 * HOUR), now()), Duration::from_secs(1));
+    }
+}
@@ -0,0 +1,429 @@
+//! Columns of items that can be reordered by dragging, within a column or between columns.
+//!
+//! While an item is dragged it follows the mouse, and a placeholder shows where it will be dropped.
+//! Items slide into their new positions rather than jumping. The items are children of the
+//! container rather than of the columns, so they can be dragged freely between columns.
+//! Moves are reported to the container widget as `ItemMoved` events.
+
+use std::time::Instant;
+
+use clock;
+use prelude::*;
+use draw::prelude::*;
+use widgets::replace_constraints;
+
+/// Fraction of the remaining distance an item slides each second, the higher the snappier
+const SLIDE_SPEED: f32 = 15.0;
+
+/// Add an item at the bottom of a column, send to the container widget.
+#[derive(Clone)]
+pub struct AddItem(pub usize, pub Widget);
+
+/// Sent to the container widget when an item is dropped in a new position.
+#[derive(Debug, Copy, Clone)]
+pub struct ItemMoved {
+    pub item: WidgetId,
+    pub from_column: usize,
+    pub from_index: usize,
+    pub to_column: usize,
+    pub to_index: usize,
+}
+
+#[derive(Debug, Copy, Clone)]
+enum ReorderInput {
+    Drag(WidgetId, DragEvent),
+    Resized(WidgetId),
+}
+
+struct Slot {
+    widget: Widget,
+    height: f32,
+    /// Offset from the slot position, decays to zero so the item slides into place
+    offset: Vector,
+    constraints: Vec<Constraint>,
+}
+
+impl Slot {
+    fn new(widget: Widget) -> Self {
+        Slot {
+            widget: widget,
+            height: 0.0,
+            offset: Vector::zero(),
+            constraints: Vec::new(),
+        }
+    }
+}
+
+struct Dragging {
+    slot: Slot,
+    from: (usize, usize),
+    /// Mouse position relative to the top left of the item
+    grab: Vector,
+}
+
+struct ReorderHandler {
+    container: Widget,
+    columns: Vec<Widget>,
+    slots: Vec<Vec<Slot>>,
+    /// Shown in the slot a dragged item will be dropped in
+    placeholder: Widget,
+    placeholder_constraints: Vec<Constraint>,
+    dragging: Option<Dragging>,
+    header_height: f32,
+    padding: f32,
+    spacing: f32,
+    last_frame: Instant,
+}
+
+multi_event!{impl EventHandler<ReorderEvent> for ReorderHandler {
+    AddItem => add_item,
+    ReorderInput => reorder_input,
+    FrameEvent => frame_event,
+}}
+
+impl ReorderHandler {
+    fn slot_positions(&self, column: usize) -> Vec<f32> {
+        let mut top = self.header_height + self.padding;
+        self.slots[column].iter().map(|slot| {
+            let position = top;
+            top += slot.height + self.spacing;
+            position
+        }).collect()
+    }
+
+    fn layout_column(&mut self, column: usize) {
+        let positions = self.slot_positions(column);
+        let column_vars = self.columns[column].layout_vars();
+        for (slot, position) in self.slots[column].iter_mut().zip(positions) {
+            let vars = slot.widget.layout_vars();
+            let constraints = vec![
+                vars.left - column_vars.left | EQ(REQUIRED) | self.padding + slot.offset.x,
+                vars.width - column_vars.width | EQ(REQUIRED) | -2.0 * self.padding,
+                vars.top - column_vars.top | EQ(REQUIRED) | position + slot.offset.y,
+            ];
+            replace_constraints(&mut slot.widget, &mut slot.constraints, constraints);
+        }
+    }
+
+    /// Rearrange a column, starting each item from where it is now so it slides to its new position
+    fn rearrange_column<F: FnOnce(&mut Vec<Slot>)>(&mut self, column: usize, f: F) {
+        let before: Vec<(WidgetId, f32)> = self.slot_positions(column).into_iter()
+            .zip(self.slots[column].iter())
+            .map(|(position, slot)| (slot.widget.id(), position + slot.offset.y))
+            .collect();
+        f(&mut self.slots[column]);
+        let after = self.slot_positions(column);
+        for (slot, position) in self.slots[column].iter_mut().zip(after) {
+            if let Some(&(_, previous)) = before.iter().find(|&&(id, _)| id == slot.widget.id()) {
+                slot.offset.y = previous - position;
+            }
+        }
+        self.layout_column(column);
+    }
+
+    fn find(&self, id: WidgetId) -> Option<(usize, usize)> {
+        for (column, slots) in self.slots.iter().enumerate() {
+            if let Some(index) = slots.iter().position(|slot| slot.widget.id() == id) {
+                return Some((column, index));
+            }
+        }
+        None
+    }
+
+    /// Column and index an item dragged to `point` would be dropped at
+    fn drop_position(&self, point: Point) -> (usize, usize) {
+        let distance = |widget: &Widget| {
+            let bounds = widget.bounds();
+            (bounds.left() - point.x).max(point.x - bounds.right()).max(0.0)
+        };
+        let column = self.columns.iter().enumerate()
+            .min_by(|&(_, a), &(_, b)| distance(a).partial_cmp(&distance(b)).unwrap())
+            .map(|(column, _)| column).unwrap();
+        let placeholder_id = self.placeholder.id();
+        let index = self.slots[column].iter()
+            .filter(|slot| slot.widget.id() != placeholder_id)
+            .filter(|slot| slot.widget.bounds().center().y < point.y)
+            .count();
+        (column, index)
+    }
+
+    fn move_placeholder(&mut self, to: (usize, usize)) {
+        let current = self.find(self.placeholder.id());
+        if current == Some(to) {
+            return;
+        }
+        let mut placeholder = None;
+        if let Some((column, index)) = current {
+            self.rearrange_column(column, |slots| placeholder = Some(slots.remove(index)));
+        }
+        let placeholder = match placeholder {
+            Some(placeholder) => placeholder,
+            None => return,
+        };
+        let (column, index) = to;
+        self.rearrange_column(column, move |slots| slots.insert(index, placeholder));
+    }
+
+    fn add_item(&mut self, event: &AddItem, _: EventArgs) {
+        let AddItem(column, ref item) = *event;
+        let mut item = item.clone();
+        let container = self.container.clone();
+        let id = item.id();
+        item.add_handler(move |event: &DragEvent, _: EventArgs| {
+            container.event(ReorderInput::Drag(id, *event));
+        });
+        let container = self.container.clone();
+        item.add_handler(move |_: &LayoutUpdated, _: EventArgs| {
+            container.event(ReorderInput::Resized(id));
+        });
+        item.make_draggable();
+        self.container.add_child(item.clone());
+        self.slots[column].push(Slot::new(item));
+        self.layout_column(column);
+    }
+
+    fn reorder_input(&mut self, event: &ReorderInput, args: EventArgs) {
+        match *event {
+            ReorderInput::Resized(id) => {
+                if let Some((column, index)) = self.find(id) {
+                    let height = self.slots[column][index].widget.bounds().height();
+                    if (height - self.slots[column][index].height).abs() > 0.5 {
+                        self.slots[column][index].height = height;
+                        self.layout_column(column);
+                    }
+                }
+            }
+            ReorderInput::Drag(id, ref drag) => self.drag(id, drag, args),
+        }
+    }
+
+    fn drag(&mut self, id: WidgetId, event: &DragEvent, args: EventArgs) {
+        match event.state {
+            DragState::Start => {
+                let (column, index) = match self.find(id) {
+                    Some(position) => position,
+                    None => return,
+                };
+                let mut slot = self.slots[column].remove(index);
+                slot.offset = Vector::zero();
+                // draw the dragged item above the others
+                let last = self.container.children().len();
+                self.container.move_child(&slot.widget, last);
+
+                // the placeholder takes the item's place, the same size
+                let vars = self.placeholder.layout_vars();
+                replace_constraints(&mut self.placeholder, &mut self.placeholder_constraints, vec![
+                    vars.height | EQ(REQUIRED) | slot.height,
+                ]);
+                self.placeholder.layout().show();
+                let mut placeholder = Slot::new(self.placeholder.clone());
+                placeholder.height = slot.height;
+                self.slots[column].insert(index, placeholder);
+                self.layout_column(column);
+
+                self.dragging = Some(Dragging {
+                    grab: event.position - slot.widget.bounds().origin,
+                    slot: slot,
+                    from: (column, index),
+                });
+                self.drag_moved(event.position);
+            }
+            DragState::Moved => {
+                if self.dragging.is_some() {
+                    self.drag_moved(event.position);
+                    let to = self.drop_position(event.position);
+                    self.move_placeholder(to);
+                }
+            }
+            DragState::End => {
+                let dragging = match self.dragging.take() {
+                    Some(dragging) => dragging,
+                    None => return,
+                };
+                let (column, index) = self.find(self.placeholder.id()).unwrap();
+                let mut placeholder = self.slots[column].remove(index);
+                replace_constraints(&mut placeholder.widget, &mut placeholder.constraints, Vec::new());
+                self.placeholder.layout().hide();
+
+                // start the dropped item where it was released, so it slides into place
+                let bounds = dragging.slot.widget.bounds();
+                self.slots[column].insert(index, dragging.slot);
+                let position = self.slot_positions(column)[index];
+                let column_bounds = self.columns[column].bounds();
+                self.slots[column][index].offset = Vector::new(
+                    bounds.left() - column_bounds.left() - self.padding,
+                    bounds.top() - column_bounds.top() - position);
+                self.layout_column(column);
+                if (column, index) != dragging.from {
+                    args.widget.event(ItemMoved {
+                        item: id,
+                        from_column: dragging.from.0,
+                        from_index: dragging.from.1,
+                        to_column: column,
+                        to_index: index,
+                    });
+                }
+                args.ui.redraw();
+            }
+        }
+    }
+
+    /// Move the dragged item to follow the mouse
+    fn drag_moved(&mut self, position: Point) {
+        if let Some(ref mut dragging) = self.dragging {
+            let container = self.container.layout_vars();
+            let column_width = self.columns[dragging.from.0].bounds().width();
+            let vars = dragging.slot.widget.layout_vars();
+            let top_left = position - dragging.grab;
+            let constraints = vec![
+                vars.left - container.left | EQ(REQUIRED) | top_left.x - self.container.bounds().left(),
+                vars.top - container.top | EQ(REQUIRED) | top_left.y - self.container.bounds().top(),
+                vars.width | EQ(REQUIRED) | column_width - 2.0 * self.padding,
+            ];
+            replace_constraints(&mut dragging.slot.widget, &mut dragging.slot.constraints, constraints);
+        }
+    }
+
+    fn frame_event(&mut self, _: &FrameEvent, args: EventArgs) {
+        let now = clock::now();
+        let elapsed = now.duration_since(self.last_frame);
+        self.last_frame = now;
+        // frames stop while nothing changes, so the first frame of an animation can follow a long gap
+        let elapsed = (elapsed.as_secs() as f32 + elapsed.subsec_nanos() as f32 / 1_000_000_000.0).min(1.0 / 30.0);
+        let decay = (1.0 - elapsed * SLIDE_SPEED).max(0.0);
+        let mut animating = false;
+        for column in 0..self.columns.len() {
+            let mut moved = false;
+            for slot in &mut self.slots[column] {
+                if slot.offset != Vector::zero() {
+                    slot.offset = slot.offset * decay;
+                    if slot.offset.x.abs() < 0.5 && slot.offset.y.abs() < 0.5 {
+                        slot.offset = Vector::zero();
+                    }
+                    moved = true;
+                }
+            }
+            if moved {
+                self.layout_column(column);
+                animating = true;
+            }
+        }
+        if animating {
+            args.ui.redraw();
+        }
+    }
+}
+
+/// Builds a container of columns whose items can be reordered by dragging.
+/// The column widgets provide the background and any header, items are stacked inside them
+/// below the header.
+pub struct ReorderableBuilder {
+    columns: Vec<Widget>,
+    items: Vec<(usize, Widget)>,
+    column_width: f32,
+    column_spacing: f32,
+    header_height: f32,
+    padding: f32,
+    spacing: f32,
+}
+
+impl Default for ReorderableBuilder {
+    fn default() -> Self {
+        ReorderableBuilder {
+            columns: Vec::new(),
+            items: Vec::new(),
+            column_width: 200.0,
+            column_spacing: 10.0,
+            header_height: 0.0,
+            padding: 10.0,
+            spacing: 5.0,
+        }
+    }
+}
+
+impl ReorderableBuilder {
+    pub fn new() -> Self {
+        Self::default()
+    }
+    /// Add a column, returns the index of the column
+    pub fn add_column(&mut self, column: Widget) -> usize {
+        self.columns.push(column);
+        self.columns.len() - 1
+    }
+    /// Add an item at the bottom of a column
+    pub fn add_item(&mut self, column: usize, item: Widget) -> &mut Self {
+        assert!(column < self.columns.len(), "item added to a column that doesn't exist");
+        self.items.push((column, item));
+        self
+    }
+    pub fn set_column_width(&mut self, column_width: f32) -> &mut Self {
+        self.column_width = column_width;
+        self
+    }
+    pub fn set_column_spacing(&mut self, column_spacing: f32) -> &mut Self {
+        self.column_spacing = column_spacing;
+        self
+    }
+    /// Set the space at the top of each column left for its header
+    pub fn set_header_height(&mut self, header_height: f32) -> &mut Self {
+        self.header_height = header_height;
+        self
+    }
+    /// Set the space between the header of a column and its first item, and either side of the items
+    pub fn set_padding(&mut self, padding: f32) -> &mut Self {
+        self.padding = padding;
+        self
+    }
+    /// Set the space between items
+    pub fn set_spacing(&mut self, spacing: f32) -> &mut Self {
+        self.spacing = spacing;
+        self
+    }
+
+    /// Create the container widget
+    pub fn build(&self) -> Widget {
+        let mut widget = Widget::new("reorderable");
+        widget.layout().no_container();
+        for (index, column) in self.columns.iter().enumerate() {
+            let mut column = column.clone();
+            column.layout().add(constraints![
+                align_top(&widget),
+                align_bottom(&widget),
+                align_left(&widget).padding(index as f32 * (self.column_width + self.column_spacing)),
+                width(self.column_width),
+            ]);
+            widget.add_child(column);
+        }
+        if let Some(last) = self.columns.last() {
+            widget.layout().add(align_right(last));
+        }
+
+        let mut placeholder = Widget::new("reorder_placeholder");
+        placeholder.set_draw_style(style!(RectStyle {
+            background_color: TRANSPARENT,
+            corner_radius: Some(3.0),
+            border: Some((1.0, GRAY_50)),
+        }));
+        placeholder.layout().hide();
+        widget.add_child(placeholder.clone());
+
+        widget.add_handler(ReorderHandler {
+            container: widget.clone(),
+            columns: self.columns.clone(),
+            slots: self.columns.iter().map(|_| Vec::new()).collect(),
+            placeholder: placeholder,
+            placeholder_constraints: Vec::new(),
+            dragging: None,
+            header_height: self.header_height,
+            padding: self.padding,
+            spacing: self.spacing,
+            last_frame: clock::now(),
+        });
+        ReorderHandler::add_adapters(&mut widget);
+        widget.receive_frame_events();
+        for &(column, ref item) in &self.items {
+            widget.event(AddItem(column, item.clone()));
+        }
+        widget
+    }
+}
@@ -0,0 +1,756 @@
+use std::time::{Duration, Instant};
+
+use clock;
+use prelude::*;
+use draw::prelude::*;
+use widgets::slider::{SliderStyle, SliderEvent, SetSliderValue, Orientation};
+
+/// Factor the content is zoomed by for each line scrolled with the mouse wheel while holding ctrl
+const ZOOM_STEP: f32 = 1.1;
+/// Time since the last scroll before the content settles on a snap point, in milliseconds
+const SNAP_DELAY: u64 = 150;
+/// Fraction of the remaining distance to a snap point the content moves each second
+const SNAP_SPEED: f32 = 15.0;
+
+/// Where the content of a scroll container can settle along one axis, once it stops scrolling.
+#[derive(Debug, Clone, PartialEq)]
+pub enum SnapPoints {
+    /// The leading edge of each child of the content, and the end of the content
+    Children,
+    /// Offsets from the start of the content, unaffected by zoom
+    Offsets(Vec<f32>),
+}
+
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum SnapStrictness {
+    /// Always settle on the nearest snap point
+    Mandatory,
+    /// Only settle on a snap point within this distance
+    Proximity(f32),
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct ScrollSnap {
+    pub points: SnapPoints,
+    pub strictness: SnapStrictness,
+}
+
+component_style!{pub struct ScrollContainer<name="scroll", style=ScrollStyle> {
+    content: Option<Widget> = None,
+    has_scrollbars: bool = false,
+    zoom_range: Option<(f32, f32)> = None,
+    snap_x: Option<ScrollSnap> = None,
+    snap_y: Option<ScrollSnap> = None,
+    line_step: f32 = 40.0,
+    page_step: Option<f32> = None,
+    pan: bool = false,
+}}
+
+impl ScrollContainer {
+    /// Set the scrollable content
+    pub fn add_content(&mut self, widget: Widget) -> &mut Self {
+        self.content = Some(widget);
+        self
+    }
+    /// Display vertical and horizontal scrollbars
+    pub fn add_scrollbar(&mut self) -> &mut Self {
+        self.has_scrollbars = true;
+        self
+    }
+    /// Let the content be zoomed between `min` and `max` times its size, with the mouse wheel
+    /// while holding ctrl, or by sending `ZoomBy` to the scroll widget.
+    /// Zooming only scales how the content is drawn, so is best suited to content that is viewed
+    /// rather than interacted with, since the content's widgets are still hit tested at their unscaled size.
+    pub fn enable_zoom(&mut self, min: f32, max: f32) -> &mut Self {
+        self.zoom_range = Some((min, max));
+        self
+    }
+    /// Settle on the nearest horizontal snap point after scrolling, ex. for a paged gallery or carousel
+    pub fn snap_horizontal(&mut self, points: SnapPoints, strictness: SnapStrictness) -> &mut Self {
+        self.snap_x = Some(ScrollSnap { points: points, strictness: strictness });
+        self
+    }
+    /// Settle on the nearest vertical snap point after scrolling
+    pub fn snap_vertical(&mut self, points: SnapPoints, strictness: SnapStrictness) -> &mut Self {
+        self.snap_y = Some(ScrollSnap { points: points, strictness: strictness });
+        self
+    }
+    /// Set the distance scrolled by the arrow keys, and by page up/page down and space.
+    /// By default a page is the size of the scroll area, less one line.
+    pub fn set_scroll_steps(&mut self, line_step: f32, page_step: Option<f32>) -> &mut Self {
+        self.line_step = line_step;
+        self.page_step = page_step;
+        self
+    }
+    /// Scroll by dragging the content with the middle mouse button, or the left button while holding space,
+    /// as in document and canvas viewers. Space no longer scrolls by a page.
+    pub fn enable_pan(&mut self) -> &mut Self {
+        self.pan = true;
+        self
+    }
+}
+
+/// Zoom the content of a scroll widget by `factor`, keeping `center` in place. Send to the scroll widget.
+/// Does nothing unless zooming has been enabled with `ScrollContainer::enable_zoom`.
+/// Useful for zoom buttons, or pinch gestures, which aren't reported by the windowing library.
+#[derive(Debug, Copy, Clone)]
+pub struct ZoomBy {
+    pub factor: f32,
+    pub center: Point,
+}
+
+/// Sent to the scroll widget when the content is zoomed, with the new zoom factor
+#[derive(Debug, Copy, Clone)]
+pub struct ZoomChanged(pub f32);
+
+/// Sent to the scroll widget when the content scrolls, or the scrollable area changes size.
+#[derive(Debug, Copy, Clone)]
+pub struct ScrollChanged {
+    /// How far the content is scrolled from the top left
+    pub offset: Vector,
+    /// The furthest the content can be scrolled, zero along an axis that doesn't scroll
+    pub extent: Size,
+}
+
+impl ScrollChanged {
+    /// The fraction of the scrollable area scrolled along each axis, from 0.0 to 1.0,
+    /// ex. for a reading progress indicator. Zero along an axis that doesn't scroll.
+    pub fn progress(&self) -> Vector {
+        let fraction = |offset: f32, extent: f32| if extent > 0.0 { offset / extent } else { 0.0 };
+        Vector::new(fraction(self.offset.x, self.extent.width), fraction(self.offset.y, self.extent.height))
+    }
+}
+
+/// Call `f` with `target` whenever the content of `scroll_widget` scrolls, to bind properties of
+/// other widgets to the scroll position, ex. a parallax header, or a toolbar that shrinks as the content scrolls.
+/// `target` is only weakly referenced, the binding does nothing once it has been dropped.
+pub fn bind_to_scroll<F>(scroll_widget: &mut Widget, target: &Widget, mut f: F)
+    where F: FnMut(&ScrollChanged, &mut Widget) + 'static
+{
+    let target = target.downgrade();
+    scroll_widget.add_handler(move |event: &ScrollChanged, _: EventArgs| {
+        if let Some(mut target) = target.upgrade() {
+            f(event, &mut target);
+        }
+    });
+}
+
+impl WidgetModifier for ScrollContainer {
+    fn apply(&self, widget: &mut Widget) {
+        let mut content_holder = Widget::new("content_holder");
+        content_holder.layout().no_container();
+        let mut content = self.content.clone().expect("Scroll bar has no content");
+        forward_event!(LayoutUpdated: |_, args| ContentLayoutUpdated(args.widget.bounds()); content -> content_holder);
+        content_holder.layout().add(constraints![
+            match_layout(widget).strength(STRONG)
+        ]);
+        {
+            let content_holder = &content_holder.layout().vars;
+            content.layout().add(constraints![
+                LAYOUT.left | LE(REQUIRED) | content_holder.left,
+                LAYOUT.top | LE(REQUIRED) | content_holder.top,
+                LAYOUT.left | EQ(WEAK) | content_holder.left,
+                LAYOUT.top | EQ(WEAK) | content_holder.top,
+                LAYOUT.right | GE(STRONG) | content_holder.right,
+                LAYOUT.bottom | GE(STRONG) | content_holder.bottom,
+            ]);
+        }
+        let mut scrollbars = if self.has_scrollbars {
+            let mut scrollbar_h = Widget::from_modifier_style_class(SliderStyle::default(), "scrollbar_slider");
+            scrollbar_h.set_name("scrollbar_h");
+            scrollbar_h.layout().add(constraints![
+                align_bottom(widget),
+                align_left(widget),
+                align_below(&content_holder),
+            ]);
+            let mut scrollbar_v = Widget::from_modifier_style_class(
+                style!(SliderStyle { orientation: Orientation::Vertical, }), "scrollbar_slider");
+            scrollbar_v.set_name("scrollbar_v");
+            scrollbar_v.layout().add(constraints![
+                align_right(widget),
+                align_top(widget),
+                align_to_right_of(&content_holder),
+            ]);
+            let mut corner = Widget::new("corner");
+            corner.set_draw_style(style!(RectStyle { background_color: GRAY_70, }));
+            corner.layout().add(constraints![
+                align_bottom(widget),
+                align_right(widget),
+                align_to_right_of(&scrollbar_h),
+                align_below(&scrollbar_v),
+                match_height(&scrollbar_h),
+                match_width(&scrollbar_v),
+            ]);
+
+            forward_event!(SliderEvent: |event, _| ScrollBarMoved::Horizontal(event.value, event.dragging); scrollbar_h -> content_holder);
+            forward_event!(SliderEvent: |event, _| ScrollBarMoved::Vertical(event.value, event.dragging); scrollbar_v -> content_holder);
+
+            Some((corner, scrollbar_h, scrollbar_v))
+        } else {
+            None
+        };
+
+        if self.zoom_range.is_some() {
+            content_holder.add_filter(ScaleFilter::default());
+            forward_event!(ZoomBy: |event, _| *event; widget -> content_holder);
+        }
+
+        if self.snap_x.is_some() || self.snap_y.is_some() {
+            content_holder.receive_frame_events();
+        }
+
+        // scroll with keys the focused widget, or one of its ancestors in the content, doesn't handle
+        widget.make_focusable_container();
+        let content_holder_ref = content_holder.clone();
+        let pan = self.pan;
+        widget.add_handler(move |event: &UnhandledKeyboardInput, args: EventArgs| {
+            let input = event.0;
+            if pan && input.virtual_keycode == Some(glutin::VirtualKeyCode::Space) {
+                content_holder_ref.event(PanKey(input.state == glutin::ElementState::Pressed));
+                *args.handled = true;
+            } else if let Some(key_scroll) = KeyScroll::from_input(input) {
+                content_holder_ref.event(key_scroll);
+                *args.handled = true;
+            }
+        });
+
+        let mut scroll_parent_handler = ScrollParent::new(&mut content, widget, self);
+        if let Some((ref mut corner, ref mut scrollbar_h, ref mut scrollbar_v)) = scrollbars {
+            scroll_parent_handler.scrollbars = Some(ScrollBars::new(scrollbar_h.clone(), scrollbar_v.clone(), corner.clone()));
+        }
+        content_holder.add_handler(scroll_parent_handler);
+        ScrollParent::add_adapters(&mut content_holder);
+
+        content_holder.add_child(content);
+        if scrollbars.is_some() {
+            content_holder.layout().add(constraints![
+                align_left(widget),
+                align_top(widget),
+            ]);
+        } else {
+            content_holder.layout().add(constraints![
+                match_layout(widget),
+            ]);
+        }
+        widget.add_child(content_holder);
+        if let Some((corner, scrollbar_h, scrollbar_v)) = scrollbars {
+            widget.add_child(corner);
+            widget.add_child(scrollbar_h);
+            widget.add_child(scrollbar_v);
+        }
+    }
+}
+
+#[allow(dead_code)]
+struct ScrollBars {
+    scrollbar_h: Widget,
+    scrollbar_v: Widget,
+    corner: Widget,
+    h_handle: Widget,
+    v_handle: Widget,
+}
+impl ScrollBars {
+    fn new(scrollbar_h: Widget, scrollbar_v: Widget, corner: Widget) -> Self {
+        let h_handle = scrollbar_h.child("slider_handle").unwrap();
+        let v_handle = scrollbar_v.child("slider_handle").unwrap();
+        ScrollBars {
+            scrollbar_h: scrollbar_h,
+            scrollbar_v: scrollbar_v,
+            corner: corner,
+            h_handle: h_handle,
+            v_handle: v_handle,
+        }
+    }
+}
+
+#[derive(Clone)]
+struct ContentLayoutUpdated(Rect);
+
+/// Space pressed or released, while panning is enabled
+#[derive(Debug, Copy, Clone)]
+struct PanKey(bool);
+
+#[derive(Debug, Copy, Clone)]
+enum KeyScroll {
+    /// Scroll by a number of lines along each axis
+    Lines(Vector),
+    /// Scroll down by a number of pages
+    Pages(f32),
+    Start,
+    End,
+}
+
+impl KeyScroll {
+    fn from_input(input: glutin::KeyboardInput) -> Option<Self> {
+        use glutin::VirtualKeyCode::*;
+        if input.state != glutin::ElementState::Pressed {
+            return None;
+        }
+        match input.virtual_keycode {
+            Some(Up) => Some(KeyScroll::Lines(Vector::new(0.0, -1.0))),
+            Some(Down) => Some(KeyScroll::Lines(Vector::new(0.0, 1.0))),
+            Some(Left) => Some(KeyScroll::Lines(Vector::new(-1.0, 0.0))),
+            Some(Right) => Some(KeyScroll::Lines(Vector::new(1.0, 0.0))),
+            Some(PageUp) => Some(KeyScroll::Pages(-1.0)),
+            Some(PageDown) => Some(KeyScroll::Pages(1.0)),
+            Some(Space) => Some(KeyScroll::Pages(if input.modifiers.shift { -1.0 } else { 1.0 })),
+            Some(Home) => Some(KeyScroll::Start),
+            Some(End) => Some(KeyScroll::End),
+            _ => None,
+        }
+    }
+}
+#[derive(Clone)]
+enum ScrollBarMoved {
+    /// The new value of the scrollbar, and whether it's still being dragged
+    Horizontal(f32, bool),
+    Vertical(f32, bool),
+}
+
+multi_event!{impl EventHandler<ScrollParentEvent> for ScrollParent {
+    LayoutUpdated => container_layout_updated,
+    ContentLayoutUpdated => content_layout_updated,
+    WidgetMouseWheel => widget_mouse_wheel,
+    WidgetMouseMoved => widget_mouse_moved,
+    ScrollBarMoved => scrollbar_moved,
+    ZoomBy => zoom_by,
+    FrameEvent => frame_event,
+    KeyScroll => key_scroll,
+    PanKey => pan_key,
+    WidgetMouseButton => mouse_button,
+    DragEvent => drag,
+    ScrollIntoView => scroll_into_view,
+}}
+
+struct ScrollParent {
+    scrollable: Widget,
+    scroll_widget: Widget,
+    content_rect: Rect,
+    container_rect: Rect,
+    width_ratio: f32,
+    height_ratio: f32,
+    /// Size of the zoomed content minus the size of the container
+    scrollable_area: Size,
+    /// Position of the zoomed content relative to the container
+    offset: Vector,
+    zoom: f32,
+    zoom_range: Option<(f32, f32)>,
+    cursor: Point,
+    snap_x: Option<ScrollSnap>,
+    snap_y: Option<ScrollSnap>,
+    /// Set when the user scrolls, the content snaps once they have stopped for `SNAP_DELAY`
+    snap_pending: bool,
+    /// The scroll offset the content is moving to
+    snap_target: Option<Vector>,
+    last_scroll: Instant,
+    last_frame: Instant,
+    line_step: f32,
+    page_step: Option<f32>,
+    pan: bool,
+    pan_key_down: bool,
+    /// Scroll offset when panning started, while panning
+    pan_start: Option<Vector>,
+    pub scrollbars: Option<ScrollBars>,
+}
+
+impl ScrollParent {
+    fn new(scrollable: &mut Widget, scroll_widget: &Widget, settings: &ScrollContainer) -> Self {
+        ScrollParent {
+            scrollable: scrollable.clone(),
+            scroll_widget: scroll_widget.clone(),
+            content_rect: Rect::zero(),
+            container_rect: Rect::zero(),
+            width_ratio: 0.0,
+            height_ratio: 0.0,
+            scrollable_area: Size::zero(),
+            offset: Vector::zero(),
+            zoom: 1.0,
+            zoom_range: settings.zoom_range,
+            cursor: Point::zero(),
+            snap_x: settings.snap_x.clone(),
+            snap_y: settings.snap_y.clone(),
+            snap_pending: false,
+            snap_target: None,
+            last_scroll: clock::now(),
+            last_frame: clock::now(),
+            line_step: settings.line_step,
+            page_step: settings.page_step,
+            pan: settings.pan,
+            pan_key_down: false,
+            pan_start: None,
+            scrollbars: None,
+        }
+    }
+
+    fn container_layout_updated(&mut self, _: &LayoutUpdated, mut args: EventArgs) {
+        self.container_rect = args.widget.bounds();
+        if self.zoom_range.is_some() {
+            let origin = self.container_rect.origin;
+            args.widget.update_filter(|filter: &mut ScaleFilter| filter.origin = origin);
+        }
+        self.update_bounds();
+    }
+
+    fn content_layout_updated(&mut self, event: &ContentLayoutUpdated, _: EventArgs) {
+        self.content_rect = event.0;
+        self.update_bounds();
+    }
+
+    fn update_bounds(&mut self) {
+        let scrollable_area = self.content_rect.size * self.zoom - self.container_rect.size;
+        let content_offset = (self.content_rect.origin - self.container_rect.origin) * self.zoom;
+        if content_offset != self.offset || scrollable_area != self.scrollable_area {
+            self.offset = content_offset;
+            self.scrollable_area = scrollable_area;
+            if self.scrollable_area.width > 0.0 {
+                self.move_slider_x();
+            }
+            if self.scrollable_area.height > 0.0 {
+                self.move_slider_y();
+            }
+            self.scroll_changed();
+        }
+        self.update_scrollbar_sizes();
+    }
+
+    fn update_scrollbar_sizes(&mut self) {
+        let width_ratio = self.container_rect.width() / (self.content_rect.width() * self.zoom);
+        let height_ratio = self.container_rect.height() / (self.content_rect.height() * self.zoom);
+        if let Some(ref mut scrollbars) = self.scrollbars {
+
+            // update handle sizes
+            let mut visibility_updated = false;
+
+            if width_ratio.is_finite() && (width_ratio - self.width_ratio).abs() > ::std::f32::EPSILON {
+                let width = self.container_rect.width() * width_ratio;
+                let mut layout = scrollbars.h_handle.layout();
+                layout.edit_width().set(width);
+                let mut layout = scrollbars.scrollbar_h.layout();
+                let scrollbar_hidden = layout.hidden;
+                let hide_scrollbar = width_ratio >= 1.0 && !scrollbar_hidden;
+                let show_scrollbar = width_ratio < 1.0 && scrollbar_hidden;
+                if hide_scrollbar | show_scrollbar {
+                    visibility_updated |= true;
+                    if hide_scrollbar {
+                        layout.hide();
+                    }
+                    if show_scrollbar {
+                        layout.show();
+                    }
+                }
+            }
+
+            if height_ratio.is_finite() && (height_ratio - self.height_ratio).abs() > ::std::f32::EPSILON {
+                let height = self.container_rect.height() * height_ratio;
+                let mut layout = scrollbars.v_handle.layout();
+                layout.edit_height().set(height);
+                let scrollbar_hidden = layout.hidden;
+                let hide_scrollbar = height_ratio >= 1.0 && !scrollbar_hidden;
+                let show_scrollbar = height_ratio < 1.0 && scrollbar_hidden;
+                if hide_scrollbar | show_scrollbar {
+                    visibility_updated |= true;
+                    let mut layout = scrollbars.scrollbar_v.layout();
+                    if hide_scrollbar {
+                        layout.hide();
+                    }
+                    if show_scrollbar {
+                        layout.show();
+                    }
+                }
+            }
+
+            if visibility_updated {
+                if !scrollbars.scrollbar_h.layout().hidden && !scrollbars.scrollbar_v.layout().hidden {
+                    scrollbars.corner.layout().show();
+                } else {
+                    scrollbars.corner.layout().hide();
+                }
+            }
+        }
+        self.width_ratio = width_ratio;
+        self.height_ratio = height_ratio;
+    }
+
+    fn widget_mouse_wheel(&mut self, event: &WidgetMouseWheel, args: EventArgs) {
+        let &WidgetMouseWheel(mouse_wheel, modifiers) = event;
+        if modifiers.ctrl && self.zoom_range.is_some() {
+            let lines = match mouse_wheel {
+                glutin::MouseScrollDelta::LineDelta(_, y) => y,
+                glutin::MouseScrollDelta::PixelDelta(glutin::dpi::LogicalPosition{y, ..}) => y as f32 / 13.0,
+            };
+            let center = self.cursor;
+            self.zoom_by(&ZoomBy { factor: ZOOM_STEP.powf(lines), center: center }, args);
+            return;
+        }
+        let scroll = get_scroll(mouse_wheel);
+        let offset = self.offset + scroll;
+        self.scroll_to(offset);
+        self.user_scrolled();
+    }
+
+    fn key_scroll(&mut self, event: &KeyScroll, _: EventArgs) {
+        let offset = match *event {
+            KeyScroll::Lines(lines) => self.offset - lines * self.line_step,
+            KeyScroll::Pages(pages) => {
+                let page = self.page_step.unwrap_or(self.container_rect.height() - self.line_step);
+                self.offset - Vector::new(0.0, pages * page)
+            }
+            KeyScroll::Start => Vector::new(self.offset.x, 0.0),
+            KeyScroll::End => Vector::new(self.offset.x, -self.scrollable_area.height),
+        };
+        self.scroll_to(offset);
+        self.user_scrolled();
+    }
+
+    fn pan_key(&mut self, event: &PanKey, args: EventArgs) {
+        let PanKey(down) = *event;
+        if down != self.pan_key_down {
+            self.pan_key_down = down;
+            if self.pan_start.is_none() {
+                args.ui.set_cursor(if down { glutin::MouseCursor::Grab } else { glutin::MouseCursor::Default });
+            }
+        }
+    }
+
+    fn mouse_button(&mut self, event: &WidgetMouseButton, args: EventArgs) {
+        let WidgetMouseButton(state, button) = *event;
+        let pan_button = button == glutin::MouseButton::Middle || (button == glutin::MouseButton::Left && self.pan_key_down);
+        if self.pan && state == glutin::ElementState::Pressed && pan_button {
+            args.widget.start_drag(args.ui);
+        }
+    }
+
+    fn drag(&mut self, event: &DragEvent, args: EventArgs) {
+        match event.state {
+            DragState::Start => {
+                self.pan_start = Some(self.offset);
+                self.snap_pending = false;
+                self.snap_target = None;
+                args.ui.set_cursor(glutin::MouseCursor::Grabbing);
+            }
+            DragState::Moved => {
+                if let Some(pan_start) = self.pan_start {
+                    self.scroll_to(pan_start + event.offset);
+                }
+            }
+            DragState::End => {
+                if self.pan_start.take().is_some() {
+                    args.ui.set_cursor(if self.pan_key_down { glutin::MouseCursor::Grab } else { glutin::MouseCursor::Default });
+                    self.user_scrolled();
+                }
+            }
+        }
+    }
+
+    fn user_scrolled(&mut self) {
+        if self.snap_x.is_some() || self.snap_y.is_some() {
+            self.snap_pending = true;
+            self.snap_target = None;
+            self.last_scroll = clock::now();
+        }
+    }
+
+    /// The scroll offset of the snap point the content should settle on along one axis, if any
+    fn snap_point(&self, snap: &ScrollSnap, offset: f32, extent: f32, horizontal: bool) -> Option<f32> {
+        let content_start = if horizontal { self.content_rect.left() } else { self.content_rect.top() };
+        let points = match snap.points {
+            SnapPoints::Children => {
+                let mut points: Vec<f32> = self.scrollable.children().iter().map(|child| {
+                    let bounds = child.bounds();
+                    let start = if horizontal { bounds.left() } else { bounds.top() };
+                    (start - content_start) * self.zoom
+                }).collect();
+                points.push(extent);
+                points
+            }
+            SnapPoints::Offsets(ref offsets) => offsets.iter().map(|offset| offset * self.zoom).collect(),
+        };
+        let nearest = points.into_iter()
+            .map(|point| point.max(0.0).min(extent))
+            .min_by(|a, b| (a - offset).abs().partial_cmp(&(b - offset).abs()).unwrap());
+        match (nearest, snap.strictness) {
+            (Some(point), SnapStrictness::Proximity(distance)) if (point - offset).abs() > distance => None,
+            (nearest, _) => nearest,
+        }
+    }
+
+    fn frame_event(&mut self, _: &FrameEvent, args: EventArgs) {
+        let now = clock::now();
+        if self.snap_pending {
+            if now.duration_since(self.last_scroll) >= Duration::from_millis(SNAP_DELAY) {
+                self.snap_pending = false;
+                let offset = -self.offset;
+                let extent = Size::new(self.scrollable_area.width.max(0.0), self.scrollable_area.height.max(0.0));
+                let x = self.snap_x.as_ref().and_then(|snap| self.snap_point(snap, offset.x, extent.width, true));
+                let y = self.snap_y.as_ref().and_then(|snap| self.snap_point(snap, offset.y, extent.height, false));
+                if x.is_some() || y.is_some() {
+                    self.snap_target = Some(Vector::new(x.unwrap_or(offset.x), y.unwrap_or(offset.y)));
+                    self.last_frame = now;
+                }
+            }
+            // keep frames coming until it's time to snap
+            args.ui.redraw();
+        }
+        if let Some(target) = self.snap_target {
+            let elapsed = now.duration_since(self.last_frame);
+            self.last_frame = now;
+            let elapsed = (elapsed.as_secs() as f32 + elapsed.subsec_nanos() as f32 / 1_000_000_000.0).min(1.0 / 30.0);
+            let decay = (1.0 - elapsed * SNAP_SPEED).max(0.0);
+            let mut offset = target + (-self.offset - target) * decay;
+            if (offset - target).length() < 0.5 {
+                offset = target;
+                self.snap_target = None;
+            }
+            self.scroll_to(-offset);
+            args.ui.redraw();
+        }
+    }
+
+    /// Move the content to `offset`, clamped to the scrollable area
+    fn scroll_into_view(&mut self, event: &ScrollIntoView, args: EventArgs) {
+        let visible = match event.visible {
+            Some(visible) => match self.container_rect.intersection(&visible) {
+                Some(visible) => visible,
+                // the container is hidden by the insets, leave it to the containers around it
+                None => return,
+            },
+            None => self.container_rect,
+        };
+        // the smallest scroll that brings the area into view, or its start if it doesn't fit
+        let distance = |start: f32, end: f32, visible_start: f32, visible_end: f32| {
+            if start < visible_start || end - start > visible_end - visible_start {
+                visible_start - start
+            } else if end > visible_end {
+                visible_end - end
+            } else {
+                0.0
+            }
+        };
+        let area = event.area;
+        let scroll = Vector::new(
+            distance(area.left(), area.right(), visible.left(), visible.right()),
+            distance(area.top(), area.bottom(), visible.top(), visible.bottom()));
+        if scroll != Vector::zero() {
+            let offset = self.offset + scroll;
+            self.scroll_to(offset);
+        }
+        *args.handled = true;
+    }
+
+    fn scroll_to(&mut self, offset: Vector) {
+        if self.scrollable_area.width > 0.0 {
+            self.offset.x = f32::min(0.0, f32::max(-self.scrollable_area.width, offset.x));
+            self.move_content_x();
+            self.move_slider_x();
+        }
+        if self.scrollable_area.height > 0.0 {
+            self.offset.y = f32::min(0.0, f32::max(-self.scrollable_area.height, offset.y));
+            self.move_content_y();
+            self.move_slider_y();
+        }
+        self.scroll_changed();
+    }
+
+    fn scroll_changed(&self) {
+        self.scroll_widget.event(ScrollChanged {
+            offset: -self.offset,
+            extent: Size::new(self.scrollable_area.width.max(0.0), self.scrollable_area.height.max(0.0)),
+        });
+    }
+
+    fn widget_mouse_moved(&mut self, event: &WidgetMouseMoved, _: EventArgs) {
+        self.cursor = event.0;
+    }
+
+    fn zoom_by(&mut self, event: &ZoomBy, mut args: EventArgs) {
+        let (min, max) = match self.zoom_range {
+            Some(zoom_range) => zoom_range,
+            None => return,
+        };
+        let zoom = (self.zoom * event.factor).max(min).min(max);
+        if (zoom - self.zoom).abs() < ::std::f32::EPSILON {
+            return;
+        }
+        // keep the point of the content under the center where it is
+        let center = event.center - self.container_rect.origin;
+        self.offset = center - (center - self.offset) * (zoom / self.zoom);
+        self.zoom = zoom;
+        self.scrollable_area = self.content_rect.size * zoom - self.container_rect.size;
+        self.offset.x = f32::min(0.0, f32::max(-self.scrollable_area.width, self.offset.x));
+        self.offset.y = f32::min(0.0, f32::max(-self.scrollable_area.height, self.offset.y));
+
+        let origin = self.container_rect.origin;
+        args.widget.update_filter(|filter: &mut ScaleFilter| {
+            filter.scale = zoom;
+            filter.origin = origin;
+        });
+        self.move_content_x();
+        self.move_content_y();
+        self.update_scrollbar_sizes();
+        if self.scrollable_area.width > 0.0 {
+            self.move_slider_x();
+        }
+        if self.scrollable_area.height > 0.0 {
+            self.move_slider_y();
+        }
+        self.scroll_changed();
+        self.scroll_widget.event(ZoomChanged(zoom));
+    }
+
+    fn scrollbar_moved(&mut self, event: &ScrollBarMoved, _: EventArgs) {
+        let dragging = match *event {
+            ScrollBarMoved::Horizontal(offset, dragging) => {
+                self.offset.x = -offset * self.scrollable_area.width;
+                self.move_content_x();
+                dragging
+            }
+            ScrollBarMoved::Vertical(offset, dragging) => {
+                self.offset.y = -offset * self.scrollable_area.height;
+                self.move_content_y();
+                dragging
+            }
+        };
+        self.scroll_changed();
+        if dragging {
+            self.snap_pending = false;
+            self.snap_target = None;
+        } else {
+            self.user_scrolled();
+        }
+    }
+
+    fn move_content_x(&mut self) {
+        let scroll_to = self.container_rect.left() + self.offset.x / self.zoom;
+        let mut layout = self.scrollable.layout();
+        layout.edit_left().set(scroll_to);
+    }
+    fn move_content_y(&mut self) {
+        let scroll_to = self.container_rect.top() + self.offset.y / self.zoom;
+        let mut layout = self.scrollable.layout();
+        layout.edit_top().set(scroll_to);
+    }
+    fn move_slider_x(&mut self) {
+        if let Some(ref mut scrollbars) = self.scrollbars {
+            let offset_x = -self.offset.x / self.scrollable_area.width;
+            scrollbars.scrollbar_h.event(SetSliderValue(offset_x));
+        }
+    }
+    fn move_slider_y(&mut self) {
+        if let Some(ref mut scrollbars) = self.scrollbars {
+            let offset_y = -self.offset.y / self.scrollable_area.height;
+            scrollbars.scrollbar_v.event(SetSliderValue(offset_y));
+        }
+    }
+}
+
+fn get_scroll(event: glutin::MouseScrollDelta) -> Vector {
+    let vec = match event {
+        glutin::MouseScrollDelta::LineDelta(x, y) => {
+            Vector::new(-x, y)
+        },
+        glutin::MouseScrollDelta::PixelDelta(glutin::dpi::LogicalPosition{x, y}) => {
+            Vector::new(-x as f32, y as f32)
+        }
+    };
+    vec * 13.0
+}
@@ -0,0 +1,319 @@
+//! A sidebar docked to the left or right edge of a widget, resized by dragging its inner edge.
+//!
+//! The sidebar can be collapsed to a narrow rail, usually holding icons, and slides between the
+//! two widths rather than jumping. Every change of width or collapsed state is reported to the
+//! sidebar widget as a `SidebarChanged` event, so the app can save it and restore it with
+//! `SidebarBuilder::set_width` and `SidebarBuilder::set_collapsed` on the next run.
+
+use std::time::Instant;
+
+use clock;
+use prelude::*;
+use draw::prelude::*;
+use widgets::replace_constraints;
+
+/// Fraction of the remaining distance the width changes each second while collapsing or expanding
+const SLIDE_SPEED: f32 = 12.0;
+/// Width of the draggable inner edge
+const HANDLE_WIDTH: f32 = 6.0;
+
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum SidebarEdge {
+    Left,
+    Right,
+}
+
+/// Send to the sidebar widget
+#[derive(Debug, Copy, Clone)]
+pub enum SidebarEvent {
+    Collapse,
+    Expand,
+    Toggle,
+    /// Set the expanded width, clamped to the minimum and maximum width
+    SetWidth(f32),
+}
+
+/// Sent to the sidebar widget when it is resized, collapsed or expanded.
+/// `width` is the expanded width, even while collapsed.
+#[derive(Debug, Copy, Clone)]
+pub struct SidebarChanged {
+    pub width: f32,
+    pub collapsed: bool,
+}
+
+#[derive(Debug, Copy, Clone)]
+struct HandleDrag(DragEvent);
+
+struct SidebarHandler {
+    sidebar: Widget,
+    content: Widget,
+    rail: Option<Widget>,
+    edge: SidebarEdge,
+    width: f32,
+    min_width: f32,
+    max_width: f32,
+    rail_width: f32,
+    collapsed: bool,
+    /// The width currently shown, moves towards the target width while animating
+    shown_width: f32,
+    width_constraints: Vec<Constraint>,
+    drag_start_width: f32,
+    last_frame: Instant,
+}
+
+multi_event!{impl EventHandler<SidebarInput> for SidebarHandler {
+    SidebarEvent => sidebar_event,
+    HandleDrag => handle_drag,
+    FrameEvent => frame_event,
+}}
+
+impl SidebarHandler {
+    fn target_width(&self) -> f32 {
+        if self.collapsed { self.rail_width } else { self.width }
+    }
+
+    fn clamp_width(&self, width: f32) -> f32 {
+        width.max(self.min_width).min(self.max_width)
+    }
+
+    fn update_width(&mut self) {
+        let vars = self.sidebar.layout_vars();
+        let constraints = vec![ vars.width | EQ(REQUIRED) | self.shown_width ];
+        replace_constraints(&mut self.sidebar, &mut self.width_constraints, constraints);
+    }
+
+    /// Show the content or rail for the current state, the content stays visible until fully collapsed
+    fn update_visibility(&mut self) {
+        let show_rail = self.collapsed && (self.shown_width - self.rail_width).abs() < 0.5;
+        if show_rail {
+            self.content.layout().hide();
+        } else {
+            self.content.layout().show();
+        }
+        if let Some(ref mut rail) = self.rail {
+            if show_rail {
+                rail.layout().show();
+            } else {
+                rail.layout().hide();
+            }
+        }
+    }
+
+    fn changed(&self) {
+        self.sidebar.event(SidebarChanged {
+            width: self.width,
+            collapsed: self.collapsed,
+        });
+    }
+
+    fn sidebar_event(&mut self, event: &SidebarEvent, args: EventArgs) {
+        let collapsed = match *event {
+            SidebarEvent::Collapse => true,
+            SidebarEvent::Expand => false,
+            SidebarEvent::Toggle => !self.collapsed,
+            SidebarEvent::SetWidth(width) => {
+                self.width = self.clamp_width(width);
+                if !self.collapsed {
+                    self.shown_width = self.width;
+                    self.update_width();
+                }
+                self.changed();
+                return;
+            }
+        };
+        if collapsed != self.collapsed {
+            self.collapsed = collapsed;
+            self.last_frame = clock::now();
+            self.update_visibility();
+            self.changed();
+            args.ui.redraw();
+        }
+    }
+
+    fn handle_drag(&mut self, event: &HandleDrag, _: EventArgs) {
+        let HandleDrag(ref drag) = *event;
+        if self.collapsed {
+            return;
+        }
+        match drag.state {
+            DragState::Start => self.drag_start_width = self.width,
+            DragState::Moved | DragState::End => {
+                let offset = match self.edge {
+                    SidebarEdge::Left => drag.offset.x,
+                    SidebarEdge::Right => -drag.offset.x,
+                };
+                self.width = self.clamp_width(self.drag_start_width + offset);
+                self.shown_width = self.width;
+                self.update_width();
+                if drag.state == DragState::End {
+                    self.changed();
+                }
+            }
+        }
+    }
+
+    fn frame_event(&mut self, _: &FrameEvent, args: EventArgs) {
+        let target = self.target_width();
+        if (self.shown_width - target).abs() < ::std::f32::EPSILON {
+            return;
+        }
+        let now = clock::now();
+        let elapsed = now.duration_since(self.last_frame);
+        self.last_frame = now;
+        // frames stop while nothing changes, so the first frame of an animation can follow a long gap
+        let elapsed = (elapsed.as_secs() as f32 + elapsed.subsec_nanos() as f32 / 1_000_000_000.0).min(1.0 / 30.0);
+        let decay = (1.0 - elapsed * SLIDE_SPEED).max(0.0);
+        self.shown_width = target + (self.shown_width - target) * decay;
+        if (self.shown_width - target).abs() < 0.5 {
+            self.shown_width = target;
+            self.update_visibility();
+        }
+        self.update_width();
+        args.ui.redraw();
+    }
+}
+
+/// Builds a sidebar docked to one edge of a parent widget.
+pub struct SidebarBuilder {
+    edge: SidebarEdge,
+    content: Option<Widget>,
+    rail: Option<Widget>,
+    width: f32,
+    min_width: f32,
+    max_width: f32,
+    rail_width: f32,
+    collapsed: bool,
+}
+
+impl SidebarBuilder {
+    pub fn new(edge: SidebarEdge) -> Self {
+        SidebarBuilder {
+            edge: edge,
+            content: None,
+            rail: None,
+            width: 250.0,
+            min_width: 150.0,
+            max_width: 500.0,
+            rail_width: 48.0,
+            collapsed: false,
+        }
+    }
+    /// Set the widget shown while the sidebar is expanded
+    pub fn set_content(&mut self, content: Widget) -> &mut Self {
+        self.content = Some(content);
+        self
+    }
+    /// Set the widget shown while the sidebar is collapsed, ex. a column of icon buttons
+    pub fn set_rail(&mut self, rail: Widget) -> &mut Self {
+        self.rail = Some(rail);
+        self
+    }
+    /// Set the initial expanded width, ex. the width from a previous `SidebarChanged`
+    pub fn set_width(&mut self, width: f32) -> &mut Self {
+        self.width = width;
+        self
+    }
+    /// Set the range of widths the sidebar can be dragged to
+    pub fn set_width_range(&mut self, min_width: f32, max_width: f32) -> &mut Self {
+        self.min_width = min_width;
+        self.max_width = max_width;
+        self
+    }
+    /// Set the width of the sidebar while collapsed
+    pub fn set_rail_width(&mut self, rail_width: f32) -> &mut Self {
+        self.rail_width = rail_width;
+        self
+    }
+    /// Start collapsed
+    pub fn set_collapsed(&mut self, collapsed: bool) -> &mut Self {
+        self.collapsed = collapsed;
+        self
+    }
+
+    /// Create the sidebar widget and add it to `parent`, docked to its edge
+    pub fn build(&self, parent: &mut Widget) -> Widget {
+        let mut sidebar = Widget::new("sidebar");
+        sidebar.layout().no_container();
+        sidebar.set_draw_style(style!(RectStyle {
+            background_color: GRAY_80,
+        }));
+
+        let mut handle = Widget::new("sidebar_handle");
+        handle.set_draw_style(style!(RectStyle {
+            background_color: GRAY_70,
+        }));
+        handle.layout().add(constraints![
+            width(HANDLE_WIDTH),
+            align_top(&sidebar),
+            align_bottom(&sidebar),
+        ]);
+        handle.layout().add(match self.edge {
+            SidebarEdge::Left => align_right(&sidebar),
+            SidebarEdge::Right => align_left(&sidebar),
+        });
+        handle.make_draggable();
+        forward_event!(DragEvent: |event, _| HandleDrag(*event); handle -> sidebar);
+
+        let inner_edge = |widget: &mut Widget| {
+            widget.layout().add(constraints![
+                align_top(&sidebar),
+                align_bottom(&sidebar),
+            ]);
+            match self.edge {
+                SidebarEdge::Left => widget.layout().add(constraints![
+                    align_left(&sidebar),
+                    to_left_of(&handle),
+                ]),
+                SidebarEdge::Right => widget.layout().add(constraints![
+                    align_right(&sidebar),
+                    to_right_of(&handle),
+                ]),
+            }
+        };
+        let mut content = self.content.clone().unwrap_or_else(|| Widget::new("sidebar_content"));
+        inner_edge(&mut content);
+        let mut rail = self.rail.clone();
+        if let Some(ref mut rail) = rail {
+            inner_edge(rail);
+        }
+
+        let width = self.width.max(self.min_width).min(self.max_width);
+        let mut handler = SidebarHandler {
+            sidebar: sidebar.clone(),
+            content: content.clone(),
+            rail: rail.clone(),
+            edge: self.edge,
+            width: width,
+            min_width: self.min_width,
+            max_width: self.max_width,
+            rail_width: self.rail_width,
+            collapsed: self.collapsed,
+            shown_width: if self.collapsed { self.rail_width } else { width },
+            width_constraints: Vec::new(),
+            drag_start_width: width,
+            last_frame: clock::now(),
+        };
+        handler.update_width();
+        handler.update_visibility();
+
+        sidebar.add_child(content);
+        if let Some(rail) = rail {
+            sidebar.add_child(rail);
+        }
+        sidebar.add_child(handle);
+        sidebar.add_handler(handler);
+        SidebarHandler::add_adapters(&mut sidebar);
+        sidebar.receive_frame_events();
+
+        sidebar.layout().add(constraints![
+            align_top(&*parent),
+            align_bottom(&*parent),
+        ]);
+        sidebar.layout().add(match self.edge {
+            SidebarEdge::Left => align_left(&*parent),
+            SidebarEdge::Right => align_right(&*parent),
+        });
+        parent.add_child(sidebar.clone());
+        sidebar
+    }
+}
@@ -0,0 +1,76 @@
+//! Skeletons, grey blocks in the shape of content that's still loading, so the layout doesn't jump
+//! when it arrives. A lighter band sweeps across the blocks, redrawn every frame.
+//!
+//! `ListItemBuilder::build_skeleton` builds a skeleton with the layout of a list item, and
+//! `skeleton_list` a list of them, ex. to return from the loading template of a `PlaceholderBuilder`.
+
+use prelude::*;
+use draw::prelude::*;
+use widgets::list::ListItemBuilder;
+
+/// Height of a skeleton standing in for a line of text
+pub const SKELETON_LINE_HEIGHT: f32 = 14.0;
+
+/// Builds a skeleton block
+pub struct SkeletonBuilder {
+    size: Option<Size>,
+    state: SkeletonState,
+}
+
+impl Default for SkeletonBuilder {
+    fn default() -> Self {
+        SkeletonBuilder {
+            size: None,
+            state: SkeletonState::default(),
+        }
+    }
+}
+
+impl SkeletonBuilder {
+    pub fn new() -> Self {
+        Self::default()
+    }
+    /// A block standing in for a line of text `width` wide
+    pub fn text_line(width: f32) -> Self {
+        let mut builder = Self::default();
+        builder.set_size(Size::new(width, SKELETON_LINE_HEIGHT));
+        builder
+    }
+    /// Fix the size of the block, otherwise it's sized by the constraints added to it
+    pub fn set_size(&mut self, size: Size) -> &mut Self {
+        self.size = Some(size);
+        self
+    }
+    pub fn set_colors(&mut self, color: Color, shimmer_color: Color) -> &mut Self {
+        self.state.color = color;
+        self.state.shimmer_color = shimmer_color;
+        self
+    }
+
+    pub fn build(&self) -> Widget {
+        let mut widget = Widget::new("skeleton");
+        widget.set_draw_state(self.state.clone());
+        if let Some(block_size) = self.size {
+            widget.layout().add(size(block_size));
+        }
+        widget
+            .add_handler(|_: &FrameEvent, args: EventArgs| {
+                args.ui.redraw();
+            })
+            .receive_frame_events();
+        widget
+    }
+}
+
+/// A vertical list of `rows` skeletons with the layout of `item`
+pub fn skeleton_list(item: &ListItemBuilder, rows: usize) -> Widget {
+    let mut list = Widget::new("skeleton_list");
+    let mut layout_settings = LinearLayoutSettings::new(Orientation::Vertical);
+    layout_settings.item_align = ItemAlignment::Fill;
+    list.linear_layout(layout_settings);
+    for _ in 0..rows {
+        let row = item.build_skeleton(&list);
+        list.add_child(row);
+    }
+    list
+}
@@ -0,0 +1,23 @@
+//! Empty widgets that take up space in a linear layout, to push siblings apart without writing
+//! constraints, ex. a flexible spacer between the left and right groups of a toolbar.
+
+use prelude::*;
+
+/// Builds spacers, to add to a parent with a linear layout
+pub struct Spacer;
+
+impl Spacer {
+    /// A gap of `length` along the axis of the parent
+    pub fn fixed(length: f32) -> Widget {
+        let mut widget = Widget::new("spacer");
+        widget.layout().set_axis_size(AxisSize::Fixed(length));
+        widget
+    }
+    /// A gap taking a share of the space left over along the axis of the parent, `grow`
+    /// relative to the other flexible spacers and children
+    pub fn flex(grow: f32) -> Widget {
+        let mut widget = Widget::new("spacer");
+        widget.layout().set_axis_size(AxisSize::Flex(grow));
+        widget
+    }
+}
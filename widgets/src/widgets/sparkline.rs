@@ -0,0 +1,90 @@
+//! A sparkline, a chart small enough to sit in a table cell or list item, showing the shape of a
+//! list of values without axes or labels.
+//!
+//! A sparkline is a single widget with no children, so thousands can be shown at once. Values
+//! can be replaced or pushed with `SparklineEvent`, or from another thread with
+//! `EventSender::update_widget`, ex.
+//! `sender.update_widget(id, move |state: &mut SparklineState| state.push(&[value]))`.
+
+use prelude::*;
+use draw::prelude::*;
+
+/// Changes the values of a sparkline, send to the sparkline widget
+#[derive(Debug, Clone)]
+pub enum SparklineEvent {
+    SetValues(Vec<f32>),
+    /// Append values, dropping the oldest beyond the maximum number of values
+    PushValues(Vec<f32>),
+}
+
+/// Builds a sparkline
+#[derive(Debug, Clone)]
+pub struct SparklineBuilder {
+    values: Vec<f32>,
+    style: SparklineStyle,
+    color: Color,
+    markers: Option<Color>,
+    max_values: Option<usize>,
+}
+
+impl Default for SparklineBuilder {
+    fn default() -> Self {
+        SparklineBuilder {
+            values: Vec::new(),
+            style: SparklineStyle::Line,
+            color: GRAY_30,
+            markers: None,
+            max_values: None,
+        }
+    }
+}
+
+impl SparklineBuilder {
+    pub fn new(values: Vec<f32>) -> Self {
+        SparklineBuilder {
+            values: values,
+            ..SparklineBuilder::default()
+        }
+    }
+    /// A line by default
+    pub fn set_style(&mut self, style: SparklineStyle) -> &mut Self {
+        self.style = style;
+        self
+    }
+    pub fn set_color(&mut self, color: Color) -> &mut Self {
+        self.color = color;
+        self
+    }
+    /// Mark the lowest and highest values with a dot of `color`
+    pub fn set_markers(&mut self, color: Color) -> &mut Self {
+        self.markers = Some(color);
+        self
+    }
+    /// Keep at most `max_values`, dropping the oldest as values are pushed
+    pub fn set_max_values(&mut self, max_values: usize) -> &mut Self {
+        self.max_values = Some(max_values);
+        self
+    }
+
+    pub fn build(&self) -> Widget {
+        let mut state = SparklineState::new(self.values.clone());
+        state.style = self.style;
+        state.color = self.color;
+        state.markers = self.markers;
+        if let Some(max_values) = self.max_values {
+            state.set_max_values(max_values);
+        }
+        let mut widget = Widget::new("sparkline");
+        widget
+            .set_draw_state(state)
+            .add_handler(|event: &SparklineEvent, mut args: EventArgs| {
+                args.widget.update(|state: &mut SparklineState| {
+                    match *event {
+                        SparklineEvent::SetValues(ref values) => state.set_values(values.clone()),
+                        SparklineEvent::PushValues(ref values) => state.push(values),
+                    }
+                });
+            });
+        widget
+    }
+}
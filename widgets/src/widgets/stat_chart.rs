@@ -0,0 +1,232 @@
+//! Histograms and box plots, for showing the distribution of datasets.
+//!
+//! Both fit their view to the data, refitting when it changes or when a dataset is shown or hidden,
+//! and share the axes and legend of the line chart. A box plot's x axis is labelled with the names
+//! of its datasets.
+
+use std::marker::PhantomData;
+
+use prelude::*;
+use draw::prelude::*;
+use draw::stat_chart::StatChart;
+use widgets::chart::{layout_chart, update_axes};
+
+/// Changes the data of a histogram or box plot, send to the chart widget
+#[derive(Debug, Clone)]
+pub enum StatChartEvent {
+    AddDataset(Dataset),
+    /// Replace the values of the dataset at an index
+    SetValues(usize, Vec<f32>),
+    /// Show or hide the dataset at an index
+    SetVisible(usize, bool),
+    /// Change how a histogram's values are binned, box plots ignore it
+    SetBinning(Binning),
+}
+
+struct StatChartHandler<T> {
+    plot: Widget,
+    x_axis: Option<Widget>,
+    y_axis: Option<Widget>,
+    legend: Option<Widget>,
+    state: PhantomData<T>,
+}
+
+impl<T: StatChart> StatChartHandler<T> {
+    fn refresh(&mut self) {
+        let (view, categories) = {
+            let draw_state = self.plot.draw_state();
+            let state = draw_state.downcast_ref::<T>().unwrap();
+            (state.view(), state.categories())
+        };
+        update_axes(&mut self.x_axis, &mut self.y_axis, view);
+        if let Some(ref mut x_axis) = self.x_axis {
+            x_axis.update(|state: &mut AxisState| state.labels = categories);
+        }
+    }
+}
+
+impl<T: StatChart> EventHandler<StatChartEvent> for StatChartHandler<T> {
+    fn handle(&mut self, event: &StatChartEvent, _: EventArgs) {
+        match *event {
+            StatChartEvent::AddDataset(ref dataset) => {
+                if let Some(ref legend) = self.legend {
+                    legend.event(LegendEvent::AddEntry(dataset.name.clone(), dataset.color));
+                }
+                let dataset = dataset.clone();
+                self.plot.update(|state: &mut T| state.datasets_mut().push(dataset));
+            }
+            StatChartEvent::SetValues(index, ref values) => {
+                let values = values.clone();
+                self.plot.update(|state: &mut T| {
+                    if let Some(dataset) = state.datasets_mut().get_mut(index) {
+                        dataset.set_values(values);
+                    }
+                });
+            }
+            StatChartEvent::SetVisible(index, visible) => {
+                if let Some(ref legend) = self.legend {
+                    legend.event(LegendEvent::SetVisible(index, visible));
+                }
+                self.plot.update(|state: &mut T| {
+                    if let Some(dataset) = state.datasets_mut().get_mut(index) {
+                        dataset.visible = visible;
+                    }
+                });
+            }
+            StatChartEvent::SetBinning(binning) => {
+                self.plot.update(|state: &mut T| state.set_binning(binning));
+            }
+        }
+        self.plot.update(|state: &mut T| state.refresh());
+        self.refresh();
+    }
+}
+
+/// The widget of a histogram or box plot drawn by `state`
+fn build_stat_chart<T: StatChart + Component>(name: &str, state: T, datasets: &[Dataset], legend: bool, axes: Option<ChartAxes>) -> Widget {
+    let mut widget = Widget::new(name);
+    let legend = if legend {
+        let mut legend = LegendBuilder::new();
+        for dataset in datasets {
+            legend.add_entry(&dataset.name, dataset.color);
+        }
+        let mut legend = legend.build();
+        let chart = widget.clone();
+        legend.add_handler(move |event: &LegendToggled, _: EventArgs| {
+            let LegendToggled(index, visible) = *event;
+            chart.event(StatChartEvent::SetVisible(index, visible));
+        });
+        Some(legend)
+    } else {
+        None
+    };
+    let mut plot = Widget::new(&format!("{}_plot", name));
+    plot.set_draw_state(state);
+    let (x_axis, y_axis) = layout_chart(&mut widget, &mut plot, legend.as_ref(), axes, None);
+    let mut handler: StatChartHandler<T> = StatChartHandler {
+        plot: plot.clone(),
+        x_axis: x_axis,
+        y_axis: y_axis,
+        legend: legend,
+        state: PhantomData,
+    };
+    handler.refresh();
+    widget
+        .add_child(plot)
+        .add_handler(handler);
+    widget
+}
+
+/// Builds a histogram of one or more datasets, with bars for each dataset side by side
+#[derive(Debug, Clone)]
+pub struct HistogramBuilder {
+    datasets: Vec<Dataset>,
+    binning: Binning,
+    axes: Option<ChartAxes>,
+    legend: bool,
+    grid: bool,
+}
+
+impl Default for HistogramBuilder {
+    fn default() -> Self {
+        HistogramBuilder {
+            datasets: Vec::new(),
+            binning: Binning::Auto,
+            axes: Some(ChartAxes::Both),
+            legend: false,
+            grid: true,
+        }
+    }
+}
+
+impl HistogramBuilder {
+    pub fn new() -> Self {
+        Self::default()
+    }
+    pub fn add_dataset(&mut self, dataset: Dataset) -> &mut Self {
+        self.datasets.push(dataset);
+        self
+    }
+    /// How values are split into bins, chosen from the data by default
+    pub fn set_binning(&mut self, binning: Binning) -> &mut Self {
+        self.binning = binning;
+        self
+    }
+    /// The axes shown beside the chart, both by default, `None` for neither
+    pub fn set_axes(&mut self, axes: Option<ChartAxes>) -> &mut Self {
+        self.axes = axes;
+        self
+    }
+    /// Show a legend above the chart, clicking a dataset in it shows or hides the dataset
+    pub fn set_legend(&mut self, legend: bool) -> &mut Self {
+        self.legend = legend;
+        self
+    }
+    /// Draw grid lines at the ticks of the y axis, on by default
+    pub fn set_grid(&mut self, grid: bool) -> &mut Self {
+        self.grid = grid;
+        self
+    }
+
+    pub fn build(&self) -> Widget {
+        let mut state = HistogramState::new(self.datasets.clone(), self.binning);
+        if !self.grid {
+            state.grid_color = None;
+        }
+        build_stat_chart("histogram", state, &self.datasets, self.legend, self.axes)
+    }
+}
+
+/// Builds a box plot of one or more datasets, side by side
+#[derive(Debug, Clone)]
+pub struct BoxPlotBuilder {
+    datasets: Vec<Dataset>,
+    axes: Option<ChartAxes>,
+    legend: bool,
+    grid: bool,
+}
+
+impl Default for BoxPlotBuilder {
+    fn default() -> Self {
+        BoxPlotBuilder {
+            datasets: Vec::new(),
+            axes: Some(ChartAxes::Both),
+            legend: false,
+            grid: true,
+        }
+    }
+}
+
+impl BoxPlotBuilder {
+    pub fn new() -> Self {
+        Self::default()
+    }
+    pub fn add_dataset(&mut self, dataset: Dataset) -> &mut Self {
+        self.datasets.push(dataset);
+        self
+    }
+    /// The axes shown beside the chart, both by default, `None` for neither. The x axis shows the
+    /// names of the datasets.
+    pub fn set_axes(&mut self, axes: Option<ChartAxes>) -> &mut Self {
+        self.axes = axes;
+        self
+    }
+    /// Show a legend above the chart, clicking a dataset in it shows or hides the dataset
+    pub fn set_legend(&mut self, legend: bool) -> &mut Self {
+        self.legend = legend;
+        self
+    }
+    /// Draw grid lines at the ticks of the y axis, on by default
+    pub fn set_grid(&mut self, grid: bool) -> &mut Self {
+        self.grid = grid;
+        self
+    }
+
+    pub fn build(&self) -> Widget {
+        let mut state = BoxPlotState::new(self.datasets.clone());
+        if !self.grid {
+            state.grid_color = None;
+        }
+        build_stat_chart("box_plot", state, &self.datasets, self.legend, self.axes)
+    }
+}
@@ -0,0 +1,359 @@
+//! Tabs, a bar of titled tabs above an area showing the content of the selected tab, for keeping
+//! several documents in one window.
+//!
+//! Tabs are selected by clicking them, reordered by dragging them along the bar and closed with
+//! their close button. Changes are reported to the tabs widget as `TabSelected`, `TabMoved` and
+//! `TabClosed` events. Closing a tab only removes it, an app that asks to save changes first should
+//! make tabs unclosable and send `TabsEvent::Close` itself once the document is saved.
+//!
+//! This stands in for native window tabbing, ex. on macOS, which merges separate windows into one,
+//! since a limn app has a single window.
+
+use prelude::*;
+use draw::prelude::*;
+use widgets::edit_text::TextUpdated;
+use widgets::text::StaticTextStyle;
+use widgets::replace_constraints;
+
+const TAB_WIDTH: f32 = 180.0;
+const TAB_HEIGHT: f32 = 32.0;
+const TAB_PADDING: f32 = 10.0;
+
+/// Send to the tabs widget
+#[derive(Clone)]
+pub enum TabsEvent {
+    /// Add a tab after the last tab and select it
+    Add(String, Widget),
+    Select(usize),
+    /// Close a tab, selecting the tab after it if it was selected
+    Close(usize),
+    /// Move a tab from one index to another
+    Move(usize, usize),
+    SetTitle(usize, String),
+}
+
+/// Sent to the tabs widget when a tab is selected, with its index
+#[derive(Debug, Copy, Clone)]
+pub struct TabSelected(pub usize);
+
+/// Sent to the tabs widget when a tab is closed, with its index before it closed
+#[derive(Debug, Copy, Clone)]
+pub struct TabClosed(pub usize);
+
+/// Sent to the tabs widget when a tab is dropped in a new position
+#[derive(Debug, Copy, Clone)]
+pub struct TabMoved {
+    pub from: usize,
+    pub to: usize,
+}
+
+#[derive(Debug, Copy, Clone)]
+enum TabInput {
+    Click(WidgetId),
+    CloseClick(WidgetId),
+    Drag(WidgetId, DragEvent),
+}
+
+struct Tab {
+    widget: Widget,
+    title: Widget,
+    content: Widget,
+    constraints: Vec<Constraint>,
+}
+
+multi_event!{impl EventHandler<TabsInput> for TabsHandler {
+    TabsEvent => tabs_event,
+    TabInput => tab_input,
+}}
+
+struct TabsHandler {
+    tabs_widget: Widget,
+    bar: Widget,
+    content_area: Widget,
+    tabs: Vec<Tab>,
+    selected: Option<usize>,
+    closable: bool,
+    /// Index of the tab being dragged
+    dragging: Option<usize>,
+}
+
+impl TabsHandler {
+    fn index_of(&self, id: WidgetId) -> Option<usize> {
+        self.tabs.iter().position(|tab| tab.widget.id() == id)
+    }
+
+    /// Place the tab at `index` `offset` from its slot in the bar
+    fn place(&mut self, index: usize, offset: f32) {
+        let bar = self.bar.layout_vars();
+        let tab = &mut self.tabs[index];
+        let vars = tab.widget.layout_vars();
+        let constraints = vec![ vars.left | EQ(REQUIRED) | bar.left + index as f32 * TAB_WIDTH + offset ];
+        replace_constraints(&mut tab.widget, &mut tab.constraints, constraints);
+    }
+
+    fn place_all(&mut self) {
+        for index in 0..self.tabs.len() {
+            self.place(index, 0.0);
+        }
+    }
+
+    fn add(&mut self, title: &str, mut content: Widget, select: bool) {
+        let mut widget = Widget::new("tab");
+        let mut draw_style = DrawStyle::from(style!(RectStyle {
+            background_color: GRAY_80,
+            border: Some((1.0, GRAY_60)),
+        }));
+        draw_style.prop_style(SELECTED.clone(), style!(RectStyle {
+            background_color: WHITE,
+        }));
+        widget.set_draw_style(draw_style);
+        widget.layout().add(constraints![
+            width(TAB_WIDTH),
+            align_top(&self.bar),
+            align_bottom(&self.bar),
+        ]);
+        let mut title_widget = Widget::from_modifier_style(StaticTextStyle::from_style(style!(TextStyle {
+            text: title.to_owned(),
+            font_size: 16.0,
+            wrap: Wrap::NoWrap,
+        })));
+        title_widget.layout().add(constraints![
+            align_left(&widget).padding(TAB_PADDING),
+            center_vertical(&widget),
+        ]);
+        widget.add_child(title_widget.clone());
+        if self.closable {
+            let mut close_button = Widget::from_modifier_style(StaticTextStyle::from_style(style!(TextStyle {
+                text: "×".to_owned(),
+                font_size: 18.0,
+                text_color: GRAY_40,
+            })));
+            close_button.layout().add(constraints![
+                align_right(&widget).padding(TAB_PADDING),
+                center_vertical(&widget),
+                to_right_of(&title_widget).padding(TAB_PADDING / 2.0).strength(WEAK),
+            ]);
+            let tabs_widget = self.tabs_widget.clone();
+            let id = widget.id();
+            close_button.add_handler(move |_: &ClickEvent, args: EventArgs| {
+                tabs_widget.event(TabInput::CloseClick(id));
+                *args.handled = true;
+            });
+            widget.add_child(close_button);
+        }
+        let tabs_widget = self.tabs_widget.clone();
+        widget.add_handler(move |_: &ClickEvent, args: EventArgs| {
+            tabs_widget.event(TabInput::Click(args.widget.id()));
+        });
+        let tabs_widget = self.tabs_widget.clone();
+        widget.add_handler(move |event: &DragEvent, args: EventArgs| {
+            tabs_widget.event(TabInput::Drag(args.widget.id(), *event));
+        });
+        widget.make_draggable();
+
+        content.layout().add(match_layout(&self.content_area));
+        content.layout().hide();
+        self.bar.add_child(widget.clone());
+        self.content_area.add_child(content.clone());
+        self.tabs.push(Tab {
+            widget: widget,
+            title: title_widget,
+            content: content,
+            constraints: Vec::new(),
+        });
+        let index = self.tabs.len() - 1;
+        self.place(index, 0.0);
+        if select {
+            self.select(index);
+        }
+    }
+
+    fn select(&mut self, index: usize) {
+        if index >= self.tabs.len() || self.selected == Some(index) {
+            return;
+        }
+        if let Some(selected) = self.selected {
+            let tab = &mut self.tabs[selected];
+            tab.widget.remove_prop(Property::Selected);
+            tab.content.layout().hide();
+        }
+        let tab = &mut self.tabs[index];
+        tab.widget.add_prop(Property::Selected);
+        tab.content.layout().show();
+        self.selected = Some(index);
+        self.tabs_widget.event(TabSelected(index));
+    }
+
+    fn close(&mut self, index: usize) {
+        if index >= self.tabs.len() {
+            return;
+        }
+        let tab = self.tabs.remove(index);
+        self.bar.remove_child(tab.widget);
+        self.content_area.remove_child(tab.content);
+        self.place_all();
+        self.tabs_widget.event(TabClosed(index));
+        if let Some(selected) = self.selected {
+            if selected > index {
+                self.selected = Some(selected - 1);
+            } else if selected == index {
+                self.selected = None;
+                if !self.tabs.is_empty() {
+                    let next = index.min(self.tabs.len() - 1);
+                    self.select(next);
+                }
+            }
+        }
+    }
+
+    fn move_tab(&mut self, from: usize, to: usize) {
+        if from >= self.tabs.len() {
+            return;
+        }
+        let to = to.min(self.tabs.len() - 1);
+        let tab = self.tabs.remove(from);
+        self.tabs.insert(to, tab);
+        self.selected = self.selected.map(|selected| {
+            if selected == from {
+                to
+            } else if from < selected && selected <= to {
+                selected - 1
+            } else if to <= selected && selected < from {
+                selected + 1
+            } else {
+                selected
+            }
+        });
+        self.place_all();
+        if from != to {
+            self.tabs_widget.event(TabMoved { from: from, to: to });
+        }
+    }
+
+    fn tabs_event(&mut self, event: &TabsEvent, _: EventArgs) {
+        match *event {
+            TabsEvent::Add(ref title, ref content) => self.add(title, content.clone(), true),
+            TabsEvent::Select(index) => self.select(index),
+            TabsEvent::Close(index) => self.close(index),
+            TabsEvent::Move(from, to) => self.move_tab(from, to),
+            TabsEvent::SetTitle(index, ref title) => {
+                if let Some(tab) = self.tabs.get(index) {
+                    tab.title.event(TextUpdated(title.clone()));
+                }
+            }
+        }
+    }
+
+    fn tab_input(&mut self, event: &TabInput, _: EventArgs) {
+        match *event {
+            TabInput::Click(id) => {
+                if let Some(index) = self.index_of(id) {
+                    self.select(index);
+                }
+            }
+            TabInput::CloseClick(id) => {
+                if let Some(index) = self.index_of(id) {
+                    self.close(index);
+                }
+            }
+            TabInput::Drag(id, ref drag) => {
+                let index = match self.dragging.or_else(|| self.index_of(id)) {
+                    Some(index) => index,
+                    None => return,
+                };
+                let max_offset = (self.tabs.len() - 1) as f32 * TAB_WIDTH;
+                let left = (index as f32 * TAB_WIDTH + drag.offset.x).max(0.0).min(max_offset);
+                match drag.state {
+                    DragState::Start => {
+                        self.dragging = Some(index);
+                        // draw the dragged tab over the others
+                        let last = self.tabs.len() - 1;
+                        let tab = self.tabs[index].widget.clone();
+                        self.bar.move_child(&tab, last);
+                        self.select(index);
+                    }
+                    DragState::Moved => {
+                        self.place(index, left - index as f32 * TAB_WIDTH);
+                    }
+                    DragState::End => {
+                        self.dragging = None;
+                        let to = (left / TAB_WIDTH).round() as usize;
+                        self.move_tab(index, to);
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// Builds a tabs widget
+pub struct TabsBuilder {
+    tabs: Vec<(String, Widget)>,
+    closable: bool,
+}
+
+impl Default for TabsBuilder {
+    fn default() -> Self {
+        TabsBuilder {
+            tabs: Vec::new(),
+            closable: true,
+        }
+    }
+}
+
+impl TabsBuilder {
+    pub fn new() -> Self {
+        Self::default()
+    }
+    pub fn add_tab(&mut self, title: &str, content: Widget) -> &mut Self {
+        self.tabs.push((title.to_owned(), content));
+        self
+    }
+    /// Show a close button on each tab, on by default
+    pub fn set_closable(&mut self, closable: bool) -> &mut Self {
+        self.closable = closable;
+        self
+    }
+
+    /// Create the tabs widget, with the first tab selected
+    pub fn build(&self) -> Widget {
+        let mut tabs_widget = Widget::new("tabs");
+        let mut bar = Widget::new("tab_bar");
+        bar.set_draw_style(style!(RectStyle {
+            background_color: GRAY_70,
+        }));
+        bar.layout().add(constraints![
+            height(TAB_HEIGHT),
+            align_top(&tabs_widget),
+            align_left(&tabs_widget),
+            align_right(&tabs_widget),
+        ]);
+        let mut content_area = Widget::new("tab_content");
+        content_area.layout().add(constraints![
+            below(&bar),
+            align_left(&tabs_widget),
+            align_right(&tabs_widget),
+            align_bottom(&tabs_widget),
+        ]);
+        tabs_widget
+            .add_child(bar.clone())
+            .add_child(content_area.clone());
+
+        let mut handler = TabsHandler {
+            tabs_widget: tabs_widget.clone(),
+            bar: bar,
+            content_area: content_area,
+            tabs: Vec::new(),
+            selected: None,
+            closable: self.closable,
+            dragging: None,
+        };
+        for &(ref title, ref content) in &self.tabs {
+            handler.add(title, content.clone(), false);
+        }
+        handler.select(0);
+        tabs_widget.add_handler(handler);
+        TabsHandler::add_adapters(&mut tabs_widget);
+        tabs_widget
+    }
+}
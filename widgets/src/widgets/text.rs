@@ -35,6 +35,39 @@ impl WidgetModifier for StaticText {
     }
 }
 
+/// Send to a widget and its descendants, with `event_subtree`, when the color it's drawn over
+/// changes, ex. after the user picks a new color for a tag, so `ContrastingText` picks the text
+/// color again
+#[derive(Debug, Copy, Clone)]
+pub struct BackgroundChanged;
+
+/// Keeps a text widget readable on whatever it's drawn over, ex. a label on a tag, avatar or
+/// calendar event with a user chosen color, by coloring its text with `Widget::contrasting_color`:
+/// black or white, or the theme's contrast colors. Apply it to the text widget with
+/// `ContrastingText.apply(&mut widget)`. The color is picked when the widget is added to a
+/// parent, its style is updated, or it's sent `BackgroundChanged`.
+#[derive(Debug, Copy, Clone)]
+pub struct ContrastingText;
+
+impl ContrastingText {
+    fn update(widget: &mut Widget) {
+        let color = widget.contrasting_color();
+        let current = widget.draw_state().downcast_ref::<TextState>().map(|state| state.text_color);
+        if current.is_some() && current != Some(color) {
+            widget.update(|state: &mut TextState| state.text_color = color);
+        }
+    }
+}
+
+impl WidgetModifier for ContrastingText {
+    fn apply(&self, widget: &mut Widget) {
+        widget.add_handler(|_: &WidgetAttachedEvent, mut args: EventArgs| ContrastingText::update(&mut args.widget));
+        widget.add_handler(|_: &StyleUpdated, mut args: EventArgs| ContrastingText::update(&mut args.widget));
+        widget.add_handler(|_: &BackgroundChanged, mut args: EventArgs| ContrastingText::update(&mut args.widget));
+        ContrastingText::update(widget);
+    }
+}
+
 #[derive(Default)]
 struct TextUpdateHandler {
     text: Option<String>,
@@ -0,0 +1,444 @@
+//! A grid of image thumbnails, ex. for a photo browser. Place it in a vertical `ScrollContainer`,
+//! the thumbnails wrap to its width.
+//!
+//! Thumbnails are generated on worker threads, downscaled from the full images, and only for the
+//! rows that are visible or nearly so, the rest are drawn as placeholders until they're scrolled
+//! to. The most recently used thumbnails are kept loaded, and if a cache directory is set they're
+//! also saved there, so they don't need to be generated again the next time the images are shown.
+//!
+//! Clicking selects a thumbnail, ctrl-click and shift-click extend the selection, and dragging
+//! across the grid selects the thumbnails under the rubber band. Double clicking a thumbnail sends
+//! a `ThumbnailActivated` event to the grid widget.
+
+use std::collections::{HashMap, HashSet, VecDeque, BTreeSet};
+use std::collections::hash_map::DefaultHasher;
+use std::fs;
+use std::hash::{Hash, Hasher};
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex, mpsc};
+use std::thread;
+use std::time::{Duration, Instant};
+
+use clock;
+use prelude::*;
+use draw::prelude::*;
+use widgets::replace_constraints;
+
+const DOUBLE_CLICK_TIME: u64 = 400;
+/// How far the mouse has to move before a press becomes a rubber band selection
+const DRAG_THRESHOLD: f32 = 4.0;
+/// Rows above and below the visible ones whose thumbnails are generated ahead of being scrolled to
+const OVERSCAN_ROWS: usize = 1;
+
+/// Changes a thumbnail grid, send to the grid widget
+#[derive(Debug, Clone)]
+pub enum ThumbnailGridEvent {
+    /// Replace the images, clearing the selection
+    SetImages(Vec<PathBuf>),
+    AddImages(Vec<PathBuf>),
+    /// Select the thumbnails at these indices
+    SetSelection(Vec<usize>),
+}
+
+/// Sent to the grid widget whenever the selection changes, with the paths of the selected images
+#[derive(Debug, Clone)]
+pub struct ThumbnailsSelected(pub Vec<PathBuf>);
+
+/// Sent to the grid widget when a thumbnail is double clicked
+#[derive(Debug, Clone)]
+pub struct ThumbnailActivated(pub PathBuf);
+
+/// Pixels of a generated thumbnail, its width, height and RGBA data
+type ThumbnailData = (u32, u32, Vec<u8>);
+
+#[derive(Debug, Clone)]
+struct ThumbnailGenerated(PathBuf, Result<ThumbnailData, String>);
+
+/// Name of the file a thumbnail of `path` is cached in, changes when the image is modified
+fn cache_name(path: &Path, size: u32) -> Option<String> {
+    let modified = fs::metadata(path).and_then(|metadata| metadata.modified()).ok()?;
+    let mut hasher = DefaultHasher::new();
+    (path, modified, size).hash(&mut hasher);
+    Some(format!("{:016x}.png", hasher.finish()))
+}
+
+/// Downscale the image at `path` to fit in a square `size` pixels wide, or read a thumbnail
+/// generated earlier from `cache_dir`
+fn generate_thumbnail(path: &Path, size: u32, cache_dir: Option<&Path>) -> Result<ThumbnailData, String> {
+    let cache_path = cache_dir.and_then(|dir| cache_name(path, size).map(|name| dir.join(name)));
+    if let Some(image) = cache_path.as_ref().and_then(|cache_path| image::open(cache_path).ok()) {
+        let image = image.to_rgba();
+        let (width, height) = image.dimensions();
+        return Ok((width, height, image.into_raw()));
+    }
+    let image = image::open(path).map_err(|err| err.to_string())?;
+    let thumbnail = image.resize(size, size, image::FilterType::Triangle).to_rgba();
+    if let Some(cache_path) = cache_path {
+        // a thumbnail that can't be cached is generated again next time
+        thumbnail.save(&cache_path).ok();
+    }
+    let (width, height) = thumbnail.dimensions();
+    Ok((width, height, thumbnail.into_raw()))
+}
+
+/// The area of `widget` that isn't clipped by any of its ancestors, ex. a scroll container
+fn visible_rect(widget: &Widget) -> Option<Rect> {
+    let mut visible = widget.bounds();
+    let mut ancestor = widget.parent();
+    while let Some(widget) = ancestor {
+        visible = visible.intersection(&widget.bounds())?;
+        ancestor = widget.parent();
+    }
+    Some(visible)
+}
+
+multi_event!{impl EventHandler<ThumbnailGridInput> for ThumbnailGridHandler {
+    ThumbnailGridEvent => grid_event,
+    ThumbnailGenerated => generated,
+    DragEvent => drag,
+    ClickEvent => click,
+    LayoutUpdated => layout_updated,
+}}
+
+struct ThumbnailGridHandler {
+    name: String,
+    requests: mpsc::Sender<PathBuf>,
+    /// Requested, or failed to generate, so not requested again
+    pending: HashSet<PathBuf>,
+    loaded: HashMap<PathBuf, ImageSource>,
+    // least recently used first
+    cache: VecDeque<PathBuf>,
+    cache_size: usize,
+    next_image: usize,
+    content_height: Option<f32>,
+    size_constraints: Vec<Constraint>,
+    /// Where a drag started, relative to the top left of the grid
+    drag_start: Point,
+    /// Whether the current press became a rubber band selection, rather than a click
+    rubber_banded: bool,
+    anchor: Option<usize>,
+    last_click: Option<(usize, Instant)>,
+}
+
+impl ThumbnailGridHandler {
+    /// Fit the height of the grid to its rows, and request thumbnails that are visible
+    fn update_layout(&mut self, widget: &mut Widget) {
+        let bounds = widget.bounds();
+        let (content_height, min_width) = {
+            let draw_state = widget.draw_state();
+            let state = draw_state.downcast_ref::<ThumbnailGridState>().unwrap();
+            (state.content_height(bounds.width()), state.thumbnail_size + state.spacing * 2.0)
+        };
+        if self.content_height != Some(content_height) {
+            let constraints = min_size(Size::new(min_width, content_height)).build(&widget.layout_vars());
+            replace_constraints(widget, &mut self.size_constraints, constraints);
+            self.content_height = Some(content_height);
+        }
+        self.request_thumbnails(widget);
+    }
+
+    /// Request any visible thumbnails that aren't loaded or already requested, and mark the rest as recently used
+    fn request_thumbnails(&mut self, widget: &mut Widget) {
+        let bounds = widget.bounds();
+        let visible = match visible_rect(widget) {
+            Some(visible) => visible,
+            None => return,
+        };
+        let paths: Vec<PathBuf> = {
+            let draw_state = widget.draw_state();
+            let state = draw_state.downcast_ref::<ThumbnailGridState>().unwrap();
+            let overscan = OVERSCAN_ROWS as f32 * (state.thumbnail_size + state.spacing);
+            let visible = visible.inflate(0.0, overscan);
+            state.visible_range(bounds, visible).map(|index| state.thumbnails[index].path.clone()).collect()
+        };
+        for path in paths {
+            if self.loaded.contains_key(&path) {
+                if let Some(index) = self.cache.iter().position(|cached| *cached == path) {
+                    self.cache.remove(index);
+                    self.cache.push_back(path);
+                }
+            } else if self.pending.insert(path.clone()) {
+                self.requests.send(path).ok();
+            }
+        }
+    }
+
+    fn thumbnail(&self, path: &PathBuf) -> Thumbnail {
+        Thumbnail {
+            path: path.clone(),
+            image: self.loaded.get(path).cloned(),
+        }
+    }
+
+    fn grid_event(&mut self, event: &ThumbnailGridEvent, mut args: EventArgs) {
+        match *event {
+            ThumbnailGridEvent::SetImages(ref paths) => {
+                let thumbnails = paths.iter().map(|path| self.thumbnail(path)).collect();
+                args.widget.update(|state: &mut ThumbnailGridState| {
+                    state.thumbnails = thumbnails;
+                    state.selected.clear();
+                });
+                self.anchor = None;
+                self.last_click = None;
+            }
+            ThumbnailGridEvent::AddImages(ref paths) => {
+                let thumbnails: Vec<Thumbnail> = paths.iter().map(|path| self.thumbnail(path)).collect();
+                args.widget.update(|state: &mut ThumbnailGridState| state.thumbnails.extend(thumbnails));
+            }
+            ThumbnailGridEvent::SetSelection(ref indices) => {
+                self.select(indices.iter().cloned().collect(), &mut args.widget);
+            }
+        }
+        self.update_layout(&mut args.widget);
+    }
+
+    fn generated(&mut self, event: &ThumbnailGenerated, mut args: EventArgs) {
+        let ThumbnailGenerated(ref path, ref data) = *event;
+        let (width, height, data) = match *data {
+            Ok(ref data) => data.clone(),
+            // left pending, so an image that can't be read isn't requested again
+            Err(_) => return,
+        };
+        self.pending.remove(path);
+        let name = format!("{}_{}", self.name, self.next_image);
+        self.next_image += 1;
+        resources().image_loader.load_raw_image(&name, width, height, data);
+        let image = ImageSource::bundled(name);
+        self.loaded.insert(path.clone(), image.clone());
+        self.cache.push_back(path.clone());
+        let mut evicted = Vec::new();
+        while self.cache.len() > self.cache_size {
+            let old_path = self.cache.pop_front().unwrap();
+            if let Some(old_image) = self.loaded.remove(&old_path) {
+                resources().image_loader.remove_image(&old_image);
+            }
+            evicted.push(old_path);
+        }
+        args.widget.update(|state: &mut ThumbnailGridState| {
+            for thumbnail in &mut state.thumbnails {
+                if thumbnail.path == *path {
+                    thumbnail.image = Some(image.clone());
+                } else if evicted.contains(&thumbnail.path) {
+                    thumbnail.image = None;
+                }
+            }
+        });
+    }
+
+    fn select(&mut self, indices: BTreeSet<usize>, widget: &mut Widget) {
+        let mut paths = Vec::new();
+        widget.update(|state: &mut ThumbnailGridState| {
+            let len = state.thumbnails.len();
+            state.selected = indices.into_iter().filter(|&index| index < len).collect();
+            paths = state.selected.iter().map(|&index| state.thumbnails[index].path.clone()).collect();
+        });
+        widget.event(ThumbnailsSelected(paths));
+    }
+
+    fn drag(&mut self, event: &DragEvent, mut args: EventArgs) {
+        let bounds = args.widget.bounds();
+        let position = event.position - bounds.origin.to_vector();
+        match event.state {
+            DragState::Start => {
+                self.drag_start = position;
+                self.rubber_banded = false;
+            }
+            DragState::Moved => {
+                if !self.rubber_banded && event.offset.length() < DRAG_THRESHOLD {
+                    return;
+                }
+                self.rubber_banded = true;
+                let start = self.drag_start;
+                let origin = Point::new(start.x.min(position.x), start.y.min(position.y));
+                let size = Size::new((start.x - position.x).abs(), (start.y - position.y).abs());
+                let rubber_band = Rect::new(origin, size);
+                let (selected, changed) = {
+                    let draw_state = args.widget.draw_state();
+                    let state = draw_state.downcast_ref::<ThumbnailGridState>().unwrap();
+                    let area = Rect::new(bounds.origin + origin.to_vector(), size);
+                    let selected = state.indices_in(area, bounds);
+                    let changed = selected != state.selected;
+                    (selected, changed)
+                };
+                args.widget.update(|state: &mut ThumbnailGridState| state.rubber_band = Some(rubber_band));
+                if changed {
+                    self.anchor = selected.iter().next().cloned();
+                    self.select(selected, &mut args.widget);
+                }
+            }
+            DragState::End => {
+                if self.rubber_banded {
+                    args.widget.update(|state: &mut ThumbnailGridState| state.rubber_band = None);
+                }
+            }
+        }
+    }
+
+    fn click(&mut self, event: &ClickEvent, mut args: EventArgs) {
+        if self.rubber_banded {
+            return;
+        }
+        let bounds = args.widget.bounds();
+        let (index, selected) = {
+            let draw_state = args.widget.draw_state();
+            let state = draw_state.downcast_ref::<ThumbnailGridState>().unwrap();
+            (state.index_at(event.position, bounds), state.selected.clone())
+        };
+        let modifiers = event.modifiers;
+        let index = match index {
+            Some(index) => index,
+            None => {
+                // clicking between thumbnails clears the selection
+                if !(modifiers.ctrl || modifiers.logo || selected.is_empty()) {
+                    self.anchor = None;
+                    self.select(BTreeSet::new(), &mut args.widget);
+                }
+                return;
+            }
+        };
+        let now = clock::now();
+        let double_click = self.last_click.take().map_or(false, |(last_index, time)| {
+            last_index == index && now.duration_since(time) < Duration::from_millis(DOUBLE_CLICK_TIME)
+        });
+        self.last_click = Some((index, now));
+        if modifiers.ctrl || modifiers.logo {
+            let mut selected = selected;
+            if !selected.remove(&index) {
+                selected.insert(index);
+            }
+            self.select(selected, &mut args.widget);
+            self.anchor = Some(index);
+        } else if let (true, Some(anchor)) = (modifiers.shift, self.anchor) {
+            let range = if anchor < index { anchor..index + 1 } else { index..anchor + 1 };
+            self.select(range.collect(), &mut args.widget);
+        } else if double_click {
+            self.last_click = None;
+            let path = args.widget.draw_state().downcast_ref::<ThumbnailGridState>().unwrap().thumbnails[index].path.clone();
+            args.widget.event(ThumbnailActivated(path));
+        } else {
+            self.select(Some(index).into_iter().collect(), &mut args.widget);
+            self.anchor = Some(index);
+        }
+    }
+
+    fn layout_updated(&mut self, _: &LayoutUpdated, mut args: EventArgs) {
+        self.update_layout(&mut args.widget);
+    }
+}
+
+/// Builds a thumbnail grid, see the [module docs](index.html)
+#[derive(Debug, Clone)]
+pub struct ThumbnailGridBuilder {
+    images: Vec<PathBuf>,
+    thumbnail_size: f32,
+    spacing: f32,
+    cache_size: usize,
+    cache_dir: Option<PathBuf>,
+    workers: usize,
+}
+
+impl Default for ThumbnailGridBuilder {
+    fn default() -> Self {
+        ThumbnailGridBuilder {
+            images: Vec::new(),
+            thumbnail_size: 128.0,
+            spacing: 8.0,
+            cache_size: 500,
+            cache_dir: None,
+            workers: 2,
+        }
+    }
+}
+
+impl ThumbnailGridBuilder {
+    pub fn new() -> Self {
+        Self::default()
+    }
+    pub fn add_image<P: Into<PathBuf>>(&mut self, path: P) -> &mut Self {
+        self.images.push(path.into());
+        self
+    }
+    pub fn add_images<I: IntoIterator<Item = PathBuf>>(&mut self, paths: I) -> &mut Self {
+        self.images.extend(paths);
+        self
+    }
+    /// Width and height of the square each thumbnail is fit into, 128 by default
+    pub fn set_thumbnail_size(&mut self, size: f32) -> &mut Self {
+        self.thumbnail_size = size;
+        self
+    }
+    /// Least space between thumbnails
+    pub fn set_spacing(&mut self, spacing: f32) -> &mut Self {
+        self.spacing = spacing;
+        self
+    }
+    /// Number of thumbnails kept loaded, those least recently visible are freed first
+    pub fn set_cache_size(&mut self, cache_size: usize) -> &mut Self {
+        self.cache_size = cache_size;
+        self
+    }
+    /// Save generated thumbnails in `dir`, and read them back rather than generating them again.
+    /// Requires the `png` feature.
+    pub fn set_cache_dir<P: Into<PathBuf>>(&mut self, dir: P) -> &mut Self {
+        self.cache_dir = Some(dir.into());
+        self
+    }
+    /// Number of threads generating thumbnails
+    pub fn set_workers(&mut self, workers: usize) -> &mut Self {
+        self.workers = workers.max(1);
+        self
+    }
+
+    pub fn build(&self) -> Widget {
+        let mut widget = Widget::new("thumbnail_grid");
+        let mut state = ThumbnailGridState::new();
+        state.thumbnails = self.images.iter().cloned().map(Thumbnail::new).collect();
+        state.thumbnail_size = self.thumbnail_size;
+        state.spacing = self.spacing;
+        widget.set_draw_state(state);
+
+        if let Some(ref cache_dir) = self.cache_dir {
+            fs::create_dir_all(cache_dir).ok();
+        }
+        let (requests, receiver) = mpsc::channel::<PathBuf>();
+        let receiver = Arc::new(Mutex::new(receiver));
+        let widget_id = widget.id();
+        let size = self.thumbnail_size.ceil() as u32;
+        for _ in 0..self.workers {
+            let (receiver, cache_dir) = (receiver.clone(), self.cache_dir.clone());
+            thread::spawn(move || {
+                let sender = EventSender;
+                loop {
+                    // the channel closes when the handler is dropped along with the widget
+                    let path = match receiver.lock().unwrap().recv() {
+                        Ok(path) => path,
+                        Err(_) => return,
+                    };
+                    let thumbnail = generate_thumbnail(&path, size, cache_dir.as_ref().map(|dir| dir.as_path()));
+                    sender.widget_event(widget_id, ThumbnailGenerated(path, thumbnail));
+                }
+            });
+        }
+
+        let mut handler = ThumbnailGridHandler {
+            name: format!("thumbnail_grid_{}", widget_id.0),
+            requests: requests,
+            pending: HashSet::new(),
+            loaded: HashMap::new(),
+            cache: VecDeque::new(),
+            cache_size: self.cache_size,
+            next_image: 0,
+            content_height: None,
+            size_constraints: Vec::new(),
+            drag_start: Point::zero(),
+            rubber_banded: false,
+            anchor: None,
+            last_click: None,
+        };
+        handler.update_layout(&mut widget);
+        widget.add_handler(handler);
+        ThumbnailGridHandler::add_adapters(&mut widget);
+        widget.make_draggable();
+        widget
+    }
+}
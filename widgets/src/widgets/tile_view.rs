@@ -0,0 +1,287 @@
+//! A pannable, zoomable view of a slippy map style tile pyramid.
+//!
+//! Tiles are fetched by a `TileProvider` on background threads, and the decoded images are kept
+//! in an LRU cache of bounded size. Newly loaded tiles fade in. Drag to pan, use the mouse wheel
+//! to zoom, or send `TileViewEvent`s to the widget.
+
+use std::collections::{HashSet, VecDeque};
+use std::f64::consts::PI;
+use std::sync::{Arc, Mutex, mpsc};
+use std::thread;
+
+use clock;
+use prelude::*;
+use draw::prelude::*;
+use draw::tile_view::LoadedTile;
+
+/// The image data for a tile, returned by a `TileProvider`.
+#[derive(Debug, Clone)]
+pub enum TileData {
+    /// An image file, ex. a PNG downloaded from a tile server
+    Encoded(Vec<u8>),
+    /// Non-premultiplied RGBA8 pixels, with the tile size as dimensions
+    Rgba(Vec<u8>),
+}
+
+/// Source of tile images. `fetch` is called on a worker thread, so it can block.
+pub trait TileProvider: Send + Sync {
+    fn fetch(&self, tile: TileCoord) -> Result<TileData, String>;
+    /// Width and height of each tile in pixels
+    fn tile_size(&self) -> u32 {
+        256
+    }
+    fn max_zoom(&self) -> u32 {
+        18
+    }
+}
+
+/// Convert latitude and longitude in degrees to world coordinates, using the web mercator projection
+pub fn lat_lon_to_world(lat: f64, lon: f64) -> (f64, f64) {
+    let lat = lat.max(-85.0511).min(85.0511).to_radians();
+    let x = (lon + 180.0) / 360.0;
+    let y = (1.0 - (lat.tan() + 1.0 / lat.cos()).ln() / PI) / 2.0;
+    (x, y)
+}
+
+/// Convert world coordinates to latitude and longitude in degrees
+pub fn world_to_lat_lon(world: (f64, f64)) -> (f64, f64) {
+    let lon = world.0 * 360.0 - 180.0;
+    let lat = (PI * (1.0 - 2.0 * world.1)).sinh().atan().to_degrees();
+    (lat, lon)
+}
+
+/// Controls the view, send to the tile view widget.
+#[derive(Debug, Copy, Clone)]
+pub enum TileViewEvent {
+    /// Center on a latitude and longitude, in degrees
+    SetCenter(f64, f64),
+    SetZoom(f32),
+}
+
+/// Sent to the tile view widget when the view changes, with the new center latitude and longitude, and zoom.
+#[derive(Debug, Copy, Clone)]
+pub struct TileViewChanged {
+    pub center: (f64, f64),
+    pub zoom: f32,
+}
+
+#[derive(Debug, Clone)]
+struct TileLoaded(TileCoord, Result<TileData, String>);
+
+struct TileViewHandler {
+    name: String,
+    tile_size: u32,
+    requests: mpsc::Sender<TileCoord>,
+    pending: HashSet<TileCoord>,
+    // least recently used first
+    cache: VecDeque<TileCoord>,
+    cache_size: usize,
+}
+
+multi_event!{impl EventHandler<TileViewInput> for TileViewHandler {
+    TileViewEvent => view_event,
+    TileLoaded => tile_loaded,
+    DragEvent => drag,
+    WidgetMouseWheel => mouse_wheel,
+    LayoutUpdated => layout_updated,
+    FrameEvent => frame_event,
+}}
+
+impl TileViewHandler {
+    fn image_source(&self, tile: TileCoord) -> ImageSource {
+        ImageSource::bundled(format!("{}_{}_{}_{}", self.name, tile.zoom, tile.x, tile.y))
+    }
+
+    /// Request any visible tiles that aren't loaded or already requested, and mark the rest as recently used
+    fn request_tiles(&mut self, widget: &mut Widget) {
+        let bounds = widget.bounds();
+        let (visible, loaded) = {
+            let draw_state = widget.draw_state();
+            let state = draw_state.downcast_ref::<TileViewState>().unwrap();
+            let visible = state.visible_tiles(bounds);
+            let loaded: Vec<bool> = visible.iter().map(|tile| state.tiles.contains_key(tile)).collect();
+            (visible, loaded)
+        };
+        for (tile, loaded) in visible.into_iter().zip(loaded) {
+            if loaded {
+                if let Some(index) = self.cache.iter().position(|cached| *cached == tile) {
+                    self.cache.remove(index);
+                    self.cache.push_back(tile);
+                }
+            } else if self.pending.insert(tile) {
+                self.requests.send(tile).ok();
+            }
+        }
+    }
+
+    fn view_changed(&mut self, mut widget: Widget) {
+        let (center, zoom) = {
+            let draw_state = widget.draw_state();
+            let state = draw_state.downcast_ref::<TileViewState>().unwrap();
+            (world_to_lat_lon(state.center), state.zoom)
+        };
+        self.request_tiles(&mut widget);
+        widget.event(TileViewChanged { center: center, zoom: zoom });
+    }
+
+    fn view_event(&mut self, event: &TileViewEvent, mut args: EventArgs) {
+        match *event {
+            TileViewEvent::SetCenter(lat, lon) => {
+                args.widget.update(|state: &mut TileViewState| state.center = lat_lon_to_world(lat, lon));
+            }
+            TileViewEvent::SetZoom(zoom) => {
+                args.widget.update(|state: &mut TileViewState| {
+                    state.zoom = zoom.max(0.0).min(state.max_zoom as f32);
+                });
+            }
+        }
+        self.view_changed(args.widget);
+    }
+
+    fn tile_loaded(&mut self, event: &TileLoaded, mut args: EventArgs) {
+        let TileLoaded(tile, ref data) = *event;
+        self.pending.remove(&tile);
+        let source = self.image_source(tile);
+        let name = match source {
+            ImageSource::Bundled(ref name) => name.clone(),
+            _ => unreachable!(),
+        };
+        match *data {
+            Ok(TileData::Encoded(ref data)) => resources().image_loader.load_image(&name, data.clone()),
+            Ok(TileData::Rgba(ref data)) => {
+                resources().image_loader.load_raw_image(&name, self.tile_size, self.tile_size, data.clone())
+            }
+            Err(_) => return,
+        }
+        self.cache.push_back(tile);
+        let mut evicted = Vec::new();
+        while self.cache.len() > self.cache_size {
+            let old_tile = self.cache.pop_front().unwrap();
+            resources().image_loader.remove_image(&self.image_source(old_tile));
+            evicted.push(old_tile);
+        }
+        args.widget.update(|state: &mut TileViewState| {
+            for old_tile in &evicted {
+                state.tiles.remove(old_tile);
+            }
+            state.tiles.insert(tile, LoadedTile { image: source, loaded: clock::now() });
+        });
+    }
+
+    fn drag(&mut self, event: &DragEvent, mut args: EventArgs) {
+        let change = event.change;
+        args.widget.update(|state: &mut TileViewState| {
+            let world_size = state.world_size();
+            state.center.0 = (state.center.0 - change.x as f64 / world_size).max(0.0).min(1.0);
+            state.center.1 = (state.center.1 - change.y as f64 / world_size).max(0.0).min(1.0);
+        });
+        self.view_changed(args.widget);
+    }
+
+    fn mouse_wheel(&mut self, event: &WidgetMouseWheel, mut args: EventArgs) {
+        let delta = match event.0 {
+            glutin::MouseScrollDelta::LineDelta(_, y) => y * 0.25,
+            glutin::MouseScrollDelta::PixelDelta(glutin::dpi::LogicalPosition{y, ..}) => y as f32 / 50.0,
+        };
+        args.widget.update(|state: &mut TileViewState| {
+            state.zoom = (state.zoom + delta).max(0.0).min(state.max_zoom as f32);
+        });
+        self.view_changed(args.widget);
+    }
+
+    fn layout_updated(&mut self, _: &LayoutUpdated, mut args: EventArgs) {
+        self.request_tiles(&mut args.widget);
+    }
+
+    fn frame_event(&mut self, _: &FrameEvent, mut args: EventArgs) {
+        let bounds = args.widget.bounds();
+        let fading = args.widget.draw_state().downcast_ref::<TileViewState>()
+            .map_or(false, |state| state.is_fading(bounds));
+        if fading {
+            args.ui.redraw();
+        }
+    }
+}
+
+/// Builds a tile view widget for a `TileProvider`.
+pub struct TileViewBuilder {
+    provider: Arc<TileProvider>,
+    center: (f64, f64),
+    zoom: f32,
+    cache_size: usize,
+    workers: usize,
+}
+
+impl TileViewBuilder {
+    pub fn new<P: TileProvider + 'static>(provider: P) -> Self {
+        TileViewBuilder {
+            provider: Arc::new(provider),
+            center: (0.5, 0.5),
+            zoom: 1.0,
+            cache_size: 256,
+            workers: 4,
+        }
+    }
+    /// Set the initial center, as latitude and longitude in degrees
+    pub fn set_center(&mut self, lat: f64, lon: f64) -> &mut Self {
+        self.center = lat_lon_to_world(lat, lon);
+        self
+    }
+    pub fn set_zoom(&mut self, zoom: f32) -> &mut Self {
+        self.zoom = zoom;
+        self
+    }
+    /// Maximum number of tiles kept loaded
+    pub fn set_cache_size(&mut self, cache_size: usize) -> &mut Self {
+        self.cache_size = cache_size;
+        self
+    }
+    /// Number of threads fetching tiles concurrently
+    pub fn set_workers(&mut self, workers: usize) -> &mut Self {
+        self.workers = ::std::cmp::max(workers, 1);
+        self
+    }
+
+    /// Create the tile view widget
+    pub fn build(&self) -> Widget {
+        let mut widget = Widget::new("tile_view");
+        let mut state = TileViewState::default();
+        state.center = self.center;
+        state.tile_size = self.provider.tile_size();
+        state.max_zoom = self.provider.max_zoom();
+        state.zoom = self.zoom.max(0.0).min(state.max_zoom as f32);
+        widget.set_draw_state(state);
+
+        let (requests, receiver) = mpsc::channel::<TileCoord>();
+        let receiver = Arc::new(Mutex::new(receiver));
+        let widget_id = widget.id();
+        for _ in 0..self.workers {
+            let (provider, receiver) = (self.provider.clone(), receiver.clone());
+            thread::spawn(move || {
+                let sender = EventSender;
+                loop {
+                    // the channel closes when the handler is dropped along with the widget
+                    let tile = match receiver.lock().unwrap().recv() {
+                        Ok(tile) => tile,
+                        Err(_) => return,
+                    };
+                    sender.widget_event(widget_id, TileLoaded(tile, provider.fetch(tile)));
+                }
+            });
+        }
+
+        widget.add_handler(TileViewHandler {
+            name: format!("tile_view_{}", widget_id.0),
+            tile_size: self.provider.tile_size(),
+            requests: requests,
+            pending: HashSet::new(),
+            cache: VecDeque::new(),
+            cache_size: self.cache_size,
+        });
+        TileViewHandler::add_adapters(&mut widget);
+        widget
+            .make_draggable()
+            .receive_frame_events();
+        widget
+    }
+}
@@ -0,0 +1,509 @@
+//! A track editor, for arranging clips in time, ex. for video, audio or animation tools.
+//!
+//! Clips can be dragged along and between tracks, or trimmed by dragging either edge. While dragging,
+//! edges snap to the grid and to the edges of other clips and the playhead. Results are reported
+//! to the timeline widget as `ClipSelected`, `ClipMoved` and `ClipTrimmed` events.
+//!
+//! The playhead is advanced every frame while playing, it can be controlled by sending
+//! `TimelineEvent`s to the timeline widget.
+
+use std::time::Instant;
+
+use clock;
+use prelude::*;
+use draw::prelude::*;
+use widgets::scroll::ScrollContainer;
+use widgets::text::StaticTextStyle;
+use widgets::replace_constraints;
+
+const RULER_HEIGHT: f32 = 20.0;
+const TRACK_HEIGHT: f32 = 40.0;
+const HEADER_WIDTH: f32 = 100.0;
+/// Width of the area at each end of a clip that trims rather than moves it
+const TRIM_HANDLE_WIDTH: f32 = 6.0;
+/// Distance in pixels within which edges snap to other clips and the playhead
+const SNAP_DISTANCE: f32 = 8.0;
+const MIN_CLIP_DURATION: f32 = 0.1;
+
+pub type ClipId = usize;
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Clip {
+    pub track: usize,
+    /// Start time, in seconds
+    pub start: f32,
+    /// Duration, in seconds
+    pub duration: f32,
+    pub label: String,
+}
+
+impl Clip {
+    pub fn new(track: usize, start: f32, duration: f32, label: &str) -> Self {
+        Clip {
+            track: track,
+            start: start,
+            duration: duration,
+            label: label.to_owned(),
+        }
+    }
+    pub fn end(&self) -> f32 {
+        self.start + self.duration
+    }
+}
+
+/// Controls playback and zoom, send to the timeline widget.
+#[derive(Debug, Copy, Clone)]
+pub enum TimelineEvent {
+    Play,
+    Pause,
+    /// Move the playhead to a time, in seconds
+    Seek(f32),
+    /// Set the horizontal scale, in pixels per second
+    SetZoom(f32),
+}
+
+#[derive(Debug, Copy, Clone)]
+pub struct ClipSelected(pub ClipId);
+#[derive(Debug, Copy, Clone)]
+pub struct ClipMoved {
+    pub id: ClipId,
+    pub track: usize,
+    pub start: f32,
+}
+#[derive(Debug, Copy, Clone)]
+pub struct ClipTrimmed {
+    pub id: ClipId,
+    pub start: f32,
+    pub duration: f32,
+}
+/// Sent to the timeline widget as the playhead moves.
+#[derive(Debug, Copy, Clone)]
+pub struct PlayheadMoved(pub f32);
+
+#[derive(Debug, Copy, Clone, PartialEq)]
+enum DragMode {
+    Move,
+    TrimStart,
+    TrimEnd,
+}
+
+#[derive(Debug, Copy, Clone)]
+enum ClipInput {
+    Drag(ClipId, DragEvent),
+    Click(ClipId),
+}
+
+struct ClipWidget {
+    clip: Clip,
+    widget: Widget,
+    constraints: Vec<Constraint>,
+    drag: Option<(DragMode, Clip)>,
+}
+
+struct TimelineHandler {
+    content: Widget,
+    ruler: Widget,
+    playhead: Widget,
+    rows: Vec<Widget>,
+    clips: Vec<ClipWidget>,
+    selected: Option<ClipId>,
+    length: f32,
+    pixels_per_second: f32,
+    snap: f32,
+    content_constraints: Vec<Constraint>,
+    playhead_constraints: Vec<Constraint>,
+    position: f32,
+    playing: Option<Instant>,
+}
+
+multi_event!{impl EventHandler<TimelineInput> for TimelineHandler {
+    TimelineEvent => timeline_event,
+    ClipInput => clip_input,
+    FrameEvent => frame_event,
+}}
+
+impl TimelineHandler {
+    fn layout_content(&mut self) {
+        let vars = self.content.layout_vars();
+        let constraints = vec![
+            vars.width | EQ(REQUIRED) | self.length * self.pixels_per_second,
+            vars.height | EQ(REQUIRED) | RULER_HEIGHT + self.rows.len() as f32 * TRACK_HEIGHT,
+        ];
+        replace_constraints(&mut self.content, &mut self.content_constraints, constraints);
+        let pixels_per_second = self.pixels_per_second;
+        self.ruler.update(|state: &mut RulerState| state.pixels_per_second = pixels_per_second);
+        for id in 0..self.clips.len() {
+            self.layout_clip(id);
+        }
+        self.layout_playhead();
+    }
+
+    fn layout_clip(&mut self, id: ClipId) {
+        let content = self.content.layout_vars();
+        let clip = &mut self.clips[id];
+        let row = self.rows[clip.clip.track].layout_vars();
+        let vars = clip.widget.layout_vars();
+        let constraints = vec![
+            vars.left - content.left | EQ(REQUIRED) | clip.clip.start * self.pixels_per_second,
+            vars.width | EQ(REQUIRED) | clip.clip.duration * self.pixels_per_second,
+            vars.top - row.top | EQ(REQUIRED) | 3.0,
+            row.bottom - vars.bottom | EQ(REQUIRED) | 3.0,
+        ];
+        replace_constraints(&mut clip.widget, &mut clip.constraints, constraints);
+    }
+
+    fn layout_playhead(&mut self) {
+        let content = self.content.layout_vars();
+        let vars = self.playhead.layout_vars();
+        let constraints = vec![
+            vars.left - content.left | EQ(REQUIRED) | self.position * self.pixels_per_second,
+        ];
+        replace_constraints(&mut self.playhead, &mut self.playhead_constraints, constraints);
+    }
+
+    fn seek(&mut self, position: f32, timeline: &Widget) {
+        self.position = position.max(0.0).min(self.length);
+        self.layout_playhead();
+        timeline.event(PlayheadMoved(self.position));
+    }
+
+    /// Snap a time to nearby clip edges or the playhead, otherwise to the grid
+    fn snap(&self, time: f32, exclude: ClipId) -> f32 {
+        let threshold = SNAP_DISTANCE / self.pixels_per_second;
+        let mut edges = vec![self.position];
+        for (id, clip) in self.clips.iter().enumerate() {
+            if id != exclude {
+                edges.push(clip.clip.start);
+                edges.push(clip.clip.end());
+            }
+        }
+        let nearest = edges.into_iter()
+            .map(|edge| (edge, (edge - time).abs()))
+            .filter(|&(_, distance)| distance < threshold)
+            .min_by(|a, b| a.1.partial_cmp(&b.1).unwrap());
+        match nearest {
+            Some((edge, _)) => edge,
+            None if self.snap > 0.0 => (time / self.snap).round() * self.snap,
+            None => time,
+        }
+    }
+
+    fn timeline_event(&mut self, event: &TimelineEvent, args: EventArgs) {
+        match *event {
+            TimelineEvent::Play => {
+                if self.position >= self.length {
+                    self.position = 0.0;
+                }
+                self.playing = Some(clock::now());
+                args.ui.redraw();
+            }
+            TimelineEvent::Pause => self.playing = None,
+            TimelineEvent::Seek(position) => self.seek(position, &args.widget),
+            TimelineEvent::SetZoom(pixels_per_second) => {
+                self.pixels_per_second = pixels_per_second.max(1.0);
+                self.layout_content();
+            }
+        }
+    }
+
+    fn frame_event(&mut self, _: &FrameEvent, args: EventArgs) {
+        if let Some(last_tick) = self.playing {
+            let now = clock::now();
+            let elapsed = now.duration_since(last_tick);
+            let elapsed = elapsed.as_secs() as f32 + elapsed.subsec_nanos() as f32 / 1_000_000_000.0;
+            let position = self.position + elapsed;
+            self.seek(position, &args.widget);
+            self.playing = if self.position < self.length { Some(now) } else { None };
+        }
+    }
+
+    fn select(&mut self, id: ClipId, timeline: &Widget) {
+        if self.selected == Some(id) {
+            return;
+        }
+        if let Some(selected) = self.selected {
+            self.clips[selected].widget.remove_prop(Property::Selected);
+        }
+        self.clips[id].widget.add_prop(Property::Selected);
+        self.selected = Some(id);
+        timeline.event(ClipSelected(id));
+    }
+
+    fn clip_input(&mut self, event: &ClipInput, args: EventArgs) {
+        match *event {
+            ClipInput::Click(id) => self.select(id, &args.widget),
+            ClipInput::Drag(id, ref drag) => self.drag(id, drag, &args.widget),
+        }
+    }
+
+    fn drag(&mut self, id: ClipId, event: &DragEvent, timeline: &Widget) {
+        match event.state {
+            DragState::Start => {
+                self.select(id, timeline);
+                let clip = &mut self.clips[id];
+                let bounds = clip.widget.bounds();
+                let mode = if event.position.x < bounds.left() + TRIM_HANDLE_WIDTH {
+                    DragMode::TrimStart
+                } else if event.position.x > bounds.right() - TRIM_HANDLE_WIDTH {
+                    DragMode::TrimEnd
+                } else {
+                    DragMode::Move
+                };
+                clip.drag = Some((mode, clip.clip.clone()));
+            }
+            DragState::Moved => {
+                let (mode, original) = match self.clips[id].drag.clone() {
+                    Some(drag) => drag,
+                    None => return,
+                };
+                let delta = event.offset.x / self.pixels_per_second;
+                let mut clip = original.clone();
+                match mode {
+                    DragMode::Move => {
+                        let tracks = (event.offset.y / TRACK_HEIGHT).round() as isize;
+                        let track = original.track as isize + tracks;
+                        clip.track = track.max(0).min(self.rows.len() as isize - 1) as usize;
+                        // snap whichever edge is closer to something to snap to
+                        let start = self.snap(original.start + delta, id);
+                        let end = self.snap(original.end() + delta, id);
+                        clip.start = if (start - (original.start + delta)).abs() <= (end - (original.end() + delta)).abs() {
+                            start
+                        } else {
+                            end - original.duration
+                        };
+                        clip.start = clip.start.max(0.0).min(self.length - clip.duration);
+                    }
+                    DragMode::TrimStart => {
+                        let start = self.snap(original.start + delta, id);
+                        clip.start = start.max(0.0).min(original.end() - MIN_CLIP_DURATION);
+                        clip.duration = original.end() - clip.start;
+                    }
+                    DragMode::TrimEnd => {
+                        let end = self.snap(original.end() + delta, id);
+                        clip.duration = (end.min(self.length) - original.start).max(MIN_CLIP_DURATION);
+                    }
+                }
+                if clip != self.clips[id].clip {
+                    self.clips[id].clip = clip;
+                    self.layout_clip(id);
+                }
+            }
+            DragState::End => {
+                if let Some((mode, original)) = self.clips[id].drag.take() {
+                    let clip = &self.clips[id].clip;
+                    if *clip == original {
+                        return;
+                    }
+                    if mode == DragMode::Move {
+                        timeline.event(ClipMoved { id: id, track: clip.track, start: clip.start });
+                    } else {
+                        timeline.event(ClipTrimmed { id: id, start: clip.start, duration: clip.duration });
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// Builds a timeline widget from a list of tracks and clips.
+#[derive(Debug, Clone)]
+pub struct TimelineBuilder {
+    tracks: Vec<String>,
+    clips: Vec<Clip>,
+    length: Option<f32>,
+    pixels_per_second: f32,
+    snap: f32,
+}
+
+impl Default for TimelineBuilder {
+    fn default() -> Self {
+        TimelineBuilder {
+            tracks: Vec::new(),
+            clips: Vec::new(),
+            length: None,
+            pixels_per_second: 50.0,
+            snap: 0.25,
+        }
+    }
+}
+
+impl TimelineBuilder {
+    pub fn new() -> Self {
+        Self::default()
+    }
+    /// Add a track, returns the index of the track
+    pub fn add_track(&mut self, name: &str) -> usize {
+        self.tracks.push(name.to_owned());
+        self.tracks.len() - 1
+    }
+    /// Add a clip, returns the `ClipId` used to identify it in events
+    pub fn add_clip(&mut self, clip: Clip) -> ClipId {
+        assert!(clip.track < self.tracks.len(), "clip added to a track that doesn't exist");
+        self.clips.push(clip);
+        self.clips.len() - 1
+    }
+    /// Set the total length in seconds, by default this is a bit longer than the last clip
+    pub fn set_length(&mut self, length: f32) -> &mut Self {
+        self.length = Some(length);
+        self
+    }
+    /// Set the initial horizontal scale
+    pub fn set_zoom(&mut self, pixels_per_second: f32) -> &mut Self {
+        self.pixels_per_second = pixels_per_second;
+        self
+    }
+    /// Set the grid interval in seconds that clip edges snap to, or 0.0 to disable
+    pub fn set_snap(&mut self, snap: f32) -> &mut Self {
+        self.snap = snap;
+        self
+    }
+
+    /// Create the timeline widget
+    pub fn build(&self) -> Widget {
+        let mut widget = Widget::new("timeline");
+        let length = self.length.unwrap_or_else(|| {
+            self.clips.iter().map(Clip::end).fold(0.0, f32::max) + 10.0
+        });
+
+        let mut headers = Widget::new("timeline_headers");
+        headers.layout().add(constraints![
+            align_top(&widget).padding(RULER_HEIGHT),
+            align_left(&widget),
+            width(HEADER_WIDTH),
+        ]);
+        let mut content = Widget::new("timeline_content");
+        let mut ruler = Widget::new("timeline_ruler");
+        ruler.set_draw_state(RulerState::default());
+        ruler.layout().add(constraints![
+            align_top(&content),
+            align_left(&content),
+            align_right(&content),
+            height(RULER_HEIGHT),
+        ]);
+        content.add_child(ruler.clone());
+
+        let mut rows = Vec::new();
+        for (index, name) in self.tracks.iter().enumerate() {
+            let top = RULER_HEIGHT + index as f32 * TRACK_HEIGHT;
+            let mut header = Widget::from_modifier_style(StaticTextStyle::from_style(style!(TextStyle {
+                text: name.clone(),
+                font_size: 16.0,
+            })));
+            header.set_name("track_header");
+            header.layout().add(constraints![
+                align_left(&headers).padding(5.0),
+                align_top(&widget).padding(top + 10.0),
+            ]);
+            headers.add_child(header);
+
+            let mut row = Widget::new("track");
+            row.set_draw_style(style!(RectStyle {
+                background_color: if index % 2 == 0 { GRAY_80 } else { GRAY_70 },
+            }));
+            row.layout().add(constraints![
+                align_left(&content),
+                align_right(&content),
+                align_top(&content).padding(top),
+                height(TRACK_HEIGHT),
+            ]);
+            content.add_child(row.clone());
+            rows.push(row);
+        }
+
+        let mut clips = Vec::new();
+        for (id, clip) in self.clips.iter().enumerate() {
+            let mut clip_widget = Widget::new("clip");
+            let mut draw_style = DrawStyle::from(style!(RectStyle {
+                background_color: BLUE_HIGHLIGHT,
+                corner_radius: Some(4.0),
+                border: Some((1.0, GRAY_30)),
+            }));
+            draw_style.prop_style(SELECTED.clone(), style!(RectStyle {
+                border: Some((2.0, WHITE)),
+            }));
+            clip_widget.set_draw_style(draw_style);
+            let mut label = Widget::from_modifier_style(StaticTextStyle::from_style(style!(TextStyle {
+                text: clip.label.clone(),
+                font_size: 14.0,
+            })));
+            label.layout().add(constraints![
+                align_left(&clip_widget).padding(TRIM_HANDLE_WIDTH),
+                center_vertical(&clip_widget),
+            ]);
+            clip_widget.add_child(label);
+            let timeline = widget.clone();
+            clip_widget.add_handler(move |event: &DragEvent, _: EventArgs| {
+                timeline.event(ClipInput::Drag(id, *event));
+            });
+            let timeline = widget.clone();
+            clip_widget.add_handler(move |_: &ClickEvent, args: EventArgs| {
+                timeline.event(ClipInput::Click(id));
+                *args.handled = true;
+            });
+            clip_widget.make_draggable();
+            content.add_child(clip_widget.clone());
+            clips.push(ClipWidget {
+                clip: clip.clone(),
+                widget: clip_widget,
+                constraints: Vec::new(),
+                drag: None,
+            });
+        }
+
+        let mut playhead = Widget::new("playhead");
+        playhead.set_draw_style(style!(RectStyle {
+            background_color: RED,
+        }));
+        playhead.layout().add(constraints![
+            align_top(&content),
+            align_bottom(&content),
+            width(2.0),
+        ]);
+        content.add_child(playhead.clone());
+
+        // clicking the ruler moves the playhead
+        let timeline = widget.clone();
+        let content_ref = content.clone();
+        let pixels_per_second = self.pixels_per_second;
+        ruler.add_handler(move |event: &ClickEvent, mut args: EventArgs| {
+            let pixels_per_second = args.widget.draw_state().downcast_ref::<RulerState>()
+                .map_or(pixels_per_second, |state| state.pixels_per_second);
+            let position = (event.position.x - content_ref.bounds().left()) / pixels_per_second;
+            timeline.event(TimelineEvent::Seek(position));
+        });
+
+        let mut scroll_widget = ScrollContainer::default();
+        scroll_widget.add_scrollbar().add_content(content.clone());
+        let mut scroll_widget = Widget::from_modifier(scroll_widget);
+        scroll_widget.layout().add(constraints![
+            align_top(&widget),
+            to_right_of(&headers),
+            align_right(&widget),
+            align_bottom(&widget),
+        ]);
+
+        let mut handler = TimelineHandler {
+            content: content,
+            ruler: ruler,
+            playhead: playhead,
+            rows: rows,
+            clips: clips,
+            selected: None,
+            length: length,
+            pixels_per_second: self.pixels_per_second,
+            snap: self.snap,
+            content_constraints: Vec::new(),
+            playhead_constraints: Vec::new(),
+            position: 0.0,
+            playing: None,
+        };
+        handler.layout_content();
+        widget.add_handler(handler);
+        TimelineHandler::add_adapters(&mut widget);
+        widget.receive_frame_events();
+        widget
+            .add_child(headers)
+            .add_child(scroll_widget);
+        widget
+    }
+}
@@ -0,0 +1,285 @@
+//! Guided tours, stepping through a sequence of widgets with an explanation of each.
+//!
+//! While a step is shown everything but its widget is dimmed, and a bubble beside the widget
+//! explains it, with buttons to go to the next step or skip the rest of the tour.
+//! Steps refer to widgets by name, so a tour can be described before the widgets exist,
+//! steps whose widget can't be found when they are reached are skipped.
+
+use prelude::*;
+use draw::prelude::*;
+use widgets::button::ButtonStyle;
+use widgets::edit_text::TextUpdated;
+use widgets::text::StaticTextStyle;
+use widgets::replace_constraints;
+
+const BUBBLE_WIDTH: f32 = 300.0;
+const BUBBLE_PADDING: f32 = 12.0;
+/// Space between the bubble and the highlighted widget
+const BUBBLE_OFFSET: f32 = 14.0;
+
+#[derive(Debug, Clone)]
+pub struct TourStep {
+    /// Name of the widget to highlight, the first match below the root is used
+    pub target: String,
+    pub title: String,
+    pub text: String,
+}
+
+impl TourStep {
+    pub fn new(target: &str, title: &str, text: &str) -> Self {
+        TourStep {
+            target: target.to_owned(),
+            title: title.to_owned(),
+            text: text.to_owned(),
+        }
+    }
+}
+
+/// Send to the tour widget
+#[derive(Debug, Copy, Clone)]
+pub enum TourEvent {
+    /// Show the first step, or restart from it
+    Start,
+    Next,
+    /// End the tour without showing the remaining steps
+    Skip,
+}
+
+/// Sent to the tour widget when a step is shown, with the index of the step
+#[derive(Debug, Copy, Clone)]
+pub struct TourStepShown(pub usize);
+
+/// Sent to the tour widget when the tour ends, `skipped` if it ended before the last step
+#[derive(Debug, Copy, Clone)]
+pub struct TourFinished {
+    pub skipped: bool,
+}
+
+multi_event!{impl EventHandler<TourInput> for TourHandler {
+    TourEvent => tour_event,
+    LayoutUpdated => layout_updated,
+}}
+
+struct TourHandler {
+    steps: Vec<TourStep>,
+    current: Option<usize>,
+    target: Option<Widget>,
+    overlay: Widget,
+    bubble: Widget,
+    bubble_constraints: Vec<Constraint>,
+    title: Widget,
+    text: Widget,
+    text_constraints: Vec<Constraint>,
+    progress: Widget,
+    next_button: Widget,
+}
+
+impl TourHandler {
+    fn tour_event(&mut self, event: &TourEvent, args: EventArgs) {
+        match *event {
+            TourEvent::Start => {
+                self.overlay.layout().show();
+                self.show_step(0, args);
+            }
+            TourEvent::Next => {
+                if let Some(current) = self.current {
+                    self.show_step(current + 1, args);
+                }
+            }
+            TourEvent::Skip => {
+                if self.current.is_some() {
+                    self.finish(true);
+                }
+            }
+        }
+    }
+
+    fn layout_updated(&mut self, _: &LayoutUpdated, _: EventArgs) {
+        // the window was resized, the highlighted widget may have moved
+        let area = self.overlay.bounds();
+        self.update_cutout(area);
+    }
+
+    /// Show the first step from `index` onwards whose widget exists, or end the tour if there are none
+    fn show_step(&mut self, index: usize, args: EventArgs) {
+        let root = args.ui.get_root();
+        let found = self.steps.iter().enumerate().skip(index).filter_map(|(index, step)| {
+            root.find_descendant(&step.target).map(|target| (index, target))
+        }).next();
+        let (index, target) = match found {
+            Some(found) => found,
+            None => {
+                let skipped = self.current.is_none();
+                self.finish(skipped);
+                return;
+            }
+        };
+        self.current = Some(index);
+        self.target = Some(target);
+        let last = index + 1 == self.steps.len();
+        let step = self.steps[index].clone();
+
+        self.title.event(TextUpdated(step.title.clone()));
+        self.progress.event(TextUpdated(format!("{} of {}", index + 1, self.steps.len())));
+        if let Some(button_text) = self.next_button.child("button_text") {
+            button_text.event(TextUpdated(if last { "Done" } else { "Next" }.to_owned()));
+        }
+        self.text.set_draw_style(style!(TextStyle {
+            text: step.text.clone(),
+            font_size: 16.0,
+        }));
+        let text_height = self.text.draw_state().downcast_ref::<TextState>()
+            .map_or(0.0, |text| text.wrapped_height(BUBBLE_WIDTH - BUBBLE_PADDING * 2.0));
+        let constraints = vec![ self.text.layout_vars().height | EQ(REQUIRED) | text_height ];
+        replace_constraints(&mut self.text, &mut self.text_constraints, constraints);
+
+        // the overlay may still be hidden, but it covers the root once shown
+        self.update_cutout(root.bounds());
+        self.overlay.event(TourStepShown(index));
+    }
+
+    /// Highlight the current widget and place the bubble beside it, `area` is the area covered by the overlay
+    fn update_cutout(&mut self, area: Rect) {
+        let target = match self.target {
+            Some(ref target) => target.clone(),
+            None => return,
+        };
+        let bounds = target.bounds();
+        self.overlay.update(|state: &mut SpotlightState| state.cutout = Some(bounds));
+
+        // put the bubble on whichever side of the widget has more room
+        let below = area.bottom() - bounds.bottom() > bounds.top() - area.top();
+        let overlay = self.overlay.layout_vars();
+        let target = target.layout_vars();
+        let vars = self.bubble.layout_vars();
+        let mut constraints = vec![
+            vars.left | EQ(WEAK) | target.left,
+            vars.left | GE(REQUIRED) | overlay.left + BUBBLE_PADDING,
+            vars.right | LE(REQUIRED) | overlay.right - BUBBLE_PADDING,
+        ];
+        if below {
+            constraints.push(vars.top | EQ(REQUIRED) | target.bottom + BUBBLE_OFFSET);
+        } else {
+            constraints.push(vars.bottom | EQ(REQUIRED) | target.top - BUBBLE_OFFSET);
+        }
+        replace_constraints(&mut self.bubble, &mut self.bubble_constraints, constraints);
+    }
+
+    fn finish(&mut self, skipped: bool) {
+        self.current = None;
+        self.target = None;
+        self.overlay.update(|state: &mut SpotlightState| state.cutout = None);
+        self.overlay.layout().hide();
+        self.overlay.event(TourFinished { skipped: skipped });
+    }
+}
+
+/// Builds a guided tour overlay. The overlay should be added as the last child of the root widget,
+/// so it's drawn over everything else, and made to fill the root. It's hidden until it receives
+/// `TourEvent::Start`.
+pub struct TourBuilder {
+    steps: Vec<TourStep>,
+}
+
+impl Default for TourBuilder {
+    fn default() -> Self {
+        TourBuilder {
+            steps: Vec::new(),
+        }
+    }
+}
+
+impl TourBuilder {
+    pub fn new() -> Self {
+        Self::default()
+    }
+    pub fn add_step(&mut self, step: TourStep) -> &mut Self {
+        self.steps.push(step);
+        self
+    }
+
+    /// Create the overlay widget
+    pub fn build(&self) -> Widget {
+        let mut overlay = Widget::new("tour");
+        overlay.layout().no_container();
+        overlay.set_draw_state(SpotlightState::default());
+        // stop clicks reaching the dimmed widgets
+        overlay.add_handler(|_: &ClickEvent, args: EventArgs| {
+            *args.handled = true;
+        });
+
+        let mut bubble = Widget::new("tour_bubble");
+        bubble.set_draw_style(style!(RectStyle {
+            background_color: WHITE,
+            corner_radius: Some(6.0),
+        }));
+        bubble.layout().add(width(BUBBLE_WIDTH));
+
+        let mut title = Widget::from_modifier_style(StaticTextStyle::from_style(style!(TextStyle {
+            font_size: 20.0,
+        })));
+        title.layout().add(constraints![
+            align_left(&bubble).padding(BUBBLE_PADDING),
+            align_top(&bubble).padding(BUBBLE_PADDING),
+        ]);
+        let mut text = Widget::new("tour_text");
+        text.layout().add(constraints![
+            below(&title).padding(BUBBLE_PADDING / 2.0),
+            align_left(&bubble).padding(BUBBLE_PADDING),
+            align_right(&bubble).padding(BUBBLE_PADDING),
+        ]);
+        let mut progress = Widget::from_modifier_style(StaticTextStyle::from_style(style!(TextStyle {
+            font_size: 14.0,
+            text_color: GRAY_50,
+        })));
+        let mut skip_button = Widget::from_modifier_style(ButtonStyle::from_text("Skip"));
+        let mut next_button = Widget::from_modifier_style(ButtonStyle::from_text("Next"));
+        next_button.layout().add(constraints![
+            below(&text).padding(BUBBLE_PADDING),
+            align_right(&bubble).padding(BUBBLE_PADDING),
+            align_bottom(&bubble).padding(BUBBLE_PADDING),
+        ]);
+        skip_button.layout().add(constraints![
+            to_left_of(&next_button).padding(BUBBLE_PADDING / 2.0),
+            align_top(&next_button),
+        ]);
+        progress.layout().add(constraints![
+            align_left(&bubble).padding(BUBBLE_PADDING),
+            center_vertical(&next_button),
+        ]);
+        let tour = overlay.clone();
+        skip_button.add_handler(move |_: &ClickEvent, args: EventArgs| {
+            tour.event(TourEvent::Skip);
+            *args.handled = true;
+        });
+        let tour = overlay.clone();
+        next_button.add_handler(move |_: &ClickEvent, args: EventArgs| {
+            tour.event(TourEvent::Next);
+            *args.handled = true;
+        });
+        bubble
+            .add_child(title.clone())
+            .add_child(text.clone())
+            .add_child(progress.clone())
+            .add_child(skip_button)
+            .add_child(next_button.clone());
+        overlay.add_child(bubble.clone());
+        overlay.layout().hide();
+
+        overlay.add_handler(TourHandler {
+            steps: self.steps.clone(),
+            current: None,
+            target: None,
+            overlay: overlay.clone(),
+            bubble: bubble,
+            bubble_constraints: Vec::new(),
+            title: title,
+            text: text,
+            text_constraints: Vec::new(),
+            progress: progress,
+            next_button: next_button,
+        });
+        TourHandler::add_adapters(&mut overlay);
+        overlay
+    }
+}
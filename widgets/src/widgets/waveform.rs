@@ -0,0 +1,55 @@
+//! Audio visualization widgets, a zoomable waveform and a level meter with peak hold.
+//!
+//! Both are designed to be fed from an audio thread, using `EventSender::update_widget` to
+//! run updates against the draw state on the UI thread, ex.
+//! `sender.update_widget(waveform_id, move |state: &mut WaveformState| state.push_samples(&chunk))`.
+
+use prelude::*;
+use draw::waveform::WaveformState;
+use draw::level_meter::LevelMeterState;
+
+#[derive(Debug, Copy, Clone)]
+pub struct WaveformBuilder;
+
+impl WaveformBuilder {
+    /// Creates a new `WaveformBuilder` showing `samples`, returns it in form of a `Widget`.
+    /// The mouse wheel zooms, dragging scrolls through the zoomed waveform.
+    pub fn new(samples: Vec<f32>) -> Widget {
+        let mut widget = Widget::new("waveform");
+        widget
+            .set_draw_state(WaveformState::new(samples))
+            .add_handler(|event: &WidgetMouseWheel, mut args: EventArgs| {
+                let zoom = match event.0 {
+                    glutin::MouseScrollDelta::LineDelta(_, y) => y,
+                    glutin::MouseScrollDelta::PixelDelta(glutin::dpi::LogicalPosition{y, ..}) => y as f32 / 13.0,
+                };
+                args.widget.update(|state: &mut WaveformState| {
+                    state.zoom_by(1.2f32.powf(zoom), 0.5);
+                });
+            })
+            .add_handler(|event: &DragEvent, mut args: EventArgs| {
+                let width = args.widget.bounds().width();
+                if width > 0.0 {
+                    let change = event.change.x / width;
+                    args.widget.update(|state: &mut WaveformState| {
+                        state.scroll_by(-change);
+                    });
+                }
+            })
+            .make_draggable();
+        widget
+    }
+}
+
+#[derive(Debug, Copy, Clone)]
+pub struct LevelMeterBuilder;
+
+impl LevelMeterBuilder {
+    /// Creates a new `LevelMeterBuilder`, returns it in form of a `Widget`
+    pub fn new() -> Widget {
+        let mut widget = Widget::new("level_meter");
+        widget.set_draw_state(LevelMeterState::default());
+        widget.layout().add(min_width(10.0));
+        widget
+    }
+}